@@ -4,17 +4,36 @@
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
 #![forbid(unsafe_code)]
-#![forbid(where_clauses_object_safety)]
 #![allow(deprecated)]
 
-use clap::Parser as ClapParser;
-use huff_core::Compiler;
+use clap::{ArgEnum, Parser as ClapParser, Subcommand};
+use huff_core::{Compiler, CompilerBuilder, Directive, LogFormat, TracingConfig, MAX_CONTRACT_SIZE};
+use huff_lexer::Lexer;
+use huff_repl::{
+    debugger::Debugger,
+    profile::GasProfile,
+    test_runner::{render_results, TestRunner, DEFAULT_RUNS},
+    tui,
+};
 use huff_utils::prelude::{
-    unpack_files, AstSpan, CodegenError, CodegenErrorKind, CompilerError, FileSource, Span,
+    apply_fixes, str_to_vec, unpack_files, AbiDiff, Artifact, ArtifactFormat, AstSpan, Bindings,
+    CodegenError, CodegenErrorKind, CombinedJson, CombinedJsonField, CompileStats, CompilerError,
+    CompilerWarning, ConstantDefinition, DataContract, Decompilation, DeploymentTransaction,
+    Diagnostic, Disassembly, EVMVersion, Explanation, FileSource, Fix, HardhatArtifact, HuffConfig,
+    LintLevel, LintLevels, Opcode, OutputLocation, PhaseReport, Relocation, RustBindingsCrate,
+    Scaffold, SolidityInterface, SourceMapping, Span, SymbolManifest, UnpackError, WarningKind,
+    ALL_LINTS, CONFIG_FILE,
 };
 use isatty::stdout_isatty;
 use spinners::{Spinner, Spinners};
-use std::{path::Path, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use yansi::Paint;
 
 /// The Huff CLI Args
@@ -44,6 +63,11 @@ struct Huff {
     #[clap(short = 'a', long = "artifacts")]
     artifacts: bool,
 
+    /// Write forge artifacts to the `out/` directory of a Foundry project, at
+    /// `<OUT>/<FILE NAME>/<CONTRACT NAME>.json`.
+    #[clap(long = "out", value_name = "OUT", conflicts_with = "artifact-format")]
+    out: Option<String>,
+
     /// Optimize compilation.
     #[clap(short = 'z', long = "optimize")]
     optimize: bool,
@@ -52,43 +76,875 @@ struct Huff {
     #[clap(short = 'b', long = "bytecode")]
     bytecode: bool,
 
+    /// Print the parsed AST of each contract as json, with the spans of its nodes.
+    #[clap(long = "ast")]
+    ast: bool,
+
+    /// Print the tokens the lexer produces for each contract, with their kind, lexeme and span,
+    /// instead of compiling.
+    #[clap(long = "tokens")]
+    tokens: bool,
+
+    /// Print the time spent in each phase of compiling each contract, with the macros expanded,
+    /// the expansions reused and the bytes emitted, as a text table or as json with `--stats=json`.
+    #[clap(
+        long = "stats",
+        value_name = "FORMAT",
+        arg_enum,
+        min_values = 0,
+        require_equals = true,
+        default_missing_value = "text"
+    )]
+    stats: Option<StatsFormat>,
+
+    /// Print an unsigned deployment transaction as json.
+    #[clap(long = "tx-json")]
+    tx_json: bool,
+
+    /// Print the selected fields of each contract as a single json document, like solc. The
+    /// fields are a comma separated list of abi, bin, bin-runtime, hashes, devdoc and userdoc.
+    #[clap(long = "combined-json", value_name = "FIELDS", use_value_delimiter = true)]
+    combined_json: Option<Vec<CombinedJsonField>>,
+
+    /// The chain id of the deployment transaction.
+    #[clap(long = "chain-id", requires = "tx-json")]
+    chain_id: Option<u64>,
+
+    /// The sender nonce of the deployment transaction.
+    #[clap(long = "nonce", requires = "tx-json")]
+    nonce: Option<u64>,
+
+    /// The EIP-1559 max fee per gas of the deployment transaction, in wei.
+    #[clap(long = "max-fee-per-gas", requires = "tx-json")]
+    max_fee_per_gas: Option<u128>,
+
+    /// The EIP-1559 max priority fee per gas of the deployment transaction, in wei.
+    #[clap(long = "max-priority-fee-per-gas", requires = "tx-json")]
+    max_priority_fee_per_gas: Option<u128>,
+
     /// Prints out to the terminal.
     #[clap(short = 'p', long = "print")]
     print: bool,
 
-    /// Verbose output.
-    #[clap(short = 'v', long = "verbose")]
-    verbose: bool,
+    /// Verbose output: -v logs the info events of the compiler, -vv the debug events and -vvv
+    /// every trace.
+    #[clap(short = 'v', long = "verbose", parse(from_occurrences))]
+    verbose: u8,
+
+    /// The format logs are written in: text, or json with an object per line.
+    #[clap(long = "log-format", value_name = "FORMAT", default_value = "text")]
+    log_format: LogFormat,
+
+    /// Append logs to a file instead of printing them.
+    #[clap(long = "log-file", value_name = "PATH")]
+    log_file: Option<PathBuf>,
+
+    /// Filter the logs of a target over the verbosity, like `codegen=trace` or `parser=off`. The
+    /// targets are codegen, parser, lexer, core, ast and analysis, among others.
+    #[clap(long = "log-filter", value_name = "DIRECTIVE", multiple_occurrences = true)]
+    log_filter: Vec<Directive>,
+
+    /// Log the bytes each statement generates, with the arg calls, constants and jump labels
+    /// they resolve.
+    #[clap(long = "debug-codegen")]
+    debug_codegen: bool,
+
+    /// Compile each contract twice, failing if the artifacts of the two compiles differ.
+    #[clap(long = "assert-deterministic")]
+    assert_deterministic: bool,
+
+    /// The format to emit errors in.
+    #[clap(long = "error-format", arg_enum, default_value = "human")]
+    error_format: ErrorFormat,
+
+    /// Accept uppercase and mixed-case opcode mnemonics.
+    #[clap(long = "case-insensitive-opcodes")]
+    case_insensitive_opcodes: bool,
+
+    /// The EVM version to generate bytecode for: paris, shanghai or cancun.
+    #[clap(long = "evm-version", default_value = "paris")]
+    evm_version: EVMVersion,
+
+    /// The shape artifacts are written in: huff, hardhat to write Hardhat artifacts to the
+    /// `artifacts/` tree of a Hardhat project, or foundry to write forge artifacts.
+    #[clap(long = "artifact-format", default_value = "huff")]
+    artifact_format: ArtifactFormat,
+
+    /// The maximum depth of nested macro invocations. Defaults to 256.
+    #[clap(long = "max-expansion-depth", value_name = "DEPTH")]
+    max_expansion_depth: Option<usize>,
+
+    /// Define a constant, replacing the constant of the same name. Defaults to 1 without a
+    /// value. Constants tested by `__IFDEF` and `__IFNDEF` blocks act as feature flags.
+    #[clap(long = "define", value_name = "NAME[=VALUE]")]
+    defines: Vec<ConstantDefinition>,
+
+    /// Override the value of a constant the contract defines, like `-c FEE=0x0a`.
+    #[clap(short = 'c', long = "constant", value_name = "NAME=VALUE")]
+    overrides: Vec<ConstantDefinition>,
+
+    /// Silence a lint. Use "warnings" to silence every lint.
+    #[clap(long = "allow", value_name = "LINT")]
+    allow: Vec<String>,
+
+    /// Report a lint as a warning. Use "warnings" to warn on every lint.
+    #[clap(long = "warn", value_name = "LINT")]
+    warn: Vec<String>,
+
+    /// Report a lint as an error, failing compilation. Use "warnings" to deny every lint.
+    #[clap(long = "deny", value_name = "LINT")]
+    deny: Vec<String>,
+
+    /// The project config file declaring post-build hooks.
+    #[clap(long = "config", default_value = CONFIG_FILE)]
+    config: String,
+
+    /// The subcommand to run instead of compiling
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+/// The Huff CLI Subcommands
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// Formats huff source files canonically, keeping their comments.
+    Fmt {
+        /// The files or directories to format. Defaults to the source path.
+        paths: Vec<String>,
+
+        /// Only check whether the files are formatted, without writing them.
+        #[clap(long = "check")]
+        check: bool,
+    },
+    /// Prints a contract with its includes inlined, as a single self-contained source.
+    ///
+    /// Files are inlined in the order they are compiled, each file once, keeping their comments.
+    /// Definitions included several times are kept once, for verification or sharing.
+    Flatten {
+        /// The entrypoint of the contract.
+        input: String,
+    },
+    /// Prints the extended explanation of an error or warning code.
+    Explain {
+        /// The code to explain, e.g. H0306.
+        code: String,
+    },
+    /// Explains the statement that generated a runtime program counter.
+    ExplainOffset {
+        /// The path to the compiled artifact json.
+        artifact: String,
+
+        /// The runtime program counter, in decimal or 0x prefixed hex.
+        pc: String,
+    },
+    /// Wraps a code table or binary file into an SSTORE2-style data contract.
+    ///
+    /// Writes the data contract artifact and the macros reading its data to the output
+    /// directory.
+    DataContract {
+        /// A binary file, or a huff file defining the code table to store.
+        input: String,
+
+        /// The code table to store, required for huff files.
+        #[clap(short = 't', long = "table")]
+        table: Option<String>,
+
+        /// The name of the data contract and its reader macros. Defaults to the table name, or
+        /// the uppercased file name.
+        #[clap(short = 'n', long = "name")]
+        name: Option<String>,
+    },
+    /// Writes the typed bindings of a contract to the output directory.
+    ///
+    /// Generates a Rust module, `<name>.rs`, with a call builder for each function, the decoders
+    /// of the events, and a `deploy` helper embedding the creation bytecode. TypeScript bindings,
+    /// `<NAME>.ts`, export the ABI and bytecode as constants, next to the `<NAME>.json` artifact
+    /// TypeChain reads.
+    Bindings {
+        /// The entrypoint of the contract.
+        input: String,
+
+        /// The language of the bindings.
+        #[clap(long = "lang", arg_enum, default_value = "rust")]
+        lang: BindingsLang,
+
+        /// The crate the Rust bindings are generated for: ethers or alloy.
+        #[clap(long = "crate", default_value = "ethers")]
+        krate: RustBindingsCrate,
+    },
+    /// Writes the Solidity interface of a contract to `I<NAME>.sol` in the output directory.
+    ///
+    /// Declares the events, errors and external functions of the contract, with the NatSpec of
+    /// their doc comments, for Solidity callers.
+    Interface {
+        /// The entrypoint of the contract.
+        input: String,
+    },
+    /// Scaffolds a Huff contract from a Solidity JSON ABI, to reimplement an existing interface.
+    ///
+    /// Writes `<NAME>.huff` to the output directory, declaring the functions, events and errors
+    /// of the ABI, with an empty macro per function jumped to from a dispatcher skeleton.
+    Scaffold {
+        /// The Solidity JSON ABI, or an artifact with an `abi` field.
+        #[clap(long = "abi")]
+        abi: String,
+
+        /// The name of the contract. Defaults to the file stem of the ABI.
+        #[clap(short = 'n', long = "name")]
+        name: Option<String>,
+    },
+    /// Writes the manifest of the public symbols of a library to the output directory.
+    ///
+    /// Consumers pin the manifest in their config, to check the library symbols they use
+    /// against it.
+    Package {
+        /// The entrypoint of the library.
+        input: String,
+    },
+    /// Writes the API documentation of a contract or library to the output directory.
+    ///
+    /// Documents the functions and their selectors, the events and their topics, the constants,
+    /// the storage layout and the macros, with their NatSpec doc comments, in `<NAME>.md` or
+    /// `<NAME>.html`.
+    Doc {
+        /// The entrypoint of the contract or library.
+        input: String,
+
+        /// The format to write the documentation in.
+        #[clap(long = "format", arg_enum, default_value = "markdown")]
+        format: DocFormat,
+    },
+    /// Writes the bundle submitted to Sourcify and Etherscan-style verifiers to the output
+    /// directory.
+    ///
+    /// Bundles the sources of a contract, by path or flattened, with the compiled bytecode, the
+    /// compiler version and the settings it was compiled with, in `<NAME>.verify.json`.
+    VerifyBundle {
+        /// The entrypoint of the contract.
+        input: String,
+
+        /// The format to bundle the sources in.
+        #[clap(long = "format", arg_enum, default_value = "standard-json")]
+        format: BundleFormat,
+    },
+    /// Lists the functions the compiled dispatcher matches, in comparison order.
+    ///
+    /// With `--format table` or `--format json`, lists the selector, signature and entered macro
+    /// of every function instead.
+    Selectors {
+        /// The contract to compile.
+        input: String,
+
+        /// Render the dispatcher as a decision tree, with its splits, calldata size checks and
+        /// fallthrough paths.
+        #[clap(long = "tree", conflicts_with = "format")]
+        tree: bool,
+
+        /// The format to list the functions in.
+        #[clap(long = "format", arg_enum, default_value = "list")]
+        format: SelectorsFormat,
+
+        /// Write the function signatures to `<NAME>.4byte.txt` in the output directory, to submit
+        /// to the 4byte directory.
+        #[clap(long = "4byte")]
+        four_byte: bool,
+    },
+    /// Prints the call graph of the macro invocations of a contract.
+    ///
+    /// Macros are sized by the runtime bytes their expansions generate, including the macros
+    /// they invoke. Labels passed to macros as arguments are drawn as edges back to the macro
+    /// defining them.
+    Graph {
+        /// The contract to compile.
+        input: String,
+
+        /// The format to print the graph in.
+        #[clap(long = "format", arg_enum, default_value = "dot")]
+        format: GraphFormat,
+    },
+    /// Prints the annotated disassembly of bytecode.
+    ///
+    /// Lists the program counter and the mnemonic of each instruction, with the data of pushes
+    /// and labels for the jump destinations. Compiled artifacts are disassembled with their
+    /// source map, naming the labels after the Huff labels and listing table bytes as data.
+    Disasm {
+        /// A compiled artifact json, a file of hex bytecode, or hex bytecode. Read from stdin if
+        /// omitted or `-`.
+        input: Option<String>,
+
+        /// Interleave the instructions with the source lines generating them, for artifacts.
+        #[clap(short = 's', long = "source")]
+        source: bool,
+    },
+    /// Decompiles runtime bytecode into a best-effort Huff skeleton.
+    ///
+    /// Writes the skeleton to `<NAME>.decompiled.huff` in the output directory, with a macro per
+    /// basic block, a function stub per dispatched selector, and a code table per run of raw
+    /// data, read from `<NAME>.<TABLE>.bin` files written alongside.
+    Decompile {
+        /// A compiled artifact json, a file of hex bytecode, or hex bytecode. Read from stdin if
+        /// omitted or `-`.
+        input: Option<String>,
+
+        /// The name of the skeleton. Defaults to the uppercased file name, or `DECOMPILED`.
+        #[clap(short = 'n', long = "name")]
+        name: Option<String>,
+    },
+    /// Steps through a call to a contract in a terminal debugger.
+    ///
+    /// Deploys the compiled contract into an in-process EVM and traces the call, showing the Huff
+    /// source line generating each instruction, the macros it was invoked through, the stack,
+    /// memory and storage. Steps move forwards and backwards by instruction or by source line,
+    /// or continue to the breakpoints set on labels and macros.
+    Debug {
+        /// The contract to compile.
+        input: String,
+
+        /// The calldata of the call, as hex bytes.
+        #[clap(long = "calldata", default_value = "")]
+        calldata: String,
+
+        /// The value of the call, in wei.
+        #[clap(long = "value", default_value = "0")]
+        value: u128,
+
+        /// A label or macro to break on, repeatable.
+        #[clap(short = 'b', long = "break", value_name = "NAME")]
+        breakpoints: Vec<String>,
+    },
+    /// Profiles the gas used by a call to a contract, by macro and source line.
+    ///
+    /// Deploys the compiled contract into an in-process EVM and executes the call, attributing
+    /// the gas of each instruction to the macros it was invoked through and the source line
+    /// generating it, so cold and warm accesses are priced as they execute.
+    Profile {
+        /// The contract to compile.
+        input: String,
+
+        /// The calldata of the call, as hex bytes.
+        #[clap(long = "calldata", default_value = "")]
+        calldata: String,
+
+        /// The value of the call, in wei.
+        #[clap(long = "value", default_value = "0")]
+        value: u128,
+
+        /// Print the gas of each macro in the folded format of flame graph tools.
+        #[clap(long = "folded")]
+        folded: bool,
+    },
+    /// Runs the tests of a contract, fuzzing those with parameters.
+    ///
+    /// Each test body is executed as the runtime bytecode of a contract in an in-process EVM,
+    /// failing if it reverts or halts. Tests with typed parameters run with random ABI-encoded
+    /// calldata, and the arguments of a failing run are shrunk before being reported.
+    Test {
+        /// The contract defining the tests.
+        input: String,
+
+        /// The number of runs of each fuzzed test. Defaults to the config, or 256.
+        #[clap(long = "runs")]
+        runs: Option<usize>,
+
+        /// The seed of the random inputs. Defaults to the config, or a random seed.
+        #[clap(long = "seed")]
+        seed: Option<u64>,
+
+        /// Only run the tests whose name contains the pattern.
+        #[clap(short = 'm', long = "match", value_name = "PATTERN")]
+        pattern: Option<String>,
+    },
+    /// Writes the compilation statistics of each file and macro to `stats.json` in the output
+    /// directory.
+    ///
+    /// Includes the token and AST node counts, the bytes generated per node, the runtime bytes
+    /// contributed and the compile time, to track their growth over time.
+    Stats {
+        /// The files or directories to compile. Defaults to the source path.
+        paths: Vec<String>,
+    },
+    /// Links the addresses of deployed libraries into a compiled artifact.
+    ///
+    /// Writes each address over the placeholders `__LINK(NAME)` left in the bytecode, then
+    /// writes the linked artifact to the output directory. Libraries left unlinked keep their
+    /// link references, to be linked in a later stage.
+    Link {
+        /// The path to the compiled artifact json.
+        artifact: String,
+
+        /// The libraries to link, as `NAME=ADDRESS`.
+        #[clap(
+            long = "libraries",
+            value_name = "NAME=ADDRESS",
+            multiple_values = true,
+            required = true
+        )]
+        libraries: Vec<String>,
+    },
+    /// Lists the functions, events and entrypoints added, removed or changed between the ABIs
+    /// of two compiled artifacts.
+    AbiDiff {
+        /// The path to the old artifact json.
+        old: String,
+
+        /// The path to the new artifact json.
+        new: String,
+
+        /// Exit with an error if any change breaks the users of the old ABI.
+        #[clap(long = "check")]
+        check: bool,
+    },
+    /// Reports the lint warnings of huff source files without compiling them.
+    ///
+    /// Lint levels are read from the `[lints]` table of the config, then from the `--allow`,
+    /// `--warn` and `--deny` flags. Exits with an error if any warning is denied.
+    Lint {
+        /// The files or directories to lint. Defaults to the source path.
+        paths: Vec<String>,
+
+        /// Apply the fixes of warnings that are safe to fix, like removing unreachable code.
+        #[clap(long = "fix")]
+        fix: bool,
+    },
+}
+
+/// The format compiler errors are emitted in
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorFormat {
+    /// Human readable, colored output
+    Human,
+    /// One json diagnostic per line
+    Json,
+}
+
+/// The format the documentation is written in
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum DocFormat {
+    /// Markdown
+    Markdown,
+    /// A standalone HTML page
+    Html,
+}
+
+/// The format the sources of a verification bundle are written in
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum BundleFormat {
+    /// Every file compiled, by path
+    StandardJson,
+    /// A single source with the includes inlined
+    Flattened,
+}
+
+/// The language contract bindings are generated in
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum BindingsLang {
+    /// Rust
+    Rust,
+    /// TypeScript
+    Ts,
+}
+
+/// The format the functions of the dispatcher are listed in
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum SelectorsFormat {
+    /// The dispatched functions in comparison order, with their gas
+    List,
+    /// A table of the selectors, signatures and macros
+    Table,
+    /// Json
+    Json,
+}
+
+/// The format the phase statistics of a compilation are printed in
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum StatsFormat {
+    /// A table of the phases by file
+    Text,
+    /// Json
+    Json,
+}
+
+/// The format the macro call graph is printed in
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum GraphFormat {
+    /// The graphviz dot language
+    Dot,
+    /// Json
+    Json,
+}
+
+/// The creation bytecode of a contract to call, the source map of its runtime bytecode and the
+/// calldata of the call
+type CompiledCall = (Vec<u8>, Vec<SourceMapping>, Vec<u8>);
+
+impl ErrorFormat {
+    /// Emits a compiler error to stderr in the given format
+    fn emit(&self, e: &CompilerError) {
+        match self {
+            ErrorFormat::Human => eprintln!("{}", Paint::red(format!("{}", e))),
+            ErrorFormat::Json => {
+                Diagnostic::from_compiler_error(e).iter().for_each(|d| eprintln!("{}", d.to_json()))
+            }
+        }
+    }
+
+    /// Emits a compiler warning to stderr in the given format
+    fn emit_warning(&self, w: &CompilerWarning) {
+        match self {
+            ErrorFormat::Human => {
+                eprintln!("{}", Paint::yellow(format!("{}", Diagnostic::from(w))))
+            }
+            ErrorFormat::Json => eprintln!("{}", Diagnostic::from(w).to_json()),
+        }
+    }
+
+    /// Emits the number of warnings emitted during compilation
+    fn emit_warning_summary(&self, count: usize) {
+        if *self == ErrorFormat::Human && count > 0 {
+            let plural = if count == 1 { "" } else { "s" };
+            eprintln!("{}", Paint::yellow(format!("{} warning{} emitted", count, plural)));
+        }
+    }
 }
 
 fn main() {
     // Parse the command line arguments
     let cli = Huff::parse();
 
-    // Initiate Tracing if Verbose
-    if cli.verbose {
-        Compiler::init_tracing_subscriber(Some(vec![tracing::Level::DEBUG.into()]));
+    // Initiate Tracing if Verbose or any log is configured
+    if cli.verbose > 0 || !cli.log_filter.is_empty() || cli.log_file.is_some() {
+        let config = TracingConfig {
+            level: TracingConfig::level_of(cli.verbose),
+            directives: cli.log_filter.clone(),
+            format: cli.log_format,
+            log_file: cli.log_file.clone(),
+        };
+        if let Err(e) = Compiler::init_tracing(&config) {
+            eprintln!("{}", Paint::red(e));
+            std::process::exit(1);
+        }
+    }
+
+    // Run the subcommand if one was provided
+    match &cli.command {
+        Some(Command::Fmt { paths, check }) => match cli.format(paths, *check) {
+            Ok(true) => return,
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                cli.error_format.emit(&e);
+                std::process::exit(1);
+            }
+        },
+        Some(Command::Flatten { input }) => match cli.flatten(input) {
+            Ok(out) => {
+                print!("{}", out);
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::Explain { code }) => match Explanation::lookup(code) {
+            Some(e) => {
+                println!("{}: {}\n\n{}", e.code, e.name, e.text);
+                return
+            }
+            None => {
+                eprintln!("{}", Paint::red(format!("Unknown error code \"{}\"", code)));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::ExplainOffset { artifact, pc }) => match cli.explain_offset(artifact, pc) {
+            Ok(explanation) => {
+                println!("{}", explanation);
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::DataContract { input, table, name }) => {
+            match cli.data_contract(input, table.as_deref(), name.as_deref()) {
+                Ok(paths) => {
+                    paths.iter().for_each(|p| println!("Wrote {}", p));
+                    return
+                }
+                Err(e) => {
+                    eprintln!("{}", Paint::red(e));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Command::Bindings { input, lang, krate }) => match cli.bindings(input, *lang, *krate) {
+            Ok(paths) => {
+                paths.iter().for_each(|p| println!("Wrote {}", p));
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::Interface { input }) => match cli.interface(input) {
+            Ok(path) => {
+                println!("Wrote {}", path);
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::Scaffold { abi, name }) => match cli.scaffold(abi, name.as_deref()) {
+            Ok(path) => {
+                println!("Wrote {}", path);
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::Package { input }) => match cli.package(input) {
+            Ok(path) => {
+                println!("Wrote {}", path);
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::Doc { input, format }) => match cli.doc(input, *format) {
+            Ok(path) => {
+                println!("Wrote {}", path);
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::VerifyBundle { input, format }) => match cli.verify_bundle(input, *format) {
+            Ok(path) => {
+                println!("Wrote {}", path);
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::Selectors { input, tree, format, four_byte }) => match cli.selectors(
+            input,
+            *tree,
+            *format,
+            *four_byte,
+        ) {
+            Ok((out, path)) => {
+                println!("{}", out);
+                if let Some(path) = path {
+                    println!("Wrote {}", path);
+                }
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::Graph { input, format }) => match cli.graph(input, *format) {
+            Ok(out) => {
+                println!("{}", out);
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::Disasm { input, source }) => match cli.disasm(input.as_deref(), *source) {
+            Ok(out) => {
+                println!("{}", out);
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::Decompile { input, name }) => {
+            match cli.decompile(input.as_deref(), name.as_deref()) {
+                Ok(paths) => {
+                    paths.iter().for_each(|p| println!("Wrote {}", p));
+                    return
+                }
+                Err(e) => {
+                    eprintln!("{}", Paint::red(e));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Command::Debug { input, calldata, value, breakpoints }) => {
+            match cli.debug(input, calldata, *value, breakpoints) {
+                Ok(()) => return,
+                Err(e) => {
+                    eprintln!("{}", Paint::red(e));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Command::Profile { input, calldata, value, folded }) => {
+            match cli.profile(input, calldata, *value, *folded) {
+                Ok(out) => {
+                    println!("{}", out);
+                    return
+                }
+                Err(e) => {
+                    eprintln!("{}", Paint::red(e));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Command::Test { input, runs, seed, pattern }) => {
+            match cli.test(input, *runs, *seed, pattern.as_deref()) {
+                Ok((out, passed)) => {
+                    println!("{}", out);
+                    if !passed {
+                        std::process::exit(1);
+                    }
+                    return
+                }
+                Err(e) => {
+                    eprintln!("{}", Paint::red(e));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Command::Link { artifact, libraries }) => match cli.link(artifact, libraries) {
+            Ok((path, unlinked)) => {
+                println!("Wrote {}", path);
+                if !unlinked.is_empty() {
+                    let unlinked = unlinked.join(", ");
+                    eprintln!("{}", Paint::yellow(format!("Left unlinked: {}", unlinked)));
+                }
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::AbiDiff { old, new, check }) => match Huff::abi_diff(old, new) {
+            Ok(diff) => {
+                println!("{}", diff);
+                if *check && diff.is_breaking() {
+                    eprintln!("{}", Paint::red("The new ABI breaks the old ABI"));
+                    std::process::exit(1);
+                }
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::Lint { paths, fix }) => match cli.lint(paths, *fix) {
+            Ok(true) => return,
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        Some(Command::Stats { paths }) => match cli.stats(paths) {
+            Ok(path) => {
+                println!("Wrote {}", path);
+                return
+            }
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        },
+        None => {}
     }
 
+    // Load the project config, if there is one
+    let config = match cli.get_config() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{}", Paint::red(format!("Failed to load config \"{}\": {}", cli.config, e)));
+            std::process::exit(1);
+        }
+    };
+
+    // Load the pinned library manifests
+    let manifests = match config
+        .manifests
+        .iter()
+        .map(|p| {
+            SymbolManifest::import(p)
+                .map_err(|e| format!("Failed to load manifest \"{}\": {}", p, e))
+        })
+        .collect::<Result<Vec<SymbolManifest>, String>>()
+    {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("{}", Paint::red(e));
+            std::process::exit(1);
+        }
+    };
+
+    // Resolve the lint levels from the config and the Huff Args
+    let lint_levels = match cli.get_lint_levels(&config) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("{}", Paint::red(e));
+            std::process::exit(1);
+        }
+    };
+
     // Create compiler from the Huff Args
     let sources: Arc<Vec<String>> = match cli.get_inputs() {
         Ok(s) => Arc::new(s),
         Err(e) => {
-            eprintln!("{}", Paint::red(format!("{}", e)));
+            cli.error_format.emit(&e);
             std::process::exit(1);
         }
     };
-    let compiler: Compiler = Compiler {
-        sources: Arc::clone(&sources),
-        output: match (&cli.output, cli.artifacts) {
-            (Some(o), true) => Some(o.clone()),
-            (None, true) => Some(cli.outputdir.clone()),
-            _ => None,
-        },
-        construct_args: cli.inputs,
-        optimize: cli.optimize,
-        bytecode: cli.bytecode,
-    };
+
+    // Dump the token streams, even if the sources don't parse
+    if cli.tokens {
+        if !cli.dump_tokens(&sources) {
+            std::process::exit(1);
+        }
+        return
+    }
+
+    let mut builder = cli
+        .compiler_builder()
+        .sources(sources.to_vec())
+        .optimize(cli.optimize)
+        .bytecode(cli.bytecode)
+        .artifact_format(match cli.out {
+            Some(_) => ArtifactFormat::Foundry,
+            None => cli.artifact_format,
+        })
+        .lint_levels(lint_levels)
+        .manifests(manifests);
+    match (&cli.out, &cli.output, cli.artifacts) {
+        (Some(out), _, _) => builder = builder.output(out),
+        (None, Some(o), true) => builder = builder.output(o),
+        (None, None, true) => builder = builder.output(&cli.outputdir),
+        _ => {}
+    }
+    if let Some(inputs) = &cli.inputs {
+        builder = builder.constructor_args(inputs.clone());
+    }
+    let compiler = builder.build();
 
     // Create compiling spinner
     tracing::debug!(target: "core", "[⠔] COMPILING");
@@ -105,7 +961,31 @@ fn main() {
         println!(" ");
     }
     match compile_res {
-        Ok(artifacts) => {
+        Ok(results) => {
+            results.iter().flat_map(|r| &r.warnings).for_each(|w| cli.error_format.emit_warning(w));
+            cli.error_format.emit_warning_summary(results.iter().map(|r| r.warnings.len()).sum());
+
+            // Report the phase statistics, including those of the files that failed to compile
+            if let Some(format) = cli.stats {
+                let files = results.iter().map(|r| r.stats.clone()).collect();
+                let report = PhaseReport { files };
+                match format {
+                    StatsFormat::Text => println!("{}", report.render()),
+                    StatsFormat::Json => println!("{}", report.to_json()),
+                }
+            }
+
+            // Report every file that failed to compile
+            let errors = results.iter().filter_map(|r| r.error()).collect::<Vec<_>>();
+            if !errors.is_empty() {
+                errors.iter().for_each(|e| {
+                    tracing::error!(target: "core", "COMPILER ERRORED: {:?}", e);
+                    cli.error_format.emit(e);
+                });
+                std::process::exit(1);
+            }
+
+            let artifacts = results.iter().filter_map(|r| r.artifact()).collect::<Vec<_>>();
             if artifacts.is_empty() {
                 let e = CompilerError::CodegenError(CodegenError {
                     kind: CodegenErrorKind::AbiGenerationFailure,
@@ -126,11 +1006,26 @@ fn main() {
                             .collect::<Vec<Span>>(),
                     ),
                     token: None,
+                    help: None,
                 });
                 tracing::error!(target: "core", "COMPILER ERRORED: {:?}", e);
-                eprintln!("{}", Paint::red(format!("{}", e)));
+                cli.error_format.emit(&e);
                 std::process::exit(1);
             }
+            if cli.assert_deterministic {
+                let compiled = artifacts.iter().map(|a| Arc::clone(a)).collect::<Vec<_>>();
+                let differing = compiler.nondeterministic_artifacts(&compiled);
+                if !differing.is_empty() {
+                    eprintln!(
+                        "{}",
+                        Paint::red(format!(
+                            "Compiling again produced different artifacts for: {}",
+                            differing.join(", ")
+                        ))
+                    );
+                    std::process::exit(1);
+                }
+            }
             if cli.bytecode {
                 match sources.len() {
                     1 => print!("{}", artifacts[0].bytecode),
@@ -139,10 +1034,54 @@ fn main() {
                         .for_each(|a| println!("\"{}\" bytecode: {}", a.file.path, a.bytecode)),
                 }
             }
+            if cli.ast {
+                for artifact in artifacts.iter() {
+                    match compiler.ast(Arc::clone(&artifact.file)) {
+                        Ok(ast) if sources.len() == 1 => println!("{}", ast.to_json()),
+                        Ok(ast) => println!("\"{}\" ast: {}", artifact.file.path, ast.to_json()),
+                        Err(e) => cli.error_format.emit(&e),
+                    }
+                }
+            }
+            if cli.debug_codegen {
+                for artifact in artifacts.iter() {
+                    match compiler.codegen_log(Arc::clone(&artifact.file)) {
+                        Ok(logs) => logs.iter().for_each(|log| {
+                            eprintln!("\"{}\" {}", artifact.file.path, log);
+                        }),
+                        Err(e) => cli.error_format.emit(&e),
+                    }
+                }
+            }
+            if cli.tx_json {
+                let txs = artifacts.iter().map(|a| cli.deployment_transaction(a));
+                match sources.len() {
+                    1 => println!("{}", txs.map(|tx| tx.to_json()).collect::<String>()),
+                    _ => artifacts.iter().zip(txs).for_each(|(a, tx)| {
+                        println!("\"{}\" deployment transaction: {}", a.file.path, tx.to_json())
+                    }),
+                }
+            }
+
+            if let Some(fields) = &cli.combined_json {
+                let contracts = artifacts.iter().map(|a| a.as_ref());
+                let combined = CombinedJson::new(contracts, fields, env!("CARGO_PKG_VERSION"));
+                println!("{}", combined.to_json());
+            }
+
+            // Run the post-build hooks on the exported artifacts
+            if let Some(output) = &compiler.output {
+                let output = OutputLocation(output.clone());
+                let format = compiler.artifact_format;
+                if let Err(e) = Huff::run_post_build_hooks(&config, &artifacts, &output, format) {
+                    eprintln!("{}", Paint::red(e));
+                    std::process::exit(1);
+                }
+            }
         }
         Err(e) => {
             tracing::error!(target: "core", "COMPILER ERRORED: {:?}", e);
-            eprintln!("{}", Paint::red(format!("{}", e)));
+            cli.error_format.emit(&e);
             std::process::exit(1);
         }
     }
@@ -170,4 +1109,847 @@ impl Huff {
             }
         }
     }
+
+    /// A compiler builder with the settings shared by every command compiling contracts
+    pub fn compiler_builder(&self) -> CompilerBuilder {
+        let mut builder = Compiler::builder()
+            .case_insensitive_opcodes(self.case_insensitive_opcodes)
+            .evm_version(self.evm_version)
+            .defines(self.defines.clone())
+            .overrides(self.overrides.clone());
+        if let Some(depth) = self.max_expansion_depth {
+            builder = builder.max_expansion_depth(depth);
+        }
+        builder
+    }
+
+    /// Builds the unsigned deployment transaction of an artifact
+    pub fn deployment_transaction(&self, artifact: &Artifact) -> DeploymentTransaction {
+        let mut tx = DeploymentTransaction::new(artifact);
+        tx.set_chain(self.chain_id, self.nonce);
+        tx.set_fees(self.max_fee_per_gas, self.max_priority_fee_per_gas);
+        tx
+    }
+
+    /// Loads the project config
+    ///
+    /// Falls back to an empty config if the default config file doesn't exist.
+    pub fn get_config(&self) -> Result<HuffConfig, std::io::Error> {
+        let path = Path::new(&self.config);
+        if self.config == CONFIG_FILE && !path.exists() {
+            return Ok(HuffConfig::default())
+        }
+        HuffConfig::load(path)
+    }
+
+    /// Runs every post-build hook on each artifact exported to the output location
+    pub fn run_post_build_hooks(
+        config: &HuffConfig,
+        artifacts: &[&Arc<Artifact>],
+        output: &OutputLocation,
+        format: ArtifactFormat,
+    ) -> Result<(), String> {
+        for hook in config.post_build.iter() {
+            for artifact in artifacts {
+                let path = Compiler::artifact_path(artifact, output, format);
+                tracing::debug!(target: "cli", "RUNNING POST-BUILD HOOK \"{}\" ON \"{}\"", hook.name(), path);
+                let status = hook.command(artifact, &path).status().map_err(|e| {
+                    format!("Failed to run post-build hook \"{}\": {}", hook.name(), e)
+                })?;
+                if !status.success() {
+                    return Err(format!(
+                        "Post-build hook \"{}\" failed on \"{}\" with {}",
+                        hook.name(),
+                        path,
+                        status
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves the lint levels of the config, then applies allowed, then warned, then denied
+    /// lints
+    ///
+    /// Errors listing the available lints if a lint is unknown.
+    pub fn get_lint_levels(&self, config: &HuffConfig) -> Result<LintLevels, String> {
+        let unknown = |lint: String| {
+            format!(
+                "Unknown lint \"{}\". Available lints: {}, {}",
+                lint,
+                ALL_LINTS,
+                WarningKind::LINTS.join(", ")
+            )
+        };
+        let mut levels = config.lint_levels().map_err(unknown)?;
+        let ordered = [
+            (&self.allow, LintLevel::Allow),
+            (&self.warn, LintLevel::Warn),
+            (&self.deny, LintLevel::Deny),
+        ];
+        for (lints, level) in ordered {
+            lints.iter().try_for_each(|lint| levels.set(lint, level)).map_err(unknown)?;
+        }
+        Ok(levels)
+    }
+
+    /// Explains the runtime bytecode at a program counter of a compiled artifact
+    ///
+    /// The artifact's source is recompiled to find the statement that generated the bytes, the
+    /// macro invocations it was generated through and the placeholder filled in the bytes.
+    pub fn explain_offset(&self, artifact: &str, pc: &str) -> Result<String, String> {
+        let pc = match pc.strip_prefix("0x") {
+            Some(hex) => usize::from_str_radix(hex, 16),
+            None => pc.parse::<usize>(),
+        }
+        .map_err(|_| format!("Invalid program counter \"{}\"", pc))?;
+        let artifact = Artifact::import(artifact)
+            .map_err(|e| format!("Failed to read artifact \"{}\": {}", artifact, e))?;
+        if pc >= artifact.runtime.len() / 2 {
+            return Err(format!(
+                "Program counter {} is out of bounds of the {} byte runtime bytecode",
+                pc,
+                artifact.runtime.len() / 2
+            ))
+        }
+
+        let compiler = self.compiler_builder().build();
+        let source_map =
+            compiler.runtime_source_map(Arc::clone(&artifact.file)).map_err(|e| e.to_string())?;
+        let mapping = source_map
+            .iter()
+            .find(|m| m.contains(pc))
+            .ok_or_else(|| format!("No statement generated the bytecode at {:#06x}", pc))?;
+
+        // Walk the instructions of the mapped bytes to the one containing the program counter
+        let byte_at = |i: usize| artifact.runtime.get(i * 2..i * 2 + 2).unwrap_or_default();
+        let mut instruction = format!("0x{}", byte_at(pc));
+        let mut i = mapping.offset;
+        while mapping.table.is_none() && i <= pc {
+            let Some(mnemonic) = Opcode::mnemonic(byte_at(i)) else { break };
+            let size = mnemonic.strip_prefix("push").and_then(|n| n.parse::<usize>().ok());
+            let end = i + 1 + size.unwrap_or_default();
+            if pc < end {
+                instruction = match size {
+                    Some(_) => {
+                        format!("{} 0x{}", mnemonic, &artifact.runtime[(i + 1) * 2..end * 2])
+                    }
+                    None => mnemonic.to_string(),
+                };
+                break
+            }
+            i = end;
+        }
+
+        let mut out = vec![format!("Runtime offset {:#06x} ({}): {}", pc, pc, instruction)];
+        out.push(format!(
+            "Generated as bytes {:#06x}..{:#06x} of {}:",
+            mapping.offset,
+            mapping.offset + mapping.size,
+            match &mapping.table {
+                Some(table) => format!("table \"{}\"", table),
+                None => "the statement".to_string(),
+            }
+        ));
+        if let Some(span) = mapping.span.0.first() {
+            out.push(span.code_frame().unwrap_or_else(|| Huff::span_location(span)));
+        }
+        match &mapping.relocation {
+            Some(Relocation::JumpLabel(label)) => {
+                out.push(format!("Relocation: jump destination of label \"{}\"", label))
+            }
+            Some(Relocation::TableStart(table)) => {
+                out.push(format!("Relocation: start offset of table \"{}\"", table))
+            }
+            Some(Relocation::Immutable(name)) => {
+                out.push(format!("Relocation: immutable \"{}\", set by the constructor", name))
+            }
+            Some(Relocation::LabelOffsets(expression)) => {
+                out.push(format!("Relocation: label offsets {}", expression))
+            }
+            None => {}
+        }
+        if mapping.table.is_none() {
+            out.push("Macro invocations:".to_string());
+            out.push("    MAIN".to_string());
+            for (depth, mi) in mapping.invocations.iter().enumerate() {
+                let location = mi.span.0.first().map(Huff::span_location).unwrap_or_default();
+                out.push(format!(
+                    "    {}└─ {}() at {}",
+                    "   ".repeat(depth),
+                    mi.macro_name,
+                    location.trim_start_matches("-> ")
+                ));
+            }
+        }
+        Ok(out.join("\n"))
+    }
+
+    /// Wraps a code table or binary file into a data contract, returning the paths written
+    ///
+    /// The artifact is written to `<NAME>.data.json` and the reader macros to `<NAME>.data.huff`
+    /// in the output directory.
+    pub fn data_contract(
+        &self,
+        input: &str,
+        table: Option<&str>,
+        name: Option<&str>,
+    ) -> Result<Vec<String>, String> {
+        let (mut data_contract, file) =
+            match (Path::new(input).extension().unwrap_or_default().eq("huff"), table) {
+                (true, Some(table)) => {
+                    let compiler = self.compiler_builder().build();
+                    let file = Compiler::fetch_sources(vec![PathBuf::from(input)])
+                        .remove(0)
+                        .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+                        .map_err(|e| e.to_string())?;
+                    let data_contract = compiler
+                        .data_contract(Arc::clone(&file), table)
+                        .map_err(|e| e.to_string())?;
+                    (data_contract, file)
+                }
+                (true, None) => {
+                    return Err(format!(
+                        "Pass the code table of \"{}\" to store with --table",
+                        input
+                    ))
+                }
+                (false, _) => {
+                    let data = fs::read(input)
+                        .map_err(|e| format!("Failed to read \"{}\": {}", input, e))?;
+                    let stem = Path::new(input).file_stem().unwrap_or_default().to_string_lossy();
+                    let name =
+                        stem.chars()
+                            .map(|c| {
+                                if c.is_ascii_alphanumeric() {
+                                    c.to_ascii_uppercase()
+                                } else {
+                                    '_'
+                                }
+                            })
+                            .collect::<String>();
+                    let file = FileSource { path: input.to_string(), ..Default::default() };
+                    (DataContract::new(&name, data), Arc::new(file))
+                }
+            };
+        if let Some(name) = name {
+            data_contract.name = name.to_string();
+        }
+
+        // Data contracts are deployed, so they are bound by the same size limit
+        let size = data_contract.runtime().len() / 2;
+        if size > MAX_CONTRACT_SIZE {
+            self.error_format.emit_warning(&CompilerWarning::new(
+                WarningKind::OversizedContract(size),
+                AstSpan(vec![]),
+            ));
+        }
+
+        let artifact_path = format!("{}/{}.data.json", self.outputdir, data_contract.name);
+        data_contract
+            .artifact(file)
+            .export(&artifact_path)
+            .map_err(|e| format!("Failed to write \"{}\": {}", artifact_path, e))?;
+        let macros_path = format!("{}/{}.data.huff", self.outputdir, data_contract.name);
+        fs::write(&macros_path, data_contract.reader_macros())
+            .map_err(|e| format!("Failed to write \"{}\": {}", macros_path, e))?;
+        Ok(vec![artifact_path, macros_path])
+    }
+
+    /// Flattens a contract and its includes into a single source
+    pub fn flatten(&self, input: &str) -> Result<String, String> {
+        let compiler = self.compiler_builder().build();
+        Compiler::fetch_sources(vec![PathBuf::from(input)])
+            .remove(0)
+            .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+            .and_then(Compiler::flatten)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Prints the tokens of each source, one per line, emitting the lexical errors found
+    ///
+    /// Returns whether every source was read and lexed without errors.
+    pub fn dump_tokens(&self, sources: &[String]) -> bool {
+        let compiler = Compiler::builder()
+            .case_insensitive_opcodes(self.case_insensitive_opcodes)
+            .build();
+        let mut ok = true;
+        for source in sources.iter() {
+            let file = match Compiler::fetch_sources(vec![PathBuf::from(source)])
+                .remove(0)
+                .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+            {
+                Ok(file) => file,
+                Err(e) => {
+                    self.error_format.emit(&e);
+                    ok = false;
+                    continue
+                }
+            };
+            for token in compiler.tokens(file) {
+                match token {
+                    Ok(token) => println!("{}", token.dump()),
+                    Err(e) => {
+                        self.error_format.emit(&CompilerError::LexicalError(e));
+                        ok = false;
+                    }
+                }
+            }
+        }
+        ok
+    }
+
+    /// Writes the bindings of a contract to the output directory, returning the paths written
+    pub fn bindings(
+        &self,
+        input: &str,
+        lang: BindingsLang,
+        krate: RustBindingsCrate,
+    ) -> Result<Vec<String>, String> {
+        let compiler = self.compiler_builder().build();
+        let file = Compiler::fetch_sources(vec![PathBuf::from(input)])
+            .remove(0)
+            .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+            .map_err(|e| e.to_string())?;
+        let artifact = compiler.gen_artifact(file).map_err(|e| e.to_string())?;
+        let bindings = match lang {
+            BindingsLang::Rust => Bindings::rust(&artifact, krate),
+            BindingsLang::Ts => Bindings::typescript(&artifact),
+        }
+        .map_err(|libraries| {
+            format!(
+                "\"{}\" has unlinked libraries: {}. Link them with `huffc link` first",
+                input,
+                libraries.join(", ")
+            )
+        })?;
+
+        let path = format!("{}/{}", self.outputdir, bindings.file_name);
+        bindings.export(&path).map_err(|e| format!("Failed to write \"{}\": {}", path, e))?;
+        let mut paths = vec![path];
+        if lang == BindingsLang::Ts {
+            let path = format!("{}/{}.json", self.outputdir, bindings.name);
+            HardhatArtifact::from(&artifact)
+                .export(&path)
+                .map_err(|e| format!("Failed to write \"{}\": {}", path, e))?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Writes the Solidity interface of a contract to `I<NAME>.sol` in the output directory,
+    /// returning its path
+    pub fn interface(&self, input: &str) -> Result<String, String> {
+        let compiler = self.compiler_builder().build();
+        let file = Compiler::fetch_sources(vec![PathBuf::from(input)])
+            .remove(0)
+            .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+            .map_err(|e| e.to_string())?;
+        let artifact = compiler.gen_artifact(file).map_err(|e| e.to_string())?;
+        let interface = SolidityInterface::new(&artifact);
+
+        let path = format!("{}/{}", self.outputdir, interface.file_name);
+        interface.export(&path).map_err(|e| format!("Failed to write \"{}\": {}", path, e))?;
+        Ok(path)
+    }
+
+    /// Scaffolds a Huff contract from a Solidity JSON ABI into `<NAME>.huff` in the output
+    /// directory, returning its path
+    pub fn scaffold(&self, abi: &str, name: Option<&str>) -> Result<String, String> {
+        let json =
+            fs::read_to_string(abi).map_err(|e| format!("Failed to read \"{}\": {}", abi, e))?;
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => {
+                let name = Path::new(abi).file_name().unwrap_or_default().to_string_lossy();
+                name.split('.').next().unwrap_or_default().to_string()
+            }
+        };
+        let scaffold = Scaffold::parse(&name, &json)
+            .map_err(|e| format!("Invalid ABI \"{}\": {}", abi, e))?;
+
+        fs::create_dir_all(&self.outputdir)
+            .map_err(|e| format!("Failed to create \"{}\": {}", self.outputdir, e))?;
+        let path = format!("{}/{}.huff", self.outputdir, name);
+        fs::write(&path, scaffold.source())
+            .map_err(|e| format!("Failed to write \"{}\": {}", path, e))?;
+        Ok(path)
+    }
+
+    /// Writes the manifest of a library to `<NAME>.manifest.json` in the output directory,
+    /// returning its path
+    pub fn package(&self, input: &str) -> Result<String, String> {
+        let compiler = self.compiler_builder().build();
+        let file = Compiler::fetch_sources(vec![PathBuf::from(input)])
+            .remove(0)
+            .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+            .map_err(|e| e.to_string())?;
+        let manifest = compiler.package(file).map_err(|e| e.to_string())?;
+
+        let path = format!("{}/{}.manifest.json", self.outputdir, manifest.name);
+        manifest.export(&path).map_err(|e| format!("Failed to write \"{}\": {}", path, e))?;
+        Ok(path)
+    }
+
+    /// Writes the documentation of a contract or library to the output directory
+    pub fn doc(&self, input: &str, format: DocFormat) -> Result<String, String> {
+        let compiler = self.compiler_builder().build();
+        let file = Compiler::fetch_sources(vec![PathBuf::from(input)])
+            .remove(0)
+            .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+            .map_err(|e| e.to_string())?;
+        let docs = compiler.docs(file).map_err(|e| e.to_string())?;
+
+        let extension = match format {
+            DocFormat::Markdown => "md",
+            DocFormat::Html => "html",
+        };
+        let path = format!("{}/{}.{}", self.outputdir, docs.name, extension);
+        docs.export(&path).map_err(|e| format!("Failed to write \"{}\": {}", path, e))?;
+        Ok(path)
+    }
+
+    /// Writes the verification bundle of a contract to `<NAME>.verify.json` in the output
+    /// directory, returning its path
+    pub fn verify_bundle(&self, input: &str, format: BundleFormat) -> Result<String, String> {
+        let mut builder = self.compiler_builder();
+        if let Some(inputs) = &self.inputs {
+            builder = builder.constructor_args(inputs.clone());
+        }
+        let compiler = builder.build();
+        let file = Compiler::fetch_sources(vec![PathBuf::from(input)])
+            .remove(0)
+            .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+            .map_err(|e| e.to_string())?;
+        let name = Path::new(input).file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let bundle = compiler
+            .verification_bundle(file, format == BundleFormat::Flattened, env!("CARGO_PKG_VERSION"))
+            .map_err(|e| e.to_string())?;
+
+        let path = format!("{}/{}.verify.json", self.outputdir, name);
+        bundle.export(&path).map_err(|e| format!("Failed to write \"{}\": {}", path, e))?;
+        Ok(path)
+    }
+
+    /// Renders the dispatcher of a contract's runtime bytecode, as a list of the dispatched
+    /// functions or as a decision tree
+    ///
+    /// With `four_byte`, also writes the function signatures to `<NAME>.4byte.txt` in the output
+    /// directory, returning its path.
+    pub fn selectors(
+        &self,
+        input: &str,
+        tree: bool,
+        format: SelectorsFormat,
+        four_byte: bool,
+    ) -> Result<(String, Option<String>), String> {
+        let compiler = self.compiler_builder().build();
+        let file = Compiler::fetch_sources(vec![PathBuf::from(input)])
+            .remove(0)
+            .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+            .map_err(|e| e.to_string())?;
+        let name = Path::new(input).file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let dispatcher = compiler.dispatcher(file).map_err(|e| e.to_string())?;
+        let out = match format {
+            _ if tree => dispatcher.tree(),
+            SelectorsFormat::List => dispatcher.list(),
+            SelectorsFormat::Table => dispatcher.table(),
+            SelectorsFormat::Json => dispatcher.to_json(),
+        };
+        if !four_byte {
+            return Ok((out, None))
+        }
+
+        fs::create_dir_all(&self.outputdir)
+            .map_err(|e| format!("Failed to create \"{}\": {}", self.outputdir, e))?;
+        let path = format!("{}/{}.4byte.txt", self.outputdir, name);
+        fs::write(&path, dispatcher.signatures())
+            .map_err(|e| format!("Failed to write \"{}\": {}", path, e))?;
+        Ok((out, Some(path)))
+    }
+
+    /// Renders the macro call graph of a contract in the given format
+    pub fn graph(&self, input: &str, format: GraphFormat) -> Result<String, String> {
+        let compiler = self.compiler_builder().build();
+        let file = Compiler::fetch_sources(vec![PathBuf::from(input)])
+            .remove(0)
+            .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+            .map_err(|e| e.to_string())?;
+        let graph = compiler.call_graph(file).map_err(|e| e.to_string())?;
+        Ok(match format {
+            GraphFormat::Dot => graph.to_dot(),
+            GraphFormat::Json => graph.to_json(),
+        })
+    }
+
+    /// Disassembles the bytecode of an artifact, a hex file, stdin or hex input
+    ///
+    /// The runtime bytecode of artifacts is disassembled with the source map of their source, so
+    /// `source` interleaves the source lines generating the instructions.
+    pub fn disasm(&self, input: Option<&str>, source: bool) -> Result<String, String> {
+        let (code, artifact) = Huff::read_bytecode(input)?;
+        let Some(artifact) = artifact else {
+            if source {
+                return Err("Source lines can only be interleaved for compiled artifacts".to_string())
+            }
+            return Ok(Disassembly::new(&code).render(false))
+        };
+
+        let compiler = self.compiler_builder().build();
+        let source_map =
+            compiler.runtime_source_map(Arc::clone(&artifact.file)).map_err(|e| e.to_string())?;
+        let mapped = source_map.iter().map(|m| m.offset + m.size).max().unwrap_or_default();
+        if mapped != code.len() {
+            return Err(format!(
+                "The source of artifact \"{}\" doesn't generate its {} byte runtime bytecode",
+                input.unwrap_or_default(),
+                code.len()
+            ))
+        }
+        Ok(Disassembly::with_source_map(&code, &source_map).render(source))
+    }
+
+    /// Decompiles the bytecode of an artifact, a hex file, stdin or hex input into a Huff
+    /// skeleton, returning the paths of the files written to the output directory
+    ///
+    /// The skeleton is written to `<NAME>.decompiled.huff`, and the raw data of its tables to
+    /// `<NAME>.<TABLE>.bin` files alongside.
+    pub fn decompile(
+        &self,
+        input: Option<&str>,
+        name: Option<&str>,
+    ) -> Result<Vec<String>, String> {
+        let (code, _) = Huff::read_bytecode(input)?;
+        let name = match (name, input.filter(|i| Path::new(i).is_file())) {
+            (Some(name), _) => name.to_string(),
+            (None, Some(path)) => {
+                let name = Path::new(path).file_name().unwrap_or_default().to_string_lossy();
+                let stem = name.split('.').next().unwrap_or_default();
+                stem.chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+                    .collect::<String>()
+            }
+            (None, None) => "DECOMPILED".to_string(),
+        };
+        let decompilation = Decompilation::new(&name, &code);
+
+        fs::create_dir_all(&self.outputdir)
+            .map_err(|e| format!("Failed to create \"{}\": {}", self.outputdir, e))?;
+        let mut paths = vec![];
+        for table in decompilation.tables.iter() {
+            let path = format!("{}/{}", self.outputdir, decompilation.table_file(table));
+            fs::write(&path, &table.data)
+                .map_err(|e| format!("Failed to write \"{}\": {}", path, e))?;
+            paths.push(path);
+        }
+        let path = format!("{}/{}.decompiled.huff", self.outputdir, name);
+        fs::write(&path, decompilation.source())
+            .map_err(|e| format!("Failed to write \"{}\": {}", path, e))?;
+        paths.insert(0, path);
+        Ok(paths)
+    }
+
+    /// Debugs a call to a contract in the terminal, with breakpoints on labels and macros
+    ///
+    /// The contract is compiled and deployed by its constructor, then the call is traced on the
+    /// deployed runtime bytecode, mapped to the source by the source map of the contract.
+    pub fn debug(
+        &self,
+        input: &str,
+        calldata: &str,
+        value: u128,
+        breakpoints: &[String],
+    ) -> Result<(), String> {
+        let (initcode, source_map, calldata) = self.compile_call(input, calldata)?;
+        let mut debugger =
+            Debugger::launch(&initcode, source_map, &calldata, value, self.evm_version)?;
+        for name in breakpoints {
+            debugger.toggle_breakpoint(name)?;
+        }
+        tui::run(&mut debugger).map_err(|e| format!("Failed to run the debugger: {}", e))
+    }
+
+    /// Profiles the gas of a call to a contract, rendered as a breakdown by macro and source
+    /// line, or in the folded format of flame graph tools
+    pub fn profile(
+        &self,
+        input: &str,
+        calldata: &str,
+        value: u128,
+        folded: bool,
+    ) -> Result<String, String> {
+        let (initcode, source_map, calldata) = self.compile_call(input, calldata)?;
+        let profile = GasProfile::run(&initcode, &source_map, &calldata, value, self.evm_version)?;
+        Ok(if folded { profile.folded() } else { profile.render() })
+    }
+
+    /// Runs the tests of a contract, returning their results and whether they all passed
+    ///
+    /// The runs and seed of the command line override those of the config. Without a seed, a
+    /// random one is printed so failures can be reproduced.
+    pub fn test(
+        &self,
+        input: &str,
+        runs: Option<usize>,
+        seed: Option<u64>,
+        pattern: Option<&str>,
+    ) -> Result<(String, bool), String> {
+        let config = self
+            .get_config()
+            .map_err(|e| format!("Failed to load config \"{}\": {}", self.config, e))?;
+        let compiler = self.compiler_builder().build();
+        let file = Compiler::fetch_sources(vec![PathBuf::from(input)])
+            .remove(0)
+            .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+            .map_err(|e| e.to_string())?;
+        let mut tests = compiler.tests(file).map_err(|e| e.to_string())?;
+        tests.retain(|t| pattern.is_none_or(|p| t.name.contains(p)));
+        if tests.is_empty() {
+            return Err(format!("No tests found in \"{}\"", input))
+        }
+
+        let seed = seed.or(config.fuzz.seed).unwrap_or_else(|| {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+            now.as_nanos() as u64
+        });
+        let runner = TestRunner {
+            runs: runs.or(config.fuzz.runs).unwrap_or(DEFAULT_RUNS),
+            seed,
+            evm_version: self.evm_version,
+        };
+        let results = runner.run_all(&tests)?;
+        let plural = if tests.len() == 1 { "" } else { "s" };
+        let out = format!(
+            "Running {} test{} for {} (seed: {})\n{}",
+            tests.len(),
+            plural,
+            input,
+            seed,
+            render_results(&results)
+        );
+        Ok((out, results.iter().all(|r| r.passed())))
+    }
+
+    /// Compiles a contract to call, returning its creation bytecode, the source map of its
+    /// runtime bytecode and the decoded calldata of the call
+    fn compile_call(&self, input: &str, calldata: &str) -> Result<CompiledCall, String> {
+        let calldata = Huff::decode_hex(calldata)
+            .ok_or_else(|| format!("Invalid calldata \"{}\", expected hex bytes", calldata))?;
+        let compiler = self.compiler_builder().build();
+        let file = Compiler::fetch_sources(vec![PathBuf::from(input)])
+            .remove(0)
+            .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+            .map_err(|e| e.to_string())?;
+        let artifact = compiler.gen_artifact(Arc::clone(&file)).map_err(|e| e.to_string())?;
+        let source_map = compiler.runtime_source_map(file).map_err(|e| e.to_string())?;
+        let initcode = str_to_vec(&artifact.bytecode).map_err(|e| e.to_string())?;
+        Ok((initcode, source_map, calldata))
+    }
+
+    /// Reads bytecode from an artifact, a hex file, stdin if the input is omitted or `-`, or the
+    /// hex input itself, along with the artifact it was read from
+    ///
+    /// The runtime bytecode of artifacts is read.
+    fn read_bytecode(input: Option<&str>) -> Result<(Vec<u8>, Option<Artifact>), String> {
+        let input = input.filter(|i| *i != "-");
+        let hex = match input {
+            Some(i) if Path::new(i).extension().unwrap_or_default().eq("json") => {
+                let artifact = Artifact::import(i)
+                    .map_err(|e| format!("Failed to read artifact \"{}\": {}", i, e))?;
+                let code = Huff::decode_hex(&artifact.runtime)
+                    .ok_or_else(|| format!("Invalid runtime bytecode in artifact \"{}\"", i))?;
+                return Ok((code, Some(artifact)))
+            }
+            Some(i) if Path::new(i).is_file() => {
+                fs::read_to_string(i).map_err(|e| format!("Failed to read \"{}\": {}", i, e))?
+            }
+            Some(i) => i.to_string(),
+            None => {
+                let mut hex = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut hex)
+                    .map_err(|e| format!("Failed to read stdin: {}", e))?;
+                hex
+            }
+        };
+        let code = Huff::decode_hex(&hex).ok_or_else(|| "Invalid hex bytecode".to_string())?;
+        Ok((code, None))
+    }
+
+    /// Decodes hex bytes, optionally 0x prefixed and surrounded by whitespace
+    fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+        let hex = hex.trim();
+        let hex = hex.strip_prefix("0x").unwrap_or(hex);
+        if !hex.len().is_multiple_of(2) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None
+        }
+        str_to_vec(hex).ok()
+    }
+
+    /// Writes the compilation statistics of the huff files at the given paths to `stats.json` in
+    /// the output directory, returning its path
+    pub fn stats(&self, paths: &[String]) -> Result<String, String> {
+        let paths = if paths.is_empty() { std::slice::from_ref(&self.source) } else { paths };
+        let file_paths = Compiler::transform_paths(&paths.to_vec()).map_err(|e| e.to_string())?;
+        let compiler = self.compiler_builder().build();
+        let mut stats = CompileStats::default();
+        for file in Compiler::fetch_sources(file_paths) {
+            let file = file
+                .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+                .map_err(|e| e.to_string())?;
+            stats.files.push(compiler.stats(file).map_err(|e| e.to_string())?);
+        }
+
+        let path = format!("{}/stats.json", self.outputdir);
+        stats.export(&path).map_err(|e| format!("Failed to write \"{}\": {}", path, e))?;
+        Ok(path)
+    }
+
+    /// Lints the huff files at the given paths, returning whether no warning was denied
+    ///
+    /// Warnings of files included by several paths are reported once. With `fix`, the fixes of
+    /// the warnings are applied to their files, and only the unfixed warnings are reported.
+    pub fn lint(&self, paths: &[String], fix: bool) -> Result<bool, String> {
+        let paths = if paths.is_empty() { std::slice::from_ref(&self.source) } else { paths };
+        let file_paths = Compiler::transform_paths(&paths.to_vec()).map_err(|e| e.to_string())?;
+        let config = self
+            .get_config()
+            .map_err(|e| format!("Failed to load config \"{}\": {}", self.config, e))?;
+        let compiler = self.compiler_builder().lint_levels(self.get_lint_levels(&config)?).build();
+
+        // Warnings of included files are found again by every file including them
+        let key = |w: &CompilerWarning| {
+            let spans = w.span.0.iter().map(|s| (s.file.as_ref().map(|f| &f.path), s.start, s.end));
+            format!("{:?}{:?}", w.kind, spans.collect::<Vec<_>>())
+        };
+        let mut warnings: Vec<(CompilerWarning, bool)> = vec![];
+        let mut seen = BTreeSet::new();
+        for file in Compiler::fetch_sources(file_paths) {
+            let file = file
+                .and_then(|f| compiler.resolve_deps(f).map_err(|e| (*e).clone()))
+                .map_err(|e| e.to_string())?;
+            let (reported, denied) = compiler.lint(file).map_err(|e| e.to_string())?;
+            let found = reported.into_iter().map(|w| (w, false));
+            for (w, denied) in found.chain(denied.into_iter().map(|w| (w, true))) {
+                if seen.insert(key(&w)) {
+                    warnings.push((w, denied));
+                }
+            }
+        }
+
+        if fix {
+            let mut fixes: BTreeMap<String, Vec<&Fix>> = BTreeMap::new();
+            for f in warnings.iter().filter_map(|(w, _)| w.fix.as_ref()) {
+                if let Some(file) = &f.span.file {
+                    fixes.entry(file.path.clone()).or_default().push(f);
+                }
+            }
+            for (path, file_fixes) in fixes.iter() {
+                let source = fs::read_to_string(path)
+                    .map_err(|e| format!("Failed to read \"{}\": {}", path, e))?;
+                fs::write(path, apply_fixes(&source, file_fixes))
+                    .map_err(|e| format!("Failed to write \"{}\": {}", path, e))?;
+                let plural = if file_fixes.len() == 1 { "" } else { "s" };
+                println!("Fixed {} warning{} in {}", file_fixes.len(), plural, path);
+            }
+            warnings.retain(|(w, _)| w.fix.as_ref().and_then(|f| f.span.file.as_ref()).is_none());
+        }
+
+        warnings.iter().for_each(|(w, _)| self.error_format.emit_warning(w));
+        self.error_format.emit_warning_summary(warnings.len());
+        let denied = warnings.iter().filter(|(_, denied)| *denied).count();
+        if denied > 0 {
+            let plural = if denied == 1 { "" } else { "s" };
+            eprintln!("{}", Paint::red(format!("{} warning{} denied", denied, plural)));
+        }
+        Ok(denied == 0)
+    }
+
+    /// Links library addresses into a compiled artifact, writing it to the output directory
+    ///
+    /// Returns the path of the linked artifact, and the libraries it still links.
+    pub fn link(&self, path: &str, libraries: &[String]) -> Result<(String, Vec<String>), String> {
+        let mut artifact = Artifact::import(path)
+            .map_err(|e| format!("Failed to read artifact \"{}\": {}", path, e))?;
+        let mut addresses = BTreeMap::new();
+        for library in libraries {
+            let (name, address) = library
+                .split_once('=')
+                .ok_or_else(|| format!("Expected NAME=ADDRESS, got \"{}\"", library))?;
+            let hex = address.strip_prefix("0x").unwrap_or(address);
+            if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("Invalid address \"{}\" of library \"{}\"", address, name))
+            }
+            if !artifact.link_references.contains_key(name) &&
+                !artifact.runtime_link_references.contains_key(name)
+            {
+                return Err(format!("Artifact \"{}\" doesn't link library \"{}\"", path, name))
+            }
+            addresses.insert(name.to_string(), hex.to_string());
+        }
+        artifact.link(&addresses);
+
+        let file_name = Path::new(path).file_name().unwrap_or_default().to_string_lossy();
+        let out = format!("{}/{}", self.outputdir, file_name);
+        artifact.export(&out).map_err(|e| format!("Failed to write \"{}\": {}", out, e))?;
+        let unlinked =
+            artifact.link_references.keys().chain(artifact.runtime_link_references.keys());
+        Ok((out, unlinked.cloned().collect::<BTreeSet<_>>().into_iter().collect()))
+    }
+
+    /// Diffs the ABIs of two compiled artifacts
+    pub fn abi_diff(old: &str, new: &str) -> Result<AbiDiff, String> {
+        let abi = |path: &str| {
+            Artifact::import(path)
+                .map_err(|e| format!("Failed to read artifact \"{}\": {}", path, e))?
+                .abi
+                .ok_or_else(|| format!("Artifact \"{}\" has no ABI", path))
+        };
+        Ok(AbiDiff::new(&abi(old)?, &abi(new)?))
+    }
+
+    /// Formats the file, line and column of a span
+    fn span_location(span: &Span) -> String {
+        match (&span.file, span.start_position()) {
+            (Some(f), Some(p)) => format!("-> {}:{}:{}", f.path, p.line, p.column),
+            _ => format!("-> {}..{}", span.start, span.end),
+        }
+    }
+
+    /// Formats the huff files at the given paths, returning whether all files were formatted
+    ///
+    /// If `check` is set, files are left untouched and the unformatted files are reported.
+    pub fn format(&self, paths: &[String], check: bool) -> Result<bool, CompilerError> {
+        let paths = if paths.is_empty() { std::slice::from_ref(&self.source) } else { paths };
+        let mut files = vec![];
+        for path in paths {
+            if Path::new(path).extension().unwrap_or_default().eq("huff") {
+                files.push(path.clone());
+            } else {
+                files.extend(unpack_files(path).map_err(CompilerError::FileUnpackError)?);
+            }
+        }
+
+        let mut formatted = true;
+        for file in files {
+            tracing::debug!(target: "fmt", "FORMATTING FILE: {}", file);
+            let source = fs::read_to_string(&file).map_err(|_| {
+                CompilerError::FileUnpackError(UnpackError::MissingFile(file.clone()))
+            })?;
+            let output = Lexer::format_source(&source);
+            if output == source {
+                continue
+            }
+            if check {
+                formatted = false;
+                eprintln!("{}", Paint::yellow(format!("Unformatted file: {}", file)));
+            } else if fs::write(&file, output).is_err() {
+                return Err(CompilerError::FileUnpackError(UnpackError::InvalidDirectory(file)))
+            } else {
+                println!("Formatted {}", file);
+            }
+        }
+        Ok(formatted)
+    }
 }
@@ -0,0 +1,182 @@
+//! ## Custom Builtins
+//!
+//! Builtins registered by library consumers, so domain-specific code generation, like the calls
+//! to the precompiles of a rollup, can live outside of the compiler.
+//!
+//! A custom builtin is invoked in macro bodies like a macro, as in `__L1_BLOCK(0x01)`, and parsed
+//! as a [BuiltinFunctionCall] when the parser knows its name. Before the macros are expanded,
+//! [lower_builtins] checks the arguments of each call against the parameters the builtin declares
+//! and replaces the call with the statements the builtin expands to, which are then generated
+//! like any other statement. Expanded statements without a span are given the span of the call,
+//! so errors and source maps point back at it.
+
+use std::{fmt::Debug, sync::Arc};
+
+use huff_utils::{
+    ast::{
+        AstSpan, BuiltinFunctionCall, BuiltinFunctionKind, Conditional, Contract, Label, MacroArg,
+        MacroDefinition, Repeat, Statement, StatementType,
+    },
+    error::CodegenError,
+    prelude::CodegenErrorKind,
+    visit::{walk_conditional_mut, walk_label_mut, walk_macro_mut, walk_repeat_mut, VisitMut},
+};
+
+/// The kind of argument a custom builtin parameter accepts
+///
+/// Arg calls, only resolved when the macro is expanded, are accepted for any parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinParam {
+    /// A literal, as in `0x01`
+    Literal,
+    /// A constant, as in `[OWNER]`
+    Constant,
+    /// An identifier, like a label, macro or table name
+    Ident,
+    /// A literal or a constant
+    Value,
+}
+
+impl BuiltinParam {
+    /// Whether the parameter accepts an argument
+    pub fn accepts(&self, arg: &MacroArg) -> bool {
+        matches!(
+            (self, arg),
+            (_, MacroArg::ArgCall(_)) |
+                (BuiltinParam::Literal | BuiltinParam::Value, MacroArg::Literal(_)) |
+                (BuiltinParam::Constant | BuiltinParam::Value, MacroArg::Constant(_)) |
+                (BuiltinParam::Ident, MacroArg::Ident(_))
+        )
+    }
+}
+
+impl std::fmt::Display for BuiltinParam {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuiltinParam::Literal => write!(f, "a literal"),
+            BuiltinParam::Constant => write!(f, "a constant"),
+            BuiltinParam::Ident => write!(f, "an identifier"),
+            BuiltinParam::Value => write!(f, "a literal or a constant"),
+        }
+    }
+}
+
+/// A builtin registered by a library consumer, expanded to statements before code generation
+pub trait CustomBuiltin: Debug + Send + Sync {
+    /// The name the builtin is invoked by, conventionally in screaming snake case prefixed with
+    /// `__`
+    fn name(&self) -> &str;
+
+    /// The kinds of the arguments the builtin takes
+    fn params(&self) -> &[BuiltinParam];
+
+    /// Expands a call, with arguments matching the parameters, to the statements generated in
+    /// its place
+    fn expand(
+        &self,
+        args: &[MacroArg],
+        span: &AstSpan,
+        contract: &Contract,
+    ) -> Result<Vec<Statement>, CodegenError>;
+}
+
+/// Replaces the calls to custom builtins in the macros of a contract with their expansions,
+/// returning the errors of the calls that failed to expand
+pub fn lower_builtins(
+    contract: &mut Contract,
+    builtins: &[Arc<dyn CustomBuiltin>],
+) -> Vec<CodegenError> {
+    if builtins.is_empty() {
+        return vec![]
+    }
+    let mut lowering = BuiltinLowering { builtins, contract: contract.clone(), errors: vec![] };
+    lowering.visit_contract_mut(contract);
+    lowering.errors
+}
+
+/// Expands the calls to custom builtins in each block of statements
+struct BuiltinLowering<'a> {
+    builtins: &'a [Arc<dyn CustomBuiltin>],
+    contract: Contract,
+    errors: Vec<CodegenError>,
+}
+
+impl BuiltinLowering<'_> {
+    /// Expands the calls of a block, leaving the other statements in place
+    fn lower(&mut self, statements: &mut Vec<Statement>) {
+        for statement in std::mem::take(statements) {
+            match statement.ty {
+                StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                    kind: BuiltinFunctionKind::Custom(name, args),
+                    span,
+                    ..
+                }) => match self.expand(&name, &args, &span) {
+                    Ok(expanded) => statements.extend(expanded),
+                    Err(e) => self.errors.push(e),
+                },
+                _ => statements.push(statement),
+            }
+        }
+    }
+
+    /// Checks the arguments of a call, then expands it
+    fn expand(
+        &self,
+        name: &str,
+        args: &[MacroArg],
+        span: &AstSpan,
+    ) -> Result<Vec<Statement>, CodegenError> {
+        let invalid = |help: String| CodegenError {
+            kind: CodegenErrorKind::InvalidBuiltinArgument(name.to_string()),
+            span: span.clone(),
+            token: None,
+            help: Some(help),
+        };
+        let Some(builtin) = self.builtins.iter().find(|b| b.name() == name) else {
+            return Err(invalid(format!("no builtin named \"{}\" is registered", name)))
+        };
+        let params = builtin.params();
+        if args.len() != params.len() {
+            tracing::error!(target: "codegen", "CUSTOM BUILTIN \"{}\" TAKES {} ARGUMENTS, GOT {}", name, params.len(), args.len());
+            return Err(invalid(format!("takes {} arguments, got {}", params.len(), args.len())))
+        }
+        if let Some((i, (param, _))) =
+            params.iter().zip(args).enumerate().find(|(_, (p, a))| !p.accepts(a))
+        {
+            tracing::error!(target: "codegen", "CUSTOM BUILTIN \"{}\" ARGUMENT {} ISN'T {}", name, i, param);
+            return Err(invalid(format!("argument {} must be {}", i + 1, param)))
+        }
+
+        tracing::info!(target: "codegen", "EXPANDING CUSTOM BUILTIN \"{}\"", name);
+        let mut expanded = builtin.expand(args, span, &self.contract).map_err(|mut e| {
+            if e.span.0.is_empty() {
+                e.span = span.clone();
+            }
+            e
+        })?;
+        expanded.iter_mut().filter(|s| s.span.0.is_empty()).for_each(|s| s.span = span.clone());
+        Ok(expanded)
+    }
+}
+
+impl VisitMut for BuiltinLowering<'_> {
+    fn visit_macro_mut(&mut self, macro_def: &mut MacroDefinition) {
+        self.lower(&mut macro_def.statements);
+        walk_macro_mut(self, macro_def)
+    }
+
+    fn visit_label_mut(&mut self, label: &mut Label) {
+        self.lower(&mut label.inner);
+        walk_label_mut(self, label)
+    }
+
+    fn visit_repeat_mut(&mut self, repeat: &mut Repeat) {
+        self.lower(&mut repeat.inner);
+        walk_repeat_mut(self, repeat)
+    }
+
+    fn visit_conditional_mut(&mut self, conditional: &mut Conditional) {
+        self.lower(&mut conditional.inner);
+        walk_conditional_mut(self, conditional)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::irgen::constants::constant_gen;
 use huff_utils::prelude::*;
 use std::str::FromStr;
 
@@ -13,7 +14,7 @@ pub fn bubble_arg_call(
     bytes: &mut Vec<(usize, Bytes)>,
     macro_def: &MacroDefinition,
     contract: &Contract,
-    scope: &mut Vec<MacroDefinition>,
+    scope: &mut [MacroId],
     offset: &mut usize,
     // mis: Parent macro invocations and their indices
     mis: &mut Vec<(usize, MacroInvocation)>,
@@ -22,14 +23,10 @@ pub fn bubble_arg_call(
     let starting_offset = *offset;
 
     // Check Constant Definitions
-    if let Some(constant) = contract.constants.iter().find(|const_def| const_def.name.eq(arg_name))
-    {
-        tracing::info!(target: "codegen", "ARGCALL IS CONSTANT: {:?}", constant);
-        let push_bytes = match &constant.value {
-            ConstVal::Literal(l) => {
-                let hex_literal: String = bytes32_to_string(l, false);
-                format!("{:02x}{}", 95 + hex_literal.len() / 2, hex_literal)
-            }
+    if let Some(value) = contract.find_constant_value(arg_name.into()) {
+        tracing::info!(target: "codegen", "ARGCALL IS CONSTANT: {:?}", value);
+        let push_bytes = match &value {
+            ConstVal::Literal(l) => Bytes::push(l),
             ConstVal::FreeStoragePointer(fsp) => {
                 // If this is reached in codegen stage,
                 // `derive_storage_pointers`
@@ -39,16 +36,17 @@ pub fn bubble_arg_call(
                     kind: CodegenErrorKind::StoragePointersNotDerived,
                     span: AstSpan(vec![]),
                     token: None,
+                    help: None,
                 })
             }
         };
-        *offset += push_bytes.len() / 2;
-        tracing::info!(target: "codegen", "OFFSET: {}, PUSH BYTES: {:?}", offset, push_bytes);
-        bytes.push((starting_offset, Bytes(push_bytes)));
+        *offset += push_bytes.len();
+        tracing::info!(target: "codegen", "OFFSET: {}, PUSH BYTES: {}", offset, push_bytes);
+        bytes.push((starting_offset, push_bytes));
     } else if let Ok(o) = Opcode::from_str(arg_name) {
         // Check Opcode Definition
-        let b = Bytes(o.to_string());
-        *offset += b.0.len() / 2;
+        let b = Bytes::from(o);
+        *offset += b.len();
         tracing::info!(target: "codegen", "RECURSE_BYTECODE ARG CALL FOUND OPCODE: {:?}", b);
         bytes.push((starting_offset, b));
     } else if let Some(macro_invoc) = mis.last() {
@@ -57,7 +55,7 @@ pub fn bubble_arg_call(
         if let Some(pos) = macro_def
             .parameters
             .iter()
-            .position(|r| r.name.as_ref().map_or(false, |s| s.eq(arg_name)))
+            .position(|r| r.name.as_ref().is_some_and(|s| s.eq(arg_name)))
         {
             tracing::info!(target: "codegen", "GOT \"{}\" POS IN ARG LIST: {}", arg_name, pos);
 
@@ -67,18 +65,17 @@ pub fn bubble_arg_call(
                     MacroArg::Literal(l) => {
                         tracing::info!(target: "codegen", "GOT LITERAL {} ARG FROM MACRO INVOCATION", bytes32_to_string(l, false));
 
-                        let hex_literal: String = bytes32_to_string(l, false);
-                        let push_bytes =
-                            format!("{:02x}{}", 95 + hex_literal.len() / 2, hex_literal);
-                        let b = Bytes(push_bytes);
-                        *offset += b.0.len() / 2;
+                        let b = Bytes::push(l);
+                        *offset += b.len();
                         bytes.push((starting_offset, b));
                     }
                     MacroArg::ArgCall(ac) => {
                         tracing::info!(target: "codegen", "GOT ARG CALL \"{}\" ARG FROM MACRO INVOCATION", ac);
                         tracing::debug!(target: "codegen", "~~~ BUBBLING UP ARG CALL");
+                        // The parent macro knows the argument by its own parameter name
+                        let bubbled_arg = ac.clone();
                         let mut new_scope = Vec::from(&scope[..scope.len().saturating_sub(1)]);
-                        let bubbled_macro_invocation = new_scope.last().unwrap().clone();
+                        let bubbled_macro_invocation = &contract.macros[*new_scope.last().unwrap()];
                         tracing::debug!(target: "codegen", "BUBBLING UP WITH MACRO DEF: {}", bubbled_macro_invocation.name);
                         tracing::debug!(target: "codegen", "CURRENT MACRO DEF: {}", macro_def.name);
 
@@ -89,18 +86,19 @@ pub fn bubble_arg_call(
                             None => {
                                 return Err(CodegenError {
                                     kind: CodegenErrorKind::MissingMacroInvocation(
-                                        macro_def.name.clone(),
+                                        macro_def.name.to_string(),
                                     ),
-                                    span: bubbled_macro_invocation.span,
+                                    span: bubbled_macro_invocation.span.clone(),
                                     token: None,
+                                    help: None,
                                 })
                             }
                         };
                         return if last_mi.1.macro_name.eq(&macro_def.name) {
                             bubble_arg_call(
-                                arg_name,
+                                &bubbled_arg,
                                 bytes,
-                                &bubbled_macro_invocation,
+                                bubbled_macro_invocation,
                                 contract,
                                 &mut new_scope,
                                 offset,
@@ -109,9 +107,9 @@ pub fn bubble_arg_call(
                             )
                         } else {
                             bubble_arg_call(
-                                arg_name,
+                                &bubbled_arg,
                                 bytes,
-                                &bubbled_macro_invocation,
+                                bubbled_macro_invocation,
                                 contract,
                                 &mut new_scope,
                                 offset,
@@ -120,17 +118,50 @@ pub fn bubble_arg_call(
                             )
                         }
                     }
+                    MacroArg::Constant(name) => {
+                        tracing::info!(target: "codegen", "GOT CONSTANT \"{}\" ARG FROM MACRO INVOCATION", name);
+
+                        // Constants are resolved against the whole contract, so a library macro
+                        // can be configured by a constant of the file importing it
+                        let push_bytes = constant_gen(name, contract, macro_invoc.1.span.clone())
+                            .map_err(|mut e| {
+                                let chain = scope
+                                    .iter()
+                                    .map(|id| contract.macros[*id].name.as_str())
+                                    .collect::<Vec<&str>>()
+                                    .join(" > ");
+                                let hint = format!(
+                                    "\"{}\" is passed as the \"{}\" argument of \"{}\", expanded as {}; define it in the contract including \"{}\"",
+                                    name, arg_name, macro_invoc.1.macro_name, chain, macro_invoc.1.macro_name
+                                );
+                                e.help = Some(match e.help {
+                                    Some(help) => format!("{} {}", help, hint),
+                                    None => hint,
+                                });
+                                e
+                            })?;
+                        *offset += push_bytes.len();
+                        bytes.push((starting_offset, push_bytes));
+                    }
                     MacroArg::Ident(iden) => {
                         tracing::debug!(target: "codegen", "FOUND IDENT ARG IN \"{}\" MACRO INVOCATION: \"{}\"!", macro_invoc.1.macro_name, iden);
                         tracing::debug!(target: "codegen", "Macro invocation index: {}", macro_invoc.0);
                         tracing::debug!(target: "codegen", "At offset: {}", *offset);
 
-                        // This should be equivalent to a label call.
-                        bytes.push((*offset, Bytes(format!("{}xxxx", Opcode::Push2))));
+                        // Opcodes are passed through as is
+                        if let Ok(o) = Opcode::from_str(iden) {
+                            let b = Bytes::from(o);
+                            *offset += b.len();
+                            bytes.push((starting_offset, b));
+                            return Ok(())
+                        }
+
+                        // Otherwise this should be equivalent to a label call.
+                        bytes.push((*offset, Bytes::placeholder()));
                         jump_table.insert(
                             *offset,
                             vec![Jump {
-                                label: iden.to_owned(),
+                                label: iden.into(),
                                 bytecode_index: 0,
                                 span: macro_invoc.1.span.clone(),
                             }],
@@ -139,10 +170,30 @@ pub fn bubble_arg_call(
                     }
                 }
             } else {
-                tracing::warn!(target: "codegen", "\"{}\" FOUND IN MACRO DEF BUT NOT IN MACRO INVOCATION!", arg_name);
+                tracing::error!(target: "codegen", "\"{}\" FOUND IN MACRO DEF BUT NOT IN MACRO INVOCATION!", arg_name);
+                return Err(CodegenError {
+                    kind: CodegenErrorKind::MacroArgCountMismatch(macro_def.name.to_string()),
+                    span: macro_invoc.1.span.clone(),
+                    token: None,
+                    help: Some(format!(
+                        "\"{}\" declares {} parameters, {} arguments were passed",
+                        macro_def.name,
+                        macro_def.parameters.len(),
+                        macro_invoc.1.args.len()
+                    )),
+                })
             }
         } else {
-            tracing::warn!(target: "codegen", "\"{}\" NOT IN ARG LIST", arg_name);
+            tracing::error!(target: "codegen", "\"{}\" NOT IN ARG LIST", arg_name);
+            return Err(CodegenError {
+                kind: CodegenErrorKind::UndeclaredArgCall(arg_name.to_string()),
+                span: macro_invoc.1.span.clone(),
+                token: None,
+                help: Some(format!(
+                    "add \"{}\" to the parameters of \"{}\" or pass it in from an enclosing macro",
+                    arg_name, macro_def.name
+                )),
+            })
         }
     } else {
         // This is a label call
@@ -153,9 +204,9 @@ pub fn bubble_arg_call(
         };
         jump_table.insert(
             mis.last().map(|mi| mi.0).unwrap_or_else(|| 0),
-            vec![Jump { label: arg_name.to_owned(), bytecode_index: 0, span: new_span }],
+            vec![Jump { label: arg_name.into(), bytecode_index: 0, span: new_span }],
         );
-        bytes.push((*offset, Bytes(format!("{}xxxx", Opcode::Push2))));
+        bytes.push((*offset, Bytes::placeholder()));
         *offset += 3;
     }
 
@@ -0,0 +1,182 @@
+use ethers_core::{types::U256, utils::keccak256};
+use huff_utils::prelude::*;
+
+use crate::{
+    irgen::{expansion::ExpansionCache, statements::check_expansion},
+    Codegen,
+};
+
+/// Checks an assertion, failing if its expression evaluates to zero
+///
+/// Comparisons that fail report the values of both sides in the help of the error.
+pub fn check_assertion(
+    expression: &Expression,
+    contract: &Contract,
+    span: &AstSpan,
+    scope: &mut Vec<MacroId>,
+    mis: &mut Vec<(usize, MacroInvocation)>,
+) -> Result<(), CodegenError> {
+    if !evaluate(expression, contract, span, scope, mis)?.is_zero() {
+        tracing::info!(target: "codegen", "VERIFIED ASSERTION {}", expression);
+        return Ok(())
+    }
+    let help = match expression {
+        Expression::Binary(operator, left, right) if operator.is_comparison() => {
+            let left = evaluate(left, contract, span, scope, mis)?;
+            let right = evaluate(right, contract, span, scope, mis)?;
+            Some(format!("the left side is {:#x}, the right side is {:#x}", left, right))
+        }
+        _ => None,
+    };
+    tracing::error!(target: "codegen", "ASSERTION FAILED: {}", expression);
+    Err(CodegenError {
+        kind: CodegenErrorKind::AssertionFailed(expression.to_string()),
+        span: span.clone(),
+        token: None,
+        help,
+    })
+}
+
+/// Evaluates a constant expression into a word
+pub fn evaluate(
+    expression: &Expression,
+    contract: &Contract,
+    span: &AstSpan,
+    scope: &mut Vec<MacroId>,
+    mis: &mut Vec<(usize, MacroInvocation)>,
+) -> Result<U256, CodegenError> {
+    let truth = |b: bool| if b { U256::one() } else { U256::zero() };
+    let value = match expression {
+        Expression::Literal(l) => U256::from_big_endian(l),
+        Expression::Constant(name) => U256::from_big_endian(&constant_value(name, contract, span)?),
+        Expression::BuiltinFunctionCall(bf) => builtin_value(bf, contract, scope, mis)?,
+        Expression::LabelOffset(label) => {
+            tracing::error!(target: "codegen", "LABEL OFFSET IN ASSERTION \"{}\"", label);
+            return Err(CodegenError {
+                kind: CodegenErrorKind::InvalidMacroStatement,
+                span: span.clone(),
+                token: None,
+                help: Some(format!(
+                    "the offset of label \"{}\" is only known once pushed, as in `__OFFSET({})`",
+                    label, label
+                )),
+            })
+        }
+        Expression::Not(e) => truth(evaluate(e, contract, span, scope, mis)?.is_zero()),
+        Expression::Binary(operator, left, right) => {
+            let left = evaluate(left, contract, span, scope, mis)?;
+            let right = evaluate(right, contract, span, scope, mis)?;
+            match operator {
+                BinaryOperator::Add => left.overflowing_add(right).0,
+                BinaryOperator::Sub => left.overflowing_sub(right).0,
+                BinaryOperator::Mul => left.overflowing_mul(right).0,
+                BinaryOperator::Div => left.checked_div(right).unwrap_or_default(),
+                BinaryOperator::Equal => truth(left == right),
+                BinaryOperator::NotEqual => truth(left != right),
+                BinaryOperator::Less => truth(left < right),
+                BinaryOperator::LessEqual => truth(left <= right),
+                BinaryOperator::Greater => truth(left > right),
+                BinaryOperator::GreaterEqual => truth(left >= right),
+                BinaryOperator::And => truth(!left.is_zero() && !right.is_zero()),
+                BinaryOperator::Or => truth(!left.is_zero() || !right.is_zero()),
+            }
+        }
+    };
+    Ok(value)
+}
+
+/// The value of a constant, its storage pointer once derived, or of a well-known constant
+///
+/// Immutables are only known at deployment, so they can't be asserted on.
+fn constant_value(
+    name: &str,
+    contract: &Contract,
+    span: &AstSpan,
+) -> Result<Literal, CodegenError> {
+    match contract.find_constant_value(name.into()) {
+        Some(ConstVal::Literal(l)) => Ok(l),
+        Some(ConstVal::FreeStoragePointer(_)) => Err(CodegenError {
+            kind: CodegenErrorKind::StoragePointersNotDerived,
+            span: span.clone(),
+            token: None,
+            help: None,
+        }),
+        None => {
+            tracing::error!(target: "codegen", "MISSING CONSTANT IN ASSERTION \"{}\"", name);
+            let help = match contract.immutables.iter().any(|i| i.name == name) {
+                true => Some(format!("'{}' is an immutable, only known at deployment", name)),
+                false => did_you_mean(name, contract.constants.iter().map(|c| c.name.as_str())),
+            };
+            Err(CodegenError {
+                kind: CodegenErrorKind::MissingConstantDefinition(name.to_string()),
+                span: span.clone(),
+                token: None,
+                help,
+            })
+        }
+    }
+}
+
+/// The value of a builtin known at compile time
+fn builtin_value(
+    bf: &BuiltinFunctionCall,
+    contract: &Contract,
+    scope: &mut Vec<MacroId>,
+    mis: &mut Vec<(usize, MacroInvocation)>,
+) -> Result<U256, CodegenError> {
+    let name = bf.args.first().and_then(|a| a.name.clone()).unwrap_or_default();
+    match &bf.kind {
+        // Table sizes are kept as their decimal digits, as codegen reads them
+        BuiltinFunctionKind::Tablesize => match contract.find_table_by_name(&name) {
+            Some(t) => Ok(U256::from(
+                bytes32_to_string(&t.size, false).parse::<usize>().unwrap_or_default(),
+            )),
+            None => {
+                tracing::error!(target: "codegen", "MISSING TABLE IN ASSERTION \"{}\"", name);
+                Err(CodegenError {
+                    kind: CodegenErrorKind::MissingMacroDefinition(name.clone()),
+                    span: bf.span.clone(),
+                    token: None,
+                    help: did_you_mean(&name, contract.tables.iter().map(|t| t.name.as_str())),
+                })
+            }
+        },
+        BuiltinFunctionKind::Codesize => {
+            let Some(id) = contract.find_macro_id((&name).into()) else {
+                tracing::error!(target: "codegen", "MISSING MACRO IN ASSERTION \"{}\"", name);
+                return Err(CodegenError {
+                    kind: CodegenErrorKind::MissingMacroDefinition(name.clone()),
+                    span: bf.span.clone(),
+                    token: None,
+                    help: did_you_mean(&name, contract.macros.iter().map(|m| m.name.as_str())),
+                })
+            };
+            // Top-level assertions expand the macro on its own, like a codehash assertion
+            let mut cache = ExpansionCache::default();
+            let mut res = match scope.is_empty() {
+                true => {
+                    Codegen::macro_to_bytecode(id, contract, &mut vec![id], 0, mis, &mut cache)?
+                }
+                false => {
+                    check_expansion(&contract.macros[id].name, &bf.span, contract, scope, mis)?;
+                    Codegen::macro_to_bytecode(id, contract, scope, 0, mis, &mut cache)?
+                }
+            };
+            if !res.errors.is_empty() {
+                return Err(res.errors.remove(0))
+            }
+            Ok(U256::from(res.bytes.iter().map(|(_, b)| b.len()).sum::<usize>()))
+        }
+        BuiltinFunctionKind::Keccak256(preimage) => Ok(U256::from_big_endian(&keccak256(preimage))),
+        BuiltinFunctionKind::RightPad(padded) => Ok(U256::from_big_endian(padded)),
+        kind => {
+            tracing::error!(target: "codegen", "BUILTIN NOT KNOWN AT COMPILE TIME: {:?}", kind);
+            Err(CodegenError {
+                kind: CodegenErrorKind::InvalidMacroStatement,
+                span: bf.span.clone(),
+                token: None,
+                help: None,
+            })
+        }
+    }
+}
@@ -1,44 +1,71 @@
+use ethers_core::utils::keccak256;
 use huff_utils::prelude::{
-    bytes32_to_string, AstSpan, CodegenError, CodegenErrorKind, ConstVal, Contract,
+    did_you_mean, AstSpan, Bytes, CodegenError, CodegenErrorKind, ConstVal, Contract, Opcode,
+    WellKnownConstant,
 };
 
+/// The push of an immutable until it is relocated, unique to its name
+pub fn immutable_marker(name: &str) -> Bytes {
+    Bytes([&[Opcode::Push32.byte()][..], &keccak256(name)].concat())
+}
+
+/// Relocates the push of an immutable into a zeroed placeholder, returning the immutable name
+///
+/// Immutables are pushed with a marker, so that pushes passed through macro arguments are
+/// relocated too.
+pub fn relocate_immutable(bytes: &mut Bytes, contract: &Contract) -> Option<String> {
+    let immutable = contract.immutables.iter().find(|i| immutable_marker(&i.name) == *bytes)?;
+    bytes.0[1..].fill(0);
+    Some(immutable.name.clone())
+}
+
 /// Transforms a constant definition into it's respective bytecode
 pub fn constant_gen(
     name: &str,
     contract: &Contract,
     ir_byte_span: AstSpan,
-) -> Result<String, CodegenError> {
-    // Get the first `ConstantDefinition` that matches the constant's name
-    let constant =
-        if let Some(m) = contract.constants.iter().find(|const_def| const_def.name.eq(&name)) {
-            m
-        } else {
-            tracing::error!(target: "codegen", "MISSING CONSTANT DEFINITION \"{}\"", name);
+) -> Result<Bytes, CodegenError> {
+    // Get the value of the first `ConstantDefinition` that matches the constant's name, or else
+    // of the well-known constant
+    let value = if let Some(v) = contract.find_constant_value(name.into()) {
+        v
+    } else if contract.immutables.iter().any(|i| i.name == name) {
+        tracing::info!(target: "codegen", "FOUND IMMUTABLE DEFINITION: {}", name);
+        return Ok(immutable_marker(name))
+    } else {
+        tracing::error!(target: "codegen", "MISSING CONSTANT DEFINITION \"{}\"", name);
 
-            return Err(CodegenError {
-                kind: CodegenErrorKind::MissingConstantDefinition(name.to_string()),
-                span: ir_byte_span,
-                token: None,
-            })
-        };
+        let candidates = contract.constants.iter().map(|c| c.name.as_str());
+        let well_known = WellKnownConstant::ALL.iter().map(|c| c.name());
+        return Err(CodegenError {
+            kind: CodegenErrorKind::MissingConstantDefinition(name.to_string()),
+            span: ir_byte_span,
+            token: None,
+            help: did_you_mean(
+                name,
+                candidates
+                    .chain(contract.immutables.iter().map(|i| i.name.as_str()))
+                    .chain(well_known),
+            ),
+        })
+    };
 
     // Generate bytecode for the constant
     // Should always be a `Literal` if storage pointers were derived in the AST
     // prior to generating the IR bytes.
-    tracing::info!(target: "codegen", "FOUND CONSTANT DEFINITION: {}", constant.name);
-    let push_bytes = match &constant.value {
-        ConstVal::Literal(l) => {
-            let hex_literal: String = bytes32_to_string(l, false);
-            format!("{:02x}{}", 95 + hex_literal.len() / 2, hex_literal)
-        }
+    tracing::info!(target: "codegen", "FOUND CONSTANT DEFINITION: {}", name);
+    let push_bytes = match &value {
+        ConstVal::Literal(l) => Bytes::push(l),
         ConstVal::FreeStoragePointer(fsp) => {
             // If this is reached in codegen stage, the `derive_storage_pointers`
             // method was not called on the AST.
             tracing::error!(target: "codegen", "STORAGE POINTERS INCORRECTLY DERIVED FOR \"{:?}\"", fsp);
+            let span = contract.constants.iter().find(|c| c.name == name).map(|c| c.span.clone());
             return Err(CodegenError {
                 kind: CodegenErrorKind::StoragePointersNotDerived,
-                span: constant.span.clone(),
+                span: span.unwrap_or(ir_byte_span),
                 token: None,
+                help: None,
             })
         }
     };
@@ -0,0 +1,86 @@
+use huff_utils::prelude::{BytecodeRes, MacroArg, MacroId, MacroInvocation, Relocation, SymbolId};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The root macro, then the names and arguments of the invocations an expansion is reached
+/// through, ending with the macro expanded
+type ExpansionKey = (MacroId, Vec<(SymbolId, Vec<MacroArg>)>, MacroId);
+
+/// The expansions of macros, reused by later invocations with the same arguments within the
+/// generation of a macro
+///
+/// Arg calls bubble up to the arguments of any invocation above them, so an expansion is keyed
+/// by the whole chain of invocations reaching it, and only reused through the same arguments.
+/// It is stored with the offset it was generated at: reusing it at another offset moves its
+/// bytes, labels, jumps and source map, and shifts the label offsets already filled in its
+/// jumps. Expansions with errors, whose spans point at the first invocation, or with offset
+/// expressions, whose values don't move with their labels, are generated again every time.
+#[derive(Debug, Default)]
+pub struct ExpansionCache {
+    expansions: BTreeMap<ExpansionKey, (usize, BytecodeRes)>,
+    /// The number of expansions reused
+    pub hits: usize,
+}
+
+impl ExpansionCache {
+    /// The key of expanding a macro in a scope, `None` for the root macro, which is expanded
+    /// once
+    pub fn key(
+        macro_id: MacroId,
+        scope: &[MacroId],
+        mis: &[(usize, MacroInvocation)],
+    ) -> Option<ExpansionKey> {
+        let root = *scope.first()?;
+        let chain = mis.iter().map(|(_, mi)| (mi.macro_name, mi.args.clone())).collect::<Vec<_>>();
+        (!chain.is_empty()).then_some((root, chain, macro_id))
+    }
+
+    /// The expansion generated for a key, moved to an offset
+    pub fn get(&mut self, key: &ExpansionKey, offset: usize) -> Option<BytecodeRes> {
+        let (generated_at, res) = self.expansions.get(key)?;
+        self.hits += 1;
+        Some(moved(res, *generated_at, offset))
+    }
+
+    /// Stores the expansion generated for a key at an offset, unless it can't be moved
+    pub fn insert(&mut self, key: ExpansionKey, offset: usize, res: &BytecodeRes) {
+        let offset_expressions = res
+            .source_map
+            .iter()
+            .any(|m| matches!(m.relocation, Some(Relocation::LabelOffsets(_))));
+        if res.errors.is_empty() && !offset_expressions {
+            self.expansions.insert(key, (offset, res.clone()));
+        }
+    }
+}
+
+/// Moves an expansion generated at an offset to another
+///
+/// The jumps filled with label offsets are the jump pushes of the source map left out of the
+/// unmatched jumps, since jumps are only filled with the labels of the expansion.
+fn moved(res: &BytecodeRes, from: usize, to: usize) -> BytecodeRes {
+    let shift = |index: usize| index - from + to;
+    let unmatched =
+        res.unmatched_jumps.iter().map(|j| j.bytecode_index).collect::<BTreeSet<usize>>();
+    let filled = res
+        .source_map
+        .iter()
+        .filter(|m| matches!(m.relocation, Some(Relocation::JumpLabel(_))))
+        .map(|m| m.offset)
+        .filter(|offset| !unmatched.contains(offset))
+        .collect::<BTreeSet<usize>>();
+
+    let mut res = res.clone();
+    for (index, bytes) in res.bytes.iter_mut() {
+        if filled.contains(index) {
+            if let [_, high, low] = bytes.0[..] {
+                bytes.fill(0, shift(u16::from_be_bytes([high, low]) as usize) as u16);
+            }
+        }
+        *index = shift(*index);
+    }
+    res.label_indices.values_mut().for_each(|i| *i = shift(*i));
+    res.unmatched_jumps.iter_mut().for_each(|j| j.bytecode_index = shift(j.bytecode_index));
+    res.table_instances.iter_mut().for_each(|j| j.bytecode_index = shift(j.bytecode_index));
+    res.source_map.iter_mut().for_each(|m| m.offset = shift(m.offset));
+    res
+}
@@ -7,7 +7,13 @@ pub mod statements;
 /// Argument Call Module
 pub mod arg_calls;
 
+/// Compile-time Assertion Module
+pub mod assertions;
+
+/// Macro Expansion Cache Module
+pub mod expansion;
+
 /// Prelude wraps common utilities.
 pub mod prelude {
-    pub use super::{arg_calls::*, constants::*, statements::*};
+    pub use super::{arg_calls::*, assertions::*, constants::*, statements::*};
 }
@@ -1,6 +1,11 @@
+use ethers_core::utils::keccak256;
 use huff_utils::prelude::*;
+use std::{collections::BTreeSet, str::FromStr};
 
-use crate::Codegen;
+use crate::{
+    irgen::{assertions::check_assertion, expansion::ExpansionCache},
+    Codegen,
+};
 
 /// Generates the respective Bytecode for a given Statement
 #[allow(clippy::too_many_arguments)]
@@ -8,13 +13,17 @@ pub fn statement_gen(
     s: &Statement,
     contract: &Contract,
     macro_def: &MacroDefinition,
-    scope: &mut Vec<MacroDefinition>,
+    scope: &mut Vec<MacroId>,
     offset: &mut usize,
     mis: &mut Vec<(usize, MacroInvocation)>,
     jump_table: &mut JumpTable,
     label_indices: &mut LabelIndices,
     table_instances: &mut Jumps,
+    offset_expressions: &mut OffsetExpressions,
+    errors: &mut Vec<CodegenError>,
+    source_map: &mut Vec<SourceMapping>,
     starting_offset: usize,
+    cache: &mut ExpansionCache,
 ) -> Result<Vec<(usize, Bytes)>, CodegenError> {
     let mut bytes = vec![];
 
@@ -23,8 +32,8 @@ pub fn statement_gen(
     match &s.ty {
         StatementType::MacroInvocation(mi) => {
             // Get the macro definition that matches the name of this invocation
-            let ir_macro = if let Some(m) = contract.find_macro_by_name(&mi.macro_name) {
-                m
+            let ir_macro_id = if let Some(id) = contract.find_macro_id(mi.macro_name) {
+                id
             } else {
                 tracing::error!(
                     target: "codegen",
@@ -32,33 +41,43 @@ pub fn statement_gen(
                     mi.macro_name
                 );
                 return Err(CodegenError {
-                    kind: CodegenErrorKind::InvalidMacroInvocation(mi.macro_name.clone()),
+                    kind: CodegenErrorKind::InvalidMacroInvocation(mi.macro_name.to_string()),
                     span: mi.span.clone(),
                     token: None,
+                    help: did_you_mean(
+                        &mi.macro_name,
+                        contract.macros.iter().map(|m| m.name.as_str()),
+                    ),
                 })
             };
+            let ir_macro = &contract.macros[ir_macro_id];
 
             tracing::info!(target: "codegen", "FOUND INNER MACRO: {}", ir_macro.name);
 
+            // Macros invoked in their own expansion, or nested too deep, would never finish
+            check_expansion(&ir_macro.name, &mi.span, contract, scope, mis)?;
+            check_invocation_args(mi, ir_macro, contract)?;
+
             // Recurse into macro invocation
-            scope.push(ir_macro.clone());
+            scope.push(ir_macro_id);
             mis.push((*offset, mi.clone()));
 
-            let mut res: BytecodeRes =
-                match Codegen::macro_to_bytecode(ir_macro.clone(), contract, scope, *offset, mis) {
-                    Ok(r) => r,
-                    Err(e) => {
-                        tracing::error!(
-                            target: "codegen",
-                            "FAILED TO RECURSE INTO MACRO \"{}\"",
-                            ir_macro.name
-                        );
-                        return Err(e)
-                    }
-                };
+            let mut res: BytecodeRes = match Codegen::macro_to_bytecode(
+                ir_macro_id, contract, scope, *offset, mis, cache,
+            ) {
+                Ok(r) => r,
+                Err(e) => {
+                    tracing::error!(
+                        target: "codegen",
+                        "FAILED TO RECURSE INTO MACRO \"{}\"",
+                        ir_macro.name
+                    );
+                    return Err(e)
+                }
+            };
 
             // Set jump table values
-            tracing::debug!(target: "codegen", "Unmatched jumps: {:?}", res.unmatched_jumps.iter().map(|uj| uj.label.clone()).collect::<Vec<String>>());
+            tracing::debug!(target: "codegen", "Unmatched jumps: {:?}", res.unmatched_jumps.iter().map(|uj| uj.label).collect::<Vec<SymbolId>>());
             for j in res.unmatched_jumps.iter_mut() {
                 let new_index = j.bytecode_index;
                 j.bytecode_index = 0;
@@ -71,18 +90,27 @@ pub fn statement_gen(
                 jump_table.insert(new_index, new_jumps);
             }
             table_instances.extend(res.table_instances);
+            offset_expressions.extend(res.unmatched_offsets);
             label_indices.extend(res.label_indices);
+            errors.append(&mut res.errors);
+            source_map.extend(res.source_map.into_iter().map(|mut m| {
+                m.invocations.insert(0, mi.clone());
+                m
+            }));
 
             // Increase offset by byte length of recursed macro
-            *offset += res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2;
+            *offset += res.bytes.iter().map(|(_, b)| b.len()).sum::<usize>();
             // Add the macro's bytecode to the final result
             bytes = [bytes, res.bytes].concat()
         }
         StatementType::Label(label) => {
             // Add JUMPDEST opcode to final result and add to label_indices
+            // Labels keep their source names. Jumps resolve against the labels of their own
+            // invocation first, so each invocation of a macro jumps to its own labels without
+            // mangling, and source maps and traces show the names as written.
             tracing::info!(target: "codegen", "RECURSE BYTECODE GOT LABEL: {:?}", label.name);
-            label_indices.insert(label.name.clone(), *offset);
-            bytes.push((*offset, Bytes(Opcode::Jumpdest.to_string())));
+            label_indices.insert(label.name, *offset);
+            bytes.push((*offset, Bytes::from(Opcode::Jumpdest)));
             *offset += 1;
         }
         StatementType::LabelCall(label) => {
@@ -92,9 +120,9 @@ pub fn statement_gen(
             tracing::info!(target: "codegen", "RECURSE BYTECODE GOT LABEL CALL: {}", label);
             jump_table.insert(
                 *offset,
-                vec![Jump { label: label.to_string(), bytecode_index: 0, span: s.span.clone() }],
+                vec![Jump { label: *label, bytecode_index: 0, span: s.span.clone() }],
             );
-            bytes.push((*offset, Bytes(format!("{}xxxx", Opcode::Push2))));
+            bytes.push((*offset, Bytes::placeholder()));
             *offset += 3;
         }
         StatementType::BuiltinFunctionCall(bf) => {
@@ -104,10 +132,10 @@ pub fn statement_gen(
             tracing::info!(target: "codegen", "RECURSE BYTECODE GOT BUILTIN FUNCTION CALL: {:?}", bf);
             match bf.kind {
                 BuiltinFunctionKind::Codesize => {
-                    let ir_macro = if let Some(m) =
-                        contract.find_macro_by_name(bf.args[0].name.as_ref().unwrap())
+                    let ir_macro_id = if let Some(id) =
+                        contract.find_macro_id(bf.args[0].name.as_ref().unwrap().into())
                     {
-                        m
+                        id
                     } else {
                         tracing::error!(
                             target: "codegen",
@@ -120,15 +148,17 @@ pub fn statement_gen(
                             ),
                             span: AstSpan(vec![Span { start: 0, end: 0, file: None }]),
                             token: None,
+                            help: did_you_mean(
+                                bf.args[0].name.as_ref().unwrap(),
+                                contract.macros.iter().map(|m| m.name.as_str()),
+                            ),
                         })
                     };
+                    let ir_macro = &contract.macros[ir_macro_id];
 
-                    let res: BytecodeRes = match Codegen::macro_to_bytecode(
-                        ir_macro.clone(),
-                        contract,
-                        scope,
-                        *offset,
-                        mis,
+                    check_expansion(&ir_macro.name, &bf.span, contract, scope, mis)?;
+                    let mut res: BytecodeRes = match Codegen::macro_to_bytecode(
+                        ir_macro_id, contract, scope, *offset, mis, cache,
                     ) {
                         Ok(r) => r,
                         Err(e) => {
@@ -140,15 +170,13 @@ pub fn statement_gen(
                             return Err(e)
                         }
                     };
+                    errors.append(&mut res.errors);
 
-                    let size = format_even_bytes(format!(
-                        "{:02x}",
-                        (res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2)
-                    ));
-                    let push_bytes = format!("{:02x}{}", 95 + size.len() / 2, size);
+                    let size = res.bytes.iter().map(|(_, b)| b.len()).sum::<usize>();
+                    let push_bytes = Bytes::push(&size.to_be_bytes());
 
-                    *offset += push_bytes.len() / 2;
-                    bytes.push((starting_offset, Bytes(push_bytes)));
+                    *offset += push_bytes.len();
+                    bytes.push((starting_offset, push_bytes));
                 }
                 BuiltinFunctionKind::Tablesize => {
                     let ir_table = if let Some(t) =
@@ -167,25 +195,265 @@ pub fn statement_gen(
                             ),
                             span: AstSpan(vec![Span { start: 0, end: 0, file: None }]),
                             token: None,
+                            help: did_you_mean(
+                                bf.args[0].name.as_ref().unwrap(),
+                                contract.tables.iter().map(|t| t.name.as_str()),
+                            ),
                         })
                     };
 
-                    let size = bytes32_to_string(&ir_table.size, false);
-                    let push_bytes = format!("{:02x}{}", 95 + size.len() / 2, size);
+                    let push_bytes = Bytes::push(&ir_table.size);
 
-                    *offset += push_bytes.len() / 2;
-                    bytes.push((starting_offset, Bytes(push_bytes)));
+                    *offset += push_bytes.len();
+                    bytes.push((starting_offset, push_bytes));
                 }
                 BuiltinFunctionKind::Tablestart => {
                     table_instances.push(Jump {
-                        label: bf.args[0].name.as_ref().unwrap().to_owned(),
+                        label: bf.args[0].name.as_ref().unwrap().into(),
                         bytecode_index: *offset,
                         span: bf.span.clone(),
                     });
 
-                    bytes.push((*offset, Bytes(format!("{}xxxx", Opcode::Push2))));
+                    bytes.push((*offset, Bytes::placeholder()));
                     *offset += 3;
                 }
+                BuiltinFunctionKind::AssertCodehash(expected) => {
+                    let name = bf.args[0].name.as_ref().unwrap();
+                    let code = Codegen::codehash_target_bytecode(name, contract, &bf.span)?;
+                    let actual = hex::encode(keccak256(code));
+                    let expected = hex::encode(expected);
+                    if actual != expected {
+                        tracing::error!(
+                            target: "codegen",
+                            "CODEHASH OF \"{}\" IS 0x{}, EXPECTED 0x{}",
+                            name,
+                            actual,
+                            expected
+                        );
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::CodehashMismatch(name.clone()),
+                            span: bf.span.clone(),
+                            token: None,
+                            help: Some(format!(
+                                "the codehash is 0x{}, update the asserted hash if the change is intended",
+                                actual
+                            )),
+                        })
+                    }
+                    tracing::info!(target: "codegen", "VERIFIED CODEHASH OF \"{}\"", name);
+                }
+                BuiltinFunctionKind::Assert(ref expression) => {
+                    check_assertion(expression, contract, &bf.span, scope, mis)?;
+                }
+                BuiltinFunctionKind::SetImmutable => {
+                    let name = bf.args[0].name.clone().unwrap_or_default();
+                    if !contract.immutables.iter().any(|i| i.name == name) {
+                        tracing::error!(target: "codegen", "MISSING IMMUTABLE PASSED TO __SET_IMMUTABLE \"{}\"", name);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::MissingConstantDefinition(name.clone()),
+                            span: bf.span.clone(),
+                            token: None,
+                            help: did_you_mean(
+                                &name,
+                                contract.immutables.iter().map(|i| i.name.as_str()),
+                            ),
+                        })
+                    }
+                    // The runtime bytecode is generated to find the placeholders, so it can't
+                    // set immutables itself
+                    if scope.iter().any(|id| contract.macros[*id].name == "MAIN") {
+                        tracing::error!(target: "codegen", "IMMUTABLE \"{}\" SET IN THE RUNTIME CODE", name);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::ImmutableSetInRuntime(name),
+                            span: bf.span.clone(),
+                            token: None,
+                            help: None,
+                        })
+                    }
+
+                    // The value is stored at each placeholder, in the memory the runtime bytecode
+                    // is copied around. Errors of `MAIN` are reported when generating it.
+                    let references = Codegen::immutable_references(contract).unwrap_or_default();
+                    let starts = references
+                        .get(&name)
+                        .map(|r| r.iter().map(|r| r.start).collect::<Vec<_>>())
+                        .unwrap_or_default();
+                    let mut code = vec![];
+                    for (i, start) in starts.iter().enumerate() {
+                        if i + 1 < starts.len() {
+                            code.push(Opcode::Dup1.byte());
+                        }
+                        code.push(Opcode::Push2.byte());
+                        code.extend_from_slice(&(*start as u16).to_be_bytes());
+                        code.push(Opcode::Mstore.byte());
+                    }
+                    if starts.is_empty() {
+                        code.push(Opcode::Pop.byte());
+                    }
+                    tracing::info!(target: "codegen", "SETTING IMMUTABLE \"{}\" AT {} PLACEHOLDERS", name, starts.len());
+                    *offset += code.len();
+                    bytes.push((starting_offset, Bytes(code)));
+                }
+                BuiltinFunctionKind::Link => {
+                    // The address is left as a placeholder, written over by `huffc link`
+                    let library = bf.args[0].name.clone().unwrap_or_default();
+                    tracing::info!(target: "codegen", "LINKING LIBRARY \"{}\"", library);
+                    let code = [&[Opcode::Push20.byte()][..], &link_marker(&library)].concat();
+                    *offset += code.len();
+                    bytes.push((starting_offset, Bytes(code)));
+                }
+                BuiltinFunctionKind::Keccak256(ref preimage) => {
+                    let hash = keccak256(preimage);
+                    tracing::info!(target: "codegen", "PUSHING KECCAK256 HASH 0x{}", hex::encode(hash));
+                    let code = [&[Opcode::Push32.byte()][..], &hash].concat();
+                    *offset += code.len();
+                    bytes.push((starting_offset, Bytes(code)));
+                }
+                BuiltinFunctionKind::RightPad(padded) => {
+                    let code = [&[Opcode::Push32.byte()][..], &padded[..]].concat();
+                    *offset += code.len();
+                    bytes.push((starting_offset, Bytes(code)));
+                }
+                BuiltinFunctionKind::MstoreString(ref string) => {
+                    tracing::info!(target: "codegen", "STORING STRING OF {} BYTES", string.len());
+                    let code = mstore_string_bytecode(string);
+                    *offset += code.len();
+                    bytes.push((starting_offset, Bytes(code)));
+                }
+                BuiltinFunctionKind::RevertString(ref reason) => {
+                    tracing::info!(target: "codegen", "REVERTING WITH REASON OF {} BYTES", reason.len());
+                    let code = revert_string_bytecode(reason);
+                    *offset += code.len();
+                    bytes.push((starting_offset, Bytes(code)));
+                }
+                BuiltinFunctionKind::Offset(ref expression) => {
+                    // PUSH2 + 2 byte value (placeholder for now, filled once the labels of the
+                    // expression are laid out)
+                    tracing::info!(target: "codegen", "PUSHING OFFSET EXPRESSION {}", expression);
+                    offset_expressions.push(OffsetExpression {
+                        expression: *expression.clone(),
+                        bytecode_index: starting_offset,
+                        span: bf.span.clone(),
+                    });
+                    bytes.push((starting_offset, Bytes::placeholder()));
+                    *offset += 3;
+                }
+                BuiltinFunctionKind::Verbatim(ref verbatim) => {
+                    tracing::warn!(target: "codegen", "SPLICING {} VERBATIM BYTES", verbatim.len());
+                    let code = verbatim.clone();
+                    *offset += code.len();
+                    bytes.push((starting_offset, Bytes(code)));
+                }
+                BuiltinFunctionKind::DecodeCalldata | BuiltinFunctionKind::EncodeOutputs => {
+                    let name = bf.args[0].name.clone().unwrap_or_default();
+                    let Some(function) = contract.find_function_by_name(&name) else {
+                        tracing::error!(target: "codegen", "MISSING FUNCTION PASSED TO BUILTIN \"{}\"", name);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::MissingFunctionDefinition(name.clone()),
+                            span: bf.span.clone(),
+                            token: None,
+                            help: did_you_mean(
+                                &name,
+                                contract.functions.iter().map(|f| f.name.as_str()),
+                            ),
+                        })
+                    };
+                    let kinds = |args: &[Argument]| {
+                        args.iter()
+                            .map(|a| a.arg_type.clone().unwrap_or_default().into())
+                            .collect::<Vec<FunctionParamType>>()
+                    };
+                    let code = match bf.kind {
+                        BuiltinFunctionKind::DecodeCalldata => {
+                            tracing::info!(target: "codegen", "DECODING THE CALLDATA OF \"{}\"", name);
+                            decode_calldata_bytecode(&kinds(&function.inputs))
+                        }
+                        _ => {
+                            let outputs = kinds(&function.outputs);
+                            if let Some(kind) = outputs.iter().find(|k| !is_value_type(k)) {
+                                tracing::error!(target: "codegen", "UNENCODABLE OUTPUT \"{}\" OF \"{}\"", kind, name);
+                                return Err(CodegenError {
+                                    kind: CodegenErrorKind::UnencodableOutput(kind.to_string()),
+                                    span: bf.span.clone(),
+                                    token: None,
+                                    help: Some(format!(
+                                        "\"{}\" returns a type that doesn't fit in a stack word",
+                                        name
+                                    )),
+                                })
+                            }
+                            tracing::info!(target: "codegen", "ENCODING THE OUTPUTS OF \"{}\"", name);
+                            encode_outputs_bytecode(outputs.len())
+                        }
+                    };
+                    *offset += code.len();
+                    bytes.push((starting_offset, Bytes(code)));
+                }
+                BuiltinFunctionKind::Emit => {
+                    let name = bf.args[0].name.clone().unwrap_or_default();
+                    let Some(event) = contract.find_event_by_name(&name) else {
+                        tracing::error!(target: "codegen", "MISSING EVENT PASSED TO __EMIT \"{}\"", name);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::MissingEventDefinition(name.clone()),
+                            span: bf.span.clone(),
+                            token: None,
+                            help: did_you_mean(
+                                &name,
+                                contract.events.iter().map(|e| e.name.as_str()),
+                            ),
+                        })
+                    };
+                    let kinds = event
+                        .parameters
+                        .iter()
+                        .map(|p| p.arg_type.clone().unwrap_or_default().into())
+                        .collect::<Vec<FunctionParamType>>();
+                    let indexed = event.parameters.iter().map(|p| p.indexed).collect::<Vec<_>>();
+                    let help = match kinds.iter().find(|k| !is_value_type(k)) {
+                        Some(kind) => Some(format!("\"{}\" doesn't fit in a stack word", kind)),
+                        None if indexed.iter().filter(|i| **i).count() > 3 => {
+                            Some("a log holds at most three indexed parameters".to_string())
+                        }
+                        None => None,
+                    };
+                    if help.is_some() {
+                        tracing::error!(target: "codegen", "EVENT \"{}\" CAN'T BE EMITTED FROM THE STACK", name);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::UnemittableEvent(name),
+                            span: bf.span.clone(),
+                            token: None,
+                            help,
+                        })
+                    }
+                    let signature = format!(
+                        "{}({})",
+                        name,
+                        kinds.iter().map(|k| k.to_string()).collect::<Vec<_>>().join(",")
+                    );
+                    tracing::info!(target: "codegen", "EMITTING EVENT \"{}\"", signature);
+                    let code = emit_bytecode(&keccak256(signature), &indexed);
+                    *offset += code.len();
+                    bytes.push((starting_offset, Bytes(code)));
+                }
+                BuiltinFunctionKind::Cheatcode(cheatcode) => {
+                    let code = hex::decode(cheatcode.bytecode()).unwrap_or_default();
+                    tracing::info!(target: "codegen", "CALLING CHEATCODE \"{}\"", cheatcode.signature());
+                    *offset += code.len();
+                    bytes.push((starting_offset, Bytes(code)));
+                }
+                // Custom builtins are lowered before the macros are expanded
+                BuiltinFunctionKind::Custom(ref name, _) => {
+                    tracing::error!(target: "codegen", "CUSTOM BUILTIN \"{}\" WAS NOT LOWERED", name);
+                    return Err(CodegenError {
+                        kind: CodegenErrorKind::InvalidBuiltinArgument(name.clone()),
+                        span: bf.span.clone(),
+                        token: None,
+                        help: Some(
+                            "lower custom builtins with `huff_codegen::lower_builtins` first"
+                                .to_string(),
+                        ),
+                    })
+                }
             }
         }
         sty => {
@@ -195,9 +463,285 @@ pub fn statement_gen(
                 kind: CodegenErrorKind::InvalidMacroStatement,
                 span: s.span.clone(),
                 token: None,
+                help: None,
             })
         }
     }
 
     Ok(bytes)
 }
+
+/// The selector of `Error(string)`, the revert reason of a failed `require`
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Pushes a word with the smallest push opcode holding its significant bytes
+fn push_word(word: &[u8]) -> Vec<u8> {
+    Bytes::push(word).0
+}
+
+/// The abi encoding of a string in words: its offset, its length, then its bytes padded on the
+/// right to a multiple of 32 bytes
+fn string_words(string: &[u8]) -> Vec<[u8; 32]> {
+    let mut words = vec![[0u8; 32], [0u8; 32]];
+    words[0][31] = 0x20;
+    words[1][24..].copy_from_slice(&(string.len() as u64).to_be_bytes());
+    for chunk in string.chunks(32) {
+        let mut word = [0u8; 32];
+        word[..chunk.len()].copy_from_slice(chunk);
+        words.push(word);
+    }
+    words
+}
+
+/// The bytecode of `__MSTORE_STRING`, storing the abi encoding of a string at the offset on the
+/// stack, then replacing the offset with the size stored
+fn mstore_string_bytecode(string: &[u8]) -> Vec<u8> {
+    let words = string_words(string);
+    let mut code = vec![];
+    for (i, word) in words.iter().enumerate() {
+        code.extend(push_word(word));
+        code.push(Opcode::Dup2.byte());
+        if i > 0 {
+            code.extend(push_word(&(32 * i).to_be_bytes()));
+            code.push(Opcode::Add.byte());
+        }
+        code.push(Opcode::Mstore.byte());
+    }
+    code.push(Opcode::Pop.byte());
+    code.extend(push_word(&(32 * words.len()).to_be_bytes()));
+    code
+}
+
+/// The bytecode of `__REVERT_STRING`, storing the selector of `Error(string)` before the abi
+/// encoding of the reason from memory offset 0, then reverting with them
+fn revert_string_bytecode(reason: &[u8]) -> Vec<u8> {
+    let words = string_words(reason);
+    let mut code = [push_word(&ERROR_SELECTOR), push_word(&[0])].concat();
+    code.push(Opcode::Mstore.byte());
+    for (i, word) in words.iter().enumerate() {
+        code.extend(push_word(word));
+        code.extend(push_word(&(32 + 32 * i).to_be_bytes()));
+        code.push(Opcode::Mstore.byte());
+    }
+    code.extend(push_word(&(4 + 32 * words.len()).to_be_bytes()));
+    code.extend(push_word(&[0x1c]));
+    code.push(Opcode::Revert.byte());
+    code
+}
+
+/// If a type is encoded in a single word, like `uint256` or `address`
+fn is_value_type(kind: &FunctionParamType) -> bool {
+    !matches!(
+        kind,
+        FunctionParamType::Bytes |
+            FunctionParamType::String |
+            FunctionParamType::Array(..) |
+            FunctionParamType::Tuple(_)
+    )
+}
+
+/// The bytecode of `__DECODE_CALLDATA`, pushing the parameters of a function from the calldata,
+/// the last first so that the first ends on top of the stack
+///
+/// Value types are loaded from the head of the encoding, after the selector. Other types are
+/// pushed as the calldata offset of their encoding: the head of static tuples and arrays, and the
+/// length of dynamic types.
+fn decode_calldata_bytecode(kinds: &[FunctionParamType]) -> Vec<u8> {
+    let mut heads = vec![];
+    let mut head = 4usize;
+    for kind in kinds {
+        heads.push(head);
+        head += kind.head_size();
+    }
+    kinds
+        .iter()
+        .zip(heads)
+        .rev()
+        .flat_map(|(kind, head)| {
+            let mut code = push_word(&head.to_be_bytes());
+            match kind {
+                kind if kind.is_dynamic() => {
+                    code.push(Opcode::Calldataload.byte());
+                    code.extend(push_word(&[4]));
+                    code.push(Opcode::Add.byte());
+                }
+                kind if !is_value_type(kind) => {}
+                _ => code.push(Opcode::Calldataload.byte()),
+            }
+            code
+        })
+        .collect()
+}
+
+/// The bytecode of `__ENCODE_OUTPUTS`, storing the outputs below the offset on the stack in
+/// memory from the offset, the first output first, then replacing the offset with the size stored
+fn encode_outputs_bytecode(outputs: usize) -> Vec<u8> {
+    let mut code = vec![];
+    for i in 0..outputs {
+        code.extend([Opcode::Swap1.byte(), Opcode::Dup2.byte()]);
+        if i > 0 {
+            code.extend(push_word(&(32 * i).to_be_bytes()));
+            code.push(Opcode::Add.byte());
+        }
+        code.push(Opcode::Mstore.byte());
+    }
+    code.push(Opcode::Pop.byte());
+    code.extend(push_word(&(32 * outputs).to_be_bytes()));
+    code
+}
+
+/// The bytecode of `__EMIT`, logging an event with its parameters on the stack, the first on top
+///
+/// The parameters that aren't indexed are stored in memory from offset 0, bringing each above
+/// the indexed parameters passed over, which are then rotated back in order. The indexed
+/// parameters are left as the topics following the hash of the event's signature.
+fn emit_bytecode(topic: &[u8; 32], indexed: &[bool]) -> Vec<u8> {
+    // `SWAPn` is `0x8f + n`, and `LOGn` is `0xa0 + n`
+    let swap = |depth: usize| Opcode::Swap1.byte() + depth as u8 - 1;
+    let mut code = vec![];
+    let mut topics = 0;
+    let mut words = 0usize;
+    for indexed in indexed {
+        if *indexed {
+            topics += 1;
+            continue
+        }
+        if topics > 0 {
+            code.push(swap(topics));
+        }
+        code.extend(push_word(&(32 * words).to_be_bytes()));
+        code.push(Opcode::Mstore.byte());
+        for depth in 1..topics {
+            code.push(swap(depth));
+        }
+        words += 1;
+    }
+    code.extend(push_word(topic));
+    code.extend(push_word(&(32 * words).to_be_bytes()));
+    code.extend(push_word(&[0]));
+    code.push(Opcode::Log0.byte() + topics as u8 + 1);
+    code
+}
+
+/// Checks that expanding the named macro doesn't recurse into a macro being expanded, and stays
+/// within the maximum expansion depth of the contract
+///
+/// The expansion chain is the root macro followed by the invocations being expanded.
+pub(crate) fn check_expansion(
+    name: &str,
+    span: &AstSpan,
+    contract: &Contract,
+    scope: &[MacroId],
+    mis: &[(usize, MacroInvocation)],
+) -> Result<(), CodegenError> {
+    let mut chain = scope
+        .first()
+        .map(|id| contract.macros[*id].name.as_str())
+        .into_iter()
+        .chain(mis.iter().map(|(_, mi)| mi.macro_name.as_str()))
+        .collect::<Vec<&str>>();
+    let recursive = chain.contains(&name);
+    chain.push(name);
+    let chain = chain.join(" > ");
+
+    if recursive {
+        tracing::error!(target: "codegen", "RECURSIVE MACRO INVOCATION: {}", chain);
+        return Err(CodegenError {
+            kind: CodegenErrorKind::RecursiveMacroInvocation(chain),
+            span: span.clone(),
+            token: None,
+            help: Some(format!("\"{}\" is invoked in its own expansion, which never ends", name)),
+        })
+    }
+    let max_depth = contract.max_expansion_depth.unwrap_or(DEFAULT_MAX_EXPANSION_DEPTH);
+    if mis.len() >= max_depth {
+        tracing::error!(target: "codegen", "MAX EXPANSION DEPTH EXCEEDED: {}", chain);
+        return Err(CodegenError {
+            kind: CodegenErrorKind::MaxExpansionDepthExceeded(max_depth),
+            span: span.clone(),
+            token: None,
+            help: Some(format!(
+                "expanded as {}; raise the limit with `--max-expansion-depth`",
+                chain
+            )),
+        })
+    }
+    Ok(())
+}
+
+/// Checks that a macro invocation passes an argument for each parameter of the macro, and that
+/// the identifiers it passes are opcodes or labels
+fn check_invocation_args(
+    mi: &MacroInvocation,
+    macro_def: &MacroDefinition,
+    contract: &Contract,
+) -> Result<(), CodegenError> {
+    if mi.args.len() != macro_def.parameters.len() {
+        tracing::error!(target: "codegen", "MACRO \"{}\" INVOKED WITH {} ARGUMENTS", mi.macro_name, mi.args.len());
+        let parameters = macro_def.parameters.iter().filter_map(|p| p.name.as_deref());
+        return Err(CodegenError {
+            kind: CodegenErrorKind::MacroArgCountMismatch(mi.macro_name.to_string()),
+            span: mi.span.clone(),
+            token: None,
+            help: Some(format!(
+                "\"{}\" is declared as {}({}), {} arguments were passed",
+                mi.macro_name,
+                mi.macro_name,
+                parameters.collect::<Vec<&str>>().join(", "),
+                mi.args.len()
+            )),
+        })
+    }
+    let mut labels = BTreeSet::new();
+    contract.macros.iter().for_each(|m| collect_label_names(&m.statements, &mut labels));
+    for arg in mi.args.iter() {
+        let MacroArg::Ident(ident) = arg else { continue };
+        if Opcode::from_str(ident).is_ok() || labels.contains(ident.as_str()) {
+            continue
+        }
+        tracing::error!(target: "codegen", "INVALID ARGUMENT \"{}\" PASSED TO MACRO \"{}\"", ident, mi.macro_name);
+        let hint = format!(
+            "\"{}\" is passed to \"{}\", but is neither an opcode nor a label",
+            ident, mi.macro_name
+        );
+        return Err(CodegenError {
+            kind: CodegenErrorKind::InvalidMacroArgument(ident.clone()),
+            span: mi.span.clone(),
+            token: None,
+            help: Some(match did_you_mean(ident, labels.iter().copied()) {
+                Some(suggestion) => format!("{}; {}", hint, suggestion),
+                None => hint,
+            }),
+        })
+    }
+    Ok(())
+}
+
+/// Recursively collects the names of the labels defined by a list of statements
+fn collect_label_names<'a>(statements: &'a [Statement], labels: &mut BTreeSet<&'a str>) {
+    for statement in statements {
+        if let StatementType::Label(label) = &statement.ty {
+            labels.insert(&label.name);
+            collect_label_names(&label.inner, labels);
+        }
+    }
+}
+
+/// Recursively collects the spans of the `__VERBATIM` calls of a list of statements
+pub(crate) fn collect_verbatim_spans<'a>(
+    statements: &'a [Statement],
+    spans: &mut BTreeSet<&'a AstSpan>,
+) {
+    for statement in statements {
+        match &statement.ty {
+            StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                kind: BuiltinFunctionKind::Verbatim(_),
+                ..
+            }) => {
+                spans.insert(&statement.span);
+            }
+            StatementType::Label(label) => collect_verbatim_spans(&label.inner, spans),
+            _ => {}
+        }
+    }
+}
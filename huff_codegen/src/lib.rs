@@ -2,7 +2,6 @@
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
 #![forbid(unsafe_code)]
-#![forbid(where_clauses_object_safety)]
 
 use huff_utils::{
     abi::*,
@@ -11,14 +10,36 @@ use huff_utils::{
     bytecode::*,
     error::CodegenError,
     prelude::{
-        bytes32_to_string, format_even_bytes, pad_n_bytes, CodegenErrorKind, FileSource, Span,
+        bytes32_to_string, did_you_mean, encode_linked, pad_n_bytes, CodegenErrorKind, FileSource,
+        Opcode, Span, SymbolId,
     },
     types::EToken,
 };
-use std::{collections::HashMap, fs, path::Path, sync::Arc};
+use ethers_core::types::U256;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    path::Path,
+    sync::Arc,
+};
 
 mod irgen;
 use crate::irgen::prelude::*;
+pub use irgen::expansion::ExpansionCache;
+
+mod builtin;
+pub use builtin::{lower_builtins, BuiltinParam, CustomBuiltin};
+
+mod plugin;
+pub use plugin::CodegenPlugin;
+
+/// Pads a value, in big endian, on the left to a number of bytes, keeping its significant bytes
+/// if it doesn't fit
+fn pad_be_bytes(value: usize, size: usize) -> Vec<u8> {
+    let be = value.to_be_bytes();
+    let start = be.iter().position(|b| *b != 0).unwrap_or(be.len() - 1);
+    [vec![0; size.saturating_sub(be.len() - start)], be[start..].to_vec()].concat()
+}
 
 /// ### Codegen
 ///
@@ -54,71 +75,259 @@ impl Codegen {
     }
 
     /// Generates main bytecode from a Contract AST
+    ///
+    /// Returns the first error encountered, see
+    /// [generate_macro_bytecode](Codegen::generate_macro_bytecode) to collect all of them.
     pub fn generate_main_bytecode(contract: &Contract) -> Result<String, CodegenError> {
-        // Find the main macro
-        let m_macro = Codegen::get_macro_by_name("MAIN", contract)?;
-
-        // For each MacroInvocation Statement, recurse into bytecode
-        let bytecode_res: BytecodeRes = Codegen::macro_to_bytecode(
-            m_macro.clone(),
-            contract,
-            &mut vec![m_macro],
-            0,
-            &mut Vec::default(),
-        )?;
-
-        // Generate the fully baked bytecode
-        Codegen::gen_table_bytecode(bytecode_res, contract)
+        Codegen::generate_macro_bytecode("MAIN", contract).map_err(|mut errors| errors.remove(0))
     }
 
     /// Generates constructor bytecode from a Contract AST
+    ///
+    /// Returns the first error encountered, see
+    /// [generate_macro_bytecode](Codegen::generate_macro_bytecode) to collect all of them.
     pub fn generate_constructor_bytecode(contract: &Contract) -> Result<String, CodegenError> {
-        // Find the constructor macro
-        let c_macro = Codegen::get_macro_by_name("CONSTRUCTOR", contract)?;
+        Codegen::generate_macro_bytecode("CONSTRUCTOR", contract)
+            .map_err(|mut errors| errors.remove(0))
+    }
+
+    /// Generates the bytecode of the named macro from a Contract AST
+    ///
+    /// Missing definitions, unmatched jumps and other errors in macro statements don't stop
+    /// code generation, so every one of them is returned in the order they are encountered.
+    pub fn generate_macro_bytecode(
+        name: &str,
+        contract: &Contract,
+    ) -> Result<String, Vec<CodegenError>> {
+        Codegen::generate_macro_bytecode_with_source_map(name, contract).map(|(b, _)| b)
+    }
+
+    /// Generates the bytecode of the named macro from a Contract AST, along with the source map
+    /// of the macro's statements, without the table bytes
+    pub fn generate_macro_bytecode_with_source_map(
+        name: &str,
+        contract: &Contract,
+    ) -> Result<(String, Vec<SourceMapping>), Vec<CodegenError>> {
+        Codegen::generate_macro_bytecode_with_cache(name, contract, &mut ExpansionCache::default())
+    }
+
+    /// Generates the bytecode of the named macro from a Contract AST, along with the source map
+    /// of the macro's statements, reusing and recording the expansions of a cache
+    pub fn generate_macro_bytecode_with_cache(
+        name: &str,
+        contract: &Contract,
+        cache: &mut ExpansionCache,
+    ) -> Result<(String, Vec<SourceMapping>), Vec<CodegenError>> {
+        // Find the macro
+        let id = Codegen::get_macro_id(name, contract).map_err(|e| vec![e])?;
 
         // For each MacroInvocation Statement, recurse into bytecode
-        let bytecode_res: BytecodeRes = Codegen::macro_to_bytecode(
-            c_macro.clone(),
-            contract,
-            &mut vec![c_macro],
-            0,
-            &mut Vec::default(),
-        )?;
-
-        // Generate the bytecode return string
-        Codegen::gen_table_bytecode(bytecode_res, contract)
+        let bytecode_res: BytecodeRes =
+            Codegen::macro_to_bytecode(id, contract, &mut vec![id], 0, &mut vec![], cache)
+                .map_err(|e| vec![e])?;
+        let source_map = bytecode_res.source_map.clone();
+
+        // Generate the fully baked bytecode
+        Codegen::gen_table_bytecode(bytecode_res, contract).map(|b| (b, source_map))
     }
 
-    /// Helper function to find a macro or generate a CodegenError
-    pub(crate) fn get_macro_by_name(
+    /// Maps the bytecode of the named macro from a Contract AST to the statements that generated
+    /// it
+    ///
+    /// Table bytes, appended after the macro's bytecode, are mapped to their table definitions.
+    pub fn generate_source_map(
         name: &str,
         contract: &Contract,
-    ) -> Result<MacroDefinition, CodegenError> {
-        if let Some(m) = contract.find_macro_by_name(name) {
-            Ok(m)
+    ) -> Result<Vec<SourceMapping>, Vec<CodegenError>> {
+        let id = Codegen::get_macro_id(name, contract).map_err(|e| vec![e])?;
+        let mut cache = ExpansionCache::default();
+        let res =
+            Codegen::macro_to_bytecode(id, contract, &mut vec![id], 0, &mut vec![], &mut cache)
+                .map_err(|e| vec![e])?;
+        if !res.errors.is_empty() {
+            return Err(res.errors)
+        }
+
+        let mut source_map = res.source_map;
+        let mut table_offset = res.bytes.iter().map(|(_, b)| b.len()).sum::<usize>();
+        for jt in contract.tables.iter() {
+            let size = bytes32_to_string(&jt.size, false).parse::<usize>().unwrap_or_default();
+            source_map.push(SourceMapping {
+                offset: table_offset,
+                size,
+                span: jt.span.clone(),
+                table: Some(jt.name.to_string()),
+                invocations: vec![],
+                relocation: None,
+            });
+            table_offset += size;
+        }
+        Ok(source_map)
+    }
+
+    /// Finds the placeholders of the immutables in the bytecode of the `MAIN` macro, by immutable
+    /// name
+    ///
+    /// Each placeholder is the 32 bytes pushed by a `PUSH32`, in the order of the bytecode.
+    pub fn immutable_references(
+        contract: &Contract,
+    ) -> Result<BTreeMap<String, Vec<ImmutableReference>>, Vec<CodegenError>> {
+        let mut references: BTreeMap<String, Vec<ImmutableReference>> = BTreeMap::new();
+        for mapping in Codegen::generate_source_map("MAIN", contract)? {
+            if let Some(Relocation::Immutable(name)) = mapping.relocation {
+                references
+                    .entry(name)
+                    .or_default()
+                    .push(ImmutableReference { start: mapping.offset + 1, length: 32 });
+            }
+        }
+        references.values_mut().for_each(|r| r.sort_by_key(|r| r.start));
+        Ok(references)
+    }
+
+    /// Finds the bytes spliced with `__VERBATIM` into the bytecode of the `MAIN` macro
+    pub fn verbatim_references(
+        contract: &Contract,
+    ) -> Result<Vec<VerbatimReference>, Vec<CodegenError>> {
+        let mut spans = BTreeSet::new();
+        contract.macros.iter().for_each(|m| collect_verbatim_spans(&m.statements, &mut spans));
+        Ok(Codegen::generate_source_map("MAIN", contract)?
+            .into_iter()
+            .filter(|mapping| mapping.table.is_none() && spans.contains(&mapping.span))
+            .map(|mapping| VerbatimReference { start: mapping.offset, length: mapping.size })
+            .collect())
+    }
+
+    /// Checks the top-level assertions of a Contract AST, returning every one that fails
+    ///
+    /// Assertions inside macros are checked as their macro's bytecode is generated.
+    pub fn check_assertions(contract: &Contract) -> Vec<CodegenError> {
+        contract
+            .assertions
+            .iter()
+            .filter_map(|a| {
+                check_assertion(&a.expression, contract, &a.span, &mut vec![], &mut vec![]).err()
+            })
+            .collect()
+    }
+
+    /// Helper function to find the id of a macro or generate a CodegenError
+    pub(crate) fn get_macro_id(name: &str, contract: &Contract) -> Result<MacroId, CodegenError> {
+        if let Some(id) = contract.find_macro_id(name.into()) {
+            Ok(id)
         } else {
             tracing::error!(target: "codegen", "MISSING \"{}\" MACRO!", name);
             Err(CodegenError {
                 kind: CodegenErrorKind::MissingMacroDefinition(name.to_string()),
                 span: AstSpan(vec![Span { start: 0, end: 0, file: None }]),
                 token: None,
+                help: did_you_mean(name, contract.macros.iter().map(|m| m.name.as_str())),
             })
         }
     }
 
+    /// Generates the bytecode hashed by a codehash assertion on the named macro or code table.
+    ///
+    /// Macros are generated on their own, as if deployed at offset zero.
+    pub(crate) fn codehash_target_bytecode(
+        name: &str,
+        contract: &Contract,
+        span: &AstSpan,
+    ) -> Result<Vec<u8>, CodegenError> {
+        if let Some(id) = contract.find_macro_id(name.into()) {
+            let mut cache = ExpansionCache::default();
+            let mut res = Codegen::macro_to_bytecode(
+                id, contract, &mut vec![id], 0, &mut vec![], &mut cache,
+            )?;
+            if !res.errors.is_empty() {
+                return Err(res.errors.remove(0))
+            }
+            if let Some(jump) = res.unmatched_jumps.first() {
+                tracing::error!(target: "codegen", "UNMATCHED JUMP LABEL \"{}\" IN CODEHASH TARGET", jump.label);
+                return Err(CodegenError {
+                    kind: CodegenErrorKind::UnmatchedJumpLabel,
+                    span: jump.span.clone(),
+                    token: None,
+                    help: None,
+                })
+            }
+            if let Some(oe) = res.unmatched_offsets.first() {
+                tracing::error!(target: "codegen", "UNMATCHED OFFSET EXPRESSION {} IN CODEHASH TARGET", oe.expression);
+                return Err(CodegenError {
+                    kind: CodegenErrorKind::UnmatchedJumpLabel,
+                    span: oe.span.clone(),
+                    token: None,
+                    help: None,
+                })
+            }
+            return Ok(res.bytes.into_iter().flat_map(|(_, b)| b.0).collect())
+        }
+        match contract.find_table_by_name(name) {
+            Some(t) if t.kind == TableKind::CodeTable => {
+                let mut code = vec![];
+                for s in t.statements.iter() {
+                    match &s.ty {
+                        StatementType::Code(c) => code.extend(c),
+                        // Code written in the table body is hex
+                        StatementType::LabelCall(c) => match hex::decode(c.as_str()) {
+                            Ok(c) => code.extend(c),
+                            Err(e) => {
+                                tracing::error!(target: "codegen", "INVALID CODE \"{}\" IN CODEHASH TARGET \"{}\"", c, t.name);
+                                return Err(CodegenError {
+                                    kind: CodegenErrorKind::InvalidCodehashTarget(
+                                        t.name.to_string(),
+                                    ),
+                                    span: s.span.clone(),
+                                    token: None,
+                                    help: Some(format!("\"{}\" isn't hex code: {}", c, e)),
+                                })
+                            }
+                        },
+                        _ => {}
+                    }
+                }
+                Ok(code)
+            }
+            Some(t) => Err(CodegenError {
+                kind: CodegenErrorKind::InvalidCodehashTarget(t.name.to_string()),
+                span: span.clone(),
+                token: None,
+                help: None,
+            }),
+            None => {
+                tracing::error!(target: "codegen", "MISSING MACRO OR TABLE PASSED TO __ASSERT_CODEHASH \"{}\"", name);
+                let candidates = contract.macros.iter().map(|m| m.name.as_str());
+                Err(CodegenError {
+                    kind: CodegenErrorKind::MissingMacroDefinition(name.to_string()),
+                    span: span.clone(),
+                    token: None,
+                    help: did_you_mean(
+                        name,
+                        candidates.chain(contract.tables.iter().map(|t| t.name.as_str())),
+                    ),
+                })
+            }
+        }
+    }
+
     /// Appends table bytecode to the end of the BytecodeRes output.
     /// Fills table JUMPDEST placeholders.
+    ///
+    /// Fails with the errors recovered from while generating the bytes, followed by any
+    /// unmatched jumps or table errors.
     pub(crate) fn gen_table_bytecode(
-        res: BytecodeRes,
+        mut res: BytecodeRes,
         contract: &Contract,
-    ) -> Result<String, CodegenError> {
+    ) -> Result<String, Vec<CodegenError>> {
+        let mut errors = std::mem::take(&mut res.errors);
+
         if !res.unmatched_jumps.is_empty() {
             tracing::error!(
                 target: "codegen",
                 "Source contains unmatched jump labels \"{}\"",
                 res.unmatched_jumps.iter().map(|uj| uj.label.to_string()).collect::<Vec<String>>().join(", ")
             );
-            return Err(CodegenError {
+            errors.push(CodegenError {
                 kind: CodegenErrorKind::UnmatchedJumpLabel,
                 span: AstSpan(
                     res.unmatched_jumps
@@ -127,76 +336,119 @@ impl Codegen {
                         .collect::<Vec<Span>>(),
                 ),
                 token: None,
-            })
+                help: None,
+            });
+        }
+
+        if !res.unmatched_offsets.is_empty() {
+            tracing::error!(
+                target: "codegen",
+                "Source contains offset expressions of unmatched labels \"{}\"",
+                res.unmatched_offsets.iter().map(|uo| uo.expression.to_string()).collect::<Vec<String>>().join(", ")
+            );
+            errors.push(CodegenError {
+                kind: CodegenErrorKind::UnmatchedJumpLabel,
+                span: AstSpan(
+                    res.unmatched_offsets
+                        .iter()
+                        .flat_map(|uo| uo.span.0.clone())
+                        .collect::<Vec<Span>>(),
+                ),
+                token: None,
+                help: None,
+            });
         }
 
         tracing::info!(target: "codegen", "GENERATING JUMPTABLE BYTECODE");
 
-        let mut bytecode = res.bytes.into_iter().map(|(_, b)| b.0).collect::<String>();
-        let mut table_offsets: HashMap<String, usize> = HashMap::new(); // table name -> bytecode offset
-        let mut table_offset = bytecode.len() / 2;
+        let mut bytecode = res.bytes.into_iter().flat_map(|(_, b)| b.0).collect::<Vec<u8>>();
+        // table name -> bytecode offset
+        let mut table_offsets: HashMap<SymbolId, usize> = HashMap::new();
+        let mut table_offset = bytecode.len();
 
-        if let Err(e) = contract.tables.iter().try_for_each(|jt| {
-            table_offsets.insert(jt.name.to_string(), table_offset);
+        for jt in contract.tables.iter() {
+            table_offsets.insert(jt.name, table_offset);
             let size = match bytes32_to_string(&jt.size, false).parse::<usize>() {
                 Ok(s) => s,
-                Err(_) => return Err(CodegenError {
-                    kind: CodegenErrorKind::UsizeConversion(format!("{:?}", jt.size)),
-                    span: jt.span.clone(),
-                    token: None
-                })
+                Err(_) => {
+                    errors.push(CodegenError {
+                        kind: CodegenErrorKind::UsizeConversion(format!("{:?}", jt.size)),
+                        span: jt.span.clone(),
+                        token: None,
+                        help: None,
+                    });
+                    continue
+                }
             };
             table_offset += size;
 
             tracing::info!(target: "codegen", "GENERATING BYTECODE FOR TABLE: \"{}\"", jt.name);
 
-            let mut table_code = String::new();
-            let collected = jt
-                .statements
-                .iter()
-                .try_for_each(|s| {
-                    if let StatementType::LabelCall(label) = &s.ty {
-                        let offset = match res.label_indices.get(label) {
-                            Some(l) => l,
-                            None => {
-                                tracing::error!(
-                                    target: "codegen",
-                                    "Definition not found for Jump Table Label: \"{}\"",
-                                    label
-                                );
-                                return Err(CodegenError {
-                                    kind: CodegenErrorKind::UnmatchedJumpLabel,
-                                    span: s.span.clone(),
-                                    token: None,
-                                });
-                            }
-                        };
-                        let hex = format_even_bytes(format!("{:02x}", offset));
+            let mut table_code = vec![];
+            for s in jt.statements.iter() {
+                // Code read from a file is placed in the table as is
+                if let StatementType::Code(code) = &s.ty {
+                    table_code.extend(code);
+                }
+                if let StatementType::LabelCall(label) = &s.ty {
+                    let offset = match res.label_indices.get(label) {
+                        Some(l) => l,
+                        None => {
+                            tracing::error!(
+                                target: "codegen",
+                                "Definition not found for Jump Table Label: \"{}\"",
+                                label
+                            );
+                            errors.push(CodegenError {
+                                kind: CodegenErrorKind::UnmatchedJumpLabel,
+                                span: s.span.clone(),
+                                token: None,
+                                help: None,
+                            });
+                            continue
+                        }
+                    };
+                    let size =
+                        if matches!(jt.kind, TableKind::JumpTablePacked) { 0x02 } else { 0x20 };
+                    table_code.extend(pad_be_bytes(*offset, size));
+                }
+            }
+            tracing::info!(target: "codegen", "SUCCESSFULLY GENERATED BYTECODE FOR TABLE: \"{}\"", jt.name);
+            bytecode.extend(table_code);
+        }
 
-                        table_code = format!("{}{}", table_code, pad_n_bytes(
-                            hex.as_str(),
-                            if matches!(jt.kind, TableKind::JumpTablePacked) { 0x02 } else { 0x20 },
-                        ));
-                    }
-                    Ok(())
+        // Table starts are pushed with two bytes, so tables after a large table can be unreachable
+        for jump in res.table_instances.iter() {
+            if let Some(o) = table_offsets.get(&jump.label).filter(|o| **o > 0xffff) {
+                tracing::error!(target: "codegen", "TABLE \"{}\" STARTS AT UNREACHABLE OFFSET {}", jump.label, o);
+                errors.push(CodegenError {
+                    kind: CodegenErrorKind::UsizeConversion(format!("{:#x}", o)),
+                    span: jump.span.clone(),
+                    token: None,
+                    help: Some(format!(
+                        "table \"{}\" starts at byte {}, beyond the 65535 bytes addressable by __tablestart",
+                        jump.label, o
+                    )),
                 });
-            if let Err(e) = collected {
-                return Err(e);
             }
-            tracing::info!(target: "codegen", "SUCCESSFULLY GENERATED BYTECODE FOR TABLE: \"{}\"", jt.name);
-            bytecode = format!("{}{}", bytecode, table_code);
-            Ok(())
-        }) {
-            return Err(e);
+        }
+
+        if !errors.is_empty() {
+            // Macros generated more than once, or also sized by a builtin, repeat their errors
+            let mut reported = vec![];
+            errors.retain(|e| {
+                let unique = !reported.contains(e);
+                reported.push(e.clone());
+                unique
+            });
+            tracing::error!(target: "codegen", "CODEGEN FAILED WITH {} ERRORS", errors.len());
+            return Err(errors)
         }
 
         res.table_instances.iter().for_each(|jump| {
             if let Some(o) = table_offsets.get(&jump.label) {
-                let before = &bytecode[0..jump.bytecode_index * 2 + 2];
-                let after = &bytecode[jump.bytecode_index * 2 + 6..];
-
-                bytecode =
-                    format!("{}{}{}", before, pad_n_bytes(format!("{:02x}", o).as_str(), 2), after);
+                let start = jump.bytecode_index + 1;
+                bytecode[start..start + 2].copy_from_slice(&(*o as u16).to_be_bytes());
                 tracing::info!(target: "codegen", "FILLED JUMPDEST FOR LABEL \"{}\"", jump.label);
             } else {
                 tracing::error!(
@@ -207,7 +459,8 @@ impl Codegen {
             }
         });
 
-        Ok(bytecode)
+        // The bytecode is only hex encoded once fully generated
+        Ok(encode_linked(&bytecode, contract.linked_libraries().iter().map(String::as_str)))
     }
 
     /// Recurses a MacroDefinition to generate Bytecode
@@ -225,18 +478,33 @@ impl Codegen {
     ///
     /// ## Arguments
     ///
-    /// * `macro_def` - Macro definition to convert to bytecode
+    /// * `macro_id` - Id of the macro definition to convert to bytecode
     /// * `contract` - Reference to the `Contract` AST generated by the parser
-    /// * `scope` - Current scope of the recursion. Contains all macro definitions recursed so far.
+    /// * `scope` - Current scope of the recursion. Contains the ids of all macro definitions
+    ///   recursed so far, indexing the macros of the contract.
     /// * `offset` - Current bytecode offset
     /// * `mis` - Vector of tuples containing parent macro invocations as well as their offsets.
+    /// * `cache` - Expansions of the macros invoked so far, reused by invocations with the same
+    ///   arguments.
     pub(crate) fn macro_to_bytecode(
-        macro_def: MacroDefinition,
+        macro_id: MacroId,
         contract: &Contract,
-        scope: &mut Vec<MacroDefinition>,
+        scope: &mut Vec<MacroId>,
         mut offset: usize,
         mis: &mut Vec<(usize, MacroInvocation)>,
+        cache: &mut ExpansionCache,
     ) -> Result<BytecodeRes, CodegenError> {
+        let macro_def = &contract.macros[macro_id];
+
+        // Reuse the expansion of an earlier invocation with the same arguments
+        let key = ExpansionCache::key(macro_id, scope, mis);
+        if let Some(res) = key.as_ref().and_then(|k| cache.get(k, offset)) {
+            tracing::info!(target: "codegen", "REUSING EXPANSION OF MACRO \"{}\" AT OFFSET {}", macro_def.name, offset);
+            mis.pop();
+            return Ok(res)
+        }
+        let generated_at = offset;
+
         // Get intermediate bytecode representation of the macro definition
         let mut bytes: Vec<(usize, Bytes)> = Vec::default();
         let ir_bytes = macro_def.to_irbytecode()?.0;
@@ -245,49 +513,105 @@ impl Codegen {
         let mut jump_table = JumpTable::new();
         let mut label_indices = LabelIndices::new();
         let mut table_instances = Jumps::new();
+        let mut offset_expressions = OffsetExpressions::new();
 
         // Loop through all intermediate bytecode representations generated from the AST
-        for (_ir_bytes_index, ir_byte) in ir_bytes.into_iter().enumerate() {
+        // Failing statements are recorded and skipped, so that every error is reported at once
+        let mut errors: Vec<CodegenError> = Vec::default();
+        let mut source_map: Vec<SourceMapping> = Vec::default();
+        for ir_byte in ir_bytes.into_iter() {
             let starting_offset = offset;
-            match ir_byte.ty {
+            let span = ir_byte.span.clone();
+            // Invoked macros map their own statements
+            let inlined = matches!(
+                &ir_byte.ty,
+                IRByteType::Statement(s) if matches!(s.ty, StatementType::MacroInvocation(_))
+            );
+            let pushes_constant =
+                matches!(&ir_byte.ty, IRByteType::Constant(_) | IRByteType::ArgCall(_));
+            let res = match ir_byte.ty {
                 IRByteType::Bytes(b) => {
-                    offset += b.0.len() / 2;
+                    offset += b.len();
                     bytes.push((starting_offset, b));
+                    Ok(())
                 }
                 IRByteType::Constant(name) => {
-                    let push_bytes = constant_gen(&name, contract, ir_byte.span)?;
-                    offset += push_bytes.len() / 2;
-                    tracing::debug!(target: "codegen", "OFFSET: {}, PUSH BYTES: {:?}", offset, push_bytes);
-                    bytes.push((starting_offset, Bytes(push_bytes)));
-                }
-                IRByteType::Statement(s) => {
-                    let mut push_bytes = statement_gen(
-                        &s,
-                        contract,
-                        &macro_def,
-                        scope,
-                        &mut offset,
-                        mis,
-                        &mut jump_table,
-                        &mut label_indices,
-                        &mut table_instances,
-                        starting_offset,
-                    )?;
-                    bytes.append(&mut push_bytes);
+                    constant_gen(&name, contract, ir_byte.span).map(|push_bytes| {
+                        offset += push_bytes.len();
+                        tracing::debug!(target: "codegen", "OFFSET: {}, PUSH BYTES: {}", offset, push_bytes);
+                        bytes.push((starting_offset, push_bytes));
+                    })
                 }
+                IRByteType::Statement(s) => statement_gen(
+                    &s,
+                    contract,
+                    macro_def,
+                    scope,
+                    &mut offset,
+                    mis,
+                    &mut jump_table,
+                    &mut label_indices,
+                    &mut table_instances,
+                    &mut offset_expressions,
+                    &mut errors,
+                    &mut source_map,
+                    starting_offset,
+                    cache,
+                )
+                .map(|mut push_bytes| bytes.append(&mut push_bytes)),
                 IRByteType::ArgCall(arg_name) => {
                     // Bubble up arg call by looking through the previous scopes.
                     // Once the arg value is found, add it to `bytes`
                     bubble_arg_call(
                         &arg_name,
                         &mut bytes,
-                        &macro_def,
+                        macro_def,
                         contract,
                         scope,
                         &mut offset,
                         mis,
                         &mut jump_table,
-                    )?
+                    )
+                }
+            };
+            // Immutables are pushed as zeroed placeholders, relocated by the constructor
+            let immutable = match bytes.last_mut() {
+                Some((_, b)) if pushes_constant && res.is_ok() => relocate_immutable(b, contract),
+                _ => None,
+            };
+            match res {
+                Ok(()) if !inlined && offset > starting_offset => {
+                    let relocation = if let Some(name) = immutable {
+                        Some(Relocation::Immutable(name))
+                    } else if let Some(jump) =
+                        jump_table.get(&starting_offset).and_then(|jumps| jumps.first())
+                    {
+                        Some(Relocation::JumpLabel(jump.label))
+                    } else {
+                        table_instances
+                            .last()
+                            .filter(|jump| jump.bytecode_index == starting_offset)
+                            .map(|jump| Relocation::TableStart(jump.label.to_string()))
+                            .or_else(|| {
+                                offset_expressions
+                                    .last()
+                                    .filter(|oe| oe.bytecode_index == starting_offset)
+                                    .map(|oe| Relocation::LabelOffsets(oe.expression.to_string()))
+                            })
+                    };
+                    source_map.push(SourceMapping {
+                        offset: starting_offset,
+                        size: offset - starting_offset,
+                        span,
+                        table: None,
+                        invocations: vec![],
+                        relocation,
+                    });
+                }
+                Ok(()) => {}
+                Err(e) => {
+                    tracing::error!(target: "codegen", "MACRO \"{}\" STATEMENT FAILED: {:?}", macro_def.name, e.kind);
+                    errors.push(e);
                 }
             }
         }
@@ -297,13 +621,29 @@ impl Codegen {
             tracing::warn!(target: "codegen", "ATTEMPTED MACRO INVOCATION POP FAILED AT SCOPE: {}", scope.len());
         }
 
-        let bytecode: String = bytes.iter().map(|byte| byte.0.to_string()).collect();
+        let bytecode: String = bytes.iter().map(|(_, b)| b.to_string()).collect();
         tracing::info!(target: "codegen", "MACRO \"{}\" GENERATED BYTECODE EXCLUDING JUMPS: {}", macro_def.name, bytecode);
 
         // Fill JUMPDEST placeholders
         let (bytes, unmatched_jumps) = Codegen::fill_unmatched(bytes, &jump_table, &label_indices)?;
 
-        Ok(BytecodeRes { bytes, label_indices, unmatched_jumps, table_instances })
+        // Fill the offset expressions whose labels are laid out
+        let (bytes, unmatched_offsets) =
+            Codegen::fill_offsets(bytes, offset_expressions, &label_indices, contract, &mut errors);
+
+        let res = BytecodeRes {
+            bytes,
+            label_indices,
+            unmatched_jumps,
+            table_instances,
+            unmatched_offsets,
+            errors,
+            source_map,
+        };
+        if let Some(key) = key {
+            cache.insert(key, generated_at, &res);
+        }
+        Ok(res)
     }
 
     /// Helper associated function to fill unmatched jump dests.
@@ -333,32 +673,29 @@ impl Codegen {
                         // Check if the jump label has been defined. If not, add `jump` to the
                         // unmatched jumps and define its `bytecode_index`
                         // at `code_index`
-                        if let Some(jump_index) = label_indices.get(jump.label.as_str()) {
-                            // Format the jump index as a 2 byte hex number
-                            let jump_value = format!("{:04x}", jump_index);
-
-                            // Get the bytes before & after the placeholder
-                            let before = &formatted_bytes.0[0..jump.bytecode_index + 2];
-                            let after = &formatted_bytes.0[jump.bytecode_index + 6..];
-
-                            // Check if a jump dest placeholder is present
-                            if !&formatted_bytes.0[jump.bytecode_index + 2..jump.bytecode_index + 6]
-                                .eq("xxxx")
-                            {
+                        if let Some(jump_index) = label_indices.get(&jump.label) {
+                            if *jump_index > u16::MAX as usize {
                                 tracing::error!(
                                     target: "codegen",
-                                    "JUMP DESTINATION PLACEHOLDER NOT FOUND FOR JUMPLABEL {}",
+                                    "JUMP DESTINATION {} OF JUMPLABEL {} EXCEEDS TWO BYTES",
+                                    jump_index,
                                     jump.label
                                 );
                             }
 
-                            // Replace the "xxxx" placeholder with the jump value
-                            formatted_bytes = Bytes(format!("{}{}{}", before, jump_value, after));
+                            // Write the jump index over the placeholder, as a 2 byte number
+                            if !formatted_bytes.fill(jump.bytecode_index, *jump_index as u16) {
+                                tracing::error!(
+                                    target: "codegen",
+                                    "JUMP DESTINATION PLACEHOLDER NOT FOUND FOR JUMPLABEL {}",
+                                    jump.label
+                                );
+                            }
                         } else {
                             // The jump did not have a corresponding label index. Add it to the
                             // unmatched jumps vec.
                             unmatched_jumps.push(Jump {
-                                label: jump.label.clone(),
+                                label: jump.label,
                                 bytecode_index: code_index,
                                 span: jump.span.clone(),
                             });
@@ -373,6 +710,56 @@ impl Codegen {
         Ok((bytes, unmatched_jumps))
     }
 
+    /// Helper associated function to fill the placeholders of offset expressions.
+    ///
+    /// Expressions whose labels are all laid out are evaluated, and their value written over the
+    /// zeroed placeholder of their push. Expressions using a label that isn't laid out yet are
+    /// returned, to be filled by the macros invoking this one like unmatched jumps.
+    ///
+    /// Expressions failing to evaluate, or whose value doesn't fit in two bytes, are added to
+    /// `errors`, leaving their placeholder.
+    #[allow(clippy::type_complexity)]
+    pub fn fill_offsets(
+        mut bytes: Vec<(usize, Bytes)>,
+        offset_expressions: OffsetExpressions,
+        label_indices: &LabelIndices,
+        contract: &Contract,
+        errors: &mut Vec<CodegenError>,
+    ) -> (Vec<(usize, Bytes)>, OffsetExpressions) {
+        let mut unmatched_offsets = OffsetExpressions::default();
+        for oe in offset_expressions {
+            let Some(resolved) = oe.expression.resolve_offsets(label_indices) else {
+                unmatched_offsets.push(oe);
+                continue
+            };
+            let value = match evaluate(&resolved, contract, &oe.span, &mut vec![], &mut vec![]) {
+                Ok(value) if value <= U256::from(u16::MAX) => value.as_usize(),
+                Ok(value) => {
+                    tracing::error!(target: "codegen", "OFFSET EXPRESSION {} OUT OF RANGE", oe.expression);
+                    errors.push(CodegenError {
+                        kind: CodegenErrorKind::OffsetOutOfRange(oe.expression.to_string()),
+                        span: oe.span.clone(),
+                        token: None,
+                        help: Some(format!("the expression evaluates to {:#x}", value)),
+                    });
+                    continue
+                }
+                Err(e) => {
+                    errors.push(e);
+                    continue
+                }
+            };
+            let placeholder = bytes.iter_mut().find(|(code_index, b)| {
+                *code_index == oe.bytecode_index && b.0.first() == Some(&Opcode::Push2.byte())
+            });
+            if let Some((_, b)) = placeholder {
+                b.fill(0, value as u16);
+                tracing::info!(target: "codegen", "FILLED OFFSET EXPRESSION {} WITH {}", oe.expression, value);
+            }
+        }
+        (bytes, unmatched_offsets)
+    }
+
     /// Generate a codegen artifact
     ///
     /// # Arguments
@@ -380,6 +767,10 @@ impl Codegen {
     /// * `args` - A vector of Tokens representing constructor arguments
     /// * `main_bytecode` - The compiled MAIN Macro bytecode
     /// * `constructor_bytecode` - The compiled `CONSTRUCTOR` Macro bytecode
+    ///
+    /// If the cached artifact has `immutable_references`, the constructor set the immutables into
+    /// memory, at their offsets in the runtime bytecode. The bootstrap then copies the rest of
+    /// the runtime bytecode around them, and returns the patched copy.
     pub fn churn(
         &mut self,
         file: Arc<FileSource>,
@@ -387,7 +778,7 @@ impl Codegen {
         main_bytecode: &str,
         constructor_bytecode: &str,
     ) -> Result<Artifact, CodegenError> {
-        let mut artifact: &mut Artifact = if let Some(art) = &mut self.artifact {
+        let artifact: &mut Artifact = if let Some(art) = &mut self.artifact {
             art
         } else {
             self.artifact = Some(Artifact::default());
@@ -397,10 +788,7 @@ impl Codegen {
         let contract_length = main_bytecode.len() / 2;
         let constructor_length = constructor_bytecode.len() / 2;
 
-        let encoded: Vec<Vec<u8>> =
-            args.iter().map(|tok| ethers_core::abi::encode(&[tok.clone()])).collect();
-        let hex_args: Vec<String> = encoded.iter().map(|tok| hex::encode(tok.as_slice())).collect();
-        let constructor_args = hex_args.join("");
+        let constructor_args = Codegen::hex_constructor_args(&args);
 
         // Constructor size optimizations
         let mut bootstrap_code_size = 9;
@@ -426,7 +814,15 @@ impl Codegen {
         };
 
         // Generate the final bytecode
-        let bootstrap_code = format!("{}80{}3d393df3", contract_size, contract_code_offset);
+        let bootstrap_code = if artifact.immutable_references.is_empty() {
+            format!("{}80{}3d393df3", contract_size, contract_code_offset)
+        } else {
+            Codegen::immutables_bootstrap(
+                &artifact.immutable_references,
+                contract_length,
+                constructor_length,
+            )
+        };
         let constructor_code = format!("{}{}", constructor_bytecode, bootstrap_code);
         artifact.bytecode =
             format!("{}{}{}", constructor_code, main_bytecode, constructor_args).to_lowercase();
@@ -435,6 +831,42 @@ impl Codegen {
         Ok(artifact.clone())
     }
 
+    /// Generates the bootstrap deploying the runtime bytecode with the immutables set in memory
+    ///
+    /// The runtime bytecode between the immutable placeholders is copied to memory with a
+    /// `CODECOPY` each, pushing offsets with two bytes so that the bootstrap size is known
+    /// upfront.
+    fn immutables_bootstrap(
+        references: &BTreeMap<String, Vec<ImmutableReference>>,
+        contract_length: usize,
+        constructor_length: usize,
+    ) -> String {
+        let mut placeholders = references.values().flatten().map(|r| r.start).collect::<Vec<_>>();
+        placeholders.sort_unstable();
+
+        // The runtime bytecode ranges to copy, around the placeholders
+        let mut copies = vec![];
+        let mut copied = 0;
+        for start in placeholders.into_iter().chain(std::iter::once(contract_length)) {
+            if start > copied {
+                copies.push((copied, start - copied));
+            }
+            copied = start + 32;
+        }
+
+        let push2 = |n: usize| format!("61{}", pad_n_bytes(format!("{:x}", n).as_str(), 2));
+        let contract_code_offset = constructor_length + copies.len() * 10 + 5;
+        let mut bootstrap_code = String::new();
+        for (offset, size) in copies {
+            bootstrap_code.push_str(&push2(size));
+            bootstrap_code.push_str(&push2(contract_code_offset + offset));
+            bootstrap_code.push_str(&push2(offset));
+            bootstrap_code.push_str("39");
+        }
+        bootstrap_code.push_str(&format!("{}3df3", push2(contract_length)));
+        bootstrap_code
+    }
+
     /// Encode constructor arguments as ethers_core::abi::token::Token
     pub fn encode_constructor_args(args: Vec<String>) -> Vec<ethers_core::abi::token::Token> {
         let tokens: Vec<ethers_core::abi::token::Token> =
@@ -442,6 +874,13 @@ impl Codegen {
         tokens
     }
 
+    /// ABI encodes constructor arguments, as appended to the creation bytecode
+    pub fn hex_constructor_args(args: &[ethers_core::abi::token::Token]) -> String {
+        args.iter()
+            .map(|tok| hex::encode(ethers_core::abi::encode(std::slice::from_ref(tok))))
+            .collect()
+    }
+
     /// Export
     ///
     /// Writes a Codegen Artifact out to the specified file.
@@ -469,6 +908,7 @@ impl Codegen {
                         })),
                     }]),
                     token: None,
+                    help: None,
                 })
             }
         }
@@ -487,6 +927,7 @@ impl Codegen {
                     })),
                 }]),
                 token: None,
+                help: None,
             })
         }
         Ok(())
@@ -518,10 +959,8 @@ impl Codegen {
 
         // If an output's specified, write the artifact out
         if let Some(o) = output {
-            if let Err(e) = Codegen::export(o, art) {
-                // Error message is sent to tracing in `export` if an error occurs
-                return Err(e)
-            }
+            // Error message is sent to tracing in `export` if an error occurs
+            Codegen::export(o, art)?
         }
 
         // Return the abi
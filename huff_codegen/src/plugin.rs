@@ -0,0 +1,46 @@
+//! ## Plugins
+//!
+//! Hooks into code generation, letting external crates transform a contract without forking
+//! the compiler.
+//!
+//! A [CodegenPlugin] is handed the contract before its macros are expanded, to inject preludes
+//! or rewrite the invocations of its own builtins into regular statements, then the bytecode of
+//! each expanded macro, to instrument it, and finally the main and constructor bytecode before
+//! they are assembled into the artifact. Plugins run in the order they are registered, and every
+//! hook defaults to doing nothing.
+
+use std::fmt::Debug;
+
+use huff_utils::{ast::Contract, error::CodegenError};
+
+/// A transform of the contract and bytecode run at the stages of code generation
+pub trait CodegenPlugin: Debug + Send + Sync {
+    /// The name of the plugin, as logged
+    fn name(&self) -> &str;
+
+    /// Transforms the contract before its `MAIN` and `CONSTRUCTOR` macros are expanded
+    fn before_expansion(&self, _contract: &mut Contract) -> Result<(), CodegenError> {
+        Ok(())
+    }
+
+    /// Transforms the bytecode of an expanded macro, `MAIN` or `CONSTRUCTOR`, including the
+    /// bytes of its tables
+    fn after_expansion(
+        &self,
+        _macro_name: &str,
+        _bytecode: &mut String,
+        _contract: &Contract,
+    ) -> Result<(), CodegenError> {
+        Ok(())
+    }
+
+    /// Transforms the main and constructor bytecode before they are assembled into the
+    /// deployable bytecode
+    fn before_assembly(
+        &self,
+        _main_bytecode: &mut String,
+        _constructor_bytecode: &mut String,
+    ) -> Result<(), CodegenError> {
+        Ok(())
+    }
+}
@@ -6,21 +6,37 @@ use huff_utils::{ast, prelude::*};
 #[test]
 fn constructs_valid_abi() {
     let constructor = ast::MacroDefinition {
-        name: "CONSTRUCTOR".to_string(),
+        name: "CONSTRUCTOR".into(),
         parameters: vec![],
         statements: vec![],
         takes: 0,
         returns: 0,
         span: AstSpan(vec![]),
+        internal: false,
     };
     let contract = Contract {
-        macros: vec![constructor],
+        macros: vec![constructor].into(),
         invocations: vec![],
         imports: vec![],
-        constants: vec![],
-        functions: vec![],
-        events: vec![],
-        tables: vec![],
+        constants: Arena::new(),
+        immutables: Arena::new(),
+        aliases: Arena::new(),
+        functions: Arena::new(),
+        events: Arena::new(),
+        errors: Arena::new(),
+        tables: Arena::new(),
+        assertions: vec![],
+        fallback: None,
+        receive: None,
+        tests: vec![],
+        natspec: Default::default(),
+        artifacts: vec![],
+        storage_layout: Default::default(),
+        max_expansion_depth: None,
+        panics: Default::default(),
+        flags: Default::default(),
+        repeat_counts: Default::default(),
+        namespaces: vec![],
     };
 
     // Generate the abi from the contract
@@ -29,9 +45,10 @@ fn constructs_valid_abi() {
     assert_eq!(
         abi,
         Abi {
-            constructor: Some(Constructor { inputs: vec![] }),
+            constructor: Some(Constructor { inputs: vec![], payable: false }),
             functions: BTreeMap::new(),
             events: BTreeMap::new(),
+            errors: BTreeMap::new(),
             receive: false,
             fallback: false
         }
@@ -41,21 +58,37 @@ fn constructs_valid_abi() {
 #[test]
 fn missing_constructor_fails() {
     let _constructor = ast::MacroDefinition {
-        name: "CONSTRUCTOR".to_string(),
+        name: "CONSTRUCTOR".into(),
         parameters: vec![],
         statements: vec![],
         takes: 0,
         returns: 0,
         span: AstSpan(vec![]),
+        internal: false,
     };
     let contract = Contract {
-        macros: vec![],
+        macros: Arena::new(),
         invocations: vec![],
         imports: vec![],
-        constants: vec![],
-        functions: vec![],
-        events: vec![],
-        tables: vec![],
+        constants: Arena::new(),
+        immutables: Arena::new(),
+        aliases: Arena::new(),
+        functions: Arena::new(),
+        events: Arena::new(),
+        errors: Arena::new(),
+        tables: Arena::new(),
+        assertions: vec![],
+        fallback: None,
+        receive: None,
+        tests: vec![],
+        natspec: Default::default(),
+        artifacts: vec![],
+        storage_layout: Default::default(),
+        max_expansion_depth: None,
+        panics: Default::default(),
+        flags: Default::default(),
+        repeat_counts: Default::default(),
+        namespaces: vec![],
     };
 
     // Generate the abi from the contract
@@ -15,7 +15,7 @@ fn lex_erc20_from_source_benchmark(c: &mut Criterion) {
     .collect();
 
     // Recurse file deps + generate flattened source
-    let file_source = file_sources.get(0).unwrap();
+    let file_source = file_sources.first().unwrap();
     let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source)).unwrap();
     let flattened = FileSource::fully_flatten(Arc::clone(&recursed_file_source));
     let full_source = FullFileSource {
@@ -42,7 +42,7 @@ fn parse_erc20_benchmark(c: &mut Criterion) {
     .collect();
 
     // Recurse file deps + generate flattened source
-    let file_source = file_sources.get(0).unwrap();
+    let file_source = file_sources.first().unwrap();
     let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source)).unwrap();
     let flattened = FileSource::fully_flatten(Arc::clone(&recursed_file_source));
     let full_source = FullFileSource {
@@ -74,7 +74,7 @@ fn codegen_erc20_benchmark(c: &mut Criterion) {
     .collect();
 
     // Recurse file deps + generate flattened source
-    let file_source = file_sources.get(0).unwrap();
+    let file_source = file_sources.first().unwrap();
     let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source)).unwrap();
     let flattened = FileSource::fully_flatten(Arc::clone(&recursed_file_source));
     let full_source = FullFileSource {
@@ -118,7 +118,7 @@ fn erc20_compilation_benchmark(c: &mut Criterion) {
             .collect();
 
         // Recurse file deps + generate flattened source
-        let file_source = file_sources.get(0).unwrap();
+        let file_source = file_sources.first().unwrap();
         let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source)).unwrap();
         let flattened = FileSource::fully_flatten(Arc::clone(&recursed_file_source));
         let full_source = FullFileSource {
@@ -158,7 +158,7 @@ fn erc721_compilation_benchmark(c: &mut Criterion) {
             .collect();
 
         // Recurse file deps + generate flattened source
-        let file_source = file_sources.get(0).unwrap();
+        let file_source = file_sources.first().unwrap();
         let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source)).unwrap();
         let flattened = FileSource::fully_flatten(Arc::clone(&recursed_file_source));
         let full_source = FullFileSource {
@@ -2,7 +2,6 @@
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
 #![forbid(unsafe_code)]
-#![forbid(where_clauses_object_safety)]
 
 use huff_codegen::*;
 use huff_lexer::*;
@@ -10,38 +9,195 @@ use huff_parser::*;
 use huff_utils::prelude::*;
 use rayon::prelude::*;
 use std::{
+    collections::BTreeMap,
     ffi::OsString,
-    fs,
+    fmt, fs,
     path::{Path, PathBuf},
-    sync::Arc,
-    time::SystemTime,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
 };
-use tracing_subscriber::{filter::Directive, EnvFilter};
+pub use tracing_subscriber::filter::Directive;
+use tracing_subscriber::{fmt::writer::BoxMakeWriter, EnvFilter};
 use uuid::Uuid;
 
+/// The maximum runtime bytecode size in bytes, as defined by EIP-170
+pub const MAX_CONTRACT_SIZE: usize = 24576;
+
+/// The name of the macro a snippet compiled by [Compiler::snippet] is the body of
+pub const SNIPPET_MACRO: &str = "SNIPPET";
+
+/// The targets the compiler logs its tracing events to, including the crates logging to their
+/// module path
+pub const TRACING_TARGETS: [&str; 18] = [
+    "abi",
+    "analysis",
+    "ast",
+    "cli",
+    "codegen",
+    "core",
+    "flatten",
+    "fmt",
+    "io",
+    "lexer",
+    "lsp",
+    "manifest",
+    "parser",
+    "huff_codegen",
+    "huff_core",
+    "huff_lexer",
+    "huff_parser",
+    "huff_utils",
+];
+
+/// The formats tracing events are logged in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogFormat {
+    /// A human readable line per event
+    #[default]
+    Text,
+    /// A json object per line, with the fields of the event flattened
+    Json,
+}
+
+impl LogFormat {
+    /// Every log format
+    pub const ALL: [LogFormat; 2] = [LogFormat::Text, LogFormat::Json];
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LogFormat::Text => write!(f, "text"),
+            LogFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LogFormat::ALL.into_iter().find(|v| v.to_string() == s.to_lowercase()).ok_or_else(|| {
+            format!(
+                "Unknown log format \"{}\". Available formats: {}",
+                s,
+                LogFormat::ALL.map(|v| v.to_string()).join(", ")
+            )
+        })
+    }
+}
+
+/// The configuration of the tracing subscriber logging a compilation
+#[derive(Debug, Default)]
+pub struct TracingConfig {
+    /// The level logged for every [compiler target](TRACING_TARGETS), logging nothing but the
+    /// `RUST_LOG` directives without one
+    pub level: Option<tracing::Level>,
+    /// The directives filtering single targets, like `codegen=trace`, over the level
+    pub directives: Vec<Directive>,
+    /// The format events are logged in
+    pub format: LogFormat,
+    /// The file events are appended to, instead of stdout
+    pub log_file: Option<PathBuf>,
+}
+
+impl TracingConfig {
+    /// The level logged for a verbosity, the number of times `-v` is passed: info, debug, then
+    /// trace
+    pub fn level_of(verbosity: u8) -> Option<tracing::Level> {
+        match verbosity {
+            0 => None,
+            1 => Some(tracing::Level::INFO),
+            2 => Some(tracing::Level::DEBUG),
+            _ => Some(tracing::Level::TRACE),
+        }
+    }
+
+    /// The filter of the logged events, combining the `RUST_LOG` directives, the level of the
+    /// compiler targets and the target directives, in increasing precedence
+    pub fn env_filter(&self) -> EnvFilter {
+        let mut env_filter = EnvFilter::from_default_env();
+        if let Some(level) = self.level {
+            for target in TRACING_TARGETS {
+                let directive = format!("{}={}", target, level).parse::<Directive>();
+                if let Ok(d) = directive {
+                    env_filter = env_filter.add_directive(d);
+                }
+            }
+        }
+        for d in self.directives.iter() {
+            env_filter = env_filter.add_directive(d.clone());
+        }
+        env_filter
+    }
+}
+
+/// The result of compiling a single top-level input file
+#[derive(Debug, Clone)]
+pub struct FileCompileResult {
+    /// The path of the input file
+    pub path: String,
+    /// The generated artifact, or the error that failed compilation
+    pub result: Result<Arc<Artifact>, Arc<CompilerError>>,
+    /// Warnings encountered while compiling the file
+    pub warnings: Vec<CompilerWarning>,
+    /// The time spent in each phase of compiling the file, and the work done
+    pub stats: PhaseStats,
+}
+
+impl FileCompileResult {
+    /// Returns the artifact if the file compiled successfully
+    pub fn artifact(&self) -> Option<&Arc<Artifact>> {
+        self.result.as_ref().ok()
+    }
+
+    /// Returns the error if the file failed to compile
+    pub fn error(&self) -> Option<&Arc<CompilerError>> {
+        self.result.as_ref().err()
+    }
+
+    /// All diagnostics of the file, errors first and then warnings
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics =
+            self.error().map(|e| Diagnostic::from_compiler_error(e)).unwrap_or_default();
+        diagnostics.extend(self.warnings.iter().map(Diagnostic::from));
+        diagnostics
+    }
+}
+
+/// A test definition compiled into the bytecode its runs execute
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledTest {
+    /// The test name
+    pub name: String,
+    /// The types of the parameters, ABI-encoded into the calldata of each run
+    pub inputs: Vec<FunctionParamType>,
+    /// The bytecode of the test body, executed as the runtime bytecode of a contract
+    pub bytecode: String,
+}
+
 /// ## The Core Huff Compiler
 ///
 /// #### Usage
 ///
-/// The canonical way to instantiate a Compiler instance is using the public associated
-/// [new](Compiler::new) function.
-///
-/// Let's say we want to create a Compiler for the `ERC20.huff` contract located in [huff-examples](https://github.com/huff-language/huff-examples/blob/main/erc20/contracts/ERC20.huff).
-///
-/// We want our Compiler to output to an `artifacts` directory, with no constructor arguments, and
-/// no verbose output:
+/// The canonical way to instantiate a Compiler instance is with a [CompilerBuilder], from
+/// [Compiler::builder], producing an immutable [CompilerSession] that derefs to its Compiler.
 ///
 /// ```rust
 /// use huff_core::Compiler;
-/// use std::sync::Arc;
+/// use huff_utils::prelude::EVMVersion;
 ///
-/// let compiler = Compiler::new(
-///     Arc::new(vec!["../huff-examples/erc20/contracts/ERC20.huff".to_string()]),
-///     Some("./artifacts".to_string()),
-///     None,
-///     false
-/// );
+/// let session = Compiler::builder()
+///     .source("./src/Counter.huff")
+///     .remapping("solady/=lib/solady/src/".parse().unwrap())
+///     .evm_version(EVMVersion::Shanghai)
+///     .output("./artifacts")
+///     .build();
+/// assert_eq!(session.evm_version, EVMVersion::Shanghai);
 /// ```
+///
+/// The deprecated [new](Compiler::new) function instantiates it from loose arguments.
 #[derive(Default, Debug, Clone)]
 pub struct Compiler {
     /// The location of the files to compile
@@ -54,10 +210,196 @@ pub struct Compiler {
     pub optimize: bool,
     /// Generate and log bytecode
     pub bytecode: bool,
+    /// Whether to accept uppercase and mixed-case opcode mnemonics
+    pub case_insensitive_opcodes: bool,
+    /// The EVM version to generate bytecode for
+    pub evm_version: EVMVersion,
+    /// The shape artifacts are exported in
+    pub artifact_format: ArtifactFormat,
+    /// The levels warnings are reported at, by lint name
+    pub lint_levels: LintLevels,
+    /// The pinned manifests of included libraries, checked against the library symbols used
+    pub manifests: Vec<SymbolManifest>,
+    /// The maximum depth of nested macro invocations, defaulting to
+    /// [DEFAULT_MAX_EXPANSION_DEPTH]
+    pub max_expansion_depth: Option<usize>,
+    /// The constants defined when compiling, replacing those of the same name
+    pub defines: Vec<ConstantDefinition>,
+    /// The constants overridden when compiling, reporting those the contract doesn't define
+    pub overrides: Vec<ConstantDefinition>,
+    /// The remappings of include paths
+    pub remappings: Vec<Remapping>,
+    /// The plugins hooked into code generation, run in order
+    pub plugins: Vec<Arc<dyn CodegenPlugin>>,
+    /// The custom builtins invoked in macro bodies, lowered before code generation
+    pub builtins: Vec<Arc<dyn CustomBuiltin>>,
+    /// Warnings collected during compilation
+    pub warnings: Arc<Mutex<Vec<CompilerWarning>>>,
+}
+
+/// Configures the settings of a [CompilerSession]
+#[derive(Default, Debug, Clone)]
+pub struct CompilerBuilder {
+    compiler: Compiler,
+}
+
+impl CompilerBuilder {
+    /// Adds a file or directory to compile
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.compiler.sources).push(source.into());
+        self
+    }
+
+    /// Sets the files and directories to compile
+    pub fn sources(mut self, sources: Vec<String>) -> Self {
+        self.compiler.sources = Arc::new(sources);
+        self
+    }
+
+    /// Adds a remapping of include paths
+    pub fn remapping(mut self, remapping: Remapping) -> Self {
+        self.compiler.remappings.push(remapping);
+        self
+    }
+
+    /// Sets the remappings of include paths
+    pub fn remappings(mut self, remappings: Vec<Remapping>) -> Self {
+        self.compiler.remappings = remappings;
+        self
+    }
+
+    /// Sets the EVM version to generate bytecode for
+    pub fn evm_version(mut self, evm_version: EVMVersion) -> Self {
+        self.compiler.evm_version = evm_version;
+        self
+    }
+
+    /// Sets whether to optimize compilation
+    pub fn optimize(mut self, optimize: bool) -> Self {
+        self.compiler.optimize = optimize;
+        self
+    }
+
+    /// Sets whether to generate and log the bytecode
+    pub fn bytecode(mut self, bytecode: bool) -> Self {
+        self.compiler.bytecode = bytecode;
+        self
+    }
+
+    /// Sets the location artifacts are exported to, exporting none without one
+    pub fn output(mut self, output: impl Into<String>) -> Self {
+        self.compiler.output = Some(output.into());
+        self
+    }
+
+    /// Sets the shape artifacts are exported in
+    pub fn artifact_format(mut self, artifact_format: ArtifactFormat) -> Self {
+        self.compiler.artifact_format = artifact_format;
+        self
+    }
+
+    /// Sets the constructor arguments, ABI-encoded after the creation bytecode
+    pub fn constructor_args(mut self, args: Vec<String>) -> Self {
+        self.compiler.construct_args = Some(args);
+        self
+    }
+
+    /// Defines a constant, replacing the constant of the same name
+    pub fn define(mut self, constant: ConstantDefinition) -> Self {
+        self.compiler.defines.push(constant);
+        self
+    }
+
+    /// Sets the constants defined when compiling
+    pub fn defines(mut self, defines: Vec<ConstantDefinition>) -> Self {
+        self.compiler.defines = defines;
+        self
+    }
+
+    /// Overrides a constant, reported if the contract doesn't define it
+    pub fn override_constant(mut self, constant: ConstantDefinition) -> Self {
+        self.compiler.overrides.push(constant);
+        self
+    }
+
+    /// Sets the constants overridden when compiling
+    pub fn overrides(mut self, overrides: Vec<ConstantDefinition>) -> Self {
+        self.compiler.overrides = overrides;
+        self
+    }
+
+    /// Sets whether to accept uppercase and mixed-case opcode mnemonics
+    pub fn case_insensitive_opcodes(mut self, case_insensitive_opcodes: bool) -> Self {
+        self.compiler.case_insensitive_opcodes = case_insensitive_opcodes;
+        self
+    }
+
+    /// Sets the levels warnings are reported at
+    pub fn lint_levels(mut self, lint_levels: LintLevels) -> Self {
+        self.compiler.lint_levels = lint_levels;
+        self
+    }
+
+    /// Sets the pinned manifests of included libraries
+    pub fn manifests(mut self, manifests: Vec<SymbolManifest>) -> Self {
+        self.compiler.manifests = manifests;
+        self
+    }
+
+    /// Sets the maximum depth of nested macro invocations
+    pub fn max_expansion_depth(mut self, max_expansion_depth: usize) -> Self {
+        self.compiler.max_expansion_depth = Some(max_expansion_depth);
+        self
+    }
+
+    /// Adds a plugin hooked into code generation, run after those already added
+    pub fn plugin(mut self, plugin: impl CodegenPlugin + 'static) -> Self {
+        self.compiler.plugins.push(Arc::new(plugin));
+        self
+    }
+
+    /// Registers a custom builtin, invoked in macro bodies by its name
+    pub fn builtin(mut self, builtin: impl CustomBuiltin + 'static) -> Self {
+        self.compiler.builtins.push(Arc::new(builtin));
+        self
+    }
+
+    /// Builds the session compiling with the configured settings
+    pub fn build(self) -> CompilerSession {
+        CompilerSession { compiler: Arc::new(self.compiler) }
+    }
+}
+
+/// A compiler with immutable settings, built by a [CompilerBuilder]
+///
+/// The session derefs to its [Compiler] for the compilation entry points and to read the
+/// settings, and is cheap to clone and share between threads. Warnings are collected over the
+/// compilations of every clone.
+#[derive(Default, Debug, Clone)]
+pub struct CompilerSession {
+    compiler: Arc<Compiler>,
+}
+
+impl CompilerSession {
+    /// A builder starting from the settings of the session, to derive a new session
+    pub fn to_builder(&self) -> CompilerBuilder {
+        CompilerBuilder {
+            compiler: Compiler { warnings: Default::default(), ..self.compiler.as_ref().clone() },
+        }
+    }
+}
+
+impl std::ops::Deref for CompilerSession {
+    type Target = Compiler;
+
+    fn deref(&self) -> &Self::Target {
+        &self.compiler
+    }
 }
 
-impl<'a> Compiler {
+impl Compiler {
     /// Public associated function to instantiate a new compiler.
+    #[deprecated(note = "configure the compiler with `Compiler::builder` instead")]
     pub fn new(
         sources: Arc<Vec<String>>,
         output: Option<String>,
@@ -67,7 +409,42 @@ impl<'a> Compiler {
         if cfg!(feature = "verbose") || verbose {
             Compiler::init_tracing_subscriber(Some(vec![tracing::Level::INFO.into()]));
         }
-        Self { sources, output, construct_args, optimize: false, bytecode: false }
+        Self {
+            sources,
+            output,
+            construct_args,
+            optimize: false,
+            bytecode: false,
+            case_insensitive_opcodes: false,
+            evm_version: Default::default(),
+            artifact_format: Default::default(),
+            lint_levels: Default::default(),
+            manifests: vec![],
+            max_expansion_depth: None,
+            defines: vec![],
+            overrides: vec![],
+            remappings: vec![],
+            plugins: vec![],
+            builtins: vec![],
+            warnings: Default::default(),
+        }
+    }
+
+    /// Creates a [CompilerBuilder] to configure a [CompilerSession]
+    pub fn builder() -> CompilerBuilder {
+        CompilerBuilder::default()
+    }
+
+    /// Returns the warnings collected during compilation
+    pub fn get_warnings(&self) -> Vec<CompilerWarning> {
+        self.warnings.lock().map(|w| w.clone()).unwrap_or_default()
+    }
+
+    /// Adds warnings to the compiler's collected warnings
+    pub fn add_warnings(&self, warnings: Vec<CompilerWarning>) {
+        if let Ok(mut w) = self.warnings.lock() {
+            w.extend(warnings);
+        }
     }
 
     /// Tracing
@@ -86,6 +463,31 @@ impl<'a> Compiler {
         }
     }
 
+    /// Creates a new tracing subscriber from its configuration, filtering events by target and
+    /// logging them in its format to stdout or its log file.
+    pub fn init_tracing(config: &TracingConfig) -> Result<(), String> {
+        let writer = match &config.log_file {
+            Some(path) => {
+                let file = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| format!("Failed to open log file {:?}: {}", path, e))?;
+                BoxMakeWriter::new(Mutex::new(file))
+            }
+            None => BoxMakeWriter::new(std::io::stdout),
+        };
+        let subscriber_builder = tracing_subscriber::fmt()
+            .with_env_filter(config.env_filter())
+            .with_writer(writer)
+            .with_ansi(config.log_file.is_none());
+        let res = match config.format {
+            LogFormat::Text => subscriber_builder.try_init(),
+            LogFormat::Json => subscriber_builder.json().flatten_event(true).try_init(),
+        };
+        res.map_err(|e| format!("Failed to initialize tracing: {}", e))
+    }
+
     /// Executor
     ///
     /// The core compilation process.
@@ -93,163 +495,773 @@ impl<'a> Compiler {
     /// ### Steps
     ///
     /// 1. Transform inputs into File Paths with [transform_paths](Compiler::transform_paths).
-    /// 2. Fetch file sources in parallel with [fetch_sources](Compiler::fetch_sources).
-    /// 3. Recurse file dependencies in parallel with [recurse_deps](Compiler::recurse_deps).
-    /// 4. For each top-level file [Parallelized], generate the artifact using
-    /// [gen_artifact](Compiler::gen_artifact).
-    /// 5. Return the compiling error(s) or successfully generated artifacts.
-    pub fn execute(&self) -> Result<Vec<Arc<Artifact>>, Arc<CompilerError<'a>>> {
+    /// 2. For each top-level file [Parallelized], fetch its source with
+    ///    [fetch_sources](Compiler::fetch_sources), recurse its dependencies with
+    ///    [recurse_deps](Compiler::recurse_deps) and generate the artifact using
+    ///    [gen_artifact](Compiler::gen_artifact).
+    /// 3. Export the generated artifacts if every file compiled successfully.
+    /// 4. Return a [FileCompileResult](FileCompileResult) for each top-level file, in input order,
+    ///    with the time spent in each phase of compiling it.
+    ///
+    /// Only failing to resolve the input paths errors the whole batch.
+    pub fn execute(&self) -> Result<Vec<FileCompileResult>, Arc<CompilerError>> {
         // Grab the input files
         let file_paths: Vec<PathBuf> = Compiler::transform_paths(&self.sources)?;
 
-        // Parallel file fetching
-        let files: Vec<Result<Arc<FileSource>, CompilerError>> =
-            Compiler::fetch_sources(file_paths);
-
-        // Unwrap errors
-        let mut errors =
-            files.iter().filter_map(|rfs| rfs.as_ref().err()).collect::<Vec<&CompilerError>>();
-        if !errors.is_empty() {
-            let error = errors.remove(0);
-            return Err(Arc::new(error.clone()))
-        }
-
-        // Unpack files into their file sources
-        let files = files
-            .iter()
-            .filter_map(|fs| fs.as_ref().map(Arc::clone).ok())
-            .collect::<Vec<Arc<FileSource>>>();
-
-        // Parallel Dependency Resolution
-        let recursed_file_sources: Vec<Result<Arc<FileSource>, Arc<CompilerError<'a>>>> =
-            files.into_par_iter().map(Compiler::recurse_deps).collect();
-
-        // Collect Recurse Deps errors and try to resolve to the first one
-        let mut errors = recursed_file_sources
-            .iter()
-            .filter_map(|rfs| rfs.as_ref().err())
-            .collect::<Vec<&Arc<CompilerError>>>();
-        if !errors.is_empty() {
-            let error = errors.remove(0);
-            return Err(Arc::clone(error))
-        }
+        // Parallel File Fetching, Dependency Resolution and Compilation
+        let mut results: Vec<FileCompileResult> = file_paths
+            .into_par_iter()
+            .map(|path| {
+                let path = String::from(path.to_string_lossy());
+                let mut stats = PhaseStats { path: path.clone(), ..Default::default() };
+                let file = stats.time(Phase::FileIo, || {
+                    Compiler::fetch_sources(vec![PathBuf::from(&path)]).remove(0)
+                });
+                match file {
+                    Ok(f) => self.compile_file_with_stats(f, stats),
+                    Err(e) => FileCompileResult {
+                        path,
+                        result: Err(Arc::new(e)),
+                        warnings: vec![],
+                        stats,
+                    },
+                }
+            })
+            .collect();
 
-        // Unpack recursed dependencies into FileSources
-        let files = recursed_file_sources
-            .iter()
-            .filter_map(|fs| fs.as_ref().map(Arc::clone).ok())
-            .collect::<Vec<Arc<FileSource>>>();
-        tracing::info!(target: "core", "COMPILER RECURSED {} FILE DEPENDENCIES", files.len());
-
-        // Parallel Compilation
-        let potential_artifacts: Vec<Result<Artifact, CompilerError<'a>>> =
-            files.into_par_iter().map(|f| self.gen_artifact(f)).collect();
-
-        // Output errors + return OR print # of successfully compiled files
-        let mut errors: Vec<CompilerError<'a>> = vec![];
-        let mut artifacts: Vec<Arc<Artifact>> = vec![];
-        for r in potential_artifacts {
-            match r {
-                Ok(a) => artifacts.push(Arc::new(a)),
-                Err(ce) => errors.push(ce),
-            }
-        }
-        if !errors.is_empty() {
-            tracing::error!(target: "core", "{} FILES FAILED TO COMPILE", errors.len());
-            return Err(Arc::new(CompilerError::FailedCompiles(errors)))
+        // Log the number of failed OR successfully compiled files
+        let artifacts =
+            results.iter().filter_map(|r| r.artifact().map(Arc::clone)).collect::<Vec<_>>();
+        let failed = results.len() - artifacts.len();
+        if failed > 0 {
+            tracing::error!(target: "core", "{} FILES FAILED TO COMPILE", failed);
         }
         match artifacts.len() {
             0 => tracing::warn!(target: "core", "NO FILES COMPILED SUCCESSFULLY"),
             num => tracing::info!(target: "core", "{} FILES COMPILED SUCCESSFULLY", num),
         }
 
-        // Grab the output
-        let output = self.get_outputs();
+        // Export only if every file compiled, leaving previous artifacts in place otherwise
+        if failed == 0 {
+            let output = self.get_outputs();
+            let written = Compiler::export_artifacts(&artifacts, &output, self.artifact_format);
+            for result in results.iter_mut() {
+                if let Some(elapsed) = written.get(&result.path) {
+                    result.stats.record(Phase::ArtifactWrite, *elapsed);
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Compiles the files of artifacts again from their sources, returning the paths of those
+    /// whose artifacts differ from the first compile
+    ///
+    /// Artifacts are compared as the json they are exported as, so a build can be checked to be
+    /// reproducible before its artifacts are verified. A file failing to compile again differs.
+    pub fn nondeterministic_artifacts(&self, artifacts: &[Arc<Artifact>]) -> Vec<String> {
+        artifacts
+            .into_par_iter()
+            .filter(|a| {
+                let recompiled = Compiler::fetch_sources(vec![PathBuf::from(&a.file.path)])
+                    .remove(0)
+                    .map(|f| self.compile_file(f));
+                let reproduced = match recompiled.as_ref().map(|r| r.artifact()) {
+                    Ok(Some(again)) => {
+                        serde_json::to_string(again).ok() == serde_json::to_string(a).ok()
+                    }
+                    _ => false,
+                };
+                if !reproduced {
+                    tracing::error!(target: "core", "ARTIFACT OF \"{}\" DIFFERS WHEN COMPILED AGAIN", a.file.path);
+                }
+                !reproduced
+            })
+            .map(|a| a.file.path.clone())
+            .collect()
+    }
 
-        // Export
-        Compiler::export_artifacts(&artifacts, &output);
+    /// Compiles a single top-level file, resolving its dependencies first.
+    pub fn compile_file(&self, file: Arc<FileSource>) -> FileCompileResult {
+        let stats = PhaseStats { path: file.path.clone(), ..Default::default() };
+        self.compile_file_with_stats(file, stats)
+    }
 
-        Ok(artifacts)
+    /// Compiles a single top-level file, adding the time spent in each phase to its statistics.
+    fn compile_file_with_stats(
+        &self,
+        file: Arc<FileSource>,
+        mut stats: PhaseStats,
+    ) -> FileCompileResult {
+        let path = file.path.clone();
+        let mut warnings = vec![];
+        let result = stats.time(Phase::FileIo, || self.resolve_deps(file)).and_then(|f| {
+            self.gen_artifact_with_warnings(f, &mut warnings, &mut stats)
+                .map(Arc::new)
+                .map_err(Arc::new)
+        });
+        self.add_warnings(warnings.clone());
+        FileCompileResult { path, result, warnings, stats }
     }
 
     /// Artifact Generation
     ///
     /// Compiles a FileSource into an Artifact.
-    pub fn gen_artifact(&self, file: Arc<FileSource>) -> Result<Artifact, CompilerError<'a>> {
+    pub fn gen_artifact(&self, file: Arc<FileSource>) -> Result<Artifact, CompilerError> {
+        let mut warnings = vec![];
+        let res = self.gen_artifact_with_warnings(file, &mut warnings, &mut Default::default());
+        self.add_warnings(warnings);
+        res
+    }
+
+    /// Compiles a FileSource into an Artifact, collecting the file's warnings.
+    ///
+    /// Warnings are filtered by the compiler's lint levels, and denied warnings fail compilation.
+    fn gen_artifact_with_warnings(
+        &self,
+        file: Arc<FileSource>,
+        warnings: &mut Vec<CompilerWarning>,
+        stats: &mut PhaseStats,
+    ) -> Result<Artifact, CompilerError> {
+        let mut found = vec![];
+        let res = self.generate_artifact(file, &mut found, stats);
+        let (reported, denied) = self.lint_levels.apply(found);
+        warnings.extend(reported);
+        match res {
+            Ok(_) if !denied.is_empty() => {
+                tracing::error!(target: "core", "{} WARNING(S) DENIED", denied.len());
+                Err(CompilerError::DeniedWarnings(denied))
+            }
+            res => res,
+        }
+    }
+
+    /// Flattens, lexes and parses a FileSource into a Contract, collecting every warning found.
+    ///
+    /// The fallback and receive definitions are wired into `MAIN`, reentrancy guards are expanded
+    /// for the compiler's EVM version, storage pointers are derived, and the library symbols used
+    /// are checked against the pinned manifests, the well-known constants against their values.
+    fn parse_contract(
+        &self,
+        file: &Arc<FileSource>,
+        warnings: &mut Vec<CompilerWarning>,
+    ) -> Result<Contract, CompilerError> {
+        self.parse_contract_with_stats(file, warnings, &mut Default::default())
+    }
+
+    /// Parses a FileSource into a Contract, adding the time spent in each phase to its statistics.
+    fn parse_contract_with_stats(
+        &self,
+        file: &Arc<FileSource>,
+        warnings: &mut Vec<CompilerWarning>,
+        stats: &mut PhaseStats,
+    ) -> Result<Contract, CompilerError> {
+        let mut contract = self.parse_source_with_stats(file, warnings, stats)?;
+        contract.wire_entrypoints();
+        contract.expand_non_reentrant(self.evm_version);
+        contract.max_expansion_depth = self.max_expansion_depth;
+
+        // Cycles are reported along with the manifest and well-known constant mismatches
+        let cycles = stats
+            .time(Phase::StorageDerivation, || contract.try_derive_storage_pointers())
+            .err()
+            .unwrap_or_default();
+        let mut mismatches = cycles
+            .into_iter()
+            .chain(self.manifests.iter().flat_map(|m| m.check(&contract)))
+            .chain(check_well_known_constants(&contract))
+            .collect::<Vec<_>>();
+        match mismatches.len() {
+            0 => Ok(contract),
+            1 => Err(CompilerError::CodegenError(mismatches.remove(0))),
+            _ => Err(CompilerError::FailedCompiles(
+                mismatches.into_iter().map(CompilerError::CodegenError).collect(),
+            )),
+        }
+    }
+
+    /// Flattens, lexes and parses a FileSource into a Contract, without deriving its storage
+    /// pointers.
+    fn parse_source(
+        &self,
+        file: &Arc<FileSource>,
+        warnings: &mut Vec<CompilerWarning>,
+    ) -> Result<Contract, CompilerError> {
+        self.parse_source_with_stats(file, warnings, &mut Default::default())
+    }
+
+    /// Parses a FileSource into a Contract, without deriving its storage pointers, adding the
+    /// time spent lexing and parsing to its statistics.
+    fn parse_source_with_stats(
+        &self,
+        file: &Arc<FileSource>,
+        warnings: &mut Vec<CompilerWarning>,
+        stats: &mut PhaseStats,
+    ) -> Result<Contract, CompilerError> {
+        let start = Instant::now();
+
         // Fully Flatten a file into a source string containing source code of file and all
         // its dependencies
-        let flattened = FileSource::fully_flatten(Arc::clone(&file));
+        let flattened = FileSource::fully_flatten(Arc::clone(file));
         tracing::info!(target: "core", "FLATTENED SOURCE FILE \"{}\"", file.path);
         let full_source = FullFileSource {
             source: &flattened.0,
-            file: Some(Arc::clone(&file)),
+            file: Some(Arc::clone(file)),
             spans: flattened.1,
         };
         tracing::debug!(target: "core", "GOT FULL SOURCE FOR PATH: {:?}", file.path);
 
         // Perform Lexical Analysis
         // Create a new lexer from the FileSource, flattening dependencies
-        let lexer: Lexer = Lexer::new(full_source);
+        let mut lexer: Lexer = Lexer::new(full_source);
+        lexer.case_insensitive_opcodes = self.case_insensitive_opcodes;
 
-        // Grab the tokens from the lexer
-        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        // Grab the tokens from the lexer, reporting every lexical error at once
+        let mut tokens: Vec<Token> = vec![];
+        let mut errors: Vec<CompilerError> = vec![];
+        for res in lexer.by_ref() {
+            match res {
+                Ok(token) => tokens.push(token),
+                Err(e) => errors.push(CompilerError::LexicalError(e)),
+            }
+        }
+        warnings.append(&mut lexer.warnings);
+        stats.record(Phase::Lexing, start.elapsed());
+        if !errors.is_empty() {
+            tracing::error!(target: "core", "LEXICAL ANALYSIS FAILED FOR \"{}\"", file.path);
+            return Err(match errors.len() {
+                1 => errors.remove(0),
+                _ => CompilerError::FailedCompiles(errors),
+            })
+        }
         tracing::info!(target: "core", "LEXICAL ANALYSIS COMPLETE FOR \"{}\"", file.path);
         tracing::info!(target: "core", "└─ TOKEN COUNT: {}", tokens.len());
 
         // Parser incantation
         let mut parser = Parser::new(tokens, Some(file.path.clone()));
+        parser.defines = self.defines.clone();
+        parser.overrides = self.overrides.clone();
+        parser.remappings = self.remappings.clone();
+        parser.builtins = self.builtins.iter().map(|b| b.name().to_string()).collect();
 
-        // Parse into an AST
-        let parse_res = parser.parse().map_err(CompilerError::ParserError);
-        let mut contract = parse_res?;
-        contract.derive_storage_pointers();
+        // Parse into an AST, reporting every error the parser recovered from
+        let parsed = stats.time(Phase::Parsing, || parser.parse());
+        let parse_res = parsed.map_err(|e| match parser.errors.len() {
+            0 | 1 => CompilerError::ParserError(e),
+            _ => CompilerError::FailedCompiles(
+                parser.errors.drain(..).map(CompilerError::ParserError).collect(),
+            ),
+        });
+        let contract = parse_res?;
         tracing::info!(target: "core", "PARSED CONTRACT [{}]", file.path);
+        Ok(contract)
+    }
 
-        // Primary Bytecode Generation
-        // See huffc: https://github.com/huff-language/huffc/blob/2e5287afbfdf9cc977b204a4fd1e89c27375b040/src/compiler/processor.ts
-        let mut cg = Codegen::new();
-        let main_bytecode = match Codegen::generate_main_bytecode(&contract) {
-            Ok(mb) => mb,
-            Err(mut e) => {
-                // Add File Source to Span
-                e.span = AstSpan(
-                    e.span
-                        .0
-                        .into_iter()
-                        .map(|mut s| {
-                            s.file = Some(Arc::clone(&file));
-                            s
-                        })
-                        .collect::<Vec<Span>>(),
-                );
-                tracing::error!(target: "codegen", "Roll Failed with CodegenError: {:?}", e);
-                return Err(CompilerError::CodegenError(e))
+    /// Maps the runtime bytecode of a FileSource to the statements that generated it.
+    ///
+    /// Used to explain a program counter, eg. of a reverting trace, without a compiled source map.
+    pub fn runtime_source_map(
+        &self,
+        file: Arc<FileSource>,
+    ) -> Result<Vec<SourceMapping>, CompilerError> {
+        let contract = self.parse_contract(&file, &mut vec![])?;
+        Codegen::generate_source_map("MAIN", &contract).map_err(|mut errors| match errors.len() {
+            1 => CompilerError::CodegenError(errors.remove(0)),
+            _ => CompilerError::FailedCompiles(
+                errors.into_iter().map(CompilerError::CodegenError).collect(),
+            ),
+        })
+    }
+
+    /// Recovers the function dispatcher of the runtime bytecode of a FileSource.
+    ///
+    /// Selectors are named after the functions the contract defines, and resolved to the macros
+    /// they enter with the source map of `MAIN`.
+    pub fn dispatcher(&self, file: Arc<FileSource>) -> Result<Dispatcher, CompilerError> {
+        let contract = self.parse_contract(&file, &mut vec![])?;
+        let to_compiler_error = |mut errors: Vec<CodegenError>| match errors.len() {
+            1 => CompilerError::CodegenError(errors.remove(0)),
+            _ => CompilerError::FailedCompiles(
+                errors.into_iter().map(CompilerError::CodegenError).collect(),
+            ),
+        };
+        let runtime =
+            Codegen::generate_macro_bytecode("MAIN", &contract).map_err(to_compiler_error)?;
+        let source_map =
+            Codegen::generate_source_map("MAIN", &contract).map_err(to_compiler_error)?;
+        let mut dispatcher = Dispatcher::analyze(&runtime);
+        dispatcher.name_functions(&contract);
+        dispatcher.resolve_macros(&source_map);
+        Ok(dispatcher)
+    }
+
+    /// Collects the compilation statistics of a FileSource and the macros it defines.
+    ///
+    /// The compile time covers parsing the file and generating its runtime bytecode.
+    pub fn stats(&self, file: Arc<FileSource>) -> Result<FileStats, CompilerError> {
+        let start = Instant::now();
+        let contract = self.parse_contract(&file, &mut vec![])?;
+        let source_map =
+            Codegen::generate_source_map("MAIN", &contract).map_err(|mut errors| {
+                match errors.len() {
+                    1 => CompilerError::CodegenError(errors.remove(0)),
+                    _ => CompilerError::FailedCompiles(
+                        errors.into_iter().map(CompilerError::CodegenError).collect(),
+                    ),
+                }
+            })?;
+        let compile_time = start.elapsed();
+
+        let flattened = FileSource::fully_flatten(Arc::clone(&file));
+        let full_source = FullFileSource {
+            source: &flattened.0,
+            file: Some(Arc::clone(&file)),
+            spans: flattened.1,
+        };
+        let mut lexer = Lexer::new(full_source);
+        lexer.case_insensitive_opcodes = self.case_insensitive_opcodes;
+        let tokens = lexer
+            .filter_map(Result::ok)
+            .filter(|t| {
+                !matches!(t.kind, TokenKind::Whitespace | TokenKind::Comment(_) | TokenKind::Eof)
+            })
+            .count();
+
+        tracing::info!(target: "core", "COLLECTED STATS FOR \"{}\"", file.path);
+        Ok(FileStats::new(
+            &file.path,
+            tokens,
+            &contract,
+            &source_map,
+            compile_time.as_micros() as u64,
+        ))
+    }
+
+    /// Builds the macro call graph of a FileSource, sized by its runtime bytecode.
+    pub fn call_graph(&self, file: Arc<FileSource>) -> Result<CallGraph, CompilerError> {
+        let contract = self.parse_contract(&file, &mut vec![])?;
+        let source_map =
+            Codegen::generate_source_map("MAIN", &contract).map_err(|mut errors| {
+                match errors.len() {
+                    1 => CompilerError::CodegenError(errors.remove(0)),
+                    _ => CompilerError::FailedCompiles(
+                        errors.into_iter().map(CompilerError::CodegenError).collect(),
+                    ),
+                }
+            })?;
+        tracing::info!(target: "core", "BUILT CALL GRAPH OF \"{}\"", file.path);
+        Ok(CallGraph::new(&contract, &source_map))
+    }
+
+    /// Compiles a snippet of macro statements, which may use the definitions of a FileSource.
+    ///
+    /// The snippet is compiled as the body of the [SNIPPET_MACRO] macro, defined in a file
+    /// depending on the FileSource, so the definitions of the FileSource can't use that name.
+    pub fn snippet(
+        &self,
+        snippet: &str,
+        file: Option<Arc<FileSource>>,
+    ) -> Result<String, CompilerError> {
+        let source = format!(
+            "#define macro {}() = takes(0) returns(0) {{\n{}\n}}\n",
+            SNIPPET_MACRO, snippet
+        );
+        let snippet_file = Arc::new(FileSource {
+            path: "<snippet>".to_string(),
+            source: Some(source),
+            dependencies: file.map(|f| vec![f]),
+            ..Default::default()
+        });
+        let contract = self.parse_contract(&snippet_file, &mut vec![])?;
+        Codegen::generate_macro_bytecode(SNIPPET_MACRO, &contract).map_err(
+            |mut errors| match errors.len() {
+                1 => CompilerError::CodegenError(errors.remove(0)),
+                _ => CompilerError::FailedCompiles(
+                    errors.into_iter().map(CompilerError::CodegenError).collect(),
+                ),
+            },
+        )
+    }
+
+    /// Compiles the tests of a FileSource, which may use all of its definitions.
+    ///
+    /// Each test body is compiled like the `MAIN` macro of a contract, shadowing the macros
+    /// sharing its name. The errors of every test are collected.
+    pub fn tests(&self, file: Arc<FileSource>) -> Result<Vec<CompiledTest>, CompilerError> {
+        let contract = self.parse_contract(&file, &mut vec![])?;
+        let mut tests = vec![];
+        let mut errors = vec![];
+        for test in contract.tests.iter() {
+            // The snapshot shares the macros of the contract instead of copying them, only
+            // replacing the macro the test shadows
+            let mut test_contract = contract.clone();
+            match test_contract.macros.position(|m| m.name == test.name) {
+                Some(id) => test_contract.macros[id] = test.clone(),
+                None => {
+                    test_contract.macros.push(test.clone());
+                }
+            }
+            match Codegen::generate_macro_bytecode(&test.name, &test_contract) {
+                Ok(bytecode) => tests.push(CompiledTest {
+                    name: test.name.to_string(),
+                    inputs: test
+                        .parameters
+                        .iter()
+                        .map(|p| p.arg_type.clone().unwrap_or_default().into())
+                        .collect(),
+                    bytecode,
+                }),
+                Err(e) => errors.extend(e.into_iter().map(CompilerError::CodegenError)),
+            }
+        }
+        tracing::info!(target: "core", "COMPILED {} TESTS OF \"{}\"", tests.len(), file.path);
+        match errors.len() {
+            0 => Ok(tests),
+            1 => Err(errors.remove(0)),
+            _ => Err(CompilerError::FailedCompiles(errors)),
+        }
+    }
+
+    /// Logs the code generation of a FileSource, for its `MAIN` and `CONSTRUCTOR` macros.
+    ///
+    /// A contract without a constructor only logs its runtime bytecode.
+    pub fn codegen_log(&self, file: Arc<FileSource>) -> Result<Vec<CodegenLog>, CompilerError> {
+        let contract = self.parse_contract(&file, &mut vec![])?;
+        let mut logs = vec![];
+        for name in ["MAIN", "CONSTRUCTOR"] {
+            if name == "CONSTRUCTOR" && !contract.macros.iter().any(|m| m.name == name) {
+                continue
             }
+            let to_error = |mut errors: Vec<CodegenError>| match errors.len() {
+                1 => CompilerError::CodegenError(errors.remove(0)),
+                _ => CompilerError::FailedCompiles(
+                    errors.into_iter().map(CompilerError::CodegenError).collect(),
+                ),
+            };
+            let bytecode = Codegen::generate_macro_bytecode(name, &contract).map_err(to_error)?;
+            let source_map = Codegen::generate_source_map(name, &contract).map_err(to_error)?;
+            logs.push(CodegenLog::new(name, &bytecode, &source_map, &contract));
+        }
+        tracing::info!(target: "core", "LOGGED CODEGEN OF \"{}\"", file.path);
+        Ok(logs)
+    }
+
+    /// Lints a FileSource without generating its bytecode.
+    ///
+    /// Returns the warnings reported and denied by the compiler's lint levels.
+    pub fn lint(
+        &self,
+        file: Arc<FileSource>,
+    ) -> Result<(Vec<CompilerWarning>, Vec<CompilerWarning>), CompilerError> {
+        let mut found = vec![];
+        let contract = self.parse_contract(&file, &mut found)?;
+        found.extend(analyze_contract(&contract));
+        tracing::info!(target: "core", "LINTED \"{}\"", file.path);
+        Ok(self.lint_levels.apply(found))
+    }
+
+    /// Lexes a FileSource and its dependencies into their tokens, without parsing them.
+    ///
+    /// The lexer skips the invalid characters it reports, so the tokens around them are kept.
+    /// Whitespace tokens are left out.
+    pub fn tokens(&self, file: Arc<FileSource>) -> Vec<Result<Token, LexicalError>> {
+        let flattened = FileSource::fully_flatten(Arc::clone(&file));
+        let full_source = FullFileSource {
+            source: &flattened.0,
+            file: Some(Arc::clone(&file)),
+            spans: flattened.1,
+        };
+        let mut lexer: Lexer = Lexer::new(full_source);
+        lexer.case_insensitive_opcodes = self.case_insensitive_opcodes;
+        lexer
+            .filter(|t| !matches!(t, Ok(Token { kind: TokenKind::Whitespace, .. })))
+            .collect()
+    }
+
+    /// Parses a FileSource into the Contract AST, as written in the source.
+    ///
+    /// Unlike compiling, the fallback and receive definitions aren't wired into `MAIN`, nor
+    /// storage pointers derived. The AST serializes to json with [Contract::to_json].
+    pub fn ast(&self, file: Arc<FileSource>) -> Result<Contract, CompilerError> {
+        self.parse_source(&file, &mut vec![])
+    }
+
+    /// Generates the manifest of the public symbols of a library FileSource.
+    ///
+    /// The library is named after the file stem of its entrypoint.
+    pub fn package(&self, file: Arc<FileSource>) -> Result<SymbolManifest, CompilerError> {
+        let contract = self.parse_source(&file, &mut vec![])?;
+        let name = Path::new(&file.path).file_stem().unwrap_or_default().to_string_lossy();
+        Ok(SymbolManifest::new(&name, &contract))
+    }
+
+    /// Generates the API documentation of a FileSource, from its definitions and doc comments.
+    ///
+    /// The documentation is named after the file stem of its entrypoint.
+    pub fn docs(&self, file: Arc<FileSource>) -> Result<Documentation, CompilerError> {
+        let contract = self.parse_source(&file, &mut vec![])?;
+        let name = Path::new(&file.path).file_stem().unwrap_or_default().to_string_lossy();
+        tracing::info!(target: "core", "DOCUMENTED \"{}\"", file.path);
+        Ok(Documentation::new(&name, &contract))
+    }
+
+    /// Flattens a FileSource and its dependencies into a single source, keeping their comments.
+    ///
+    /// The FileSource must have its dependencies recursed. Files are flattened in the order they
+    /// are compiled, each file once.
+    pub fn flatten(file: Arc<FileSource>) -> Result<String, CompilerError> {
+        let files: Vec<(String, SyntaxTree)> = Compiler::compiled_files(file)
+            .into_iter()
+            .map(|fs| {
+                let tree = Lexer::syntax_tree(fs.source.as_deref().unwrap_or_default());
+                (fs.path.clone(), tree)
+            })
+            .collect();
+        tracing::info!(target: "core", "FLATTENING {} FILES", files.len());
+        SyntaxTree::flatten(&files)
+            .map_err(|path| CompilerError::FileUnpackError(UnpackError::UnflattenableInclude(path)))
+    }
+
+    /// Lists a FileSource and its dependencies in the order they are compiled, each file once.
+    fn compiled_files(file: Arc<FileSource>) -> Vec<Arc<FileSource>> {
+        let mut files: Vec<Arc<FileSource>> = vec![];
+        let mut visited: Vec<String> = vec![];
+        let mut stack = vec![file];
+        while let Some(fs) = stack.pop() {
+            let canonical_path = FileSource::canonical_path(&fs.path);
+            if visited.contains(&canonical_path) {
+                continue
+            }
+            visited.push(canonical_path);
+            stack.extend(fs.dependencies.iter().flatten().rev().cloned());
+            files.push(fs);
+        }
+        files
+    }
+
+    /// Generates the bundle submitted to verifiers for a FileSource, pinning the compiler
+    /// version and the settings of the compiler.
+    ///
+    /// The FileSource must have its dependencies recursed. The bundle holds every file compiled,
+    /// by path, or if `flattened` the single source flattened from them, named after the
+    /// entrypoint.
+    pub fn verification_bundle(
+        &self,
+        file: Arc<FileSource>,
+        flattened: bool,
+        version: &str,
+    ) -> Result<VerificationBundle, CompilerError> {
+        let artifact = self.gen_artifact(Arc::clone(&file))?;
+        let (entrypoint, sources) = if flattened {
+            let stem = Path::new(&file.path).file_stem().unwrap_or_default().to_string_lossy();
+            let entrypoint = format!("{}.flat.huff", stem);
+            let source = Compiler::flatten(Arc::clone(&file))?;
+            (entrypoint.clone(), BTreeMap::from([(entrypoint, BundleSource::new(source))]))
+        } else {
+            let sources = Compiler::compiled_files(Arc::clone(&file))
+                .into_iter()
+                .map(|fs| {
+                    let content = fs.source.clone().unwrap_or_default();
+                    (fs.path.clone(), BundleSource::new(content))
+                })
+                .collect();
+            (file.path.clone(), sources)
         };
-        tracing::info!(target: "core", "MAIN BYTECODE GENERATED [{}]", main_bytecode);
         let inputs = self.get_constructor_args();
-        let constructor_bytecode = match Codegen::generate_constructor_bytecode(&contract) {
-            Ok(mb) => mb,
-            Err(mut e) => {
-                if !inputs.is_empty() {
-                    // Add File Source to Span
+        let settings = BundleSettings {
+            evm_version: self.evm_version.to_string(),
+            case_insensitive_opcodes: self.case_insensitive_opcodes,
+            max_expansion_depth: self.max_expansion_depth.unwrap_or(DEFAULT_MAX_EXPANSION_DEPTH),
+            defines: BundleSettings::constant_values(&self.defines),
+            overrides: BundleSettings::constant_values(&self.overrides),
+            encoded_constructor_arguments: Codegen::hex_constructor_args(
+                &Codegen::encode_constructor_args(inputs.clone()),
+            ),
+            constructor_arguments: inputs,
+        };
+        tracing::info!(target: "core", "BUNDLED {} SOURCES OF \"{}\"", sources.len(), file.path);
+        Ok(VerificationBundle::new(version, &entrypoint, sources, settings, &artifact))
+    }
+
+    /// Generates the data contract storing a code table of a FileSource.
+    pub fn data_contract(
+        &self,
+        file: Arc<FileSource>,
+        table: &str,
+    ) -> Result<DataContract, CompilerError> {
+        let contract = self.parse_contract(&file, &mut vec![])?;
+        let codegen_error = |kind: CodegenErrorKind, span: AstSpan, help: Option<String>| {
+            CompilerError::CodegenError(CodegenError { kind, span, token: None, help })
+        };
+        match contract.find_table_by_name(table) {
+            Some(t) => DataContract::from_table(&t).ok_or_else(|| {
+                tracing::error!(target: "core", "TABLE \"{}\" HAS NO CODE TO STORE", t.name);
+                codegen_error(CodegenErrorKind::InvalidDataTable(t.name.to_string()), t.span, None)
+            }),
+            None => {
+                tracing::error!(target: "core", "MISSING DATA CONTRACT TABLE \"{}\"", table);
+                Err(codegen_error(
+                    CodegenErrorKind::MissingMacroDefinition(table.to_string()),
+                    AstSpan(vec![]),
+                    did_you_mean(table, contract.tables.iter().map(|t| t.name.as_str())),
+                ))
+            }
+        }
+    }
+
+    /// Generates the Artifact of a FileSource, collecting every warning found.
+    fn generate_artifact(
+        &self,
+        file: Arc<FileSource>,
+        warnings: &mut Vec<CompilerWarning>,
+        stats: &mut PhaseStats,
+    ) -> Result<Artifact, CompilerError> {
+        let contract = self.parse_contract_with_stats(&file, warnings, stats)?;
+
+        // Analyze the contract for unused and shadowed definitions
+        warnings.extend(analyze_contract(&contract));
+
+        let start = Instant::now();
+        let res = self.codegen_artifact(file, contract, warnings, stats);
+        stats.record(Phase::Codegen, start.elapsed());
+
+        // Arg calls failing codegen are reported by their error alone
+        if let Err(e) = &res {
+            let errors = match e {
+                CompilerError::FailedCompiles(errors) => errors.iter().collect(),
+                e => vec![e],
+            };
+            let failed = errors
+                .into_iter()
+                .filter_map(|e| match e {
+                    CompilerError::CodegenError(CodegenError {
+                        kind: CodegenErrorKind::UndeclaredArgCall(arg),
+                        ..
+                    }) => Some(arg),
+                    _ => None,
+                })
+                .collect::<Vec<&String>>();
+            warnings.retain(|w| {
+                !matches!(&w.kind, WarningKind::UndeclaredArgCall(_, arg) if failed.contains(&arg))
+            });
+        }
+        res
+    }
+
+    /// Generates the bytecode and ABI of a parsed Contract into its Artifact, counting the macros
+    /// expanded, the expansions reused and the bytes emitted.
+    fn codegen_artifact(
+        &self,
+        file: Arc<FileSource>,
+        mut contract: Contract,
+        warnings: &mut Vec<CompilerWarning>,
+        stats: &mut PhaseStats,
+    ) -> Result<Artifact, CompilerError> {
+        // Primary Bytecode Generation
+        // See huffc: https://github.com/huff-language/huffc/blob/2e5287afbfdf9cc977b204a4fd1e89c27375b040/src/compiler/processor.ts
+        let mut cg = Codegen::new();
+        // Add File Source to Spans missing one
+        let with_file = |errors: Vec<CodegenError>| {
+            errors
+                .into_iter()
+                .map(|mut e| {
                     e.span = AstSpan(
                         e.span
                             .0
                             .into_iter()
                             .map(|mut s| {
-                                s.file = Some(Arc::clone(&file));
+                                s.file = s.file.or_else(|| Some(Arc::clone(&file)));
                                 s
                             })
                             .collect::<Vec<Span>>(),
                     );
+                    e
+                })
+                .collect::<Vec<CodegenError>>()
+        };
+        let mut errors: Vec<CodegenError> = vec![];
+        for plugin in self.plugins.iter() {
+            tracing::debug!(target: "codegen", "RUNNING PLUGIN \"{}\" BEFORE EXPANSION", plugin.name());
+            if let Err(e) = plugin.before_expansion(&mut contract) {
+                errors.extend(with_file(vec![e]));
+            }
+        }
+        errors.extend(with_file(lower_builtins(&mut contract, &self.builtins)));
+        errors.extend(with_file(Codegen::check_assertions(&contract)));
+        let expanded = |name: &str, mut bytecode: String, errors: &mut Vec<CodegenError>| {
+            for plugin in self.plugins.iter() {
+                tracing::debug!(target: "codegen", "RUNNING PLUGIN \"{}\" AFTER EXPANDING \"{}\"", plugin.name(), name);
+                if let Err(e) = plugin.after_expansion(name, &mut bytecode, &contract) {
+                    errors.extend(with_file(vec![e]));
+                }
+            }
+            bytecode
+        };
+        // The expansions are keyed by their root macro, so both bytecodes share the cache
+        let mut cache = ExpansionCache::default();
+        let main = Codegen::generate_macro_bytecode_with_cache("MAIN", &contract, &mut cache);
+        let mut main_bytecode = match main {
+            Ok((mb, source_map)) => {
+                stats.macros_expanded += count_expansions(&source_map);
+                expanded("MAIN", mb, &mut errors)
+            }
+            Err(e) => {
+                tracing::error!(target: "codegen", "Roll Failed with CodegenErrors: {:?}", e);
+                errors.extend(with_file(e));
+                "".to_string()
+            }
+        };
+        tracing::info!(target: "core", "MAIN BYTECODE GENERATED [{}]", main_bytecode);
+        if main_bytecode.len() / 2 > MAX_CONTRACT_SIZE {
+            tracing::warn!(target: "core", "MAIN BYTECODE EXCEEDS THE CONTRACT SIZE LIMIT");
+            warnings.push(CompilerWarning::new(
+                WarningKind::OversizedContract(main_bytecode.len() / 2),
+                AstSpan(vec![Span { start: 0, end: 0, file: Some(Arc::clone(&file)) }]),
+            ));
+        }
+        let inputs = self.get_constructor_args();
+        let constructor =
+            Codegen::generate_macro_bytecode_with_cache("CONSTRUCTOR", &contract, &mut cache);
+        let mut constructor_bytecode = match constructor {
+            Ok((mb, source_map)) => {
+                stats.macros_expanded += count_expansions(&source_map);
+                expanded("CONSTRUCTOR", mb, &mut errors)
+            }
+            Err(e) => {
+                if contract.macros.iter().any(|m| m.name == "CONSTRUCTOR") {
+                    tracing::error!(target: "codegen", "Roll Failed with CodegenErrors: {:?}", e);
+                    errors.extend(with_file(e));
+                } else if !inputs.is_empty() {
                     tracing::error!(target: "codegen", "Constructor inputs provided, but contract missing \"CONSTRUCTOR\" macro!");
-                    return Err(CompilerError::CodegenError(e))
+                    errors.extend(with_file(e));
+                } else {
+                    tracing::warn!(target: "codegen", "Contract has no \"CONSTRUCTOR\" macro definition!");
                 }
-                tracing::warn!(target: "codegen", "Contract has no \"CONSTRUCTOR\" macro definition!");
                 "".to_string()
             }
         };
+        stats.expansions_reused += cache.hits;
+
+        for plugin in self.plugins.iter() {
+            tracing::debug!(target: "codegen", "RUNNING PLUGIN \"{}\" BEFORE ASSEMBLY", plugin.name());
+            if let Err(e) = plugin.before_assembly(&mut main_bytecode, &mut constructor_bytecode) {
+                errors.extend(with_file(vec![e]));
+            }
+        }
+
+        // Report every codegen error of the contract
+        match errors.len() {
+            0 => {}
+            1 => return Err(CompilerError::CodegenError(errors.remove(0))),
+            _ => {
+                return Err(CompilerError::FailedCompiles(
+                    errors.into_iter().map(CompilerError::CodegenError).collect(),
+                ))
+            }
+        }
+
+        stats.bytes_emitted = (main_bytecode.len() + constructor_bytecode.len()) / 2;
 
         // Encode Constructor Arguments
         tracing::info!(target: "core", "CONSTRUCTOR BYTECODE GENERATED [{}]", constructor_bytecode);
@@ -257,15 +1269,43 @@ impl<'a> Compiler {
         let encoded_inputs = Codegen::encode_constructor_args(inputs);
         tracing::info!(target: "core", "ENCODED {} INPUTS", encoded_inputs.len());
 
+        // The bootstrap copies the runtime bytecode around the immutables the constructor set
+        if !contract.immutables.is_empty() {
+            let immutable_references = Codegen::immutable_references(&contract)
+                .map_err(|e| CompilerError::CodegenError(with_file(e).remove(0)))?;
+            cg.artifact = Some(Artifact { immutable_references, ..Default::default() });
+        }
+
         // Generate Artifact with ABI
         let churn_res = cg.churn(file, encoded_inputs, &main_bytecode, &constructor_bytecode);
         match churn_res {
             Ok(mut artifact) => {
+                artifact.storage_layout = contract
+                    .storage_layout
+                    .iter()
+                    .map(|(name, slot)| (name.clone(), bytes32_to_string(slot, true)))
+                    .collect();
+                artifact.panics = contract
+                    .panics
+                    .iter()
+                    .map(|p| (p.to_string(), p.message().to_string()))
+                    .collect();
+                let libraries = contract.linked_libraries();
+                let libraries = || libraries.iter().map(String::as_str);
+                artifact.link_references = find_link_references(&artifact.bytecode, libraries());
+                artifact.runtime_link_references =
+                    find_link_references(&artifact.runtime, libraries());
+                artifact.verbatim_references =
+                    Codegen::verbatim_references(&contract).unwrap_or_default();
+
                 // Then we can have the code gen output the artifact
+                let natspec = contract.natspec.clone();
                 let abiout = cg.abi_gen(contract, None);
                 match abiout {
                     Ok(abi) => {
                         tracing::info!(target: "core", "GENERATED ABI");
+                        artifact.devdoc = DevDoc::new(&natspec, &abi);
+                        artifact.userdoc = UserDoc::new(&natspec, &abi);
                         artifact.abi = Some(abi)
                     }
                     Err(e) => {
@@ -282,7 +1322,7 @@ impl<'a> Compiler {
     }
 
     /// Get the file sources for a vec of PathBufs
-    pub fn fetch_sources(paths: Vec<PathBuf>) -> Vec<Result<Arc<FileSource>, CompilerError<'a>>> {
+    pub fn fetch_sources(paths: Vec<PathBuf>) -> Vec<Result<Arc<FileSource>, CompilerError>> {
         paths
             .into_par_iter()
             .map(|pb| {
@@ -305,7 +1345,37 @@ impl<'a> Compiler {
     }
 
     /// Recurses file dependencies
-    pub fn recurse_deps(fs: Arc<FileSource>) -> Result<Arc<FileSource>, Arc<CompilerError<'a>>> {
+    pub fn recurse_deps(fs: Arc<FileSource>) -> Result<Arc<FileSource>, Arc<CompilerError>> {
+        Compiler::recurse_included_deps(fs, &[], &[])
+    }
+
+    /// Recurses file dependencies, resolving includes with the compiler's remappings
+    pub fn resolve_deps(&self, fs: Arc<FileSource>) -> Result<Arc<FileSource>, Arc<CompilerError>> {
+        Compiler::recurse_included_deps(fs, &[], &self.remappings)
+    }
+
+    /// Recurses the dependencies of a file, included through a chain of files
+    ///
+    /// A file included again by one of its own dependencies is a cycle, reported with the files
+    /// of the cycle rather than recursed into.
+    fn recurse_included_deps(
+        fs: Arc<FileSource>,
+        includers: &[String],
+        remappings: &[Remapping],
+    ) -> Result<Arc<FileSource>, Arc<CompilerError>> {
+        let canonical_path = FileSource::canonical_path(&fs.path);
+        if let Some(start) =
+            includers.iter().position(|i| FileSource::canonical_path(i) == canonical_path)
+        {
+            let mut cycle = includers[start..].to_vec();
+            cycle.push(fs.path.clone());
+            tracing::error!(target: "core", "CIRCULAR INCLUDE: {}", cycle.join(" -> "));
+            return Err(Arc::new(CompilerError::FileUnpackError(UnpackError::CircularInclude(
+                cycle,
+            ))))
+        }
+        let includers = [includers, std::slice::from_ref(&fs.path)].concat();
+
         let mut new_fs = FileSource { path: fs.path.clone(), ..Default::default() };
         let file_source = if let Some(s) = &fs.source {
             s.clone()
@@ -326,14 +1396,18 @@ impl<'a> Compiler {
         if !imports.is_empty() {
             tracing::info!(target: "core", "IMPORT LEXICAL ANALYSIS COMPLETE ON {:?}", imports);
         }
+        // Bundled modules are read from the compiler rather than the filesystem
+        let (bundled, imports): (Vec<String>, Vec<String>) =
+            imports.into_iter().partition(|import| is_bundled_path(import));
         let localized_imports: Vec<String> = imports
             .iter()
-            .map(|import| {
-                FileSource::localize_file(&fs.path, import).unwrap_or_default().replacen(
+            .map(|import| match FileSource::remap(import, remappings) {
+                Some(remapped) => remapped,
+                None => FileSource::localize_file(&fs.path, import).unwrap_or_default().replacen(
                     "contracts/contracts",
                     "contracts",
                     1,
-                )
+                ),
             })
             .collect();
         if !localized_imports.is_empty() {
@@ -346,21 +1420,42 @@ impl<'a> Compiler {
             Ok(p) => p,
             Err(e) => return Err(Arc::new(e)),
         };
+        for path in bundled {
+            let Some(source) = bundled_module(&path) else {
+                tracing::error!(target: "core", "UNKNOWN BUNDLED MODULE: {}", path);
+                return Err(Arc::new(CompilerError::FileUnpackError(UnpackError::MissingFile(path))))
+            };
+            file_sources.push(Arc::new(FileSource {
+                id: Uuid::new_v4(),
+                path,
+                source: Some(source.to_string()),
+                access: None,
+                dependencies: None,
+            }));
+        }
         if !file_sources.is_empty() {
             tracing::info!(target: "core", "FETCHED {} FILE SOURCES", file_sources.len());
         }
 
         // Now that we have all the file sources, we have to recurse and get their source
+        // Cycles are reported, since recursing them would never end
         file_sources = file_sources
             .into_par_iter()
-            .map(|inner_fs| match Compiler::recurse_deps(Arc::clone(&inner_fs)) {
-                Ok(new_fs) => new_fs,
-                Err(e) => {
-                    tracing::error!(target: "core", "NESTED DEPENDENCY RESOLUTION FAILED: \"{:?}\"", e);
-                    Arc::clone(&inner_fs)
+            .map(|inner_fs| {
+                let recursed =
+                    Compiler::recurse_included_deps(Arc::clone(&inner_fs), &includers, remappings);
+                match recursed {
+                    Ok(new_fs) => Ok(new_fs),
+                    Err(e) => match *e {
+                        CompilerError::FileUnpackError(UnpackError::CircularInclude(_)) => Err(e),
+                        _ => {
+                            tracing::error!(target: "core", "NESTED DEPENDENCY RESOLUTION FAILED: \"{:?}\"", e);
+                            Ok(Arc::clone(&inner_fs))
+                        }
+                    },
                 }
             })
-            .collect();
+            .collect::<Result<Vec<Arc<FileSource>>, Arc<CompilerError>>>()?;
 
         // Finally set the parent deps
         new_fs.dependencies = Some(file_sources);
@@ -370,45 +1465,70 @@ impl<'a> Compiler {
 
     /// Export Artifacts
     ///
-    /// 1. Cleans any previous artifacts in the output directory.
-    /// 2. Exports artifacts in parallel as serialized json `Artifact` objects.
-    pub fn export_artifacts(artifacts: &Vec<Arc<Artifact>>, output: &OutputLocation) {
+    /// 1. Cleans any previous artifacts in the output directory, for the huff artifact format.
+    /// 2. Exports artifacts in parallel as serialized json, in the shape of the artifact format.
+    ///
+    /// Returns the time spent writing each artifact, by the path of its source file.
+    pub fn export_artifacts(
+        artifacts: &Vec<Arc<Artifact>>,
+        output: &OutputLocation,
+        format: ArtifactFormat,
+    ) -> BTreeMap<String, Duration> {
         // Exit if empty output location
         if output.0.is_empty() {
             tracing::warn!(target: "core", "Exiting artifact export with empty output location!");
-            return
+            return BTreeMap::new()
         }
 
-        // Clean the Output Directory
-        tracing::warn!(target: "core", "REMOVING DIRECTORY: \"{}\"", output.0);
-        if !output.0.is_empty() && fs::remove_dir_all(&output.0).is_ok() {
-            tracing::info!(target: "core", "OUTPUT DIRECTORY DELETED!");
+        // Clean the Output Directory, unless shared with the artifacts of Hardhat or forge
+        if format == ArtifactFormat::Huff {
+            tracing::warn!(target: "core", "REMOVING DIRECTORY: \"{}\"", output.0);
+            if fs::remove_dir_all(&output.0).is_ok() {
+                tracing::info!(target: "core", "OUTPUT DIRECTORY DELETED!");
+            }
         }
 
-        // Is the output a directory or a file?
-        let is_file = std::path::PathBuf::from(&output.0).extension().is_some();
-
         // Export the artifacts with parallelized io
-        artifacts.into_par_iter().for_each(|a| {
-            // If it's a file type, we just export to `output.0`
-            let json_out = match is_file {
-                true => output.0.clone(),
-                false => format!(
-                    "{}/{}.json",
-                    output.0,
-                    a.file.path.to_uppercase().replacen("./", "", 1)
-                ),
-            };
+        artifacts
+            .into_par_iter()
+            .map(|a| {
+                let start = Instant::now();
+                let json_out = Compiler::artifact_path(a, output, format);
+                let exported = match format {
+                    ArtifactFormat::Huff => a.export(&json_out),
+                    ArtifactFormat::Hardhat => HardhatArtifact::from(a.as_ref()).export(&json_out),
+                    ArtifactFormat::Foundry => FoundryArtifact::from(a.as_ref()).export(&json_out),
+                };
+                if let Err(e) = exported {
+                    tracing::error!(target: "core", "ARTIFACT EXPORT FAILED!\nError: {:?}", e);
+                }
+                tracing::info!(target: "core", "EXPORTED ARTIFACT TO \"{}\"", json_out);
+                (a.file.path.clone(), start.elapsed())
+            })
+            .collect()
+    }
 
-            if let Err(e) = a.export(&json_out) {
-                tracing::error!(target: "core", "ARTIFACT EXPORT FAILED!\nError: {:?}", e);
-            }
-            tracing::info!(target: "core", "EXPORTED ARTIFACT TO \"{}\"", json_out);
-        });
+    /// The path an artifact is exported to in the output location
+    pub fn artifact_path(
+        artifact: &Artifact,
+        output: &OutputLocation,
+        format: ArtifactFormat,
+    ) -> String {
+        // If the output is a file, we just export to `output.0`
+        match std::path::PathBuf::from(&output.0).extension().is_some() {
+            true => output.0.clone(),
+            false if format == ArtifactFormat::Hardhat => HardhatArtifact::path(artifact, &output.0),
+            false if format == ArtifactFormat::Foundry => FoundryArtifact::path(artifact, &output.0),
+            false => format!(
+                "{}/{}.json",
+                output.0,
+                artifact.file.path.to_uppercase().replacen("./", "", 1)
+            ),
+        }
     }
 
     /// Transforms File Strings into PathBufs
-    pub fn transform_paths(sources: &Vec<String>) -> Result<Vec<PathBuf>, CompilerError<'a>> {
+    pub fn transform_paths(sources: &Vec<String>) -> Result<Vec<PathBuf>, CompilerError> {
         let mut paths = vec![];
         for f in sources {
             // If the file is huff, use the path, otherwise unpack
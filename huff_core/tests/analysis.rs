@@ -0,0 +1,520 @@
+use std::fs;
+
+use huff_codegen::Codegen;
+use huff_core::Compiler;
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+fn parse(source: &str) -> Contract {
+    let full_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(full_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    parser.parse().unwrap()
+}
+
+#[test]
+fn test_unused_macro_parameter() {
+    let source = r#"
+    #define macro STORE(slot, unused) = takes(1) returns(0) {
+        <slot> sstore
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x01 STORE(0x00, 0x01)
+    }
+    "#;
+    let contract = parse(source);
+
+    let warnings = analyze_macro_parameters(&contract);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0].kind,
+        WarningKind::UnusedMacroParameter("STORE".to_string(), "unused".to_string())
+    );
+    assert_eq!(warnings[0].kind.code(), "H0502");
+    assert_eq!(
+        warnings[0].kind.message(),
+        "Parameter \"unused\" of macro \"STORE\" is never referenced"
+    );
+    let span = &warnings[0].span.0[0];
+    assert_eq!(&source[span.start..span.end], "unused");
+}
+
+#[test]
+fn test_undeclared_arg_call() {
+    let source = r#"
+    #define constant OWNER = FREE_STORAGE_POINTER()
+
+    #define macro INNER(value) = takes(0) returns(0) {
+        <value> <OWNER> <missing> sstore
+    }
+
+    #define macro OUTER(value) = takes(0) returns(0) {
+        INNER(<value>)
+        label:
+            INNER(<other>)
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        OUTER(0x01)
+    }
+    "#;
+    let contract = parse(source);
+
+    let warnings = analyze_macro_parameters(&contract);
+    assert_eq!(
+        warnings.iter().map(|w| (w.kind.code(), w.kind.message())).collect::<Vec<(&str, String)>>(),
+        vec![
+            (
+                "H0503",
+                "Arg call \"<missing>\" references a parameter not declared by macro \"INNER\""
+                    .to_string()
+            ),
+            (
+                "H0503",
+                "Arg call \"<other>\" references a parameter not declared by macro \"OUTER\""
+                    .to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_unused_definitions() {
+    let source = r#"
+    #define constant USED = 0x01
+    #define constant UNUSED = 0x02
+    #define constant PASSED = 0x03
+
+    #define macro INNER(value) = takes(0) returns(0) {
+        [USED] <value> sstore
+    }
+
+    #define macro SIZED() = takes(0) returns(0) {
+        0x00
+    }
+
+    #define macro DEAD() = takes(0) returns(0) {
+        0x00
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        INNER(PASSED)
+        __codesize(SIZED)
+    }
+    "#;
+    let contract = parse(source);
+
+    let warnings = analyze_unused_definitions(&contract);
+    assert_eq!(
+        warnings.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(),
+        vec![
+            WarningKind::UnusedMacro("DEAD".to_string()),
+            WarningKind::UnusedConstant("UNUSED".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_unreached_definitions() {
+    let source = r#"
+    #define function transfer(address,uint256) nonpayable returns ()
+    #define function approve(address,uint256) nonpayable returns ()
+
+    #define constant TRANSFER = 0xa9059cbb
+    #define constant DEAD_SLOT = 0x01
+
+    #define jumptable JUMPS {
+        dispatch
+    }
+    #define jumptable DEAD_TABLE {
+        dispatch
+    }
+
+    #define macro DEAD() = takes(0) returns(0) {
+        [DEAD_SLOT] sload DEADER()
+        __tablesize(DEAD_TABLE)
+    }
+
+    #define macro DEADER() = takes(0) returns(0) {
+        0x00
+    }
+
+    #define macro JUMP_TO(target) = takes(0) returns(0) {
+        <target> jump
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __tablestart(JUMPS) pop
+        0x00 calldataload 0xe0 shr [TRANSFER] eq
+        JUMP_TO(passed)
+        dispatch:
+            stop
+        passed:
+            stop
+        stray:
+            stop
+    }
+    "#;
+    let contract = parse(source);
+
+    // Definitions only referenced by unreached macros aren't used either
+    let warnings = analyze_unused_definitions(&contract);
+    assert_eq!(
+        warnings.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(),
+        vec![
+            WarningKind::UnusedMacro("DEAD".to_string()),
+            WarningKind::UnusedMacro("DEADER".to_string()),
+            WarningKind::UnusedConstant("DEAD_SLOT".to_string()),
+            WarningKind::UnusedTable("DEAD_TABLE".to_string()),
+            WarningKind::UnusedFunction("approve".to_string()),
+            WarningKind::UnusedLabel("MAIN".to_string(), "stray".to_string()),
+        ]
+    );
+    assert_eq!(
+        warnings.iter().map(|w| w.kind.code()).collect::<Vec<_>>()[3..],
+        ["H0516", "H0518", "H0517"]
+    );
+    assert_eq!(warnings[5].kind.message(), "Label \"stray\" in macro \"MAIN\" is never jumped to");
+
+    // Each warning points at the name of its definition
+    let names = warnings
+        .iter()
+        .flat_map(|w| w.span.0.iter().map(|s| &source[s.start..s.end]))
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["DEAD", "DEADER", "DEAD_SLOT", "DEAD_TABLE", "approve", "stray"]);
+}
+
+#[test]
+fn test_library_definitions() {
+    let source = r#"
+    #define constant SLOT = 0x01
+    #define constant UNUSED = 0x02
+
+    #define macro LOAD() = takes(0) returns(1) {
+        [SLOT] sload
+    }
+
+    #define macro STORE() = takes(1) returns(0) {
+        [SLOT] sstore
+    }
+    "#;
+    let contract = parse(source);
+
+    // Without entrypoints, every macro is used
+    let warnings = analyze_unused_definitions(&contract);
+    assert_eq!(
+        warnings.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(),
+        vec![WarningKind::UnusedConstant("UNUSED".to_string())]
+    );
+}
+
+#[test]
+fn test_shadowed_labels() {
+    let source = r#"
+    #define macro MAIN() = takes(0) returns(0) {
+        first jump
+        first:
+            0x00
+        second:
+            0x01
+        first:
+            0x02
+    }
+    "#;
+    let contract = parse(source);
+
+    let warnings = analyze_shadowed_labels(&contract);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0].kind,
+        WarningKind::ShadowedLabel("MAIN".to_string(), "first".to_string())
+    );
+    assert_eq!(warnings[0].kind.code(), "H0506");
+}
+
+#[test]
+fn test_unreachable_code() {
+    let source = r#"
+    #define macro INNER() = takes(0) returns(0) {
+        stop
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x00 0x00 revert
+        0x01 dup1
+        reachable:
+            0x00 jump
+            0x02
+        halted:
+            stop
+            INNER()
+            0x03
+    }
+    "#;
+    let contract = parse(source);
+
+    let warnings = analyze_unreachable_code(&contract);
+    assert_eq!(
+        warnings.iter().map(|w| w.kind.message()).collect::<Vec<String>>(),
+        vec![
+            "Code following \"revert\" in macro \"MAIN\" is unreachable",
+            "Code following \"jump\" in macro \"MAIN\" is unreachable",
+        ]
+    );
+    let spans = warnings[0].span.0.iter().map(|s| &source[s.start..s.end]).collect::<Vec<_>>();
+    assert_eq!(spans, vec!["01"]);
+    assert_eq!(warnings[1].kind.code(), "H0508");
+}
+
+#[test]
+fn test_missing_jump_targets() {
+    let source = r#"
+    #define macro INNER() = takes(0) returns(0) {
+        outer jump
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        INNER()
+        outer:
+            dup17 swap16 swap20
+            0x01 otuer jumpi
+    }
+    "#;
+    let contract = parse(source);
+
+    let warnings = analyze_jump_targets(&contract);
+    assert_eq!(
+        warnings.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(),
+        vec![
+            WarningKind::StackTooDeep("MAIN".to_string(), "dup17".to_string()),
+            WarningKind::StackTooDeep("MAIN".to_string(), "swap20".to_string()),
+            WarningKind::MissingJumpTarget("MAIN".to_string(), "otuer".to_string()),
+        ]
+    );
+    assert_eq!(warnings[1].kind.code(), "H0511");
+    assert_eq!(warnings[2].kind.code(), "H0509");
+    assert_eq!(warnings[2].help.as_deref(), Some("did you mean 'outer'?"));
+}
+
+#[test]
+fn test_magic_selectors() {
+    let source = r#"
+    #define function transfer(address,uint256) nonpayable returns ()
+    #define function balanceOf(address) view returns (uint256)
+    #define constant TRANSFER = 0xa9059cbb
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x00 calldataload 0xe0 shr
+        dup1 0xa9059cbb eq transfer jumpi
+        dup1 0x12345678 eq other jumpi
+        0x70a08231 0x0000000000000001 eq
+        transfer:
+        other:
+    }
+    "#;
+    let contract = parse(source);
+
+    let warnings = analyze_magic_selectors(&contract);
+    assert_eq!(
+        warnings.iter().map(|w| w.kind.message()).collect::<Vec<String>>(),
+        vec![
+            "Selector \"0xa9059cbb\" in macro \"MAIN\" should be a named constant",
+            "Selector \"0x12345678\" in macro \"MAIN\" should be a named constant",
+            "Selector \"0x70a08231\" in macro \"MAIN\" should be a named constant",
+        ]
+    );
+    assert_eq!(warnings[0].help.as_deref(), Some("push the constant instead: [TRANSFER]"));
+    assert_eq!(warnings[1].help, None);
+    assert_eq!(
+        warnings[2].help.as_deref(),
+        Some("define a constant for the selector of function \"balanceOf\": #define constant BALANCE_OF_SELECTOR = 0x70a08231")
+    );
+}
+
+#[test]
+fn test_stack_heights() {
+    let source = r#"
+    #define macro LOAD(offset) = takes(0) returns(1) {
+        <offset> calldataload
+    }
+
+    #define macro STORE() = takes(1) returns(0) {
+        sstore
+    }
+
+    #define macro RETURN_WORD() = takes(1) returns(0) {
+        0x00 mstore 0x20 0x00 return
+    }
+
+    #define macro LEAKY() = takes(0) returns(0) {
+        LOAD(0x04) LOAD(0x24)
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        LOAD(0x00) 0xe0 shr
+        dup1 0x6057361d eq store jumpi
+        0x00 0x00 revert
+
+        store:
+            LOAD(0x04) 0x00 sstore
+            STORE()
+            RETURN_WORD()
+    }
+    "#;
+    let contract = parse(source);
+
+    let warnings = analyze_stack_heights(&contract);
+    assert_eq!(
+        warnings.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(),
+        vec![
+            WarningKind::StackUnderflow("STORE".to_string(), "sstore".to_string(), 2, 1),
+            WarningKind::StackMismatch("LEAKY".to_string(), 0, 0, 2),
+            WarningKind::StackUnderflow("MAIN".to_string(), "RETURN_WORD()".to_string(), 1, 0),
+        ]
+    );
+    let span = |w: &CompilerWarning| {
+        w.span.0.iter().map(|s| &source[s.start..s.end]).collect::<Vec<_>>().join(" ")
+    };
+    assert_eq!(span(&warnings[0]), "sstore");
+    assert_eq!(span(&warnings[1]), "LOAD");
+    assert_eq!(warnings[0].kind.code(), "H0512");
+    assert_eq!(warnings[1].kind.code(), "H0513");
+    assert_eq!(
+        warnings[1].kind.message(),
+        "Macro \"LEAKY\" ends with 2 stack item(s), but declares takes(0) returns(0)"
+    );
+}
+
+#[test]
+fn test_aliases() {
+    let source = r#"
+    #define alias fmp = 0x40 mload
+    #define alias zero = 0x00
+    #define alias unused = caller sload
+
+    #define macro MAIN() = takes(0) returns(0) {
+        fmp 0x40 mload
+        0x00 0x40 mload eq pop pop
+        zero
+    }
+    "#;
+    let contract = parse(source);
+    assert_eq!(
+        Codegen::generate_main_bytecode(&contract).unwrap(),
+        "60405160405160006040511450506000"
+    );
+
+    let warnings = analyze_contract(&contract)
+        .into_iter()
+        .filter(|w| w.kind.lint().ends_with("-alias"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(),
+        vec![
+            WarningKind::UnusedAlias("unused".to_string()),
+            WarningKind::InlinedAlias("MAIN".to_string(), "fmp".to_string()),
+            WarningKind::InlinedAlias("MAIN".to_string(), "fmp".to_string()),
+        ]
+    );
+    assert_eq!(warnings[0].kind.code(), "H0514");
+    assert_eq!(warnings[1].kind.code(), "H0515");
+    assert_eq!(warnings[1].help.as_deref(), Some("use the alias instead: fmp"));
+
+    // Expanded statements aren't spelled out, and single statement aliases aren't reported
+    let span = &warnings[1].span.0;
+    assert_eq!(span.len(), 1);
+    assert_eq!(span[0].start, source.find("fmp 0x40").unwrap() + 6);
+}
+
+#[test]
+fn test_tests_reach_macros() {
+    let source = r#"
+    #define alias fmp = 0x40 mload
+    #define constant LIMIT = 0x3e8
+
+    #define macro CHECKED() = takes(0) returns(0) {
+        fmp [LIMIT] gt pop
+    }
+
+    #define macro DEAD() = takes(0) returns(0) {
+        stop
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        stop
+    }
+
+    #define test TEST_CHECKED() = {
+        CHECKED()
+    }
+    "#;
+    let contract = parse(source);
+
+    // Macros, constants and aliases only used by tests aren't reported
+    let warnings = analyze_unused_definitions(&contract);
+    assert_eq!(
+        warnings.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(),
+        vec![WarningKind::UnusedMacro("DEAD".to_string())]
+    );
+}
+
+#[test]
+fn test_assertions_use_definitions() {
+    let source = r#"
+    #define constant MAX_OWNERS = 0x02
+    #define constant SCALE = 0x0a
+
+    #define jumptable OWNERS {
+        done done
+    }
+
+    __ASSERT(__tablesize(OWNERS) == [MAX_OWNERS] * 0x20)
+    __ASSERT(__codesize(SIZED) < 0x10)
+
+    #define macro SIZED() = takes(0) returns(0) {
+        0x00 pop
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __ASSERT([SCALE] > 0x01)
+        done:
+    }
+    "#;
+    let contract = parse(source);
+
+    // Definitions only referenced by assertions aren't reported
+    let warnings = analyze_unused_definitions(&contract);
+    assert_eq!(warnings.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(), vec![]);
+}
+
+#[test]
+fn test_included_definitions() {
+    let dir = std::env::temp_dir().join(format!("huff_included_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("Math.huff"),
+        "#define constant ONE = 0x01\n#define macro ADD() = takes(2) returns(1) {\n    add\n}\n\
+         #define macro SUB() = takes(2) returns(1) {\n    sub\n}\n",
+    )
+    .unwrap();
+    let main = dir.join("Main.huff");
+    fs::write(
+        &main,
+        "#include \"./Math.huff\"\n#define constant UNUSED = 0x02\n\
+         #define macro MAIN() = takes(0) returns(0) {\n    [ONE] [ONE] ADD() pop\n}\n",
+    )
+    .unwrap();
+
+    // The definitions of the included library are left to compiling the library
+    let compiler = Compiler::builder().source(main.to_string_lossy()).build();
+    let results = compiler.execute().unwrap();
+    assert_eq!(
+        results[0].warnings.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(),
+        vec![WarningKind::UnusedConstant("UNUSED".to_string())]
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+const LIB: &str = r#"#define function transfer(address,uint256) nonpayable returns (bool)
+#define event Transfer(address indexed,address indexed,uint256)
+#define constant OWNER = FREE_STORAGE_POINTER()
+#define constant BALANCES = FREE_STORAGE_POINTER()
+
+#define macro MAIN() = takes(0) returns(0) {
+    [OWNER] sload [BALANCES] sload
+    stop
+}
+"#;
+
+const MAIN: &str = r#"#include artifact "./out/Lib.json"
+
+#define macro MAIN() = takes(0) returns(0) {
+    [LIB_TRANSFER_SELECTOR] [LIB_TRANSFER_EVENT_HASH] [LIB_BALANCES]
+    __tablestart(LIB_RUNTIME)
+    stop
+}
+"#;
+
+#[test]
+fn test_compile_against_artifact() {
+    let dir = std::env::temp_dir().join(format!("huff_compile_artifact_{}", std::process::id()));
+    let file = |name: &str, source: &str| {
+        Arc::new(FileSource {
+            path: dir.join(name).to_string_lossy().to_string(),
+            source: Some(source.to_string()),
+            ..Default::default()
+        })
+    };
+
+    // The storage layout is recorded in the artifact
+    let lib = Compiler::default().gen_artifact(file("Lib.huff", LIB)).unwrap();
+    assert_eq!(
+        lib.storage_layout.clone().into_iter().collect::<Vec<_>>(),
+        vec![
+            ("BALANCES".to_string(), "0x01".to_string()),
+            ("OWNER".to_string(), "0x00".to_string())
+        ]
+    );
+    lib.export(dir.join("out/Lib.json").to_str().unwrap()).unwrap();
+
+    // The importing contract is compiled without the library's sources
+    let compiler = Compiler::default();
+    let main = compiler.gen_artifact(file("Main.huff", MAIN)).unwrap();
+    // The code tables follow the runtime code, the runtime table starting at 44 + 16 bytes
+    let topic = "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+    assert_eq!(lib.bytecode.len() / 2, 16);
+    assert_eq!(
+        main.runtime,
+        format!("63a9059cbb7f{}600161003c00{}{}", topic, lib.bytecode, lib.runtime)
+    );
+
+    // Imported definitions aren't reported as unused
+    assert!(compiler.get_warnings().is_empty());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
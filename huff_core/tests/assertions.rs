@@ -0,0 +1,201 @@
+mod common;
+
+use huff_codegen::Codegen;
+use huff_lexer::Lexer;
+use huff_parser::Parser;
+use huff_utils::prelude::*;
+
+use common::compile;
+
+fn parse(source: &str) -> Contract {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    Parser::new(tokens, None).parse().unwrap()
+}
+
+const SOURCE: &str = r#"#define constant MAX_OWNERS = 0x02
+#define constant SCALE = 0x0a
+
+#define jumptable OWNERS {
+    owner_0 owner_1
+}
+
+__ASSERT(__tablesize(OWNERS) == [MAX_OWNERS] * 0x20)
+__ASSERT([SCALE] > 0x01 && !([SCALE] == 0x00))
+__ASSERT(__codesize(DISPATCH) == 0x04)
+
+#define macro DISPATCH() = takes(0) returns(0) {
+    __ASSERT(([SCALE] - 0x0b) / 0x02 > [SCALE])
+    owner_0 jump
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    DISPATCH()
+    owner_0:
+    owner_1:
+        __ASSERT(__KECCAK256('') != 0x00 || [MAX_OWNERS])
+        0x01 pop
+}
+"#;
+
+#[test]
+fn test_parse_assertions() {
+    let contract = parse(SOURCE);
+
+    // Top-level assertions belong to the contract, macro assertions are statements
+    assert_eq!(contract.assertions.len(), 3);
+    match &contract.assertions[0].expression {
+        Expression::Binary(BinaryOperator::Equal, left, right) => {
+            assert!(matches!(
+                **left,
+                Expression::BuiltinFunctionCall(BuiltinFunctionCall {
+                    kind: BuiltinFunctionKind::Tablesize,
+                    ..
+                })
+            ));
+            // Multiplication binds tighter than the comparison
+            assert_eq!(
+                **right,
+                Expression::Binary(
+                    BinaryOperator::Mul,
+                    Box::new(Expression::Constant("MAX_OWNERS".into())),
+                    Box::new(Expression::Literal(str_to_bytes32("20"))),
+                )
+            );
+        }
+        e => panic!("Expected an equality, got {:?}", e),
+    }
+    assert_eq!(
+        contract.assertions[1].expression.to_string(),
+        "([SCALE] > 0x01) && !([SCALE] == 0x00)"
+    );
+    match &contract.macros[0].statements[0].ty {
+        StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+            kind: BuiltinFunctionKind::Assert(expression),
+            ..
+        }) => assert_eq!(expression.to_string(), "(([SCALE] - 0x0b) / 0x02) > [SCALE]"),
+        ty => panic!("Expected an assertion, got {:?}", ty),
+    }
+}
+
+#[test]
+fn test_assertions_hold() {
+    let contract = parse(SOURCE);
+    assert!(Codegen::check_assertions(&contract).is_empty());
+
+    // Assertions don't generate any bytecode, and the subtraction wraps around
+    let artifact = compile(SOURCE).unwrap();
+    let table = format!("{}04{}05", "00".repeat(31), "00".repeat(31));
+    assert_eq!(artifact.runtime, format!("610004565b5b600150{}", table));
+}
+
+#[test]
+fn test_failed_assertion() {
+    let source = r#"#define constant MAX_OWNERS = 0x10
+
+#define jumptable OWNERS {
+    owner_0 owner_1
+}
+
+__ASSERT(__tablesize(OWNERS) == [MAX_OWNERS])
+
+#define macro MAIN() = takes(0) returns(0) {
+    owner_0:
+    owner_1:
+}
+"#;
+    match compile(source) {
+        Err(CompilerError::CodegenError(e)) => {
+            assert_eq!(
+                e.kind,
+                CodegenErrorKind::AssertionFailed("__tablesize(OWNERS) == [MAX_OWNERS]".into())
+            );
+            assert_eq!(e.help.as_deref(), Some("the left side is 0x40, the right side is 0x10"));
+            // The spans cover the whole assertion
+            let (start, end) = (e.span.0.first().unwrap().start, e.span.0.last().unwrap().end);
+            assert_eq!(&source[start..=end], "__ASSERT(__tablesize(OWNERS) == [MAX_OWNERS])");
+        }
+        r => panic!("Expected a failed assertion, got {:?}", r),
+    }
+
+    // Assertions in macros fail as the macro is generated
+    let source = r#"#define constant SCALE = 0x00
+
+#define macro MAIN() = takes(0) returns(0) {
+    __ASSERT([SCALE])
+}
+"#;
+    match compile(source) {
+        Err(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.kind, CodegenErrorKind::AssertionFailed("[SCALE]".into()));
+            assert_eq!(e.help, None);
+        }
+        r => panic!("Expected a failed assertion, got {:?}", r),
+    }
+}
+
+#[test]
+fn test_assertion_errors() {
+    let source = r#"#define constant SCALE = 0x0a
+#define immutable OWNER
+
+__ASSERT([SCAL] > 0x00)
+__ASSERT([OWNER] != 0x00)
+__ASSERT(__codesize(MAIN) > 0x00)
+
+#define macro MAIN() = takes(0) returns(0) {
+    __ASSERT(__codesize(MAIN) > 0x00)
+}
+"#;
+    match compile(source) {
+        Err(CompilerError::FailedCompiles(errors)) => {
+            let errors = errors
+                .into_iter()
+                .map(|e| match e {
+                    CompilerError::CodegenError(e) => (e.kind, e.help),
+                    e => panic!("Expected a codegen error, got {:?}", e),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                errors,
+                vec![
+                    (
+                        CodegenErrorKind::MissingConstantDefinition("SCAL".to_string()),
+                        Some("did you mean 'SCALE'?".to_string())
+                    ),
+                    (
+                        CodegenErrorKind::MissingConstantDefinition("OWNER".to_string()),
+                        Some("'OWNER' is an immutable, only known at deployment".to_string())
+                    ),
+                    // The codesize of a macro asserting on its own codesize never ends
+                    (
+                        CodegenErrorKind::RecursiveMacroInvocation("MAIN > MAIN".to_string()),
+                        Some(
+                            "\"MAIN\" is invoked in its own expansion, which never ends"
+                                .to_string()
+                        )
+                    ),
+                    (
+                        CodegenErrorKind::RecursiveMacroInvocation("MAIN > MAIN".to_string()),
+                        Some(
+                            "\"MAIN\" is invoked in its own expansion, which never ends"
+                                .to_string()
+                        )
+                    ),
+                ]
+            );
+        }
+        r => panic!("Expected failed compiles, got {:?}", r),
+    }
+}
+
+#[test]
+fn test_invalid_assertion_operand() {
+    let source = r#"__ASSERT(0x01 == __tablestart(OWNERS))"#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let e = Parser::new(tokens, None).parse().unwrap_err();
+    assert_eq!(e.kind, ParserErrorKind::InvalidArgs(TokenKind::Ident("__tablestart".to_string())));
+}
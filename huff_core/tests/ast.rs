@@ -0,0 +1,56 @@
+mod common;
+
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+use common::source_file;
+
+#[test]
+fn test_ast_json() {
+    let source = r#"/// @notice Gets the value
+#define function get() view returns (uint256)
+#define constant SLOT = FREE_STORAGE_POINTER()
+
+#define macro MAIN() = takes(0) returns(0) {
+    [SLOT] sload 0x00 mstore
+    0x20 0x00 return
+}
+"#;
+    let contract = Compiler::default().ast(source_file("Ast.huff", source)).unwrap();
+    let json = contract.to_json();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    // Spans reference their file by path, and literals are hex strings
+    let statements = &value["macros"][0]["statements"];
+    assert_eq!(statements[0]["ty"], serde_json::json!({ "Constant": "SLOT" }));
+    assert_eq!(
+        statements[0]["span"],
+        serde_json::json!([{ "start": 171, "end": 175, "file": "Ast.huff" }])
+    );
+    assert_eq!(statements[1]["ty"], serde_json::json!({ "Opcode": "Sload" }));
+    assert_eq!(
+        statements[2]["ty"],
+        serde_json::json!({
+            "Literal": "0x0000000000000000000000000000000000000000000000000000000000000000"
+        })
+    );
+    assert_eq!(value["functions"][0]["signature"], "0x6d4ce63c");
+    assert_eq!(
+        value["natspec"],
+        serde_json::json!([
+            ["Function", "get", { "notice": "Gets the value", "dev": null, "params": {} }]
+        ])
+    );
+
+    // The AST deserializes back, its files keeping only their path
+    let deserialized: Contract = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.to_json(), json);
+    let file = Arc::new(FileSource { path: "Ast.huff".to_string(), ..Default::default() });
+    let mut statements = contract.macros[0].statements.clone();
+    for statement in statements.iter_mut() {
+        statement.span.0.iter_mut().for_each(|span| span.file = Some(Arc::clone(&file)));
+    }
+    assert_eq!(deserialized.macros[0].statements, statements);
+}
@@ -0,0 +1,91 @@
+use std::fs;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_per_file_results() {
+    let dir = std::env::temp_dir().join(format!("huff_batch_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let valid = dir.join("Valid.huff");
+    let invalid = dir.join("Invalid.huff");
+    let missing = dir.join("Missing.huff");
+    fs::write(
+        &valid,
+        "#define macro MAIN(unused) = takes(0) returns(1) {\n    0x00 calldataload\n}\n",
+    )
+    .unwrap();
+    fs::write(&invalid, "#define macro MAIN() = takes(0) returns(0) {\n    UNKNOWN()\n}\n")
+        .unwrap();
+
+    let sources = vec![&valid, &invalid, &missing]
+        .into_iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect::<Vec<String>>();
+    let compiler = Compiler::builder().sources(sources.clone()).build();
+    let results = compiler.execute().unwrap();
+
+    // A result for each input, in order
+    assert_eq!(results.iter().map(|r| r.path.clone()).collect::<Vec<String>>(), sources);
+
+    // The valid file compiled, with its own warnings
+    assert!(results[0].artifact().is_some());
+    assert_eq!(results[0].warnings.len(), 1);
+    assert_eq!(results[0].warnings[0].kind.code(), "H0502");
+
+    // The invalid file failed on its own
+    assert!(results[1].artifact().is_none());
+    assert_eq!(results[1].diagnostics()[0].code, "H0310");
+
+    // The missing file failed to be fetched
+    match results[2].error().map(|e| e.as_ref()) {
+        Some(CompilerError::FileUnpackError(UnpackError::MissingFile(f))) => {
+            assert_eq!(f, &sources[2])
+        }
+        e => panic!("Expected a missing file error, got {:?}", e),
+    }
+
+    // The shared warnings hold the warnings of every file
+    assert_eq!(compiler.get_warnings(), results[0].warnings);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_lint_levels() {
+    let dir = std::env::temp_dir().join(format!("huff_lints_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("Lints.huff");
+    fs::write(
+        &file,
+        "#define constant UNUSED = 0x01\n#define macro MAIN(unused) = takes(0) returns(1) {\n    0x00\n}\n",
+    )
+    .unwrap();
+    let sources = vec![file.to_string_lossy().to_string()];
+
+    // Allowed lints are dropped
+    let mut levels = LintLevels::default();
+    levels.set("unused-macro-parameter", LintLevel::Allow).unwrap();
+    let compiler = Compiler::builder().sources(sources.clone()).lint_levels(levels).build();
+    let results = compiler.execute().unwrap();
+    assert!(results[0].artifact().is_some());
+    assert_eq!(
+        results[0].warnings.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(),
+        vec![WarningKind::UnusedConstant("UNUSED".to_string())]
+    );
+
+    // Denied lints fail compilation, while the others are still reported
+    let mut levels = LintLevels::default();
+    levels.set("unused-constant", LintLevel::Deny).unwrap();
+    let compiler = Compiler::builder().sources(sources).lint_levels(levels).build();
+    let results = compiler.execute().unwrap();
+    match results[0].error().map(|e| e.as_ref()) {
+        Some(CompilerError::DeniedWarnings(denied)) => {
+            assert_eq!(denied[0].kind, WarningKind::UnusedConstant("UNUSED".to_string()))
+        }
+        e => panic!("Expected denied warnings, got {:?}", e),
+    }
+    assert_eq!(results[0].warnings[0].kind.lint(), "unused-macro-parameter");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
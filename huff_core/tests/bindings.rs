@@ -0,0 +1,86 @@
+mod common;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+use common::source_file;
+
+fn compile(path: &str, source: &str) -> Artifact {
+    Compiler::default().gen_artifact(source_file(path, source)).unwrap()
+}
+
+#[test]
+fn test_generates_rust_bindings() {
+    let artifact = compile(
+        "./src/SimpleStore.huff",
+        "#define function get() view returns (uint256)\n#define macro MAIN() = takes(0) returns(0) {\n    0x01 pop\n}\n",
+    );
+    let abi = r#"[{"inputs":[],"name":"get","outputs":[{"name":"","type":"uint256"}],"stateMutability":"view","type":"function"}]"#;
+
+    let bindings = Bindings::rust(&artifact, RustBindingsCrate::Ethers).unwrap();
+    assert_eq!(bindings.name, "SimpleStore");
+    assert_eq!(bindings.file_name, "simple_store.rs");
+    assert_eq!(
+        bindings.source,
+        format!(
+            "//! Rust bindings of the `SimpleStore` contract, generated by `huffc bindings` from \"./src/SimpleStore.huff\".\n\nethers::contract::abigen!(\n    SimpleStore,\n    r#\"{{\"abi\":{},\"bytecode\":\"0x60038060093d393df3600150\",\"deployedBytecode\":\"0x600150\"}}\"#\n);\n",
+            abi
+        )
+    );
+
+    let bindings = Bindings::rust(&artifact, RustBindingsCrate::Alloy).unwrap();
+    assert_eq!(
+        bindings.source,
+        format!(
+            "//! Rust bindings of the `SimpleStore` contract, generated by `huffc bindings` from \"./src/SimpleStore.huff\".\n\nalloy::sol!(\n    #[allow(missing_docs)]\n    #[sol(rpc, bytecode = \"60038060093d393df3600150\", deployed_bytecode = \"600150\")]\n    SimpleStore,\n    r#\"{}\"#\n);\n",
+            abi
+        )
+    );
+}
+
+#[test]
+fn test_generates_typescript_bindings() {
+    let artifact = compile(
+        "./src/SimpleStore.huff",
+        "#define function get() view returns (uint256)\n#define macro MAIN() = takes(0) returns(0) {\n    0x01 pop\n}\n",
+    );
+
+    let bindings = Bindings::typescript(&artifact).unwrap();
+    assert_eq!(bindings.file_name, "SimpleStore.ts");
+    assert_eq!(
+        bindings.source,
+        r#"// TypeScript bindings of the `SimpleStore` contract, generated by `huffc bindings` from "./src/SimpleStore.huff".
+
+export const SIMPLE_STORE_ABI = [
+  {
+    "inputs": [],
+    "name": "get",
+    "outputs": [
+      {
+        "name": "",
+        "type": "uint256"
+      }
+    ],
+    "stateMutability": "view",
+    "type": "function"
+  }
+] as const;
+
+export type SimpleStoreAbi = typeof SIMPLE_STORE_ABI;
+
+export const SIMPLE_STORE_BYTECODE = "0x60038060093d393df3600150" as const;
+
+export const SIMPLE_STORE_DEPLOYED_BYTECODE = "0x600150" as const;
+"#
+    );
+}
+
+#[test]
+fn test_rejects_unlinked_libraries() {
+    let artifact = compile(
+        "Linked.huff",
+        "#define macro MAIN() = takes(0) returns(0) {\n    __LINK(Math) pop\n}\n",
+    );
+    assert_eq!(Bindings::rust(&artifact, RustBindingsCrate::Ethers), Err(vec!["Math".to_string()]));
+    assert_eq!(Bindings::typescript(&artifact), Err(vec!["Math".to_string()]));
+}
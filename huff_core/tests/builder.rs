@@ -0,0 +1,70 @@
+use std::{fs, str::FromStr};
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_builds_session_with_remappings() {
+    let dir = std::env::temp_dir().join(format!("huff_builder_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(dir.join("lib/math/src")).unwrap();
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(
+        dir.join("lib/math/src/Math.huff"),
+        "#define macro ONE() = takes(0) returns(1) {\n    0x01\n}\n",
+    )
+    .unwrap();
+    let main = dir.join("src/Main.huff");
+    fs::write(
+        &main,
+        "#include \"math/Math.huff\"\n#define constant FEE = 0x02\n#define macro MAIN() = \
+         takes(0) returns(0) {\n    ONE() [FEE] add pop\n}\n",
+    )
+    .unwrap();
+
+    let remapping = format!("math/={}/", dir.join("lib/math/src").to_string_lossy());
+    let session = Compiler::builder()
+        .source(main.to_string_lossy())
+        .remapping(remapping.parse().unwrap())
+        .evm_version(EVMVersion::Shanghai)
+        .define(ConstantDefinition::from_str("FEE=0x03").unwrap())
+        .build();
+
+    // The settings are read through the session
+    assert_eq!(session.sources.as_slice(), [main.to_string_lossy().to_string()]);
+    assert_eq!(session.evm_version, EVMVersion::Shanghai);
+    assert_eq!(session.output, None);
+
+    let results = session.execute().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    let artifact = results[0].artifact().unwrap();
+    assert_eq!(artifact.runtime, "600160030150");
+
+    // Derived sessions start from the settings of the session
+    let derived = session.to_builder().evm_version(EVMVersion::Paris).build();
+    assert_eq!(derived.remappings, session.remappings);
+    assert_eq!(derived.evm_version, EVMVersion::Paris);
+    assert_eq!(session.evm_version, EVMVersion::Shanghai);
+}
+
+#[test]
+fn test_parses_remappings() {
+    let remapping = "solady/=lib/solady/src/".parse::<Remapping>().unwrap();
+    assert_eq!(remapping.prefix, "solady/");
+    assert_eq!(remapping.path, "lib/solady/src/");
+    assert!("lib/solady/src/".parse::<Remapping>().is_err());
+
+    // The longest matching prefix wins
+    let remappings = vec![
+        remapping,
+        Remapping { prefix: "solady/tokens/".to_string(), path: "tokens/".to_string() },
+    ];
+    assert_eq!(
+        FileSource::remap("solady/tokens/ERC20.huff", &remappings),
+        Some("tokens/ERC20.huff".to_string())
+    );
+    assert_eq!(
+        FileSource::remap("solady/Auth.huff", &remappings),
+        Some("lib/solady/src/Auth.huff".to_string())
+    );
+    assert_eq!(FileSource::remap("./Auth.huff", &remappings), None);
+}
@@ -366,3 +366,473 @@ fn test_label_clashing() {
     let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
     assert_eq!(mbytes, String::from("600861004960003961012861005160003960003560e01c8063a9059cbb14610022575b60208703516202ffe016806020015b60206020015b60206020015b60206020015b602060200100310037003d004300000000000000000000000000000000000000000000000000000000000000310000000000000000000000000000000000000000000000000000000000000037000000000000000000000000000000000000000000000000000000000000003d0000000000000000000000000000000000000000000000000000000000000043"));
 }
+
+#[test]
+fn test_assert_codehash_builtin() {
+    let source = |hash: &str| {
+        format!(
+            r#"
+        #define macro CHILD() = takes(0) returns(0) {{
+            0x01 0x02 add
+        }}
+
+        #define macro MAIN() = takes(0) returns(0) {{
+            __ASSERT_CODEHASH(CHILD, {})
+            __codesize(CHILD)
+        }}
+    "#,
+            hash
+        )
+    };
+    let parse = |source: &str| {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut parser = Parser::new(tokens, None);
+        let mut contract = parser.parse().unwrap();
+        contract.derive_storage_pointers();
+        contract
+    };
+
+    // keccak256(0x6001600201)
+    let hash = "0xdecae447f2d47ba6808e14c600b3b7611ce1cc9a632546acfd1130ca42c07c4d";
+
+    // A matching codehash emits no bytecode
+    let contract = parse(&source(hash));
+    assert_eq!(Codegen::generate_main_bytecode(&contract).unwrap(), "6005");
+
+    // A mismatched codehash fails compilation
+    let contract = parse(&source("0x01"));
+    let e = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert_eq!(e.kind, CodegenErrorKind::CodehashMismatch("CHILD".to_string()));
+    assert_eq!(
+        e.help,
+        Some(format!(
+            "the codehash is {}, update the asserted hash if the change is intended",
+            hash
+        ))
+    );
+
+    // An unknown target fails compilation
+    let contract = parse(&source(hash).replace("CODEHASH(CHILD", "CODEHASH(CHLID"));
+    let e = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert_eq!(e.kind, CodegenErrorKind::MissingMacroDefinition("CHLID".to_string()));
+    assert_eq!(e.help, Some("did you mean 'CHILD'?".to_string()));
+
+    // Code written in a table body must be hex
+    let table = "#define table CODE { c0ffee code }";
+    let contract = parse(&(source(hash).replace("CODEHASH(CHILD", "CODEHASH(CODE") + table));
+    let e = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert_eq!(e.kind, CodegenErrorKind::InvalidCodehashTarget("CODE".to_string()));
+    assert_eq!(
+        e.help,
+        Some("\"code\" isn't hex code: Invalid character 'o' at position 1".to_string())
+    );
+}
+
+#[test]
+fn test_table_from_file() {
+    let dir = std::env::temp_dir().join(format!("huff_table_file_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("blob.bin"), [0xde, 0xad, 0xbe, 0xef]).unwrap();
+    std::fs::write(dir.join("large.bin"), vec![0xff; 0x10000]).unwrap();
+    let base = dir.join("Data.huff").to_string_lossy().to_string();
+    let parse = |source: &str| {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut contract = Parser::new(tokens, Some(base.clone())).parse().unwrap();
+        contract.derive_storage_pointers();
+        contract
+    };
+
+    // The file contents are placed in the code section
+    let contract = parse(
+        r#"
+        #define table DATA = file("./blob.bin")
+
+        #define macro MAIN() = takes(0) returns(0) {
+            __tablesize(DATA) __tablestart(DATA) 0x00 codecopy
+        }
+    "#,
+    );
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(mbytes, "6004610008600039deadbeef");
+
+    // Tables starting after a large table can't be addressed
+    let contract = parse(
+        r#"
+        #define table LARGE = file("./large.bin")
+        #define table DATA = file("./blob.bin")
+
+        #define macro MAIN() = takes(0) returns(0) {
+            __tablestart(LARGE) __tablestart(DATA)
+        }
+    "#,
+    );
+    let errors = Codegen::generate_macro_bytecode("MAIN", &contract).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, CodegenErrorKind::UsizeConversion("0x10006".to_string()));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cheatcode_builtins() {
+    let source: &str = r#"
+        #define test TEST_WARP() = {
+            0x64 __WARP()
+        }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut contract = Parser::new(tokens, None).parse().unwrap();
+    contract.macros.push(contract.tests.remove(0));
+
+    // The argument is stored after the selector from `msize`, and the cheatcode address called
+    let bytes = Codegen::generate_macro_bytecode("TEST_WARP", &contract).unwrap();
+    assert_eq!(bytes, format!("6064{}", Cheatcode::Warp.bytecode()));
+    assert_eq!(
+        Cheatcode::Warp.bytecode(),
+        [
+            "59",
+            "63e5d6bf02",
+            "60e01b",
+            "8152",
+            "908160040152",
+            "600060006024",
+            "83",
+            "6000",
+            "737109709ecfa91a80626ff3989d68f67f5b1dd12d",
+            "5af1",
+            "5050",
+        ]
+        .concat()
+    );
+    assert_eq!(Cheatcode::Warp.selector(), [0xe5, 0xd6, 0xbf, 0x02]);
+    assert_eq!(Cheatcode::from_builtin("__EXPECT_EMIT"), Some(Cheatcode::ExpectEmit));
+    assert_eq!(Cheatcode::decode(&Cheatcode::Deal.selector()), Some(Cheatcode::Deal));
+}
+
+#[test]
+fn test_keccak256_builtin() {
+    let source: &str = r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            __KECCAK256("eip1967.proxy.implementation")
+            __KECCAK256('')
+            __KECCAK256(0x0001)
+            __KECCAK256(0x01)
+            __KECCAK256("say \"hi\"")
+            __KECCAK256('say "hi"')
+        }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let contract = Parser::new(tokens, None).parse().unwrap();
+
+    let preimages = contract.macros[0]
+        .statements
+        .iter()
+        .map(|s| match &s.ty {
+            StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                kind: BuiltinFunctionKind::Keccak256(preimage),
+                ..
+            }) => preimage.clone(),
+            ty => panic!("Expected a keccak256 builtin, got {:?}", ty),
+        })
+        .collect::<Vec<_>>();
+    // Hex literals keep their leading zeros, and strings have their escapes resolved
+    assert_eq!(preimages[2], vec![0x00, 0x01]);
+    assert_eq!(preimages[3], vec![0x01]);
+    assert_eq!(preimages[4], b"say \"hi\"".to_vec());
+    assert_eq!(preimages[4], preimages[5]);
+
+    // Each hash is pushed as 32 bytes
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    let pushes = mbytes.as_bytes().chunks(66).map(|c| std::str::from_utf8(c).unwrap());
+    let pushes = pushes.collect::<Vec<_>>();
+    assert_eq!(pushes.len(), 6);
+    assert!(pushes.iter().all(|p| p.starts_with("7f")));
+    // The EIP-1967 implementation slot is the hash minus one
+    assert_eq!(pushes[0], "7f360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbd");
+    assert_eq!(pushes[1], "7fc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+    assert_ne!(pushes[2], pushes[3]);
+    assert_eq!(pushes[4], pushes[5]);
+}
+
+#[test]
+fn test_keccak256_builtin_invalid_preimage() {
+    let source: &str = r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            __KECCAK256(OWNER)
+        }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let e = Parser::new(tokens, None).parse().unwrap_err();
+    assert_eq!(e.kind, ParserErrorKind::InvalidArgs(TokenKind::Ident("OWNER".to_string())));
+}
+
+#[test]
+fn test_rightpad_builtin() {
+    let parse = |source: &str| {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer
+            .into_iter()
+            .collect::<Result<Vec<Token>, _>>()
+            .map_err(CompilerError::LexicalError)?;
+        Parser::new(tokens, None).parse().map_err(CompilerError::ParserError)
+    };
+
+    // Literals keep their leading zeros, and are pushed padded on the right
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            __RIGHTPAD(0xdead) __RIGHTPAD(0x00ff) __RIGHTPAD(0x0)
+        }
+    "#,
+    )
+    .unwrap();
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(
+        mbytes,
+        [
+            format!("7fdead{}", "00".repeat(30)),
+            format!("7f00ff{}", "00".repeat(30)),
+            format!("7f{}", "00".repeat(32)),
+        ]
+        .concat()
+    );
+
+    // Literals wider than 32 bytes fail with their span
+    let source = format!(
+        "#define macro MAIN() = takes(0) returns(0) {{\n    __RIGHTPAD(0x{})\n}}",
+        "ab".repeat(33)
+    );
+    match parse(&source) {
+        Err(CompilerError::LexicalError(e)) => {
+            assert_eq!(e.kind, LexicalErrorKind::LiteralTooLong(format!("0x{}", "ab".repeat(33))));
+            assert_eq!(e.span, Span::new(62..128, None));
+        }
+        r => panic!("Expected a literal too long, got {:?}", r),
+    }
+
+    // Only literals can be padded
+    match parse("#define macro MAIN() = takes(0) returns(0) {\n    __RIGHTPAD(OWNER)\n}") {
+        Err(CompilerError::ParserError(e)) => {
+            assert_eq!(e.kind, ParserErrorKind::InvalidArgs(TokenKind::Ident("OWNER".to_string())))
+        }
+        r => panic!("Expected invalid arguments, got {:?}", r),
+    }
+}
+
+#[test]
+fn test_string_builtins() {
+    let parse = |source: &str| {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        Parser::new(tokens, None).parse()
+    };
+    let word = format!("7f6869{}", "00".repeat(30));
+
+    // Strings are stored abi encoded at the offset on the stack, replaced by the size stored
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 __MSTORE_STRING("hi")
+        }
+    "#,
+    )
+    .unwrap();
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(
+        mbytes,
+        ["6000", "60208152", "60028160200152", &word, "8160400152", "506060"].concat()
+    );
+
+    // Reasons are stored after the selector of `Error(string)`, ending in 0x1c
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            __REVERT_STRING("hi")
+        }
+    "#,
+    )
+    .unwrap();
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(
+        mbytes,
+        ["6308c379a0600052", "6020602052", "6002604052", &word, "606052", "6064601cfd"].concat()
+    );
+
+    // Only strings can be stored
+    let e = parse("#define macro MAIN() = takes(0) returns(0) {\n    __REVERT_STRING(0x01)\n}")
+        .unwrap_err();
+    assert_eq!(e.kind, ParserErrorKind::InvalidArgs(TokenKind::Literal(str_to_bytes32("01"))));
+}
+
+#[test]
+fn test_abi_builtins() {
+    let parse = |source: &str| {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        Parser::new(tokens, None).parse().unwrap()
+    };
+
+    // Parameters are pushed the last first, value types loaded from their head, static tuples as
+    // the offset of their head and dynamic types as the offset of their length
+    let contract = parse(
+        r#"
+        #define function send(address to, uint256 amount, bytes data, (uint256,uint256) pair) nonpayable returns ()
+        #define function get() view returns (uint256, bool)
+
+        #define macro SEND() = takes(0) returns(4) {
+            __DECODE_CALLDATA(send)
+        }
+
+        #define macro GET() = takes(2) returns(1) {
+            0x00 __ENCODE_OUTPUTS(get)
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            SEND() GET() 0x00 return
+        }
+    "#,
+    );
+    assert_eq!(
+        Codegen::generate_macro_bytecode("SEND", &contract).unwrap(),
+        ["6064", "604435600401", "602435", "600435"].concat()
+    );
+    // Outputs are stored the first first, from the offset on the stack replaced by the size
+    assert_eq!(
+        Codegen::generate_macro_bytecode("GET", &contract).unwrap(),
+        ["6000", "908152", "908160200152", "506040"].concat()
+    );
+    assert!(analyze_stack_heights(&contract).is_empty());
+
+    // The function must be declared, with outputs fitting in a word
+    let contract = parse(
+        r#"
+        #define function name() view returns (string)
+
+        #define macro MAIN() = takes(0) returns(0) {
+            __DECODE_CALLDATA(nme)
+            0x00 __ENCODE_OUTPUTS(name)
+        }
+    "#,
+    );
+    let errors = Codegen::generate_macro_bytecode("MAIN", &contract).unwrap_err();
+    assert_eq!(
+        errors.iter().map(|e| e.kind.clone()).collect::<Vec<_>>(),
+        vec![
+            CodegenErrorKind::MissingFunctionDefinition("nme".to_string()),
+            CodegenErrorKind::UnencodableOutput("string".to_string()),
+        ]
+    );
+    assert_eq!(errors[0].help.as_deref(), Some("did you mean 'name'?"));
+}
+
+#[test]
+fn test_emit_builtin() {
+    let parse = |source: &str| {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        Parser::new(tokens, None).parse().unwrap()
+    };
+    let topic = "7fddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+    // Data is stored in memory from offset 0, and the indexed parameters are kept in order as
+    // the topics following the hash of the signature
+    let contract = parse(
+        r#"
+        #define event Transfer(address indexed from, address indexed to, uint256 amount)
+        #define event Deposit(uint256 assets, address indexed owner, uint256 shares)
+
+        #define macro TRANSFER() = takes(3) returns(0) {
+            __EMIT(Transfer)
+        }
+
+        #define macro DEPOSIT() = takes(3) returns(0) {
+            __EMIT(Deposit)
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            0x01 caller caller TRANSFER()
+        }
+    "#,
+    );
+    assert_eq!(
+        Codegen::generate_macro_bytecode("TRANSFER", &contract).unwrap(),
+        ["91600052", "90", topic, "6020", "6000", "a3"].concat()
+    );
+    let deposit = Codegen::generate_macro_bytecode("DEPOSIT", &contract).unwrap();
+    assert!(deposit.starts_with(["600052", "90602052"].concat().as_str()));
+    assert!(deposit.ends_with("60406000a2"));
+    assert!(analyze_stack_heights(&contract).is_empty());
+
+    // The event must be declared, with parameters fitting in a word and at most three indexed
+    let contract = parse(
+        r#"
+        #define event Named(string name)
+        #define event Indexed(uint256 indexed a, uint256 indexed b, uint256 indexed c, uint256 indexed d)
+
+        #define macro MAIN() = takes(0) returns(0) {
+            __EMIT(Transfr)
+            __EMIT(Named)
+            __EMIT(Indexed)
+        }
+    "#,
+    );
+    let errors = Codegen::generate_macro_bytecode("MAIN", &contract).unwrap_err();
+    assert_eq!(
+        errors.iter().map(|e| e.kind.clone()).collect::<Vec<_>>(),
+        vec![
+            CodegenErrorKind::MissingEventDefinition("Transfr".to_string()),
+            CodegenErrorKind::UnemittableEvent("Named".to_string()),
+            CodegenErrorKind::UnemittableEvent("Indexed".to_string()),
+        ]
+    );
+    assert_eq!(errors[1].help.as_deref(), Some("\"string\" doesn't fit in a stack word"));
+}
+
+#[test]
+fn test_verbatim_builtin() {
+    let parse = |source: &str| {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        Parser::new(tokens, None).parse()
+    };
+
+    // Bytes are spliced as written, leading zeros included
+    let contract = parse(
+        r#"
+        #define macro PUSH_ZEROS() = takes(0) returns(2) {
+            __VERBATIM(0x5f5f)
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            PUSH_ZEROS() __VERBATIM(0x00fe) sstore
+        }
+    "#,
+    )
+    .unwrap();
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(mbytes, "5f5f00fe55");
+    assert_eq!(
+        Codegen::verbatim_references(&contract).unwrap(),
+        vec![VerbatimReference { start: 0, length: 2 }, VerbatimReference { start: 2, length: 2 }]
+    );
+
+    // Only whole bytes can be spliced
+    let e = parse("#define macro MAIN() = takes(0) returns(0) {\n    __VERBATIM(0x5f5)\n}")
+        .unwrap_err();
+    assert_eq!(e.kind, ParserErrorKind::InvalidVerbatimBytes("0x5f5".to_string()));
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+fn compile(source: &str) -> Result<Artifact, Arc<CompilerError>> {
+    let file = Arc::new(FileSource {
+        path: "Token.huff".to_string(),
+        source: Some(source.to_string()),
+        ..Default::default()
+    });
+    let file = Compiler::recurse_deps(file)?;
+    Compiler::default().gen_artifact(file).map_err(Arc::new)
+}
+
+#[test]
+fn test_bundled_modules() {
+    assert_eq!(bundled_module("std/Auth.huff"), bundled_module("<std/Auth.huff>"));
+    assert!(bundled_module("<std/Missing.huff>").is_none());
+    assert!(is_bundled_path("<std/Auth.huff>"));
+    assert!(!is_bundled_path("./std/Auth.huff"));
+}
+
+#[test]
+fn test_resolves_bundled_includes() {
+    let source = r#"#include <std/ERC20.huff>
+#include <std/Auth.huff>
+
+#define macro CONSTRUCTOR() = takes(0) returns(0) {
+    AUTH_CONSTRUCTOR()
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x00 calldataload 0xe0 shr
+    ERC20_DISPATCH()
+    AUTH_DISPATCH()
+    0x00 0x00 revert
+}
+"#;
+    let file = Arc::new(FileSource {
+        path: "Token.huff".to_string(),
+        source: Some(source.to_string()),
+        ..Default::default()
+    });
+    let file = Compiler::recurse_deps(file).unwrap();
+    let dependencies = file.dependencies.as_ref().unwrap();
+    assert_eq!(
+        dependencies.iter().map(|d| d.path.as_str()).collect::<Vec<_>>(),
+        vec!["<std/ERC20.huff>", "<std/Auth.huff>"]
+    );
+    assert!(dependencies.iter().all(|d| d.source.is_some()));
+
+    // The definitions of the modules are part of the artifact
+    let artifact = compile(source).unwrap();
+    let abi = artifact.abi.unwrap();
+    assert!(abi.functions.contains_key("transferFrom"));
+    assert!(abi.functions.contains_key("transferOwnership"));
+    assert!(abi.events.contains_key("OwnershipTransferred"));
+}
+
+#[test]
+fn test_compiles_bundled_modules() {
+    let dispatch = [
+        ("ERC721", "ERC721_DISPATCH()"),
+        ("Proxy", "PROXY_DISPATCH() PROXY_DELEGATE()"),
+        ("SafeMath", "0x01 0x02 SAFE_ADD() 0x03 SAFE_MUL() 0x01 SAFE_SUB() 0x01 SAFE_DIV() 0x01 SAFE_MOD()"),
+    ];
+    for (module, body) in dispatch {
+        let source = format!(
+            "#include <std/{}.huff>\n#define macro MAIN() = takes(0) returns(0) {{\n    0x00 calldataload 0xe0 shr\n    {}\n    0x00 0x00 revert\n}}\n",
+            module, body
+        );
+        let artifact = compile(&source).unwrap_or_else(|e| panic!("{} failed: {:?}", module, e));
+        assert!(!artifact.runtime.is_empty());
+    }
+}
+
+#[test]
+fn test_missing_bundled_module() {
+    let file = Arc::new(FileSource {
+        path: "Token.huff".to_string(),
+        source: Some("#include <std/ERC1155.huff>".to_string()),
+        ..Default::default()
+    });
+    let err = Compiler::recurse_deps(file).unwrap_err();
+    assert_eq!(
+        *err,
+        CompilerError::FileUnpackError(UnpackError::MissingFile("<std/ERC1155.huff>".to_string()))
+    );
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+const SOURCE: &str = r#"#define constant OWNER = FREE_STORAGE_POINTER()
+
+#define macro GET_OWNER() = takes(0) returns(1) {
+    [OWNER] sload
+}
+
+#define macro CHECK_OWNER(ok) = takes(0) returns(0) {
+    GET_OWNER() caller eq <ok> jumpi
+}
+
+#define macro UNUSED() = takes(0) returns(0) {
+    GET_OWNER()
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    CHECK_OWNER(ok) GET_OWNER()
+    0x00 0x00 revert
+    ok:
+        CHECK_OWNER(ok)
+        0x00 0x00 return
+}
+"#;
+
+fn call_graph() -> CallGraph {
+    let file = Arc::new(FileSource {
+        path: "Graph.huff".to_string(),
+        source: Some(SOURCE.to_string()),
+        ..Default::default()
+    });
+    Compiler::default().call_graph(file).unwrap()
+}
+
+#[test]
+fn test_macro_sizes() {
+    let graph = call_graph();
+    let sizes = graph
+        .macros
+        .iter()
+        .map(|m| (m.name.as_str(), m.expansions, m.expanded_size))
+        .collect::<Vec<_>>();
+
+    // Sizes include the invoked macros, and macros missing from the runtime bytecode are empty
+    assert_eq!(
+        sizes,
+        vec![("GET_OWNER", 3, 9), ("CHECK_OWNER", 2, 18), ("UNUSED", 0, 0), ("MAIN", 1, 32)]
+    );
+    assert_eq!(graph.macros[0].file.as_deref(), Some("Graph.huff"));
+}
+
+#[test]
+fn test_invocation_and_label_edges() {
+    let graph = call_graph();
+    let edge = |from: &str, to: &str, label: Option<&str>, count| CallGraphEdge {
+        from: from.to_string(),
+        to: to.to_string(),
+        label: label.map(String::from),
+        count,
+    };
+    assert_eq!(
+        graph.edges,
+        vec![
+            edge("CHECK_OWNER", "GET_OWNER", None, 1),
+            edge("UNUSED", "GET_OWNER", None, 1),
+            edge("MAIN", "CHECK_OWNER", None, 2),
+            edge("CHECK_OWNER", "MAIN", Some("ok"), 2),
+            edge("MAIN", "GET_OWNER", None, 1),
+        ]
+    );
+
+    assert_eq!(
+        graph.to_dot(),
+        r#"digraph calls {
+    "GET_OWNER" [label="GET_OWNER\n3 expansions, 9 bytes"];
+    "CHECK_OWNER" [label="CHECK_OWNER\n2 expansions, 18 bytes"];
+    "UNUSED" [label="UNUSED\n0 expansions, 0 bytes"];
+    "MAIN" [label="MAIN\n1 expansion, 32 bytes"];
+    "CHECK_OWNER" -> "GET_OWNER";
+    "UNUSED" -> "GET_OWNER";
+    "MAIN" -> "CHECK_OWNER" [label="2"];
+    "CHECK_OWNER" -> "MAIN" [style=dashed, label="ok (2)"];
+    "MAIN" -> "GET_OWNER";
+}"#
+    );
+
+    // Invocation edges have no label in json
+    let json: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+    assert_eq!(
+        json["edges"][0],
+        serde_json::json!({"from": "CHECK_OWNER", "to": "GET_OWNER", "count": 1})
+    );
+    assert_eq!(serde_json::from_str::<CallGraph>(&graph.to_json()).unwrap(), graph);
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use huff_core::Compiler;
+
+use common::source_file;
+
+#[test]
+fn test_codegen_log() {
+    let source = r#"#define constant FEE_BPS = 0x1e
+#define macro TAKE_FEE(fee) = takes(1) returns(1) {
+    <fee> mul
+}
+#define macro MAIN() = takes(0) returns(0) {
+    0x04 calldataload TAKE_FEE([FEE_BPS])
+    done jump
+    done:
+        stop
+}
+#define macro CONSTRUCTOR() = takes(0) returns(0) {
+    [FEE_BPS] pop
+}
+"#;
+    let logs = Compiler::default().codegen_log(source_file("Fees.huff", source)).unwrap();
+    assert_eq!(
+        logs.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(),
+        vec!["MAIN", "CONSTRUCTOR"]
+    );
+
+    let arg_call = &logs[0].entries[2];
+    assert_eq!(arg_call.offset, 3);
+    assert_eq!(arg_call.instructions, vec!["push1 0x1e"]);
+    assert_eq!(arg_call.location.as_deref(), Some("Fees.huff:3:6"));
+    assert_eq!(arg_call.expansion, vec!["MAIN", "TAKE_FEE"]);
+    assert_eq!(
+        arg_call.to_string(),
+        "0x0003  601e         push1 0x1e       <- <fee> at Fees.huff:3:6 in MAIN > TAKE_FEE: <fee> resolved to constant [FEE_BPS] = 0x1e through argument \"fee\" of TAKE_FEE passed by MAIN"
+    );
+    assert_eq!(
+        logs[0].entries[4].resolution.as_deref(),
+        Some("jump destination of label \"done\"")
+    );
+    assert_eq!(logs[1].entries[0].resolution.as_deref(), Some("constant [FEE_BPS] = 0x1e"));
+}
@@ -0,0 +1,40 @@
+use std::{str::FromStr, sync::Arc};
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_combines_selected_fields() {
+    let file = Arc::new(FileSource {
+        path: "./src/Counter.huff".to_string(),
+        source: Some(
+            "#define function get() view returns (uint256)\n#define macro MAIN() = takes(0) returns(0) {\n    0x01 pop\n}\n"
+                .to_string(),
+        ),
+        ..Default::default()
+    });
+    let artifact = Compiler::default().gen_artifact(file).unwrap();
+
+    let fields = "abi,bin,bin-runtime,hashes"
+        .split(',')
+        .map(|f| CombinedJsonField::from_str(f).unwrap())
+        .collect::<Vec<_>>();
+    let combined = CombinedJson::new(std::iter::once(&artifact), &fields, "0.1.0");
+    assert_eq!(
+        combined.to_json(),
+        r#"{"contracts":{"src/Counter.huff:Counter":{"abi":[{"inputs":[],"name":"get","outputs":[{"name":"","type":"uint256"}],"stateMutability":"view","type":"function"}],"bin":"60038060093d393df3600150","bin-runtime":"600150","hashes":{"get()":"6d4ce63c"}}},"version":"0.1.0"}"#
+    );
+
+    // Only the selected fields are combined
+    let combined =
+        CombinedJson::new(std::iter::once(&artifact), &[CombinedJsonField::Bin], "0.1.0");
+    assert_eq!(
+        combined.to_json(),
+        r#"{"contracts":{"src/Counter.huff:Counter":{"bin":"60038060093d393df3600150"}},"version":"0.1.0"}"#
+    );
+
+    assert_eq!(
+        CombinedJsonField::from_str("asm"),
+        Err("Invalid combined json field \"asm\". Available fields: abi,bin,bin-runtime,hashes,devdoc,userdoc".to_string())
+    );
+}
@@ -0,0 +1,42 @@
+//! Fixtures shared by the integration tests compiling sources in memory
+
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+/// Compiles a source with the default compiler
+pub fn compile(source: &str) -> Result<Artifact, CompilerError> {
+    compile_with(&Compiler::default(), source)
+}
+
+/// Compiles a source with a configured compiler
+pub fn compile_with(compiler: &Compiler, source: &str) -> Result<Artifact, CompilerError> {
+    compiler.gen_artifact(source_file("Contract.huff", source))
+}
+
+/// A file holding a source in memory, at a path
+pub fn source_file(path: &str, source: &str) -> Arc<FileSource> {
+    Arc::new(FileSource {
+        path: path.to_string(),
+        source: Some(source.to_string()),
+        ..Default::default()
+    })
+}
+
+/// The kinds of the codegen errors a source fails to compile with
+pub fn codegen_errors(source: &str) -> Vec<CodegenErrorKind> {
+    match compile(source) {
+        Err(CompilerError::CodegenError(e)) => vec![e.kind],
+        Err(CompilerError::FailedCompiles(errors)) => errors
+            .into_iter()
+            .filter_map(|e| match e {
+                CompilerError::CodegenError(e) => Some(e.kind),
+                _ => None,
+            })
+            .collect(),
+        res => panic!("Expected codegen errors, got {:?}", res.map(|a| a.runtime)),
+    }
+}
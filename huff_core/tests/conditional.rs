@@ -0,0 +1,51 @@
+use std::{str::FromStr, sync::Arc};
+
+use huff_core::{Compiler, CompilerSession};
+use huff_utils::prelude::*;
+
+const SOURCE: &str = r#"#define constant FEE = 0x01
+
+#define macro MAIN() = takes(0) returns(0) {
+    __IFDEF(DEBUG) {
+        0x00 calldataload pop
+    }
+    __IFNDEF(MAINNET) {
+        [FEE] pop
+    }
+    stop
+}
+"#;
+
+fn compiler(defines: &[&str]) -> CompilerSession {
+    let defines = defines.iter().map(|d| ConstantDefinition::from_str(d).unwrap()).collect();
+    Compiler::builder().defines(defines).build()
+}
+
+fn file() -> Arc<FileSource> {
+    Arc::new(FileSource {
+        path: "Conditional.huff".to_string(),
+        source: Some(SOURCE.to_string()),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_compiles_variants() {
+    assert_eq!(compiler(&[]).gen_artifact(file()).unwrap().runtime, "60015000");
+    assert_eq!(compiler(&["DEBUG"]).gen_artifact(file()).unwrap().runtime, "6000355060015000");
+    assert_eq!(compiler(&["MAINNET"]).gen_artifact(file()).unwrap().runtime, "00");
+    assert_eq!(
+        compiler(&["DEBUG", "FEE=0x64"]).gen_artifact(file()).unwrap().runtime,
+        "6000355060645000"
+    );
+}
+
+#[test]
+fn test_flags_are_used() {
+    // Constants compiled out are unused, the flags and defined constants are not reported
+    let (reported, _) = compiler(&["MAINNET", "UNUSED"]).lint(file()).unwrap();
+    assert_eq!(
+        reported.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(),
+        vec![WarningKind::UnusedConstant("FEE".to_string())]
+    );
+}
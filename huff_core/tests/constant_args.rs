@@ -0,0 +1,57 @@
+use std::fs;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_constant_args_across_files() {
+    let dir = std::env::temp_dir().join(format!("huff_constant_args_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+
+    // The library macro is configured by a constant it doesn't define
+    fs::write(
+        dir.join("Fees.huff"),
+        "#define macro TAKE_FEE(fee) = takes(1) returns(1) {\n    <fee> mul 0x2710 swap1 div\n}\n#define macro CHARGE() = takes(1) returns(1) {\n    TAKE_FEE([FEE_BPS])\n}\n",
+    )
+    .unwrap();
+    let main = dir.join("Main.huff");
+    fs::write(
+        &main,
+        "#include \"./Fees.huff\"\n#define constant FEE_BPS = 0x1e\n#define macro MAIN() = takes(0) returns(1) {\n    0x04 calldataload CHARGE()\n}\n",
+    )
+    .unwrap();
+    let missing = dir.join("Missing.huff");
+    fs::write(
+        &missing,
+        "#include \"./Fees.huff\"\n#define constant FEE_BP = 0x1e\n#define macro MAIN() = takes(0) returns(1) {\n    0x04 calldataload CHARGE()\n}\n",
+    )
+    .unwrap();
+
+    let sources = vec![&main, &missing]
+        .into_iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect::<Vec<String>>();
+    let compiler = Compiler::builder().sources(sources).build();
+    let results = compiler.execute().unwrap();
+
+    // The constant of the importing file is pushed
+    assert_eq!(results[0].artifact().unwrap().runtime, "600435601e026127109004");
+
+    // Unresolved constants point to the invocation passing them, with the expansion chain
+    match results[1].error().map(|e| e.as_ref()) {
+        Some(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.kind, CodegenErrorKind::MissingConstantDefinition("FEE_BPS".to_string()));
+            assert_eq!(
+                e.help,
+                Some(
+                    "did you mean 'FEE_BP'? \"FEE_BPS\" is passed as the \"fee\" argument of \"TAKE_FEE\", expanded as MAIN > CHARGE > TAKE_FEE; define it in the contract including \"TAKE_FEE\""
+                        .to_string()
+                )
+            );
+            assert!(e.span.0.iter().all(|s| s.file.as_ref().unwrap().path.ends_with("Fees.huff")));
+        }
+        e => panic!("Expected a missing constant error, got {:?}", e),
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
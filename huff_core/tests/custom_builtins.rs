@@ -0,0 +1,74 @@
+mod common;
+
+use huff_codegen::{BuiltinParam, CustomBuiltin};
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+use common::compile_with;
+
+/// Pushes the hash of an L1 block, by number
+#[derive(Debug)]
+struct L1Block;
+
+impl CustomBuiltin for L1Block {
+    fn name(&self) -> &str {
+        "__L1_BLOCK"
+    }
+
+    fn params(&self) -> &[BuiltinParam] {
+        &[BuiltinParam::Value]
+    }
+
+    fn expand(
+        &self,
+        args: &[MacroArg],
+        _span: &AstSpan,
+        _contract: &Contract,
+    ) -> Result<Vec<Statement>, CodegenError> {
+        let number = match &args[0] {
+            MacroArg::Literal(l) => StatementType::Literal(*l),
+            MacroArg::Constant(c) => StatementType::Constant(*c),
+            MacroArg::ArgCall(a) => StatementType::ArgCall(a.clone()),
+            MacroArg::Ident(_) => unreachable!("identifiers are rejected"),
+        };
+        Ok(vec![
+            Statement { ty: number, span: AstSpan(vec![]) },
+            Statement { ty: StatementType::Opcode(Opcode::Blockhash), span: AstSpan(vec![]) },
+        ])
+    }
+}
+
+fn compile(source: &str) -> Result<Artifact, CompilerError> {
+    compile_with(&Compiler::builder().builtin(L1Block).build(), source)
+}
+
+#[test]
+fn test_expands_custom_builtins() {
+    let artifact = compile(
+        "#define constant BLOCK = 0x03\n#define macro HASH(n) = takes(0) returns(1) {\n    \
+         __L1_BLOCK(<n>)\n}\n#define macro MAIN() = takes(0) returns(0) {\n    \
+         __L1_BLOCK(0x01) done:\n        __L1_BLOCK([BLOCK]) HASH(0x02)\n}\n",
+    )
+    .unwrap();
+    assert_eq!(artifact.runtime, "6001405b600340600240");
+}
+
+#[test]
+fn test_checks_custom_builtin_arguments() {
+    let source = "#define macro MAIN() = takes(0) returns(0) {\n    __L1_BLOCK(done) done:\n}\n";
+    match compile(source) {
+        Err(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.kind, CodegenErrorKind::InvalidBuiltinArgument("__L1_BLOCK".to_string()));
+            assert_eq!(e.help, Some("argument 1 must be a literal or a constant".to_string()));
+            assert_eq!(e.span.0[0].start, source.find("__L1_BLOCK").unwrap());
+        }
+        res => panic!("expected an invalid argument, got {:?}", res),
+    }
+
+    match compile("#define macro MAIN() = takes(0) returns(0) {\n    __L1_BLOCK()\n}\n") {
+        Err(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.help, Some("takes 1 arguments, got 0".to_string()));
+        }
+        res => panic!("expected an invalid argument, got {:?}", res),
+    }
+}
@@ -0,0 +1,58 @@
+use std::{fs, sync::Arc};
+
+use huff_codegen::Codegen;
+use huff_core::Compiler;
+use huff_lexer::Lexer;
+use huff_parser::Parser;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_table_data_contract() {
+    let dir = std::env::temp_dir().join(format!("huff_data_contract_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("blob.bin"), [0xde, 0xad, 0xbe, 0xef]).unwrap();
+    let main = dir.join("Main.huff");
+    fs::write(
+        &main,
+        "#define table DATA = file(\"./blob.bin\")\n#define jumptable LABELS {\n    done\n}\n#define macro MAIN() = takes(0) returns(0) {\n    done:\n}\n",
+    )
+    .unwrap();
+    let path = main.to_string_lossy().to_string();
+    let file = Arc::new(FileSource {
+        path: path.clone(),
+        source: Some(fs::read_to_string(&main).unwrap()),
+        ..Default::default()
+    });
+    let compiler = Compiler::default();
+
+    // Code tables are stored as is
+    let data_contract = compiler.data_contract(Arc::clone(&file), "DATA").unwrap();
+    assert_eq!(data_contract, DataContract::new("DATA", vec![0xde, 0xad, 0xbe, 0xef]));
+
+    // Jump tables have no code to store, and missing tables are suggested
+    match compiler.data_contract(Arc::clone(&file), "LABELS") {
+        Err(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.kind, CodegenErrorKind::InvalidDataTable("LABELS".to_string()))
+        }
+        r => panic!("Expected an invalid data table error, got {:?}", r),
+    }
+    match compiler.data_contract(file, "DAT") {
+        Err(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.kind, CodegenErrorKind::MissingMacroDefinition("DAT".to_string()));
+            assert_eq!(e.help, Some("did you mean 'DATA'?".to_string()));
+        }
+        r => panic!("Expected a missing table error, got {:?}", r),
+    }
+
+    // The generated reader macros compile
+    let source = format!(
+        "{}\n#define macro MAIN() = takes(0) returns(0) {{\n    DATA_SIZE() 0x00 0x00 [ADDRESS] READ_DATA()\n}}\n#define constant ADDRESS = 0x01\n",
+        data_contract.reader_macros()
+    );
+    let full_source = FullFileSource { source: &source, file: None, spans: vec![] };
+    let tokens = Lexer::new(full_source).map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let contract = Parser::new(tokens, None).parse().unwrap();
+    assert_eq!(Codegen::generate_main_bytecode(&contract).unwrap(), "600460006000600191600101913c");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::compile;
+
+#[test]
+fn test_decimal_literals_push_minimal_width() {
+    let source = r#"#define constant GAS_STIPEND = 2_300
+#define constant ONE_ETHER = 1_000_000_000_000_000_000
+
+#define macro ADD_FEE(fee) = takes(1) returns(1) {
+    <fee> add
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    0 1 255 256 [GAS_STIPEND] [ONE_ETHER] 0xDE_AD_BE_EF
+    ADD_FEE(1_000)
+    stop
+}
+"#;
+    let artifact = compile(source).unwrap();
+    assert_eq!(
+        artifact.runtime,
+        "6000600160ff6101006108fc670de0b6b3a764000063deadbeef6103e80100"
+    );
+}
+
+#[test]
+fn test_decimal_counts_stay_numbers() {
+    // `takes` and `returns` still take plain numbers
+    let source = r#"#define macro DOUBLE() = takes(1) returns(1) {
+    2 mul
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    21 DOUBLE() pop
+}
+"#;
+    assert_eq!(compile(source).unwrap().runtime, "601560020250");
+}
+
+#[test]
+fn test_binary_literals() {
+    let source = r#"#define constant PAUSED_FLAG = 0b0000_0100
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x00 sload [PAUSED_FLAG] and 0b1_0000_0000 or stop
+}
+"#;
+    assert_eq!(compile(source).unwrap().runtime, "6000546004166101001700");
+}
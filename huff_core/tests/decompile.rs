@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+const SOURCE: &str = r#"#define function transfer(address,uint256) nonpayable returns ()
+#define function owner() view returns (address)
+
+#define jumptable__packed JUMPS {
+    transfer owner
+}
+
+#define macro TRANSFER() = takes(0) returns(0) {
+    0x04 calldataload 0x24 calldataload sstore
+    stop
+}
+
+#define macro OWNER() = takes(0) returns(0) {
+    __tablesize(JUMPS) __tablestart(JUMPS) 0x00 codecopy
+    0x00 sload 0x00 mstore 0x20 0x00 return
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x00 calldataload 0xe0 shr
+    dup1 0xa9059cbb eq transfer jumpi
+    dup1 0x8da5cb5b eq owner jumpi
+    0x00 0x00 revert
+    transfer:
+        TRANSFER()
+    owner:
+        OWNER()
+}
+"#;
+
+fn compile(path: &str, source: &str) -> Vec<u8> {
+    let file = Arc::new(FileSource {
+        path: path.to_string(),
+        source: Some(source.to_string()),
+        ..Default::default()
+    });
+    str_to_vec(&Compiler::default().gen_artifact(file).unwrap().runtime).unwrap()
+}
+
+#[test]
+fn test_decompile_skeleton() {
+    let code = compile("Token.huff", SOURCE);
+    let decompilation = Decompilation::new("TOKEN", &code);
+
+    // Dispatched functions are stubbed, and name the blocks they're dispatched to
+    assert_eq!(
+        decompilation.functions.iter().map(|f| (f.name.as_str(), f.target)).collect::<Vec<_>>(),
+        vec![("func_a9059cbb", 0x21), ("func_8da5cb5b", 0x2a)]
+    );
+    assert_eq!(
+        decompilation
+            .blocks
+            .iter()
+            .map(|b| (b.name.as_str(), b.offset, b.takes, b.returns))
+            .collect::<Vec<_>>(),
+        vec![
+            ("BLOCK_0000", 0x00, 0, 1),
+            ("BLOCK_0011", 0x11, 1, 1),
+            ("BLOCK_001C", 0x1c, 0, 0),
+            ("FUNC_A9059CBB", 0x21, 0, 0),
+            ("FUNC_8DA5CB5B", 0x2a, 0, 0),
+        ]
+    );
+
+    // The table bytes following the last block are raw data
+    assert_eq!(decompilation.tables.len(), 1);
+    assert_eq!(decompilation.tables[0].name, "DATA_003E");
+    assert_eq!(decompilation.tables[0].data, vec![0x00, 0x21, 0x00, 0x2a]);
+    assert_eq!(decompilation.table_file(&decompilation.tables[0]), "TOKEN.DATA_003E.bin");
+
+    let source = decompilation.source();
+    assert!(source.contains("#define function func_a9059cbb() nonpayable returns ()\n"));
+    assert!(source.contains("#define table DATA_003E = file(\"./TOKEN.DATA_003E.bin\")\n"));
+    assert!(source.contains(
+        "#define macro BLOCK_0011() = takes(1) returns(1) {\n    dup1\n    0x8da5cb5b\n    eq\n    func_8da5cb5b\n    jumpi\n}"
+    ));
+    assert!(source.contains("    0x04\n    __tablestart(DATA_003E)\n    0x00\n    codecopy\n"));
+    assert!(source.ends_with(
+        "    func_a9059cbb:\n        FUNC_A9059CBB()\n    func_8da5cb5b:\n        FUNC_8DA5CB5B()\n}\n"
+    ));
+}
+
+#[test]
+fn test_decompile_round_trip() {
+    let code = compile("Token.huff", SOURCE);
+    let decompilation = Decompilation::new("TOKEN", &code);
+
+    // Compiling the skeleton reproduces bytecode generated by huff
+    let dir = std::env::temp_dir().join(format!("huff_decompile_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for table in decompilation.tables.iter() {
+        std::fs::write(dir.join(decompilation.table_file(table)), &table.data).unwrap();
+    }
+    let path = dir.join("TOKEN.decompiled.huff").to_string_lossy().to_string();
+    assert_eq!(compile(&path, &decompilation.source()), code);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_decompile_raw_data() {
+    // Jump destinations decoded from data aren't code, and undefined opcodes are data
+    let code = str_to_vec("6005565b005b0c5b00fe5b01").unwrap();
+    let decompilation = Decompilation::new("RAW", &code);
+    assert_eq!(
+        decompilation.blocks.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(),
+        vec!["BLOCK_0000", "LABEL_0005"]
+    );
+    assert_eq!(
+        decompilation.tables.iter().map(|t| (t.offset, t.data.clone())).collect::<Vec<_>>(),
+        vec![(0x03, vec![0x5b, 0x00]), (0x06, vec![0x0c, 0x5b, 0x00, 0xfe, 0x5b, 0x01])]
+    );
+}
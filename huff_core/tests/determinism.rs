@@ -0,0 +1,33 @@
+use std::fs;
+
+use huff_core::Compiler;
+
+#[test]
+fn test_recompiled_artifacts_match() {
+    let dir = std::env::temp_dir().join(format!("huff_determinism_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("Deterministic.huff");
+    fs::write(
+        &file,
+        "#define function get() view returns (uint256)\n#define event Set(uint256)\n\
+         #define constant SLOT = FREE_STORAGE_POINTER()\n\
+         #define jumptable TABLE {\n    one two\n}\n\
+         #define macro MAIN() = takes(0) returns(0) {\n    __tablesize(TABLE) \
+         __tablestart(TABLE) [SLOT] sload\n    one jump two jump\n    one:\n        stop\n    \
+         two:\n        stop\n}\n",
+    )
+    .unwrap();
+
+    let sources = vec![file.to_string_lossy().to_string()];
+    let compiler = Compiler::builder().sources(sources.clone()).build();
+    let results = compiler.execute().unwrap();
+    let artifacts = results.iter().filter_map(|r| r.artifact().cloned()).collect::<Vec<_>>();
+    assert_eq!(artifacts.len(), 1);
+    assert!(compiler.nondeterministic_artifacts(&artifacts).is_empty());
+
+    // A source changed between the compiles produces a different artifact
+    fs::write(&file, "#define macro MAIN() = takes(0) returns(0) {\n    0x01\n}\n").unwrap();
+    assert_eq!(compiler.nondeterministic_artifacts(&artifacts), sources);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+const SOURCE: &str = r#"#define macro MAIN() = takes(0) returns(0) {
+    0x00 calldataload 0xe0 shr
+    0xa9059cbb eq done jumpi
+    __tablestart(JUMPS) pop
+    0x00 0x00 revert
+    done:
+        stop
+}
+
+#define jumptable JUMPS {
+    done
+}
+"#;
+
+#[test]
+fn test_disassemble_bytecode() {
+    // Jump destinations hidden in push data aren't labelled
+    let code = str_to_vec("6080600b565b615b0000fe5b600c0c61").unwrap();
+    let disassembly = Disassembly::new(&code);
+    assert_eq!(
+        disassembly.render(false),
+        r#"0x0000  push1 0x80
+0x0002  push1 0x0b               // -> label_000b
+0x0004  jump
+label_0005:
+0x0005  jumpdest
+0x0006  push2 0x5b00
+0x0009  stop
+0x000a  invalid
+label_000b:
+0x000b  jumpdest
+0x000c  push1 0x0c
+0x000e  0x0c (undefined opcode)
+0x000f  push2 (truncated)"#
+    );
+    assert_eq!(disassembly.instructions[1].push_data(), Some(&[0x0b][..]));
+    assert_eq!(disassembly.instructions[1].target, Some("label_000b".to_string()));
+}
+
+#[test]
+fn test_disassemble_with_source_map() {
+    let file = Arc::new(FileSource {
+        path: "Disasm.huff".to_string(),
+        source: Some(SOURCE.to_string()),
+        ..Default::default()
+    });
+    let compiler = Compiler::default();
+    let artifact = compiler.gen_artifact(Arc::clone(&file)).unwrap();
+    let source_map = compiler.runtime_source_map(file).unwrap();
+    let code = str_to_vec(&artifact.runtime).unwrap();
+    let disassembly = Disassembly::with_source_map(&code, &source_map);
+
+    // Labels are named after the Huff labels, and tables listed as data
+    assert_eq!(
+        disassembly.render(false).lines().skip(6).collect::<Vec<_>>(),
+        vec![
+            "0x000c  push2 0x0019             // -> done",
+            "0x000f  jumpi",
+            "0x0010  push2 0x001b",
+            "0x0013  pop",
+            "0x0014  push1 0x00",
+            "0x0016  push1 0x00",
+            "0x0018  revert",
+            "done:",
+            "0x0019  jumpdest",
+            "0x001a  stop",
+            "0x001b  table JUMPS 0x0000000000000000000000000000000000000000000000000000000000000019",
+        ]
+    );
+
+    // Each statement is preceded by the source line generating it
+    let listing = disassembly.render(true);
+    assert!(listing.starts_with("        // Disasm.huff:2: 0x00 calldataload 0xe0 shr\n0x0000"));
+    assert!(listing.contains(
+        "        // Disasm.huff:6: done:\ndone:\n0x0019  jumpdest\n        // Disasm.huff:7: stop\n"
+    ));
+    assert!(listing.contains("        // Disasm.huff:10: #define jumptable JUMPS {\n0x001b  table"));
+}
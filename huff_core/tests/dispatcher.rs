@@ -0,0 +1,153 @@
+mod common;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+use common::source_file;
+
+fn dispatcher(source: &str) -> Dispatcher {
+    Compiler::default().dispatcher(source_file("Dispatcher.huff", source)).unwrap()
+}
+
+#[test]
+fn test_linear_dispatcher() {
+    let source = r#"#define function transfer(address,uint256) nonpayable returns ()
+#define function balanceOf(address) view returns (uint256)
+#define function owner() view returns (address)
+
+#define macro MAIN() = takes(0) returns(0) {
+    calldatasize iszero receive jumpi
+    0x00 calldataload 0xe0 shr
+    dup1 0xa9059cbb eq transfer jumpi
+    dup1 0x70a08231 eq balanceOf jumpi
+    dup1 0xa9059cbb eq transfer jumpi
+    0x00 0x00 revert
+
+    receive:
+        stop
+    transfer:
+        0x00 0x00 return
+    balanceOf:
+        0x00 0x00 return
+}
+"#;
+    let dispatcher = dispatcher(source);
+    assert_eq!(
+        dispatcher.root.steps[0],
+        DispatchStep::Guard { offset: 0x05, check: CalldataCheck::Empty, target: 0x32 }
+    );
+    assert_eq!(dispatcher.root.steps[1], DispatchStep::Selector { offset: 0x0b });
+    assert_eq!(
+        dispatcher.root.fallthrough,
+        Fallthrough { offset: 0x2d, end: 0x31, halt: Halt::Opcode("revert") }
+    );
+
+    // The second comparison of the transfer selector can't be reached
+    assert_eq!(
+        dispatcher.list(),
+        "0x0016 0xa9059cbb transfer(address,uint256) -> 0x0034, 52 gas\n\
+         0x0021 0x70a08231 balanceOf(address) -> 0x003a, 74 gas\n\
+         0x002c 0xa9059cbb transfer(address,uint256) -> 0x0034, 96 gas, unreachable past 0x0016\n\
+         owner() is never dispatched"
+    );
+    assert_eq!(dispatcher.undispatched(), vec!["owner()"]);
+}
+
+#[test]
+fn test_split_dispatcher_tree() {
+    let source = r#"#define function transfer(address,uint256) nonpayable returns ()
+#define function balanceOf(address) view returns (uint256)
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x00 calldataload 0xe0 shr
+    dup1 0x70a08231 lt upper jumpi
+    dup1 0x70a08231 eq balanceOf jumpi
+    dup1 0xa9059cbb eq transfer jumpi
+    fallback jump
+    upper:
+        dup1 0xa9059cbb eq transfer jumpi
+    fallback:
+        0x00 0x00 revert
+    transfer:
+        0x00 0x00 return
+    balanceOf:
+        0x00 0x00 return
+}
+"#;
+    let dispatcher = dispatcher(source);
+    assert_eq!(
+        dispatcher.tree(),
+        "Dispatcher\n\
+         ├─ 0x0005 selector loaded\n\
+         ├─ 0x0010 if selector > 0x70a08231\n\
+         │  ├─ 0x0036 0xa9059cbb transfer(address,uint256) -> 0x003d, 57 gas\n\
+         │  └─ 0x0037 fallthrough: revert at 0x003c\n\
+         ├─ 0x001b 0x70a08231 balanceOf(address) -> 0x0043, 56 gas\n\
+         ├─ 0x0026 0xa9059cbb transfer(address,uint256) -> 0x003d, 78 gas, unreachable past 0x0010\n\
+         └─ 0x0027 fallthrough: revert at 0x003c"
+    );
+    assert!(dispatcher.undispatched().is_empty());
+}
+
+#[test]
+fn test_computed_jump_dispatcher() {
+    // Jumping through a table indexed by the selector ends the recovered dispatcher
+    let dispatcher = Dispatcher::analyze("60003560e01c56");
+    assert_eq!(dispatcher.root.steps, vec![DispatchStep::Selector { offset: 0x05 }]);
+    assert_eq!(dispatcher.root.fallthrough.halt, Halt::ComputedJump);
+    assert!(dispatcher.matches().is_empty());
+}
+
+#[test]
+fn test_selector_table() {
+    let source = r#"#define function transfer(address,uint256) nonpayable returns ()
+#define function balanceOf(address) view returns (uint256)
+#define function owner() view returns (address)
+
+#define macro TRANSFER() = takes(0) returns(0) {
+    0x00 0x00 return
+}
+
+#define macro DISPATCH() = takes(1) returns(1) {
+    dup1 0xa9059cbb eq transfer jumpi
+    dup1 0x70a08231 eq balanceOf jumpi
+    0x00 0x00 revert
+
+    transfer:
+        TRANSFER()
+    balanceOf:
+        0x00 0x00 return
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x00 calldataload 0xe0 shr
+    DISPATCH()
+}
+"#;
+    let dispatcher = dispatcher(source);
+
+    // Inline functions enter the macro defining their label
+    assert_eq!(
+        dispatcher.table(),
+        "SELECTOR    SIGNATURE                  MACRO\n\
+         0xa9059cbb  transfer(address,uint256)  TRANSFER\n\
+         0x70a08231  balanceOf(address)         DISPATCH\n\
+         0x8da5cb5b  owner()                    -"
+    );
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&dispatcher.to_json()).unwrap(),
+        serde_json::json!([
+            {
+                "selector": "0xa9059cbb",
+                "signature": "transfer(address,uint256)",
+                "macro": "TRANSFER"
+            },
+            {"selector": "0x70a08231", "signature": "balanceOf(address)", "macro": "DISPATCH"},
+            {"selector": "0x8da5cb5b", "signature": "owner()", "macro": null}
+        ])
+    );
+    assert_eq!(
+        dispatcher.signatures(),
+        "balanceOf(address)\nowner()\ntransfer(address,uint256)\n"
+    );
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+use common::source_file;
+
+const SOURCE: &str = r#"/// @notice The owner
+#define constant OWNER = FREE_STORAGE_POINTER()
+#define constant MAX = 0x64
+
+/// @notice Transfers tokens
+/// @dev Reverts if the balance is too low
+/// @param amount The amount
+/// @param to The recipient
+#define function transfer(address to, uint256 amount) nonpayable returns ()
+
+#define event Transfer(address indexed from, address indexed to, uint256)
+
+/// @notice Reads the <owner>
+#define macro OWNER_OF() = takes(0) returns(1) {
+    [OWNER] sload
+}
+"#;
+
+fn docs(source: &str) -> Documentation {
+    Compiler::default().docs(source_file("Ownable.huff", source)).unwrap()
+}
+
+#[test]
+fn test_markdown_docs() {
+    let expected = r#"# Ownable
+
+## Functions
+
+| Function | Selector | Description |
+|---|---|---|
+| `transfer` | `0xa9059cbb` | Transfers tokens |
+
+### transfer
+
+```huff
+#define function transfer(address to, uint256 amount) nonpayable returns ()
+```
+
+Transfers tokens
+
+Reverts if the balance is too low
+
+| Parameter | Description |
+|---|---|
+| `to` | The recipient |
+| `amount` | The amount |
+
+## Events
+
+| Event | Topic | Description |
+|---|---|---|
+| `Transfer` | `0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef` |  |
+
+### Transfer
+
+```huff
+#define event Transfer(address indexed from, address indexed to, uint256)
+```
+
+## Constants
+
+| Constant | Value | Description |
+|---|---|---|
+| `MAX` | `0x64` |  |
+
+## Storage Layout
+
+| Constant | Slot | Description |
+|---|---|---|
+| `OWNER` | `FREE_STORAGE_POINTER()` | The owner |
+
+## Macros
+
+| Macro | Stack | Description |
+|---|---|---|
+| `OWNER_OF` | `takes (0) returns (1)` | Reads the <owner> |
+
+### OWNER_OF
+
+```huff
+#define macro OWNER_OF() = takes (0) returns (1)
+```
+
+Reads the <owner>
+"#;
+    assert_eq!(docs(SOURCE).to_markdown(), expected);
+}
+
+#[test]
+fn test_html_docs() {
+    let html = docs(SOURCE).to_html();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>Ownable</title>"));
+    assert!(html.contains("<tr><td><code>transfer</code></td><td><code>0xa9059cbb</code></td>"));
+    assert!(html.contains("<p>Reads the &lt;owner&gt;</p>"));
+    assert!(!html.contains("<owner>"));
+}
+
+#[test]
+fn test_storage_layout_of_contract() {
+    // The slots are derived in the order MAIN uses them
+    let source = format!(
+        "{}\n#define macro MAIN() = takes(0) returns(0) {{\n    OWNER_OF() pop\n}}\n",
+        SOURCE
+    );
+    let docs = docs(&source);
+    assert_eq!(docs.storage.len(), 1);
+    assert_eq!(docs.storage[0].value, "0x00");
+    assert_eq!(docs.macros.len(), 2);
+}
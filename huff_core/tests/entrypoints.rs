@@ -0,0 +1,68 @@
+mod common;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+use common::source_file;
+
+const SOURCE: &str = r#"#define function owner() view returns (address)
+
+#define constant OWNER = FREE_STORAGE_POINTER()
+
+#define receive() = takes(0) returns(0) {
+    callvalue [OWNER] sstore
+}
+
+#define fallback() = takes(0) returns(0) {
+    0x01 0x00 mstore
+    0x20 0x00 return
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x00 calldataload 0xe0 shr
+    0x8da5cb5b eq owner jumpi
+    fallback jump
+    owner:
+        [OWNER] sload 0x00 mstore
+        0x20 0x00 return
+}
+"#;
+
+#[test]
+fn test_entrypoints_wired_into_main() {
+    let compiler = Compiler::default();
+    let artifact = compiler.gen_artifact(source_file("Entrypoints.huff", SOURCE)).unwrap();
+
+    // Empty calldata jumps to the receive definition, placed after a stop
+    // The fallback definition is placed right after MAIN, at the `fallback` label
+    assert_eq!(
+        artifact.runtime,
+        "36156100325760003560e01c638da5cb5b1461001a57610026565b60005460005260206000f35b600160005260206000f3005b34600055"
+    );
+    assert!(compiler.get_warnings().is_empty());
+
+    let abi = artifact.abi.unwrap();
+    assert!(abi.receive);
+    assert!(abi.fallback);
+
+    let dispatcher = compiler.dispatcher(source_file("Entrypoints.huff", SOURCE)).unwrap();
+    assert_eq!(
+        dispatcher.root.steps[0],
+        DispatchStep::Guard { offset: 0x05, check: CalldataCheck::Empty, target: 0x32 }
+    );
+}
+
+#[test]
+fn test_missing_entrypoints() {
+    let source = r#"#define macro MAIN() = takes(0) returns(0) {
+    0x00 0x00 revert
+}
+"#;
+    let artifact =
+        Compiler::default().gen_artifact(source_file("Entrypoints.huff", source)).unwrap();
+    assert_eq!(artifact.runtime, "60006000fd");
+
+    let abi = artifact.abi.unwrap();
+    assert!(!abi.receive);
+    assert!(!abi.fallback);
+}
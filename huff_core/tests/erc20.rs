@@ -16,7 +16,7 @@ fn test_erc20_compile() {
     .collect();
 
     // Recurse file deps + generate flattened source
-    let file_source = file_sources.get(0).unwrap();
+    let file_source = file_sources.first().unwrap();
     let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source)).unwrap();
     println!("Recursed file source: {:?}", recursed_file_source);
     let flattened = FileSource::fully_flatten(Arc::clone(&recursed_file_source));
@@ -1,8 +1,12 @@
+mod common;
+
 use huff_codegen::*;
 use huff_lexer::*;
 use huff_parser::*;
 use huff_utils::prelude::*;
 
+use common::compile;
+
 #[test]
 fn test_storage_pointers_not_derived() {
     let source = r#"
@@ -53,7 +57,8 @@ fn test_storage_pointers_not_derived() {
                         Span { start: 44, end: 45, file: None },
                         Span { start: 46, end: 68, file: None }
                     ]),
-                    token: None
+                    token: None,
+                    help: None
                 }
             )
         }
@@ -109,7 +114,8 @@ fn test_invalid_constant_definition() {
                         "UNKNOWN_CONSTANT_DEFINITION".to_string()
                     ),
                     span: AstSpan(vec![Span { start: const_start, end: const_end, file: None }]),
-                    token: None
+                    token: None,
+                    help: None
                 }
             )
         }
@@ -195,7 +201,8 @@ fn test_missing_constructor() {
                 CodegenError {
                     kind: CodegenErrorKind::MissingMacroDefinition("CONSTRUCTOR".to_string()),
                     span: AstSpan(vec![Span { start: 0, end: 0, file: None }]),
-                    token: None
+                    token: None,
+                    help: None
                 }
             )
         }
@@ -228,7 +235,8 @@ fn test_missing_main() {
                 CodegenError {
                     kind: CodegenErrorKind::MissingMacroDefinition("MAIN".to_string()),
                     span: AstSpan(vec![Span { start: 0, end: 0, file: None }]),
-                    token: None
+                    token: None,
+                    help: None
                 }
             )
         }
@@ -273,7 +281,8 @@ fn test_unknown_macro_definition() {
                         Span { start: 351, end: 352, file: None },
                         Span { start: 352, end: 353, file: None }
                     ]),
-                    token: None
+                    token: None,
+                    help: None
                 }
             )
         }
@@ -281,7 +290,7 @@ fn test_unknown_macro_definition() {
 }
 
 #[test]
-fn test_unmatched_jump_label() {
+fn test_undefined_label_argument() {
     let source = r#"
     #define macro MINT(error) = takes(0) returns (0) {
         0x04 calldataload   // [to]
@@ -314,16 +323,108 @@ fn test_unmatched_jump_label() {
             assert_eq!(
                 e,
                 CodegenError {
-                    kind: CodegenErrorKind::UnmatchedJumpLabel,
+                    kind: CodegenErrorKind::InvalidMacroArgument("err".to_string()),
                     span: AstSpan(vec![
                         Span { start: 372, end: 376, file: None },
                         Span { start: 376, end: 377, file: None },
                         Span { start: 377, end: 380, file: None },
                         Span { start: 380, end: 381, file: None }
                     ]),
-                    token: None
+                    token: None,
+                    help: Some(
+                        "\"err\" is passed to \"MINT\", but is neither an opcode nor a label"
+                            .to_string()
+                    )
                 }
             )
         }
     }
 }
+
+#[test]
+fn test_misspelled_definitions_suggest_names() {
+    let source = r#"
+    #define constant OWNER_SLOT = 0x00
+
+    #define macro TRANSFER_FROM() = takes(0) returns (0) {
+        [OWNER_SLTO] sload
+    }
+
+    #define macro MAIN() = takes(0) returns (0) {
+        TRANSFER_FORM()
+    }
+    "#;
+
+    let full_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(full_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, Some("".to_string()));
+    let mut contract = parser.parse().unwrap();
+    contract.derive_storage_pointers();
+
+    // The misspelled macro invocation suggests the defined macro
+    let e = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert_eq!(e.kind, CodegenErrorKind::InvalidMacroInvocation("TRANSFER_FORM".to_string()));
+    assert_eq!(e.help, Some("did you mean 'TRANSFER_FROM'?".to_string()));
+    let diagnostic = &Diagnostic::from_compiler_error(&CompilerError::CodegenError(e))[0];
+    assert_eq!(diagnostic.help, Some("did you mean 'TRANSFER_FROM'?".to_string()));
+
+    // The misspelled constant suggests the defined constant
+    let e = Codegen::generate_macro_bytecode("TRANSFER_FROM", &contract).unwrap_err().remove(0);
+    assert_eq!(e.kind, CodegenErrorKind::MissingConstantDefinition("OWNER_SLTO".to_string()));
+    assert_eq!(e.help, Some("did you mean 'OWNER_SLOT'?".to_string()));
+}
+
+#[test]
+fn test_codegen_reports_every_error() {
+    let source = r#"
+    #define macro INNER() = takes(0) returns (0) {
+        [MISSING_INNER] sload
+    }
+
+    #define macro MAIN() = takes(0) returns (0) {
+        [MISSING] sload
+        INNER()
+        INNER()
+        UNKNOWN()
+        missing_label jump
+    }
+    "#;
+
+    let full_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(full_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, Some("".to_string()));
+    let mut contract = parser.parse().unwrap();
+    contract.derive_storage_pointers();
+
+    // Every error is reported once, in the order they are encountered
+    let errors = Codegen::generate_macro_bytecode("MAIN", &contract).unwrap_err();
+    assert_eq!(
+        errors.iter().map(|e| e.kind.clone()).collect::<Vec<CodegenErrorKind>>(),
+        vec![
+            CodegenErrorKind::MissingConstantDefinition("MISSING".to_string()),
+            CodegenErrorKind::MissingConstantDefinition("MISSING_INNER".to_string()),
+            CodegenErrorKind::InvalidMacroInvocation("UNKNOWN".to_string()),
+            CodegenErrorKind::UnmatchedJumpLabel,
+        ]
+    );
+
+    // The first error is returned when generating the main bytecode
+    assert_eq!(Codegen::generate_main_bytecode(&contract).unwrap_err(), errors[0]);
+}
+
+#[test]
+fn test_recovery_at_end_of_file() {
+    for (source, line) in [
+        ("#define function g(addr", "#define function g(addr"),
+        ("#define constant X =", "#define constant X ="),
+        ("#define macro MAIN() = takes(0) returns(0) {}\n#define macro", "#define macro"),
+        ("#define macro MAIN() = takes(0) returns(0) {\n    0x01", "\"EOF\""),
+    ] {
+        // The errors of definitions cut off by the end of the file are still formatted
+        let err = compile(source).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains(line), "{source:?} rendered as {message}");
+    }
+}
@@ -0,0 +1,88 @@
+use huff_codegen::{Codegen, ExpansionCache};
+use huff_lexer::Lexer;
+use huff_parser::Parser;
+use huff_utils::prelude::*;
+
+fn parse(source: &str) -> Contract {
+    let full_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(full_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    Parser::new(tokens, None).parse().unwrap()
+}
+
+#[test]
+fn test_reused_expansions_move_labels_and_jumps() {
+    let contract = parse(
+        r#"
+    #define macro REQUIRE() = takes(1) returns(0) {
+        ok jumpi
+        0x00 dup1 revert
+        ok:
+    }
+
+    #define macro SKIP(dest) = takes(0) returns(0) {
+        <dest> jump
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x01 REQUIRE()
+        0x02 REQUIRE()
+        SKIP(done) SKIP(done)
+        done:
+            stop
+    }
+    "#,
+    );
+
+    // Each invocation of `REQUIRE` jumps to its own label, and both skips to `done`
+    let mut cache = ExpansionCache::default();
+    let (bytecode, _) =
+        Codegen::generate_macro_bytecode_with_cache("MAIN", &contract, &mut cache).unwrap();
+    assert_eq!(bytecode, "600161000a57600080fd5b600261001557600080fd5b61001e5661001e565b00");
+
+    // The second invocations of `REQUIRE` and `SKIP` reuse the expansions of the first ones
+    assert_eq!(cache.hits, 2);
+
+    let source_map = Codegen::generate_source_map("MAIN", &contract).unwrap();
+    let jumps = source_map
+        .iter()
+        .filter(|m| matches!(m.relocation, Some(Relocation::JumpLabel(_))))
+        .map(|m| (m.offset, m.invocations.iter().map(|i| i.macro_name.as_str()).collect()))
+        .collect::<Vec<(usize, Vec<&str>)>>();
+    assert_eq!(
+        jumps,
+        [(2, vec!["REQUIRE"]), (13, vec!["REQUIRE"]), (22, vec!["SKIP"]), (26, vec!["SKIP"])]
+    );
+}
+
+#[test]
+fn test_expansions_are_reused_through_the_same_invocations() {
+    let hits = |main: &str| {
+        let contract = parse(&format!(
+            r#"
+    #define macro INNER(value) = takes(0) returns(0) {{
+        <value> pop
+    }}
+
+    #define macro OUTER(value) = takes(0) returns(0) {{
+        INNER(<value>)
+    }}
+
+    #define macro MAIN() = takes(0) returns(0) {{
+        {}
+    }}
+    "#,
+            main
+        ));
+        let mut cache = ExpansionCache::default();
+        Codegen::generate_macro_bytecode_with_cache("MAIN", &contract, &mut cache).unwrap();
+        cache.hits
+    };
+
+    // The second `OUTER` is reused along with the `INNER` it expands
+    assert_eq!(hits("OUTER(0x01) OUTER(0x01)"), 1);
+
+    // `INNER` is passed the argument of `OUTER`, so it's reached through another chain of
+    // invocations when the argument differs
+    assert_eq!(hits("OUTER(0x01) OUTER(0x02)"), 0);
+}
@@ -1,28 +1,25 @@
-use std::{path::PathBuf, sync::Arc};
+use std::path::PathBuf;
 
 use huff_core::Compiler;
 use huff_utils::prelude::{CompilerError, OutputLocation, UnpackError};
 
 #[test]
 fn test_get_outputs_no_output() {
-    let compiler: Compiler = Compiler::new(Arc::new(vec![]), None, None, false);
+    let compiler = Compiler::builder().build();
     let ol: OutputLocation = compiler.get_outputs();
     assert_eq!(ol, OutputLocation::default());
 }
 
 #[test]
 fn test_get_outputs_with_output() {
-    let compiler: Compiler =
-        Compiler::new(Arc::new(vec![]), Some("./test_out/".to_string()), None, false);
+    let compiler = Compiler::builder().output("./test_out/").build();
     let ol: OutputLocation = compiler.get_outputs();
     assert_eq!(ol, OutputLocation("./test_out/".to_string()));
 }
 
 #[test]
 fn test_transform_paths() {
-    let _compiler: Compiler =
-        Compiler::new(Arc::new(vec![]), Some("./test_out/".to_string()), None, false);
-    let path_bufs: Result<Vec<PathBuf>, CompilerError<'_>> = Compiler::transform_paths(&vec![
+    let path_bufs: Result<Vec<PathBuf>, CompilerError> = Compiler::transform_paths(&vec![
         "../huff-examples/erc20/contracts/ERC20.huff".to_string(),
         "../huff-examples/erc20/contracts/utils/".to_string(),
     ]);
@@ -54,9 +51,7 @@ fn test_transform_paths() {
 
 #[test]
 fn test_transform_paths_non_huff() {
-    let _compiler: Compiler =
-        Compiler::new(Arc::new(vec![]), Some("./test_out/".to_string()), None, false);
-    let path_bufs: Result<Vec<PathBuf>, CompilerError<'_>> =
+    let path_bufs: Result<Vec<PathBuf>, CompilerError> =
         Compiler::transform_paths(&vec!["./ERC20.txt".to_string()]);
     assert!(path_bufs.is_err());
     match path_bufs {
@@ -71,9 +66,7 @@ fn test_transform_paths_non_huff() {
 
 #[test]
 fn test_transform_paths_no_dir() {
-    let _compiler: Compiler =
-        Compiler::new(Arc::new(vec![]), Some("./test_out/".to_string()), None, false);
-    let path_bufs: Result<Vec<PathBuf>, CompilerError<'_>> =
+    let path_bufs: Result<Vec<PathBuf>, CompilerError> =
         Compiler::transform_paths(&vec!["./examples/random_dir/".to_string()]);
     assert!(path_bufs.is_err());
     match path_bufs {
@@ -0,0 +1,66 @@
+use std::{fs, sync::Arc};
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+fn recurse(path: &std::path::Path) -> Arc<FileSource> {
+    let file = Arc::new(FileSource {
+        path: path.to_string_lossy().to_string(),
+        source: Some(fs::read_to_string(path).unwrap()),
+        ..Default::default()
+    });
+    Compiler::recurse_deps(file).unwrap()
+}
+
+#[test]
+fn test_flattens_includes() {
+    let dir = std::env::temp_dir().join(format!("huff_flatten_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+
+    // `Shared.huff` is included twice, and `get` is defined twice
+    fs::write(
+        dir.join("Main.huff"),
+        "/* Main contract */\n#include \"./A.huff\"\n// The shared helpers\n#include \"./Shared.huff\"\n\n#define function get() view returns (uint256)\n\n#define macro MAIN() = takes(0) returns(0) {\n    ADD_ONE()   // [x]\n    [ONE] add pop\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("A.huff"),
+        "#include \"./Shared.huff\"\n\n/// Adds one\n#define macro ADD_ONE() = takes(0) returns(1) {\n    [ONE] ONE_MORE()\n}\n\n#define function get() view returns (uint256)\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("Shared.huff"),
+        "// One\n#define constant ONE = 0x01\n\n#define macro ONE_MORE() = takes(1) returns(1) {\n    [ONE] add\n}\n",
+    )
+    .unwrap();
+
+    let flattened = Compiler::flatten(recurse(&dir.join("Main.huff"))).unwrap();
+    assert_eq!(
+        flattened,
+        format!(
+            "// {}\n\n/* Main contract */\n\n// The shared helpers\n\n#define function get() view returns (uint256)\n\n#define macro MAIN() = takes(0) returns(0) {{\n    ADD_ONE()   // [x]\n    [ONE] add pop\n}}\n\n// {}\n\n/// Adds one\n#define macro ADD_ONE() = takes(0) returns(1) {{\n    [ONE] ONE_MORE()\n}}\n\n// {}\n\n// One\n#define constant ONE = 0x01\n\n#define macro ONE_MORE() = takes(1) returns(1) {{\n    [ONE] add\n}}\n",
+            path("Main.huff"),
+            path("A.huff"),
+            path("Shared.huff")
+        )
+    );
+
+    // The flattened source compiles on its own, to the same bytecode
+    fs::write(dir.join("Flat.huff"), &flattened).unwrap();
+    let original = Compiler::default().gen_artifact(recurse(&dir.join("Main.huff"))).unwrap();
+    let flat = Compiler::default().gen_artifact(recurse(&dir.join("Flat.huff"))).unwrap();
+    assert_eq!(flat.runtime, original.runtime);
+    assert_eq!(flat.abi, original.abi);
+
+    // Namespaced includes can't be inlined
+    fs::write(dir.join("Namespaced.huff"), "#include \"./Shared.huff\" as Shared\n").unwrap();
+    assert_eq!(
+        Compiler::flatten(recurse(&dir.join("Namespaced.huff"))),
+        Err(CompilerError::FileUnpackError(UnpackError::UnflattenableInclude(path(
+            "Namespaced.huff"
+        ))))
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,49 @@
+use std::fs;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_exports_foundry_artifacts() {
+    let dir = std::env::temp_dir().join(format!("huff_foundry_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(dir.join("src")).unwrap();
+    let source = dir.join("src").join("Counter.huff");
+    fs::write(
+        &source,
+        "#define function get() view returns (uint256)\n#define immutable OWNER\n#define macro CONSTRUCTOR() = takes(0) returns(0) {\n    caller __SET_IMMUTABLE(OWNER)\n}\n#define macro MAIN() = takes(0) returns(0) {\n    [OWNER] pop\n}\n",
+    )
+    .unwrap();
+
+    // The artifacts forge wrote to `out/` are kept
+    let out = dir.join("out").to_string_lossy().to_string();
+    fs::create_dir_all(dir.join("out").join("Test.sol")).unwrap();
+    fs::write(dir.join("out").join("Test.sol").join("Test.json"), "{}").unwrap();
+
+    let compiler = Compiler::builder()
+        .source(source.to_string_lossy())
+        .output(&out)
+        .artifact_format(ArtifactFormat::Foundry)
+        .build();
+    let results = compiler.execute().unwrap();
+    let artifact = results[0].artifact().unwrap();
+    assert!(dir.join("out").join("Test.sol").join("Test.json").exists());
+
+    // Artifacts are written to `<OUT>/<FILE NAME>/<CONTRACT NAME>.json`
+    let location = OutputLocation(out.clone());
+    let path = Compiler::artifact_path(artifact, &location, ArtifactFormat::Foundry);
+    assert_eq!(path, format!("{}/Counter.huff/Counter.json", out));
+    let exported: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+
+    assert_eq!(exported["abi"][0]["type"], "constructor");
+    assert_eq!(exported["abi"][1]["name"], "get");
+    assert_eq!(exported["bytecode"]["object"], format!("0x{}", artifact.bytecode));
+    assert_eq!(exported["deployedBytecode"]["object"], format!("0x{}", artifact.runtime));
+    assert_eq!(
+        exported["deployedBytecode"]["immutableReferences"]["OWNER"],
+        serde_json::json!([{ "start": 1, "length": 32 }])
+    );
+    assert_eq!(exported["methodIdentifiers"], serde_json::json!({ "get()": "6d4ce63c" }));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
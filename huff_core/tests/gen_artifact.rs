@@ -6,13 +6,13 @@ use huff_utils::prelude::*;
 #[test]
 fn test_missing_constructor() {
     let source = r#"
-    #define macro MINT() = takes(0) returns (0) {
+    #define macro MINT() = takes(0) returns (3) {
         0x04 calldataload   // [to]
         0x00                // [from (0x00), to]
         0x24 calldataload   // [value, from, to]
     }
 
-    #define macro MAIN() = takes(0) returns (0) {
+    #define macro MAIN() = takes(0) returns (4) {
         0x00 calldataload 0xE0 shr
         dup1 0x40c10f19 eq mints jumpi
 
@@ -31,7 +31,7 @@ fn test_missing_constructor() {
     };
 
     // Instantiate a new compiler
-    let compiler = Compiler::new(Arc::new(vec![]), None, None, false);
+    let compiler = Compiler::builder().build();
 
     // Generate the compile artifact
     let arc_source = Arc::new(full_source);
@@ -76,7 +76,7 @@ fn test_missing_constructor_with_inputs() {
     };
 
     // Instantiate a new compiler
-    let compiler = Compiler::new(Arc::new(vec![]), None, Some(vec!["0".to_string()]), false);
+    let compiler = Compiler::builder().constructor_args(vec!["0".to_string()]).build();
 
     // Generate the compile artifact
     let arc_source = Arc::new(full_source);
@@ -88,7 +88,8 @@ fn test_missing_constructor_with_inputs() {
                 CompilerError::CodegenError(CodegenError {
                     kind: CodegenErrorKind::MissingMacroDefinition("CONSTRUCTOR".to_string()),
                     span: AstSpan(vec![Span { start: 0, end: 0, file: Some(arc_source) }]),
-                    token: None
+                    token: None,
+                    help: None
                 })
             )
         }
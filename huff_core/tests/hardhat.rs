@@ -0,0 +1,65 @@
+use std::fs;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_exports_hardhat_artifacts() {
+    let dir = std::env::temp_dir().join(format!("huff_hardhat_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(dir.join("src")).unwrap();
+    let source = dir.join("src").join("Counter.huff");
+    fs::write(
+        &source,
+        "#define function get() view returns (uint256)\n#define event Bumped(uint256)\n#define macro MAIN() = takes(0) returns(0) {\n    __LINK(Math) pop\n}\n",
+    )
+    .unwrap();
+    let output = dir.join("artifacts").to_string_lossy().to_string();
+
+    let compiler = Compiler::builder()
+        .source(source.to_string_lossy())
+        .output(&output)
+        .artifact_format(ArtifactFormat::Hardhat)
+        .build();
+    let results = compiler.execute().unwrap();
+    let artifact = results[0].artifact().unwrap();
+
+    // Artifacts are written to `<OUTPUT>/<SOURCE NAME>/<CONTRACT NAME>.json`
+    let source_name = source.to_string_lossy().to_string();
+    let location = OutputLocation(output.clone());
+    let path = Compiler::artifact_path(artifact, &location, ArtifactFormat::Hardhat);
+    assert_eq!(path, format!("{}/{}/Counter.json", output, source_name.trim_start_matches('/')));
+    let exported: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+
+    assert_eq!(exported["_format"], "hh-sol-artifact-1");
+    assert_eq!(exported["contractName"], "Counter");
+    assert_eq!(exported["sourceName"], source_name.as_str());
+    assert_eq!(
+        exported["abi"],
+        serde_json::json!([
+            {
+                "type": "function",
+                "name": "get",
+                "inputs": [],
+                "outputs": [{ "name": "", "type": "uint256" }],
+                "stateMutability": "view",
+            },
+            {
+                "type": "event",
+                "name": "Bumped",
+                "inputs": [{ "name": "", "type": "uint256", "indexed": false }],
+                "anonymous": false,
+            },
+        ])
+    );
+    assert_eq!(exported["bytecode"], format!("0x{}", artifact.bytecode));
+    assert_eq!(exported["deployedBytecode"], format!("0x{}", artifact.runtime));
+
+    // Libraries are linked by name, under the source name of the contract linking them
+    assert_eq!(
+        exported["deployedLinkReferences"][source_name.as_str()]["Math"],
+        serde_json::json!([{ "start": 1, "length": 20 }])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
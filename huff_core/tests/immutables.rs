@@ -0,0 +1,86 @@
+mod common;
+
+use huff_utils::prelude::*;
+
+use common::compile;
+
+const SOURCE: &str = r#"#define immutable OWNER
+#define immutable SCALE
+
+#define macro PUSH_ARG(value) = takes(0) returns(1) {
+    <value>
+}
+
+#define macro CONSTRUCTOR() = takes(0) returns(0) {
+    caller __SET_IMMUTABLE(OWNER)
+    0x03 __SET_IMMUTABLE(SCALE)
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    [OWNER] 0x00 mstore
+    PUSH_ARG([SCALE]) 0x20 mstore
+    0x40 0x00 return
+}
+"#;
+
+#[test]
+fn test_immutable_references() {
+    let artifact = compile(SOURCE).unwrap();
+
+    // Immutables are pushed as zeroed placeholders, also when passed as macro arguments
+    let placeholder = format!("7f{}", "00".repeat(32));
+    assert_eq!(artifact.runtime, format!("{}600052{}60205260406000f3", placeholder, placeholder));
+    let reference = |start| vec![ImmutableReference { start, length: 32 }];
+    assert_eq!(
+        artifact.immutable_references,
+        [("OWNER".to_string(), reference(1)), ("SCALE".to_string(), reference(37))].into()
+    );
+    let json = serde_json::to_value(&artifact).unwrap();
+    assert_eq!(json["immutableReferences"]["SCALE"][0]["start"], 37);
+}
+
+#[test]
+fn test_constructor_patches_immutables() {
+    let artifact = compile(SOURCE).unwrap();
+
+    // The constructor stores the values at the placeholders, then the bootstrap copies the
+    // runtime bytecode around them, from offset 0x2e
+    let constructor = "3361000152600361002552";
+    let copies = ["61000161002e61000039", "61000461004f61002139", "61000861007361004539"];
+    let bootstrap = format!("{}61004d3df3", copies.join(""));
+    assert_eq!(artifact.bytecode, format!("{}{}{}", constructor, bootstrap, artifact.runtime));
+}
+
+#[test]
+fn test_set_immutable_errors() {
+    let source = r#"#define immutable OWNER
+
+#define macro MAIN() = takes(0) returns(0) {
+    caller __SET_IMMUTABLE(OWNER)
+}
+"#;
+    match compile(source) {
+        Err(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.kind, CodegenErrorKind::ImmutableSetInRuntime("OWNER".to_string()))
+        }
+        r => panic!("Expected an immutable set in the runtime code, got {:?}", r),
+    }
+
+    let source = r#"#define immutable OWNER
+
+#define macro CONSTRUCTOR() = takes(0) returns(0) {
+    caller __SET_IMMUTABLE(OWNR)
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    [OWNER] pop
+}
+"#;
+    match compile(source) {
+        Err(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.kind, CodegenErrorKind::MissingConstantDefinition("OWNR".to_string()));
+            assert_eq!(e.help.as_deref(), Some("did you mean 'OWNER'?"));
+        }
+        r => panic!("Expected a missing immutable, got {:?}", r),
+    }
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+use common::source_file;
+
+#[test]
+fn test_generates_solidity_interface() {
+    let source = r#"
+/// @notice Emitted when the value changes
+/// @param value The new value
+#define event ValueSet(address indexed setter, uint256 value)
+#define error Unauthorized(address caller)
+
+/// @notice Sets the value
+/// @dev Only the owner can set it
+/// @param value The new value
+#define function set(uint256 value) nonpayable returns ()
+#define function get() view returns (uint256)
+#define function batch(bytes data, uint256[] amounts) payable returns (bytes[])
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x01 pop
+}
+"#;
+    let artifact =
+        Compiler::default().gen_artifact(source_file("./src/Store.huff", source)).unwrap();
+
+    let interface = SolidityInterface::new(&artifact);
+    assert_eq!(interface.name, "IStore");
+    assert_eq!(interface.file_name, "IStore.sol");
+    assert_eq!(
+        interface.source,
+        r#"// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.0;
+
+/// @title IStore
+/// @notice The interface of the `Store` contract, generated by `huffc interface` from "./src/Store.huff".
+interface IStore {
+    /// @notice Emitted when the value changes
+    /// @param value The new value
+    event ValueSet(address indexed setter, uint256 value);
+
+    error Unauthorized(address caller);
+
+    function batch(bytes calldata data, uint256[] calldata amounts) external payable returns (bytes[] memory);
+
+    function get() external view returns (uint256);
+
+    /// @notice Sets the value
+    /// @dev Only the owner can set it
+    /// @param value The new value
+    function set(uint256 value) external;
+}
+"#
+    );
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use huff_utils::prelude::*;
+
+use common::compile;
+
+/// The lexical error a source fails to compile with, and its rendered message
+fn lexical_error(source: &str) -> (LexicalError, String) {
+    match compile(source) {
+        Err(CompilerError::LexicalError(e)) => {
+            let message = CompilerError::LexicalError(e.clone()).to_string();
+            (e, message)
+        }
+        res => panic!("Expected a lexical error, got {:?}", res.map(|a| a.runtime)),
+    }
+}
+
+#[test]
+fn test_hex_literal_too_long() {
+    let literal = format!("0x{}", "ab".repeat(33));
+    let source =
+        format!("#define macro MAIN() = takes(0) returns(0) {{\n    {} pop\n}}\n", literal);
+    let (e, message) = lexical_error(&source);
+    assert_eq!(e.kind, LexicalErrorKind::LiteralTooLong(literal));
+    assert!(message.contains("Literal Exceeds 32 Bytes"));
+    assert_eq!(&source[e.span.start..e.span.end], "ab".repeat(33));
+}
+
+#[test]
+fn test_lexical_errors_are_all_reported() {
+    let source = format!(
+        "#define macro MAIN() = takes(0) returns(0) {{\n    0x{} 0x{}\n}}\n",
+        "ab".repeat(33),
+        "cd".repeat(40)
+    );
+    match compile(&source) {
+        Err(CompilerError::FailedCompiles(errors)) => {
+            assert_eq!(errors.len(), 2);
+            assert!(errors.iter().all(|e| matches!(
+                e,
+                CompilerError::LexicalError(LexicalError {
+                    kind: LexicalErrorKind::LiteralTooLong(_),
+                    ..
+                })
+            )));
+        }
+        res => panic!("Expected two lexical errors, got {:?}", res.map(|a| a.runtime)),
+    }
+}
+
+#[test]
+fn test_binary_literal_errors() {
+    let source = "#define macro MAIN() = takes(0) returns(0) {\n    0b pop\n}\n";
+    let (e, message) = lexical_error(source);
+    assert_eq!(e.kind, LexicalErrorKind::EmptyLiteral("0b".to_string()));
+    assert!(message.contains("Literal Has No Digits"));
+
+    let literal = format!("0b1{}", "0".repeat(256));
+    let source =
+        format!("#define macro MAIN() = takes(0) returns(0) {{\n    {} pop\n}}\n", literal);
+    let (e, message) = lexical_error(&source);
+    assert_eq!(e.kind, LexicalErrorKind::LiteralTooLong(literal));
+    assert!(message.contains("Literal Exceeds 32 Bytes"));
+}
+
+#[test]
+fn test_decimal_literal_too_long() {
+    let main = |literal: &str| {
+        format!("#define macro MAIN() = takes(0) returns(0) {{\n    {} pop\n}}\n", literal)
+    };
+
+    // 2^256 is one past the largest word
+    let literal = "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+    let (e, message) = lexical_error(&main(literal));
+    assert_eq!(e.kind, LexicalErrorKind::LiteralTooLong(literal.to_string()));
+    assert!(message.contains("Literal Exceeds 32 Bytes"));
+
+    // The largest word still compiles
+    let literal = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+    assert_eq!(compile(&main(literal)).unwrap().runtime, format!("7f{}50", "ff".repeat(32)));
+}
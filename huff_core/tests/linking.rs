@@ -0,0 +1,70 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use huff_utils::prelude::*;
+
+use common::compile;
+
+const SOURCE: &str = r#"#define macro CONSTRUCTOR() = takes(0) returns(0) {
+    __LINK(Registry) pop
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    __LINK(Math) pop
+    loop:
+        __LINK(Registry) pop
+        loop jump
+}
+"#;
+
+#[test]
+fn test_link_references() {
+    let artifact = compile(SOURCE).unwrap();
+
+    let math = link_placeholder("Math");
+    let registry = link_placeholder("Registry");
+    assert_eq!(math.len(), 40);
+    assert!(math.starts_with("__$") && math.ends_with("$__"));
+    assert_eq!(artifact.runtime, format!("73{}505b73{}5061001656", math, registry));
+
+    let reference = |start| vec![LinkReference { start, length: 20 }];
+    assert_eq!(
+        artifact.runtime_link_references,
+        [("Math".to_string(), reference(1)), ("Registry".to_string(), reference(24))].into()
+    );
+    assert_eq!(artifact.link_references["Registry"][0], LinkReference { start: 1, length: 20 });
+    assert!(artifact.link_references.contains_key("Math"));
+
+    let json = serde_json::to_value(&artifact).unwrap();
+    assert_eq!(json["deployedLinkReferences"]["Registry"][0]["start"], 24);
+    assert_eq!(json["linkReferences"]["Registry"][0]["length"], 20);
+}
+
+#[test]
+fn test_link_artifact() {
+    let mut artifact = compile(SOURCE).unwrap();
+    let math = "00000000000000000000000000000000000000aA";
+    artifact.link(&BTreeMap::from([("Math".to_string(), math.to_string())]));
+
+    // Only the linked library is replaced, the other keeps its placeholder and references
+    let registry = link_placeholder("Registry");
+    assert_eq!(artifact.runtime, format!("73{}505b73{}5061001656", math.to_lowercase(), registry));
+    assert!(!artifact.runtime_link_references.contains_key("Math"));
+    assert!(!artifact.link_references.contains_key("Math"));
+    assert!(artifact.runtime_link_references.contains_key("Registry"));
+}
+
+#[test]
+fn test_link_without_library() {
+    let source = r#"#define macro MAIN() = takes(0) returns(0) {
+    __LINK() pop
+}
+"#;
+    match compile(source) {
+        Err(CompilerError::ParserError(e)) => {
+            assert!(matches!(e.kind, ParserErrorKind::InvalidArgs(_)))
+        }
+        r => panic!("Expected invalid arguments, got {:?}", r),
+    }
+}
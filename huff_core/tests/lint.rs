@@ -0,0 +1,89 @@
+mod common;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+use common::source_file;
+
+const SOURCE: &str = r#"#define function transfer(address,uint256) nonpayable returns ()
+#define constant TRANSFER = 0xa9059cbb
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x00 calldataload 0xe0 shr
+    0xa9059cbb eq transfer jumpi
+    0x00 0x00 REVERT
+    0x01 0x02 add // unreachable
+        pop
+    transfer:
+        stop
+}
+"#;
+
+#[test]
+fn test_lint_without_codegen() {
+    let mut compiler = Compiler { case_insensitive_opcodes: true, ..Default::default() };
+    compiler.lint_levels.set("unreachable-code", LintLevel::Deny).unwrap();
+    let (reported, denied) = compiler.lint(source_file("Lint.huff", SOURCE)).unwrap();
+
+    assert_eq!(
+        reported.iter().map(|w| w.kind.lint()).collect::<Vec<_>>(),
+        vec!["opcode-case", "unused-constant"]
+    );
+    assert_eq!(denied.len(), 1);
+    assert_eq!(
+        denied[0].kind,
+        WarningKind::UnreachableCode("MAIN".to_string(), "revert".to_string())
+    );
+
+    // The selector lint is opt-in
+    compiler.lint_levels.set("magic-selector", LintLevel::Warn).unwrap();
+    let (reported, _) = compiler.lint(source_file("Lint.huff", SOURCE)).unwrap();
+    assert_eq!(reported.last().map(|w| w.kind.lint()), Some("magic-selector"));
+}
+
+#[test]
+fn test_apply_lint_fixes() {
+    let mut compiler = Compiler { case_insensitive_opcodes: true, ..Default::default() };
+    compiler.lint_levels.set("magic-selector", LintLevel::Warn).unwrap();
+    let (reported, _) = compiler.lint(source_file("Lint.huff", SOURCE)).unwrap();
+    let fixes = reported.iter().filter_map(|w| w.fix.as_ref()).collect::<Vec<_>>();
+    assert_eq!(fixes.len(), 3);
+
+    // Unreachable lines are removed with their comments
+    let fixed = apply_fixes(SOURCE, &fixes);
+    assert_eq!(
+        fixed,
+        SOURCE
+            .replace("REVERT", "revert")
+            .replace("0xa9059cbb eq", "[TRANSFER] eq")
+            .replace("    0x01 0x02 add // unreachable\n        pop\n", "")
+    );
+    let (reported, _) = compiler.lint(source_file("Lint.huff", &fixed)).unwrap();
+    assert!(reported.iter().all(|w| w.fix.is_none()));
+}
+
+#[test]
+fn test_apply_alias_fixes() {
+    let source = "#define alias fmp = 0x40 mload\n#define alias owner = [OWNER] sload\n#define constant OWNER = FREE_STORAGE_POINTER()\n\n#define macro MAIN() = takes(0) returns(0) {\n    0x40\n    mload [OWNER] sload eq\n    0x40 /* free memory */ mload pop pop\n}\n";
+    let (reported, _) = Compiler::default().lint(source_file("Lint.huff", source)).unwrap();
+    assert_eq!(
+        reported.iter().map(|w| w.kind.lint()).collect::<Vec<_>>(),
+        vec!["unused-alias", "unused-alias", "inlined-alias", "inlined-alias", "inlined-alias"]
+    );
+
+    // Statements separated by comments are left alone
+    let fixes = reported.iter().filter_map(|w| w.fix.as_ref()).collect::<Vec<_>>();
+    assert_eq!(fixes.len(), 2);
+    let fixed = apply_fixes(source, &fixes);
+    assert_eq!(fixed, source.replace("0x40\n    mload [OWNER] sload eq", "fmp owner eq"));
+}
+
+#[test]
+fn test_repeat_counts_are_used() {
+    let source = "#define constant WORDS = 0x03\n#define constant UNUSED = 0x04\n\n#define macro MAIN() = takes(0) returns(0) {\n    __REPEAT([WORDS]) { 0x00 mload pop }\n}\n";
+    let (reported, _) = Compiler::default().lint(source_file("Lint.huff", source)).unwrap();
+    assert_eq!(
+        reported.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(),
+        vec![WarningKind::UnusedConstant("UNUSED".to_string())]
+    );
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+use common::{codegen_errors, compile, compile_with};
+
+#[test]
+fn test_passes_opcode_arguments() {
+    let source = r#"#define macro OP(op) = takes(2) returns(1) {
+    <op>
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x01 0x02 OP(add)
+}
+"#;
+    assert_eq!(compile(source).unwrap().runtime, "6001600201");
+}
+
+#[test]
+fn test_bubbles_arguments_through_renamed_parameters() {
+    let source = r#"#define macro INNER(y) = takes(0) returns(1) {
+    <y>
+}
+
+#define macro OUTER(x) = takes(0) returns(1) {
+    INNER(<x>)
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    OUTER(0x42) pop
+}
+"#;
+    assert_eq!(compile(source).unwrap().runtime, "604250");
+}
+
+#[test]
+fn test_reports_argument_count_mismatch() {
+    let source = r#"#define macro STORE(slot, value) = takes(0) returns(0) {
+    <value> <slot> sstore
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    STORE(0x01)
+}
+"#;
+    assert_eq!(
+        codegen_errors(source),
+        vec![CodegenErrorKind::MacroArgCountMismatch("STORE".to_string())]
+    );
+}
+
+#[test]
+fn test_reports_invalid_ident_arguments() {
+    let source = r#"#define macro JUMP_TO(dest) = takes(0) returns(0) {
+    <dest> jump
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    JUMP_TO(fail)
+    fails:
+        0x00 dup1 revert
+}
+"#;
+    assert_eq!(
+        codegen_errors(source),
+        vec![CodegenErrorKind::InvalidMacroArgument("fail".to_string())]
+    );
+}
+
+#[test]
+fn test_reports_undeclared_arg_calls() {
+    let source = r#"#define macro LOAD(slot) = takes(0) returns(1) {
+    <offset> sload
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    LOAD(0x01) pop
+}
+"#;
+    assert_eq!(
+        codegen_errors(source),
+        vec![CodegenErrorKind::UndeclaredArgCall("offset".to_string())]
+    );
+
+    // The arg call is reported by its error alone, not by the undeclared-arg-call lint too
+    let compiler = Compiler::default();
+    assert!(compile_with(&compiler, source).is_err());
+    assert_eq!(
+        compiler.get_warnings().into_iter().map(|w| w.kind).collect::<Vec<_>>(),
+        vec![WarningKind::UnusedMacroParameter("LOAD".to_string(), "slot".to_string())]
+    );
+}
+
+#[test]
+fn test_passes_parameter_defaults() {
+    let source = r#"#define macro REQUIRE(err = fail) = takes(1) returns(0) {
+    iszero <err> jumpi
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x01 REQUIRE()
+    0x01 REQUIRE(done)
+    done:
+        stop
+    fail:
+        0x00 dup1 revert
+}
+"#;
+    assert_eq!(compile(source).unwrap().runtime, "6001156100105760011561000e575b005b600080fd");
+}
@@ -0,0 +1,125 @@
+use std::{fs, sync::Arc};
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+const LIBRARY: &str = "#define constant OWNER = FREE_STORAGE_POINTER()\n#define constant FEE = 0x1e\n#define macro ONLY_OWNER() = takes(0) returns(0) {\n    caller [OWNER] sload eq authorized jumpi\n    0x00 0x00 revert\n    authorized:\n}\n";
+
+#[test]
+fn test_package_manifest() {
+    let dir = std::env::temp_dir().join(format!("huff_manifest_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let library = dir.join("Ownable.huff");
+    fs::write(&library, LIBRARY).unwrap();
+
+    let file = Arc::new(FileSource {
+        path: library.to_string_lossy().to_string(),
+        source: Some(LIBRARY.to_string()),
+        ..Default::default()
+    });
+    let manifest = Compiler::default().package(file).unwrap();
+    assert_eq!(
+        manifest,
+        SymbolManifest {
+            name: "Ownable".to_string(),
+            macros: vec![MacroSignature {
+                name: "ONLY_OWNER".to_string(),
+                parameters: vec![],
+                takes: 0,
+                returns: 0,
+            }],
+            constants: vec![ConstantSignature {
+                name: "FEE".to_string(),
+                value: "0x1e".to_string()
+            }],
+            storage: vec![ConstantSignature {
+                name: "OWNER".to_string(),
+                value: "FREE_STORAGE_POINTER()".to_string(),
+            }],
+        }
+    );
+
+    // Manifests round trip through json
+    let path = dir.join("Ownable.manifest.json").to_string_lossy().to_string();
+    manifest.export(&path).unwrap();
+    assert_eq!(SymbolManifest::import(&path).unwrap(), manifest);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_breaking_library_changes() {
+    let dir = std::env::temp_dir().join(format!("huff_manifest_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let library = dir.join("Ownable.huff");
+    fs::write(&library, LIBRARY).unwrap();
+    let main = dir.join("Main.huff");
+    fs::write(
+        &main,
+        "#include \"./Ownable.huff\"\n#define macro MAIN() = takes(0) returns(0) {\n    ONLY_OWNER()\n    [FEE] [OWNER] sstore\n}\n",
+    )
+    .unwrap();
+    let file = Arc::new(FileSource {
+        path: library.to_string_lossy().to_string(),
+        source: Some(LIBRARY.to_string()),
+        ..Default::default()
+    });
+    let manifest = Compiler::default().package(file).unwrap();
+
+    let compiler =
+        Compiler::builder().source(main.to_string_lossy()).manifests(vec![manifest]).build();
+
+    // The library matches its manifest
+    let results = compiler.execute().unwrap();
+    assert!(results[0].artifact().is_some());
+
+    // Changing the stack effects of a macro or the value of a constant breaks the consumer
+    fs::write(&library, LIBRARY.replace("takes(0)", "takes(1)").replace("0x1e", "0x32")).unwrap();
+    let results = compiler.execute().unwrap();
+    match results[0].error().map(|e| e.as_ref()) {
+        Some(CompilerError::FailedCompiles(errors)) => {
+            let errors = errors
+                .iter()
+                .map(|e| match e {
+                    CompilerError::CodegenError(e) => (e.kind.clone(), e.help.clone().unwrap()),
+                    e => panic!("Expected a codegen error, got {:?}", e),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                errors,
+                vec![
+                    (
+                        CodegenErrorKind::ManifestMismatch("ONLY_OWNER".to_string()),
+                        "\"ONLY_OWNER\" of library \"Ownable\" was packaged as \"ONLY_OWNER() = takes (0) returns (0)\", now defined as \"ONLY_OWNER() = takes (1) returns (0)\"".to_string()
+                    ),
+                    (
+                        CodegenErrorKind::ManifestMismatch("FEE".to_string()),
+                        "\"FEE\" of library \"Ownable\" was packaged with value 0x1e, now 0x32"
+                            .to_string()
+                    ),
+                ]
+            );
+        }
+        e => panic!("Expected manifest mismatches, got {:?}", e),
+    }
+
+    // Removed symbols break the consumer too
+    fs::write(&library, LIBRARY.replace("#define constant OWNER = FREE_STORAGE_POINTER()\n", ""))
+        .unwrap();
+    let results = compiler.execute().unwrap();
+    match results[0].error().map(|e| e.as_ref()) {
+        Some(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.kind, CodegenErrorKind::ManifestMismatch("OWNER".to_string()));
+            assert_eq!(
+                e.help,
+                Some(
+                    "\"OWNER\" of library \"Ownable\" was packaged with value FREE_STORAGE_POINTER(), now removed"
+                        .to_string()
+                )
+            );
+        }
+        e => panic!("Expected a manifest mismatch, got {:?}", e),
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
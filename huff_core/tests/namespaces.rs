@@ -0,0 +1,64 @@
+use std::{fs, sync::Arc};
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+fn compile(main: &std::path::Path) -> Result<Artifact, Arc<CompilerError>> {
+    let file = Arc::new(FileSource {
+        path: main.to_string_lossy().to_string(),
+        source: Some(fs::read_to_string(main).unwrap()),
+        ..Default::default()
+    });
+    let file = Compiler::recurse_deps(file)?;
+    Compiler::default().gen_artifact(file).map_err(Arc::new)
+}
+
+#[test]
+fn test_namespaced_includes() {
+    let dir = std::env::temp_dir().join(format!("huff_namespaces_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+
+    // Both libraries define `ADD` and `FEE`, referencing their own definitions unqualified
+    fs::write(
+        dir.join("Math.huff"),
+        "#define constant FEE = 0x02\n#define macro ADD() = takes(2) returns(1) {\n    INNER() [FEE] add\n}\n#define macro INNER() = takes(2) returns(1) {\n    add\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("Safe.huff"),
+        "#include \"./Checks.huff\"\n#define constant FEE = 0x03\n#define macro ADD() = takes(2) returns(1) {\n    [FEE] pop CHECKED_ADD()\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("Checks.huff"),
+        "#define macro CHECKED_ADD() = takes(2) returns(1) {\n    dup2 add swap1 dup2 lt overflow jumpi\n    overflow:\n}\n",
+    )
+    .unwrap();
+    let main = dir.join("Main.huff");
+    fs::write(
+        &main,
+        "#include \"./Math.huff\" as Math\n#include \"./Safe.huff\" as Safe\n#define constant FEE = 0x01\n#define macro MAIN() = takes(0) returns(0) {\n    [Math.FEE] [FEE] Math.ADD() [Safe.FEE] Safe.ADD() pop\n}\n",
+    )
+    .unwrap();
+
+    // The files included without a namespace, like `Checks.huff`, stay global
+    let artifact = compile(&main).unwrap();
+    assert_eq!(artifact.runtime, "600260010160020160036003508101908110610016575b50");
+
+    // Definitions of a namespace are only reachable through its name
+    let unqualified = dir.join("Unqualified.huff");
+    fs::write(
+        &unqualified,
+        "#include \"./Math.huff\" as Math\n#define macro MAIN() = takes(0) returns(0) {\n    0x01 0x02 ADD() pop\n}\n",
+    )
+    .unwrap();
+    let err = compile(&unqualified).unwrap_err();
+    match err.as_ref() {
+        CompilerError::CodegenError(e) => {
+            assert_eq!(e.kind, CodegenErrorKind::InvalidMacroInvocation("ADD".to_string()))
+        }
+        e => panic!("Expected a codegen error, got {:?}", e),
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+use common::source_file;
+
+const SOURCE: &str = r#"/// @notice Transfers tokens
+/// @dev Reverts if the balance is too low
+/// @param to The recipient
+#define function transfer(address to, uint256 amount) nonpayable returns ()
+
+/// @notice A transfer happened
+#define event Transfer(address indexed from, address indexed to, uint256)
+
+/// @notice Does nothing
+#define macro MAIN() = takes(0) returns(0) {
+    stop
+}
+"#;
+
+fn artifact(source: &str) -> Artifact {
+    Compiler::default().gen_artifact(source_file("Token.huff", source)).unwrap()
+}
+
+#[test]
+fn test_userdoc_and_devdoc() {
+    let artifact = artifact(SOURCE);
+    let json = serde_json::to_value(&artifact).unwrap();
+    assert_eq!(
+        json["userdoc"],
+        serde_json::json!({
+            "kind": "user",
+            "methods": { "transfer(address,uint256)": { "notice": "Transfers tokens" } },
+            "events": { "Transfer(address,address,uint256)": { "notice": "A transfer happened" } },
+            "macros": { "MAIN": { "notice": "Does nothing" } },
+            "version": 1
+        })
+    );
+    assert_eq!(
+        json["devdoc"],
+        serde_json::json!({
+            "kind": "dev",
+            "methods": {
+                "transfer(address,uint256)": {
+                    "details": "Reverts if the balance is too low",
+                    "params": { "to": "The recipient" }
+                }
+            },
+            "events": {},
+            "version": 1
+        })
+    );
+}
+
+#[test]
+fn test_undocumented_artifact() {
+    let artifact = artifact("#define macro MAIN() = takes(0) returns(0) {\n    stop\n}\n");
+    assert_eq!(artifact.userdoc, UserDoc::default());
+    assert_eq!(artifact.devdoc, DevDoc::default());
+}
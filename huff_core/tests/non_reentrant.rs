@@ -0,0 +1,106 @@
+mod common;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+use common::source_file;
+
+const SOURCE: &str = r#"#define macro WITHDRAW() = takes(0) returns(0) {
+    0x00 0x00 0x00 0x00 selfbalance caller gas call pop
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    NON_REENTRANT(WITHDRAW)
+    stop
+}
+"#;
+
+/// The keccak256 hash of the lock slot preimage
+const SLOT: &str = "4918b114ae26c67d50969da6d839157d6613cb2014588fe64d3d52ac9b163efc";
+
+/// The revert of the reentrant call panic: `Panic(0x100)`
+const PANIC: &str = "634e487b7160e01b60005261010060045260246000fd";
+
+fn artifact(source: &str, evm_version: EVMVersion) -> Artifact {
+    let compiler = Compiler { evm_version, ..Default::default() };
+    let artifact = compiler.gen_artifact(source_file("Guard.huff", source)).unwrap();
+    assert!(compiler.get_warnings().is_empty());
+    artifact
+}
+
+fn runtime(source: &str, evm_version: EVMVersion) -> String {
+    artifact(source, evm_version).runtime
+}
+
+#[test]
+fn test_transient_storage_guard() {
+    // The lock is checked, held while the wrapped macro runs, and released
+    let expected = format!(
+        "7f{slot}5c1561003d57{panic}5b60017f{slot}5d600060006000600047335af15060007f{slot}5d00",
+        slot = SLOT,
+        panic = PANIC
+    );
+    assert_eq!(runtime(SOURCE, EVMVersion::Cancun), expected);
+}
+
+#[test]
+fn test_storage_guard_fallback() {
+    let expected = format!(
+        "7f{slot}541561003d57{panic}5b60017f{slot}55600060006000600047335af15060007f{slot}5500",
+        slot = SLOT,
+        panic = PANIC
+    );
+    assert_eq!(runtime(SOURCE, EVMVersion::Paris), expected);
+    assert_eq!(runtime(SOURCE, EVMVersion::Shanghai), expected);
+}
+
+#[test]
+fn test_guards_in_labels() {
+    // Each guard jumps to its own label
+    let source = r#"#define macro INCREMENT() = takes(1) returns(1) {
+    0x01 add
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x00 calldataload first jumpi
+    0x00 NON_REENTRANT(INCREMENT) NON_REENTRANT(INCREMENT)
+    first:
+        NON_REENTRANT(INCREMENT)
+        0x00 mstore
+        0x20 0x00 return
+}
+"#;
+    let bytecode = runtime(source, EVMVersion::Cancun);
+    assert_eq!(bytecode.matches("5c15").count(), 3);
+    assert_eq!(bytecode.matches(&format!("{}5d", SLOT)).count(), 6);
+}
+
+#[test]
+fn test_defined_non_reentrant_macro() {
+    let source = r#"#define macro NON_REENTRANT() = takes(0) returns(0) {
+    0x00 0x00 revert
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    NON_REENTRANT()
+}
+"#;
+    let artifact = artifact(source, EVMVersion::Cancun);
+    assert_eq!(artifact.runtime, "60006000fd");
+    assert!(artifact.panics.is_empty());
+}
+
+#[test]
+fn test_guard_panic_recorded() {
+    let artifact = artifact(SOURCE, EVMVersion::Cancun);
+    assert_eq!(
+        artifact.panics.into_iter().collect::<Vec<_>>(),
+        vec![("0x0100".to_string(), "reentrant call to a NON_REENTRANT guarded macro".to_string())]
+    );
+
+    // The revert data the guard returns decodes to the panic
+    let mut data = PANIC_SELECTOR.to_vec();
+    data.extend(str_to_bytes32("0100"));
+    assert_eq!(PanicCode::decode(&data), Some(PanicCode::ReentrantCall));
+    assert_eq!(PanicCode::decode(&data[..35]), None);
+}
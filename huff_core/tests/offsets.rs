@@ -0,0 +1,49 @@
+mod common;
+
+use huff_utils::prelude::*;
+
+use common::{codegen_errors, compile};
+
+#[test]
+fn test_pushes_label_distances() {
+    // Labels laid out by the invoking macro are filled once it is generated
+    let source = r#"#define macro RETURN_REGION() = takes(0) returns(0) {
+    __OFFSET(end) - __OFFSET(start) dup1 __OFFSET(start) 0x00 codecopy 0x00 return
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    RETURN_REGION()
+    start:
+        0x01 0x02
+    end:
+    __OFFSET(end) + 0x20
+}
+"#;
+    let artifact = compile(source).unwrap();
+    // `start` is at 0x0d and `end` at 0x12
+    assert_eq!(artifact.runtime, "6100058061000d6000396000f35b600160025b610032");
+}
+
+#[test]
+fn test_reports_offsets_out_of_range() {
+    let source = r#"#define macro MAIN() = takes(0) returns(0) {
+    __OFFSET(start) - __OFFSET(end)
+    start:
+        0x00
+    end:
+}
+"#;
+    assert_eq!(
+        codegen_errors(source),
+        vec![CodegenErrorKind::OffsetOutOfRange("__OFFSET(start) - __OFFSET(end)".to_string())]
+    );
+}
+
+#[test]
+fn test_reports_unmatched_offset_labels() {
+    let source = r#"#define macro MAIN() = takes(0) returns(0) {
+    __OFFSET(missing) 0x00 mstore
+}
+"#;
+    assert_eq!(codegen_errors(source), vec![CodegenErrorKind::UnmatchedJumpLabel]);
+}
@@ -0,0 +1,59 @@
+use std::{str::FromStr, sync::Arc};
+
+use huff_core::{Compiler, CompilerSession};
+use huff_utils::prelude::*;
+
+const SOURCE: &str = r#"#define constant FEE = 0x01
+#define constant OWNER = 0x00
+
+#define macro MAIN() = takes(0) returns(0) {
+    [FEE] [OWNER] sstore
+    __IFDEF(DEBUG) {
+        0x00 calldataload pop
+    }
+    stop
+}
+"#;
+
+fn compiler(overrides: &[&str]) -> CompilerSession {
+    let overrides = overrides.iter().map(|o| ConstantDefinition::from_str(o).unwrap()).collect();
+    Compiler::builder().overrides(overrides).build()
+}
+
+fn file() -> Arc<FileSource> {
+    Arc::new(FileSource {
+        path: "Overrides.huff".to_string(),
+        source: Some(SOURCE.to_string()),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_overrides_constants() {
+    assert_eq!(compiler(&[]).gen_artifact(file()).unwrap().runtime, "600160005500");
+    assert_eq!(
+        compiler(&["FEE=0x64", "OWNER=0xdead"]).gen_artifact(file()).unwrap().runtime,
+        "606461dead5500"
+    );
+}
+
+#[test]
+fn test_reports_unknown_overrides() {
+    // Overrides don't define constants, even ones tested by `__IFDEF`
+    match compiler(&["FEES=0x64", "DEBUG"]).gen_artifact(file()) {
+        Err(CompilerError::FailedCompiles(errors)) => assert_eq!(
+            errors
+                .iter()
+                .map(|e| match e {
+                    CompilerError::ParserError(pe) => pe.kind.clone(),
+                    e => panic!("Expected a parser error, got {:?}", e),
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                ParserErrorKind::UnknownConstantOverride("FEES".to_string()),
+                ParserErrorKind::UnknownConstantOverride("DEBUG".to_string()),
+            ]
+        ),
+        res => panic!("Expected the unknown overrides to fail, got {:?}", res.map(|a| a.runtime)),
+    }
+}
@@ -0,0 +1,119 @@
+use std::sync::{Arc, Mutex};
+
+use huff_codegen::CodegenPlugin;
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+/// Injects `0x2a pop` at the start of `MAIN`, appends a `STOP` to its bytecode, and records the
+/// hooks it ran
+#[derive(Debug, Default)]
+struct Instrument {
+    hooks: Arc<Mutex<Vec<String>>>,
+}
+
+impl CodegenPlugin for Instrument {
+    fn name(&self) -> &str {
+        "instrument"
+    }
+
+    fn before_expansion(&self, contract: &mut Contract) -> Result<(), CodegenError> {
+        self.hooks.lock().unwrap().push("before_expansion".to_string());
+        let main = contract.macros.iter_mut().find(|m| m.name == "MAIN").unwrap();
+        let prelude = [
+            StatementType::Literal(str_to_bytes32("2a")),
+            StatementType::Opcode(Opcode::Pop),
+        ];
+        for ty in prelude.into_iter().rev() {
+            main.statements.insert(0, Statement { ty, span: AstSpan(vec![]) });
+        }
+        Ok(())
+    }
+
+    fn after_expansion(
+        &self,
+        macro_name: &str,
+        bytecode: &mut String,
+        _contract: &Contract,
+    ) -> Result<(), CodegenError> {
+        self.hooks.lock().unwrap().push(format!("after_expansion {}", macro_name));
+        if macro_name == "MAIN" {
+            bytecode.push_str("00");
+        }
+        Ok(())
+    }
+
+    fn before_assembly(
+        &self,
+        _main_bytecode: &mut String,
+        _constructor_bytecode: &mut String,
+    ) -> Result<(), CodegenError> {
+        self.hooks.lock().unwrap().push("before_assembly".to_string());
+        Ok(())
+    }
+}
+
+/// Fails compilation of contracts without a `PRELUDE` macro
+#[derive(Debug)]
+struct RequirePrelude;
+
+impl CodegenPlugin for RequirePrelude {
+    fn name(&self) -> &str {
+        "require-prelude"
+    }
+
+    fn before_expansion(&self, contract: &mut Contract) -> Result<(), CodegenError> {
+        match contract.find_macro_by_name("PRELUDE") {
+            Some(_) => Ok(()),
+            None => Err(CodegenError {
+                kind: CodegenErrorKind::PluginFailed(self.name().to_string()),
+                span: AstSpan(vec![]),
+                token: None,
+                help: Some("define a `PRELUDE` macro".to_string()),
+            }),
+        }
+    }
+}
+
+fn source() -> Arc<FileSource> {
+    Arc::new(FileSource {
+        path: "Plugins.huff".to_string(),
+        source: Some(
+            "#define macro CONSTRUCTOR() = takes(0) returns(0) {}\n#define macro MAIN() = \
+             takes(0) returns(0) {\n    0x01 pop\n}\n"
+                .to_string(),
+        ),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_runs_plugin_hooks() {
+    let instrument = Instrument::default();
+    let hooks = Arc::clone(&instrument.hooks);
+    let session = Compiler::builder().plugin(instrument).build();
+
+    let artifact = session.gen_artifact(source()).unwrap();
+    assert_eq!(artifact.runtime, "602a5060015000");
+    assert_eq!(
+        *hooks.lock().unwrap(),
+        [
+            "before_expansion",
+            "after_expansion MAIN",
+            "after_expansion CONSTRUCTOR",
+            "before_assembly"
+        ]
+    );
+}
+
+#[test]
+fn test_reports_plugin_failures() {
+    let session = Compiler::builder().plugin(RequirePrelude).build();
+
+    match session.gen_artifact(source()) {
+        Err(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.kind, CodegenErrorKind::PluginFailed("require-prelude".to_string()));
+            assert_eq!(e.help, Some("define a `PRELUDE` macro".to_string()));
+        }
+        res => panic!("expected a plugin failure, got {:?}", res),
+    }
+}
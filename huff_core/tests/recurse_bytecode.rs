@@ -178,3 +178,77 @@ fn recurse_macro_bytecode() {
     // Check the bytecode
     assert_eq!(artifact.bytecode.to_lowercase(), expected_bytecode.to_lowercase());
 }
+
+fn parse(source: &str) -> Contract {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    parser.parse().unwrap()
+}
+
+#[test]
+fn detects_recursive_macro_invocations() {
+    let source = r#"
+    #define macro PING() = takes(0) returns(0) {
+        0x01 PONG()
+    }
+
+    #define macro PONG() = takes(0) returns(0) {
+        PING()
+    }
+
+    #define macro SELF() = takes(0) returns(0) {
+        __codesize(SELF)
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        PING()
+        SELF()
+    }
+    "#;
+    let contract = parse(source);
+
+    let errors = Codegen::generate_macro_bytecode("MAIN", &contract).unwrap_err();
+    assert_eq!(
+        errors.iter().map(|e| e.kind.clone()).collect::<Vec<_>>(),
+        vec![
+            CodegenErrorKind::RecursiveMacroInvocation("MAIN > PING > PONG > PING".to_string()),
+            CodegenErrorKind::RecursiveMacroInvocation("MAIN > SELF > SELF".to_string()),
+        ]
+    );
+    assert_eq!(errors[0].kind.code(), "H0316");
+    let span = &errors[0].span.0;
+    assert_eq!(&source[span[0].start..span[span.len() - 1].end], "PING()");
+}
+
+#[test]
+fn limits_the_expansion_depth() {
+    let source = r#"
+    #define macro C() = takes(0) returns(0) {
+        0x01
+    }
+
+    #define macro B() = takes(0) returns(0) {
+        C()
+    }
+
+    #define macro A() = takes(0) returns(0) {
+        B()
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        A()
+    }
+    "#;
+    let mut contract = parse(source);
+    assert_eq!(Codegen::generate_main_bytecode(&contract).unwrap(), "6001");
+
+    contract.max_expansion_depth = Some(2);
+    let e = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert_eq!(e.kind, CodegenErrorKind::MaxExpansionDepthExceeded(2));
+    assert_eq!(
+        e.help.as_deref(),
+        Some("expanded as MAIN > A > B > C; raise the limit with `--max-expansion-depth`")
+    );
+}
@@ -1,7 +1,7 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{fs, path::PathBuf, sync::Arc};
 
 use huff_core::Compiler;
-use huff_utils::files::FileSource;
+use huff_utils::prelude::*;
 
 #[test]
 fn test_recursing_fs_dependencies() {
@@ -42,3 +42,42 @@ fn test_recursing_external_dependencies() {
         assert_eq!(dep.dependencies.as_ref().unwrap().len(), 0);
     }
 }
+
+/// Recurses the dependencies of a file, returning the cycle of a circular include
+fn include_cycle(path: &std::path::Path) -> Vec<String> {
+    let path = path.to_string_lossy().to_string();
+    let file = Arc::new(FileSource { path, ..Default::default() });
+    match Compiler::recurse_deps(file).map_err(|e| (*e).clone()) {
+        Err(CompilerError::FileUnpackError(UnpackError::CircularInclude(cycle))) => cycle,
+        res => panic!("Expected a circular include, got {:?}", res.map(|f| f.path.clone())),
+    }
+}
+
+#[test]
+fn test_reports_circular_includes() {
+    let dir = std::env::temp_dir().join(format!("huff_cycles_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+
+    // The cycle starts at the first file included again, past the files leading to it
+    fs::write(dir.join("Main.huff"), "#include \"./A.huff\"\n").unwrap();
+    fs::write(dir.join("A.huff"), "#include \"./B.huff\"\n#define constant A = 0x01\n").unwrap();
+    fs::write(dir.join("B.huff"), "#include \"./C.huff\"\n").unwrap();
+    fs::write(dir.join("C.huff"), "#include \"./A.huff\"\n").unwrap();
+    let cycle = include_cycle(&dir.join("Main.huff"));
+    assert_eq!(cycle.len(), 4);
+    for (file, name) in cycle.iter().zip(["A.huff", "B.huff", "C.huff", "A.huff"]) {
+        assert!(file.ends_with(name), "{} is not {}", file, name);
+    }
+
+    // Files including themselves are a cycle of one file
+    fs::write(dir.join("Self.huff"), "#include \"./Self.huff\"\n").unwrap();
+    assert_eq!(include_cycle(&dir.join("Self.huff")), vec![path("Self.huff"), path("Self.huff")]);
+
+    // The error lists the cycle
+    let error = CompilerError::FileUnpackError(UnpackError::CircularInclude(cycle));
+    assert!(error.to_string().contains("A.huff \u{2192} "));
+    assert_eq!(error.code(), Some("H0405"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use huff_utils::prelude::*;
+
+use common::compile;
+
+#[test]
+fn test_repeats_unroll() {
+    let source = r#"#define constant WORDS = 0x03
+
+#define macro COPY_WORD(i) = takes(0) returns(0) {
+    <i> 0x20 mul calldataload // [word]
+    <i> 0x20 mul mstore       // []
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    __REPEAT([WORDS], I) {
+        COPY_WORD([I])
+    }
+    __REPEAT(2) { 0x00 }
+    return
+}
+"#;
+    let artifact = compile(source).unwrap();
+    let copy = |i: &str| format!("60{i}6020023560{i}60200252");
+    assert_eq!(
+        artifact.runtime,
+        [copy("00"), copy("01"), copy("02"), "60006000f3".to_string()].concat()
+    );
+}
+
+#[test]
+fn test_invalid_repeat_count() {
+    let source = r#"#define macro MAIN() = takes(0) returns(0) {
+    __REPEAT(0x6001) { 0x00 pop }
+}
+"#;
+    match compile(source) {
+        Err(CompilerError::ParserError(e)) => {
+            assert_eq!(e.kind, ParserErrorKind::InvalidRepeatCount("0x6001".to_string()))
+        }
+        r => panic!("Expected an invalid repeat count, got {:?}", r),
+    }
+}
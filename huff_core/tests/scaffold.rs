@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+const ABI: &str = r#"[
+    {"type":"constructor","inputs":[{"name":"asset","type":"address"}],"stateMutability":"nonpayable"},
+    {"type":"function","name":"deposit","inputs":[{"name":"assets","type":"uint256"},{"name":"receiver","type":"address"}],"outputs":[{"name":"shares","type":"uint256"}],"stateMutability":"nonpayable"},
+    {"type":"function","name":"totalAssets","inputs":[],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view"},
+    {"type":"function","name":"totalAssets","inputs":[{"name":"at","type":"uint256"}],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view"},
+    {"type":"function","name":"balance","inputs":[],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view"},
+    {"type":"function","name":"multicall","inputs":[{"name":"calls","type":"tuple[]","components":[{"name":"target","type":"address"},{"name":"data","type":"bytes"}]}],"outputs":[],"stateMutability":"payable"},
+    {"type":"event","name":"Deposit","inputs":[{"name":"caller","type":"address","indexed":true},{"name":"owner","type":"address","indexed":true},{"name":"assets","type":"uint256","indexed":false}],"anonymous":false},
+    {"type":"error","name":"ZeroShares","inputs":[{"name":"assets","type":"uint256"}]},
+    {"type":"receive","stateMutability":"payable"},
+    {"type":"fallback","stateMutability":"payable"}
+]"#;
+
+#[test]
+fn test_scaffolds_huff_from_abi() {
+    let scaffold = Scaffold::parse("Vault", ABI).unwrap();
+    let source = scaffold.source();
+
+    assert!(source.contains(
+        "#define function deposit(uint256 assets, address receiver) nonpayable returns (uint256)\n"
+    ));
+    assert!(source.contains(
+        "#define event Deposit(address indexed caller, address indexed owner, uint256 assets)\n"
+    ));
+    assert!(source.contains("#define error ZeroShares(uint256 assets)\n"));
+    assert!(source.contains("#define function CONSTRUCTOR(address asset) nonpayable returns ()\n"));
+    assert!(source.contains(
+        "#define function multicall((address,bytes)[] calls) payable returns ()\n"
+    ));
+    assert!(source.contains("    dup1 0xcaa5c23f eq multicall jumpi\n"));
+    // Overloads are disambiguated by selector, and opcodes aren't used as labels
+    assert!(source.contains("    totalAssets_94605857:\n        TOTAL_ASSETS_94605857()\n"));
+    assert!(source.contains("    fn_balance:\n        BALANCE()\n"));
+    assert!(source
+        .contains("#define macro BALANCE() = takes(0) returns(0) {\n    0x00 dup1 revert\n}\n"));
+    assert!(source.contains("#define receive() = takes(0) returns(0) {\n    stop\n}\n"));
+    assert!(source.contains("    fallback jump\n"));
+
+    // The skeleton compiles
+    let file = Arc::new(FileSource {
+        path: "Vault.huff".to_string(),
+        source: Some(source.clone()),
+        ..Default::default()
+    });
+    let abi = Compiler::default().gen_artifact(file).unwrap().abi.unwrap();
+    assert!(abi.functions.contains_key("deposit"));
+    assert_eq!(
+        abi.functions.get("multicall").unwrap().signature(),
+        "multicall((address,bytes)[])"
+    );
+    assert!(abi.events.contains_key("Deposit"));
+    assert_eq!(abi.errors.get("ZeroShares").unwrap().selector(), [0xdc, 0x6d, 0x33, 0x6e]);
+    assert_eq!(abi.constructor.unwrap().inputs[0].name, "asset");
+    assert!(abi.receive && abi.fallback);
+}
@@ -0,0 +1,103 @@
+use huff_codegen::Codegen;
+use huff_lexer::Lexer;
+use huff_parser::Parser;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_runtime_source_map() {
+    let source = r#"
+    #define jumptable__packed SWITCH {
+        done
+    }
+
+    #define macro INNER(dest) = takes(0) returns(0) {
+        0x01 <dest> jumpi
+    }
+
+    #define macro OUTER() = takes(0) returns(0) {
+        INNER(done)
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __tablestart(SWITCH)
+        OUTER()
+        done:
+            stop
+    }
+    "#;
+
+    let full_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(full_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    assert_eq!(Codegen::generate_main_bytecode(&contract).unwrap(), "61000b6001610009575b000009");
+    let source_map = Codegen::generate_source_map("MAIN", &contract).unwrap();
+
+    // (offset, size, invocations, relocation, table) of every mapped range
+    let ranges = source_map
+        .iter()
+        .map(|m| {
+            (
+                m.offset,
+                m.size,
+                m.invocations.iter().map(|mi| mi.macro_name.as_str()).collect::<Vec<&str>>(),
+                m.relocation.clone(),
+                m.table.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ranges,
+        vec![
+            (0, 3, vec![], Some(Relocation::TableStart("SWITCH".to_string())), None),
+            (3, 2, vec!["OUTER", "INNER"], None, None),
+            (5, 3, vec!["OUTER", "INNER"], Some(Relocation::JumpLabel("done".into())), None),
+            (8, 1, vec!["OUTER", "INNER"], None, None),
+            (9, 1, vec![], None, None),
+            (10, 1, vec![], None, None),
+            (11, 2, vec![], None, Some("SWITCH".to_string())),
+        ]
+    );
+
+    // Each statement maps to its own source
+    let stop = source_map.iter().find(|m| m.contains(10)).unwrap();
+    assert_eq!(&source[stop.span.0[0].start..stop.span.0[0].end], "stop");
+}
+
+#[test]
+fn test_labels_keep_source_names() {
+    let source = r#"
+    #define macro SKIP() = takes(0) returns(0) {
+        skip jump
+        skip:
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        SKIP()
+        SKIP()
+    }
+    "#;
+
+    let full_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(full_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    // Labels are not mangled, each invocation jumps to its own label
+    assert_eq!(Codegen::generate_main_bytecode(&contract).unwrap(), "610004565b610009565b");
+    let source_map = Codegen::generate_source_map("MAIN", &contract).unwrap();
+    let jumps = source_map
+        .iter()
+        .filter_map(|m| m.relocation.clone().map(|r| (m.offset, r)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        jumps,
+        vec![
+            (0, Relocation::JumpLabel("skip".into())),
+            (5, Relocation::JumpLabel("skip".into())),
+        ]
+    );
+}
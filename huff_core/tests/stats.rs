@@ -0,0 +1,93 @@
+mod common;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+use common::source_file;
+
+#[test]
+fn test_file_and_macro_stats() {
+    let source = r#"#define constant OWNER = FREE_STORAGE_POINTER()
+
+// Loads the owner
+#define macro GET_OWNER() = takes(0) returns(1) {
+    [OWNER] sload
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    GET_OWNER() GET_OWNER() eq
+    done jumpi
+    done:
+        0x00 0x00 return
+}
+"#;
+    let stats = Compiler::default().stats(source_file("Stats.huff", source)).unwrap();
+
+    // Comments and whitespace aren't counted
+    assert_eq!(stats.path, "Stats.huff");
+    assert_eq!(stats.tokens, 55);
+    assert_eq!(stats.nodes, 14);
+    assert_eq!(stats.bytes, 17);
+    assert_eq!(stats.expansion, 17.0 / 14.0);
+
+    // Bytes are attributed to the macro generating them, once per expansion
+    let get_owner = &stats.macros[0];
+    assert_eq!(get_owner.name, "GET_OWNER");
+    assert_eq!(get_owner.file.as_deref(), Some("Stats.huff"));
+    assert_eq!(get_owner.tokens, 20);
+    assert_eq!(get_owner.nodes, 2);
+    assert_eq!(get_owner.invocations, 2);
+    assert_eq!(get_owner.bytes, 6);
+    assert_eq!(get_owner.expansion, 3.0);
+
+    // Label statements are counted with the label
+    let main = &stats.macros[1];
+    assert_eq!((main.nodes, main.invocations, main.bytes), (9, 1, 11));
+    assert_eq!(stats.macros.iter().map(|m| m.bytes).sum::<usize>(), stats.bytes);
+}
+
+#[test]
+fn test_phase_stats() {
+    let source = r#"#define constant OWNER = FREE_STORAGE_POINTER()
+
+#define macro GET_OWNER() = takes(0) returns(1) {
+    [OWNER] sload
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    GET_OWNER() GET_OWNER() eq
+    0x00 mstore
+}
+"#;
+    let stats = Compiler::default().compile_file(source_file("Phases.huff", source)).stats;
+
+    // Artifacts are only written by a full compilation
+    assert_eq!(stats.path, "Phases.huff");
+    assert_eq!(
+        stats.timings_us.keys().copied().collect::<Vec<_>>(),
+        vec![Phase::FileIo, Phase::Lexing, Phase::Parsing, Phase::StorageDerivation, Phase::Codegen]
+    );
+    assert_eq!(stats.macros_expanded, 2);
+    assert_eq!(stats.expansions_reused, 1);
+    assert_eq!(stats.bytes_emitted, 10);
+
+    let report = PhaseReport { files: vec![stats.clone(), stats] };
+    let total = report.total();
+    assert_eq!((total.macros_expanded, total.bytes_emitted), (4, 20));
+    assert_eq!(total.expansions_reused, 2);
+
+    let table = report.render();
+    let header = table.lines().next().unwrap();
+    let columns = header.split_whitespace().collect::<Vec<_>>();
+    assert_eq!(columns, ["Phases.huff", "Phases.huff", "total"]);
+    assert!(table.lines().any(|l| l.starts_with("artifact write (us)")));
+    let reused = table.lines().find(|l| l.starts_with("expansions reused")).unwrap();
+    let reused = reused.split_whitespace().collect::<Vec<_>>();
+    assert_eq!(reused, ["expansions", "reused", "1", "1", "2"]);
+    let bytes = table.lines().last().unwrap().split_whitespace().collect::<Vec<_>>();
+    assert_eq!(bytes, ["bytes", "emitted", "10", "10", "20"]);
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["files"][0]["macros_expanded"], 2);
+    assert!(json["files"][0]["timings_us"]["storage_derivation"].is_u64());
+}
@@ -0,0 +1,80 @@
+use std::fs;
+
+use huff_core::Compiler;
+use huff_lexer::Lexer;
+use huff_parser::Parser;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_cycles_through_imports() {
+    let dir = std::env::temp_dir().join(format!("huff_cycles_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("Lib.huff"),
+        "#define constant BALANCE = FREE_STORAGE_POINTER()\n#define macro B() = takes(0) returns(0) {\n    [BALANCE] sload A()\n}\n",
+    )
+    .unwrap();
+    let main = dir.join("Main.huff");
+    fs::write(
+        &main,
+        "#include \"./Lib.huff\"\n#define constant OWNER = FREE_STORAGE_POINTER()\n#define macro A() = takes(0) returns(0) {\n    [OWNER] sload B()\n}\n#define macro MAIN() = takes(0) returns(0) {\n    A()\n}\n",
+    )
+    .unwrap();
+
+    let compiler = Compiler::builder().source(main.to_string_lossy()).build();
+    let results = compiler.execute().unwrap();
+    match results[0].error().map(|e| e.as_ref()) {
+        Some(CompilerError::CodegenError(e)) => {
+            assert_eq!(
+                e.kind,
+                CodegenErrorKind::RecursiveMacroInvocation("MAIN > A > B > A".to_string())
+            );
+            assert_eq!(
+                e.help.as_deref(),
+                Some("\"A\" is invoked in its own expansion, which never ends")
+            );
+            assert!(e.span.0[0].file.as_ref().unwrap().path.ends_with("Lib.huff"));
+        }
+        e => panic!("Expected a recursive macro invocation, got {:?}", e),
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_slots_derived_around_cycles() {
+    let source = r#"
+    #define constant OWNER = FREE_STORAGE_POINTER()
+    #define constant BALANCE = FREE_STORAGE_POINTER()
+    #define constant TOTAL = FREE_STORAGE_POINTER()
+
+    #define macro SIZED() = takes(0) returns(0) {
+        [BALANCE] sload __codesize(SIZED)
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        [OWNER] sload SIZED() [TOTAL] sload
+    }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let mut contract = parser.parse().unwrap();
+
+    let errors = contract.try_derive_storage_pointers().unwrap_err();
+    assert_eq!(
+        errors.iter().map(|e| e.kind.clone()).collect::<Vec<_>>(),
+        vec![CodegenErrorKind::RecursiveMacroInvocation("MAIN > SIZED > SIZED".to_string())]
+    );
+    let span = &errors[0].span.0;
+    assert!(source[span[0].start..].starts_with("__codesize(SIZED)"));
+
+    // The constants reached are still assigned slots in push order
+    let slot = |name: &str| {
+        contract.constants.iter().find(|c| c.name == name).map(|c| c.value.clone()).unwrap()
+    };
+    assert_eq!(slot("OWNER"), ConstVal::Literal(str_to_bytes32("0")));
+    assert_eq!(slot("BALANCE"), ConstVal::Literal(str_to_bytes32("1")));
+    assert_eq!(slot("TOTAL"), ConstVal::Literal(str_to_bytes32("2")));
+}
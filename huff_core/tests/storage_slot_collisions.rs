@@ -0,0 +1,102 @@
+mod common;
+
+use huff_utils::prelude::*;
+
+use common::compile;
+
+#[test]
+fn test_derived_slot_collides_with_literal_slot() {
+    let source = r#"#define constant OWNER = FREE_STORAGE_POINTER()
+#define constant BALANCE = FREE_STORAGE_POINTER()
+#define constant BALANCE_SLOT = 0x01
+
+#define macro MAIN() = takes(0) returns(0) {
+    caller [OWNER] sstore
+    callvalue [BALANCE] sstore
+    callvalue [BALANCE_SLOT] sstore
+}
+"#;
+    match compile(source) {
+        Err(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.kind, CodegenErrorKind::StorageSlotCollision("BALANCE".to_string()));
+            assert_eq!(
+                e.help.as_deref(),
+                Some("\"BALANCE\" is derived the slot 0x01, already used by \"BALANCE_SLOT\"")
+            );
+            // Both definitions are spanned
+            assert_eq!(
+                e.span.0.first().unwrap().start,
+                source.find("#define constant BALANCE ").unwrap()
+            );
+            assert_eq!(e.span.0.last().unwrap().end, source.find("0x01").unwrap() + "0x01".len());
+        }
+        r => panic!("Expected a storage slot collision, got {:?}", r),
+    }
+}
+
+#[test]
+fn test_literal_slot_used_in_label() {
+    let source = r#"#define constant OWNER = FREE_STORAGE_POINTER()
+#define constant OWNER_SLOT = 0x00
+
+#define macro MAIN() = takes(0) returns(0) {
+    caller [OWNER] sstore
+    0x00 calldataload read jumpi
+    stop
+    read:
+        [OWNER_SLOT] sload
+}
+"#;
+    match compile(source) {
+        Err(CompilerError::CodegenError(e)) => assert_eq!(e.kind.code(), "H0318"),
+        r => panic!("Expected a storage slot collision, got {:?}", r),
+    }
+}
+
+#[test]
+fn test_literals_not_used_as_slots() {
+    // The literals are only stored as values, or to slots the derived pointers don't reach
+    let source = r#"#define constant OWNER = FREE_STORAGE_POINTER()
+#define constant ZERO = 0x00
+#define constant HIGH_SLOT = 0x1000
+
+#define macro MAIN() = takes(0) returns(0) {
+    [ZERO] [OWNER] sstore
+    caller [HIGH_SLOT] sstore
+    [ZERO] 0x00 mstore
+}
+"#;
+    assert!(compile(source).is_ok());
+}
+
+#[test]
+fn test_literal_slot_in_sized_reservation() {
+    let source = r#"#define constant BALANCES = FREE_STORAGE_POINTER(4)
+#define constant OWNER = FREE_STORAGE_POINTER()
+#define constant LAST_BALANCE_SLOT = 0x03
+
+#define macro MAIN() = takes(0) returns(0) {
+    [BALANCES] sload [OWNER] sload
+    [LAST_BALANCE_SLOT] sload
+    pop pop pop
+}
+"#;
+    match compile(source) {
+        Err(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.kind, CodegenErrorKind::StorageSlotCollision("BALANCES".to_string()));
+            assert_eq!(
+                e.help.as_deref(),
+                Some(
+                    "\"BALANCES\" is derived the slot 0x03, already used by \"LAST_BALANCE_SLOT\""
+                )
+            );
+        }
+        r => panic!("Expected a storage slot collision, got {:?}", r),
+    }
+
+    // The slot after the reservation is the next pointer's
+    let source = source.replace("0x03", "0x05");
+    let artifact = compile(&source).unwrap();
+    assert_eq!(artifact.storage_layout["BALANCES"], "0x00");
+    assert_eq!(artifact.storage_layout["OWNER"], "0x04");
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+use common::source_file;
+
+#[test]
+fn test_dumps_tokens_past_errors() {
+    // Doesn't parse, and has an invalid character
+    let source = "#define macro MAIN() = {\n    $ dup1 // copy\n";
+    let tokens = Compiler::default().tokens(source_file("Tokens.huff", source));
+
+    let dumped =
+        tokens.iter().filter_map(|t| t.as_ref().ok()).map(|t| t.dump()).collect::<Vec<_>>();
+    assert_eq!(
+        dumped[..dumped.len() - 1],
+        [
+            "Tokens.huff:1:1\t0..7\tDefine\t\"#define\"",
+            "Tokens.huff:1:9\t8..13\tMacro\t\"macro\"",
+            "Tokens.huff:1:15\t14..18\tIdent\t\"MAIN\"",
+            "Tokens.huff:1:19\t18..19\tOpenParen\t\"(\"",
+            "Tokens.huff:1:20\t19..20\tCloseParen\t\")\"",
+            "Tokens.huff:1:22\t21..22\tAssign\t\"=\"",
+            "Tokens.huff:1:24\t23..24\tOpenBrace\t\"{\"",
+            "Tokens.huff:2:7\t31..35\tOpcode\t\"dup1\"",
+            "Tokens.huff:2:12\t36..43\tComment\t\"// copy\"",
+        ]
+    );
+    assert_eq!(dumped.last().map(|t| t.contains("Eof")), Some(true));
+
+    let errors = tokens.into_iter().filter_map(|t| t.err()).collect::<Vec<_>>();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, LexicalErrorKind::InvalidCharacter('$'));
+}
@@ -0,0 +1,43 @@
+use huff_core::{Compiler, LogFormat, TracingConfig};
+
+#[test]
+fn test_verbosity_levels() {
+    assert_eq!(TracingConfig::level_of(0), None);
+    assert_eq!(TracingConfig::level_of(1), Some(tracing::Level::INFO));
+    assert_eq!(TracingConfig::level_of(2), Some(tracing::Level::DEBUG));
+    assert_eq!(TracingConfig::level_of(5), Some(tracing::Level::TRACE));
+    assert_eq!("JSON".parse::<LogFormat>(), Ok(LogFormat::Json));
+    assert!("yaml".parse::<LogFormat>().is_err());
+}
+
+#[test]
+fn test_logs_filtered_json_to_file() {
+    let log_file = std::env::temp_dir().join(format!("huff_tracing_{}.log", std::process::id()));
+    let config = TracingConfig {
+        level: TracingConfig::level_of(1),
+        directives: vec!["codegen=debug".parse().unwrap(), "parser=off".parse().unwrap()],
+        format: LogFormat::Json,
+        log_file: Some(log_file.clone()),
+    };
+    Compiler::init_tracing(&config).unwrap();
+
+    tracing::debug!(target: "codegen", "logged by the target directive");
+    tracing::debug!(target: "core", "filtered by the level");
+    tracing::info!(target: "core", "logged by the level");
+    tracing::error!(target: "parser", "turned off");
+
+    let logs = std::fs::read_to_string(&log_file).unwrap();
+    std::fs::remove_file(&log_file).unwrap();
+    let events = logs
+        .lines()
+        .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+        .map(|e| (e["target"].as_str().unwrap().to_string(), e["message"].clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            ("codegen".to_string(), "logged by the target directive".into()),
+            ("core".to_string(), "logged by the level".into()),
+        ]
+    );
+}
@@ -0,0 +1,79 @@
+use std::{fs, str::FromStr, sync::Arc};
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+fn recurse(path: &std::path::Path) -> Arc<FileSource> {
+    let file = Arc::new(FileSource {
+        path: path.to_string_lossy().to_string(),
+        source: Some(fs::read_to_string(path).unwrap()),
+        ..Default::default()
+    });
+    Compiler::recurse_deps(file).unwrap()
+}
+
+#[test]
+fn test_bundles_sources_and_settings() {
+    let dir = std::env::temp_dir().join(format!("huff_verification_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+
+    let main = "#include \"./Lib.huff\"\n#define function get() view returns (uint256)\n#define macro CONSTRUCTOR() = takes(0) returns(0) {}\n#define macro MAIN() = takes(0) returns(0) {\n    [FEE] pop\n}\n";
+    let lib = "#define constant FEE = 0x01\n";
+    fs::write(dir.join("Main.huff"), main).unwrap();
+    fs::write(dir.join("Lib.huff"), lib).unwrap();
+
+    let compiler = Compiler::builder()
+        .constructor_args(vec!["0x02".to_string()])
+        .override_constant(ConstantDefinition::from_str("FEE=0x03").unwrap())
+        .build();
+    let file = recurse(&dir.join("Main.huff"));
+    let artifact = compiler.gen_artifact(Arc::clone(&file)).unwrap();
+
+    // Every file compiled is bundled by path, pinned by its hash
+    let bundle = compiler.verification_bundle(Arc::clone(&file), false, "0.1.0").unwrap();
+    assert_eq!(bundle.language, "Huff");
+    assert_eq!(
+        bundle.compiler,
+        BundleCompiler { name: "huffc".to_string(), version: "0.1.0".to_string() }
+    );
+    assert_eq!(bundle.entrypoint, path("Main.huff"));
+    assert_eq!(bundle.sources.keys().cloned().collect::<Vec<String>>(), vec![
+        path("Lib.huff"),
+        path("Main.huff")
+    ]);
+    assert_eq!(bundle.sources[&path("Lib.huff")].content, lib);
+    assert_eq!(
+        bundle.sources[&path("Lib.huff")].keccak256,
+        format!("0x{}", ethers_core::utils::hex::encode(ethers_core::utils::keccak256(lib)))
+    );
+    assert_eq!(bundle.settings.evm_version, EVMVersion::default().to_string());
+    assert_eq!(bundle.settings.max_expansion_depth, DEFAULT_MAX_EXPANSION_DEPTH);
+    assert_eq!(bundle.settings.overrides["FEE"], "0x03");
+    assert_eq!(bundle.settings.constructor_arguments, vec!["0x02".to_string()]);
+    assert_eq!(
+        bundle.settings.encoded_constructor_arguments,
+        "0000000000000000000000000000000000000000000000000000000000000002"
+    );
+    assert_eq!(bundle.bytecode, format!("0x{}", artifact.bytecode));
+    assert_eq!(bundle.deployed_bytecode, format!("0x{}", artifact.runtime));
+    assert_eq!(bundle.abi, artifact.abi);
+
+    // A flattened bundle holds a single source, named after the entrypoint
+    let flattened = compiler.verification_bundle(Arc::clone(&file), true, "0.1.0").unwrap();
+    assert_eq!(flattened.entrypoint, "Main.flat.huff");
+    assert_eq!(
+        flattened.sources["Main.flat.huff"].content,
+        Compiler::flatten(Arc::clone(&file)).unwrap()
+    );
+    assert_eq!(flattened.bytecode, bundle.bytecode);
+
+    // The bundle round trips through its json export
+    let out = path("Main.verify.json");
+    bundle.export(&out).unwrap();
+    let exported: VerificationBundle =
+        serde_json::from_str(&fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(exported, bundle);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,81 @@
+use std::{fs, sync::Arc};
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+fn compile(main: &std::path::Path) -> Result<Artifact, Arc<CompilerError>> {
+    let file = Arc::new(FileSource {
+        path: main.to_string_lossy().to_string(),
+        source: Some(fs::read_to_string(main).unwrap()),
+        ..Default::default()
+    });
+    let file = Compiler::recurse_deps(file)?;
+    Compiler::default().gen_artifact(file).map_err(Arc::new)
+}
+
+#[test]
+fn test_internal_definitions() {
+    let dir = std::env::temp_dir().join(format!("huff_visibility_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+
+    fs::write(
+        dir.join("Fees.huff"),
+        "#define internal constant FEE = 0x02\n#define internal macro ADD_FEE() = takes(1) returns(1) {\n    [FEE] add\n}\n#define macro CHARGE() = takes(1) returns(1) {\n    ADD_FEE()\n}\n",
+    )
+    .unwrap();
+
+    // The library references its own internal definitions
+    let main = dir.join("Main.huff");
+    fs::write(
+        &main,
+        "#include \"./Fees.huff\"\n#define macro MAIN() = takes(0) returns(0) {\n    0x01 CHARGE() pop\n}\n",
+    )
+    .unwrap();
+    let artifact = compile(&main).unwrap();
+    assert_eq!(artifact.runtime, "600160020150");
+
+    // Other files can't, qualified or not
+    for (include, prefix) in [("", ""), (" as Fees", "Fees.")] {
+        let private = dir.join("Private.huff");
+        fs::write(
+            &private,
+            format!(
+                "#include \"./Fees.huff\"{}\n#define macro MAIN() = takes(0) returns(0) {{\n    0x01 {}ADD_FEE() [{}FEE] pop pop\n}}\n",
+                include, prefix, prefix
+            ),
+        )
+        .unwrap();
+        let err = compile(&private).unwrap_err();
+        let errors = match err.as_ref() {
+            CompilerError::FailedCompiles(errors) => errors
+                .iter()
+                .map(|e| match e {
+                    CompilerError::ParserError(pe) => pe.clone(),
+                    e => panic!("Expected a parser error, got {:?}", e),
+                })
+                .collect::<Vec<ParserError>>(),
+            e => panic!("Expected parser errors, got {:?}", e),
+        };
+        assert_eq!(
+            errors.iter().map(|e| e.kind.clone()).collect::<Vec<ParserErrorKind>>(),
+            vec![
+                ParserErrorKind::PrivateDefinition(format!("{}ADD_FEE", prefix)),
+                ParserErrorKind::PrivateDefinition(format!("{}FEE", prefix)),
+            ]
+        );
+
+        // Errors span the reference, then the definition
+        for error in errors {
+            let files = error
+                .spans
+                .0
+                .iter()
+                .filter_map(|s| s.file.as_ref().map(|f| f.path.clone()))
+                .collect::<Vec<String>>();
+            assert!(files.first().unwrap().ends_with("Private.huff"));
+            assert!(files.last().unwrap().ends_with("Fees.huff"));
+        }
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
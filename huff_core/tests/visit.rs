@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+const SOURCE: &str = r#"#define constant OWNER = FREE_STORAGE_POINTER()
+#define constant LIMIT = 0x02
+
+#define macro CHECK(slot) = takes(0) returns(0) {
+    <slot> sload pop
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    CHECK([OWNER])
+    done:
+        [OWNER] __ASSERT([LIMIT] > 0x01) __codesize(CHECK) pop
+}
+
+#define test OWNER_IS_SET() = {
+    [OWNER] sload
+}
+"#;
+
+fn contract() -> Contract {
+    let file = Arc::new(FileSource {
+        path: "Visit.huff".to_string(),
+        source: Some(SOURCE.to_string()),
+        ..Default::default()
+    });
+    Compiler::default().ast(file).unwrap()
+}
+
+/// Collects the references to constants and macros, in visiting order
+#[derive(Default)]
+struct References {
+    constants: Vec<String>,
+    macros: Vec<String>,
+}
+
+impl Visitor for References {
+    fn visit_constant_ref(&mut self, name: &str, _span: &AstSpan) {
+        self.constants.push(name.to_string());
+    }
+
+    fn visit_macro_ref(&mut self, name: &str, _span: &AstSpan) {
+        self.macros.push(name.to_string());
+    }
+}
+
+/// Renames a constant, along with its references
+struct RenameConstant<'a> {
+    from: &'a str,
+    to: &'a str,
+}
+
+impl VisitMut for RenameConstant<'_> {
+    fn visit_constant_mut(&mut self, constant: &mut ConstantDefinition) {
+        if constant.name == self.from {
+            constant.name = self.to.into();
+        }
+    }
+
+    fn visit_constant_ref_mut(&mut self, name: &mut SymbolId, _span: &AstSpan) {
+        if *name == self.from {
+            *name = self.to.into();
+        }
+    }
+}
+
+#[test]
+fn test_visits_references() {
+    let contract = contract();
+    let mut references = References::default();
+    references.visit_contract(&contract);
+
+    // Arguments, labels, expressions and tests are walked into, without following invocations
+    assert_eq!(references.constants, ["OWNER", "OWNER", "LIMIT", "OWNER"]);
+    assert_eq!(references.macros, ["CHECK", "CHECK"]);
+}
+
+#[test]
+fn test_transforms_in_place() {
+    let mut contract = contract();
+    RenameConstant { from: "OWNER", to: "ADMIN" }.visit_contract_mut(&mut contract);
+
+    let mut references = References::default();
+    references.visit_contract(&contract);
+    assert_eq!(references.constants, ["ADMIN", "ADMIN", "LIMIT", "ADMIN"]);
+    assert_eq!(contract.constants[0].name, "ADMIN");
+
+    // The renamed contract derives the same slot for the renamed constant
+    contract.derive_storage_pointers();
+    assert_eq!(contract.storage_layout.keys().collect::<Vec<_>>(), ["ADMIN"]);
+}
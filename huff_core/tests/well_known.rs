@@ -0,0 +1,109 @@
+mod common;
+
+use huff_utils::prelude::*;
+
+use common::compile;
+
+#[test]
+fn test_well_known_values() {
+    // The EIP-1967 slots are the published ones
+    let hex = |c: WellKnownConstant| bytes32_to_string(&c.value(), false);
+    assert_eq!(
+        hex(WellKnownConstant::Eip1967ImplementationSlot),
+        "360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc"
+    );
+    assert_eq!(
+        hex(WellKnownConstant::Eip1967AdminSlot),
+        "b53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103"
+    );
+    assert_eq!(
+        hex(WellKnownConstant::Eip1967BeaconSlot),
+        "a3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50"
+    );
+    assert_eq!(WellKnownConstant::Blake2fPrecompile.value()[31], 0x09);
+    assert_eq!(
+        WellKnownConstant::from_name("POINT_EVALUATION_PRECOMPILE"),
+        Some(WellKnownConstant::PointEvaluationPrecompile)
+    );
+    assert_eq!(WellKnownConstant::from_name("OWNER"), None);
+}
+
+#[test]
+fn test_well_known_constants() {
+    let source = r#"#define macro STATICCALL(target) = takes(0) returns(0) {
+    0x20 0x00 0x80 0x00 <target> gas staticcall pop
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    [EIP1967_IMPLEMENTATION_SLOT] sload pop
+    STATICCALL([ECRECOVER_PRECOMPILE])
+    STATICCALL([SHA256_PRECOMPILE])
+}
+"#;
+    let artifact = compile(source).unwrap();
+    let slot = "7f360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+    let call = |target: &str| format!("6020600060806000{}5afa50", target);
+    assert_eq!(artifact.runtime, format!("{}5450{}{}", slot, call("6001"), call("6002")));
+}
+
+#[test]
+fn test_well_known_constant_definitions() {
+    // Definitions with the well-known value are fine
+    let source = r#"#define constant EIP1967_ADMIN_SLOT = 0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103
+
+#define macro MAIN() = takes(0) returns(0) {
+    [EIP1967_ADMIN_SLOT] sload pop
+}
+"#;
+    assert!(compile(source).is_ok());
+
+    // A mistyped value, or a derived slot, fails
+    let source = r#"#define constant EIP1967_IMPLEMENTATION_SLOT = 0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbd
+#define constant EIP1967_BEACON_SLOT = FREE_STORAGE_POINTER()
+
+#define macro MAIN() = takes(0) returns(0) {
+    [EIP1967_IMPLEMENTATION_SLOT] sload [EIP1967_BEACON_SLOT] sload
+}
+"#;
+    match compile(source) {
+        Err(CompilerError::FailedCompiles(errors)) => {
+            let errors = errors
+                .into_iter()
+                .map(|e| match e {
+                    CompilerError::CodegenError(e) => (e.kind, e.help.unwrap_or_default()),
+                    e => panic!("Expected a codegen error, got {:?}", e),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                errors,
+                vec![
+                    (
+                        CodegenErrorKind::WellKnownConstantMismatch(
+                            "EIP1967_IMPLEMENTATION_SLOT".to_string()
+                        ),
+                        "the well-known value is 0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc, remove the definition to use it".to_string()
+                    ),
+                    (
+                        CodegenErrorKind::WellKnownConstantMismatch(
+                            "EIP1967_BEACON_SLOT".to_string()
+                        ),
+                        "the well-known value is 0xa3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50, remove the definition to use it".to_string()
+                    ),
+                ]
+            );
+        }
+        r => panic!("Expected well-known constant mismatches, got {:?}", r),
+    }
+
+    // Misspelled names suggest the well-known constant
+    let source = r#"#define macro MAIN() = takes(0) returns(0) {
+    [EIP1967_ADMN_SLOT] sload pop
+}
+"#;
+    match compile(source) {
+        Err(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.help.as_deref(), Some("did you mean 'EIP1967_ADMIN_SLOT'?"))
+        }
+        r => panic!("Expected a missing constant, got {:?}", r),
+    }
+}
@@ -3,7 +3,6 @@
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
 #![forbid(unsafe_code)]
-#![forbid(where_clauses_object_safety)]
 
 use huff_utils::prelude::*;
 use regex::Regex;
@@ -30,8 +29,14 @@ pub enum Context {
     Abi,
     /// Lexing args of functions inputs/outputs and events
     AbiArgs,
+    /// Test definition context
+    TestDefinition,
+    /// Lexing the typed parameters of a test
+    TestArgs,
     /// constant context
     Constant,
+    /// Alias context, up to the next definition
+    Alias,
 }
 
 /// ## Lexer
@@ -56,6 +61,14 @@ pub struct Lexer<'a> {
     pub eof_returned: bool,
     /// Current context.
     pub context: Context,
+    /// Whether to accept uppercase and mixed-case opcode mnemonics.
+    pub case_insensitive_opcodes: bool,
+    /// Style warnings collected while lexing.
+    pub warnings: Vec<CompilerWarning>,
+    /// The braces opened in the current macro body, by blocks like `__REPEAT`.
+    pub nested_braces: usize,
+    /// The parentheses opened in the current ABI or test parameters, by tuple types.
+    pub nested_parens: usize,
 }
 
 impl<'a> Lexer<'a> {
@@ -70,9 +83,46 @@ impl<'a> Lexer<'a> {
             eof: false,
             eof_returned: false,
             context: Context::Global,
+            case_insensitive_opcodes: false,
+            warnings: vec![],
+            nested_braces: 0,
+            nested_parens: 0,
         }
     }
 
+    /// Public associated function that instantiates a new lexer accepting uppercase and
+    /// mixed-case opcode mnemonics.
+    pub fn new_case_insensitive(source: FullFileSource<'a>) -> Self {
+        Self { case_insensitive_opcodes: true, ..Lexer::new(source) }
+    }
+
+    /// Lowercases all opcode mnemonics in the source code, leaving everything else untouched.
+    pub fn normalize_opcode_case(source: &str) -> String {
+        let full_source = FullFileSource { source, file: None, spans: vec![] };
+        let mut lexer = Lexer::new_case_insensitive(full_source);
+        lexer.by_ref().for_each(drop);
+        let mut normalized = source.to_string();
+        for span in lexer.warnings.iter().rev().filter_map(|w| w.span.0.first()) {
+            normalized
+                .replace_range(span.start..span.end, &source[span.start..span.end].to_lowercase());
+        }
+        normalized
+    }
+
+    /// Formats the source code canonically, keeping its comments and lowercasing all opcode
+    /// mnemonics.
+    pub fn format_source(source: &str) -> String {
+        let normalized = Lexer::normalize_opcode_case(source);
+        Lexer::syntax_tree(&normalized).format()
+    }
+
+    /// Builds the concrete syntax tree of the source code, keeping its whitespace and comments
+    pub fn syntax_tree(source: &str) -> SyntaxTree {
+        let full_source = FullFileSource { source, file: None, spans: vec![] };
+        let tokens = Lexer::new(full_source).filter_map(Result::ok).collect::<Vec<Token>>();
+        SyntaxTree::new(source, &tokens)
+    }
+
     // `// #include "./Utils.huff"`
     /// Lex all imports
     pub fn lex_imports(source: &str) -> Vec<String> {
@@ -117,24 +167,37 @@ impl<'a> Lexer<'a> {
                         }
                     }
 
-                    // Then we should have an import path between quotes
+                    // Then we should have an import path between quotes, or the path of a
+                    // bundled module between angle brackets, kept with its brackets
                     match peekable_source.peek() {
-                        Some(char) => match char {
-                            '"' | '\'' => {
-                                peekable_source.next();
-                                let mut import = String::new();
-                                while peekable_source.peek().is_some() {
-                                    match peekable_source.next().unwrap() {
-                                        '"' | '\'' => {
-                                            imports.push(import);
-                                            break
-                                        }
-                                        c => import.push(c),
+                        Some('<') => {
+                            let mut import = String::new();
+                            for c in peekable_source.by_ref() {
+                                import.push(c);
+                                match c {
+                                    '>' => {
+                                        imports.push(import);
+                                        break
                                     }
+                                    '\n' => break,
+                                    _ => {}
                                 }
                             }
-                            _ => { /* Ignore non-include tokens */ }
-                        },
+                        }
+                        Some('"') | Some('\'') => {
+                            peekable_source.next();
+                            let mut import = String::new();
+                            while peekable_source.peek().is_some() {
+                                match peekable_source.next().unwrap() {
+                                    '"' | '\'' => {
+                                        imports.push(import);
+                                        break
+                                    }
+                                    c => import.push(c),
+                                }
+                            }
+                        }
+                        Some(_) => { /* Ignore non-include tokens */ }
                         None => { /* EOF */ }
                     }
                 } else if nc.ne(&include_chars_iterator.next().unwrap()) {
@@ -147,12 +210,12 @@ impl<'a> Lexer<'a> {
     }
 
     /// Public associated function that returns a shared reference to the current lexing span.
-    pub fn current_span(&self) -> Ref<Span> {
+    pub fn current_span(&self) -> Ref<'_, Span> {
         self.span.borrow()
     }
 
     /// Public associated function that returns an exclusive reference to the current lexing span.
-    pub fn current_span_mut(&self) -> RefMut<Span> {
+    pub fn current_span_mut(&self) -> RefMut<'_, Span> {
         self.span.borrow_mut()
     }
 
@@ -212,9 +275,8 @@ impl<'a> Lexer<'a> {
 
     /// Consumes the characters
     pub fn consume(&mut self) -> Option<char> {
-        self.chars.next().map(|x| {
+        self.chars.next().inspect(|_| {
             self.current_span_mut().end += 1;
-            x
         })
     }
 
@@ -228,7 +290,7 @@ impl<'a> Lexer<'a> {
     /// Consume characters until a sequence matches
     pub fn seq_consume(&mut self, word: &str) {
         let mut current_pos = self.current_span().start;
-        while self.peek() != None {
+        while self.peek().is_some() {
             let peeked = self.peek_n_chars_from(word.len(), current_pos);
             if word == peeked {
                 break
@@ -253,12 +315,33 @@ impl<'a> Lexer<'a> {
         exclusive_span.start = exclusive_span.end;
     }
 
+    /// Check if a potential opcode matches an opcode mnemonic ignoring case.
+    ///
+    /// Always false unless `case_insensitive_opcodes` is set. To avoid clashing with
+    /// uppercase constants, arguments and macros, a non-lowercase mnemonic is not an opcode
+    /// when it is wrapped in brackets (`[BALANCE]`), angle brackets (`<caller>`), or is part
+    /// of a longer identifier or invocation (`RETURN_DATA()`).
+    pub fn is_case_insensitive_opcode(&mut self, opcode: &str, pot_op: &str) -> bool {
+        if !self.case_insensitive_opcodes || !opcode.eq_ignore_ascii_case(pot_op) {
+            return false
+        }
+        let after_op = self.current_span().start + pot_op.len();
+        let is_identifier = matches!(self.nth_peek(after_op), Some('(') | Some('_'));
+        let is_wrapped = matches!(
+            self.lookback.as_ref().map(|t| &t.kind),
+            Some(TokenKind::OpenBracket) | Some(TokenKind::LeftAngle)
+        );
+        !is_identifier && !is_wrapped
+    }
+
     /// Check if a given keyword follows the keyword rules in the `source`. If not, it is a
     /// `TokenKind::Ident`.
     ///
     /// Rules:
-    /// - The `macro`, `function`, `constant`, `event`, `jumptable`, `jumptable__packed`, and
-    ///   `table` keywords must be preceded by a `#define` keyword.
+    /// - The `macro`, `fallback`, `receive`, `test`, `function`, `constant`, `immutable`, `alias`,
+    ///   `event`, `error`, `jumptable`, `jumptable__packed`, and `table` keywords must be preceded
+    ///   by a `#define` keyword, or by an `internal` modifier.
+    /// - The `internal` keyword must be preceded by a `#define` keyword.
     /// - The `takes` keyword must be preceded by an assignment operator: `=`.
     /// - The `nonpayable`, `payable`, `view`, and `pure` keywords must be preceeded by one of these
     ///   keywords or a close paren.
@@ -267,12 +350,22 @@ impl<'a> Lexer<'a> {
     pub fn check_keyword_rules(&mut self, found_kind: &Option<TokenKind>) -> bool {
         match found_kind {
             Some(TokenKind::Macro) |
+            Some(TokenKind::Fallback) |
+            Some(TokenKind::Receive) |
+            Some(TokenKind::Test) |
             Some(TokenKind::Function) |
             Some(TokenKind::Constant) |
+            Some(TokenKind::Immutable) |
+            Some(TokenKind::Alias) |
             Some(TokenKind::Event) |
+            Some(TokenKind::Error) |
             Some(TokenKind::JumpTable) |
             Some(TokenKind::JumpTablePacked) |
-            Some(TokenKind::CodeTable) => self.checked_lookback(TokenKind::Define),
+            Some(TokenKind::CodeTable) => {
+                self.checked_lookback(TokenKind::Define) ||
+                    self.checked_lookback(TokenKind::Internal)
+            }
+            Some(TokenKind::Internal) => self.checked_lookback(TokenKind::Define),
             Some(TokenKind::NonPayable) |
             Some(TokenKind::Payable) |
             Some(TokenKind::View) |
@@ -305,11 +398,12 @@ impl<'a> Lexer<'a> {
 }
 
 impl<'a> Iterator for Lexer<'a> {
-    type Item = Result<Token, LexicalError<'a>>;
+    type Item = Result<Token, LexicalError>;
 
     /// Iterates over the source code
     fn next(&mut self) -> Option<Self::Item> {
         self.reset();
+        let mut non_lowercase_opcode: Option<&str> = None;
         if let Some(ch) = self.consume() {
             let kind = match ch {
                 // Comments
@@ -352,6 +446,10 @@ impl<'a> Iterator for Lexer<'a> {
                     }
 
                     if let Some(kind) = &found_kind {
+                        // Aliases extend up to the next definition
+                        if self.context == Context::Alias {
+                            self.context = Context::Global;
+                        }
                         kind.clone()
                     } else {
                         // Otherwise we don't support # prefixed indentifiers
@@ -368,11 +466,18 @@ impl<'a> Iterator for Lexer<'a> {
 
                     let keys = [
                         TokenKind::Macro,
+                        TokenKind::Fallback,
+                        TokenKind::Receive,
+                        TokenKind::Test,
                         TokenKind::Function,
                         TokenKind::Constant,
+                        TokenKind::Immutable,
+                        TokenKind::Internal,
+                        TokenKind::Alias,
                         TokenKind::Takes,
                         TokenKind::Returns,
                         TokenKind::Event,
+                        TokenKind::Error,
                         TokenKind::NonPayable,
                         TokenKind::Payable,
                         TokenKind::Indexed,
@@ -408,9 +513,15 @@ impl<'a> Iterator for Lexer<'a> {
 
                     if let Some(kind) = &found_kind {
                         match kind {
-                            TokenKind::Macro => self.context = Context::MacroDefinition,
-                            TokenKind::Function | TokenKind::Event => self.context = Context::Abi,
+                            TokenKind::Macro | TokenKind::Fallback | TokenKind::Receive => {
+                                self.context = Context::MacroDefinition
+                            }
+                            TokenKind::Test => self.context = Context::TestDefinition,
+                            TokenKind::Function | TokenKind::Event | TokenKind::Error => {
+                                self.context = Context::Abi
+                            }
                             TokenKind::Constant => self.context = Context::Constant,
+                            TokenKind::Alias => self.context = Context::Alias,
                             _ => (),
                         }
                     }
@@ -435,7 +546,7 @@ impl<'a> Iterator for Lexer<'a> {
 
                     let potential_label: String =
                         self.dyn_peek(|c| c.is_alphanumeric() || c == &'_' || c == &':');
-                    if let true = potential_label.ends_with(':') {
+                    if potential_label.ends_with(':') {
                         self.dyn_consume(|c| c.is_alphanumeric() || c == &'_');
                         let label = self.slice();
                         if let Some(l) = label.get(0..label.len()) {
@@ -455,15 +566,18 @@ impl<'a> Iterator for Lexer<'a> {
                         self.dyn_consume(|c| c.is_alphabetic());
                     }
 
-                    // goes over all opcodes
+                    // goes over all opcodes, which macro bodies and aliases contain
+                    let in_code = matches!(self.context, Context::MacroBody | Context::Alias);
                     for opcode in OPCODES {
-                        if self.context != Context::MacroBody || found_kind != None {
+                        if !in_code || found_kind.is_some() {
                             break
                         }
-                        if opcode == pot_op {
+                        let non_lowercase = opcode != pot_op;
+                        if !non_lowercase || self.is_case_insensitive_opcode(opcode, &pot_op) {
                             self.dyn_consume(|c| c.is_alphanumeric());
                             if let Some(o) = OPCODES_MAP.get(opcode) {
                                 found_kind = Some(TokenKind::Opcode(o.to_owned()));
+                                non_lowercase_opcode = non_lowercase.then_some(opcode);
                             } else {
                                 tracing::error!(target: "lexer", "[huff_lexer] Fatal Opcode Mapping!");
                             }
@@ -471,9 +585,9 @@ impl<'a> Iterator for Lexer<'a> {
                         }
                     }
 
-                    // Last case ; we are in ABI context and
+                    // Last case ; we are in ABI or test parameters context and
                     // we are parsing an EVM type
-                    if self.context == Context::AbiArgs {
+                    if matches!(self.context, Context::AbiArgs | Context::TestArgs) {
                         let curr_char = self.peek()?;
                         if !['(', ')'].contains(&curr_char) {
                             self.dyn_consume(|c| c.is_alphanumeric() || *c == '[' || *c == ']');
@@ -500,7 +614,7 @@ impl<'a> Iterator for Lexer<'a> {
                                                 .map_err(|_| {
                                                     let err = LexicalError {
                                                         kind: LexicalErrorKind::InvalidArraySize(
-                                                            &words[1],
+                                                            words[1].clone(),
                                                         ),
                                                         span: self.current_span().clone(),
                                                     };
@@ -517,7 +631,9 @@ impl<'a> Iterator for Lexer<'a> {
                                     found_kind = Some(TokenKind::ArrayType(primitive, size_vec));
                                 } else {
                                     let err = LexicalError {
-                                        kind: LexicalErrorKind::InvalidPrimitiveType(&words[0]),
+                                        kind: LexicalErrorKind::InvalidPrimitiveType(
+                                            words[0].clone(),
+                                        ),
                                         span: self.current_span().clone(),
                                     };
                                     tracing::error!(target: "lexer", "{}", format!("{:?}", err));
@@ -538,13 +654,20 @@ impl<'a> Iterator for Lexer<'a> {
                     } else {
                         self.dyn_consume(|c| c.is_alphanumeric() || c.eq(&'_'));
 
+                        // Definitions of namespaced includes are qualified, like `Math.ADD`
+                        let end = self.current_span().end;
+                        if self.nth_peek(end) == Some('.') &&
+                            self.nth_peek(end + 1).is_some_and(|c| c.is_alphabetic() || c == '_')
+                        {
+                            self.consume();
+                            self.dyn_consume(|c| c.is_alphanumeric() || c.eq(&'_'));
+                        }
+
                         let slice = self.slice();
-                        // Check for built-in function calls
-                        if self.context == Context::MacroBody &&
-                            matches!(
-                                slice.as_ref(),
-                                "__codesize" | "__tablesize" | "__tablestart"
-                            )
+                        // Check for built-in function calls, assertions are also top-level
+                        if (self.context == Context::MacroBody &&
+                            BUILTIN_FUNCTIONS.contains(&slice.as_str())) ||
+                            slice == "__ASSERT"
                         {
                             TokenKind::BuiltinFunction(slice)
                         } else {
@@ -558,24 +681,91 @@ impl<'a> Iterator for Lexer<'a> {
                                     // to match `x` in the actual hex)
                     self.dyn_consume(|c| {
                         c.is_numeric() ||
-                            // Match a-f & A-F
-                            matches!(c, '\u{0041}'..='\u{0046}' | '\u{0061}'..='\u{0066}')
+                            // Match a-f & A-F, and underscores separating digits
+                            matches!(c, '\u{0041}'..='\u{0046}' | '\u{0061}'..='\u{0066}' | '_')
                     });
                     self.current_span_mut().start += 2; // Ignore the "0x"
-                    TokenKind::Literal(str_to_bytes32(self.slice().as_ref()))
+                    let digits = self.slice().replace('_', "");
+                    if digits.len() > 64 {
+                        tracing::error!(target: "lexer", "LITERAL EXCEEDS 32 BYTES: 0x{}", digits);
+                        let span = self
+                            .source
+                            .relative_span(self.current_span())
+                            .unwrap_or_else(|| self.current_span().clone());
+                        return Some(Err(LexicalError::new(
+                            LexicalErrorKind::LiteralTooLong(format!("0x{}", self.slice())),
+                            span,
+                        )))
+                    }
+                    TokenKind::Literal(str_to_bytes32(digits.as_ref()))
+                }
+                // If it's the start of a binary literal
+                ch if ch == '0' && self.peek() == Some('b') => {
+                    self.consume(); // Consume the 'b' after '0'
+                    self.dyn_consume(|c| matches!(c, '0' | '1' | '_'));
+                    self.current_span_mut().start += 2; // Ignore the "0b"
+                    let digits = self.slice().replace('_', "");
+                    if digits.is_empty() {
+                        tracing::error!(target: "lexer", "BINARY LITERAL HAS NO DIGITS: 0b{}", self.slice());
+                        let span = self
+                            .source
+                            .relative_span(self.current_span())
+                            .unwrap_or_else(|| self.current_span().clone());
+                        return Some(Err(LexicalError::new(
+                            LexicalErrorKind::EmptyLiteral(format!("0b{}", self.slice())),
+                            span,
+                        )))
+                    }
+                    match bin_str_to_bytes32(&digits) {
+                        Some(literal) => TokenKind::Literal(literal),
+                        None => {
+                            tracing::error!(target: "lexer", "LITERAL EXCEEDS 32 BYTES: 0b{}", self.slice());
+                            let span = self
+                                .source
+                                .relative_span(self.current_span())
+                                .unwrap_or_else(|| self.current_span().clone());
+                            return Some(Err(LexicalError::new(
+                                LexicalErrorKind::LiteralTooLong(format!("0b{}", self.slice())),
+                                span,
+                            )))
+                        }
+                    }
+                }
+                '=' if self.peek() == Some('=') => {
+                    self.consume();
+                    TokenKind::Equal
                 }
                 '=' => TokenKind::Assign,
+                '!' if self.peek() == Some('=') => {
+                    self.consume();
+                    TokenKind::NotEqual
+                }
+                '!' => TokenKind::Not,
+                '&' if self.peek() == Some('&') => {
+                    self.consume();
+                    TokenKind::And
+                }
+                '|' if self.peek() == Some('|') => {
+                    self.consume();
+                    TokenKind::Or
+                }
                 '(' => {
                     match self.context {
                         Context::Abi => self.context = Context::AbiArgs,
+                        Context::TestDefinition => self.context = Context::TestArgs,
                         Context::MacroBody => self.context = Context::MacroArgs,
+                        Context::AbiArgs | Context::TestArgs => self.nested_parens += 1,
                         _ => {}
                     }
                     TokenKind::OpenParen
                 }
                 ')' => {
                     match self.context {
+                        Context::AbiArgs | Context::TestArgs if self.nested_parens > 0 => {
+                            self.nested_parens -= 1
+                        }
                         Context::AbiArgs => self.context = Context::Abi,
+                        Context::TestArgs => self.context = Context::TestDefinition,
                         Context::MacroArgs => self.context = Context::MacroBody,
                         _ => {}
                     }
@@ -584,29 +774,84 @@ impl<'a> Iterator for Lexer<'a> {
                 '[' => TokenKind::OpenBracket,
                 ']' => TokenKind::CloseBracket,
                 '{' => {
-                    if self.context == Context::MacroDefinition {
-                        self.context = Context::MacroBody;
+                    match self.context {
+                        Context::MacroDefinition | Context::TestDefinition => {
+                            self.context = Context::MacroBody
+                        }
+                        Context::MacroBody => self.nested_braces += 1,
+                        _ => {}
                     }
                     TokenKind::OpenBrace
                 }
                 '}' => {
                     if self.context == Context::MacroBody {
-                        self.context = Context::Global;
+                        match self.nested_braces {
+                            0 => self.context = Context::Global,
+                            _ => self.nested_braces -= 1,
+                        }
                     }
                     TokenKind::CloseBrace
                 }
                 '+' => TokenKind::Add,
                 '-' => TokenKind::Sub,
                 '*' => TokenKind::Mul,
+                '<' if self.peek() == Some('=') => {
+                    self.consume();
+                    TokenKind::LessEqual
+                }
+                '>' if self.peek() == Some('=') => {
+                    self.consume();
+                    TokenKind::GreaterEqual
+                }
+                // Bundled modules are included with their path between angle brackets
+                '<' if self.checked_lookback(TokenKind::Include) => {
+                    self.dyn_consume(|c| !matches!(c, '>' | '\n'));
+                    if self.peek() != Some('>') {
+                        tracing::error!(target: "lexer", "UNTERMINATED BUNDLED PATH");
+                        return Some(Err(LexicalError::new(
+                            LexicalErrorKind::UnexpectedEof,
+                            self.current_span().clone(),
+                        )))
+                    }
+                    self.consume();
+                    let path = self.slice();
+                    TokenKind::BundledPath(path[1..path.len() - 1].to_string())
+                }
                 '<' => TokenKind::LeftAngle,
                 '>' => TokenKind::RightAngle,
                 // NOTE: TokenKind::Div is lexed further up since it overlaps with comment
                 ':' => TokenKind::Colon,
                 // identifiers
                 ',' => TokenKind::Comma,
+                // Decimals are literals in code and constants, and numbers elsewhere, eg. in
+                // `takes(n)`, both with underscores separating their digits
                 '0'..='9' => {
-                    self.dyn_consume(char::is_ascii_digit);
-                    TokenKind::Num(self.slice().parse().unwrap())
+                    self.dyn_consume(|c| c.is_ascii_digit() || *c == '_');
+                    let digits = self.slice().replace('_', "");
+                    let in_code = matches!(
+                        self.context,
+                        Context::MacroBody | Context::MacroArgs | Context::Alias | Context::Constant
+                    );
+                    let kind = match in_code {
+                        true => dec_str_to_bytes32(&digits).map(TokenKind::Literal),
+                        false => digits.parse().ok().map(TokenKind::Num).or_else(|| {
+                            dec_str_to_bytes32(&digits).map(TokenKind::Literal)
+                        }),
+                    };
+                    match kind {
+                        Some(kind) => kind,
+                        None => {
+                            tracing::error!(target: "lexer", "NUMBER TOO LARGE: {}", self.slice());
+                            let span = self
+                                .source
+                                .relative_span(self.current_span())
+                                .unwrap_or_else(|| self.current_span().clone());
+                            return Some(Err(LexicalError::new(
+                                LexicalErrorKind::LiteralTooLong(self.slice()),
+                                span,
+                            )))
+                        }
+                    }
                 }
                 // Lexes Spaces and Newlines as Whitespace
                 ch if ch.is_ascii_whitespace() => {
@@ -619,9 +864,11 @@ impl<'a> Iterator for Lexer<'a> {
                         Some('"') => {
                             self.consume();
                             let str = self.slice();
-                            break TokenKind::Str((&str[1..str.len() - 1]).to_string())
+                            break TokenKind::Str(str[1..str.len() - 1].to_string())
                         }
-                        Some('\\') if matches!(self.nth_peek(1), Some('\\') | Some('"')) => {
+                        Some('\\')
+                            if matches!(self.chars.clone().nth(1), Some('\\') | Some('"')) =>
+                        {
                             self.consume();
                         }
                         Some(_) => {}
@@ -642,9 +889,11 @@ impl<'a> Iterator for Lexer<'a> {
                         Some('\'') => {
                             self.consume();
                             let str = self.slice();
-                            break TokenKind::Str((&str[1..str.len() - 1]).to_string())
+                            break TokenKind::Str(str[1..str.len() - 1].to_string())
                         }
-                        Some('\\') if matches!(self.nth_peek(1), Some('\\') | Some('\'')) => {
+                        Some('\\')
+                            if matches!(self.chars.clone().nth(1), Some('\\') | Some('\'')) =>
+                        {
                             self.consume();
                         }
                         Some(_) => {}
@@ -681,6 +930,15 @@ impl<'a> Iterator for Lexer<'a> {
                     self.current_span().clone()
                 }
             };
+            if let Some(opcode) = non_lowercase_opcode {
+                tracing::warn!(target: "lexer", "NON-LOWERCASE OPCODE \"{}\"", self.slice());
+                let mut warning = CompilerWarning::new(
+                    WarningKind::NonLowercaseOpcode(self.slice().to_string(), opcode.to_string()),
+                    AstSpan(vec![new_span.clone()]),
+                );
+                warning.fix = Some(Fix { span: new_span.clone(), replacement: opcode.to_string() });
+                self.warnings.push(warning);
+            }
             let token = Token { kind, span: new_span };
             if token.kind != TokenKind::Whitespace {
                 self.lookback = Some(token.clone());
@@ -4,7 +4,8 @@ use std::ops::Deref;
 
 #[test]
 fn parses_builtin_function_in_macro_body() {
-    let builtin_funcs = ["__codesize", "__tablesize", "__tablestart"];
+    let builtin_funcs =
+        ["__codesize", "__tablesize", "__tablestart", "__ASSERT_CODEHASH", "__KECCAK256"];
 
     for builtin in builtin_funcs {
         let source = &format!(
@@ -69,7 +70,8 @@ fn parses_builtin_function_in_macro_body() {
 #[test]
 #[should_panic]
 fn fails_to_parse_builtin_outside_macro_body() {
-    let builtin_funcs = ["__codesize", "__tablesize", "__tablestart"];
+    let builtin_funcs =
+        ["__codesize", "__tablesize", "__tablestart", "__ASSERT_CODEHASH", "__KECCAK256"];
 
     for builtin in builtin_funcs {
         let source = &format!("{}(MAIN)", builtin);
@@ -95,3 +97,18 @@ fn fails_to_parse_builtin_outside_macro_body() {
         assert!(lexer.eof);
     }
 }
+
+#[test]
+fn parses_escaped_string_builtin_argument() {
+    let source = r#"__KECCAK256("say \"hi\" \\") __KECCAK256('it\'s')"#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let strings = lexer
+        .into_iter()
+        .filter_map(|t| match t.unwrap().kind {
+            TokenKind::Str(s) => Some(s),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(strings, [r#"say \"hi\" \\"#, r#"it\'s"#]);
+}
@@ -0,0 +1,113 @@
+use huff_lexer::*;
+use huff_utils::prelude::*;
+
+fn syntax_tree(source: &str) -> SyntaxTree {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let tokens = Lexer::new(flattened_source).filter_map(Result::ok).collect::<Vec<Token>>();
+    SyntaxTree::new(source, &tokens)
+}
+
+const SOURCE: &str = r#"#include "./Ownable.huff"
+
+/* Storage */
+#define constant OWNER = FREE_STORAGE_POINTER()
+
+// Returns the owner
+// of the contract
+#define macro GET_OWNER() = takes (0) returns (1) {
+    [OWNER] sload   // [owner]
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    GET_OWNER() 0x00 mstore
+    0x20 0x00 return
+}
+"#;
+
+#[test]
+fn renders_source_losslessly() {
+    let tree = syntax_tree(SOURCE);
+    assert_eq!(tree.to_string(), SOURCE);
+    assert_eq!(
+        tree.nodes.iter().map(|n| (n.kind, n.name())).collect::<Vec<_>>(),
+        vec![
+            (NodeKind::Include, None),
+            (NodeKind::Constant, Some("OWNER")),
+            (NodeKind::Macro, Some("GET_OWNER")),
+            (NodeKind::Macro, Some("MAIN")),
+        ]
+    );
+    assert_eq!(tree.trailing.len(), 1);
+
+    // Hex literals keep their prefix
+    assert!(tree
+        .tokens()
+        .iter()
+        .any(|t| t.text == "0x20" && matches!(t.kind, TokenKind::Literal(_))));
+
+    // Comments are kept as trivia
+    let close = tree.tokens().into_iter().find(|t| t.text == "}").unwrap();
+    assert_eq!(close.leading[1].kind, TriviaKind::Comment);
+    assert_eq!(close.leading[1].text, "// [owner]");
+}
+
+#[test]
+fn nests_tokens_by_delimiters() {
+    let tree = syntax_tree(SOURCE);
+    let body = match tree.nodes[2].elements.last().unwrap() {
+        SyntaxElement::Group(g) => g,
+        e => panic!("expected the macro body, found {:?}", e),
+    };
+    assert_eq!(body.delimiter, Delimiter::Brace);
+    assert!(body.close.is_some());
+    match &body.elements[0] {
+        SyntaxElement::Group(g) => {
+            assert_eq!(g.delimiter, Delimiter::Bracket);
+            assert_eq!(g.elements.len(), 1);
+        }
+        e => panic!("expected the constant reference, found {:?}", e),
+    }
+}
+
+#[test]
+fn definition_comments() {
+    let tree = syntax_tree(SOURCE);
+    let comments =
+        |i: usize| tree.nodes[i].comments().iter().map(|c| c.text.clone()).collect::<Vec<String>>();
+    assert_eq!(comments(1), vec!["/* Storage */"]);
+    assert_eq!(comments(2), vec!["// Returns the owner", "// of the contract"]);
+    assert!(comments(3).is_empty());
+}
+
+#[test]
+fn keeps_invalid_source() {
+    // An unclosed macro body and a character the lexer rejects
+    let source = "#define macro A() = takes(0) returns(0) {\n    0x00 $\n#define macro B() = takes(0) returns(0) {}\n";
+    let tree = syntax_tree(source);
+    assert_eq!(tree.to_string(), source);
+    assert_eq!(tree.nodes.len(), 2);
+
+    let body = match tree.nodes[0].elements.last().unwrap() {
+        SyntaxElement::Group(g) => g,
+        e => panic!("expected the macro body, found {:?}", e),
+    };
+    assert!(body.close.is_none());
+    assert!(tree.nodes[1].tokens()[0].leading.iter().any(|t| t.kind == TriviaKind::Skipped));
+}
+
+#[test]
+fn splits_top_level_assertions() {
+    let source = "#define constant MAX = 0x02\n__ASSERT([MAX] > 0x01)\n#define macro MAIN() = takes(0) returns(0) {\n    __ASSERT([MAX])\n}\n";
+    let tree = syntax_tree(source);
+    assert_eq!(tree.to_string(), source);
+
+    // Assertions in macro bodies stay in their macro
+    assert_eq!(
+        tree.nodes.iter().map(|n| (n.kind, n.name())).collect::<Vec<_>>(),
+        vec![
+            (NodeKind::Constant, Some("MAX")),
+            (NodeKind::Assertion, None),
+            (NodeKind::Macro, Some("MAIN")),
+        ]
+    );
+}
@@ -23,3 +23,24 @@ fn end_of_file() {
     assert!(lexer.eof);
     assert!(lexer.next().is_none());
 }
+
+#[test]
+fn errors_outlive_the_source() {
+    // Lexical errors own their payloads, so they can be kept after the source is dropped
+    let errors: Vec<CompilerError> = {
+        let source = "0x01 $".to_string();
+        let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+        Lexer::new(flattened_source)
+            .filter_map(|t| t.err())
+            .map(CompilerError::LexicalError)
+            .collect()
+    };
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0],
+        CompilerError::LexicalError(LexicalError {
+            kind: LexicalErrorKind::InvalidCharacter('$'),
+            ..
+        })
+    ));
+}
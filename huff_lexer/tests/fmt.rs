@@ -0,0 +1,237 @@
+use huff_lexer::Lexer;
+
+const UNFORMATTED: &str = r#"// SPDX-License-Identifier: MIT
+
+#include "./Utils.huff"
+#include   "./Ownable.huff"
+
+/* Storage */
+#define constant OWNER=FREE_STORAGE_POINTER()   // slot 0
+#define function owner( ) view returns(address)
+#define event OwnerUpdated(address indexed,address)
+
+
+/// Returns the owner
+#define macro GET_OWNER( ) = takes(0)returns(1) {
+[OWNER] SLOAD // [owner]
+  0x00 mstore    // []
+
+        0x20 0x00 return
+}
+
+#define macro CHECK(error) = takes (1) returns (0) {
+  // the caller must be the owner
+  caller eq ok jumpi
+  <error> 0x00 revert // revert
+  ok:
+  // continue
+  GET_OWNER() pop
+}
+
+#define macro EMPTY() = takes(0) returns(0) {
+}
+
+#define jumptable TABLE {
+ok ok
+}
+"#;
+
+const FORMATTED: &str = r#"// SPDX-License-Identifier: MIT
+
+#include "./Ownable.huff"
+#include "./Utils.huff"
+
+/* Storage */
+#define constant OWNER = FREE_STORAGE_POINTER() // slot 0
+#define function owner() view returns (address)
+#define event OwnerUpdated(address indexed,address)
+
+/// Returns the owner
+#define macro GET_OWNER() = takes (0) returns (1) {
+    [OWNER] sload    // [owner]
+    0x00 mstore      // []
+
+    0x20 0x00 return
+}
+
+#define macro CHECK(error) = takes (1) returns (0) {
+    // the caller must be the owner
+    caller eq ok jumpi
+    <error> 0x00 revert    // revert
+    ok:
+        // continue
+        GET_OWNER() pop
+}
+
+#define macro EMPTY() = takes (0) returns (0) {}
+
+#define jumptable TABLE {
+    ok ok
+}
+"#;
+
+#[test]
+fn formats_source_canonically() {
+    assert_eq!(Lexer::format_source(UNFORMATTED), FORMATTED);
+}
+
+#[test]
+fn formatting_is_idempotent() {
+    assert_eq!(Lexer::format_source(FORMATTED), FORMATTED);
+}
+
+#[test]
+fn wraps_long_macro_signatures() {
+    let source = "#define macro TRANSFER_FROM(from_address, to_address, amount, allowance_slot, balance_slot) = takes(0) returns(0) {\n    <from_address> <to_address>\n}\n";
+    let formatted = Lexer::format_source(source);
+    assert_eq!(
+        formatted,
+        r#"#define macro TRANSFER_FROM(
+    from_address,
+    to_address,
+    amount,
+    allowance_slot,
+    balance_slot
+) = takes (0) returns (0) {
+    <from_address> <to_address>
+}
+"#
+    );
+    assert_eq!(Lexer::format_source(&formatted), formatted);
+}
+
+#[test]
+fn keeps_unformattable_definitions() {
+    // Comments inside a header and characters the lexer rejects
+    let source = "#define macro A(/* none */) = takes(0) returns(0) {\n  0x00   $\n}\n#define macro B() = takes(0) returns(0) {\n 0x01\n}\n";
+    assert_eq!(
+        Lexer::format_source(source),
+        "#define macro A(/* none */) = takes(0) returns(0) {\n  0x00   $\n}\n#define macro B() = takes (0) returns (0) {\n    0x01\n}\n"
+    );
+}
+
+#[test]
+fn formats_aliases() {
+    let source =
+        "#define alias   fmp=0x40   MLOAD\n#define alias owner = [OWNER]\n    sload // [owner]\n";
+    let formatted = Lexer::format_source(source);
+    assert_eq!(
+        formatted,
+        "#define alias fmp = 0x40 mload\n#define alias owner = [OWNER] sload // [owner]\n"
+    );
+    assert_eq!(Lexer::format_source(&formatted), formatted);
+}
+
+#[test]
+fn drops_trailing_commas() {
+    let source = "#define function transfer(address,uint256,) nonpayable returns(bool,)\n#define macro FOO(a, b,) = takes(0) returns(0) {\n    <a> <b>\n}\n#define macro TRANSFER_FROM(from_address, to_address, amount, allowance_slot, balance_slot,) = takes(0) returns(0) {}\n";
+    let formatted = Lexer::format_source(source);
+    assert_eq!(
+        formatted,
+        r#"#define function transfer(address,uint256) nonpayable returns (bool)
+#define macro FOO(a, b) = takes (0) returns (0) {
+    <a> <b>
+}
+#define macro TRANSFER_FROM(
+    from_address,
+    to_address,
+    amount,
+    allowance_slot,
+    balance_slot
+) = takes (0) returns (0) {}
+"#
+    );
+    assert_eq!(Lexer::format_source(&formatted), formatted);
+}
+
+#[test]
+fn formats_tuple_types() {
+    let source = "#define function fill( (address, (uint256,bool) ) [2] orders) nonpayable returns ()\n";
+    let formatted = Lexer::format_source(source);
+    assert_eq!(
+        formatted,
+        "#define function fill((address,(uint256,bool))[2] orders) nonpayable returns ()\n"
+    );
+    assert_eq!(Lexer::format_source(&formatted), formatted);
+}
+
+#[test]
+fn formats_tests() {
+    let source = "#define test   TEST_ADD( uint256 a,uint256 b )={\n0x00 calldataload\n  pop }\n";
+    let formatted = Lexer::format_source(source);
+    assert_eq!(
+        formatted,
+        "#define test TEST_ADD(uint256 a, uint256 b) = {\n    0x00 calldataload\n    pop\n}\n"
+    );
+    assert_eq!(Lexer::format_source(&formatted), formatted);
+}
+
+#[test]
+fn formats_storage_reservations() {
+    let source = "#define constant BALANCES=FREE_STORAGE_POINTER( 4 )\n";
+    let formatted = Lexer::format_source(source);
+    assert_eq!(formatted, "#define constant BALANCES = FREE_STORAGE_POINTER(4)\n");
+    assert_eq!(Lexer::format_source(&formatted), formatted);
+}
+
+#[test]
+fn formats_immutables() {
+    let source = "#define macro CONSTRUCTOR() = takes(0) returns(0) {\ncaller __SET_IMMUTABLE( OWNER )\n}\n#define   immutable   OWNER\n";
+    let formatted = Lexer::format_source(source);
+    assert_eq!(
+        formatted,
+        "#define macro CONSTRUCTOR() = takes (0) returns (0) {\n    caller __SET_IMMUTABLE(OWNER)\n}\n#define immutable OWNER\n"
+    );
+    assert_eq!(Lexer::format_source(&formatted), formatted);
+}
+
+#[test]
+fn formats_assertions() {
+    let source = "__ASSERT( [A]<[B]*0x02&&!( __tablesize(T)==0x40 ) )\n#define macro MAIN() = takes(0) returns(0) {\n__ASSERT(<a>!=0x00)\n}\n";
+    let formatted = Lexer::format_source(source);
+    assert_eq!(
+        formatted,
+        "__ASSERT([A] < [B] * 0x02 && !(__tablesize(T) == 0x40))\n#define macro MAIN() = takes (0) returns (0) {\n    __ASSERT(<a> != 0x00)\n}\n"
+    );
+    assert_eq!(Lexer::format_source(&formatted), formatted);
+}
+
+#[test]
+fn indents_repeat_blocks() {
+    let source = "#define macro MAIN() = takes(0) returns(0) {\n  __REPEAT(0x02, I) {\n  [I] pop // []\n    __REPEAT(3) { 0x00 }\n}\n stop\n}\n";
+    let formatted = Lexer::format_source(source);
+    assert_eq!(
+        formatted,
+        r#"#define macro MAIN() = takes (0) returns (0) {
+    __REPEAT(0x02, I) {
+        [I] pop    // []
+        __REPEAT(3) { 0x00 }
+    }
+    stop
+}
+"#
+    );
+    assert_eq!(Lexer::format_source(&formatted), formatted);
+}
+
+#[test]
+fn formats_parameter_defaults() {
+    let source = "#define macro REQUIRE(value,err=fail,code =[ERR]) = takes(0) returns(0) {\n<value> <err> jumpi\n}\n";
+    let formatted = Lexer::format_source(source);
+    assert_eq!(
+        formatted,
+        "#define macro REQUIRE(value, err = fail, code = [ERR]) = takes (0) returns (0) {\n    <value> <err> jumpi\n}\n"
+    );
+    assert_eq!(Lexer::format_source(&formatted), formatted);
+}
+
+#[test]
+fn formats_internal_definitions() {
+    let source = "#define  internal constant FEE=0x01\n#define internal macro ADD_FEE() = takes(1) returns(1) {\n[FEE] add\n}\n";
+    let formatted = Lexer::format_source(source);
+    assert_eq!(
+        formatted,
+        "#define internal constant FEE = 0x01\n#define internal macro ADD_FEE() = takes (1) returns (1) {\n    [FEE] add\n}\n"
+    );
+    assert_eq!(Lexer::format_source(&formatted), formatted);
+}
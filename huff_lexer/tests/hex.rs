@@ -60,6 +60,19 @@ fn parses_odd_len_hex() {
     assert!(lexer.eof);
 }
 
+#[test]
+fn parses_hex_with_underscores() {
+    let source = "0xDE_AD_BE_EF";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let mut lexer = Lexer::new(flattened_source);
+    let tok = lexer.next().unwrap().unwrap();
+    assert_eq!(
+        tok,
+        Token::new(TokenKind::Literal(str_to_bytes32("deadbeef")), Span::new(2..13, None))
+    );
+    assert!(lexer.eof);
+}
+
 // TODO: This doesn't exactly belong here.
 #[test]
 fn converts_literal_to_hex_string() {
@@ -76,3 +89,71 @@ fn converts_literal_to_hex_string() {
         assert_eq!(format!("0x{}", source), bytes32_to_string(&str_to_bytes32(source), true));
     }
 }
+
+#[test]
+fn fails_to_lex_hex_longer_than_32_bytes() {
+    let digits = "ff".repeat(33);
+    let source = format!("0x{} 0x{}", "ff".repeat(32), digits);
+    let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+    let mut lexer = Lexer::new(flattened_source);
+
+    // 32 bytes still fit in a literal
+    let tok = lexer.next().unwrap().unwrap();
+    assert_eq!(tok.kind, TokenKind::Literal([0xff; 32]));
+    lexer.next(); // whitespace
+
+    let err = lexer.next().unwrap().unwrap_err();
+    assert_eq!(err.kind, LexicalErrorKind::LiteralTooLong(format!("0x{}", digits)));
+    assert_eq!(err.span, Span::new(69..source.len(), None));
+}
+
+#[test]
+fn parses_binary_literals() {
+    let source = "0b1010 0b1111_0000_1";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap())
+        .filter(|x| !matches!(x.kind, TokenKind::Whitespace | TokenKind::Eof))
+        .collect::<Vec<Token>>();
+    assert_eq!(
+        tokens,
+        vec![
+            Token::new(TokenKind::Literal(str_to_bytes32("0a")), Span::new(2..6, None)),
+            Token::new(TokenKind::Literal(str_to_bytes32("01e1")), Span::new(9..20, None)),
+        ]
+    );
+}
+
+#[test]
+fn fails_to_lex_binary_without_digits() {
+    let source = "0b 0b__";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let mut lexer = Lexer::new(flattened_source);
+
+    let err = lexer.next().unwrap().unwrap_err();
+    assert_eq!(err.kind, LexicalErrorKind::EmptyLiteral("0b".to_string()));
+    assert_eq!(err.span, Span::new(2..2, None));
+    assert_eq!(err.kind.code(), "H0106");
+    lexer.next(); // whitespace
+
+    let err = lexer.next().unwrap().unwrap_err();
+    assert_eq!(err.kind, LexicalErrorKind::EmptyLiteral("0b__".to_string()));
+}
+
+#[test]
+fn fails_to_lex_binary_longer_than_32_bytes() {
+    let source = format!("0b{} 0b{}", "1".repeat(256), "1".repeat(257));
+    let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+    let mut lexer = Lexer::new(flattened_source);
+
+    // 256 digits still fit in a literal
+    let tok = lexer.next().unwrap().unwrap();
+    assert_eq!(tok.kind, TokenKind::Literal([0xff; 32]));
+    lexer.next(); // whitespace
+
+    let err = lexer.next().unwrap().unwrap_err();
+    assert_eq!(err.kind, LexicalErrorKind::LiteralTooLong(format!("0b{}", "1".repeat(257))));
+    assert_eq!(err.span, Span::new(261..source.len(), None));
+}
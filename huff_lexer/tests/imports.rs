@@ -181,3 +181,64 @@ fn include_with_string_single_quote() {
     assert_eq!(lexer.current_span().end, source.len());
     assert!(lexer.eof);
 }
+
+#[test]
+fn lex_bundled_imports() {
+    let source = r#"
+    #include <std/SafeMath.huff>
+    #include "./utils/Ownable.huff"
+    "#;
+    let lexed_imports = Lexer::lex_imports(source);
+    assert_eq!(lexed_imports, vec!["<std/SafeMath.huff>", "./utils/Ownable.huff"]);
+}
+
+#[test]
+fn include_with_bundled_path() {
+    let source = "#include <std/SafeMath.huff>";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap())
+        .filter(|x| !matches!(x.kind, TokenKind::Whitespace))
+        .collect::<Vec<Token>>();
+    assert_eq!(
+        tokens,
+        vec![
+            Token::new(TokenKind::Include, Span::new(0..8, None)),
+            Token::new(
+                TokenKind::BundledPath("std/SafeMath.huff".to_string()),
+                Span::new(9..28, None)
+            ),
+            Token::new(TokenKind::Eof, Span::new(28..28, None)),
+        ]
+    );
+
+    // An unterminated path fails to lex
+    let source = "#include <std/SafeMath.huff\n";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    assert!(lexer.into_iter().any(|x| x.is_err()));
+}
+
+#[test]
+fn include_with_namespace() {
+    let source = "#include \"./Math.huff\" as Math\n#define macro MAIN() = takes(0) returns(0) {\n    [Math.FEE] Math.ADD()\n}";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap())
+        .filter(|x| !matches!(x.kind, TokenKind::Whitespace))
+        .collect::<Vec<Token>>();
+    assert_eq!(tokens[2], Token::new(TokenKind::Ident("as".to_string()), Span::new(23..25, None)));
+    assert_eq!(
+        tokens[3],
+        Token::new(TokenKind::Ident("Math".to_string()), Span::new(26..30, None))
+    );
+
+    // Qualified names are single identifiers
+    let kinds = tokens.iter().map(|t| t.kind.clone()).collect::<Vec<TokenKind>>();
+    assert!(kinds.contains(&TokenKind::Ident("Math.FEE".to_string())));
+    assert!(kinds.contains(&TokenKind::Ident("Math.ADD".to_string())));
+}
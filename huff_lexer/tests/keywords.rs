@@ -649,3 +649,102 @@ fn parses_include_with_extra_suffix() {
     assert_eq!(unwrapped, Token::new(TokenKind::Include, span.clone()));
     assert_eq!(lexer.current_span().deref(), &span);
 }
+
+#[test]
+fn parses_entrypoint_keywords() {
+    for (keyword, kind) in [("fallback", TokenKind::Fallback), ("receive", TokenKind::Receive)] {
+        let source = format!("#define {}() fallback jump", keyword);
+        let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer
+            .into_iter()
+            .map(|x| x.unwrap().kind)
+            .filter(|k| *k != TokenKind::Whitespace)
+            .collect::<Vec<TokenKind>>();
+
+        // Only keywords following a `#define` are entrypoints, labels may share their names
+        assert_eq!(tokens[1], kind);
+        assert_eq!(tokens[4], TokenKind::Ident("fallback".to_string()));
+    }
+}
+
+#[test]
+fn parses_alias_keyword() {
+    let source = "#define alias fmp = 0x40 mload\n#define macro mstore() = takes(0) returns(0) {}";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap().kind)
+        .filter(|k| *k != TokenKind::Whitespace)
+        .collect::<Vec<TokenKind>>();
+
+    // Aliases contain opcodes up to the next definition
+    assert_eq!(
+        tokens[..6],
+        [
+            TokenKind::Define,
+            TokenKind::Alias,
+            TokenKind::Ident("fmp".to_string()),
+            TokenKind::Assign,
+            TokenKind::Literal(str_to_bytes32("40")),
+            TokenKind::Opcode(Opcode::Mload),
+        ]
+    );
+    assert_eq!(tokens[7], TokenKind::Macro);
+    assert_eq!(tokens[8], TokenKind::Ident("mstore".to_string()));
+}
+
+#[test]
+fn parses_test_keyword_and_parameter_types() {
+    let source = "#define test TEST_ADD(uint256 a, bool[] flags) = { test }";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap().kind)
+        .filter(|k| *k != TokenKind::Whitespace)
+        .collect::<Vec<TokenKind>>();
+
+    assert_eq!(tokens[1], TokenKind::Test);
+    assert_eq!(tokens[4], TokenKind::PrimitiveType(PrimitiveEVMType::Uint(256)));
+    assert_eq!(tokens[5], TokenKind::Ident("a".to_string()));
+    assert_eq!(tokens[7], TokenKind::ArrayType(PrimitiveEVMType::Bool, vec![0]));
+    // The keyword is an identifier in the body
+    assert_eq!(tokens[12], TokenKind::Ident("test".to_string()));
+}
+
+#[test]
+fn parses_immutable_keyword() {
+    let source = "#define immutable OWNER\n#define macro immutable() = takes(0) returns(0) {}";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap().kind)
+        .filter(|k| *k != TokenKind::Whitespace)
+        .collect::<Vec<TokenKind>>();
+
+    assert_eq!(tokens[1], TokenKind::Immutable);
+    assert_eq!(tokens[2], TokenKind::Ident("OWNER".to_string()));
+    // The keyword is an identifier when not following `#define`
+    assert_eq!(tokens[5], TokenKind::Ident("immutable".to_string()));
+}
+
+#[test]
+fn parses_internal_keyword() {
+    let source = "#define internal macro HELPER() = takes(0) returns(0) {}\n#define constant internal = 0x01";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap().kind)
+        .filter(|k| *k != TokenKind::Whitespace)
+        .collect::<Vec<TokenKind>>();
+
+    assert_eq!(tokens[1], TokenKind::Internal);
+    assert_eq!(tokens[2], TokenKind::Macro);
+    assert_eq!(tokens[3], TokenKind::Ident("HELPER".to_string()));
+    // The keyword is an identifier when not following `#define`
+    assert_eq!(tokens[19], TokenKind::Ident("internal".to_string()));
+}
@@ -33,3 +33,46 @@ fn lexes_large_numbers() {
     assert_eq!(lexer.current_span().end, source.len());
     assert!(lexer.eof);
 }
+
+#[test]
+fn lexes_decimal_literals_in_code_and_constants() {
+    let source = "#define constant SUPPLY = 1_000_000\n#define macro MAIN() = takes(0) returns(0) { 256 }";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let kinds = lexer
+        .into_iter()
+        .map(|x| x.unwrap().kind)
+        .filter(|k| matches!(k, TokenKind::Literal(_) | TokenKind::Num(_)))
+        .collect::<Vec<TokenKind>>();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Literal(str_to_bytes32("0f4240")),
+            TokenKind::Num(0),
+            TokenKind::Num(0),
+            TokenKind::Literal(str_to_bytes32("0100")),
+        ]
+    );
+}
+
+#[test]
+fn lexes_underscores_in_numbers() {
+    let source = "1_000";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let mut lexer = Lexer::new(flattened_source);
+    let tok = lexer.next().unwrap().unwrap();
+    assert_eq!(tok, Token::new(TokenKind::Num(1000), Span::new(0..5, None)));
+}
+
+#[test]
+fn fails_to_lex_decimals_longer_than_32_bytes() {
+    // The largest word still fits
+    let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+    let source = format!("#define constant MAX = {}\n#define constant OVER = {}6", max, max);
+    let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let results = lexer.into_iter().collect::<Vec<_>>();
+    assert!(results.iter().any(|r| matches!(r, Ok(t) if t.kind == TokenKind::Literal([0xff; 32]))));
+    let err = results.into_iter().find_map(Result::err).unwrap();
+    assert_eq!(err.kind, LexicalErrorKind::LiteralTooLong(format!("{}6", max)));
+}
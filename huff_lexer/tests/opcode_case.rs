@@ -0,0 +1,73 @@
+use huff_lexer::Lexer;
+use huff_utils::prelude::{FullFileSource, Opcode, Token, TokenKind, WarningKind};
+
+fn lex(source: &str, case_insensitive: bool) -> (Vec<TokenKind>, Lexer<'_>) {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let mut lexer = Lexer::new(flattened_source);
+    lexer.case_insensitive_opcodes = case_insensitive;
+    let kinds = lexer
+        .by_ref()
+        .map(|x| x.unwrap())
+        .map(|t: Token| t.kind)
+        .filter(|k| !matches!(k, TokenKind::Whitespace | TokenKind::Eof))
+        .collect::<Vec<TokenKind>>();
+    (kinds, lexer)
+}
+
+#[test]
+fn uppercase_opcodes_are_idents_by_default() {
+    let source = "#define macro MAIN() = takes(0) returns(0) { 0x00 MSTORE }";
+    let (kinds, lexer) = lex(source, false);
+    assert!(kinds.contains(&TokenKind::Ident("MSTORE".to_string())));
+    assert!(lexer.warnings.is_empty());
+}
+
+#[test]
+fn case_insensitive_opcodes_are_lexed_with_warnings() {
+    let source = "#define macro MAIN() = takes(0) returns(0) { CallDataLoad MSTORE add }";
+    let (kinds, lexer) = lex(source, true);
+    assert!(kinds.contains(&TokenKind::Opcode(Opcode::Calldataload)));
+    assert!(kinds.contains(&TokenKind::Opcode(Opcode::Mstore)));
+    assert!(kinds.contains(&TokenKind::Opcode(Opcode::Add)));
+
+    // Only the non-lowercase mnemonics are linted
+    assert_eq!(lexer.warnings.len(), 2);
+    assert_eq!(
+        lexer.warnings[0].kind,
+        WarningKind::NonLowercaseOpcode("CallDataLoad".to_string(), "calldataload".to_string())
+    );
+    assert!(lexer.warnings.iter().all(|w| w.kind.code() == "H0501"));
+    let first = &lexer.warnings[0].span.0[0];
+    assert_eq!(&source[first.start..first.end], "CallDataLoad");
+}
+
+#[test]
+fn case_insensitive_opcodes_skip_constants_args_and_invocations() {
+    let source = "#define macro MAIN() = takes(0) returns(0) { [BALANCE] <CALLER> RETURN() }";
+    let (kinds, lexer) = lex(source, true);
+    assert!(kinds.contains(&TokenKind::Ident("BALANCE".to_string())));
+    assert!(kinds.contains(&TokenKind::Ident("CALLER".to_string())));
+    assert!(kinds.contains(&TokenKind::Ident("RETURN".to_string())));
+    assert!(lexer.warnings.is_empty());
+}
+
+#[test]
+fn normalizes_opcode_case() {
+    let source = r#"
+    #define constant BALANCE = FREE_STORAGE_POINTER()
+    #define macro MAIN() = takes(0) returns(0) {
+        // MSTORE in a comment is untouched
+        [BALANCE] SLOAD 0x00 MStore
+        RETURN_DATA() Stop
+    }
+    "#;
+    let expected = r#"
+    #define constant BALANCE = FREE_STORAGE_POINTER()
+    #define macro MAIN() = takes(0) returns(0) {
+        // MSTORE in a comment is untouched
+        [BALANCE] sload 0x00 mstore
+        RETURN_DATA() stop
+    }
+    "#;
+    assert_eq!(Lexer::normalize_opcode_case(source), expected);
+}
@@ -281,3 +281,32 @@ fn lexes_comma_sparse() {
     assert_eq!(lexer.current_span().end, source.len());
     assert!(lexer.eof);
 }
+
+#[test]
+fn lexes_comparison_and_logical_ops() {
+    let source = "== != <= >= && || ! < >";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap())
+        .filter(|t| !matches!(t.kind, TokenKind::Whitespace | TokenKind::Eof))
+        .collect::<Vec<Token>>();
+
+    let expected = vec![
+        (TokenKind::Equal, 0..2),
+        (TokenKind::NotEqual, 3..5),
+        (TokenKind::LessEqual, 6..8),
+        (TokenKind::GreaterEqual, 9..11),
+        (TokenKind::And, 12..14),
+        (TokenKind::Or, 15..17),
+        (TokenKind::Not, 18..19),
+        (TokenKind::LeftAngle, 20..21),
+        (TokenKind::RightAngle, 22..23),
+    ];
+    let expected = expected
+        .into_iter()
+        .map(|(kind, range)| Token::new(kind, Span::new(range, None)))
+        .collect::<Vec<Token>>();
+    assert_eq!(tokens, expected);
+}
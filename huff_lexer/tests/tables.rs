@@ -12,7 +12,7 @@ fn parses_jump_table() {
         .filter(|x| !matches!(x.kind, TokenKind::Whitespace))
         .collect::<Vec<Token>>();
 
-    assert_eq!(tokens.get(0).unwrap().kind, TokenKind::Define);
+    assert_eq!(tokens.first().unwrap().kind, TokenKind::Define);
     assert_eq!(tokens.get(1).unwrap().kind, TokenKind::JumpTable);
     assert_eq!(tokens.get(2).unwrap().kind, TokenKind::Ident(String::from("JUMP_TABLE")));
     assert_eq!(tokens.get(3).unwrap().kind, TokenKind::OpenParen);
@@ -30,7 +30,7 @@ fn parses_packed_jump_table() {
         .filter(|x| !matches!(x.kind, TokenKind::Whitespace))
         .collect::<Vec<Token>>();
 
-    assert_eq!(tokens.get(0).unwrap().kind, TokenKind::Define);
+    assert_eq!(tokens.first().unwrap().kind, TokenKind::Define);
     assert_eq!(tokens.get(1).unwrap().kind, TokenKind::JumpTablePacked);
     assert_eq!(tokens.get(2).unwrap().kind, TokenKind::Ident(String::from("JUMP_TABLE_PACKED")));
     assert_eq!(tokens.get(3).unwrap().kind, TokenKind::OpenParen);
@@ -48,7 +48,7 @@ fn parses_code_table() {
         .filter(|x| !matches!(x.kind, TokenKind::Whitespace))
         .collect::<Vec<Token>>();
 
-    assert_eq!(tokens.get(0).unwrap().kind, TokenKind::Define);
+    assert_eq!(tokens.first().unwrap().kind, TokenKind::Define);
     assert_eq!(tokens.get(1).unwrap().kind, TokenKind::CodeTable);
     assert_eq!(tokens.get(2).unwrap().kind, TokenKind::Ident(String::from("CODE_TABLE")));
     assert_eq!(tokens.get(3).unwrap().kind, TokenKind::OpenParen);
@@ -0,0 +1,189 @@
+//! ## Completion
+//!
+//! Completion candidates at a position of a huff source buffer.
+
+use crate::document::Document;
+use huff_lexer::Lexer;
+use huff_utils::prelude::*;
+use serde_json::{json, Value};
+
+/// The kind of a completion candidate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CompletionKind {
+    /// A parameter of the enclosing macro
+    Parameter,
+    /// A constant
+    Constant,
+    /// A macro or function macro
+    Macro,
+    /// A builtin function
+    Builtin,
+    /// An opcode
+    Opcode,
+}
+
+impl CompletionKind {
+    /// The LSP `CompletionItemKind` number
+    pub fn lsp(&self) -> u8 {
+        match self {
+            CompletionKind::Parameter => 6,
+            CompletionKind::Constant => 21,
+            CompletionKind::Macro => 3,
+            CompletionKind::Builtin => 2,
+            CompletionKind::Opcode => 14,
+        }
+    }
+}
+
+/// A completion candidate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    /// The identifier completed
+    pub label: String,
+    /// The kind of candidate
+    pub kind: CompletionKind,
+    /// A short description of the candidate, like the stack effects of a macro
+    pub detail: Option<String>,
+    /// The text inserted for the candidate, wrapped in the syntax it's referenced with
+    pub insert_text: String,
+}
+
+impl Completion {
+    /// The LSP completion item of the candidate
+    pub fn lsp(&self) -> Value {
+        json!({
+            "label": self.label,
+            "kind": self.kind.lsp(),
+            "detail": self.detail,
+            "insertText": self.insert_text,
+        })
+    }
+}
+
+/// The completion candidates at a byte offset of a source buffer
+///
+/// The buffer may be partially invalid, candidates are taken from the definitions the parser
+/// recovers.
+pub fn completions(text: &str, offset: usize) -> Vec<Completion> {
+    complete(&Document::parse("", text), &[], offset)
+}
+
+/// The completion candidates at a byte offset of a document, including the definitions of the
+/// documents it includes
+///
+/// The identifier before the offset filters the candidates. After a `<`, only the parameters
+/// of the enclosing macro are candidates, and after a `[` only the constants, the well-known
+/// constants included.
+pub fn complete(document: &Document, included: &[Document], offset: usize) -> Vec<Completion> {
+    let text = &document.text;
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let start = text[..offset]
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let prefix = text[start..offset].to_lowercase();
+    let opener = text[..start].chars().last();
+
+    let mut candidates = vec![];
+    if opener != Some('[') {
+        let wrap = |name: &str| match opener {
+            Some('<') => name.to_string(),
+            _ => format!("<{}>", name),
+        };
+        candidates.extend(enclosing_parameters(text, start).into_iter().map(|p| Completion {
+            insert_text: wrap(&p),
+            label: p,
+            kind: CompletionKind::Parameter,
+            detail: None,
+        }));
+    }
+    if opener != Some('<') {
+        let wrap = |name: &str| match opener {
+            Some('[') => name.to_string(),
+            _ => format!("[{}]", name),
+        };
+        let constants = std::iter::once(document)
+            .chain(included.iter())
+            .flat_map(|d| d.contract.constants.iter());
+        candidates.extend(constants.map(|c| Completion {
+            label: c.name.to_string(),
+            kind: CompletionKind::Constant,
+            detail: Some(match &c.value {
+                ConstVal::Literal(l) => bytes32_to_string(l, true),
+                ConstVal::FreeStoragePointer(fsp) => fsp.to_string(),
+            }),
+            insert_text: wrap(&c.name),
+        }));
+        // Well-known constants only complete from a prefix, not to drown the defined ones
+        if !prefix.is_empty() {
+            candidates.extend(WellKnownConstant::ALL.iter().map(|c| Completion {
+                label: c.name().to_string(),
+                kind: CompletionKind::Constant,
+                detail: Some(bytes32_to_string(&c.value(), true)),
+                insert_text: wrap(c.name()),
+            }));
+        }
+    }
+    if opener != Some('<') && opener != Some('[') {
+        let macros =
+            std::iter::once(document).chain(included.iter()).flat_map(|d| d.contract.macros.iter());
+        candidates.extend(macros.map(|m| Completion {
+            label: m.name.to_string(),
+            kind: CompletionKind::Macro,
+            detail: Some(format!("takes ({}) returns ({})", m.takes, m.returns)),
+            insert_text: format!("{}()", m.name),
+        }));
+        candidates.extend(BUILTIN_FUNCTIONS.iter().map(|b| Completion {
+            label: b.to_string(),
+            kind: CompletionKind::Builtin,
+            detail: None,
+            insert_text: format!("{}()", b),
+        }));
+        candidates.extend(OPCODES.iter().map(|o| Completion {
+            label: o.to_string(),
+            kind: CompletionKind::Opcode,
+            detail: None,
+            insert_text: o.to_string(),
+        }));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|c| c.label.to_lowercase().starts_with(&prefix))
+        .filter(|c| seen.insert((c.kind, c.label.clone())))
+        .collect()
+}
+
+/// The parameter names of the macro whose definition precedes an offset
+///
+/// Only the definition header is lexed, so parameters complete while the macro body is being
+/// written and doesn't parse.
+fn enclosing_parameters(text: &str, offset: usize) -> Vec<String> {
+    let start = match text[..offset].rfind("#define") {
+        Some(s) => s,
+        None => return vec![],
+    };
+    let full_source = FullFileSource { source: &text[start..offset], file: None, spans: vec![] };
+    let tokens = Lexer::new(full_source)
+        .map_while(Result::ok)
+        .filter(|t| !matches!(t.kind, TokenKind::Whitespace | TokenKind::Comment(_)))
+        .map(|t| t.kind)
+        .collect::<Vec<TokenKind>>();
+
+    match tokens.as_slice() {
+        [TokenKind::Define, TokenKind::Macro, TokenKind::Ident(_), TokenKind::OpenParen, rest @ ..] => {
+            rest.iter()
+                .take_while(|t| **t != TokenKind::CloseParen)
+                .filter_map(|t| match t {
+                    TokenKind::Ident(name) => Some(name.clone()),
+                    _ => None,
+                })
+                .collect()
+        }
+        _ => vec![],
+    }
+}
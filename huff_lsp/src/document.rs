@@ -0,0 +1,195 @@
+//! ## Document
+//!
+//! The diagnostics and definitions of a huff source file, and their LSP representations.
+
+use huff_lexer::Lexer;
+use huff_parser::Parser;
+use huff_utils::prelude::*;
+use serde_json::{json, Value};
+use std::ops::Range;
+
+/// The LSP `SymbolKind` number of a symbol
+pub fn lsp_kind(kind: SymbolKind) -> u8 {
+    match kind {
+        SymbolKind::Macro => 12,
+        SymbolKind::Test => 6,
+        SymbolKind::Constant => 14,
+        SymbolKind::Alias => 25,
+        SymbolKind::Label => 20,
+        SymbolKind::Function => 6,
+        SymbolKind::Event => 24,
+        SymbolKind::Error => 24,
+        SymbolKind::Table => 18,
+    }
+}
+
+/// A parsed huff source file
+#[derive(Debug, Clone)]
+pub struct Document {
+    /// The file path, used to resolve includes
+    pub path: String,
+    /// The source code
+    pub text: String,
+    /// The definitions that could be parsed
+    pub contract: Contract,
+    /// The identifiers defined by the parsed definitions
+    pub symbols: SymbolTable,
+    /// The lexing and parsing errors
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Document {
+    /// Lexes and parses a source file, recovering as many definitions as possible
+    pub fn parse(path: &str, text: &str) -> Self {
+        let full_source = FullFileSource { source: text, file: None, spans: vec![] };
+        let mut tokens = vec![];
+        let mut diagnostics = vec![];
+        for token in Lexer::new(full_source) {
+            match token {
+                Ok(t) => tokens.push(t),
+                Err(e) => {
+                    // The lexer can't recover, so parse the tokens up to the error
+                    let eof = Token { kind: TokenKind::Eof, span: e.span.clone() };
+                    diagnostics = Diagnostic::from_compiler_error(&CompilerError::LexicalError(e));
+                    tokens.push(eof);
+                    break
+                }
+            }
+        }
+
+        let mut parser = Parser::new(tokens, Some(path.to_string()));
+        let contract = parser.parse_partial();
+        diagnostics.extend(parser.errors.iter().flat_map(|e| {
+            Diagnostic::from_compiler_error(&CompilerError::ParserError(e.clone())).into_iter()
+        }));
+
+        Self {
+            path: path.to_string(),
+            text: text.to_string(),
+            contract,
+            symbols: parser.symbols,
+            diagnostics,
+        }
+    }
+
+    /// The byte range of a symbol's identifier
+    pub fn name_range(&self, symbol: &Symbol) -> Range<usize> {
+        symbol.span.start..symbol.span.end
+    }
+
+    /// The byte range of a symbol's whole definition
+    pub fn definition_range(&self, symbol: &Symbol) -> Range<usize> {
+        let spans = symbol.definition.0.iter().filter(|s| s.end <= self.text.len());
+        match (spans.clone().map(|s| s.start).min(), spans.map(|s| s.end).max()) {
+            (Some(start), Some(end)) => start..end,
+            _ => self.name_range(symbol),
+        }
+    }
+
+    /// The identifier at a byte offset, if any
+    pub fn word_at(&self, offset: usize) -> Option<&str> {
+        self.word_range(offset).map(|r| &self.text[r])
+    }
+
+    /// The byte range of the identifier at a byte offset, if any
+    pub fn word_range(&self, offset: usize) -> Option<Range<usize>> {
+        let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        let offset = offset.min(self.text.len());
+        let start = self.text[..offset].rfind(|c| !is_ident(c)).map(|i| i + 1).unwrap_or(0);
+        let end = self.text[offset..]
+            .find(|c| !is_ident(c))
+            .map(|i| offset + i)
+            .unwrap_or(self.text.len());
+        (start < end).then_some(start..end)
+    }
+
+    /// Converts an LSP position, a 0-indexed line and UTF-16 character, to a byte offset
+    pub fn offset_at(&self, line: usize, character: usize) -> usize {
+        let line_start = match line {
+            0 => 0,
+            _ => match self.text.match_indices('\n').nth(line - 1) {
+                Some((i, _)) => i + 1,
+                None => return self.text.len(),
+            },
+        };
+        let mut units = 0;
+        for (i, c) in self.text[line_start..].char_indices() {
+            if units >= character || c == '\n' {
+                return line_start + i
+            }
+            units += c.len_utf16();
+        }
+        self.text.len()
+    }
+
+    /// Converts a byte offset to an LSP position
+    pub fn position(&self, offset: usize) -> Value {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &self.text[..offset];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        json!({
+            "line": before.matches('\n').count(),
+            "character": before[line_start..].encode_utf16().count(),
+        })
+    }
+
+    /// Converts a byte range to an LSP range
+    pub fn range(&self, range: &Range<usize>) -> Value {
+        json!({ "start": self.position(range.start), "end": self.position(range.end) })
+    }
+
+    /// The LSP diagnostics of the document
+    pub fn lsp_diagnostics(&self) -> Vec<Value> {
+        self.diagnostics
+            .iter()
+            .map(|d| {
+                let start = d.spans.iter().map(|s| s.start).min().unwrap_or_default();
+                let end = d.spans.iter().map(|s| s.end).max().unwrap_or_default();
+                let message = match &d.help {
+                    Some(help) => format!("{}\nhelp: {}", d.message, help),
+                    None => d.message.clone(),
+                };
+                json!({
+                    "range": self.range(&(start..end)),
+                    "severity": match d.severity {
+                        Severity::Error => 1,
+                        Severity::Warning => 2,
+                    },
+                    "code": d.code,
+                    "source": "huff",
+                    "message": message,
+                })
+            })
+            .collect()
+    }
+
+    /// The LSP document symbols of the document, with the labels of a macro as its children
+    pub fn lsp_symbols(&self) -> Vec<Value> {
+        let mut definitions = self.symbols.definitions().collect::<Vec<&Symbol>>();
+        definitions.sort_by_key(|s| s.span.start);
+        definitions
+            .into_iter()
+            .map(|s| {
+                let mut symbol = self.lsp_symbol(s);
+                if s.kind == SymbolKind::Macro {
+                    symbol["children"] =
+                        self.symbols.labels(&s.name).map(|l| self.lsp_symbol(l)).collect();
+                }
+                symbol
+            })
+            .collect()
+    }
+
+    /// The LSP document symbol of a symbol
+    fn lsp_symbol(&self, symbol: &Symbol) -> Value {
+        json!({
+            "name": symbol.name,
+            "kind": lsp_kind(symbol.kind),
+            "range": self.range(&self.definition_range(symbol)),
+            "selectionRange": self.range(&self.name_range(symbol)),
+        })
+    }
+}
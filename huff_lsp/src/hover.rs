@@ -0,0 +1,203 @@
+//! ## Hover
+//!
+//! Structured information about the identifier at a position of a huff source buffer.
+
+use crate::document::Document;
+use huff_utils::prelude::*;
+use serde_json::{json, Value};
+use std::ops::Range;
+use tiny_keccak::{Hasher, Keccak};
+
+/// The information about an identifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HoverInfo {
+    /// An opcode, with its stack effect and gas cost
+    Opcode {
+        /// The opcode mnemonic
+        mnemonic: String,
+        /// The opcode byte
+        byte: u8,
+        /// The number of stack items taken
+        inputs: usize,
+        /// The number of stack items returned
+        outputs: usize,
+        /// The static gas cost
+        gas: u64,
+        /// Whether more gas is charged depending on the operands or state
+        dynamic_gas: bool,
+    },
+    /// A macro, with its parameters and stack effects
+    Macro {
+        /// The macro name
+        name: String,
+        /// The parameter names
+        parameters: Vec<String>,
+        /// The number of stack items taken
+        takes: usize,
+        /// The number of stack items returned
+        returns: usize,
+    },
+    /// A constant and its value
+    Constant {
+        /// The constant name
+        name: String,
+        /// The hex value, or `FREE_STORAGE_POINTER(n)` for storage pointers
+        value: String,
+    },
+    /// An ABI function and its selector
+    Function {
+        /// The canonical signature, eg. `transfer(address,uint256)`
+        signature: String,
+        /// The function selector
+        selector: [u8; 4],
+        /// The state mutability
+        mutability: String,
+        /// The return types
+        outputs: Vec<String>,
+    },
+    /// An ABI event and its topic
+    Event {
+        /// The canonical signature, eg. `Transfer(address,address,uint256)`
+        signature: String,
+        /// The event topic, the hash of its signature
+        topic: [u8; 32],
+    },
+}
+
+/// The information about the identifier under a position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hover {
+    /// The information about the identifier
+    pub info: HoverInfo,
+    /// The byte range of the identifier
+    pub range: Range<usize>,
+}
+
+impl Hover {
+    /// The markdown description of the identifier
+    pub fn markdown(&self) -> String {
+        match &self.info {
+            HoverInfo::Opcode { mnemonic, byte, inputs, outputs, gas, dynamic_gas } => format!(
+                "**{}** (`0x{:02x}`)\n\nStack: takes {}, returns {}\n\nGas: {}{}",
+                mnemonic,
+                byte,
+                inputs,
+                outputs,
+                gas,
+                if *dynamic_gas { ", plus a dynamic cost" } else { "" }
+            ),
+            HoverInfo::Macro { name, parameters, takes, returns } => format!(
+                "```huff\n#define macro {}({}) = takes ({}) returns ({})\n```",
+                name,
+                parameters.join(", "),
+                takes,
+                returns
+            ),
+            HoverInfo::Constant { name, value } => {
+                format!("```huff\n#define constant {} = {}\n```", name, value)
+            }
+            HoverInfo::Function { signature, selector, mutability, outputs } => format!(
+                "```huff\n#define function {} {} returns ({})\n```\n\nSelector: `0x{}`",
+                signature,
+                mutability,
+                outputs.join(","),
+                to_hex(selector)
+            ),
+            HoverInfo::Event { signature, topic } => {
+                format!("```huff\n#define event {}\n```\n\nTopic: `0x{}`", signature, to_hex(topic))
+            }
+        }
+    }
+
+    /// The LSP hover of the identifier
+    pub fn lsp(&self, document: &Document) -> Value {
+        json!({
+            "contents": { "kind": "markdown", "value": self.markdown() },
+            "range": document.range(&self.range),
+        })
+    }
+}
+
+/// The information about the identifier at a byte offset of a source buffer
+///
+/// The buffer may be partially invalid, identifiers resolve to the definitions the parser
+/// recovers.
+pub fn hover(text: &str, offset: usize) -> Option<Hover> {
+    resolve(&Document::parse("", text), &[], offset)
+}
+
+/// The information about the identifier at a byte offset of a document, resolving definitions
+/// in the documents it includes
+///
+/// Definitions take precedence over opcodes, the same way they're resolved in macro bodies.
+pub fn resolve(document: &Document, included: &[Document], offset: usize) -> Option<Hover> {
+    let range = document.word_range(offset)?;
+    let word = &document.text[range.clone()];
+    let contracts = || std::iter::once(document).chain(included.iter()).map(|d| &d.contract);
+
+    let info = if let Some(m) = contracts().find_map(|c| c.macros.iter().find(|m| m.name == word)) {
+        HoverInfo::Macro {
+            name: m.name.to_string(),
+            parameters: m.parameters.iter().filter_map(|p| p.name.clone()).collect(),
+            takes: m.takes,
+            returns: m.returns,
+        }
+    } else if let Some(c) = contracts().find_map(|c| c.constants.iter().find(|c| c.name == word)) {
+        HoverInfo::Constant {
+            name: c.name.to_string(),
+            value: match &c.value {
+                ConstVal::Literal(l) => bytes32_to_string(l, true),
+                ConstVal::FreeStoragePointer(fsp) => fsp.to_string(),
+            },
+        }
+    } else if let Some(c) = WellKnownConstant::from_name(word) {
+        HoverInfo::Constant {
+            name: c.name().to_string(),
+            value: bytes32_to_string(&c.value(), true),
+        }
+    } else if let Some(f) = contracts().find_map(|c| c.functions.iter().find(|f| f.name == word)) {
+        HoverInfo::Function {
+            signature: signature(&f.name, &f.inputs),
+            selector: f.signature,
+            mutability: match f.fn_type {
+                FunctionType::View => "view",
+                FunctionType::Payable => "payable",
+                FunctionType::NonPayable => "nonpayable",
+                FunctionType::Pure => "pure",
+            }
+            .to_string(),
+            outputs: f.outputs.iter().filter_map(|a| a.arg_type.clone()).collect(),
+        }
+    } else if let Some(e) = contracts().find_map(|c| c.events.iter().find(|e| e.name == word)) {
+        let signature = signature(&e.name, &e.parameters);
+        let mut topic = [0u8; 32];
+        let mut hasher = Keccak::v256();
+        hasher.update(signature.as_bytes());
+        hasher.finalize(&mut topic);
+        HoverInfo::Event { signature, topic }
+    } else if let Some(o) = OPCODES_MAP.get(word) {
+        let (inputs, outputs) = o.stack_effect();
+        HoverInfo::Opcode {
+            mnemonic: word.to_string(),
+            byte: o.byte(),
+            inputs,
+            outputs,
+            gas: o.gas(),
+            dynamic_gas: o.has_dynamic_gas(),
+        }
+    } else {
+        return None
+    };
+    Some(Hover { info, range })
+}
+
+/// The canonical signature of a function or event
+fn signature(name: &str, arguments: &[Argument]) -> String {
+    let types = arguments.iter().filter_map(|a| a.arg_type.clone()).collect::<Vec<String>>();
+    format!("{}({})", name, types.join(","))
+}
+
+/// Encodes bytes as lowercase hex
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,20 @@
+#![doc = include_str!("../README.md")]
+#![warn(missing_docs)]
+#![warn(unused_extern_crates)]
+#![forbid(unsafe_code)]
+
+use huff_lsp::Server;
+
+fn main() {
+    // Messages are exchanged over stdio
+    let mut server = Server::new();
+    if let Err(e) = server.run(&mut std::io::stdin().lock(), &mut std::io::stdout().lock()) {
+        eprintln!("huff-lsp: {}", e);
+        std::process::exit(1);
+    }
+
+    // Exiting without a shutdown request is an error
+    if !server.shutdown {
+        std::process::exit(1);
+    }
+}
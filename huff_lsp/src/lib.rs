@@ -0,0 +1,283 @@
+#![doc = include_str!("../README.md")]
+#![warn(missing_docs)]
+#![warn(unused_extern_crates)]
+#![forbid(unsafe_code)]
+
+use serde_json::{json, Value};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io::{BufRead, Write},
+    path::Path,
+};
+
+/// Completion Module
+pub mod completion;
+
+/// Document Module
+pub mod document;
+
+/// Hover Module
+pub mod hover;
+use document::Document;
+use huff_utils::prelude::{bundled_module, is_bundled_path, Symbol};
+
+/// RPC Module
+pub mod rpc;
+
+/// The Huff Language Server
+///
+/// Documents are synced in full, and diagnostics are published each time a document is opened
+/// or changed.
+#[derive(Debug, Default)]
+pub struct Server {
+    /// The open documents, by uri
+    pub documents: HashMap<String, Document>,
+    /// Whether the client requested a shutdown
+    pub shutdown: bool,
+}
+
+impl Server {
+    /// Public associated function to instantiate a new server.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serves messages until the client sends an `exit` notification or closes the input
+    pub fn run<R: BufRead, W: Write>(
+        &mut self,
+        reader: &mut R,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        while let Some(message) = rpc::read_message(reader)? {
+            if message["method"] == "exit" {
+                break
+            }
+            for reply in self.handle(&message) {
+                rpc::write_message(writer, &reply)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles a message, returning the messages to send back to the client
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        tracing::debug!(target: "lsp", "HANDLING \"{}\"", method);
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "documentSymbolProvider": true,
+                    "definitionProvider": true,
+                    "completionProvider": { "triggerCharacters": ["<", "["] },
+                    "hoverProvider": true,
+                },
+                "serverInfo": { "name": "huff-lsp", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                return self.update(&document["uri"], &document["text"])
+            }
+            "textDocument/didChange" => {
+                let text = params["contentChanges"].as_array().and_then(|c| c.last());
+                return self
+                    .update(&params["textDocument"]["uri"], &text.unwrap_or(&Value::Null)["text"])
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                self.documents.remove(uri);
+                return vec![publish_diagnostics(uri, vec![])]
+            }
+            "textDocument/documentSymbol" => {
+                self.document(params).map(|d| Value::Array(d.lsp_symbols()))
+            }
+            "textDocument/definition" => self.definition(params),
+            "textDocument/completion" => self.completion(params),
+            "textDocument/hover" => self.hover(params),
+            _ => Err((rpc::METHOD_NOT_FOUND, format!("Unknown method \"{}\"", method))),
+        };
+
+        // Notifications don't get a response
+        match message.get("id") {
+            Some(id) => vec![match result {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err((code, message)) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": code, "message": message },
+                }),
+            }],
+            None => vec![],
+        }
+    }
+
+    /// Parses the new text of a document and publishes its diagnostics
+    fn update(&mut self, uri: &Value, text: &Value) -> Vec<Value> {
+        let (uri, text) = match (uri.as_str(), text.as_str()) {
+            (Some(u), Some(t)) => (u, t),
+            _ => return vec![],
+        };
+        let document = Document::parse(&uri_to_path(uri), text);
+        let diagnostics = document.lsp_diagnostics();
+        self.documents.insert(uri.to_string(), document);
+        vec![publish_diagnostics(uri, diagnostics)]
+    }
+
+    /// The open document of a request
+    fn document(&self, params: &Value) -> Result<&Document, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        self.documents
+            .get(uri)
+            .ok_or_else(|| (rpc::INVALID_PARAMS, format!("Unknown document \"{}\"", uri)))
+    }
+
+    /// The byte offset of the position of a request
+    fn offset(document: &Document, params: &Value) -> usize {
+        let position = &params["position"];
+        document.offset_at(
+            position["line"].as_u64().unwrap_or_default() as usize,
+            position["character"].as_u64().unwrap_or_default() as usize,
+        )
+    }
+
+    /// Resolves the definition of the identifier at a position
+    fn definition(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let document = self.document(params)?;
+        let offset = Self::offset(document, params);
+        let word = match document.word_at(offset) {
+            Some(w) => w,
+            None => return Ok(Value::Null),
+        };
+
+        // Labels resolve within the enclosing macro, other identifiers across includes
+        let found = match document.symbols.resolve(word, None, offset) {
+            Some(s) => Some((document.clone(), s.clone())),
+            None => self.find_definition(document, word),
+        };
+        Ok(match found {
+            Some((d, s)) => {
+                json!({ "uri": path_to_uri(&d.path), "range": d.range(&d.name_range(&s)) })
+            }
+            None => Value::Null,
+        })
+    }
+
+    /// Lists the completion candidates at a position
+    fn completion(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let document = self.document(params)?;
+        let offset = Self::offset(document, params);
+        let included = self.included(document);
+        Ok(Value::Array(
+            completion::complete(document, &included, offset).iter().map(|c| c.lsp()).collect(),
+        ))
+    }
+
+    /// Describes the identifier at a position
+    fn hover(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let document = self.document(params)?;
+        let offset = Self::offset(document, params);
+        let included = self.included(document);
+        Ok(hover::resolve(document, &included, offset).map_or(Value::Null, |h| h.lsp(document)))
+    }
+
+    /// Finds a definition in a document or the files it includes, breadth first
+    pub fn find_definition(&self, document: &Document, name: &str) -> Option<(Document, Symbol)> {
+        std::iter::once(document.clone())
+            .chain(self.included(document))
+            .find_map(|d| d.symbols.get(name).cloned().map(|s| (d, s)))
+    }
+
+    /// The files a document includes, recursively, breadth first
+    pub fn included(&self, document: &Document) -> Vec<Document> {
+        let mut visited = HashSet::from([document.path.clone()]);
+        let mut queue = VecDeque::from([document.clone()]);
+        let mut included = vec![];
+        while let Some(d) = queue.pop_front() {
+            for import in d.contract.imports.iter() {
+                let path = import.to_string_lossy().to_string();
+                if visited.insert(path.clone()) {
+                    queue.extend(self.load(&path));
+                }
+            }
+            if d.path != document.path {
+                included.push(d);
+            }
+        }
+        included
+    }
+
+    /// Loads an included file, preferring the unsaved text of an open document
+    fn load(&self, path: &str) -> Option<Document> {
+        if is_bundled_path(path) {
+            return bundled_module(path).map(|source| Document::parse(path, source))
+        }
+        let canonical = |p: &str| std::fs::canonicalize(p).ok();
+        match self
+            .documents
+            .values()
+            .find(|d| d.path == path || canonical(&d.path) == canonical(path))
+        {
+            Some(d) => Some(d.clone()),
+            None => std::fs::read_to_string(path).ok().map(|text| Document::parse(path, &text)),
+        }
+    }
+}
+
+/// Builds a `textDocument/publishDiagnostics` notification
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+/// Converts a `file://` uri to a file path
+pub fn uri_to_path(uri: &str) -> String {
+    let path = uri.strip_prefix("file://").unwrap_or(uri);
+    let mut bytes = vec![];
+    let mut i = 0;
+    while i < path.len() {
+        let decoded = path
+            .get(i + 1..i + 3)
+            .filter(|_| path.as_bytes()[i] == b'%')
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match decoded {
+            Some(b) => {
+                bytes.push(b);
+                i += 3;
+            }
+            None => {
+                bytes.push(path.as_bytes()[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+/// Converts a file path to a `file://` uri
+pub fn path_to_uri(path: &str) -> String {
+    let path = std::fs::canonicalize(path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string());
+    let encoded = path
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect::<String>();
+    match Path::new(&path).is_absolute() {
+        true => format!("file://{}", encoded),
+        false => encoded,
+    }
+}
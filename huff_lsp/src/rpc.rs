@@ -0,0 +1,45 @@
+//! ## RPC
+//!
+//! Reads and writes the `Content-Length` framed JSON-RPC messages of the Language Server
+//! Protocol.
+
+use serde_json::Value;
+use std::io::{BufRead, Error, ErrorKind, Write};
+
+/// The JSON-RPC error code of an unknown method
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// The JSON-RPC error code of invalid method parameters
+pub const INVALID_PARAMS: i64 = -32602;
+
+/// Reads the next message, returning `None` once the input is closed
+pub fn read_message<R: BufRead>(reader: &mut R) -> std::io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None)
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let length = content_length
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "missing Content-Length header"))?;
+    let mut content = vec![0; length];
+    reader.read_exact(&mut content)?;
+    serde_json::from_slice(&content).map(Some).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Writes a message with its `Content-Length` header
+pub fn write_message<W: Write>(writer: &mut W, message: &Value) -> std::io::Result<()> {
+    let content = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", content.len(), content)?;
+    writer.flush()
+}
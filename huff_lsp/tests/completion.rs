@@ -0,0 +1,88 @@
+use huff_lsp::completion::{completions, CompletionKind};
+use huff_utils::prelude::OPCODES;
+
+const SOURCE: &str = r#"#define constant OWNER = FREE_STORAGE_POINTER()
+#define constant FEE = 0x1e
+#define macro ONLY_OWNER() = takes(0) returns(0) {
+    caller [OWNER] sload eq
+}
+#define macro TRANSFER(recipient, amount) = takes(0) returns(0) {
+    ONLY_OWNER()
+    <amount> [FEE] mul
+    "#;
+
+fn labels(text: &str, offset: usize, kind: CompletionKind) -> Vec<String> {
+    completions(text, offset).into_iter().filter(|c| c.kind == kind).map(|c| c.label).collect()
+}
+
+#[test]
+fn test_completions_of_partial_buffer() {
+    // The macro being written doesn't parse, but its parameters complete
+    let source = format!("{}O", SOURCE);
+    let candidates = completions(&source, source.len());
+    assert_eq!(
+        candidates.iter().map(|c| (c.label.as_str(), c.kind)).collect::<Vec<_>>(),
+        vec![
+            ("OWNER", CompletionKind::Constant),
+            ("ONLY_OWNER", CompletionKind::Macro),
+            ("origin", CompletionKind::Opcode),
+            ("or", CompletionKind::Opcode),
+        ]
+    );
+    assert_eq!(candidates[0].insert_text, "[OWNER]");
+    assert_eq!(candidates[1].insert_text, "ONLY_OWNER()");
+    assert_eq!(candidates[1].detail, Some("takes (0) returns (0)".to_string()));
+
+    // Without a prefix, every candidate completes
+    let source = format!("{}\n    ", SOURCE);
+    assert_eq!(
+        labels(&source, source.len(), CompletionKind::Parameter),
+        vec!["recipient", "amount"]
+    );
+    assert_eq!(labels(&source, source.len(), CompletionKind::Opcode).len(), OPCODES.len());
+
+    // Builtins complete from their prefix
+    let source = format!("{}__t", SOURCE);
+    assert_eq!(
+        labels(&source, source.len(), CompletionKind::Builtin),
+        vec!["__tablesize", "__tablestart"]
+    );
+}
+
+#[test]
+fn test_completions_after_openers() {
+    // Only parameters complete in arg calls, without their brackets
+    let source = format!("{}<r", SOURCE);
+    let candidates = completions(&source, source.len());
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].label, "recipient");
+    assert_eq!(candidates[0].insert_text, "recipient");
+
+    // Only constants complete in constant pushes
+    let source = format!("{}[", SOURCE);
+    assert_eq!(labels(&source, source.len(), CompletionKind::Constant), vec!["OWNER", "FEE"]);
+    assert_eq!(completions(&source, source.len()).len(), 2);
+
+    // Parameters are scoped to the enclosing macro
+    let offset = SOURCE.find("caller").unwrap();
+    assert!(labels(SOURCE, offset, CompletionKind::Parameter).is_empty());
+}
+
+#[test]
+fn test_well_known_constant_completions() {
+    // Well-known constants complete from their prefix, after the defined constants
+    let source = format!("{}[EIP1967_", SOURCE);
+    let candidates = completions(&source, source.len());
+    assert_eq!(
+        candidates.iter().map(|c| c.label.as_str()).collect::<Vec<_>>(),
+        vec!["EIP1967_IMPLEMENTATION_SLOT", "EIP1967_ADMIN_SLOT", "EIP1967_BEACON_SLOT"]
+    );
+    assert_eq!(candidates[0].insert_text, "EIP1967_IMPLEMENTATION_SLOT");
+    assert_eq!(
+        candidates[1].detail.as_deref(),
+        Some("0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103")
+    );
+
+    let source = format!("{}F", SOURCE);
+    assert_eq!(labels(&source, source.len(), CompletionKind::Constant), vec!["FEE"]);
+}
@@ -0,0 +1,97 @@
+use huff_lsp::hover::{hover, HoverInfo};
+
+const SOURCE: &str = r#"#define function transfer(address,uint256) nonpayable returns (bool)
+#define event Transfer(address indexed,address indexed,uint256)
+#define constant OWNER = FREE_STORAGE_POINTER()
+#define constant FEE = 0x1e
+#define macro TRANSFER(recipient, amount) = takes(1) returns(2) {
+    <amount> [FEE] mul
+}
+#define macro MAIN() = takes(0) returns(0) {
+    TRANSFER(caller, 0x01)
+    __FUNC_SIG(transfer) __EVENT_HASH(Transfer) log1
+    [OWNER] sload
+"#;
+
+fn info(word: &str, nth: usize) -> HoverInfo {
+    let offset = SOURCE.match_indices(word).nth(nth).unwrap().0 + 1;
+    hover(SOURCE, offset).unwrap().info
+}
+
+#[test]
+fn test_hover_definitions() {
+    // The last macro doesn't parse, but references resolve to the recovered definitions
+    assert_eq!(
+        info("TRANSFER(", 1),
+        HoverInfo::Macro {
+            name: "TRANSFER".to_string(),
+            parameters: vec!["recipient".to_string(), "amount".to_string()],
+            takes: 1,
+            returns: 2,
+        }
+    );
+    assert_eq!(
+        info("FEE", 1),
+        HoverInfo::Constant { name: "FEE".to_string(), value: "0x1e".to_string() }
+    );
+    assert_eq!(
+        info("OWNER", 1),
+        HoverInfo::Constant {
+            name: "OWNER".to_string(),
+            value: "FREE_STORAGE_POINTER()".to_string()
+        }
+    );
+
+    let function = info("transfer", 1);
+    assert_eq!(
+        function,
+        HoverInfo::Function {
+            signature: "transfer(address,uint256)".to_string(),
+            selector: [0xa9, 0x05, 0x9c, 0xbb],
+            mutability: "nonpayable".to_string(),
+            outputs: vec!["bool".to_string()],
+        }
+    );
+
+    let offset = SOURCE.find("Transfer)").unwrap();
+    let event = hover(SOURCE, offset).unwrap();
+    assert_eq!(&SOURCE[event.range.clone()], "Transfer");
+    assert_eq!(
+        event.markdown(),
+        "```huff\n#define event Transfer(address,address,uint256)\n```\n\nTopic: `0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef`"
+    );
+}
+
+#[test]
+fn test_hover_opcodes() {
+    assert_eq!(
+        info("log1", 0),
+        HoverInfo::Opcode {
+            mnemonic: "log1".to_string(),
+            byte: 0xa1,
+            inputs: 3,
+            outputs: 0,
+            gas: 750,
+            dynamic_gas: true,
+        }
+    );
+    assert_eq!(
+        hover(SOURCE, SOURCE.find("sload").unwrap()).unwrap().markdown(),
+        "**sload** (`0x54`)\n\nStack: takes 1, returns 1\n\nGas: 100, plus a dynamic cost"
+    );
+
+    // Parameters and whitespace have nothing to describe
+    assert!(hover(SOURCE, SOURCE.find("recipient").unwrap()).is_none());
+    assert!(hover(SOURCE, SOURCE.find("    <amount>").unwrap()).is_none());
+}
+
+#[test]
+fn test_hover_well_known_constants() {
+    let source =
+        "#define macro MAIN() = takes(0) returns(0) {\n    [ECRECOVER_PRECOMPILE] pop\n}\n";
+    let offset = source.find("ECRECOVER").unwrap();
+    assert_eq!(
+        hover(source, offset).unwrap().info,
+        HoverInfo::Constant { name: "ECRECOVER_PRECOMPILE".to_string(), value: "0x01".to_string() }
+    );
+}
@@ -0,0 +1,245 @@
+use huff_lsp::{rpc, Server};
+use serde_json::{json, Value};
+use std::fs;
+
+fn open(server: &mut Server, uri: &str, text: &str) -> Vec<Value> {
+    server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": uri, "languageId": "huff", "version": 1, "text": text } }
+    }))
+}
+
+fn request(server: &mut Server, method: &str, params: Value) -> Value {
+    let replies =
+        server.handle(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }));
+    assert_eq!(replies.len(), 1);
+    replies[0].clone()
+}
+
+#[test]
+fn test_initialize() {
+    let mut server = Server::new();
+    let response = request(&mut server, "initialize", json!({ "capabilities": {} }));
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["result"]["capabilities"]["textDocumentSync"], 1);
+    assert_eq!(response["result"]["capabilities"]["definitionProvider"], true);
+    assert_eq!(response["result"]["capabilities"]["hoverProvider"], true);
+
+    // Unknown requests are errors, unknown notifications are ignored
+    let response = request(&mut server, "textDocument/formatting", json!({}));
+    assert_eq!(response["error"]["code"], rpc::METHOD_NOT_FOUND);
+    assert!(server.handle(&json!({ "jsonrpc": "2.0", "method": "$/cancelRequest" })).is_empty());
+}
+
+#[test]
+fn test_publish_diagnostics() {
+    let mut server = Server::new();
+    let uri = "file:///contracts/Main.huff";
+    let source = "#define macro MAIN() = takes(0) returns(0) {\n    0x00 0x00 return\n}\n";
+    let replies = open(&mut server, uri, source);
+    assert_eq!(replies[0]["method"], "textDocument/publishDiagnostics");
+    assert_eq!(replies[0]["params"]["uri"], uri);
+    assert_eq!(replies[0]["params"]["diagnostics"], json!([]));
+
+    // Changes are parsed again, and every recovered error is reported
+    let replies = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": {
+            "textDocument": { "uri": uri, "version": 2 },
+            "contentChanges": [{ "text": "#define macro MAIN() = takes(0) returns(0) {\n    0x00 0x00 return\n}\n#define nothing\n" }]
+        }
+    }));
+    let diagnostics = replies[0]["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["code"], "H0203");
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(diagnostics[0]["range"]["start"], json!({ "line": 3, "character": 0 }));
+
+    // Closing a document clears its diagnostics
+    let replies = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didClose",
+        "params": { "textDocument": { "uri": uri } }
+    }));
+    assert_eq!(replies[0]["params"]["diagnostics"], json!([]));
+    assert!(server.documents.is_empty());
+}
+
+#[test]
+fn test_document_symbols() {
+    let mut server = Server::new();
+    let uri = "file:///contracts/Main.huff";
+    let source = r#"#define function transfer(address,uint256) nonpayable returns ()
+#define constant OWNER = FREE_STORAGE_POINTER()
+#define macro MAIN() = takes(0) returns(0) {
+    [OWNER] sload
+}
+"#;
+    open(&mut server, uri, source);
+    let response = request(
+        &mut server,
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": uri } }),
+    );
+    let symbols = response["result"].as_array().unwrap();
+    assert_eq!(
+        symbols
+            .iter()
+            .map(|s| (s["name"].as_str().unwrap(), s["kind"].as_u64().unwrap()))
+            .collect::<Vec<_>>(),
+        vec![("transfer", 6), ("OWNER", 14), ("MAIN", 12)]
+    );
+    assert_eq!(
+        symbols[2]["selectionRange"],
+        json!({ "start": { "line": 2, "character": 14 }, "end": { "line": 2, "character": 18 } })
+    );
+}
+
+#[test]
+fn test_label_symbols_and_definition() {
+    let mut server = Server::new();
+    let uri = "file:///contracts/Main.huff";
+    let source =
+        "#define macro MAIN() = takes(0) returns(0) {\n    done jump\n    done:\n        stop\n}\n";
+    open(&mut server, uri, source);
+
+    // Labels are children of their macro
+    let response = request(
+        &mut server,
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": uri } }),
+    );
+    let children = response["result"][0]["children"].as_array().unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0]["name"], "done");
+    assert_eq!(children[0]["kind"], 20);
+
+    // Label calls resolve to the label of the enclosing macro
+    let response = request(
+        &mut server,
+        "textDocument/definition",
+        json!({ "textDocument": { "uri": uri }, "position": { "line": 1, "character": 5 } }),
+    );
+    assert_eq!(response["result"]["uri"], uri);
+    assert_eq!(
+        response["result"]["range"],
+        json!({ "start": { "line": 2, "character": 4 }, "end": { "line": 2, "character": 8 } })
+    );
+}
+
+#[test]
+fn test_completion_request() {
+    let mut server = Server::new();
+    let uri = "file:///contracts/Main.huff";
+    let source = "#define constant OWNER = FREE_STORAGE_POINTER()\n#define macro MAIN(to) = takes(0) returns(0) {\n    [OW\n}\n";
+    open(&mut server, uri, source);
+
+    let response = request(
+        &mut server,
+        "textDocument/completion",
+        json!({ "textDocument": { "uri": uri }, "position": { "line": 2, "character": 7 } }),
+    );
+    assert_eq!(
+        response["result"],
+        json!([{ "label": "OWNER", "kind": 21, "detail": "FREE_STORAGE_POINTER()", "insertText": "OWNER" }])
+    );
+}
+
+#[test]
+fn test_hover_request() {
+    let mut server = Server::new();
+    let uri = "file:///contracts/Main.huff";
+    let source = "#define constant FEE = 0x1e\n#define macro MAIN() = takes(0) returns(0) {\n    [FEE] callvalue mul\n}\n";
+    open(&mut server, uri, source);
+
+    let response = request(
+        &mut server,
+        "textDocument/hover",
+        json!({ "textDocument": { "uri": uri }, "position": { "line": 2, "character": 12 } }),
+    );
+    assert_eq!(response["result"]["contents"]["kind"], "markdown");
+    assert_eq!(
+        response["result"]["contents"]["value"],
+        "**callvalue** (`0x34`)\n\nStack: takes 0, returns 1\n\nGas: 2"
+    );
+    assert_eq!(
+        response["result"]["range"],
+        json!({ "start": { "line": 2, "character": 10 }, "end": { "line": 2, "character": 19 } })
+    );
+
+    // Nothing to describe between identifiers
+    let response = request(
+        &mut server,
+        "textDocument/hover",
+        json!({ "textDocument": { "uri": uri }, "position": { "line": 0, "character": 21 } }),
+    );
+    assert_eq!(response["result"], Value::Null);
+}
+
+#[test]
+fn test_definition_across_includes() {
+    let dir = std::env::temp_dir().join(format!("huff_lsp_{}", std::process::id()));
+    fs::create_dir_all(dir.join("utils")).unwrap();
+    let utils = dir.join("utils").join("Ownable.huff");
+    fs::write(&utils, "#define macro ONLY_OWNER() = takes(0) returns(0) {\n    caller pop\n}\n")
+        .unwrap();
+    let main = dir.join("Main.huff");
+    let source = "#include \"./utils/Ownable.huff\"\n\n#define macro MAIN() = takes(0) returns(0) {\n    ONLY_OWNER()\n}\n";
+    fs::write(&main, source).unwrap();
+
+    let mut server = Server::new();
+    let uri = format!("file://{}", main.to_string_lossy());
+    let replies = open(&mut server, &uri, source);
+    assert_eq!(replies[0]["params"]["diagnostics"], json!([]));
+
+    // The macro is defined in the included file
+    let response = request(
+        &mut server,
+        "textDocument/definition",
+        json!({ "textDocument": { "uri": uri }, "position": { "line": 3, "character": 8 } }),
+    );
+    assert_eq!(response["result"]["uri"], huff_lsp::path_to_uri(&utils.to_string_lossy()));
+    assert_eq!(
+        response["result"]["range"],
+        json!({ "start": { "line": 0, "character": 14 }, "end": { "line": 0, "character": 24 } })
+    );
+
+    // Identifiers without a definition resolve to nothing
+    let response = request(
+        &mut server,
+        "textDocument/definition",
+        json!({ "textDocument": { "uri": uri }, "position": { "line": 3, "character": 18 } }),
+    );
+    assert_eq!(response["result"], Value::Null);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_run_over_framed_messages() {
+    let mut input = vec![];
+    for message in [
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "shutdown" }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+        json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }),
+    ] {
+        rpc::write_message(&mut input, &message).unwrap();
+    }
+
+    let mut server = Server::new();
+    let mut output = vec![];
+    server.run(&mut input.as_slice(), &mut output).unwrap();
+    assert!(server.shutdown);
+
+    // Messages after the exit notification are not handled
+    let mut reader = output.as_slice();
+    assert_eq!(rpc::read_message(&mut reader).unwrap().unwrap()["id"], 1);
+    assert_eq!(
+        rpc::read_message(&mut reader).unwrap().unwrap(),
+        json!({ "jsonrpc": "2.0", "id": 2, "result": null })
+    );
+    assert_eq!(rpc::read_message(&mut reader).unwrap(), None);
+}
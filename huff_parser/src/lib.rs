@@ -2,16 +2,21 @@
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
 #![forbid(unsafe_code)]
-#![forbid(where_clauses_object_safety)]
 
 use huff_utils::{
     ast::*,
     error::*,
-    prelude::{str_to_bytes32, FileSource, Span},
+    prelude::{
+        bundled_module, str_to_bytes32, Artifact, FileSource, NatSpec, Remapping, Span, Symbol,
+        SymbolKind, SymbolTable,
+    },
     token::{Token, TokenKind},
     types::*,
 };
-use std::path::Path;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 use tiny_keccak::{Hasher, Keccak};
 
 /// The Parser
@@ -27,39 +32,107 @@ pub struct Parser {
     pub base: Option<String>,
     /// A collection of current spans
     pub spans: Vec<Span>,
+    /// All errors encountered while parsing, in order
+    pub errors: Vec<ParserError>,
+    /// The identifiers defined by the parsed definitions
+    pub symbols: SymbolTable,
+    /// The comments right above each `#define`, by the start of its span
+    pub docs: BTreeMap<usize, Vec<String>>,
+    /// The constants defined when compiling, replacing those of the same name
+    pub defines: Vec<ConstantDefinition>,
+    /// The constants overridden when compiling, which the contract must define
+    pub overrides: Vec<ConstantDefinition>,
+    /// The remappings of include paths, resolved before localizing them
+    pub remappings: Vec<Remapping>,
+    /// The names of the custom builtins registered by library consumers, invoked like macros
+    pub builtins: Vec<String>,
 }
 
 impl Parser {
     /// Public associated function that instantiates a Parser.
     pub fn new(tokens: Vec<Token>, base: Option<String>) -> Self {
-        let initial_token = tokens.get(0).unwrap().clone();
-        Self { tokens, cursor: 0, current_token: initial_token, base, spans: vec![] }
+        let initial_token = tokens.first().unwrap().clone();
+        Self {
+            tokens,
+            cursor: 0,
+            current_token: initial_token,
+            base,
+            spans: vec![],
+            errors: vec![],
+            symbols: SymbolTable::new(),
+            docs: BTreeMap::new(),
+            defines: vec![],
+            overrides: vec![],
+            remappings: vec![],
+            builtins: vec![],
+        }
     }
 
     /// Resets the current token and cursor to the first token in the parser's token vec
     ///
     /// PANICS if the tokens vec is empty!
     pub fn reset(&mut self) {
-        self.current_token = self.tokens.get(0).unwrap().clone();
+        self.current_token = self.tokens.first().unwrap().clone();
         self.cursor = 0;
     }
 
     /// Parse
     pub fn parse(&mut self) -> Result<Contract, ParserError> {
+        let contract = self.parse_partial();
+
+        // Bubble up the first error, all of them are kept in `errors`
+        match self.errors.first() {
+            Some(e) => {
+                tracing::error!(target: "parser", "PARSING FAILED WITH {} ERRORS", self.errors.len());
+                Err(e.clone())
+            }
+            None => Ok(contract),
+        }
+    }
+
+    /// Parses every definition that can be recovered, keeping the errors in `errors`
+    ///
+    /// Useful for tools that work with incomplete source code, like editors.
+    pub fn parse_partial(&mut self) -> Contract {
+        // Keep the comments above the definitions for their doc comments
+        self.docs = BTreeMap::new();
+        let mut comments = vec![];
+        for token in self.tokens.iter() {
+            match &token.kind {
+                TokenKind::Comment(c) => comments.push(c.clone()),
+                TokenKind::Whitespace => {}
+                TokenKind::Define => {
+                    self.docs.insert(token.span.start, std::mem::take(&mut comments));
+                }
+                _ => comments.clear(),
+            }
+        }
+
         // Remove all whitespaces, newlines, and comments first
         self.tokens
             .retain(|token| !matches!(token.kind, TokenKind::Whitespace | TokenKind::Comment(_)));
 
         // Reset the initial token
         self.reset();
+        self.errors = vec![];
+        self.symbols = SymbolTable::new();
 
         // Initialize an empty Contract
         let mut contract = Contract::default();
 
         // First iterate over imports
-        while !self.check(TokenKind::Eof) && !self.check(TokenKind::Define) {
-            contract.imports.push(self.parse_imports()?);
-            tracing::info!(target: "parser", "SUCCESSFULLY PARSED IMPORTS");
+        while !self.check(TokenKind::Eof) &&
+            !self.check(TokenKind::Define) &&
+            !self.check(TokenKind::BuiltinFunction(String::default()))
+        {
+            let start = self.cursor;
+            match self.parse_include(&mut contract) {
+                Ok(()) => tracing::info!(target: "parser", "SUCCESSFULLY PARSED IMPORTS"),
+                Err(e) => {
+                    self.errors.push(e);
+                    self.recover(start, &[TokenKind::Include, TokenKind::Define]);
+                }
+            }
         }
 
         // Iterate over tokens and construct the Contract aka AST
@@ -67,49 +140,227 @@ impl Parser {
             // Reset our spans
             self.spans = vec![];
 
-            // first token should be keyword "#define"
-            self.match_kind(TokenKind::Define)?;
-
-            // match to fucntion, constant, macro, or event
-            match self.current_token.kind {
-                TokenKind::Function => {
-                    let func = self.parse_function()?;
-                    tracing::info!(target: "parser", "SUCCESSFULLY PARSED FUNCTION {}", func.name);
-                    contract.functions.push(func);
-                }
-                TokenKind::Event => {
-                    let ev = self.parse_event()?;
-                    tracing::info!(target: "parser", "SUCCESSFULLY PARSED EVENT {}", ev.name);
-                    contract.events.push(ev);
-                }
-                TokenKind::Constant => {
-                    let c = self.parse_constant()?;
-                    tracing::info!(target: "parser", "SUCCESSFULLY PARSED CONSTANT {}", c.name);
-                    contract.constants.push(c);
-                }
-                TokenKind::Macro => {
-                    let m = self.parse_macro()?;
-                    tracing::info!(target: "parser", "SUCCESSFULLY PARSED MACRO {}", m.name);
-                    contract.macros.push(m);
-                }
-                TokenKind::JumpTable | TokenKind::JumpTablePacked | TokenKind::CodeTable => {
-                    contract.tables.push(self.parse_table()?);
-                }
-                _ => {
-                    tracing::error!(
-                        target: "parser",
-                        "Invalid definition. Must be a function, event, constant, or macro. Got: {}",
-                        self.current_token.kind
-                    );
-                    return Err(ParserError {
-                        kind: ParserErrorKind::InvalidDefinition,
-                        spans: AstSpan(self.spans.clone()),
-                    })
+            let start = self.cursor;
+            let res = match self.current_token.kind {
+                TokenKind::BuiltinFunction(_) => {
+                    self.parse_assertion().map(|a| contract.assertions.push(a))
                 }
+                // The includes of included files follow the definitions of the including file
+                TokenKind::Include => self.parse_include(&mut contract),
+                _ => self.parse_definition(&mut contract),
             };
+            if let Err(e) = res {
+                self.errors.push(e);
+                self.recover(start, &[TokenKind::Include, TokenKind::Define]);
+            }
+        }
+
+        contract.qualify_namespaces();
+        self.errors.extend(contract.check_visibility());
+        self.errors.extend(contract.override_constants(&self.overrides));
+        contract.define_constants(&self.defines);
+        self.errors.extend(contract.expand_blocks());
+        contract.expand_aliases();
+        contract.fill_default_args();
+        contract
+    }
+
+    /// Parses an include into the contract, of a huff file or of a prebuilt artifact
+    ///
+    /// A huff file may be included under a namespace, as in `#include "./math.huff" as Math`.
+    pub fn parse_include(&mut self, contract: &mut Contract) -> Result<(), ParserError> {
+        let is_artifact =
+            matches!(self.peek().map(|t| t.kind), Some(TokenKind::Ident(i)) if i == "artifact");
+        if is_artifact {
+            return self.parse_artifact_import(contract)
+        }
+        self.spans = vec![];
+        let import = self.parse_imports()?;
+        if self.current_token.kind == TokenKind::Ident("as".to_string()) {
+            self.consume();
+            let name = self.match_kind(TokenKind::Ident("NAMESPACE".to_string()))?.to_string();
+            tracing::info!(target: "parser", "INCLUDING {:?} AS \"{}\"", import, name);
+            let span = AstSpan(self.spans.clone());
+            contract.namespaces.push(Namespace { name, path: import.clone(), span });
+        }
+        contract.imports.push(import);
+        Ok(())
+    }
+
+    /// Parses a single top-level definition into the contract
+    pub fn parse_definition(&mut self, contract: &mut Contract) -> Result<(), ParserError> {
+        // first token should be keyword "#define", documented by the comments above it
+        let natspec = self.docs.get(&self.current_token.span.start).and_then(|c| NatSpec::parse(c));
+        self.match_kind(TokenKind::Define)?;
+
+        // macros and constants may be hidden from the files including their own
+        let internal = self.check(TokenKind::Internal);
+        if internal {
+            let kind = self.peek().map(|t| t.kind);
+            if !matches!(kind, Some(TokenKind::Macro) | Some(TokenKind::Constant)) {
+                tracing::error!(target: "parser", "INTERNAL MODIFIER ON A DEFINITION THAT ISN'T A CONSTANT OR MACRO: {:?}", kind);
+                self.consume();
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidDefinition,
+                    spans: AstSpan(self.spans.clone()),
+                })
+            }
+            self.consume();
         }
 
-        Ok(contract)
+        // the definition's name follows its keyword
+        let name_span = self.peek().map(|t| t.span);
+
+        // match to fucntion, constant, macro, or event
+        match self.current_token.kind {
+            TokenKind::Function => {
+                let func = self.parse_function()?;
+                tracing::info!(target: "parser", "SUCCESSFULLY PARSED FUNCTION {}", func.name);
+                self.define(&func.name, SymbolKind::Function, name_span, &func.span);
+                if let Some(n) = natspec {
+                    contract.natspec.insert((SymbolKind::Function, func.name.to_string()), n);
+                }
+                contract.functions.push(func);
+            }
+            TokenKind::Event => {
+                let ev = self.parse_event()?;
+                tracing::info!(target: "parser", "SUCCESSFULLY PARSED EVENT {}", ev.name);
+                self.define(&ev.name, SymbolKind::Event, name_span, &ev.span);
+                if let Some(n) = natspec {
+                    contract.natspec.insert((SymbolKind::Event, ev.name.clone()), n);
+                }
+                contract.events.push(ev);
+            }
+            TokenKind::Error => {
+                let e = self.parse_error()?;
+                tracing::info!(target: "parser", "SUCCESSFULLY PARSED ERROR {}", e.name);
+                self.define(&e.name, SymbolKind::Error, name_span, &e.span);
+                if let Some(n) = natspec {
+                    contract.natspec.insert((SymbolKind::Error, e.name.clone()), n);
+                }
+                contract.errors.push(e);
+            }
+            TokenKind::Constant => {
+                let mut c = self.parse_constant()?;
+                c.internal = internal;
+                tracing::info!(target: "parser", "SUCCESSFULLY PARSED CONSTANT {}", c.name);
+                self.define(&c.name, SymbolKind::Constant, name_span, &c.span);
+                if let Some(n) = natspec {
+                    contract.natspec.insert((SymbolKind::Constant, c.name.to_string()), n);
+                }
+                contract.constants.push(c);
+            }
+            TokenKind::Immutable => {
+                let i = self.parse_immutable()?;
+                tracing::info!(target: "parser", "SUCCESSFULLY PARSED IMMUTABLE {}", i.name);
+                self.define(&i.name, SymbolKind::Constant, name_span, &i.span);
+                if let Some(n) = natspec {
+                    contract.natspec.insert((SymbolKind::Constant, i.name.clone()), n);
+                }
+                contract.immutables.push(i);
+            }
+            TokenKind::Alias => {
+                let a = self.parse_alias()?;
+                tracing::info!(target: "parser", "SUCCESSFULLY PARSED ALIAS {}", a.name);
+                self.define(&a.name, SymbolKind::Alias, name_span, &a.span);
+                contract.aliases.push(a);
+            }
+            TokenKind::Macro => {
+                let mut m = self.parse_macro()?;
+                m.internal = internal;
+                tracing::info!(target: "parser", "SUCCESSFULLY PARSED MACRO {}", m.name);
+                self.define(&m.name, SymbolKind::Macro, name_span, &m.span);
+                self.symbols.insert_labels(&m);
+                if let Some(n) = natspec {
+                    contract.natspec.insert((SymbolKind::Macro, m.name.to_string()), n);
+                }
+                contract.macros.push(m);
+            }
+            TokenKind::Fallback | TokenKind::Receive => {
+                // The first definition is used, like for macros
+                let entrypoint = match self.current_token.kind {
+                    TokenKind::Fallback => &mut contract.fallback,
+                    _ => &mut contract.receive,
+                };
+                let name_span = Some(self.current_token.span.clone());
+                let e = self.parse_entrypoint()?;
+                tracing::info!(target: "parser", "SUCCESSFULLY PARSED {}", e.name.to_uppercase());
+                self.define(&e.name, SymbolKind::Macro, name_span, &e.span);
+                self.symbols.insert_labels(&e);
+                entrypoint.get_or_insert(e);
+            }
+            TokenKind::Test => {
+                let t = self.parse_test()?;
+                tracing::info!(target: "parser", "SUCCESSFULLY PARSED TEST {}", t.name);
+                self.define(&t.name, SymbolKind::Test, name_span, &t.span);
+                self.symbols.insert_labels(&t);
+                contract.tests.push(t);
+            }
+            TokenKind::JumpTable | TokenKind::JumpTablePacked | TokenKind::CodeTable => {
+                let t = self.parse_table()?;
+                self.define(&t.name, SymbolKind::Table, name_span, &t.span);
+                contract.tables.push(t);
+            }
+            _ => {
+                tracing::error!(
+                    target: "parser",
+                    "Invalid definition. Must be a function, event, constant, or macro. Got: {}",
+                    self.current_token.kind
+                );
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidDefinition,
+                    spans: AstSpan(self.spans.clone()),
+                })
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Records a top-level definition in the symbol table
+    fn define(&mut self, name: &str, kind: SymbolKind, name_span: Option<Span>, span: &AstSpan) {
+        let name_span = match name_span.or_else(|| span.0.first().cloned()) {
+            Some(s) => s,
+            None => return,
+        };
+        self.symbols.insert(Symbol {
+            name: name.to_string(),
+            kind,
+            span: name_span,
+            definition: span.clone(),
+            scope: None,
+        });
+    }
+
+    /// Recovers from a parser error by skipping tokens until one of the boundary kinds (or EOF).
+    ///
+    /// At least one token is skipped if the error occured at the `start` cursor, to guarantee
+    /// progress.
+    pub fn recover(&mut self, start: usize, boundaries: &[TokenKind]) {
+        let at_boundary = |p: &mut Parser| {
+            boundaries.iter().any(|b| p.check(b.clone())) || p.check(TokenKind::Eof)
+        };
+        if self.cursor == start && !self.check(TokenKind::Eof) {
+            self.consume();
+        }
+        while !at_boundary(self) {
+            self.consume();
+        }
+        tracing::debug!(target: "parser", "RECOVERED AT TOKEN: {}", self.current_token.kind);
+        self.spans = vec![];
+    }
+
+    /// Records an invalid statement token and skips over it, if it can be recovered from.
+    ///
+    /// Tokens that may start the next definition, or EOF, are not recoverable at the statement
+    /// level and are returned as an error to recover at the definition boundary instead.
+    fn recover_statement(&mut self, error: ParserError) -> Result<(), ParserError> {
+        if self.check(TokenKind::Eof) || self.check(TokenKind::Define) {
+            return Err(error)
+        }
+        self.errors.push(error);
+        self.consume();
+        Ok(())
     }
 
     /// Parses Contract Imports
@@ -117,6 +368,22 @@ impl Parser {
         // First token should be keyword "#include"
         self.match_kind(TokenKind::Include)?;
 
+        // Bundled modules are validated against the modules embedded in the compiler
+        if let TokenKind::BundledPath(p) = self.current_token.kind.clone() {
+            let path = format!("<{}>", p);
+            if bundled_module(&p).is_none() {
+                tracing::error!(target: "parser", "UNKNOWN BUNDLED MODULE: {}", path);
+                let mut new_spans = std::mem::take(&mut self.spans);
+                new_spans.push(self.current_token.span.clone());
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidImportPath(path),
+                    spans: AstSpan(new_spans),
+                })
+            }
+            self.consume();
+            return Ok(PathBuf::from(path))
+        }
+
         // Then let's grab and validate the file path
         self.match_kind(TokenKind::Str("x".to_string()))?;
         let tok = self.peek_behind().unwrap().kind;
@@ -133,14 +400,16 @@ impl Parser {
             }
         };
 
-        // Localize import path using out base
-        p = match &self.base {
-            Some(b) => FileSource::localize_file(b, &p).unwrap_or_default().replacen(
+        // Remap the import path, or localize it relative to the including file
+        let including = self.peek_behind().and_then(|t| t.span.file).map(|f| f.path.clone());
+        p = match (FileSource::remap(&p, &self.remappings), including.or(self.base.clone())) {
+            (Some(remapped), _) => remapped,
+            (None, Some(b)) => FileSource::localize_file(&b, &p).unwrap_or_default().replacen(
                 "contracts/contracts",
                 "contracts",
                 1,
             ),
-            None => p,
+            (None, None) => p,
         };
         tracing::info!(target: "parser", "LOCALIZED IMPORT: {}", p);
 
@@ -160,6 +429,71 @@ impl Parser {
         Ok(path.to_path_buf())
     }
 
+    /// Parses the import of a prebuilt artifact, as in `#include artifact "./out/Lib.json"`,
+    /// defining its selectors, event hashes, storage slots and bytecode in the contract.
+    ///
+    /// The path is relative to the including file. The definitions are prefixed with the
+    /// artifact's file name, up to its first `.`, in screaming snake case.
+    pub fn parse_artifact_import(&mut self, contract: &mut Contract) -> Result<(), ParserError> {
+        self.spans = vec![];
+        self.match_kind(TokenKind::Include)?;
+        self.match_kind(TokenKind::Ident("artifact".to_string()))?;
+        let path_span = self.current_token.span.clone();
+        let path = match self.current_token.kind.clone() {
+            TokenKind::Str(p) => {
+                self.consume();
+                p
+            }
+            kind => {
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidName(kind),
+                    spans: AstSpan(vec![path_span]),
+                })
+            }
+        };
+
+        let localized = match &self.base {
+            Some(b) => FileSource::localize_file(b, &path).unwrap_or_default(),
+            None => path.clone(),
+        };
+        tracing::info!(target: "parser", "READING ARTIFACT: {}", localized);
+        let artifact = Artifact::import(&localized).map_err(|e| {
+            tracing::error!(target: "parser", "FAILED TO READ ARTIFACT \"{}\": {}", localized, e);
+            ParserError {
+                kind: ParserErrorKind::InvalidArtifactImport(path.clone()),
+                spans: AstSpan(vec![path_span.clone()]),
+            }
+        })?;
+
+        let file_name = Path::new(&localized).file_name().unwrap_or_default().to_string_lossy();
+        let name = to_screaming_snake_case(file_name.split('.').next().unwrap_or_default());
+        let span = AstSpan(self.spans.clone());
+        let (constants, tables) = (contract.constants.len(), contract.tables.len());
+        contract
+            .import_artifact(
+                ArtifactImport {
+                    name,
+                    path: Path::new(&localized).to_path_buf(),
+                    span: span.clone(),
+                },
+                &artifact,
+            )
+            .map_err(|e| {
+                tracing::error!(target: "parser", "INVALID BYTECODE IN ARTIFACT \"{}\": {}", localized, e);
+                ParserError {
+                    kind: ParserErrorKind::InvalidArtifactImport(path.clone()),
+                    spans: AstSpan(vec![path_span.clone()]),
+                }
+            })?;
+        for c in contract.constants.iter().skip(constants) {
+            self.define(&c.name, SymbolKind::Constant, None, &span);
+        }
+        for t in contract.tables.iter().skip(tables) {
+            self.define(&t.name, SymbolKind::Table, None, &span);
+        }
+        Ok(())
+    }
+
     /// Match current token to a type.
     pub fn match_kind(&mut self, kind: TokenKind) -> Result<TokenKind, ParserError> {
         if std::mem::discriminant(&self.current_token.kind) == std::mem::discriminant(&kind) {
@@ -204,7 +538,7 @@ impl Parser {
         if self.cursor >= self.tokens.len() {
             None
         } else {
-            Some(self.tokens.get(self.cursor + 1).unwrap().clone())
+            self.tokens.get(self.cursor + 1).cloned()
         }
     }
 
@@ -267,7 +601,7 @@ impl Parser {
         hasher.finalize(&mut signature);
 
         Ok(Function {
-            name,
+            name: name.into(),
             signature,
             inputs,
             fn_type,
@@ -302,6 +636,26 @@ impl Parser {
         Ok(Event { name, parameters, span: AstSpan(self.spans.clone()) })
     }
 
+    /// Parse a custom error.
+    ///
+    /// It should parse the following : error ERROR_NAME(uint256 a, ...)
+    pub fn parse_error(&mut self) -> Result<ErrorDefinition, ParserError> {
+        self.match_kind(TokenKind::Error)?;
+        let name: String = self.match_kind(TokenKind::Ident("ERROR_NAME".to_string()))?.to_string();
+
+        // Parse the error's parameters
+        let parameters: Vec<Argument> = self.parse_args(true, true, false)?;
+
+        let mut selector = [0u8; 4];
+        let mut hasher = Keccak::v256();
+        let types =
+            parameters.iter().map(|p| p.arg_type.clone().unwrap_or_default()).collect::<Vec<_>>();
+        hasher.update(format!("{}({})", name, types.join(",")).as_bytes());
+        hasher.finalize(&mut selector);
+
+        Ok(ErrorDefinition { name, selector, parameters, span: AstSpan(self.spans.clone()) })
+    }
+
     /// Parse a constant.
     pub fn parse_constant(&mut self) -> Result<ConstantDefinition, ParserError> {
         // Constant Identifier
@@ -329,7 +683,7 @@ impl Parser {
         let value: ConstVal = match self.current_token.kind.clone() {
             TokenKind::FreeStoragePointer => {
                 self.consume();
-                ConstVal::FreeStoragePointer(FreeStoragePointer {})
+                ConstVal::FreeStoragePointer(self.parse_storage_reservation()?)
             }
             TokenKind::Literal(l) => {
                 self.consume();
@@ -351,7 +705,109 @@ impl Parser {
         self.spans = vec![];
 
         // Return the Constant Definition
-        Ok(ConstantDefinition { name, value, span: AstSpan(new_spans) })
+        Ok(ConstantDefinition {
+            name: name.into(),
+            value,
+            span: AstSpan(new_spans),
+            internal: false,
+        })
+    }
+
+    /// Parses an immutable.
+    ///
+    /// It should parse the following : immutable NAME
+    ///
+    /// Immutables have no value in the source, the constructor sets them with `__SET_IMMUTABLE`.
+    pub fn parse_immutable(&mut self) -> Result<ImmutableDefinition, ParserError> {
+        self.match_kind(TokenKind::Immutable)?;
+        let name: String = self.match_kind(TokenKind::Ident("NAME".to_string()))?.to_string();
+
+        let new_spans = self.spans.clone();
+        self.spans = vec![];
+        Ok(ImmutableDefinition { name, span: AstSpan(new_spans) })
+    }
+
+    /// Parses the size of a `FREE_STORAGE_POINTER(n)` reservation, following its keyword
+    ///
+    /// The size is a number or a hex literal of at least one slot. `FREE_STORAGE_POINTER()`
+    /// reserves a single slot.
+    pub fn parse_storage_reservation(&mut self) -> Result<FreeStoragePointer, ParserError> {
+        let size = match self.current_token.kind.clone() {
+            TokenKind::Num(n) => Some(n),
+            TokenKind::Literal(l) => {
+                let (high, low) = l.split_at(24);
+                high.iter()
+                    .all(|b| *b == 0)
+                    .then(|| u64::from_be_bytes(low.try_into().unwrap()))
+                    .and_then(|n| usize::try_from(n).ok())
+            }
+            _ => return Ok(FreeStoragePointer::default()),
+        };
+        match size {
+            Some(size) if size > 0 => {
+                self.consume();
+                self.match_kind(TokenKind::CloseParen)?;
+                Ok(FreeStoragePointer { size })
+            }
+            _ => {
+                tracing::error!(target: "parser", "INVALID STORAGE RESERVATION SIZE: {}", self.current_token.kind);
+                let kind = self.current_token.kind.clone();
+                self.spans.push(self.current_token.span.clone());
+                let new_spans = self.spans.clone();
+                self.spans = vec![];
+                Err(ParserError {
+                    kind: ParserErrorKind::InvalidConstantValue(kind),
+                    spans: AstSpan(new_spans),
+                })
+            }
+        }
+    }
+
+    /// Parses an opcode alias.
+    ///
+    /// It should parse the following : alias ALIAS_NAME = 0x40 mload
+    ///
+    /// The aliased literals, opcodes and constant pushes extend up to the next definition.
+    pub fn parse_alias(&mut self) -> Result<AliasDefinition, ParserError> {
+        self.match_kind(TokenKind::Alias)?;
+        let name: String = self.match_kind(TokenKind::Ident("ALIAS_NAME".to_string()))?.to_string();
+        self.match_kind(TokenKind::Assign)?;
+
+        let mut statements: Vec<Statement> = Vec::new();
+        loop {
+            let curr_spans = vec![self.current_token.span.clone()];
+            let ty = match self.current_token.kind.clone() {
+                TokenKind::Define | TokenKind::Eof if !statements.is_empty() => break,
+                TokenKind::Literal(val) => {
+                    self.consume();
+                    StatementType::Literal(val)
+                }
+                TokenKind::Opcode(o) => {
+                    self.consume();
+                    StatementType::Opcode(o)
+                }
+                TokenKind::OpenBracket => {
+                    let (constant, const_span) = self.parse_constant_push()?;
+                    statements.push(Statement {
+                        ty: StatementType::Constant(constant.into()),
+                        span: AstSpan(vec![const_span]),
+                    });
+                    continue
+                }
+                kind => {
+                    tracing::error!(target: "parser", "TOKEN MISMATCH - ALIAS BODY: {}", kind);
+                    return Err(ParserError {
+                        kind: ParserErrorKind::InvalidTokenInAliasBody(kind),
+                        spans: AstSpan(curr_spans),
+                    })
+                }
+            };
+            statements.push(Statement { ty, span: AstSpan(curr_spans) });
+        }
+
+        let new_spans = self.spans.clone();
+        self.spans = vec![];
+        Ok(AliasDefinition { name, statements, span: AstSpan(new_spans) })
     }
 
     /// Parses a macro.
@@ -363,7 +819,7 @@ impl Parser {
             self.match_kind(TokenKind::Ident("MACRO_NAME".to_string()))?.to_string();
         tracing::info!(target: "parser", "PARSING MACRO: \"{}\"", macro_name);
 
-        let macro_arguments: Vec<Argument> = self.parse_args(true, false, false)?;
+        let macro_arguments: Vec<Argument> = self.parse_macro_params()?;
         self.match_kind(TokenKind::Assign)?;
         self.match_kind(TokenKind::Takes)?;
         let macro_takes: usize = self.parse_single_arg()?;
@@ -381,6 +837,43 @@ impl Parser {
         ))
     }
 
+    /// Parses a fallback or receive definition into a macro named after its keyword.
+    ///
+    /// Like macros, their definition declares stack effects, but no parameters.
+    pub fn parse_entrypoint(&mut self) -> Result<MacroDefinition, ParserError> {
+        let name = self.current_token.kind.to_string();
+        self.consume();
+        tracing::info!(target: "parser", "PARSING {}", name.to_uppercase());
+
+        self.match_kind(TokenKind::OpenParen)?;
+        self.match_kind(TokenKind::CloseParen)?;
+        self.match_kind(TokenKind::Assign)?;
+        self.match_kind(TokenKind::Takes)?;
+        let takes: usize = self.parse_single_arg()?;
+        self.match_kind(TokenKind::Returns)?;
+        let returns: usize = self.parse_single_arg()?;
+        let statements: Vec<Statement> = self.parse_body()?;
+
+        Ok(MacroDefinition::new(name, vec![], statements, takes, returns, self.spans.clone()))
+    }
+
+    /// Parses a test definition into a macro with typed parameters.
+    ///
+    /// It should parse the following : test TEST_NAME(uint256 a, ...) = {...}
+    ///
+    /// Tests run as the whole runtime bytecode of a contract, so they declare no stack effects.
+    pub fn parse_test(&mut self) -> Result<MacroDefinition, ParserError> {
+        self.match_kind(TokenKind::Test)?;
+        let name: String = self.match_kind(TokenKind::Ident("TEST_NAME".to_string()))?.to_string();
+        tracing::info!(target: "parser", "PARSING TEST: \"{}\"", name);
+
+        let parameters: Vec<Argument> = self.parse_args(true, true, false)?;
+        self.match_kind(TokenKind::Assign)?;
+        let statements: Vec<Statement> = self.parse_body()?;
+
+        Ok(MacroDefinition::new(name, parameters, statements, 0, 0, self.spans.clone()))
+    }
+
     /// Parse the body of a macro.
     ///
     /// Only HEX, OPCODES, labels and MACRO calls should be authorized.
@@ -421,19 +914,12 @@ impl Parser {
                             if let Some(i) = self.spans.iter().position(|s| s.eq(&curr_spans[0])) {
                                 curr_spans.append(&mut self.spans[(i + 1)..].to_vec());
                             }
-                            statements.push(Statement {
-                                ty: StatementType::MacroInvocation(MacroInvocation {
-                                    macro_name: ident_str.to_string(),
-                                    args: lit_args,
-                                    span: AstSpan(curr_spans.clone()),
-                                }),
-                                span: AstSpan(curr_spans),
-                            });
+                            statements.push(self.invocation(ident_str, lit_args, curr_spans));
                         }
                         _ => {
                             tracing::info!(target: "parser", "LABEL CALL TO: {}", ident_str);
                             statements.push(Statement {
-                                ty: StatementType::LabelCall(ident_str),
+                                ty: StatementType::LabelCall(ident_str.into()),
                                 span: AstSpan(curr_spans),
                             });
                         }
@@ -447,7 +933,7 @@ impl Parser {
                     tracing::info!(target: "parser", "PARSED LABEL \"{}\" INSIDE MACRO WITH {} STATEMENTS.", l, inner_statements.len());
                     statements.push(Statement {
                         ty: StatementType::Label(Label {
-                            name: l,
+                            name: l.into(),
                             inner: inner_statements,
                             span: AstSpan(curr_spans.clone()),
                         }),
@@ -458,7 +944,7 @@ impl Parser {
                     let (constant, const_span) = self.parse_constant_push()?;
                     tracing::info!(target: "parser", "PARSING MACRO BODY: [CONSTANT: {}]", constant);
                     statements.push(Statement {
-                        ty: StatementType::Constant(constant),
+                        ty: StatementType::Constant(constant.into()),
                         span: AstSpan(vec![const_span]),
                     });
                 }
@@ -470,27 +956,17 @@ impl Parser {
                         span: AstSpan(vec![arg_span]),
                     });
                 }
-                TokenKind::BuiltinFunction(f) => {
-                    let mut curr_spans = vec![self.current_token.span.clone()];
-                    self.match_kind(TokenKind::BuiltinFunction(String::default()))?;
-                    let args = self.parse_args(true, false, false)?;
-                    args.iter().for_each(|a| curr_spans.extend_from_slice(&a.span.0));
-                    tracing::info!(target: "parser", "PARSING MACRO BODY: [BUILTIN FN: {}({:?})]", f, args);
-                    statements.push(Statement {
-                        ty: StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
-                            kind: BuiltinFunctionKind::from(f.as_str()),
-                            args,
-                            span: AstSpan(curr_spans.clone()),
-                        }),
-                        span: AstSpan(curr_spans),
-                    });
+                TokenKind::BuiltinFunction(_) => {
+                    let statement = self.parse_builtin_call()?;
+                    tracing::info!(target: "parser", "PARSING MACRO BODY: [{}]", statement.ty);
+                    statements.push(statement);
                 }
                 kind => {
                     tracing::error!(target: "parser", "TOKEN MISMATCH - MACRO BODY: {}", kind);
-                    return Err(ParserError {
+                    self.recover_statement(ParserError {
                         kind: ParserErrorKind::InvalidTokenInMacroBody(kind),
                         spans: AstSpan(vec![self.current_token.span.clone()]),
-                    })
+                    })?;
                 }
             };
         }
@@ -549,19 +1025,12 @@ impl Parser {
                             if let Some(i) = self.spans.iter().position(|s| s.eq(&curr_spans[0])) {
                                 curr_spans.append(&mut self.spans[(i + 1)..].to_vec());
                             }
-                            statements.push(Statement {
-                                ty: StatementType::MacroInvocation(MacroInvocation {
-                                    macro_name: ident_str.to_string(),
-                                    args: lit_args,
-                                    span: AstSpan(curr_spans.clone()),
-                                }),
-                                span: AstSpan(curr_spans),
-                            });
+                            statements.push(self.invocation(ident_str, lit_args, curr_spans));
                         }
                         _ => {
                             tracing::info!(target: "parser", "LABEL CALL TO: {}", ident_str);
                             statements.push(Statement {
-                                ty: StatementType::LabelCall(ident_str),
+                                ty: StatementType::LabelCall(ident_str.into()),
                                 span: AstSpan(curr_spans),
                             });
                         }
@@ -571,7 +1040,7 @@ impl Parser {
                     let (constant, const_span) = self.parse_constant_push()?;
                     tracing::info!(target: "parser", "PARSING LABEL BODY: [CONSTANT: {}]", constant);
                     statements.push(Statement {
-                        ty: StatementType::Constant(constant),
+                        ty: StatementType::Constant(constant.into()),
                         span: AstSpan(vec![const_span]),
                     });
                 }
@@ -583,19 +1052,126 @@ impl Parser {
                         span: AstSpan(vec![arg_span]),
                     });
                 }
+                TokenKind::BuiltinFunction(_) => {
+                    let statement = self.parse_builtin_call()?;
+                    tracing::info!(target: "parser", "PARSING LABEL BODY: [{}]", statement.ty);
+                    statements.push(statement);
+                }
                 kind => {
                     let curr_spans = vec![self.current_token.span.clone()];
                     tracing::error!(target: "parser", "TOKEN MISMATCH - LABEL BODY: {}", kind);
-                    return Err(ParserError {
+                    self.recover_statement(ParserError {
                         kind: ParserErrorKind::InvalidTokenInLabelDefinition(kind),
                         spans: AstSpan(curr_spans),
-                    })
+                    })?;
                 }
             };
         }
         Ok(statements)
     }
 
+    /// The statement of an invocation, calling a custom builtin if one is registered by the
+    /// name, or a macro
+    fn invocation(&self, name: String, args: Vec<MacroArg>, spans: Vec<Span>) -> Statement {
+        let ty = match self.builtins.contains(&name) {
+            true => {
+                tracing::info!(target: "parser", "PARSED CUSTOM BUILTIN FN: {}({:?})", name, args);
+                StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                    kind: BuiltinFunctionKind::Custom(name, args),
+                    args: vec![],
+                    span: AstSpan(spans.clone()),
+                })
+            }
+            false => StatementType::MacroInvocation(MacroInvocation {
+                macro_name: name.into(),
+                args,
+                span: AstSpan(spans.clone()),
+            }),
+        };
+        Statement { ty, span: AstSpan(spans) }
+    }
+
+    /// Parses a builtin function call, in a macro or label body
+    pub fn parse_builtin_call(&mut self) -> Result<Statement, ParserError> {
+        let mut curr_spans = vec![self.current_token.span.clone()];
+        let f = match self.match_kind(TokenKind::BuiltinFunction(String::default()))? {
+            TokenKind::BuiltinFunction(f) => f,
+            _ => unreachable!("the token kind was matched"),
+        };
+        let (kind, args) = match f.as_str() {
+            "__REPEAT" => return self.parse_repeat(curr_spans),
+            "__IFDEF" | "__IFNDEF" => return self.parse_conditional(f == "__IFDEF", curr_spans),
+            "__ASSERT_CODEHASH" => {
+                let (arg, hash) = self.parse_assert_codehash_args()?;
+                (BuiltinFunctionKind::AssertCodehash(hash), vec![arg])
+            }
+            "__KECCAK256" => {
+                let (arg, preimage) = self.parse_keccak256_args()?;
+                (BuiltinFunctionKind::Keccak256(preimage), vec![arg])
+            }
+            "__RIGHTPAD" => {
+                let (arg, padded) = self.parse_rightpad_args()?;
+                (BuiltinFunctionKind::RightPad(padded), vec![arg])
+            }
+            "__VERBATIM" => {
+                let (arg, bytes) = self.parse_verbatim_args()?;
+                (BuiltinFunctionKind::Verbatim(bytes), vec![arg])
+            }
+            "__MSTORE_STRING" => {
+                let (arg, string) = self.parse_string_args()?;
+                (BuiltinFunctionKind::MstoreString(string), vec![arg])
+            }
+            "__REVERT_STRING" => {
+                let (arg, reason) = self.parse_string_args()?;
+                (BuiltinFunctionKind::RevertString(reason), vec![arg])
+            }
+            "__ASSERT" => {
+                let (arg, expression) = self.parse_assertion_args()?;
+                (BuiltinFunctionKind::Assert(Box::new(expression)), vec![arg])
+            }
+            // Only the arithmetic operators, of precedence 4 and up, are read after the offset, so
+            // that a following arg call isn't mistaken for a comparison
+            "__OFFSET" => {
+                let start = self.spans.len();
+                let label = self.parse_offset_args()?;
+                let label = Expression::LabelOffset(label.into());
+                let expression = self.parse_binary_expression(label, 4)?;
+                let arg =
+                    Argument { span: AstSpan(self.spans[start..].to_vec()), ..Default::default() };
+                (BuiltinFunctionKind::Offset(Box::new(expression)), vec![arg])
+            }
+            // Builtins naming a single definition
+            "__SET_IMMUTABLE" | "__LINK" | "__DECODE_CALLDATA" | "__ENCODE_OUTPUTS" | "__EMIT" => {
+                let args = self.parse_args(true, false, false)?;
+                if args.len() != 1 {
+                    return Err(ParserError {
+                        kind: ParserErrorKind::InvalidArgs(TokenKind::BuiltinFunction(f)),
+                        spans: AstSpan(curr_spans),
+                    })
+                }
+                (BuiltinFunctionKind::from(f.as_str()), args)
+            }
+            _ => match BuiltinFunctionKind::from(f.as_str()) {
+                // Cheatcodes take their arguments from the stack
+                kind @ BuiltinFunctionKind::Cheatcode(_) => {
+                    self.parse_empty_args()?;
+                    (kind, vec![])
+                }
+                kind => (kind, self.parse_args(true, false, false)?),
+            },
+        };
+        args.iter().for_each(|a| curr_spans.extend_from_slice(&a.span.0));
+        tracing::info!(target: "parser", "PARSED BUILTIN FN: {}({:?})", f, args);
+        Ok(Statement {
+            ty: StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                kind,
+                args,
+                span: AstSpan(curr_spans.clone()),
+            }),
+            span: AstSpan(curr_spans),
+        })
+    }
+
     /// Parse new lines.
     ///
     /// No-return since newlines are non-essential.
@@ -612,6 +1188,8 @@ impl Parser {
     /// Arguments can be typed or not. Between parenthesis.
     /// Works for both inputs and outputs.
     /// It should parse the following : (uint256 a, bool b, ...)
+    ///
+    /// The last argument may be followed by a trailing comma, but a comma must follow an argument.
     pub fn parse_args(
         &mut self,
         select_name: bool,
@@ -627,7 +1205,10 @@ impl Parser {
             // type comes first
             if select_type {
                 arg_spans.push(self.current_token.span.clone());
-                arg.arg_type = Some(self.parse_arg_type()?.to_string());
+                arg.arg_type = Some(match self.check(TokenKind::OpenParen) {
+                    true => self.parse_tuple_type()?,
+                    false => self.parse_arg_type()?.to_string(),
+                });
                 // Check if the argument is indexed
                 if has_indexed && self.check(TokenKind::Indexed) {
                     arg.indexed = true;
@@ -642,6 +1223,14 @@ impl Parser {
                 arg.name = Some(self.match_kind(TokenKind::Ident("x".to_string()))?.to_string())
             }
 
+            // an argument without a type or name, like in `(a,,)`, is invalid
+            if arg_spans.is_empty() {
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidArgs(self.current_token.kind.clone()),
+                    spans: AstSpan(vec![self.current_token.span.clone()]),
+                })
+            }
+
             // multiple args possible
             if self.check(TokenKind::Comma) {
                 self.consume();
@@ -656,6 +1245,442 @@ impl Parser {
         Ok(args)
     }
 
+    /// Parses the parameters of a macro definition: (a, b = fail, ...)
+    ///
+    /// A parameter may be followed by a default, passed by the invocations omitting it. Defaults
+    /// are literals, labels, opcodes or constants. Only trailing arguments can be omitted, so the
+    /// parameters following a default must have one too.
+    pub fn parse_macro_params(&mut self) -> Result<Vec<Argument>, ParserError> {
+        let mut params: Vec<Argument> = Vec::new();
+        self.match_kind(TokenKind::OpenParen)?;
+        while !self.check(TokenKind::CloseParen) {
+            let mut param = Argument::default();
+            let mut param_spans = vec![self.current_token.span.clone()];
+            match self.current_token.kind.clone() {
+                TokenKind::Ident(name) => param.name = Some(name),
+                kind => {
+                    return Err(ParserError {
+                        kind: ParserErrorKind::InvalidArgs(kind),
+                        spans: AstSpan(param_spans),
+                    })
+                }
+            }
+            self.consume();
+
+            if self.check(TokenKind::Assign) {
+                self.consume();
+                let default_span = self.current_token.span.clone();
+                match self.parse_macro_arg()? {
+                    // There is no enclosing macro to take an arg call from
+                    MacroArg::ArgCall(_) => {
+                        return Err(ParserError {
+                            kind: ParserErrorKind::InvalidMacroArgs(TokenKind::LeftAngle),
+                            spans: AstSpan(vec![default_span]),
+                        })
+                    }
+                    default => param.default = Some(default),
+                }
+                param_spans.push(default_span);
+            } else if params.iter().any(|p| p.default.is_some()) {
+                return Err(ParserError {
+                    kind: ParserErrorKind::MissingParameterDefault(
+                        param.name.unwrap_or_default(),
+                    ),
+                    spans: AstSpan(param_spans),
+                })
+            }
+
+            // multiple params possible
+            if self.check(TokenKind::Comma) {
+                self.consume();
+            }
+
+            param.span = AstSpan(param_spans);
+            params.push(param);
+        }
+        // consume close parenthesis
+        self.match_kind(TokenKind::CloseParen)?;
+        Ok(params)
+    }
+
+    /// Parses the arguments of a codehash assertion: (NAME, 0x...)
+    ///
+    /// Returns the named macro or table as an argument, along with the expected hash. The hash may
+    /// be followed by a trailing comma.
+    pub fn parse_assert_codehash_args(&mut self) -> Result<(Argument, Literal), ParserError> {
+        self.match_kind(TokenKind::OpenParen)?;
+        let name_span = self.current_token.span.clone();
+        let name = match self.match_kind(TokenKind::Ident("x".to_string())) {
+            Ok(TokenKind::Ident(name)) => name,
+            _ => {
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidArgs(self.current_token.kind.clone()),
+                    spans: AstSpan(vec![self.current_token.span.clone()]),
+                })
+            }
+        };
+        self.match_kind(TokenKind::Comma)?;
+        let hash_span = self.current_token.span.clone();
+        let hash = match self.match_kind(TokenKind::Literal(Literal::default())) {
+            Ok(TokenKind::Literal(hash)) => hash,
+            _ => {
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidArgs(self.current_token.kind.clone()),
+                    spans: AstSpan(vec![self.current_token.span.clone()]),
+                })
+            }
+        };
+        if self.check(TokenKind::Comma) {
+            self.consume();
+        }
+        self.match_kind(TokenKind::CloseParen)?;
+        let arg = Argument {
+            name: Some(name),
+            span: AstSpan(vec![name_span, hash_span]),
+            ..Default::default()
+        };
+        Ok((arg, hash))
+    }
+
+    /// Parses the preimage of `__KECCAK256`, a string or a hex literal : ("x") or (0x01)
+    ///
+    /// Strings are hashed as their UTF-8 bytes, with their escapes resolved. Hex literals are
+    /// hashed as their bytes as written, keeping their leading zeros.
+    pub fn parse_keccak256_args(&mut self) -> Result<(Argument, Vec<u8>), ParserError> {
+        self.match_kind(TokenKind::OpenParen)?;
+        let span = self.current_token.span.clone();
+        let preimage = match self.current_token.kind.clone() {
+            TokenKind::Str(s) => unescape(&s),
+            TokenKind::Literal(l) => written_bytes(&l, &span),
+            kind => {
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidArgs(kind),
+                    spans: AstSpan(vec![span]),
+                })
+            }
+        };
+        self.consume();
+        if self.check(TokenKind::Comma) {
+            self.consume();
+        }
+        self.match_kind(TokenKind::CloseParen)?;
+        Ok((Argument { span: AstSpan(vec![span]), ..Default::default() }, preimage))
+    }
+
+    /// Parses the count, optional index constant and body of a `__REPEAT` block :
+    /// (0x04, INDEX) { ... }
+    ///
+    /// The count is a literal or a constant, resolved when the repeat is expanded. The body can't
+    /// define labels.
+    pub fn parse_repeat(&mut self, mut spans: Vec<Span>) -> Result<Statement, ParserError> {
+        self.match_kind(TokenKind::OpenParen)?;
+        spans.push(self.current_token.span.clone());
+        let count = match self.current_token.kind.clone() {
+            TokenKind::Literal(l) => {
+                self.consume();
+                MacroArg::Literal(l)
+            }
+            TokenKind::OpenBracket => MacroArg::Constant(self.parse_constant_push()?.0.into()),
+            kind => {
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidArgs(kind),
+                    spans: AstSpan(vec![self.current_token.span.clone()]),
+                })
+            }
+        };
+        let index = match self.check(TokenKind::Comma) {
+            true => {
+                self.consume();
+                spans.push(self.current_token.span.clone());
+                Some(self.match_kind(TokenKind::Ident(String::default()))?.to_string())
+            }
+            false => None,
+        };
+        self.match_kind(TokenKind::CloseParen)?;
+        let inner = self.parse_body()?;
+        // Each repetition would define the labels again
+        if let Some(l) = find_label(&inner) {
+            return Err(ParserError {
+                kind: ParserErrorKind::InvalidTokenInMacroBody(TokenKind::Label(
+                    l.name.to_string(),
+                )),
+                spans: AstSpan(vec![l.span.0[0].clone()]),
+            })
+        }
+        inner.iter().for_each(|s| spans.extend_from_slice(&s.span.0));
+        tracing::info!(target: "parser", "PARSED REPEAT WITH {} STATEMENTS", inner.len());
+        Ok(Statement {
+            ty: StatementType::Repeat(Repeat { count, index, inner, span: AstSpan(spans.clone()) }),
+            span: AstSpan(spans),
+        })
+    }
+
+    /// Parses the tested constant and body of an `__IFDEF` or `__IFNDEF` block : (NAME) { ... }
+    pub fn parse_conditional(
+        &mut self,
+        defined: bool,
+        mut spans: Vec<Span>,
+    ) -> Result<Statement, ParserError> {
+        self.match_kind(TokenKind::OpenParen)?;
+        spans.push(self.current_token.span.clone());
+        let constant = match self.current_token.kind.clone() {
+            TokenKind::Ident(name) => {
+                self.consume();
+                name
+            }
+            kind => {
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidArgs(kind),
+                    spans: AstSpan(vec![self.current_token.span.clone()]),
+                })
+            }
+        };
+        self.match_kind(TokenKind::CloseParen)?;
+        let inner = self.parse_body()?;
+        inner.iter().for_each(|s| spans.extend_from_slice(&s.span.0));
+        tracing::info!(target: "parser", "PARSED CONDITIONAL ON \"{}\" WITH {} STATEMENTS", constant, inner.len());
+        Ok(Statement {
+            ty: StatementType::Conditional(Conditional {
+                constant: constant.into(),
+                defined,
+                inner,
+                span: AstSpan(spans.clone()),
+            }),
+            span: AstSpan(spans),
+        })
+    }
+
+    /// Parses a top-level assertion : __ASSERT(expr)
+    pub fn parse_assertion(&mut self) -> Result<Assertion, ParserError> {
+        let span = self.current_token.span.clone();
+        match self.match_kind(TokenKind::BuiltinFunction(String::default()))? {
+            TokenKind::BuiltinFunction(f) if f == "__ASSERT" => {}
+            kind => {
+                tracing::error!(target: "parser", "INVALID TOP-LEVEL BUILTIN: {}", kind);
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidDefinition,
+                    spans: AstSpan(vec![span]),
+                })
+            }
+        }
+        let (arg, expression) = self.parse_assertion_args()?;
+        let mut spans = vec![span];
+        spans.extend(arg.span.0);
+        tracing::info!(target: "parser", "PARSED ASSERTION: {}", expression);
+        Ok(Assertion { expression, span: AstSpan(spans) })
+    }
+
+    /// Parses the constant expression of `__ASSERT` : (expr)
+    pub fn parse_assertion_args(&mut self) -> Result<(Argument, Expression), ParserError> {
+        self.match_kind(TokenKind::OpenParen)?;
+        let start = self.spans.len();
+        let expression = self.parse_expression(0)?;
+        let spans = self.spans[start..].to_vec();
+        self.match_kind(TokenKind::CloseParen)?;
+        Ok((Argument { span: AstSpan(spans), ..Default::default() }, expression))
+    }
+
+    /// Parses a constant expression, of the operators binding at least as tight as `precedence`
+    pub fn parse_expression(&mut self, precedence: usize) -> Result<Expression, ParserError> {
+        let left = self.parse_operand()?;
+        self.parse_binary_expression(left, precedence)
+    }
+
+    /// Parses the operators binding at least as tight as `precedence` following an operand
+    pub fn parse_binary_expression(
+        &mut self,
+        mut left: Expression,
+        precedence: usize,
+    ) -> Result<Expression, ParserError> {
+        while let Some((operator, p)) = BinaryOperator::from_token(&self.current_token.kind) {
+            if p < precedence {
+                break
+            }
+            self.consume();
+            let right = self.parse_expression(p + 1)?;
+            left = Expression::Binary(operator, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    /// Parses an operand of a constant expression
+    pub fn parse_operand(&mut self) -> Result<Expression, ParserError> {
+        let span = self.current_token.span.clone();
+        let invalid = |kind: TokenKind| ParserError {
+            kind: ParserErrorKind::InvalidArgs(kind),
+            spans: AstSpan(vec![span.clone()]),
+        };
+        match self.current_token.kind.clone() {
+            TokenKind::Literal(l) => {
+                self.consume();
+                Ok(Expression::Literal(l))
+            }
+            TokenKind::Num(n) => {
+                self.consume();
+                Ok(Expression::Literal(str_to_bytes32(&format!("{:x}", n))))
+            }
+            TokenKind::Not => {
+                self.consume();
+                Ok(Expression::Not(Box::new(self.parse_operand()?)))
+            }
+            TokenKind::OpenParen => {
+                self.consume();
+                let expression = self.parse_expression(0)?;
+                self.match_kind(TokenKind::CloseParen)?;
+                Ok(expression)
+            }
+            TokenKind::OpenBracket => {
+                self.consume();
+                let name = match self.match_kind(TokenKind::Ident(String::default())) {
+                    Ok(TokenKind::Ident(name)) => name,
+                    _ => return Err(invalid(self.current_token.kind.clone())),
+                };
+                self.match_kind(TokenKind::CloseBracket)?;
+                Ok(Expression::Constant(name.into()))
+            }
+            // Builtins are lexed as identifiers outside of macro bodies
+            TokenKind::BuiltinFunction(f) | TokenKind::Ident(f) => {
+                let (kind, args) = match f.as_str() {
+                    "__tablesize" | "__codesize" => {
+                        self.consume();
+                        let args = self.parse_args(true, false, false)?;
+                        if args.len() != 1 {
+                            return Err(invalid(TokenKind::BuiltinFunction(f)))
+                        }
+                        (BuiltinFunctionKind::from(f.as_str()), args)
+                    }
+                    "__KECCAK256" => {
+                        self.consume();
+                        let (arg, preimage) = self.parse_keccak256_args()?;
+                        (BuiltinFunctionKind::Keccak256(preimage), vec![arg])
+                    }
+                    "__RIGHTPAD" => {
+                        self.consume();
+                        let (arg, padded) = self.parse_rightpad_args()?;
+                        (BuiltinFunctionKind::RightPad(padded), vec![arg])
+                    }
+                    "__OFFSET" => {
+                        self.consume();
+                        return Ok(Expression::LabelOffset(self.parse_offset_args()?.into()))
+                    }
+                    _ => return Err(invalid(self.current_token.kind.clone())),
+                };
+                let mut spans = vec![span];
+                args.iter().for_each(|a| spans.extend_from_slice(&a.span.0));
+                Ok(Expression::BuiltinFunctionCall(BuiltinFunctionCall {
+                    kind,
+                    args,
+                    span: AstSpan(spans),
+                }))
+            }
+            kind => Err(invalid(kind)),
+        }
+    }
+
+    /// Parses the literal of `__VERBATIM` : (0x5f5f)
+    ///
+    /// Returns the bytes of the literal as written, which must be a whole number of bytes.
+    pub fn parse_verbatim_args(&mut self) -> Result<(Argument, Vec<u8>), ParserError> {
+        self.match_kind(TokenKind::OpenParen)?;
+        let span = self.current_token.span.clone();
+        let literal = match self.match_kind(TokenKind::Literal(Literal::default())) {
+            Ok(TokenKind::Literal(l)) => l,
+            _ => {
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidArgs(self.current_token.kind.clone()),
+                    spans: AstSpan(vec![self.current_token.span.clone()]),
+                })
+            }
+        };
+        let digits = span.end.saturating_sub(span.start);
+        if digits == 0 || !digits.is_multiple_of(2) {
+            let written = format!("0x{}", &hex::encode(literal)[64 - digits.min(64)..]);
+            tracing::error!(target: "parser", "VERBATIM LITERAL OF {} DIGITS: {}", digits, written);
+            return Err(ParserError {
+                kind: ParserErrorKind::InvalidVerbatimBytes(written),
+                spans: AstSpan(vec![span]),
+            })
+        }
+        self.match_kind(TokenKind::CloseParen)?;
+        let bytes = written_bytes(&literal, &span);
+        Ok((Argument { span: AstSpan(vec![span]), ..Default::default() }, bytes))
+    }
+
+    /// Parses the label of `__OFFSET` : (label)
+    pub fn parse_offset_args(&mut self) -> Result<String, ParserError> {
+        self.match_kind(TokenKind::OpenParen)?;
+        let label = match self.match_kind(TokenKind::Ident(String::default())) {
+            Ok(TokenKind::Ident(label)) => label,
+            _ => {
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidArgs(self.current_token.kind.clone()),
+                    spans: AstSpan(vec![self.current_token.span.clone()]),
+                })
+            }
+        };
+        self.match_kind(TokenKind::CloseParen)?;
+        Ok(label)
+    }
+
+    /// Parses the literal of `__RIGHTPAD` : (0x01)
+    ///
+    /// Returns the bytes of the literal as written, padded on the right to 32 bytes.
+    pub fn parse_rightpad_args(&mut self) -> Result<(Argument, Literal), ParserError> {
+        self.match_kind(TokenKind::OpenParen)?;
+        let span = self.current_token.span.clone();
+        let bytes = match self.match_kind(TokenKind::Literal(Literal::default())) {
+            Ok(TokenKind::Literal(l)) => written_bytes(&l, &span),
+            _ => {
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidArgs(self.current_token.kind.clone()),
+                    spans: AstSpan(vec![self.current_token.span.clone()]),
+                })
+            }
+        };
+        if self.check(TokenKind::Comma) {
+            self.consume();
+        }
+        self.match_kind(TokenKind::CloseParen)?;
+        let mut padded = [0u8; 32];
+        padded[..bytes.len()].copy_from_slice(&bytes);
+        Ok((Argument { span: AstSpan(vec![span]), ..Default::default() }, padded))
+    }
+
+    /// Parses the string of `__MSTORE_STRING` or `__REVERT_STRING` : ("reason")
+    ///
+    /// Returns the bytes of the string, with its escapes resolved.
+    pub fn parse_string_args(&mut self) -> Result<(Argument, Vec<u8>), ParserError> {
+        self.match_kind(TokenKind::OpenParen)?;
+        let span = self.current_token.span.clone();
+        let bytes = match self.match_kind(TokenKind::Str(String::default())) {
+            Ok(TokenKind::Str(s)) => unescape(&s),
+            _ => {
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidArgs(self.current_token.kind.clone()),
+                    spans: AstSpan(vec![self.current_token.span.clone()]),
+                })
+            }
+        };
+        if self.check(TokenKind::Comma) {
+            self.consume();
+        }
+        self.match_kind(TokenKind::CloseParen)?;
+        Ok((Argument { span: AstSpan(vec![span]), ..Default::default() }, bytes))
+    }
+
+    /// Parses empty arguments : ()
+    pub fn parse_empty_args(&mut self) -> Result<(), ParserError> {
+        self.match_kind(TokenKind::OpenParen)?;
+        if !self.check(TokenKind::CloseParen) {
+            return Err(ParserError {
+                kind: ParserErrorKind::InvalidArgs(self.current_token.kind.clone()),
+                spans: AstSpan(vec![self.current_token.span.clone()]),
+            })
+        }
+        self.consume();
+        Ok(())
+    }
+
     /// Parses the following : (x)
     pub fn parse_single_arg(&mut self) -> Result<usize, ParserError> {
         self.match_kind(TokenKind::OpenParen)?;
@@ -679,43 +1704,13 @@ impl Parser {
     }
 
     /// Parse the arguments of a macro call.
+    ///
+    /// The last argument may be followed by a trailing comma.
     pub fn parse_macro_call_args(&mut self) -> Result<Vec<MacroArg>, ParserError> {
         let mut args = vec![];
         self.match_kind(TokenKind::OpenParen)?;
         while !self.check(TokenKind::CloseParen) {
-            // We can pass either directly hex values or labels (without the ":")
-            match self.current_token.kind.clone() {
-                TokenKind::Literal(lit) => {
-                    args.push(MacroArg::Literal(lit));
-                    self.consume();
-                }
-                TokenKind::Ident(ident) => {
-                    args.push(MacroArg::Ident(ident));
-                    self.consume();
-                }
-                TokenKind::LeftAngle => {
-                    // Passed into the Macro Call like:
-                    // GET_SLOT_FROM_KEY(<mem_ptr>)  // [slot]
-                    self.consume();
-                    let arg_name =
-                        self.match_kind(TokenKind::Ident("ARG_CALL".to_string()))?.to_string();
-                    args.push(MacroArg::ArgCall(arg_name));
-                    self.match_kind(TokenKind::RightAngle)?;
-                }
-                arg => {
-                    tracing::error!(
-                        target: "parser",
-                        "Invalid macro call arguments. Must be of kind Ident or Literal. Got: {}",
-                        self.current_token.kind
-                    );
-                    let new_spans = self.spans.clone();
-                    self.spans = vec![];
-                    return Err(ParserError {
-                        kind: ParserErrorKind::InvalidMacroArgs(arg),
-                        spans: AstSpan(new_spans),
-                    })
-                }
-            }
+            args.push(self.parse_macro_arg()?);
             if self.check(TokenKind::Comma) {
                 self.consume();
             }
@@ -725,6 +1720,51 @@ impl Parser {
         Ok(args)
     }
 
+    /// Parse a single macro argument, passed by a macro call or as the default of a parameter.
+    pub fn parse_macro_arg(&mut self) -> Result<MacroArg, ParserError> {
+        // We can pass either directly hex values or labels (without the ":")
+        match self.current_token.kind.clone() {
+            TokenKind::Literal(lit) => {
+                self.consume();
+                Ok(MacroArg::Literal(lit))
+            }
+            TokenKind::Ident(ident) => {
+                self.consume();
+                Ok(MacroArg::Ident(ident))
+            }
+            TokenKind::LeftAngle => {
+                // Passed into the Macro Call like:
+                // GET_SLOT_FROM_KEY(<mem_ptr>)  // [slot]
+                self.consume();
+                let arg_name =
+                    self.match_kind(TokenKind::Ident("ARG_CALL".to_string()))?.to_string();
+                self.match_kind(TokenKind::RightAngle)?;
+                Ok(MacroArg::ArgCall(arg_name))
+            }
+            TokenKind::OpenBracket => {
+                // Passed into the Macro Call like:
+                // TAKE_FEE([FEE_BPS])  // [fee]
+                // The constant may be defined by any file of the contract, including the
+                // one importing the macro
+                let (constant, _) = self.parse_constant_push()?;
+                Ok(MacroArg::Constant(constant.into()))
+            }
+            arg => {
+                tracing::error!(
+                    target: "parser",
+                    "Invalid macro call arguments. Must be of kind Ident, Literal, Arg Call or Constant. Got: {}",
+                    self.current_token.kind
+                );
+                let new_spans = self.spans.clone();
+                self.spans = vec![];
+                Err(ParserError {
+                    kind: ParserErrorKind::InvalidMacroArgs(arg),
+                    spans: AstSpan(new_spans),
+                })
+            }
+        }
+    }
+
     /// Parses a table (JumpTable, JumpTablePacked, or CodeTable).
     ///
     /// It should parse the following : (jumptable|jumptable__packed|table) NAME() {...}
@@ -738,8 +1778,13 @@ impl Parser {
         let _ = self.match_kind(TokenKind::CloseParen);
         let _ = self.match_kind(TokenKind::Assign);
 
-        // Parse the core table
-        let table_statements: Vec<Statement> = self.parse_table_body()?;
+        // Parse the core table, code tables can also be read from a file
+        let table_statements: Vec<Statement> = match &self.current_token.kind {
+            TokenKind::Ident(i) if i == "file" && kind == TableKind::CodeTable => {
+                vec![self.parse_table_file()?]
+            }
+            _ => self.parse_table_body()?,
+        };
         let size = match kind {
             TableKind::JumpTablePacked => table_statements.len() * 0x02,
             TableKind::JumpTable => table_statements.len() * 0x20,
@@ -749,6 +1794,8 @@ impl Parser {
                     .map(|s| {
                         if let StatementType::LabelCall(l) = &s.ty {
                             l.len()
+                        } else if let StatementType::Code(c) = &s.ty {
+                            c.len() * 2
                         } else {
                             // TODO: Throw an error here.
                             tracing::error!(
@@ -785,7 +1832,7 @@ impl Parser {
             match &self.current_token.kind {
                 TokenKind::Ident(ident_str) => {
                     statements.push(Statement {
-                        ty: StatementType::LabelCall(ident_str.to_string()),
+                        ty: StatementType::LabelCall(ident_str.into()),
                         span: AstSpan(new_spans),
                     });
                     self.consume();
@@ -804,6 +1851,49 @@ impl Parser {
         Ok(statements)
     }
 
+    /// Parses a code table read from a binary file, as in `file("./blob.bin")`.
+    ///
+    /// The path is relative to the file defining the table. The file contents are placed in the
+    /// table as is, without passing through the lexer.
+    pub fn parse_table_file(&mut self) -> Result<Statement, ParserError> {
+        self.match_kind(TokenKind::Ident("file".to_string()))?;
+        self.match_kind(TokenKind::OpenParen)?;
+        let path_span = self.current_token.span.clone();
+        let path = match self.current_token.kind.clone() {
+            TokenKind::Str(p) => {
+                self.consume();
+                p
+            }
+            kind => {
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidTableBodyToken(kind),
+                    spans: AstSpan(vec![path_span]),
+                })
+            }
+        };
+        self.match_kind(TokenKind::CloseParen)?;
+
+        let localized = match &self.base {
+            Some(b) => FileSource::localize_file(b, &path).unwrap_or_default(),
+            None => path.clone(),
+        };
+        tracing::info!(target: "parser", "READING TABLE FILE: {}", localized);
+
+        match std::fs::read(&localized) {
+            Ok(code) => Ok(Statement {
+                ty: StatementType::Code(code),
+                span: AstSpan(vec![path_span]),
+            }),
+            Err(e) => {
+                tracing::error!(target: "parser", "FAILED TO READ TABLE FILE \"{}\": {}", localized, e);
+                Err(ParserError {
+                    kind: ParserErrorKind::InvalidTableFile(path),
+                    spans: AstSpan(vec![path_span]),
+                })
+            }
+        }
+    }
+
     /// Parses a constant push.
     pub fn parse_constant_push(&mut self) -> Result<(String, Span), ParserError> {
         self.match_kind(TokenKind::OpenBracket)?;
@@ -884,6 +1974,46 @@ impl Parser {
         }
     }
 
+    /// Parses a tuple type into its canonical form, like `(address,uint256)[]`.
+    ///
+    /// The components of a tuple are unnamed types, tuples themselves or not, and the tuple may
+    /// be followed by the sizes of an array of tuples.
+    pub fn parse_tuple_type(&mut self) -> Result<String, ParserError> {
+        self.match_kind(TokenKind::OpenParen)?;
+        let mut components = vec![];
+        while !self.check(TokenKind::CloseParen) {
+            components.push(match self.check(TokenKind::OpenParen) {
+                true => self.parse_tuple_type()?,
+                false => self.parse_arg_type()?.to_string(),
+            });
+            if !self.check(TokenKind::CloseParen) {
+                self.match_kind(TokenKind::Comma)?;
+            }
+        }
+        // a tuple must have at least one component
+        if components.is_empty() {
+            return Err(ParserError {
+                kind: ParserErrorKind::InvalidArgs(self.current_token.kind.clone()),
+                spans: AstSpan(vec![self.current_token.span.clone()]),
+            })
+        }
+        self.match_kind(TokenKind::CloseParen)?;
+
+        let mut tuple = format!("({})", components.join(","));
+        while self.check(TokenKind::OpenBracket) {
+            self.consume();
+            match self.current_token.kind {
+                TokenKind::Num(size) if size > 0 => {
+                    self.consume();
+                    tuple.push_str(&format!("[{}]", size));
+                }
+                _ => tuple.push_str("[]"),
+            }
+            self.match_kind(TokenKind::CloseBracket)?;
+        }
+        Ok(tuple)
+    }
+
     /// Parses a primitive EVM type.
     /// Arrays of primitive types are not considered as primitive types themselves.
     pub fn parse_primitive_type(
@@ -927,3 +2057,33 @@ impl Parser {
         }
     }
 }
+
+/// The bytes of a literal as written, keeping its leading zeros
+///
+/// Literals are padded on the left to 32 bytes when lexed, their span has the digits as written.
+fn written_bytes(literal: &Literal, span: &Span) -> Vec<u8> {
+    let len = span.end.saturating_sub(span.start).div_ceil(2);
+    literal[32 - len.min(32)..].to_vec()
+}
+
+/// The bytes of a string literal, with its escapes resolved
+fn unescape(s: &str) -> Vec<u8> {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped.into_bytes()
+}
+
+/// The first label defined by a list of statements, including in their conditional blocks
+fn find_label(statements: &[Statement]) -> Option<&Label> {
+    statements.iter().find_map(|s| match &s.ty {
+        StatementType::Label(l) => Some(l),
+        StatementType::Conditional(c) => find_label(&c.inner),
+        _ => None,
+    })
+}
@@ -23,3 +23,89 @@ fn build_abi_from_ast() {
     );
     assert_eq!(abi.functions.get("test").unwrap().inputs[1].kind, FunctionParamType::String);
 }
+
+#[test]
+fn build_complete_abi_from_ast() {
+    let source = r#"
+#define function CONSTRUCTOR(address owner) payable returns ()
+#define event Transfer(address indexed from, address indexed to, uint256 amount)
+#define error InsufficientBalance(uint256 available, uint256 required)
+"#;
+
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap())
+        .filter(|x| !matches!(x.kind, TokenKind::Whitespace))
+        .collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+    assert_eq!(contract.errors[0].name, "InsufficientBalance");
+    assert_eq!(contract.errors[0].selector, [0xcf, 0x47, 0x91, 0x81]);
+    let abi = Abi::from(contract);
+
+    // The constructor's signature is declared by the `CONSTRUCTOR` function
+    let constructor = abi.constructor.as_ref().unwrap();
+    assert_eq!(constructor.inputs[0].kind, FunctionParamType::Address);
+    assert!(constructor.payable);
+    assert!(abi.functions.is_empty());
+
+    let transfer = abi.events.get("Transfer").unwrap();
+    assert_eq!(transfer.inputs.iter().map(|i| i.indexed).collect::<Vec<_>>(), [true, true, false]);
+
+    let error = abi.errors.get("InsufficientBalance").unwrap();
+    assert_eq!(error.signature(), "InsufficientBalance(uint256,uint256)");
+    assert_eq!(error.selector(), [0xcf, 0x47, 0x91, 0x81]);
+    let json = abi.json_abi();
+    assert_eq!(json[2]["type"], "error");
+    assert_eq!(json[2]["inputs"][1]["name"], "required");
+}
+
+#[test]
+fn build_tuple_abi_from_ast() {
+    let source = r#"
+#define function multicall((address,bytes)[] calls) payable returns ((bool,bytes)[])
+#define event Filled((address,(uint256,bool))[2][] orders, uint256 indexed id)
+"#;
+
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap())
+        .filter(|x| !matches!(x.kind, TokenKind::Whitespace))
+        .collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+    // The selector hashes the canonical tuple type
+    assert_eq!(contract.functions[0].inputs[0].arg_type.as_deref(), Some("(address,bytes)[]"));
+    assert_eq!(contract.functions[0].inputs[0].name.as_deref(), Some("calls"));
+    assert_eq!(contract.functions[0].signature, [0xca, 0xa5, 0xc2, 0x3f]);
+    let abi = Abi::from(contract);
+
+    let multicall = abi.functions.get("multicall").unwrap();
+    assert_eq!(
+        multicall.inputs[0].kind,
+        FunctionParamType::Array(
+            Box::new(FunctionParamType::Tuple(vec![
+                FunctionParamType::Address,
+                FunctionParamType::Bytes
+            ])),
+            vec![0]
+        )
+    );
+    assert_eq!(multicall.signature(), "multicall((address,bytes)[])");
+
+    let filled = abi.events.get("Filled").unwrap();
+    assert_eq!(filled.inputs[0].kind.to_string(), "(address,(uint256,bool))[2][]");
+    assert!(filled.inputs[1].indexed);
+
+    let json = abi.json_abi();
+    assert_eq!(json[0]["inputs"][0]["type"], "tuple[]");
+    assert_eq!(json[0]["inputs"][0]["components"][0]["type"], "address");
+    assert_eq!(json[0]["outputs"][0]["type"], "tuple[]");
+    assert_eq!(json[1]["inputs"][0]["type"], "tuple[2][]");
+    assert_eq!(json[1]["inputs"][0]["components"][1]["type"], "tuple");
+    assert_eq!(json[1]["inputs"][0]["components"][1]["components"][1]["type"], "bool");
+}
@@ -0,0 +1,119 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+fn parse(source: &str) -> (Parser, Contract) {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse_partial();
+    (parser, contract)
+}
+
+#[test]
+fn test_parses_alias() {
+    let source = "#define alias fmp = 0x40 mload\n#define alias owner = [OWNER] sload";
+    let (parser, contract) = parse(source);
+    assert!(parser.errors.is_empty());
+
+    let span = |start, end| Span { start, end, file: None };
+    assert_eq!(
+        contract.aliases[0],
+        AliasDefinition {
+            name: "fmp".to_string(),
+            statements: vec![
+                Statement {
+                    ty: StatementType::Literal(str_to_bytes32("40")),
+                    span: AstSpan(vec![span(22, 24)]),
+                },
+                Statement {
+                    ty: StatementType::Opcode(Opcode::Mload),
+                    span: AstSpan(vec![span(25, 30)]),
+                },
+            ],
+            span: AstSpan(vec![
+                span(0, 7),
+                span(8, 13),
+                span(14, 17),
+                span(18, 19),
+                span(22, 24),
+                span(25, 30)
+            ]),
+        }
+    );
+    assert_eq!(
+        contract.aliases[1].statements.iter().map(|s| s.ty.clone()).collect::<Vec<_>>(),
+        vec![StatementType::Constant("OWNER".into()), StatementType::Opcode(Opcode::Sload)]
+    );
+    assert_eq!(parser.symbols.get("owner").unwrap().kind, SymbolKind::Alias);
+}
+
+#[test]
+fn test_expands_aliases() {
+    let source = r#"
+    #define macro MAIN() = takes(0) returns(0) {
+        fmp pop
+        done jump
+        done:
+            0x20 fmp return
+    }
+
+    #define alias fmp = 0x40 mload
+    "#;
+    let (parser, contract) = parse(source);
+    assert!(parser.errors.is_empty());
+
+    // Aliases are expanded where they're used, even before their definition
+    let main = &contract.macros[0];
+    let types = main.statements.iter().map(|s| s.ty.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        types[..5],
+        [
+            StatementType::Literal(str_to_bytes32("40")),
+            StatementType::Opcode(Opcode::Mload),
+            StatementType::Opcode(Opcode::Pop),
+            StatementType::LabelCall("done".into()),
+            StatementType::Opcode(Opcode::Jump),
+        ]
+    );
+    let label = match &main.statements[5].ty {
+        StatementType::Label(l) => l,
+        ty => panic!("Expected a label, got {:?}", ty),
+    };
+    assert_eq!(
+        label.inner.iter().map(|s| s.ty.clone()).collect::<Vec<_>>(),
+        vec![
+            StatementType::Literal(str_to_bytes32("20")),
+            StatementType::Literal(str_to_bytes32("40")),
+            StatementType::Opcode(Opcode::Mload),
+            StatementType::Opcode(Opcode::Return),
+        ]
+    );
+
+    // Expanded statements are spanned by the use, then by the aliased statement
+    let alias = &contract.aliases[0];
+    let expanded = &main.statements[1].span.0;
+    assert_eq!(&source[expanded[0].start..expanded[0].end], "fmp");
+    assert_eq!(expanded[1], alias.statements[1].span.0[0]);
+    assert!(alias.is_expansion(&main.statements[1]));
+    assert!(!alias.is_expansion(&main.statements[2]));
+}
+
+#[test]
+fn test_invalid_alias_body() {
+    let source = "#define alias load = <offset> calldataload\n#define alias empty =\n#define alias ok = 0x01";
+    let (parser, contract) = parse(source);
+    assert_eq!(
+        parser.errors.iter().map(|e| e.kind.clone()).collect::<Vec<_>>(),
+        vec![
+            ParserErrorKind::InvalidTokenInAliasBody(TokenKind::LeftAngle),
+            ParserErrorKind::InvalidTokenInAliasBody(TokenKind::Define),
+        ]
+    );
+    assert_eq!(parser.errors[0].kind.code(), "H0221");
+
+    // Parsing recovers at the next definition
+    assert_eq!(contract.aliases.len(), 1);
+    assert_eq!(contract.aliases[0].name, "ok");
+}
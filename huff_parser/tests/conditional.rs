@@ -0,0 +1,79 @@
+use std::str::FromStr;
+
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+const SOURCE: &str = r#"
+    #define constant DEBUG = 0x00
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __IFDEF(DEBUG) { 0x01 }
+        __IFNDEF(DEBUG) { 0x02 }
+        __IFDEF(TESTNET) {
+            0x03
+            __IFNDEF(DEBUG) { 0x04 }
+        }
+        __REPEAT(2, I) {
+            __IFDEF(DEBUG) { [I] }
+        }
+    }
+"#;
+
+fn parse(source: &str, defines: &[&str]) -> (Parser, Contract) {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    parser.defines = defines.iter().map(|d| ConstantDefinition::from_str(d).unwrap()).collect();
+    let contract = parser.parse_partial();
+    (parser, contract)
+}
+
+fn literals(contract: &Contract) -> Vec<String> {
+    contract.macros[0]
+        .statements
+        .iter()
+        .map(|s| match &s.ty {
+            StatementType::Literal(l) => bytes32_to_string(l, true),
+            ty => panic!("Expected a literal, got {}", ty),
+        })
+        .collect()
+}
+
+#[test]
+fn test_expands_conditionals() {
+    // Blocks test whether constants are defined, whatever their value
+    let (parser, contract) = parse(SOURCE, &[]);
+    assert!(parser.errors.is_empty());
+    assert_eq!(literals(&contract), vec!["0x01", "0x00", "0x01"]);
+    assert_eq!(
+        contract.flags.iter().cloned().collect::<Vec<_>>(),
+        vec!["DEBUG".to_string(), "TESTNET".to_string()]
+    );
+
+    // Defines add constants or replace them
+    let (parser, contract) = parse(SOURCE, &["TESTNET"]);
+    assert!(parser.errors.is_empty());
+    assert_eq!(literals(&contract), vec!["0x01", "0x03", "0x00", "0x01"]);
+
+    let (_, contract) = parse(SOURCE, &["DEBUG=0x20"]);
+    assert_eq!(contract.constants.len(), 1);
+    assert_eq!(contract.constants[0].value, ConstVal::Literal(str_to_bytes32("20")));
+}
+
+#[test]
+fn test_invalid_conditionals() {
+    // Conditionals test a constant by name
+    let (parser, _) =
+        parse("#define macro MAIN() = takes(0) returns(0) {\n    __IFDEF([DEBUG]) { }\n}", &[]);
+    assert_eq!(parser.errors[0].kind, ParserErrorKind::InvalidArgs(TokenKind::OpenBracket));
+
+    // Labels in conditionals can't be repeated
+    let source = "#define macro MAIN() = takes(0) returns(0) {\n    __REPEAT(2) { __IFDEF(DEBUG) { done: stop } }\n}";
+    let (parser, _) = parse(source, &[]);
+    assert_eq!(
+        parser.errors[0].kind,
+        ParserErrorKind::InvalidTokenInMacroBody(TokenKind::Label("done".to_string()))
+    );
+}
@@ -16,15 +16,16 @@ fn test_parses_free_storage_pointer_constant() {
     assert_eq!(
         fsp_constant,
         ConstantDefinition {
-            name: "FSP_LOCATION".to_string(),
-            value: ConstVal::FreeStoragePointer(FreeStoragePointer {}),
+            name: "FSP_LOCATION".into(),
+            value: ConstVal::FreeStoragePointer(FreeStoragePointer { size: 1 }),
             span: AstSpan(vec![
                 Span { start: 0, end: 7, file: None },
                 Span { start: 8, end: 16, file: None },
                 Span { start: 17, end: 29, file: None },
                 Span { start: 30, end: 31, file: None },
                 Span { start: 32, end: 54, file: None }
-            ])
+            ]),
+            internal: false,
         }
     );
 }
@@ -48,7 +49,7 @@ fn test_parses_literal_constant() {
     assert_eq!(
         fsp_constant,
         ConstantDefinition {
-            name: "LITERAL".to_string(),
+            name: "LITERAL".into(),
             value: ConstVal::Literal(arr),
             span: AstSpan(vec![
                 Span { start: 0, end: 7, file: None },
@@ -56,7 +57,68 @@ fn test_parses_literal_constant() {
                 Span { start: 17, end: 24, file: None },
                 Span { start: 25, end: 26, file: None },
                 Span { start: 29, end: 93, file: None }
-            ])
+            ]),
+            internal: false,
         }
     );
 }
+
+#[test]
+fn test_parses_immutable() {
+    let source = "#define immutable OWNER\n#define macro MAIN() = takes(0) returns(0) {\n    caller __SET_IMMUTABLE(OWNER) [OWNER]\n}";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+    assert_eq!(parser.current_token.kind, TokenKind::Eof);
+
+    assert_eq!(
+        contract.immutables,
+        Arena::from(vec![ImmutableDefinition {
+            name: "OWNER".to_string(),
+            span: AstSpan(vec![
+                Span { start: 0, end: 7, file: None },
+                Span { start: 8, end: 17, file: None },
+                Span { start: 18, end: 23, file: None },
+            ])
+        }])
+    );
+    assert!(contract.constants.is_empty());
+    assert_eq!(parser.symbols.get("OWNER").unwrap().kind, SymbolKind::Constant);
+
+    // Immutables are set with a builtin, and pushed like constants
+    let statements = &contract.macros[0].statements;
+    match &statements[1].ty {
+        StatementType::BuiltinFunctionCall(call) => {
+            assert_eq!(call.kind, BuiltinFunctionKind::SetImmutable);
+            assert_eq!(call.args[0].name.as_deref(), Some("OWNER"));
+        }
+        ty => panic!("Expected a builtin function call, got {}", ty),
+    }
+    assert_eq!(statements[2].ty, StatementType::Constant("OWNER".into()));
+}
+
+#[test]
+fn test_parses_internal_definitions() {
+    let source = "#define internal constant FEE = 0x01\n#define internal macro HELPER() = takes(0) returns(0) {}\n#define macro MAIN() = takes(0) returns(0) {}";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    assert!(contract.constants[0].internal);
+    assert!(contract.macros[0].internal);
+    assert!(!contract.macros[1].internal);
+    // The definitions span their modifier
+    assert_eq!(contract.constants[0].span.0[1], Span { start: 8, end: 16, file: None });
+
+    // Only macros and constants can be internal
+    let source = "#define internal function transfer(address,uint256) nonpayable returns ()";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    assert_eq!(parser.parse().unwrap_err().kind, ParserErrorKind::InvalidDefinition);
+}
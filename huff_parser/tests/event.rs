@@ -14,6 +14,7 @@ fn test_parse_event() {
                         arg_type: Some(String::from("uint256")),
                         name: Some(String::from("a")),
                         indexed: true,
+                        default: None,
                         span: AstSpan(vec![
                             // "uint256"
                             Span { start: 24, end: 31, file: None },
@@ -27,6 +28,7 @@ fn test_parse_event() {
                         arg_type: Some(String::from("uint8")),
                         name: None,
                         indexed: true,
+                        default: None,
                         span: AstSpan(vec![
                             // "uint8"
                             Span { start: 42, end: 47, file: None },
@@ -70,6 +72,7 @@ fn test_parse_event() {
                         arg_type: Some(String::from("uint256")),
                         name: None,
                         indexed: false,
+                        default: None,
                         span: AstSpan(vec![
                             // "uint256"
                             Span { start: 24, end: 31, file: None },
@@ -79,6 +82,7 @@ fn test_parse_event() {
                         arg_type: Some(String::from("uint8")),
                         name: Some(String::from("b")),
                         indexed: false,
+                        default: None,
                         span: AstSpan(vec![
                             // "uint8"
                             Span { start: 32, end: 37, file: None },
@@ -118,6 +122,7 @@ fn test_parse_event() {
                         arg_type: Some(String::from("uint256")),
                         name: None,
                         indexed: true,
+                        default: None,
                         span: AstSpan(vec![
                             // "uint256"
                             Span { start: 24, end: 31, file: None },
@@ -129,6 +134,7 @@ fn test_parse_event() {
                         arg_type: Some(String::from("uint8")),
                         name: None,
                         indexed: false,
+                        default: None,
                         span: AstSpan(vec![
                             // "uint8"
                             Span { start: 40, end: 45, file: None },
@@ -174,3 +180,24 @@ fn test_parse_event() {
         assert_eq!(event, expected);
     }
 }
+
+#[test]
+fn test_parse_multiline_event_with_trailing_comma() {
+    let source = r#"#define event Transfer(
+    address indexed from,
+    address indexed to, // the recipient
+    uint256 value,
+)"#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let event = parser.parse().unwrap().events[0].clone();
+
+    assert_eq!(
+        event.parameters.iter().map(|p| (p.name.clone().unwrap(), p.indexed)).collect::<Vec<_>>(),
+        [("from".to_string(), true), ("to".to_string(), true), ("value".to_string(), false)]
+    );
+    let value = &event.parameters[2].span.0;
+    assert_eq!(&source[value[0].start..value[1].end], "uint256 value");
+}
@@ -18,18 +18,20 @@ fn parses_valid_function_definition() {
         (
             0,
             Function {
-                name: "test".to_string(),
+                name: "test".into(),
                 inputs: vec![
                     Argument {
                         name: None,
                         arg_type: Some(String::from("uint256")),
                         indexed: false,
+                        default: None,
                         span: AstSpan(vec![Span { start: 22, end: 29, file: None }]),
                     },
                     Argument {
                         name: Some(String::from("b")),
                         arg_type: Some(String::from("bool")),
                         indexed: false,
+                        default: None,
                         span: AstSpan(vec![
                             Span { start: 30, end: 34, file: None },
                             Span { start: 35, end: 36, file: None },
@@ -41,6 +43,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    default: None,
                     span: AstSpan(vec![Span { start: 51, end: 58, file: None }]),
                 }],
                 signature: [84, 204, 215, 119],
@@ -65,11 +68,12 @@ fn parses_valid_function_definition() {
         (
             1,
             Function {
-                name: "test".to_string(),
+                name: "test".into(),
                 inputs: vec![Argument {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    default: None,
                     span: AstSpan(vec![Span { start: 22, end: 29, file: None }]),
                 }],
                 fn_type: FunctionType::Pure,
@@ -77,6 +81,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    default: None,
                     span: AstSpan(vec![Span { start: 44, end: 51, file: None }]),
                 }],
                 signature: [41, 233, 159, 7],
@@ -98,11 +103,12 @@ fn parses_valid_function_definition() {
         (
             2,
             Function {
-                name: "test".to_string(),
+                name: "test".into(),
                 inputs: vec![Argument {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    default: None,
                     span: AstSpan(vec![Span { start: 22, end: 29, file: None }]),
                 }],
                 fn_type: FunctionType::NonPayable,
@@ -110,6 +116,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    default: None,
                     span: AstSpan(vec![Span { start: 50, end: 57, file: None }]),
                 }],
                 signature: [41, 233, 159, 7],
@@ -131,11 +138,12 @@ fn parses_valid_function_definition() {
         (
             3,
             Function {
-                name: "test".to_string(),
+                name: "test".into(),
                 inputs: vec![Argument {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    default: None,
                     span: AstSpan(vec![Span { start: 22, end: 29, file: None }]),
                 }],
                 fn_type: FunctionType::Payable,
@@ -143,6 +151,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    default: None,
                     span: AstSpan(vec![Span { start: 47, end: 54, file: None }]),
                 }],
                 signature: [41, 233, 159, 7],
@@ -164,11 +173,12 @@ fn parses_valid_function_definition() {
         (
             4,
             Function {
-                name: "test".to_string(),
+                name: "test".into(),
                 inputs: vec![Argument {
                     name: None,
                     arg_type: Some(String::from("uint256[], bool[5]")),
                     indexed: false,
+                    default: None,
                     span: AstSpan(vec![]),
                 }],
                 fn_type: FunctionType::Payable,
@@ -176,6 +186,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    default: None,
                     span: AstSpan(vec![]),
                 }],
                 signature: [5, 191, 166, 243],
@@ -211,3 +222,43 @@ fn cannot_parse_invalid_function_definition() {
     let mut parser = Parser::new(tokens, None);
     parser.parse().unwrap();
 }
+
+#[test]
+fn parses_multiline_function_with_trailing_commas() {
+    let source = r#"#define function transfer(
+    address to, // the recipient
+    /* amount */ uint256 amount,
+) nonpayable returns (bool,)"#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let function = parser.parse().unwrap().functions[0].clone();
+
+    // Same signature as `transfer(address,uint256)`
+    assert_eq!(function.signature, [169, 5, 156, 187]);
+    assert_eq!(function.inputs.len(), 2);
+    assert_eq!(function.outputs.len(), 1);
+    let text = |span: &Span| &source[span.start..span.end];
+    assert_eq!(
+        function.inputs[1].span.0.iter().map(text).collect::<Vec<_>>(),
+        ["uint256", "amount"]
+    );
+    assert_eq!(text(function.span.0.last().unwrap()), ")");
+}
+
+#[test]
+fn cannot_parse_empty_function_argument() {
+    for source in [
+        "#define function test(,) view returns()",
+        "#define function test(uint256,,) view returns()",
+    ] {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut parser = Parser::new(tokens, None);
+        let error = parser.parse().unwrap_err();
+        assert_eq!(error.kind, ParserErrorKind::InvalidArgs(TokenKind::Comma));
+        assert_eq!(&source[error.spans.0[0].start..error.spans.0[0].end], ",");
+    }
+}
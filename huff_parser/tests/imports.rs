@@ -31,3 +31,97 @@ fn fails_to_parse_invalid_import() {
     let import_path = contract.imports[0].clone();
     assert_eq!(import_path.to_str().unwrap(), "../huff-examples/erc20/contracts/ERC1155.huff");
 }
+
+#[test]
+fn parses_artifact_import() {
+    let dir = std::env::temp_dir().join(format!("huff_artifact_import_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut abi = Abi::new();
+    abi.functions.insert(
+        "balanceOf".to_string(),
+        huff_utils::abi::Function {
+            name: "balanceOf".into(),
+            inputs: vec![FunctionParam {
+                name: String::new(),
+                kind: FunctionParamType::Address,
+                internal_type: None,
+            }],
+            outputs: vec![],
+            constant: false,
+            state_mutability: FunctionType::View,
+        },
+    );
+    let artifact = Artifact {
+        bytecode: "600a".to_string(),
+        abi: Some(abi),
+        storage_layout: [("OWNER".to_string(), "0x01".to_string())].into_iter().collect(),
+        panics: Default::default(),
+        devdoc: Default::default(),
+        userdoc: Default::default(),
+        immutable_references: Default::default(),
+        link_references: Default::default(),
+        runtime_link_references: Default::default(),
+        verbatim_references: Default::default(),
+        ..Default::default()
+    };
+    artifact.export(dir.join("out/ERC20Token.huff.json").to_str().unwrap()).unwrap();
+    let base = dir.join("Main.huff").to_string_lossy().to_string();
+    let parse = |source: &str| {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        Parser::new(tokens, Some(base.clone())).parse()
+    };
+
+    // Definitions are prefixed with the artifact name, and spanned by the include
+    let source = "#include artifact \"./out/ERC20Token.huff.json\"";
+    let contract = parse(source).unwrap();
+    assert_eq!(contract.artifacts.len(), 1);
+    assert_eq!(contract.artifacts[0].name, "ERC20_TOKEN");
+    assert_eq!(contract.artifacts[0].span.0.len(), 3);
+    let constants = contract
+        .constants
+        .iter()
+        .map(|c| (c.name.as_str(), c.value.clone()))
+        .collect::<Vec<(&str, ConstVal)>>();
+    assert_eq!(
+        constants,
+        vec![
+            ("ERC20_TOKEN_BALANCE_OF_SELECTOR", ConstVal::Literal(str_to_bytes32("70a08231"))),
+            ("ERC20_TOKEN_OWNER", ConstVal::Literal(str_to_bytes32("01"))),
+        ]
+    );
+    assert_eq!(
+        contract.tables.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+        vec!["ERC20_TOKEN_BYTECODE"]
+    );
+    assert_eq!(contract.tables[0].statements[0].ty, StatementType::Code(vec![0x60, 0x0a]));
+
+    // A missing artifact is reported at its path
+    let source = "#include artifact \"./out/Missing.json\"";
+    let err = parse(source).unwrap_err();
+    assert_eq!(err.kind, ParserErrorKind::InvalidArtifactImport("./out/Missing.json".to_string()));
+    assert_eq!(err.spans.0[0].start, source.find("\"./out/Missing.json\"").unwrap());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn parses_bundled_import() {
+    let parse = |source: &str| {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        Parser::new(tokens, Some("./contracts/Main.huff".to_string())).parse()
+    };
+
+    // Bundled modules are not localized against the base
+    let contract = parse("#include <std/SafeMath.huff>").unwrap();
+    assert_eq!(contract.imports[0].to_str().unwrap(), "<std/SafeMath.huff>");
+
+    // Modules missing from the bundle are reported at their path
+    let source = "#include <std/Missing.huff>";
+    let err = parse(source).unwrap_err();
+    assert_eq!(err.kind, ParserErrorKind::InvalidImportPath("<std/Missing.huff>".to_string()));
+    assert_eq!(err.spans.0.last().unwrap().start, source.find('<').unwrap());
+}
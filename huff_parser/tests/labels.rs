@@ -21,7 +21,7 @@ fn multiline_labels() {
     // Grab the first macro
     let macro_definition = parser.parse().unwrap().macros[0].clone();
     let md_expected = MacroDefinition {
-        name: "HELLO_WORLD".to_string(),
+        name: "HELLO_WORLD".into(),
         parameters: vec![],
         statements: vec![
             Statement {
@@ -46,11 +46,11 @@ fn multiline_labels() {
             },
             Statement {
                 ty: StatementType::Label(Label {
-                    name: "cool_label".to_string(),
+                    name: "cool_label".into(),
                     inner: vec![
                         Statement {
                             ty: StatementType::MacroInvocation(MacroInvocation {
-                                macro_name: "HELLO".to_string(),
+                                macro_name: "HELLO".into(),
                                 args: vec![],
                                 span: AstSpan(vec![
                                     Span { start: 121, end: 126, file: None },
@@ -160,6 +160,7 @@ fn multiline_labels() {
             // "}"
             Span { start: 158, end: 159, file: None },
         ]),
+        internal: false,
     };
     assert_eq!(macro_definition.name, md_expected.name);
     assert_eq!(macro_definition.parameters, md_expected.parameters);
@@ -174,3 +175,35 @@ fn multiline_labels() {
         assert_eq!(s.span, md_expected.statements[i].span);
     }
 }
+
+#[test]
+fn label_offset_expressions() {
+    let source = r#"
+    #define macro COPY(size) = takes(0) returns(0) {
+        __OFFSET(end) - __OFFSET(start) <size> 0x00 codecopy
+        start:
+            0x00
+        end:
+    }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    // The arg call following the expression isn't read as a comparison
+    let statements = &contract.macros[0].statements;
+    let expression = Expression::Binary(
+        BinaryOperator::Sub,
+        Box::new(Expression::LabelOffset("end".into())),
+        Box::new(Expression::LabelOffset("start".into())),
+    );
+    match &statements[0].ty {
+        StatementType::BuiltinFunctionCall(bf) => {
+            assert_eq!(bf.kind, BuiltinFunctionKind::Offset(Box::new(expression)))
+        }
+        ty => panic!("Expected an offset expression, got {:?}", ty),
+    }
+    assert_eq!(statements[1].ty, StatementType::ArgCall("size".to_string()));
+}
@@ -13,7 +13,7 @@ fn empty_macro() {
     // Grab the first macro
     let macro_definition = parser.parse().unwrap().macros[0].clone();
     let expected = MacroDefinition {
-        name: "HELLO_WORLD".to_string(),
+        name: "HELLO_WORLD".into(),
         parameters: vec![],
         statements: vec![],
         takes: 0,
@@ -36,6 +36,7 @@ fn empty_macro() {
             Span { start: 50, end: 51, file: None },
             Span { start: 51, end: 52, file: None },
         ]),
+        internal: false,
     };
     assert_eq!(macro_definition, expected);
     assert_eq!(parser.current_token.kind, TokenKind::Eof);
@@ -53,7 +54,7 @@ fn macro_with_simple_body() {
     // Grab the first macro
     let macro_definition = parser.parse().unwrap().macros[0].clone();
     let expected = MacroDefinition {
-        name: "HELLO_WORLD".to_string(),
+        name: "HELLO_WORLD".into(),
         parameters: vec![],
         statements: vec![
             Statement {
@@ -102,6 +103,7 @@ fn macro_with_simple_body() {
             Span { start: 75, end: 78, file: None },
             Span { start: 79, end: 80, file: None },
         ]),
+        internal: false,
     };
     assert_eq!(macro_definition, expected);
     assert_eq!(parser.current_token.kind, TokenKind::Eof);
@@ -140,11 +142,12 @@ fn macro_with_arg_calls() {
     // Grab the first macro
     let macro_definition = parser.parse().unwrap().macros[0].clone();
     let expected = MacroDefinition {
-        name: "TRANSFER_TAKE_FROM".to_string(),
+        name: "TRANSFER_TAKE_FROM".into(),
         parameters: vec![Argument {
             arg_type: None,
             name: Some("error".to_string()),
             indexed: false,
+            default: None,
             span: AstSpan(vec![Span { start: 67, end: 72, file: None }]),
         }],
         statements: vec![
@@ -153,12 +156,12 @@ fn macro_with_arg_calls() {
                 span: AstSpan(vec![Span { start: 209, end: 213, file: None }]),
             },
             Statement {
-                ty: StatementType::Constant("BALANCE_LOCATION".to_string()),
+                ty: StatementType::Constant("BALANCE_LOCATION".into()),
                 span: AstSpan(vec![Span { start: 265, end: 281, file: None }]),
             },
             Statement {
                 ty: StatementType::MacroInvocation(MacroInvocation {
-                    macro_name: "LOAD_ELEMENT_FROM_KEYS".to_string(),
+                    macro_name: "LOAD_ELEMENT_FROM_KEYS".into(),
                     args: vec![MacroArg::Literal(str_to_bytes32("00"))],
                     span: AstSpan(vec![
                         Span { start: 283, end: 305, file: None },
@@ -211,12 +214,12 @@ fn macro_with_arg_calls() {
                 span: AstSpan(vec![Span { start: 911, end: 915, file: None }]),
             },
             Statement {
-                ty: StatementType::Constant("BALANCE_LOCATION".to_string()),
+                ty: StatementType::Constant("BALANCE_LOCATION".into()),
                 span: AstSpan(vec![Span { start: 982, end: 998, file: None }]),
             },
             Statement {
                 ty: StatementType::MacroInvocation(MacroInvocation {
-                    macro_name: "STORE_ELEMENT_FROM_KEYS".to_string(),
+                    macro_name: "STORE_ELEMENT_FROM_KEYS".into(),
                     args: vec![MacroArg::Literal(str_to_bytes32("00"))],
                     span: AstSpan(vec![
                         Span { start: 1000, end: 1023, file: None },
@@ -280,6 +283,7 @@ fn macro_with_arg_calls() {
             Span { start: 1028, end: 1029, file: None },
             Span { start: 1055, end: 1056, file: None },
         ]),
+        internal: false,
     };
     assert_eq!(macro_definition, expected);
     assert_eq!(parser.current_token.kind, TokenKind::Eof);
@@ -307,16 +311,16 @@ fn macro_labels() {
     // Grab the first macro
     let macro_definition = parser.parse().unwrap().macros[0].clone();
     let expected = MacroDefinition {
-        name: "LABEL_FILLED".to_string(),
+        name: "LABEL_FILLED".into(),
         parameters: vec![],
         statements: vec![
             Statement {
                 ty: StatementType::Label(Label {
-                    name: "__label__".to_string(),
+                    name: "__label__".into(),
                     inner: vec![
                         Statement {
                             ty: StatementType::MacroInvocation(MacroInvocation {
-                                macro_name: "TRANSFER_GIVE_TO".to_string(),
+                                macro_name: "TRANSFER_GIVE_TO".into(),
                                 args: vec![],
                                 span: AstSpan(vec![
                                     Span { start: 89, end: 105, file: None },
@@ -365,11 +369,11 @@ fn macro_labels() {
             },
             Statement {
                 ty: StatementType::Label(Label {
-                    name: "error".to_string(),
+                    name: "error".into(),
                     inner: vec![
                         Statement {
                             ty: StatementType::MacroInvocation(MacroInvocation {
-                                macro_name: "TRANSFER_GIVE_TO".to_string(),
+                                macro_name: "TRANSFER_GIVE_TO".into(),
                                 args: vec![],
                                 span: AstSpan(vec![
                                     Span { start: 164, end: 180, file: None },
@@ -453,6 +457,7 @@ fn macro_labels() {
             Span { start: 205, end: 211, file: None },
             Span { start: 216, end: 217, file: None },
         ]),
+        internal: false,
     };
     assert_eq!(macro_definition, expected);
     assert_eq!(parser.current_token.kind, TokenKind::Eof);
@@ -479,17 +484,18 @@ fn macro_invocation_with_arg_call() {
     // Grab the first macro
     let macro_definition = parser.parse().unwrap().macros[0].clone();
     let expected = MacroDefinition {
-        name: "ARG_CALL".to_string(),
+        name: "ARG_CALL".into(),
         parameters: vec![Argument {
             arg_type: None,
             name: Some("error".to_string()),
             indexed: false,
+            default: None,
             span: AstSpan(vec![Span { start: 28, end: 33, file: None }]),
         }],
         statements: vec![
             Statement {
                 ty: StatementType::MacroInvocation(MacroInvocation {
-                    macro_name: "TRANSFER_TAKE_FROM".to_string(),
+                    macro_name: "TRANSFER_TAKE_FROM".into(),
                     args: vec![MacroArg::ArgCall("error".to_string())],
                     span: AstSpan(vec![
                         Span { start: 67, end: 85, file: None },
@@ -511,7 +517,7 @@ fn macro_invocation_with_arg_call() {
             },
             Statement {
                 ty: StatementType::MacroInvocation(MacroInvocation {
-                    macro_name: "TRANSFER_GIVE_TO".to_string(),
+                    macro_name: "TRANSFER_GIVE_TO".into(),
                     args: vec![MacroArg::ArgCall("error".to_string())],
                     span: AstSpan(vec![
                         Span { start: 103, end: 119, file: None },
@@ -595,11 +601,42 @@ fn macro_invocation_with_arg_call() {
             Span { start: 173, end: 179, file: None },
             Span { start: 184, end: 185, file: None },
         ]),
+        internal: false,
     };
     assert_eq!(macro_definition, expected);
     assert_eq!(parser.current_token.kind, TokenKind::Eof);
 }
 
+#[test]
+fn macro_invocation_with_constant_arg() {
+    let source = r#"
+    #define macro MAIN() = takes(0) returns(0) {
+        TAKE_FEE([FEE_BPS], 0x01, <recipient>)
+    }
+    "#;
+
+    // Parse tokens
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    // The constant is kept by name, to be resolved when the invocation is expanded
+    let macro_definition = parser.parse().unwrap().macros[0].clone();
+    match &macro_definition.statements[0].ty {
+        StatementType::MacroInvocation(mi) => assert_eq!(
+            mi.args,
+            vec![
+                MacroArg::Constant("FEE_BPS".into()),
+                MacroArg::Literal(str_to_bytes32("01")),
+                MacroArg::ArgCall("recipient".to_string()),
+            ]
+        ),
+        ty => panic!("Expected a macro invocation, got {:?}", ty),
+    }
+    assert_eq!(parser.current_token.kind, TokenKind::Eof);
+}
+
 #[test]
 fn macro_with_builtin_fn_call() {
     // Not valid source, just for testing
@@ -618,7 +655,7 @@ fn macro_with_builtin_fn_call() {
     // Grab the first macro
     let macro_definition = parser.parse().unwrap().macros[0].clone();
     let expected = MacroDefinition {
-        name: "BUILTIN_TEST".to_string(),
+        name: "BUILTIN_TEST".into(),
         parameters: vec![],
         statements: vec![Statement {
             ty: StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
@@ -627,6 +664,7 @@ fn macro_with_builtin_fn_call() {
                     arg_type: None,
                     name: Some("TEST".to_string()),
                     indexed: false,
+                    default: None,
                     span: AstSpan(vec![Span { start: 77, end: 81, file: None }]),
                 }],
                 span: AstSpan(vec![
@@ -663,7 +701,213 @@ fn macro_with_builtin_fn_call() {
             Span { start: 81, end: 82, file: None },
             Span { start: 87, end: 88, file: None },
         ]),
+        internal: false,
     };
     assert_eq!(macro_definition, expected);
     assert_eq!(parser.current_token.kind, TokenKind::Eof);
 }
+
+#[test]
+fn parses_fallback_and_receive() {
+    let source = r#"#define fallback() = takes(0) returns(0) {
+    0x00 0x00 revert
+}
+#define receive() = takes(0) returns(0) {
+    stop
+}
+#define fallback() = takes(0) returns(0) {
+    stop
+}
+"#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    // The entrypoints aren't macros until they're wired into MAIN
+    assert!(contract.macros.is_empty());
+
+    // The first definition of each entrypoint is kept
+    let fallback = contract.fallback.unwrap();
+    assert_eq!(fallback.name, "fallback");
+    assert!(fallback.parameters.is_empty());
+    assert_eq!(fallback.statements.len(), 3);
+    let receive = contract.receive.unwrap();
+    assert_eq!(receive.name, "receive");
+    assert_eq!(receive.statements.len(), 1);
+}
+
+#[test]
+fn macro_with_trailing_commas() {
+    let source = r#"
+    #define macro TRANSFER(
+        to, // the recipient
+        amount,
+    ) = takes(0) returns(0) {
+        <to> <amount>
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        TRANSFER(
+            0x01, // to
+            [AMOUNT],
+        )
+        __codesize(TRANSFER,)
+        __ASSERT_CODEHASH(TRANSFER, 0x01,)
+    }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    let transfer = &contract.macros[0];
+    assert_eq!(
+        transfer.parameters.iter().map(|p| p.name.clone().unwrap()).collect::<Vec<_>>(),
+        ["to", "amount"]
+    );
+    let statements = &contract.macros[1].statements;
+    match &statements[0].ty {
+        StatementType::MacroInvocation(mi) => assert_eq!(
+            mi.args,
+            vec![MacroArg::Literal(str_to_bytes32("01")), MacroArg::Constant("AMOUNT".into())]
+        ),
+        ty => panic!("Expected a macro invocation, got {:?}", ty),
+    }
+    match &statements[1].ty {
+        StatementType::BuiltinFunctionCall(call) => assert_eq!(call.args.len(), 1),
+        ty => panic!("Expected a builtin function call, got {:?}", ty),
+    }
+    match &statements[2].ty {
+        StatementType::BuiltinFunctionCall(call) => {
+            assert_eq!(call.kind, BuiltinFunctionKind::AssertCodehash(str_to_bytes32("01")))
+        }
+        ty => panic!("Expected a builtin function call, got {:?}", ty),
+    }
+}
+
+#[test]
+fn macro_with_empty_parameter() {
+    let source = "#define macro EMPTY(,) = takes(0) returns(0) {}";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let error = parser.parse().unwrap_err();
+    assert_eq!(error.kind, ParserErrorKind::InvalidArgs(TokenKind::Comma));
+    assert_eq!(error.spans.0, vec![Span { start: 20, end: 21, file: None }]);
+}
+
+#[test]
+fn macro_with_cheatcode_builtins() {
+    let source = r#"
+    #define macro TEST() = takes(0) returns(0) {
+        0xbeef __PRANK()
+        done:
+            __EXPECT_REVERT()
+    }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    // Cheatcodes take their arguments from the stack, in macro and label bodies
+    let statements = &contract.macros[0].statements;
+    match &statements[1].ty {
+        StatementType::BuiltinFunctionCall(call) => {
+            assert_eq!(call.kind, BuiltinFunctionKind::Cheatcode(Cheatcode::Prank));
+            assert!(call.args.is_empty());
+        }
+        ty => panic!("Expected a builtin function call, got {:?}", ty),
+    }
+    match &statements[2].ty {
+        StatementType::Label(label) => assert_eq!(
+            label.inner[0].ty,
+            StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                kind: BuiltinFunctionKind::Cheatcode(Cheatcode::ExpectRevert),
+                args: vec![],
+                span: label.inner[0].span.clone(),
+            })
+        ),
+        ty => panic!("Expected a label, got {:?}", ty),
+    }
+
+    // Arguments between the parentheses are rejected
+    let source = "#define macro TEST() = takes(0) returns(0) { __WARP(0x01) }";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let error = parser.parse().unwrap_err();
+    assert_eq!(error.kind, ParserErrorKind::InvalidArgs(TokenKind::Literal(str_to_bytes32("01"))));
+}
+
+#[test]
+fn macro_with_parameter_defaults() {
+    let source = r#"
+    #define macro REQUIRE(value, err = fail, code = [ERR_CODE]) = takes(0) returns(0) {
+        <value> <err> jumpi
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        REQUIRE(0x01)
+        REQUIRE(0x01, done)
+        done:
+        fail:
+    }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    let defaults =
+        contract.macros[0].parameters.iter().map(|p| p.default.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        defaults,
+        vec![
+            None,
+            Some(MacroArg::Ident("fail".to_string())),
+            Some(MacroArg::Constant("ERR_CODE".into()))
+        ]
+    );
+
+    // Invocations are passed the defaults of the trailing parameters they omit
+    let args = contract.macros[1]
+        .statements
+        .iter()
+        .filter_map(|s| match &s.ty {
+            StatementType::MacroInvocation(mi) => Some(mi.args.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        args,
+        vec![
+            vec![
+                MacroArg::Literal(str_to_bytes32("01")),
+                MacroArg::Ident("fail".to_string()),
+                MacroArg::Constant("ERR_CODE".into())
+            ],
+            vec![
+                MacroArg::Literal(str_to_bytes32("01")),
+                MacroArg::Ident("done".to_string()),
+                MacroArg::Constant("ERR_CODE".into())
+            ]
+        ]
+    );
+
+    // Parameters following a default must have one too
+    let source = "#define macro REQUIRE(err = fail, value) = takes(0) returns(0) {}";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let error = parser.parse().unwrap_err();
+    assert_eq!(error.kind, ParserErrorKind::MissingParameterDefault("value".to_string()));
+}
@@ -0,0 +1,91 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+fn parse(source: &str) -> Contract {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    parser.parse().unwrap()
+}
+
+#[test]
+fn test_doc_comments_of_definitions() {
+    let source = r#"/// @notice Transfers tokens
+/// @dev Reverts if the balance is too low
+/// @param to The recipient
+/// @param amount The amount
+///        of tokens
+#define function transfer(address to, uint256 amount) nonpayable returns ()
+
+/**
+ * A transfer happened
+ * @param from The sender
+ */
+#define event Transfer(address indexed from, address indexed to, uint256)
+
+/// @dev Stores the caller
+#define macro MAIN() = takes(0) returns(0) {
+    caller 0x00 sstore
+}
+"#;
+    let contract = parse(source);
+    let transfer = NatSpec {
+        notice: Some("Transfers tokens".to_string()),
+        dev: Some("Reverts if the balance is too low".to_string()),
+        params: [
+            ("to".to_string(), "The recipient".to_string()),
+            ("amount".to_string(), "The amount\nof tokens".to_string()),
+        ]
+        .into_iter()
+        .collect(),
+    };
+    let event = NatSpec {
+        notice: Some("A transfer happened".to_string()),
+        dev: None,
+        params: [("from".to_string(), "The sender".to_string())].into_iter().collect(),
+    };
+    let main = NatSpec { dev: Some("Stores the caller".to_string()), ..Default::default() };
+    assert_eq!(
+        contract.natspec,
+        [
+            ((SymbolKind::Function, "transfer".to_string()), transfer),
+            ((SymbolKind::Event, "Transfer".to_string()), event),
+            ((SymbolKind::Macro, "MAIN".to_string()), main),
+        ]
+        .into_iter()
+        .collect::<NatSpecs>()
+    );
+}
+
+#[test]
+fn test_plain_comments_are_not_doc_comments() {
+    let source = r#"// Transfers tokens
+#define function transfer(address,uint256) nonpayable returns ()
+
+/// Stores the caller, documenting the constant and not the macro
+#define constant OWNER = FREE_STORAGE_POINTER()
+#define macro MAIN() = takes(0) returns(0) {
+    caller [OWNER] sstore
+}
+"#;
+    let natspec = NatSpec {
+        notice: Some("Stores the caller, documenting the constant and not the macro".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        parse(source).natspec,
+        [((SymbolKind::Constant, "OWNER".to_string()), natspec)].into_iter().collect::<NatSpecs>()
+    );
+}
+
+#[test]
+fn test_doc_comment_tags() {
+    let comments = ["/// Notice without a tag".to_string(), "/// @author Huff".to_string()];
+    let natspec = NatSpec::parse(&comments).unwrap();
+    assert_eq!(natspec.notice, Some("Notice without a tag".to_string()));
+    assert_eq!(natspec.dev, None);
+    assert!(natspec.params.is_empty());
+    assert_eq!(NatSpec::parse(&["// @notice Plain".to_string()]), None);
+}
@@ -0,0 +1,88 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+#[test]
+fn recovers_at_definition_boundaries() {
+    let source = r#"
+    #define macro BROKEN() = takes(0) returns {
+        0x00
+    }
+
+    #define unknown FOO() = takes(0) returns(0) {}
+
+    #define constant OWNER = FREE_STORAGE_POINTER()
+
+    #define macro MAIN() = takes(0) returns(0) {
+        [OWNER] sload
+    }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    // The first error is still returned
+    let err = parser.parse().unwrap_err();
+    assert_eq!(err, parser.errors[0]);
+
+    // While every definition error was collected
+    assert_eq!(
+        parser.errors.iter().map(|e| e.kind.clone()).collect::<Vec<ParserErrorKind>>(),
+        vec![
+            ParserErrorKind::UnexpectedType(TokenKind::Returns),
+            ParserErrorKind::InvalidDefinition
+        ]
+    );
+}
+
+#[test]
+fn recovers_at_statement_boundaries() {
+    let source = r#"
+    #define macro MAIN() = takes(0) returns(0) {
+        0x00 = mstore
+        label:
+            0x01 = 0x02 )
+    }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    assert!(parser.parse().is_err());
+    assert_eq!(
+        parser.errors.iter().map(|e| e.kind.clone()).collect::<Vec<ParserErrorKind>>(),
+        vec![
+            ParserErrorKind::InvalidTokenInMacroBody(TokenKind::Assign),
+            ParserErrorKind::InvalidTokenInLabelDefinition(TokenKind::Assign),
+            ParserErrorKind::InvalidTokenInLabelDefinition(TokenKind::CloseParen),
+        ]
+    );
+}
+
+#[test]
+fn no_errors_on_valid_source() {
+    let source = "#define macro MAIN() = takes(0) returns(0) { 0x00 mstore }";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    assert!(parser.parse().is_ok());
+    assert!(parser.errors.is_empty());
+}
+
+#[test]
+fn recovers_at_end_of_file() {
+    for source in ["#define function g(addr", "#define constant X =", "#define macro"] {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut parser = Parser::new(tokens, None);
+
+        assert!(parser.parse().is_err());
+        assert_eq!(parser.errors.len(), 1);
+        assert_eq!(parser.peek(), None);
+    }
+}
@@ -0,0 +1,138 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+fn parse(source: &str) -> (Parser, Contract) {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse_partial();
+    (parser, contract)
+}
+
+fn types(statements: &[Statement]) -> Vec<StatementType> {
+    statements.iter().map(|s| s.ty.clone()).collect()
+}
+
+#[test]
+fn test_expands_repeats() {
+    let source = r#"
+    #define macro MAIN() = takes(0) returns(0) {
+        __REPEAT(3) { pop }
+        __REPEAT([WORDS], I) { [I] STORE([I]) }
+        stop
+    }
+
+    #define constant WORDS = 0x02
+    "#;
+    let (parser, contract) = parse(source);
+    assert!(parser.errors.is_empty());
+
+    // Blocks are repeated with their index, even with a count defined after them
+    let store = |i: &str| {
+        StatementType::MacroInvocation(MacroInvocation {
+            macro_name: "STORE".into(),
+            args: vec![MacroArg::Literal(str_to_bytes32(i))],
+            span: AstSpan::default(),
+        })
+    };
+    let mut statements = types(&contract.macros[0].statements);
+    for ty in statements.iter_mut() {
+        if let StatementType::MacroInvocation(mi) = ty {
+            mi.span = AstSpan::default();
+        }
+    }
+    assert_eq!(
+        statements,
+        vec![
+            StatementType::Opcode(Opcode::Pop),
+            StatementType::Opcode(Opcode::Pop),
+            StatementType::Opcode(Opcode::Pop),
+            StatementType::Literal(str_to_bytes32("00")),
+            store("00"),
+            StatementType::Literal(str_to_bytes32("01")),
+            store("01"),
+            StatementType::Opcode(Opcode::Stop),
+        ]
+    );
+}
+
+#[test]
+fn test_expands_nested_repeats() {
+    let source = r#"
+    #define macro MAIN() = takes(0) returns(0) {
+        __REPEAT(2, I) {
+            __REPEAT([I], J) { [I] [J] }
+            __REPEAT(1, I) { [I] }
+        }
+    }
+    "#;
+    let (parser, contract) = parse(source);
+    assert!(parser.errors.is_empty());
+
+    // Inner repeats see the indices of the outer ones, unless they shadow them
+    let literal = |i: &str| StatementType::Literal(str_to_bytes32(i));
+    assert_eq!(
+        types(&contract.macros[0].statements),
+        vec![literal("00"), literal("01"), literal("00"), literal("00")]
+    );
+}
+
+#[test]
+fn test_expands_repeats_in_labels() {
+    let source = r#"
+    #define macro MAIN() = takes(0) returns(0) {
+        done jump
+        done:
+            __REPEAT(0x02) { 0x00 }
+            __REPEAT(0) { pop }
+    }
+    "#;
+    let (parser, contract) = parse(source);
+    assert!(parser.errors.is_empty());
+    match &contract.macros[0].statements[2].ty {
+        StatementType::Label(l) => {
+            let zero = StatementType::Literal(str_to_bytes32("00"));
+            assert_eq!(types(&l.inner), vec![zero.clone(), zero]);
+        }
+        ty => panic!("Expected a label, got {}", ty),
+    }
+}
+
+#[test]
+fn test_invalid_repeat_counts() {
+    let source = r#"
+    #define constant SLOT = FREE_STORAGE_POINTER()
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __REPEAT([SLOT]) { pop }
+        __REPEAT([MISSING]) { pop }
+        __REPEAT(0x6001) { pop }
+        __REPEAT(0x6000) { }
+    }
+    "#;
+    let (parser, contract) = parse(source);
+    assert_eq!(
+        parser.errors.iter().map(|e| e.kind.clone()).collect::<Vec<_>>(),
+        vec![
+            ParserErrorKind::InvalidRepeatCount("[SLOT]".to_string()),
+            ParserErrorKind::InvalidRepeatCount("[MISSING]".to_string()),
+            ParserErrorKind::InvalidRepeatCount("0x6001".to_string()),
+        ]
+    );
+    assert!(contract.macros[0].statements.is_empty());
+
+    // Counts are literals or constants
+    let (parser, _) = parse("#define macro MAIN() = takes(0) returns(0) {\n    __REPEAT(<n>) { }\n}");
+    assert_eq!(parser.errors[0].kind, ParserErrorKind::InvalidArgs(TokenKind::LeftAngle));
+
+    // Repetitions can't define labels
+    let (parser, _) = parse(
+        "#define macro MAIN() = takes(0) returns(0) {\n    __REPEAT(2) { done: stop }\n}",
+    );
+    assert_eq!(
+        parser.errors[0].kind,
+        ParserErrorKind::InvalidTokenInMacroBody(TokenKind::Label("done".to_string()))
+    );
+}
@@ -19,15 +19,16 @@ fn derives_storage_pointers() {
     assert_eq!(
         fsp_constant,
         ConstantDefinition {
-            name: "FSP_LOCATION".to_string(),
-            value: ConstVal::FreeStoragePointer(FreeStoragePointer {}),
+            name: "FSP_LOCATION".into(),
+            value: ConstVal::FreeStoragePointer(FreeStoragePointer { size: 1 }),
             span: AstSpan(vec![
                 Span { start: 0, end: 7, file: None },
                 Span { start: 8, end: 16, file: None },
                 Span { start: 17, end: 29, file: None },
                 Span { start: 30, end: 31, file: None },
                 Span { start: 32, end: 54, file: None }
-            ])
+            ]),
+            internal: false,
         }
     );
 
@@ -35,15 +36,16 @@ fn derives_storage_pointers() {
     assert_eq!(
         fsp_constant,
         ConstantDefinition {
-            name: "FSP_LOCATION_2".to_string(),
-            value: ConstVal::FreeStoragePointer(FreeStoragePointer {}),
+            name: "FSP_LOCATION_2".into(),
+            value: ConstVal::FreeStoragePointer(FreeStoragePointer { size: 1 }),
             span: AstSpan(vec![
                 Span { start: 55, end: 62, file: None },
                 Span { start: 63, end: 71, file: None },
                 Span { start: 72, end: 86, file: None },
                 Span { start: 87, end: 88, file: None },
                 Span { start: 89, end: 111, file: None }
-            ])
+            ]),
+            internal: false,
         }
     );
 
@@ -51,7 +53,7 @@ fn derives_storage_pointers() {
     assert_eq!(
         num_constant,
         ConstantDefinition {
-            name: "NUM".to_string(),
+            name: "NUM".into(),
             value: ConstVal::Literal(str_to_bytes32("a57B")),
             span: AstSpan(vec![
                 Span { start: 112, end: 119, file: None },
@@ -59,7 +61,8 @@ fn derives_storage_pointers() {
                 Span { start: 129, end: 132, file: None },
                 Span { start: 133, end: 134, file: None },
                 Span { start: 137, end: 141, file: None }
-            ])
+            ]),
+            internal: false,
         }
     );
 
@@ -67,7 +70,90 @@ fn derives_storage_pointers() {
     contract.derive_storage_pointers();
 
     // Ensure that the storage pointers were set for the FSP constants in the AST
-    assert_eq!(contract.constants[0].value, ConstVal::FreeStoragePointer(FreeStoragePointer));
-    assert_eq!(contract.constants[1].value, ConstVal::FreeStoragePointer(FreeStoragePointer));
+    assert_eq!(
+        contract.constants[0].value,
+        ConstVal::FreeStoragePointer(FreeStoragePointer { size: 1 })
+    );
+    assert_eq!(
+        contract.constants[1].value,
+        ConstVal::FreeStoragePointer(FreeStoragePointer { size: 1 })
+    );
     assert_eq!(contract.constants[2].value, ConstVal::Literal(str_to_bytes32("a57B")));
 }
+
+fn parse(source: &str) -> Result<Contract, ParserError> {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    Parser::new(tokens, None).parse()
+}
+
+#[test]
+fn derives_sized_storage_reservations() {
+    let source = r#"#define constant OWNER = FREE_STORAGE_POINTER()
+#define constant BALANCES = FREE_STORAGE_POINTER(3)
+#define constant CONFIG = FREE_STORAGE_POINTER(0x10)
+#define constant TOTAL_SUPPLY = FREE_STORAGE_POINTER()
+
+#define macro MAIN() = takes(0) returns(0) {
+    [OWNER] sload [BALANCES] sload [CONFIG] sload [TOTAL_SUPPLY] sload
+}
+"#;
+    let mut contract = parse(source).unwrap();
+    let sizes = contract
+        .constants
+        .iter()
+        .map(|c| match &c.value {
+            ConstVal::FreeStoragePointer(fsp) => fsp.size,
+            ConstVal::Literal(_) => 0,
+        })
+        .collect::<Vec<usize>>();
+    assert_eq!(sizes, vec![1, 3, 16, 1]);
+
+    // Each reservation advances the next free slot by its size
+    contract.derive_storage_pointers();
+    let slots = contract.constants.iter().map(|c| c.value.clone()).collect::<Vec<ConstVal>>();
+    assert_eq!(
+        slots,
+        vec![
+            ConstVal::Literal(str_to_bytes32("00")),
+            ConstVal::Literal(str_to_bytes32("01")),
+            ConstVal::Literal(str_to_bytes32("04")),
+            ConstVal::Literal(str_to_bytes32("14")),
+        ]
+    );
+}
+
+#[test]
+fn rejects_empty_storage_reservations() {
+    for size in ["0", "0x00"] {
+        let source = format!("#define constant BALANCES = FREE_STORAGE_POINTER({})\n", size);
+        let error = parse(&source).unwrap_err();
+        assert!(matches!(error.kind, ParserErrorKind::InvalidConstantValue(_)));
+        assert_eq!(error.kind.code(), "H0204");
+    }
+    let error = parse("#define constant BALANCES = FREE_STORAGE_POINTER(2\n").unwrap_err();
+    assert!(matches!(error.kind, ParserErrorKind::UnexpectedType(_)));
+}
+
+#[test]
+fn derives_storage_pointers_passed_as_macro_args() {
+    let source = r#"#define constant OWNER = FREE_STORAGE_POINTER()
+#define constant BALANCES = FREE_STORAGE_POINTER()
+
+#define macro SLOT_OF(mapping) = takes(1) returns(1) {
+    0x00 mstore <mapping> 0x20 mstore 0x40 0x00 sha3
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    caller SLOT_OF([BALANCES]) sload [OWNER] sload
+}
+"#;
+    let mut contract = parse(source).unwrap();
+    contract.derive_storage_pointers();
+    let slots = contract.constants.iter().map(|c| c.value.clone()).collect::<Vec<ConstVal>>();
+    assert_eq!(
+        slots,
+        vec![ConstVal::Literal(str_to_bytes32("01")), ConstVal::Literal(str_to_bytes32("00"))]
+    );
+}
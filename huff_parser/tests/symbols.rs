@@ -0,0 +1,101 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+fn parse(source: &str) -> Parser {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    parser.parse_partial();
+    parser
+}
+
+#[test]
+fn symbol_table_of_definitions() {
+    let source = r#"#define function transfer(address,uint256) nonpayable returns ()
+#define event Transfer(address,address,uint256)
+#define constant OWNER = FREE_STORAGE_POINTER()
+#define jumptable SWITCH = {
+    first
+}
+#define macro MAIN() = takes(0) returns(0) {
+    [OWNER] sload
+    first jumpi
+    first:
+        0x00 0x00 return
+}
+"#;
+    let parser = parse(source);
+    assert!(parser.errors.is_empty());
+
+    let symbols = &parser.symbols;
+    assert_eq!(
+        symbols.symbols.iter().map(|s| (s.name.as_str(), s.kind)).collect::<Vec<_>>(),
+        vec![
+            ("transfer", SymbolKind::Function),
+            ("Transfer", SymbolKind::Event),
+            ("OWNER", SymbolKind::Constant),
+            ("SWITCH", SymbolKind::Table),
+            ("MAIN", SymbolKind::Macro),
+            ("first", SymbolKind::Label),
+        ]
+    );
+
+    // Symbols are spanned by their identifier
+    for symbol in symbols.symbols.iter() {
+        assert_eq!(&source[symbol.span.start..symbol.span.end], symbol.name);
+    }
+
+    // Labels are scoped to their macro
+    let label = symbols.label("MAIN", "first").unwrap();
+    assert_eq!(label.scope, Some("MAIN".to_string()));
+    assert!(symbols.get("first").is_none());
+    assert_eq!(symbols.labels("MAIN").count(), 1);
+}
+
+#[test]
+fn symbol_table_position_queries() {
+    let source = r#"#define constant first = 0x01
+#define macro MAIN() = takes(0) returns(0) {
+    first jumpi
+    first:
+        [first] pop
+}
+"#;
+    let parser = parse(source);
+    let symbols = &parser.symbols;
+
+    // The identifier under a position
+    let offset = source.find("MAIN").unwrap() + 2;
+    assert_eq!(symbols.at(None, offset).unwrap().name, "MAIN");
+    assert_eq!(symbols.at(None, source.find("takes").unwrap()), None);
+
+    // The definition containing a position
+    let jump = source.find("first jumpi").unwrap();
+    assert_eq!(symbols.enclosing(None, jump).unwrap().name, "MAIN");
+    assert_eq!(symbols.enclosing(None, 3).unwrap().name, "first");
+
+    // Labels shadow top-level definitions inside their macro only
+    let resolved = symbols.resolve("first", None, jump).unwrap();
+    assert_eq!(resolved.kind, SymbolKind::Label);
+    let resolved = symbols.resolve("first", None, 3).unwrap();
+    assert_eq!(resolved.kind, SymbolKind::Constant);
+}
+
+#[test]
+fn symbol_table_of_recovered_definitions() {
+    let source = r#"#define macro BROKEN() = takes(0) returns(0) {
+    0x00 #define
+}
+#define macro MAIN() = takes(0) returns(0) {
+    0x00 0x00 return
+}
+"#;
+    let parser = parse(source);
+    assert!(!parser.errors.is_empty());
+
+    // Only the definitions that could be parsed are defined
+    assert!(parser.symbols.get("BROKEN").is_none());
+    assert_eq!(parser.symbols.get("MAIN").unwrap().kind, SymbolKind::Macro);
+}
@@ -20,7 +20,7 @@ fn table_with_no_body() {
         assert_eq!(
             table_definition,
             TableDefinition {
-                name: "TEST_TABLE".to_string(),
+                name: "TEST_TABLE".into(),
                 kind: TableKind::from(kind),
                 statements: vec![],
                 size: Literal::default(),
@@ -65,11 +65,11 @@ fn table_with_body() {
         assert_eq!(
             table_definition,
             TableDefinition {
-                name: "TEST_TABLE".to_string(),
+                name: "TEST_TABLE".into(),
                 kind: TableKind::from(kind),
                 statements: vec![
                     Statement {
-                        ty: StatementType::LabelCall("label_call_1".to_string()),
+                        ty: StatementType::LabelCall("label_call_1".into()),
                         span: AstSpan(vec![Span {
                             start: lb1_start,
                             end: lb1_start + "label_call_1".len(),
@@ -77,7 +77,7 @@ fn table_with_body() {
                         }]),
                     },
                     Statement {
-                        ty: StatementType::LabelCall("label_call_2".to_string()),
+                        ty: StatementType::LabelCall("label_call_2".into()),
                         span: AstSpan(vec![Span {
                             start: lb2_start,
                             end: lb2_start + "label_call_2".len(),
@@ -85,7 +85,7 @@ fn table_with_body() {
                         }]),
                     },
                     Statement {
-                        ty: StatementType::LabelCall("label_call_3".to_string()),
+                        ty: StatementType::LabelCall("label_call_3".into()),
                         span: AstSpan(vec![Span {
                             start: lb3_start,
                             end: lb3_start + "label_call_3".len(),
@@ -112,3 +112,43 @@ fn table_with_body() {
         assert_eq!(parser.current_token.kind, TokenKind::Eof);
     }
 }
+
+#[test]
+fn code_table_from_file() {
+    let dir = std::env::temp_dir().join(format!("huff_table_file_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("blob.bin"), [0xde, 0xad, 0xbe, 0xef, 0x00]).unwrap();
+    let base = dir.join("Data.huff").to_string_lossy().to_string();
+    let parse = |source: &str| {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        Parser::new(tokens, Some(base.clone())).parse()
+    };
+
+    // The file contents are read relative to the defining file
+    let source = "#define table DATA = file(\"./blob.bin\")";
+    let path_start = source.find("\"./blob.bin\"").unwrap();
+    let table = parse(source).unwrap().tables[0].clone();
+    assert_eq!(table.kind, TableKind::CodeTable);
+    assert_eq!(table.size, str_to_bytes32("5"));
+    assert_eq!(
+        table.statements,
+        vec![Statement {
+            ty: StatementType::Code(vec![0xde, 0xad, 0xbe, 0xef, 0x00]),
+            span: AstSpan(vec![Span {
+                start: path_start,
+                end: path_start + "\"./blob.bin\"".len(),
+                file: None
+            }]),
+        }]
+    );
+
+    // A missing file is reported at its path
+    let source = "#define table DATA = file(\"./missing.bin\")";
+    let err = parse(source).unwrap_err();
+    assert_eq!(err.kind, ParserErrorKind::InvalidTableFile("./missing.bin".to_string()));
+    assert_eq!(err.spans.0[0].start, source.find("\"./missing.bin\"").unwrap());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,53 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+fn parse(source: &str) -> (Parser, Contract) {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse_partial();
+    (parser, contract)
+}
+
+#[test]
+fn test_parses_test() {
+    let source = r#"
+    #define macro ADD() = takes(2) returns(1) {
+        add
+    }
+
+    #define test TEST_ADD(uint256 a, bytes32[2] b) = {
+        0x00 calldataload
+        done jump
+        done:
+            stop
+    }
+    "#;
+    let (parser, contract) = parse(source);
+    assert!(parser.errors.is_empty());
+    assert_eq!(contract.macros.len(), 1);
+
+    let test = &contract.tests[0];
+    assert_eq!(test.name, "TEST_ADD");
+    assert_eq!((test.takes, test.returns), (0, 0));
+    assert_eq!(
+        test.parameters.iter().map(|p| (p.arg_type.clone(), p.name.clone())).collect::<Vec<_>>(),
+        vec![
+            (Some("uint256".to_string()), Some("a".to_string())),
+            (Some("bytes32[2]".to_string()), Some("b".to_string())),
+        ]
+    );
+    assert_eq!(test.statements.len(), 5);
+    assert_eq!(parser.symbols.get("TEST_ADD").unwrap().kind, SymbolKind::Test);
+    assert!(parser.symbols.label("TEST_ADD", "done").is_some());
+}
+
+#[test]
+fn test_rejects_test_without_types() {
+    let (parser, contract) = parse("#define test TEST_ADD(a) = {}\n#define test TEST_OK() = {}");
+    assert_eq!(parser.errors.len(), 1);
+    assert_eq!(contract.tests.len(), 1);
+    assert_eq!(contract.tests[0].name, "TEST_OK");
+}
@@ -0,0 +1,86 @@
+//! ## Cheatcodes
+//!
+//! Executes the cheatcodes tests call, for the test runner.
+//!
+//! A call to the cheatcode address isn't executed as a call to a contract: like a revm
+//! inspector overriding a call, the cheatcode is applied to the interpreter and host, and the call
+//! succeeds without return data, refunding the gas it was given. `prank` sets the address
+//! `caller` returns for the rest of the test, `deal` sets a balance, and `warp` and `roll` set the
+//! block timestamp and number. `expectRevert` expects the rest of the test to revert, and
+//! `expectEmit` expects the log emitted after the next one to match it, like the event emitted
+//! before the call of a Foundry test. A call with unknown calldata fails the test.
+
+use ethers_core::abi::{self, ParamType, Token};
+use huff_utils::prelude::Cheatcode;
+use revm_interpreter::{
+    primitives::{Address, Log, U256},
+    CallInputs, Interpreter,
+};
+
+use crate::host::CallHost;
+
+/// The expectations the cheatcodes called by a test set
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cheatcodes {
+    /// Whether the test is expected to revert
+    pub expect_revert: bool,
+    /// The indices of the expected logs, each to be matched by the log emitted after it
+    pub expected_emits: Vec<usize>,
+    /// Why a cheatcode call failed, ending the test
+    pub failure: Option<String>,
+}
+
+impl Cheatcodes {
+    /// Applies the cheatcode of a call to the cheatcode address
+    pub fn call(
+        &mut self,
+        inputs: &CallInputs,
+        interpreter: &mut Interpreter,
+        host: &mut CallHost,
+    ) -> Result<(), String> {
+        let Some(cheatcode) = Cheatcode::decode(&inputs.input) else {
+            return Err(format!("Unknown cheatcode 0x{}", hex::encode(&inputs.input)))
+        };
+        let params = match cheatcode {
+            Cheatcode::Prank => vec![ParamType::Address],
+            Cheatcode::Deal => vec![ParamType::Address, ParamType::Uint(256)],
+            Cheatcode::Warp | Cheatcode::Roll => vec![ParamType::Uint(256)],
+            Cheatcode::ExpectRevert | Cheatcode::ExpectEmit => vec![],
+        };
+        let args = abi::decode(&params, &inputs.input[4..])
+            .map_err(|e| format!("Invalid arguments of {}: {}", cheatcode.signature(), e))?;
+        match (cheatcode, args.as_slice()) {
+            (Cheatcode::Prank, [Token::Address(caller)]) => {
+                interpreter.contract.caller = Address::from_slice(caller.as_bytes())
+            }
+            (Cheatcode::Deal, [Token::Address(address), Token::Uint(balance)]) => {
+                host.balances
+                    .insert(Address::from_slice(address.as_bytes()), U256::from_limbs(balance.0));
+            }
+            (Cheatcode::Warp, [Token::Uint(timestamp)]) => {
+                host.env.block.timestamp = U256::from_limbs(timestamp.0)
+            }
+            (Cheatcode::Roll, [Token::Uint(number)]) => {
+                host.env.block.number = U256::from_limbs(number.0)
+            }
+            (Cheatcode::ExpectRevert, []) => self.expect_revert = true,
+            (Cheatcode::ExpectEmit, []) => self.expected_emits.push(host.logs.len()),
+            _ => unreachable!("the arguments are decoded from the parameters of the cheatcode"),
+        }
+        Ok(())
+    }
+
+    /// Checks the log emitted after each expected log matches its topics and data
+    pub fn check_emits(&self, logs: &[Log]) -> Result<(), String> {
+        for index in self.expected_emits.iter() {
+            match (logs.get(*index), logs.get(index + 1)) {
+                (Some(expected), Some(emitted)) if expected.data == emitted.data => {}
+                (Some(_), Some(_)) => {
+                    return Err(format!("Log {} doesn't match the expected log", index + 1))
+                }
+                _ => return Err("Expected a log to be emitted".to_string()),
+            }
+        }
+        Ok(())
+    }
+}
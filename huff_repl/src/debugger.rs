@@ -0,0 +1,600 @@
+//! ## Debugger
+//!
+//! Steps through the execution of a compiled contract at the Huff source level, for
+//! `huffc debug`.
+//!
+//! The constructor of the contract is executed to deploy it, then its runtime bytecode is
+//! executed with the given calldata, recording the stack, memory and storage before each
+//! instruction. Stepping moves through the recorded trace, in both directions. Each step is
+//! mapped to the Huff statement generating its instruction, and the macro invocations it was
+//! generated through, with the source map of the runtime bytecode.
+//!
+//! Breakpoints are set on labels, hit at their jump destination, and on macros, hit when an
+//! expansion of the macro is entered.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use huff_utils::prelude::{
+    DisassembledInstruction, Disassembly, EVMVersion, SourceMapping, CHEATCODE_ADDRESS,
+};
+use revm_interpreter::{
+    opcode::make_instruction_table,
+    primitives::{Address, Bytecode, Bytes, CancunSpec, MergeSpec, ShanghaiSpec, Spec, B256, U256},
+    CallOutcome, Contract, Gas, InstructionResult, Interpreter, InterpreterAction,
+    InterpreterResult, SharedMemory,
+};
+
+use crate::{cheatcodes::Cheatcodes, host::CallHost, render_memory, GAS_LIMIT};
+
+/// The maximum number of steps recorded in a trace, the execution continues unrecorded past it
+pub const MAX_STEPS: usize = 100_000;
+
+/// The number of source lines shown around the current one
+const SOURCE_CONTEXT: usize = 4;
+
+/// The key bindings, shown in the footer
+const KEYS: &str = "s/S step  n/N line  c/C continue  g/G start/end  b breakpoint  q quit";
+
+/// What a trace records of each step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Recording {
+    /// The whole state before the instruction
+    Full,
+    /// The program counter, opcode and remaining gas
+    Gas,
+    /// No steps
+    Nothing,
+}
+
+/// Records the state before an instruction
+type Recorder<'a> = &'a dyn Fn(&Interpreter, &CallHost);
+
+/// An instruction executed on the debugger host
+type HostInstruction<'a> = Box<dyn Fn(&mut Interpreter, &mut CallHost) + 'a>;
+
+/// The state before an instruction of a trace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// The program counter of the instruction
+    pub pc: usize,
+    /// The opcode of the instruction
+    pub opcode: u8,
+    /// The stack, bottom first
+    pub stack: Vec<U256>,
+    /// The memory
+    pub memory: Vec<u8>,
+    /// The storage, by slot
+    pub storage: BTreeMap<U256, U256>,
+    /// The gas remaining
+    pub gas_remaining: u64,
+}
+
+/// The recorded execution of runtime bytecode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    /// The steps, one per executed instruction
+    pub steps: Vec<Step>,
+    /// How the execution ended
+    pub result: InstructionResult,
+    /// The data returned or reverted with
+    pub return_data: Vec<u8>,
+    /// The gas used by the execution
+    pub gas_used: u64,
+    /// Whether the execution ran past [MAX_STEPS], not recording the steps after it
+    pub truncated: bool,
+}
+
+impl Trace {
+    /// Executes the runtime bytecode of a deployed contract in a new transaction, recording each
+    /// step
+    ///
+    /// Calls and creates aren't executed, they end the trace with a `CallOrCreate` result.
+    pub fn new(
+        code: &[u8],
+        calldata: &[u8],
+        value: U256,
+        host: &mut CallHost,
+        evm_version: EVMVersion,
+    ) -> Self {
+        Self::record(code, calldata, value, host, evm_version, Recording::Full, None)
+    }
+
+    /// Executes the runtime bytecode of a deployed contract, recording only the program
+    /// counter, opcode and remaining gas of each step
+    ///
+    /// The stack, memory and storage of the steps are left empty, and steps are recorded past
+    /// [MAX_STEPS].
+    pub fn gas_only(
+        code: &[u8],
+        calldata: &[u8],
+        value: U256,
+        host: &mut CallHost,
+        evm_version: EVMVersion,
+    ) -> Self {
+        Self::record(code, calldata, value, host, evm_version, Recording::Gas, None)
+    }
+
+    /// Executes the runtime bytecode of a deployed contract, recording none of its steps
+    pub fn untraced(
+        code: &[u8],
+        calldata: &[u8],
+        value: U256,
+        host: &mut CallHost,
+        evm_version: EVMVersion,
+    ) -> Self {
+        Self::record(code, calldata, value, host, evm_version, Recording::Nothing, None)
+    }
+
+    /// Executes the runtime bytecode of a test, recording none of its steps
+    ///
+    /// Calls to the [cheatcode address](CHEATCODE_ADDRESS) execute their cheatcode and the
+    /// execution goes on. A failing cheatcode ends the trace with a `CallOrCreate` result, and
+    /// is recorded in the cheatcodes.
+    pub fn with_cheatcodes(
+        code: &[u8],
+        calldata: &[u8],
+        value: U256,
+        host: &mut CallHost,
+        evm_version: EVMVersion,
+        cheatcodes: &mut Cheatcodes,
+    ) -> Self {
+        Self::record(code, calldata, value, host, evm_version, Recording::Nothing, Some(cheatcodes))
+    }
+
+    /// Executes runtime bytecode, recording each step in full, only its gas, or not at all
+    fn record(
+        code: &[u8],
+        calldata: &[u8],
+        value: U256,
+        host: &mut CallHost,
+        evm_version: EVMVersion,
+        recording: Recording,
+        mut cheatcodes: Option<&mut Cheatcodes>,
+    ) -> Self {
+        host.begin_transaction();
+        let mut interpreter =
+            Interpreter::new(Box::new(contract(code, calldata, value)), GAS_LIMIT, false);
+        let steps = RefCell::new(vec![]);
+        let truncated = RefCell::new(false);
+        let full = recording == Recording::Full;
+        let record = |interpreter: &Interpreter, host: &CallHost| {
+            let mut steps = steps.borrow_mut();
+            if full && steps.len() == MAX_STEPS {
+                *truncated.borrow_mut() = true;
+                return
+            }
+            // The instruction pointer is past the opcode of the executed instruction
+            let pc = interpreter.program_counter() - 1;
+            steps.push(Step {
+                pc,
+                opcode: interpreter.contract.bytecode.bytecode()[pc],
+                stack: if full { interpreter.stack.data().clone() } else { vec![] },
+                memory: match full {
+                    true => interpreter.shared_memory.context_memory().to_vec(),
+                    false => vec![],
+                },
+                storage: match full {
+                    true => host.storage.iter().map(|(k, v)| (*k, *v)).collect(),
+                    false => BTreeMap::new(),
+                },
+                gas_remaining: interpreter.gas.remaining(),
+            });
+        };
+        let record = (recording != Recording::Nothing).then_some(&record as Recorder);
+        let mut memory = SharedMemory::new();
+        let action = loop {
+            let action = match evm_version {
+                EVMVersion::Paris => run::<MergeSpec>(&mut interpreter, memory, host, record),
+                EVMVersion::Shanghai => run::<ShanghaiSpec>(&mut interpreter, memory, host, record),
+                EVMVersion::Cancun => run::<CancunSpec>(&mut interpreter, memory, host, record),
+            };
+            // Cheatcode calls are answered in place, the execution going on after them
+            let (Some(cheatcodes), InterpreterAction::Call { inputs, return_memory_offset }) =
+                (cheatcodes.as_deref_mut(), &action)
+            else {
+                break action
+            };
+            if inputs.contract != Address::from(CHEATCODE_ADDRESS) {
+                break action
+            }
+            if let Err(failure) = cheatcodes.call(inputs, &mut interpreter, host) {
+                cheatcodes.failure = Some(failure);
+                break action
+            }
+            let result = InterpreterResult {
+                result: InstructionResult::Return,
+                output: Bytes::new(),
+                gas: Gas::new(inputs.gas_limit),
+            };
+            memory = interpreter.take_memory();
+            interpreter.insert_call_outcome(
+                &mut memory,
+                CallOutcome::new(result, return_memory_offset.clone()),
+            );
+        };
+        Self {
+            steps: steps.into_inner(),
+            result: interpreter.instruction_result,
+            return_data: match action {
+                InterpreterAction::Return { result } => result.output.to_vec(),
+                _ => vec![],
+            },
+            gas_used: interpreter.gas.spend(),
+            truncated: truncated.into_inner(),
+        }
+    }
+
+    /// The gas used by each step, including the memory it expands
+    ///
+    /// Empty if the trace is truncated, since the gas of the last recorded step is unknown.
+    pub fn step_gas(&self) -> Vec<u64> {
+        if self.truncated {
+            return vec![]
+        }
+        let remaining = GAS_LIMIT.saturating_sub(self.gas_used);
+        let next = self.steps.iter().skip(1).map(|s| s.gas_remaining).chain([remaining]);
+        self.steps.iter().zip(next).map(|(s, next)| s.gas_remaining.saturating_sub(next)).collect()
+    }
+}
+
+/// Deploys a contract by executing its constructor, returning the runtime bytecode
+///
+/// The storage the constructor writes is kept in the host.
+pub fn deploy(
+    initcode: &[u8],
+    host: &mut CallHost,
+    evm_version: EVMVersion,
+) -> Result<Vec<u8>, String> {
+    host.begin_transaction();
+    let mut interpreter =
+        Interpreter::new(Box::new(contract(initcode, &[], U256::ZERO)), GAS_LIMIT, false);
+    let memory = SharedMemory::new();
+    let action = match evm_version {
+        EVMVersion::Paris => run::<MergeSpec>(&mut interpreter, memory, host, None),
+        EVMVersion::Shanghai => run::<ShanghaiSpec>(&mut interpreter, memory, host, None),
+        EVMVersion::Cancun => run::<CancunSpec>(&mut interpreter, memory, host, None),
+    };
+    match action {
+        InterpreterAction::Return { result } if result.is_ok() => Ok(result.output.to_vec()),
+        _ => Err(format!(
+            "The constructor failed to deploy the contract: {:?}",
+            interpreter.instruction_result
+        )),
+    }
+}
+
+/// Deploys a contract, checking the source map maps the deployed runtime bytecode
+pub(crate) fn deploy_with_source_map(
+    initcode: &[u8],
+    source_map: &[SourceMapping],
+    host: &mut CallHost,
+    evm_version: EVMVersion,
+) -> Result<Vec<u8>, String> {
+    let code = deploy(initcode, host, evm_version)?;
+    let mapped = source_map.iter().map(|m| m.offset + m.size).max().unwrap_or_default();
+    if mapped != code.len() {
+        return Err(format!(
+            "The constructor deployed {} bytes of runtime bytecode, the source maps {}",
+            code.len(),
+            mapped
+        ))
+    }
+    Ok(code)
+}
+
+/// The contract executing bytecode, at the zero address
+fn contract(code: &[u8], calldata: &[u8], value: U256) -> Contract {
+    Contract::new(
+        Bytes::from(calldata.to_vec()),
+        Bytecode::new_raw(Bytes::from(code.to_vec())),
+        B256::ZERO,
+        Address::ZERO,
+        Address::ZERO,
+        value,
+    )
+}
+
+/// Runs the interpreter to the end of its bytecode or its next call, recording the state before
+/// each instruction
+fn run<SPEC: Spec>(
+    interpreter: &mut Interpreter,
+    memory: SharedMemory,
+    host: &mut CallHost,
+    record: Option<Recorder>,
+) -> InterpreterAction {
+    let instructions = make_instruction_table::<CallHost, SPEC>();
+    let table: [HostInstruction; 256] = std::array::from_fn(|i| {
+        let instruction = instructions[i];
+        Box::new(move |interpreter: &mut Interpreter, host: &mut CallHost| {
+            if let Some(record) = record {
+                record(interpreter, host);
+            }
+            instruction(interpreter, host)
+        }) as HostInstruction
+    });
+    interpreter.run(memory, &table, host)
+}
+
+/// A debugging session, moving through the trace of an execution
+#[derive(Debug, Clone)]
+pub struct Debugger {
+    /// The trace of the execution
+    pub trace: Trace,
+    /// The source map of the runtime bytecode
+    pub source_map: Vec<SourceMapping>,
+    /// The labels and macros to break on
+    pub breakpoints: BTreeSet<String>,
+    /// The index of the current step
+    pub position: usize,
+    /// The message of the last action, shown in the footer
+    pub message: Option<String>,
+    /// The instructions of the runtime bytecode, by program counter
+    instructions: BTreeMap<usize, DisassembledInstruction>,
+}
+
+impl Debugger {
+    /// Deploys a contract and traces a call to it, starting a session at its first step
+    ///
+    /// The source map must map the runtime bytecode the constructor deploys.
+    pub fn launch(
+        initcode: &[u8],
+        source_map: Vec<SourceMapping>,
+        calldata: &[u8],
+        value: u128,
+        evm_version: EVMVersion,
+    ) -> Result<Self, String> {
+        let mut host = CallHost::default();
+        let code = deploy_with_source_map(initcode, &source_map, &mut host, evm_version)?;
+        let trace = Trace::new(&code, calldata, U256::from(value), &mut host, evm_version);
+        Ok(Self::new(&code, source_map, trace))
+    }
+
+    /// Starts a session at the first step of a trace of runtime bytecode
+    pub fn new(code: &[u8], source_map: Vec<SourceMapping>, trace: Trace) -> Self {
+        let instructions = Disassembly::with_source_map(code, &source_map)
+            .instructions
+            .into_iter()
+            .map(|i| (i.pc, i))
+            .collect();
+        Self {
+            trace,
+            source_map,
+            breakpoints: BTreeSet::new(),
+            position: 0,
+            message: None,
+            instructions,
+        }
+    }
+
+    /// The current step, `None` for an empty trace
+    pub fn step(&self) -> Option<&Step> {
+        self.trace.steps.get(self.position)
+    }
+
+    /// The source mapping of the instruction of a step
+    pub fn mapping(&self, position: usize) -> Option<&SourceMapping> {
+        let pc = self.trace.steps.get(position)?.pc;
+        self.source_map.iter().find(|m| m.contains(pc))
+    }
+
+    /// The path and line of the statement generating the instruction of a step
+    pub fn source_line(&self, position: usize) -> Option<(String, usize)> {
+        let span = self.mapping(position)?.span.0.first()?;
+        Some((span.file.as_ref()?.path.clone(), span.start_position()?.line))
+    }
+
+    /// The labels and macros a breakpoint can be set on
+    pub fn breakables(&self) -> BTreeSet<String> {
+        let labels = self.instructions.values().filter_map(|i| i.label.clone());
+        let macros = self
+            .source_map
+            .iter()
+            .flat_map(|m| m.invocations.iter().map(|i| i.macro_name.to_string()));
+        labels.chain(macros).chain(std::iter::once("MAIN".to_string())).collect()
+    }
+
+    /// Sets a breakpoint on a label or macro, or removes it if already set, returning whether
+    /// it's set
+    pub fn toggle_breakpoint(&mut self, name: &str) -> Result<bool, String> {
+        if self.breakpoints.remove(name) {
+            return Ok(false)
+        }
+        if !self.breakables().contains(name) {
+            return Err(format!(
+                "No label or macro \"{}\" is executed by the runtime bytecode",
+                name
+            ))
+        }
+        self.breakpoints.insert(name.to_string());
+        Ok(true)
+    }
+
+    /// The breakpoint a step hits, if any
+    pub fn breakpoint_hit(&self, position: usize) -> Option<&String> {
+        let step = self.trace.steps.get(position)?;
+        let label = self.instructions.get(&step.pc).and_then(|i| i.label.as_ref());
+        let invocations =
+            self.mapping(position).map(|m| m.invocations.as_slice()).unwrap_or_default();
+        let previous =
+            position.checked_sub(1).and_then(|p| self.mapping(p)).map(|m| m.invocations.as_slice());
+        self.breakpoints.iter().find(|name| {
+            if label == Some(*name) {
+                return true
+            }
+            if name.as_str() == "MAIN" {
+                return position == 0
+            }
+            // A macro is entered by a step in an expansion the previous step isn't in
+            match invocations.iter().position(|i| &i.macro_name == *name) {
+                Some(i) => !previous.is_some_and(|p| p.starts_with(&invocations[..=i])),
+                None => false,
+            }
+        })
+    }
+
+    /// Moves to the next step, or the previous one
+    pub fn step_by(&mut self, forward: bool) {
+        self.move_until(forward, |_, _| true);
+    }
+
+    /// Moves to the next step generated by another source line, or the previous one
+    pub fn next_line(&mut self, forward: bool) {
+        let line = self.source_line(self.position);
+        self.move_until(forward, |debugger, p| {
+            let next = debugger.source_line(p);
+            next.is_some() && next != line
+        });
+    }
+
+    /// Moves to the next step hitting a breakpoint, or the previous one, or to the end of the
+    /// trace if none does
+    pub fn continue_to_breakpoint(&mut self, forward: bool) {
+        if self.move_until(forward, |debugger, p| debugger.breakpoint_hit(p).is_some()) {
+            let hit = self.breakpoint_hit(self.position).cloned().unwrap_or_default();
+            self.message = Some(format!("Hit breakpoint \"{}\"", hit));
+        }
+    }
+
+    /// Moves to the first step, or the last one
+    pub fn go_to_end(&mut self, forward: bool) {
+        self.position = if forward { self.trace.steps.len().saturating_sub(1) } else { 0 };
+        self.message = None;
+    }
+
+    /// Moves step by step until a step satisfies a predicate, returning whether one did
+    ///
+    /// Stops at the first or last step if none does.
+    fn move_until(&mut self, forward: bool, until: impl Fn(&Self, usize) -> bool) -> bool {
+        self.message = None;
+        let last = self.trace.steps.len().saturating_sub(1);
+        let mut position = self.position;
+        loop {
+            match forward {
+                true if position < last => position += 1,
+                false if position > 0 => position -= 1,
+                _ => {
+                    self.position = position;
+                    self.message = Some(match forward {
+                        true => {
+                            format!("Reached the end of the execution: {:?}", self.trace.result)
+                        }
+                        false => "Reached the start of the execution".to_string(),
+                    });
+                    return false
+                }
+            }
+            if until(self, position) {
+                self.position = position;
+                return true
+            }
+        }
+    }
+
+    /// Renders the current step in a screen of a width and height
+    ///
+    /// Shows the source lines around the statement generating the instruction, the macro
+    /// invocations it was generated through, then the stack, memory and storage.
+    pub fn render(&self, width: usize, height: usize) -> Vec<String> {
+        let Some(step) = self.step() else {
+            return vec![format!("The execution has no steps: {:?}", self.trace.result)]
+        };
+        let mut lines = vec![format!(
+            "step {}/{}  pc {:#06x}  gas {} remaining  ends with {:?}{}",
+            self.position + 1,
+            self.trace.steps.len(),
+            step.pc,
+            step.gas_remaining,
+            self.trace.result,
+            if self.trace.truncated { " (truncated)" } else { "" }
+        )];
+
+        let mapping = self.mapping(self.position);
+        let invocations = mapping
+            .map(|m| {
+                let chain = m.invocations.iter().map(|i| format!("{}()", i.macro_name));
+                std::iter::once("MAIN()".to_string()).chain(chain).collect::<Vec<_>>().join(" > ")
+            })
+            .unwrap_or_default();
+        let location = self
+            .source_line(self.position)
+            .map(|(path, line)| format!("{}:{}", path, line))
+            .unwrap_or_else(|| "unmapped bytecode".to_string());
+        lines.push(section(&format!("{}  {}", location, invocations), width));
+        let span = mapping.and_then(|m| m.span.0.first());
+        match span.and_then(|s| Some((s.file.as_ref()?.source.as_ref()?, s.start_position()?.line)))
+        {
+            Some((source, line)) => {
+                let first = line.saturating_sub(SOURCE_CONTEXT).max(1);
+                for (i, code) in
+                    source.lines().enumerate().skip(first - 1).take(SOURCE_CONTEXT * 2 + 1)
+                {
+                    let marker = if i + 1 == line { ">" } else { " " };
+                    lines.push(format!("{} {:>4} | {}", marker, i + 1, code));
+                }
+            }
+            None => lines.push("  no source".to_string()),
+        }
+
+        let instruction = self.instructions.get(&step.pc);
+        lines.push(section("instruction", width));
+        lines.push(format!(
+            "  {:#06x}  {}{}",
+            step.pc,
+            instruction.map(listing).unwrap_or_else(|| format!("0x{:02x}", step.opcode)),
+            instruction
+                .and_then(|i| i.label.as_ref())
+                .map(|l| format!("  ({}:)", l))
+                .unwrap_or_default()
+        ));
+
+        lines.push(section(&format!("stack ({})", step.stack.len()), width));
+        lines.extend(
+            step.stack.iter().rev().enumerate().map(|(i, item)| format!("  {}: {:#x}", i, item)),
+        );
+        lines.push(section(&format!("memory ({} bytes)", step.memory.len()), width));
+        lines.extend(render_memory(&step.memory).lines().skip(1).map(|l| l.to_string()));
+        lines.push(section(&format!("storage ({} slots)", step.storage.len()), width));
+        lines.extend(
+            step.storage.iter().map(|(slot, value)| format!("  {:#x}: {:#x}", slot, value)),
+        );
+
+        // Keep the footer at the bottom, cutting the sections that don't fit
+        let footer = vec![
+            section("", width),
+            match &self.message {
+                Some(message) => message.clone(),
+                None if self.breakpoints.is_empty() => KEYS.to_string(),
+                None => format!(
+                    "breakpoints: {}",
+                    self.breakpoints.iter().cloned().collect::<Vec<_>>().join(", ")
+                ),
+            },
+        ];
+        lines.truncate(height.saturating_sub(footer.len()));
+        lines.resize(height.saturating_sub(footer.len()), String::new());
+        lines.extend(footer);
+        lines.into_iter().map(|l| l.chars().take(width).collect()).collect()
+    }
+}
+
+/// The header of a section of a screen width
+fn section(title: &str, width: usize) -> String {
+    match title {
+        "" => "─".repeat(width),
+        _ => format!("── {} {}", title, "─".repeat(width)),
+    }
+}
+
+/// The mnemonic and push data of an instruction
+fn listing(instruction: &DisassembledInstruction) -> String {
+    match (instruction.mnemonic, instruction.push_data()) {
+        (Some(mnemonic), Some(data)) if !data.is_empty() => {
+            format!("{} 0x{}", mnemonic, hex::encode(data))
+        }
+        (Some(mnemonic), _) => mnemonic.to_string(),
+        (None, _) => format!("0x{} (undefined opcode)", hex::encode(&instruction.bytes)),
+    }
+}
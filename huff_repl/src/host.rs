@@ -0,0 +1,125 @@
+//! ## Host
+//!
+//! The host of the contracts executed by the debugger and the gas profiler.
+//!
+//! Unlike the `DummyHost` of revm, the host prices each call as its own transaction, following
+//! EIP-2929 and EIP-2200: the storage slots accessed before the call are cold again, and the
+//! value of a slot at the start of the call is its original value. The storage the constructor
+//! writes is priced like the storage of a deployed contract called by a new transaction.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use revm_interpreter::{
+    primitives::{Address, Bytecode, Env, Log, B256, KECCAK_EMPTY, U256},
+    Host, SelfDestructResult,
+};
+
+/// A host keeping the storage of a single contract across transactions
+#[derive(Debug, Clone, Default)]
+pub struct CallHost {
+    /// The environment of the transactions
+    pub env: Env,
+    /// The storage, by slot
+    pub storage: BTreeMap<U256, U256>,
+    /// The balances of the accounts, zero if missing
+    pub balances: BTreeMap<Address, U256>,
+    /// The transient storage of the current transaction
+    pub transient_storage: BTreeMap<U256, U256>,
+    /// The logs emitted by the current transaction
+    pub logs: Vec<Log>,
+    /// The storage at the start of the current transaction
+    original: BTreeMap<U256, U256>,
+    /// The slots accessed by the current transaction
+    warm_slots: BTreeSet<U256>,
+    /// The accounts accessed by the current transaction
+    warm_accounts: BTreeSet<Address>,
+}
+
+impl CallHost {
+    /// Starts a new transaction, keeping the storage
+    ///
+    /// The accessed slots and accounts are cold again, and the transient storage and logs are
+    /// cleared.
+    pub fn begin_transaction(&mut self) {
+        self.original = self.storage.clone();
+        self.warm_slots.clear();
+        self.warm_accounts.clear();
+        self.transient_storage.clear();
+        self.logs.clear();
+    }
+
+    /// Accesses an account, returning whether it's the first access of the transaction
+    fn access(&mut self, address: Address) -> bool {
+        // The contract executing is already warm
+        address != Address::ZERO && self.warm_accounts.insert(address)
+    }
+}
+
+impl Host for CallHost {
+    fn env(&self) -> &Env {
+        &self.env
+    }
+
+    fn env_mut(&mut self) -> &mut Env {
+        &mut self.env
+    }
+
+    fn load_account(&mut self, address: Address) -> Option<(bool, bool)> {
+        Some((self.access(address), false))
+    }
+
+    fn block_hash(&mut self, _number: U256) -> Option<B256> {
+        Some(B256::ZERO)
+    }
+
+    fn balance(&mut self, address: Address) -> Option<(U256, bool)> {
+        let balance = self.balances.get(&address).copied().unwrap_or_default();
+        Some((balance, self.access(address)))
+    }
+
+    fn code(&mut self, address: Address) -> Option<(Bytecode, bool)> {
+        Some((Bytecode::default(), self.access(address)))
+    }
+
+    fn code_hash(&mut self, address: Address) -> Option<(B256, bool)> {
+        Some((KECCAK_EMPTY, self.access(address)))
+    }
+
+    fn sload(&mut self, _address: Address, index: U256) -> Option<(U256, bool)> {
+        let is_cold = self.warm_slots.insert(index);
+        Some((self.storage.get(&index).copied().unwrap_or_default(), is_cold))
+    }
+
+    fn sstore(
+        &mut self,
+        _address: Address,
+        index: U256,
+        value: U256,
+    ) -> Option<(U256, U256, U256, bool)> {
+        let is_cold = self.warm_slots.insert(index);
+        let original = self.original.get(&index).copied().unwrap_or_default();
+        let present = self.storage.insert(index, value).unwrap_or_default();
+        Some((original, present, value, is_cold))
+    }
+
+    fn tload(&mut self, _address: Address, index: U256) -> U256 {
+        self.transient_storage.get(&index).copied().unwrap_or_default()
+    }
+
+    fn tstore(&mut self, _address: Address, index: U256, value: U256) {
+        self.transient_storage.insert(index, value);
+    }
+
+    fn log(&mut self, log: Log) {
+        self.logs.push(log);
+    }
+
+    fn selfdestruct(&mut self, _address: Address, target: Address) -> Option<SelfDestructResult> {
+        Some(SelfDestructResult {
+            had_value: false,
+            target_exists: true,
+            is_cold: self.access(target),
+            previously_destroyed: false,
+        })
+    }
+}
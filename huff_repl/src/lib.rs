@@ -0,0 +1,317 @@
+#![doc = include_str!("../README.md")]
+#![warn(missing_docs)]
+#![warn(unused_extern_crates)]
+#![forbid(unsafe_code)]
+
+/// Cheatcodes Module
+pub mod cheatcodes;
+
+/// Debugger Module
+pub mod debugger;
+
+/// Host Module
+pub mod host;
+
+/// Gas Profile Module
+pub mod profile;
+
+/// Test Runner Module
+pub mod test_runner;
+
+/// Terminal UI Module
+pub mod tui;
+
+use std::{collections::BTreeMap, fmt, path::PathBuf, sync::Arc};
+
+use huff_core::{Compiler, CompilerSession};
+use huff_utils::prelude::{str_to_vec, CompilerError, EVMVersion, FileSource};
+use revm_interpreter::{
+    gas::memory_gas,
+    opcode::make_instruction_table,
+    primitives::{Address, Bytecode, Bytes, CancunSpec, Env, MergeSpec, ShanghaiSpec, B256, U256},
+    Contract, DummyHost, InstructionResult, Interpreter, InterpreterAction, SharedMemory,
+};
+
+/// The gas available to each snippet
+pub const GAS_LIMIT: u64 = 30_000_000;
+
+/// The commands of the REPL, with their description
+pub const COMMANDS: [(&str, &str); 9] = [
+    (":load <file>", "Load the definitions of a Huff file, to use in snippets"),
+    (":calldata <hex>", "Set the calldata of the snippets"),
+    (":stack", "Print the stack"),
+    (":memory", "Print the memory"),
+    (":storage", "Print the storage"),
+    (":reset", "Clear the stack, memory, storage and calldata"),
+    (":bytecode <snippet>", "Print the bytecode of a snippet, without executing it"),
+    (":help", "Print the commands"),
+    (":quit", "Exit the REPL"),
+];
+
+/// A REPL session, executing Huff snippets on revm
+///
+/// The stack, memory and storage a snippet leaves are kept for the next snippet, so a sequence
+/// of stack manipulations can be built up one line at a time. A snippet that doesn't stop or
+/// return successfully leaves them as they were.
+pub struct Repl {
+    /// The compiler generating the bytecode of the snippets
+    pub compiler: CompilerSession,
+    /// The loaded file, whose definitions the snippets can use
+    pub file: Option<Arc<FileSource>>,
+    /// The stack, bottom first
+    pub stack: Vec<U256>,
+    /// The memory
+    pub memory: Vec<u8>,
+    /// The calldata of the snippets
+    pub calldata: Vec<u8>,
+    /// The host, holding the storage
+    host: DummyHost,
+}
+
+/// The outcome of executing a snippet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Execution {
+    /// The bytecode of the snippet
+    pub bytecode: String,
+    /// How the execution ended
+    pub result: InstructionResult,
+    /// The stack after the execution, bottom first
+    pub stack: Vec<U256>,
+    /// The memory after the execution
+    pub memory: Vec<u8>,
+    /// The storage slots the execution changed, with their value before and after it
+    pub storage_diff: BTreeMap<U256, (U256, U256)>,
+    /// The gas used by the execution
+    pub gas_used: u64,
+    /// The data returned or reverted with
+    pub return_data: Vec<u8>,
+}
+
+impl Execution {
+    /// Whether the execution stopped or returned, keeping its state
+    pub fn is_success(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+impl Repl {
+    /// Creates a session with an empty stack, memory and storage
+    pub fn new(compiler: CompilerSession) -> Self {
+        Self {
+            compiler,
+            file: None,
+            stack: vec![],
+            memory: vec![],
+            calldata: vec![],
+            host: DummyHost::new(Env::default()),
+        }
+    }
+
+    /// Loads the definitions of a Huff file and its imports, replacing the loaded file
+    pub fn load(&mut self, path: &str) -> Result<(), CompilerError> {
+        let file = Compiler::fetch_sources(vec![PathBuf::from(path)])
+            .remove(0)
+            .and_then(|f| self.compiler.resolve_deps(f).map_err(|e| (*e).clone()))?;
+        // Parse the file alone first, so its errors aren't reported against a snippet
+        self.compiler.snippet("", Some(Arc::clone(&file)))?;
+        self.file = Some(file);
+        Ok(())
+    }
+
+    /// The storage, by slot
+    pub fn storage(&self) -> BTreeMap<U256, U256> {
+        self.host.storage.iter().map(|(k, v)| (*k, *v)).collect()
+    }
+
+    /// Clears the stack, memory, storage and calldata, keeping the loaded file
+    pub fn reset(&mut self) {
+        self.stack.clear();
+        self.memory.clear();
+        self.calldata.clear();
+        self.host.clear();
+    }
+
+    /// Compiles a snippet, returning its bytecode
+    pub fn compile(&self, snippet: &str) -> Result<String, CompilerError> {
+        self.compiler.snippet(snippet, self.file.clone())
+    }
+
+    /// Compiles and executes a snippet on the stack, memory and storage of the session
+    pub fn execute(&mut self, snippet: &str) -> Result<Execution, CompilerError> {
+        let bytecode = self.compile(snippet)?;
+        let code = str_to_vec(&bytecode).unwrap_or_default();
+        let contract = Contract::new(
+            Bytes::from(self.calldata.clone()),
+            Bytecode::new_raw(Bytes::from(code)),
+            B256::ZERO,
+            Address::ZERO,
+            Address::ZERO,
+            U256::ZERO,
+        );
+        let mut interpreter = Interpreter::new(Box::new(contract), GAS_LIMIT, false);
+        for item in self.stack.iter() {
+            // The stack of the session never exceeds the limit, a snippet can't grow it past it
+            let _ = interpreter.stack.push(*item);
+        }
+        let mut memory = SharedMemory::new();
+        memory.resize(self.memory.len());
+        memory.set(0, &self.memory);
+        // The memory left by previous snippets was paid for when they expanded it
+        let paid = memory_gas(self.memory.len() / 32);
+        interpreter.gas.record_memory(paid);
+
+        let storage = self.host.storage.clone();
+        let transient_storage = self.host.transient_storage.clone();
+        let action = match self.compiler.evm_version {
+            EVMVersion::Paris => {
+                interpreter.run(memory, &make_instruction_table::<_, MergeSpec>(), &mut self.host)
+            }
+            EVMVersion::Shanghai => interpreter.run(
+                memory,
+                &make_instruction_table::<_, ShanghaiSpec>(),
+                &mut self.host,
+            ),
+            EVMVersion::Cancun => {
+                interpreter.run(memory, &make_instruction_table::<_, CancunSpec>(), &mut self.host)
+            }
+        };
+        // Transient storage only lasts for the transaction of a snippet
+        self.host.transient_storage = transient_storage;
+        // Calls and creates stop the snippet, with a `CallOrCreate` result
+        let return_data = match action {
+            InterpreterAction::Return { result } => result.output.to_vec(),
+            _ => vec![],
+        };
+
+        let execution = Execution {
+            bytecode,
+            result: interpreter.instruction_result,
+            stack: interpreter.stack.data().clone(),
+            memory: interpreter.shared_memory.context_memory().to_vec(),
+            storage_diff: self
+                .host
+                .storage
+                .iter()
+                .map(|(slot, value)| {
+                    (*slot, (storage.get(slot).copied().unwrap_or_default(), *value))
+                })
+                .filter(|(_, (before, after))| before != after)
+                .collect(),
+            gas_used: interpreter.gas.spend().saturating_sub(paid),
+            return_data,
+        };
+        if execution.is_success() {
+            self.stack = execution.stack.clone();
+            self.memory = execution.memory.clone();
+        } else {
+            self.host.storage = storage;
+        }
+        Ok(execution)
+    }
+
+    /// Evaluates a line of input, either a command or a snippet, returning the output to print
+    ///
+    /// `:quit` is left to the caller, since it ends the session.
+    pub fn eval(&mut self, line: &str) -> Result<String, String> {
+        let line = line.trim();
+        let (command, argument) = match line.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line, ""),
+        };
+        match command {
+            ":load" => {
+                if argument.is_empty() {
+                    return Err("Usage: :load <file>".to_string())
+                }
+                self.load(argument).map_err(|e| e.to_string())?;
+                Ok(format!("Loaded \"{}\"", argument))
+            }
+            ":calldata" => {
+                let hex = argument.strip_prefix("0x").unwrap_or(argument);
+                if hex.len() % 2 != 0 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(format!("Invalid calldata \"{}\", expected hex bytes", argument))
+                }
+                self.calldata = str_to_vec(hex).map_err(|e| e.to_string())?;
+                Ok(format!("calldata: 0x{}", hex::encode(&self.calldata)))
+            }
+            ":stack" => Ok(render_stack(&self.stack)),
+            ":memory" => Ok(render_memory(&self.memory)),
+            ":storage" => Ok(render_storage(&self.storage())),
+            ":reset" => {
+                self.reset();
+                Ok("Cleared the stack, memory, storage and calldata".to_string())
+            }
+            ":bytecode" => {
+                self.compile(argument).map(|b| format!("0x{}", b)).map_err(|e| e.to_string())
+            }
+            ":help" => Ok(COMMANDS
+                .iter()
+                .map(|(command, description)| format!("{:<22}{}", command, description))
+                .chain(std::iter::once(
+                    "Any other input is executed as a snippet of macro statements".to_string(),
+                ))
+                .collect::<Vec<_>>()
+                .join("\n")),
+            c if c.starts_with(':') => {
+                Err(format!("Unknown command \"{}\", type :help for the commands", c))
+            }
+            _ => self.execute(line).map(|e| e.to_string()).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Execution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:?}, {} gas used", self.result, self.gas_used)?;
+        writeln!(f, "{}", render_stack(&self.stack))?;
+        if !self.memory.is_empty() {
+            writeln!(f, "{}", render_memory(&self.memory))?;
+        }
+        if !self.storage_diff.is_empty() {
+            writeln!(f, "storage:")?;
+            for (slot, (before, after)) in self.storage_diff.iter() {
+                writeln!(f, "  {:#x}: {:#x} -> {:#x}", slot, before, after)?;
+            }
+        }
+        if !self.return_data.is_empty() {
+            writeln!(f, "returned: 0x{}", hex::encode(&self.return_data))?;
+        }
+        write!(f, "bytecode: 0x{}", self.bytecode)
+    }
+}
+
+/// Renders a stack given bottom first, listing the top of the stack first
+pub fn render_stack(stack: &[U256]) -> String {
+    if stack.is_empty() {
+        return "stack: empty".to_string()
+    }
+    let mut lines = vec!["stack:".to_string()];
+    for (i, item) in stack.iter().rev().enumerate() {
+        lines.push(format!("  {}: {:#x}", i, item));
+    }
+    lines.join("\n")
+}
+
+/// Renders memory, one word per line
+pub fn render_memory(memory: &[u8]) -> String {
+    if memory.is_empty() {
+        return "memory: empty".to_string()
+    }
+    let mut lines = vec!["memory:".to_string()];
+    for (i, word) in memory.chunks(32).enumerate() {
+        lines.push(format!("  {:#06x}: {}", i * 32, hex::encode(word)));
+    }
+    lines.join("\n")
+}
+
+/// Renders storage, one slot per line
+pub fn render_storage(storage: &BTreeMap<U256, U256>) -> String {
+    if storage.is_empty() {
+        return "storage: empty".to_string()
+    }
+    let mut lines = vec!["storage:".to_string()];
+    for (slot, value) in storage.iter() {
+        lines.push(format!("  {:#x}: {:#x}", slot, value));
+    }
+    lines.join("\n")
+}
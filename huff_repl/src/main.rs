@@ -0,0 +1,68 @@
+#![doc = include_str!("../README.md")]
+#![warn(missing_docs)]
+#![warn(unused_extern_crates)]
+#![forbid(unsafe_code)]
+
+use clap::Parser as ClapParser;
+use huff_core::Compiler;
+use huff_repl::Repl;
+use huff_utils::prelude::EVMVersion;
+use std::io::{self, BufRead, Write};
+use yansi::Paint;
+
+/// The `huff-repl` CLI Args
+#[derive(ClapParser, Debug, Clone)]
+#[clap(name = "huff-repl", version, about, long_about = None)]
+struct Args {
+    /// A Huff file to load, whose definitions snippets can use.
+    #[clap(value_parser)]
+    file: Option<String>,
+
+    /// The EVM version to generate and execute bytecode for: paris, shanghai or cancun.
+    #[clap(long = "evm-version", default_value = "paris", value_parser)]
+    evm_version: EVMVersion,
+
+    /// Accept uppercase and mixed-case opcode mnemonics.
+    #[clap(long = "case-insensitive-opcodes", action)]
+    case_insensitive_opcodes: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    let mut repl = Repl::new(
+        Compiler::builder()
+            .case_insensitive_opcodes(args.case_insensitive_opcodes)
+            .evm_version(args.evm_version)
+            .build(),
+    );
+    if let Some(file) = &args.file {
+        if let Err(e) = repl.load(file) {
+            eprintln!("{}", Paint::red(format!("{}", e)));
+            std::process::exit(1);
+        }
+    }
+
+    println!("Huff REPL, executing on {}. Type :help for the commands.", args.evm_version);
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("{}", Paint::cyan("huff> "));
+        let _ = io::stdout().flush();
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(e)) => {
+                eprintln!("{}", Paint::red(format!("Failed to read input: {}", e)));
+                std::process::exit(1);
+            }
+            None => break,
+        };
+        match line.trim() {
+            "" => continue,
+            ":quit" | ":exit" => break,
+            line => match repl.eval(line) {
+                Ok(output) => println!("{}", output),
+                Err(e) => eprintln!("{}", Paint::red(e)),
+            },
+        }
+    }
+}
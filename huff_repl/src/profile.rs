@@ -0,0 +1,243 @@
+//! ## Gas Profile
+//!
+//! Attributes the gas used by a call to a contract to the macros and source lines generating
+//! the executed instructions, printed by `huffc profile`.
+//!
+//! The call is executed on revm like the debugger does, as a new transaction, so the cold and
+//! warm storage accesses, the memory expansion and the branches taken are priced as they execute.
+//! The gas of each instruction, including the memory it expands, is attributed to the chain of
+//! macro invocations it was generated through. Each macro is charged the gas of the macros it
+//! invokes, giving the breakdown of a flame graph, printed as a tree or in the folded format of
+//! flame graph tools. The intrinsic gas of the transaction isn't included.
+
+use std::collections::BTreeMap;
+
+use huff_utils::prelude::{EVMVersion, SourceMapping};
+use revm_interpreter::{primitives::U256, InstructionResult};
+
+use crate::{
+    debugger::{deploy_with_source_map, Trace},
+    host::CallHost,
+};
+
+/// The width of the bars of the breakdown, for the whole gas used
+const BAR_WIDTH: usize = 20;
+
+/// The gas profile of a call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasProfile {
+    /// How the execution ended
+    pub result: InstructionResult,
+    /// The gas used by the execution
+    pub gas_used: u64,
+    /// The number of executed instructions
+    pub instructions: usize,
+    /// The gas of `MAIN`, and of the macros it invokes
+    pub root: ProfileNode,
+    /// The gas of each source line, most expensive first
+    pub lines: Vec<LineProfile>,
+}
+
+/// The gas used by the expansions of a macro, invoked through the same chain of macros
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileNode {
+    /// The macro name
+    pub name: String,
+    /// The gas used, including the macros it invokes
+    pub gas: u64,
+    /// The gas used by the statements of the macro itself
+    pub self_gas: u64,
+    /// The number of executed instructions, including the macros it invokes
+    pub instructions: usize,
+    /// The invoked macros, most expensive first
+    pub children: Vec<ProfileNode>,
+}
+
+/// The gas used by the instructions a source line generates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineProfile {
+    /// The path of the file
+    pub path: String,
+    /// The line number
+    pub line: usize,
+    /// The trimmed code of the line
+    pub code: String,
+    /// The gas used
+    pub gas: u64,
+    /// The number of executed instructions
+    pub instructions: usize,
+}
+
+impl ProfileNode {
+    /// An invoked macro that used no gas yet
+    fn new(name: &str) -> Self {
+        Self { name: name.to_string(), gas: 0, self_gas: 0, instructions: 0, children: vec![] }
+    }
+
+    /// The node of an invoked macro, added if it's the first invocation
+    fn child(&mut self, name: &str) -> &mut ProfileNode {
+        match self.children.iter().position(|c| c.name == name) {
+            Some(i) => &mut self.children[i],
+            None => {
+                self.children.push(ProfileNode::new(name));
+                self.children.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// Sorts the invoked macros, most expensive first
+    fn sort(&mut self) {
+        self.children.sort_by(|a, b| b.gas.cmp(&a.gas).then_with(|| a.name.cmp(&b.name)));
+        self.children.iter_mut().for_each(|c| c.sort());
+    }
+}
+
+impl GasProfile {
+    /// Deploys a contract and profiles a call to it
+    ///
+    /// The source map must map the runtime bytecode the constructor deploys.
+    pub fn run(
+        initcode: &[u8],
+        source_map: &[SourceMapping],
+        calldata: &[u8],
+        value: u128,
+        evm_version: EVMVersion,
+    ) -> Result<Self, String> {
+        let mut host = CallHost::default();
+        let code = deploy_with_source_map(initcode, source_map, &mut host, evm_version)?;
+        let trace = Trace::gas_only(&code, calldata, U256::from(value), &mut host, evm_version);
+        Ok(Self::new(&trace, source_map))
+    }
+
+    /// Attributes the gas of each step of a trace to the macros and source lines generating it
+    pub fn new(trace: &Trace, source_map: &[SourceMapping]) -> Self {
+        let size = source_map.iter().map(|m| m.offset + m.size).max().unwrap_or_default();
+        let mut mappings = vec![None; size];
+        for mapping in source_map.iter() {
+            mappings[mapping.offset..mapping.offset + mapping.size].fill(Some(mapping));
+        }
+
+        let mut root = ProfileNode::new("MAIN");
+        let mut lines: BTreeMap<(String, usize), LineProfile> = BTreeMap::new();
+        for (step, gas) in trace.steps.iter().zip(trace.step_gas()) {
+            let mapping = mappings.get(step.pc).copied().flatten();
+            let mut node = &mut root;
+            node.gas += gas;
+            node.instructions += 1;
+            for invocation in mapping.iter().flat_map(|m| m.invocations.iter()) {
+                node = node.child(&invocation.macro_name);
+                node.gas += gas;
+                node.instructions += 1;
+            }
+            node.self_gas += gas;
+
+            let span = mapping.and_then(|m| m.span.0.first());
+            let Some((file, position)) =
+                span.and_then(|s| Some((s.file.as_ref()?, s.start_position()?)))
+            else {
+                continue
+            };
+            let line =
+                lines.entry((file.path.clone(), position.line)).or_insert_with(|| LineProfile {
+                    path: file.path.clone(),
+                    line: position.line,
+                    code: file
+                        .source
+                        .as_ref()
+                        .and_then(|s| s.lines().nth(position.line - 1))
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                    gas: 0,
+                    instructions: 0,
+                });
+            line.gas += gas;
+            line.instructions += 1;
+        }
+        root.sort();
+
+        let mut lines = lines.into_values().collect::<Vec<_>>();
+        lines.sort_by_key(|l| std::cmp::Reverse(l.gas));
+        Self {
+            result: trace.result,
+            gas_used: trace.gas_used,
+            instructions: trace.steps.len(),
+            root,
+            lines,
+        }
+    }
+
+    /// Renders the breakdown of the gas by macro, as a tree, then by source line
+    pub fn render(&self) -> String {
+        let mut lines = vec![
+            format!(
+                "Executed {} instructions using {} gas, ending with {:?}",
+                self.instructions, self.gas_used, self.result
+            ),
+            "The intrinsic gas of the transaction is excluded.".to_string(),
+            String::new(),
+            "Gas by macro, including the macros it invokes:".to_string(),
+            format!("{:>9} {:>9} {:>7}", "gas", "self", "%"),
+        ];
+        let mut nodes = vec![(0, &self.root)];
+        while let Some((depth, node)) = nodes.pop() {
+            lines.push(format!(
+                "{:>9} {:>9} {:>6.1}%  {:<width$}  {}{}()",
+                node.gas,
+                node.self_gas,
+                self.percent(node.gas),
+                self.bar(node.gas),
+                "  ".repeat(depth),
+                node.name,
+                width = BAR_WIDTH
+            ));
+            nodes.extend(node.children.iter().rev().map(|c| (depth + 1, c)));
+        }
+
+        lines.push(String::new());
+        lines.push("Gas by source line:".to_string());
+        lines.push(format!("{:>9} {:>9} {:>7}", "gas", "instrs", "%"));
+        for line in self.lines.iter() {
+            lines.push(format!(
+                "{:>9} {:>9} {:>6.1}%  {}:{}  {}",
+                line.gas,
+                line.instructions,
+                self.percent(line.gas),
+                line.path,
+                line.line,
+                line.code
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// Renders the gas of each macro in the folded format of flame graph tools
+    ///
+    /// Each line is the chain of macros from `MAIN`, separated by semicolons, followed by the
+    /// gas used by the statements of the last macro.
+    pub fn folded(&self) -> String {
+        let mut lines = vec![];
+        let mut nodes = vec![(self.root.name.clone(), &self.root)];
+        while let Some((stack, node)) = nodes.pop() {
+            if node.self_gas > 0 {
+                lines.push(format!("{} {}", stack, node.self_gas));
+            }
+            nodes.extend(node.children.iter().rev().map(|c| (format!("{};{}", stack, c.name), c)));
+        }
+        lines.join("\n")
+    }
+
+    /// The percentage of the gas used
+    fn percent(&self, gas: u64) -> f64 {
+        match self.gas_used {
+            0 => 0.0,
+            used => gas as f64 * 100.0 / used as f64,
+        }
+    }
+
+    /// The bar of a share of the gas used
+    fn bar(&self, gas: u64) -> String {
+        let width = (self.percent(gas) * BAR_WIDTH as f64 / 100.0).round() as usize;
+        "█".repeat(width.min(BAR_WIDTH))
+    }
+}
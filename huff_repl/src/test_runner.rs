@@ -0,0 +1,424 @@
+//! ## Test Runner
+//!
+//! Runs the tests a contract defines with `#define test`, for `huffc test`.
+//!
+//! Each test body is executed as the runtime bytecode of a contract, with fresh storage, on the
+//! host of the debugger. A test passes if its execution stops or returns, and fails if it reverts
+//! or halts exceptionally. Calls to the cheatcode address execute their
+//! [cheatcode](crate::cheatcodes), which can expect the test to revert or to emit logs. Other
+//! calls and creates aren't executed, they fail the test.
+//!
+//! Tests declaring typed parameters are fuzzed: each run executes the test with random
+//! arguments, ABI-encoded into its calldata without a selector, so the body reads the first
+//! static argument with `0x00 calldataload`. Integers favour their edge values and small
+//! values, and the lengths of bytes, strings and dynamic arrays are kept short. The arguments of
+//! every run derive from the seed of the runner, so a failure is reproduced by running again with
+//! the same seed. The arguments of a failing run are shrunk towards zero values and shorter
+//! lengths, as long as the test keeps failing, before being reported.
+
+use std::fmt;
+
+use ethers_core::{
+    abi::{self, ParamType, Token},
+    types::{Address, I256, U256},
+};
+use huff_core::CompiledTest;
+use huff_utils::prelude::{str_to_vec, EVMVersion, FunctionParamType, PanicCode};
+use rand::{distributions::Alphanumeric, rngs::StdRng, Rng, SeedableRng};
+
+use crate::{cheatcodes::Cheatcodes, debugger::Trace, host::CallHost};
+
+/// The default number of runs of a fuzzed test
+pub const DEFAULT_RUNS: usize = 256;
+
+/// The maximum number of shrunk arguments tried for a failing run
+pub const MAX_SHRINKS: usize = 1024;
+
+/// The maximum length of random bytes and strings
+const MAX_LENGTH: usize = 64;
+
+/// The maximum length of random dynamic arrays
+const MAX_ARRAY_LENGTH: usize = 4;
+
+/// The selector of the `Error(string)` revert data
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Runs compiled tests, fuzzing those with parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestRunner {
+    /// The number of runs of a fuzzed test
+    pub runs: usize,
+    /// The seed the random arguments derive from
+    pub seed: u64,
+    /// The EVM version the tests are executed on
+    pub evm_version: EVMVersion,
+}
+
+impl Default for TestRunner {
+    fn default() -> Self {
+        Self { runs: DEFAULT_RUNS, seed: 0, evm_version: EVMVersion::default() }
+    }
+}
+
+/// The result of a test
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    /// The test name
+    pub name: String,
+    /// The types of the test parameters
+    pub inputs: Vec<FunctionParamType>,
+    /// The number of runs executed, up to the failing one
+    pub runs: usize,
+    /// The mean gas used by the passing runs
+    pub mean_gas: u64,
+    /// The median gas used by the passing runs
+    pub median_gas: u64,
+    /// Why the test failed, `None` if it passed
+    pub failure: Option<TestFailure>,
+}
+
+/// The failing run of a test, after shrinking its arguments
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestFailure {
+    /// Why the run failed
+    pub reason: String,
+    /// The arguments of the run, empty for tests without parameters
+    pub args: Vec<Token>,
+    /// The calldata of the run
+    pub calldata: Vec<u8>,
+    /// The number of times the arguments were shrunk
+    pub shrinks: usize,
+}
+
+impl TestRunner {
+    /// Runs each test
+    pub fn run_all(&self, tests: &[CompiledTest]) -> Result<Vec<TestResult>, String> {
+        tests.iter().map(|t| self.run(t)).collect()
+    }
+
+    /// Runs a test, once if it has no parameters or [runs](TestRunner::runs) times otherwise
+    ///
+    /// The runs stop at the first failure, whose arguments are then shrunk.
+    pub fn run(&self, test: &CompiledTest) -> Result<TestResult, String> {
+        let code = str_to_vec(&test.bytecode)
+            .map_err(|e| format!("Invalid bytecode of test \"{}\": {}", test.name, e))?;
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let runs = if test.inputs.is_empty() { 1 } else { self.runs };
+
+        let mut gas = vec![];
+        let mut failure = None;
+        for _ in 0..runs {
+            let args = test.inputs.iter().map(|i| random_token(i, &mut rng)).collect::<Vec<_>>();
+            match self.execute(&code, &args) {
+                Ok(used) => gas.push(used),
+                Err(reason) => {
+                    failure = Some(self.shrink(&code, args, reason));
+                    break
+                }
+            }
+        }
+
+        gas.sort_unstable();
+        Ok(TestResult {
+            name: test.name.clone(),
+            inputs: test.inputs.clone(),
+            runs: gas.len() + failure.is_some() as usize,
+            mean_gas: match gas.len() {
+                0 => 0,
+                n => gas.iter().sum::<u64>() / n as u64,
+            },
+            median_gas: gas.get(gas.len() / 2).copied().unwrap_or_default(),
+            failure,
+        })
+    }
+
+    /// Executes a test with the given arguments, returning the gas used or why it failed
+    fn execute(&self, code: &[u8], args: &[Token]) -> Result<u64, String> {
+        let mut host = CallHost::default();
+        let mut cheatcodes = Cheatcodes::default();
+        let trace = Trace::with_cheatcodes(
+            code,
+            &abi::encode(args),
+            Default::default(),
+            &mut host,
+            self.evm_version,
+            &mut cheatcodes,
+        );
+        if let Some(failure) = cheatcodes.failure {
+            return Err(failure)
+        }
+        match (cheatcodes.expect_revert, trace.result.is_ok(), trace.result.is_revert()) {
+            (false, true, _) => cheatcodes.check_emits(&host.logs).map(|()| trace.gas_used),
+            (true, _, true) => Ok(trace.gas_used),
+            (true, true, _) => Err("Expected a revert".to_string()),
+            _ => Err(failure_reason(&trace)),
+        }
+    }
+
+    /// Shrinks the arguments of a failing run, one argument at a time, while the test fails
+    fn shrink(&self, code: &[u8], mut args: Vec<Token>, mut reason: String) -> TestFailure {
+        let mut shrinks = 0;
+        let mut tries = 0;
+        'shrinking: while tries < MAX_SHRINKS {
+            for i in 0..args.len() {
+                for candidate in shrink_token(&args[i]) {
+                    if tries == MAX_SHRINKS {
+                        break 'shrinking
+                    }
+                    tries += 1;
+                    let mut shrunk = args.clone();
+                    shrunk[i] = candidate;
+                    if let Err(r) = self.execute(code, &shrunk) {
+                        args = shrunk;
+                        reason = r;
+                        shrinks += 1;
+                        continue 'shrinking
+                    }
+                }
+            }
+            break
+        }
+        TestFailure { reason, calldata: abi::encode(&args), args, shrinks }
+    }
+}
+
+impl TestResult {
+    /// If the test passed
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+
+    /// The test name followed by the types of its parameters
+    pub fn signature(&self) -> String {
+        let inputs = self.inputs.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+        format!("{}({})", self.name, inputs.join(","))
+    }
+}
+
+impl fmt::Display for TestResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.failure, self.inputs.is_empty()) {
+            (None, true) => write!(f, "[PASS] {} (gas: {})", self.signature(), self.mean_gas),
+            (None, false) => write!(
+                f,
+                "[PASS] {} (runs: {}, μ: {}, ~: {})",
+                self.signature(),
+                self.runs,
+                self.mean_gas,
+                self.median_gas
+            ),
+            (Some(failure), true) => {
+                write!(f, "[FAIL. Reason: {}] {}", failure.reason, self.signature())
+            }
+            (Some(failure), false) => write!(
+                f,
+                "[FAIL. Reason: {}] {} (runs: {}, shrinks: {})\n    counterexample: args=[{}] \
+                 calldata=0x{}",
+                failure.reason,
+                self.signature(),
+                self.runs,
+                failure.shrinks,
+                failure.args.iter().map(render_token).collect::<Vec<_>>().join(", "),
+                hex::encode(&failure.calldata)
+            ),
+        }
+    }
+}
+
+/// Renders the results of tests, followed by their summary
+pub fn render_results(results: &[TestResult]) -> String {
+    let mut lines = results.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+    let failed = results.iter().filter(|r| !r.passed()).count();
+    lines.push(format!(
+        "Test result: {}. {} passed; {} failed",
+        if failed == 0 { "ok" } else { "FAILED" },
+        results.len() - failed,
+        failed
+    ));
+    lines.join("\n")
+}
+
+/// Why an execution failed, decoding the revert data of `Error(string)` and catalogued panics
+fn failure_reason(trace: &Trace) -> String {
+    let data = &trace.return_data;
+    if !trace.result.is_revert() {
+        return format!("{:?}", trace.result)
+    }
+    if let Some(panic) = PanicCode::decode(data) {
+        return format!("Revert: panic {:#x} ({})", panic.code(), panic.message())
+    }
+    if data.len() >= 4 && data[..4] == ERROR_SELECTOR {
+        if let Ok(Some(Token::String(message))) =
+            abi::decode(&[ParamType::String], &data[4..]).map(|mut t| t.pop())
+        {
+            return format!("Revert: {}", message)
+        }
+    }
+    match data.is_empty() {
+        true => "Revert".to_string(),
+        false => format!("Revert: 0x{}", hex::encode(data)),
+    }
+}
+
+/// The mask of the lowest bits of a word
+fn mask(bits: usize) -> U256 {
+    match bits {
+        256 => U256::MAX,
+        bits => (U256::one() << bits) - 1,
+    }
+}
+
+/// Sign extends the lowest bits of a word, as a two's complement integer
+fn sign_extend(value: U256, bits: usize) -> U256 {
+    match bits < 256 && value.bit(bits - 1) {
+        true => value | !mask(bits),
+        false => value & mask(bits),
+    }
+}
+
+/// Generates a random argument of a type
+///
+/// A quarter of the integers are an edge value of their type, the others a random value of a
+/// random width.
+pub fn random_token(kind: &FunctionParamType, rng: &mut StdRng) -> Token {
+    let word = |rng: &mut StdRng| U256::from_big_endian(&rng.gen::<[u8; 32]>());
+    let bytes = |rng: &mut StdRng, len: usize| (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+    match kind {
+        FunctionParamType::Uint(bits) => {
+            if rng.gen_ratio(1, 4) {
+                let edges = [U256::zero(), U256::one(), mask(*bits)];
+                return Token::Uint(edges[rng.gen_range(0..edges.len())])
+            }
+            let width = rng.gen_range(1..=*bits);
+            Token::Uint(word(rng) & mask(width))
+        }
+        FunctionParamType::Int(bits) => {
+            if rng.gen_ratio(1, 4) {
+                let min = sign_extend(U256::one() << (bits - 1), *bits);
+                let edges = [U256::zero(), U256::one(), U256::MAX, min, mask(bits - 1)];
+                return Token::Int(edges[rng.gen_range(0..edges.len())])
+            }
+            let width = rng.gen_range(1..=*bits);
+            Token::Int(sign_extend(word(rng) & mask(width), width))
+        }
+        FunctionParamType::Bool => Token::Bool(rng.gen()),
+        FunctionParamType::Address => Token::Address(Address::from(rng.gen::<[u8; 20]>())),
+        FunctionParamType::FixedBytes(len) => Token::FixedBytes(bytes(rng, *len)),
+        FunctionParamType::Bytes => {
+            let len = rng.gen_range(0..=MAX_LENGTH);
+            Token::Bytes(bytes(rng, len))
+        }
+        FunctionParamType::String => {
+            let len = rng.gen_range(0..=MAX_LENGTH);
+            Token::String((0..len).map(|_| char::from(rng.sample(Alphanumeric))).collect())
+        }
+        FunctionParamType::Array(kind, sizes) => random_array(kind, sizes, rng),
+        FunctionParamType::Tuple(kinds) => {
+            Token::Tuple(kinds.iter().map(|k| random_token(k, rng)).collect())
+        }
+    }
+}
+
+/// Generates a random array, of the outermost size last, zero for dynamic arrays
+fn random_array(kind: &FunctionParamType, sizes: &[usize], rng: &mut StdRng) -> Token {
+    let (outer, inner) = match sizes.split_last() {
+        Some(split) => split,
+        None => return random_token(kind, rng),
+    };
+    match outer {
+        0 => {
+            let len = rng.gen_range(0..=MAX_ARRAY_LENGTH);
+            Token::Array((0..len).map(|_| random_array(kind, inner, rng)).collect())
+        }
+        len => Token::FixedArray((0..*len).map(|_| random_array(kind, inner, rng)).collect()),
+    }
+}
+
+/// The simpler arguments a failing argument is shrunk to, simplest first
+///
+/// Integers shrink towards zero, halving the distance to the original value between candidates
+/// so a boundary is found in a few steps, booleans to false, addresses and fixed bytes to zero, and
+/// bytes, strings and dynamic arrays to shorter lengths. The elements of arrays and tuples are
+/// shrunk one at a time.
+pub fn shrink_token(token: &Token) -> Vec<Token> {
+    let elements = |tokens: &Vec<Token>, wrap: fn(Vec<Token>) -> Token| {
+        let mut candidates = vec![];
+        for (i, element) in tokens.iter().enumerate() {
+            for shrunk in shrink_token(element) {
+                let mut tokens = tokens.clone();
+                tokens[i] = shrunk;
+                candidates.push(wrap(tokens));
+            }
+        }
+        candidates
+    };
+    let mut candidates = match token {
+        Token::Uint(v) if !v.is_zero() => {
+            let mut candidates = vec![Token::Uint(U256::zero())];
+            let mut delta = *v >> 1;
+            while !delta.is_zero() {
+                candidates.push(Token::Uint(*v - delta));
+                delta >>= 1;
+            }
+            candidates
+        }
+        Token::Int(v) if !v.is_zero() => {
+            let v = I256::from_raw(*v);
+            let mut candidates = vec![Token::Int(U256::zero())];
+            let mut delta = v / I256::from(2);
+            while !delta.is_zero() {
+                candidates.push(Token::Int((v - delta).into_raw()));
+                delta /= I256::from(2);
+            }
+            candidates
+        }
+        Token::Bool(true) => vec![Token::Bool(false)],
+        Token::Address(a) if !a.is_zero() => vec![Token::Address(Address::zero())],
+        Token::FixedBytes(b) if b.iter().any(|b| *b != 0) => {
+            vec![Token::FixedBytes(vec![0; b.len()])]
+        }
+        Token::Bytes(b) if !b.is_empty() => vec![
+            Token::Bytes(vec![]),
+            Token::Bytes(b[..b.len() / 2].to_vec()),
+            Token::Bytes(b[..b.len() - 1].to_vec()),
+        ],
+        Token::String(s) if !s.is_empty() => {
+            let chars = s.chars().collect::<Vec<_>>();
+            vec![
+                Token::String(String::new()),
+                Token::String(chars[..chars.len() / 2].iter().collect()),
+                Token::String(chars[..chars.len() - 1].iter().collect()),
+            ]
+        }
+        Token::Array(tokens) if !tokens.is_empty() => {
+            let mut candidates = vec![
+                Token::Array(vec![]),
+                Token::Array(tokens[..tokens.len() / 2].to_vec()),
+                Token::Array(tokens[..tokens.len() - 1].to_vec()),
+            ];
+            candidates.extend(elements(tokens, Token::Array));
+            candidates
+        }
+        Token::FixedArray(tokens) => elements(tokens, Token::FixedArray),
+        Token::Tuple(tokens) => elements(tokens, Token::Tuple),
+        _ => vec![],
+    };
+    candidates.dedup();
+    candidates.retain(|c| c != token);
+    candidates
+}
+
+/// Renders an argument like Solidity literals, integers in decimal
+pub fn render_token(token: &Token) -> String {
+    let list = |tokens: &[Token]| tokens.iter().map(render_token).collect::<Vec<_>>().join(", ");
+    match token {
+        Token::Uint(v) => v.to_string(),
+        Token::Int(v) => I256::from_raw(*v).to_string(),
+        Token::Bool(b) => b.to_string(),
+        Token::Address(a) => format!("0x{}", hex::encode(a.as_bytes())),
+        Token::FixedBytes(b) | Token::Bytes(b) => format!("0x{}", hex::encode(b)),
+        Token::String(s) => format!("{:?}", s),
+        Token::Array(tokens) | Token::FixedArray(tokens) => format!("[{}]", list(tokens)),
+        Token::Tuple(tokens) => format!("({})", list(tokens)),
+    }
+}
@@ -0,0 +1,92 @@
+//! ## Terminal UI
+//!
+//! Draws a [Debugger] session in the terminal, moving through it with single key commands.
+
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue, style, terminal,
+};
+
+use crate::debugger::Debugger;
+
+/// Runs a debugging session in the terminal until it's quit
+///
+/// The terminal is switched to its alternate screen for the session, and restored after it.
+pub fn run(debugger: &mut Debugger) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    queue!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = event_loop(debugger, &mut stdout);
+    queue!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    stdout.flush()?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+/// Draws the session and handles key presses, until `q`, escape or ctrl-c
+fn event_loop(debugger: &mut Debugger, stdout: &mut io::Stdout) -> io::Result<()> {
+    // The breakpoint being typed after `b`
+    let mut prompt: Option<String> = None;
+    loop {
+        let (width, height) = terminal::size()?;
+        let mut lines = debugger.render(width as usize, height as usize);
+        if let (Some(input), Some(last)) = (&prompt, lines.last_mut()) {
+            *last = format!("break on label or macro: {}", input);
+        }
+        queue!(stdout, cursor::MoveTo(0, 0))?;
+        for (i, line) in lines.iter().enumerate() {
+            queue!(
+                stdout,
+                cursor::MoveTo(0, i as u16),
+                style::Print(line),
+                terminal::Clear(terminal::ClearType::UntilNewLine)
+            )?;
+        }
+        stdout.flush()?;
+
+        let Event::Key(KeyEvent { code, modifiers, kind: KeyEventKind::Press, .. }) =
+            event::read()?
+        else {
+            continue
+        };
+        if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('c') {
+            return Ok(())
+        }
+        if let Some(input) = prompt.as_mut() {
+            match code {
+                KeyCode::Enter => {
+                    let name = input.trim().to_string();
+                    prompt = None;
+                    debugger.message = Some(match debugger.toggle_breakpoint(&name) {
+                        Ok(true) => format!("Set a breakpoint on \"{}\"", name),
+                        Ok(false) => format!("Removed the breakpoint on \"{}\"", name),
+                        Err(e) => e,
+                    });
+                }
+                KeyCode::Esc => prompt = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            continue
+        }
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('s') | KeyCode::Right => debugger.step_by(true),
+            KeyCode::Char('S') | KeyCode::Left => debugger.step_by(false),
+            KeyCode::Char('n') | KeyCode::Down => debugger.next_line(true),
+            KeyCode::Char('N') | KeyCode::Up => debugger.next_line(false),
+            KeyCode::Char('c') => debugger.continue_to_breakpoint(true),
+            KeyCode::Char('C') => debugger.continue_to_breakpoint(false),
+            KeyCode::Char('g') | KeyCode::Home => debugger.go_to_end(false),
+            KeyCode::Char('G') | KeyCode::End => debugger.go_to_end(true),
+            KeyCode::Char('b') => prompt = Some(String::new()),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_repl::test_runner::{TestResult, TestRunner};
+use huff_utils::prelude::*;
+
+const SOURCE: &str = r#"#include <std/SafeMath.huff>
+#include <std/ERC20.huff>
+#include <std/ERC721.huff>
+
+#define macro ASSERT() = takes(1) returns(0) {
+    ok jumpi
+    0x00 0x00 revert
+    ok:
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x00 calldataload 0xe0 shr
+    ERC20_DISPATCH()
+    ERC721_DISPATCH()
+    0x00 0x00 revert
+}
+
+#define test TEST_SAFE_MATH() = {
+    0x03 0x02 SAFE_ADD() 0x05 eq ASSERT()
+    0x02 0x05 SAFE_SUB() 0x03 eq ASSERT()
+    0x03 0x04 SAFE_MUL() 0x0c eq ASSERT()
+    0x00 0x04 SAFE_MUL() iszero ASSERT()
+    0x03 0x0c SAFE_DIV() 0x04 eq ASSERT()
+    0x05 0x0c SAFE_MOD() 0x02 eq ASSERT()
+}
+
+#define test TEST_SAFE_ADD_COMMUTES(uint128 a, uint128 b) = {
+    0x20 calldataload 0x00 calldataload SAFE_ADD()
+    0x00 calldataload 0x20 calldataload SAFE_ADD()
+    eq ASSERT()
+}
+
+#define test TEST_SAFE_ADD_OVERFLOWS() = {
+    0x01 0x00 not SAFE_ADD()
+}
+
+#define test TEST_SAFE_SUB_UNDERFLOWS() = {
+    0x02 0x01 SAFE_SUB()
+}
+
+#define test TEST_SAFE_MUL_OVERFLOWS() = {
+    0x02 0x00 not SAFE_MUL()
+}
+
+#define test TEST_SAFE_DIV_BY_ZERO() = {
+    0x00 0x01 SAFE_DIV()
+}
+
+#define test TEST_ERC20_MINT_BURN() = {
+    0x64 0xbeef ERC20_MINT()
+    0x28 0xbeef ERC20_BURN()
+    [ERC20_TOTAL_SUPPLY_SLOT] sload 0x3c eq ASSERT()
+    0xbeef ERC20_SLOT_OF([ERC20_BALANCE_SLOT]) sload 0x3c eq ASSERT()
+}
+
+#define test TEST_ERC20_BURN_ABOVE_BALANCE() = {
+    0x64 0xbeef ERC20_MINT()
+    0x65 0xbeef ERC20_BURN()
+}
+
+#define test TEST_ERC721_MINT_BURN() = {
+    0x01 0xbeef ERC721_MINT()
+    0x02 0xbeef ERC721_MINT()
+    0x01 ERC721_SLOT_OF([ERC721_OWNER_SLOT]) sload 0xbeef eq ASSERT()
+    0xbeef ERC721_SLOT_OF([ERC721_BALANCE_SLOT]) sload 0x02 eq ASSERT()
+    0x01 ERC721_BURN()
+    0x01 ERC721_SLOT_OF([ERC721_OWNER_SLOT]) sload iszero ASSERT()
+    0xbeef ERC721_SLOT_OF([ERC721_BALANCE_SLOT]) sload 0x01 eq ASSERT()
+}
+
+#define test TEST_ERC721_MINT_TWICE() = {
+    0x01 0xbeef ERC721_MINT()
+    0x01 0xcafe ERC721_MINT()
+}
+"#;
+
+fn run_tests() -> Vec<TestResult> {
+    let file = Arc::new(FileSource {
+        path: "Tests.huff".to_string(),
+        source: Some(SOURCE.to_string()),
+        ..Default::default()
+    });
+    let file = Compiler::recurse_deps(file).unwrap();
+    let tests = Compiler::default().tests(file).unwrap();
+    TestRunner { runs: 64, seed: 42, ..Default::default() }.run_all(&tests).unwrap()
+}
+
+#[test]
+fn test_bundled_modules_execute() {
+    let results = run_tests();
+    let reason = |name: &str| {
+        let result = results.iter().find(|r| r.name == name).unwrap();
+        result.failure.as_ref().map(|f| f.reason.clone())
+    };
+
+    for name in
+        ["TEST_SAFE_MATH", "TEST_SAFE_ADD_COMMUTES", "TEST_ERC20_MINT_BURN", "TEST_ERC721_MINT_BURN"]
+    {
+        assert_eq!(reason(name), None, "{} failed", name);
+    }
+
+    // Overflows revert with the panics of checked arithmetic in solc
+    let panic = |code: &str| format!("Revert: 0x4e487b71{:0>64}", code);
+    assert_eq!(reason("TEST_SAFE_ADD_OVERFLOWS"), Some(panic("11")));
+    assert_eq!(reason("TEST_SAFE_SUB_UNDERFLOWS"), Some(panic("11")));
+    assert_eq!(reason("TEST_SAFE_MUL_OVERFLOWS"), Some(panic("11")));
+    assert_eq!(reason("TEST_SAFE_DIV_BY_ZERO"), Some(panic("12")));
+
+    // Moving more tokens than owned reverts
+    assert_eq!(reason("TEST_ERC20_BURN_ABOVE_BALANCE").unwrap(), "Revert");
+    assert_eq!(reason("TEST_ERC721_MINT_TWICE").unwrap(), "Revert");
+}
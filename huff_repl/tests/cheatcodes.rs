@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_repl::test_runner::{TestResult, TestRunner};
+use huff_utils::prelude::*;
+
+const SOURCE: &str = r#"#define constant OWNER = 0xbeef
+
+#define macro ONLY_OWNER() = takes(0) returns(0) {
+    caller [OWNER] eq authorized jumpi
+    0x00 0x00 revert
+    authorized:
+}
+
+#define macro EMIT_VALUE() = takes(1) returns(0) {
+    0x00 mstore
+    0xdd 0x20 0x00 log1
+}
+
+#define test TEST_PRANK() = {
+    [OWNER] __PRANK()
+    ONLY_OWNER()
+}
+
+#define test TEST_EXPECT_REVERT() = {
+    __EXPECT_REVERT()
+    ONLY_OWNER()
+}
+
+#define test TEST_UNEXPECTED_SUCCESS() = {
+    [OWNER] __PRANK()
+    __EXPECT_REVERT()
+    ONLY_OWNER()
+}
+
+#define test TEST_DEAL(uint256 amount) = {
+    0x00 calldataload [OWNER] __DEAL()
+    [OWNER] balance 0x00 calldataload eq ok jumpi
+    0x00 0x00 revert
+    ok:
+}
+
+#define test TEST_WARP_ROLL() = {
+    0x2a 0x00 mstore
+    0x64 __WARP()
+    0x07 __ROLL()
+    timestamp 0x64 eq number 0x07 eq and
+    0x00 mload 0x2a eq and ok jumpi
+    0x00 0x00 revert
+    ok:
+}
+
+#define test TEST_EXPECT_EMIT() = {
+    __EXPECT_EMIT()
+    0x05 0x00 mstore 0xdd 0x20 0x00 log1
+    0x05 EMIT_VALUE()
+}
+
+#define test TEST_EMIT_MISMATCH() = {
+    __EXPECT_EMIT()
+    0x05 0x00 mstore 0xdd 0x20 0x00 log1
+    0x06 EMIT_VALUE()
+}
+
+#define test TEST_EMIT_MISSING() = {
+    __EXPECT_EMIT()
+    0x05 0x00 mstore 0xdd 0x20 0x00 log1
+}
+
+#define test TEST_UNKNOWN_CHEATCODE() = {
+    0xdeadbeef 0xe0 shl 0x00 mstore
+    0x00 0x00 0x04 0x00 0x00 0x7109709ecfa91a80626ff3989d68f67f5b1dd12d gas call
+}
+"#;
+
+fn run_tests() -> Vec<TestResult> {
+    let file = Arc::new(FileSource { source: Some(SOURCE.to_string()), ..Default::default() });
+    let tests = Compiler::default().tests(file).unwrap();
+    TestRunner { runs: 16, seed: 7, ..Default::default() }.run_all(&tests).unwrap()
+}
+
+fn reason(result: &TestResult) -> Option<&str> {
+    result.failure.as_ref().map(|f| f.reason.as_str())
+}
+
+#[test]
+fn test_state_cheatcodes() {
+    let results = run_tests();
+
+    // `prank` changes the caller, `deal` a balance, and `warp` and `roll` the block, keeping the
+    // memory of the test
+    assert_eq!(reason(&results[0]), None);
+    assert_eq!(reason(&results[3]), None);
+    assert_eq!(results[3].runs, 16);
+    assert_eq!(reason(&results[4]), None);
+}
+
+#[test]
+fn test_expectation_cheatcodes() {
+    let results = run_tests();
+    assert_eq!(reason(&results[1]), None);
+    assert_eq!(reason(&results[2]), Some("Expected a revert"));
+    assert_eq!(reason(&results[5]), None);
+    assert_eq!(reason(&results[6]), Some("Log 1 doesn't match the expected log"));
+    assert_eq!(reason(&results[7]), Some("Expected a log to be emitted"));
+    assert_eq!(reason(&results[8]), Some("Unknown cheatcode 0xdeadbeef"));
+}
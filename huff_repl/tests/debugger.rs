@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_repl::{
+    debugger::{deploy, Debugger},
+    host::CallHost,
+};
+use huff_utils::prelude::*;
+use revm_interpreter::{primitives::U256, InstructionResult};
+
+const SOURCE: &str = r#"#define macro CONSTRUCTOR() = takes(0) returns(0) {
+    0x2a 0x01 sstore
+}
+
+#define macro STORE_VALUE() = takes(1) returns(0) {
+    0x00 sstore
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x04 calldataload
+    STORE_VALUE()
+    0x00 calldataload 0xe0 shr
+    0x01 eq done jumpi
+    0x00 0x00 revert
+    done:
+        stop
+}
+"#;
+
+fn launch(calldata: &str) -> Debugger {
+    let file = Arc::new(FileSource {
+        path: "Debug.huff".to_string(),
+        source: Some(SOURCE.to_string()),
+        ..Default::default()
+    });
+    let compiler = Compiler::default();
+    let artifact = compiler.gen_artifact(Arc::clone(&file)).unwrap();
+    let source_map = compiler.runtime_source_map(file).unwrap();
+    let initcode = str_to_vec(&artifact.bytecode).unwrap();
+    let calldata = str_to_vec(calldata).unwrap();
+    Debugger::launch(&initcode, source_map, &calldata, 0, EVMVersion::default()).unwrap()
+}
+
+#[test]
+fn test_trace_call() {
+    let debugger = launch(&format!("00000001{:064x}", 7));
+    let trace = &debugger.trace;
+    assert_eq!(trace.result, InstructionResult::Stop);
+    assert!(!trace.truncated);
+
+    // The storage written by the constructor is deployed with the contract
+    let first = &trace.steps[0];
+    assert_eq!(first.pc, 0);
+    assert_eq!(first.storage.get(&U256::from(1)), Some(&U256::from(0x2a)));
+    assert!(first.stack.is_empty());
+    let last = trace.steps.last().unwrap();
+    assert_eq!(last.storage.get(&U256::ZERO), Some(&U256::from(7)));
+    assert_eq!(debugger.source_line(trace.steps.len() - 1), Some(("Debug.huff".to_string(), 16)));
+}
+
+#[test]
+fn test_breakpoints() {
+    let mut debugger = launch(&format!("00000001{:064x}", 7));
+    assert!(debugger.toggle_breakpoint("STORE_VALUE").unwrap());
+    assert!(debugger.toggle_breakpoint("done").unwrap());
+    assert!(debugger.toggle_breakpoint("MISSING").is_err());
+
+    // Macros break when their expansion is entered
+    debugger.continue_to_breakpoint(true);
+    assert_eq!(debugger.message, Some("Hit breakpoint \"STORE_VALUE\"".to_string()));
+    assert_eq!(debugger.source_line(debugger.position), Some(("Debug.huff".to_string(), 6)));
+    assert_eq!(debugger.step().unwrap().stack, vec![U256::from(7)]);
+    let mapping = debugger.mapping(debugger.position).unwrap();
+    assert_eq!(mapping.invocations[0].macro_name, "STORE_VALUE");
+
+    // Labels break at their jump destination
+    debugger.continue_to_breakpoint(true);
+    assert_eq!(debugger.message, Some("Hit breakpoint \"done\"".to_string()));
+    assert_eq!(debugger.step().unwrap().opcode, 0x5b);
+
+    debugger.continue_to_breakpoint(true);
+    assert_eq!(debugger.message, Some("Reached the end of the execution: Stop".to_string()));
+    assert_eq!(debugger.position, debugger.trace.steps.len() - 1);
+
+    // Continuing backwards finds the breakpoints in reverse
+    debugger.continue_to_breakpoint(false);
+    assert_eq!(debugger.message, Some("Hit breakpoint \"done\"".to_string()));
+    assert!(!debugger.toggle_breakpoint("done").unwrap());
+    debugger.continue_to_breakpoint(false);
+    assert_eq!(debugger.message, Some("Hit breakpoint \"STORE_VALUE\"".to_string()));
+}
+
+#[test]
+fn test_stepping() {
+    let mut debugger = launch("00000002");
+    assert_eq!(debugger.trace.result, InstructionResult::Revert);
+
+    debugger.step_by(true);
+    assert_eq!(debugger.position, 1);
+    debugger.step_by(false);
+    debugger.step_by(false);
+    assert_eq!(debugger.position, 0);
+    assert_eq!(debugger.message, Some("Reached the start of the execution".to_string()));
+
+    // Stepping by line skips the instructions of the current line
+    debugger.next_line(true);
+    assert_eq!(debugger.source_line(debugger.position), Some(("Debug.huff".to_string(), 6)));
+    assert_eq!(debugger.position, 2);
+    debugger.next_line(true);
+    assert_eq!(debugger.source_line(debugger.position), Some(("Debug.huff".to_string(), 12)));
+    debugger.next_line(false);
+    assert_eq!(debugger.source_line(debugger.position), Some(("Debug.huff".to_string(), 6)));
+
+    debugger.go_to_end(true);
+    assert_eq!(debugger.step().unwrap().opcode, 0xfd);
+}
+
+#[test]
+fn test_render() {
+    let mut debugger = launch(&format!("00000001{:064x}", 7));
+    debugger.toggle_breakpoint("STORE_VALUE").unwrap();
+    debugger.continue_to_breakpoint(true);
+    debugger.step_by(true);
+
+    let screen = debugger.render(100, 40);
+    assert_eq!(screen.len(), 40);
+    assert!(screen.iter().all(|l| l.chars().count() <= 100));
+    assert!(screen[0].starts_with("step 4/"), "{}", screen[0]);
+    assert!(screen[1].starts_with("── Debug.huff:6  MAIN() > STORE_VALUE() ──"), "{}", screen[1]);
+    assert!(screen.contains(&">    6 |     0x00 sstore".to_string()), "{:#?}", screen);
+    assert!(screen.contains(&"  0x0005  sstore".to_string()), "{:#?}", screen);
+    assert!(screen.contains(&"  0: 0x0".to_string()) && screen.contains(&"  1: 0x7".to_string()));
+    assert!(screen.contains(&"  0x1: 0x2a".to_string()), "{:#?}", screen);
+    assert_eq!(screen[39], "breakpoints: STORE_VALUE");
+
+    // Sections that don't fit are cut, keeping the footer
+    let screen = debugger.render(100, 5);
+    assert_eq!(screen.len(), 5);
+    assert_eq!(screen[4], "breakpoints: STORE_VALUE");
+}
+
+#[test]
+fn test_deploy_immutables() {
+    let source = r#"#define immutable OWNER
+#define immutable SCALE
+
+#define macro CONSTRUCTOR() = takes(0) returns(0) {
+    0x01 __SET_IMMUTABLE(OWNER)
+    0x03 __SET_IMMUTABLE(SCALE)
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    [OWNER] [SCALE] add 0x00 mstore
+    0x20 0x00 return
+}
+"#;
+    let file = Arc::new(FileSource {
+        path: "Immutables.huff".to_string(),
+        source: Some(source.to_string()),
+        ..Default::default()
+    });
+    let artifact = Compiler::default().gen_artifact(file).unwrap();
+    let initcode = str_to_vec(&artifact.bytecode).unwrap();
+    let code = deploy(&initcode, &mut CallHost::default(), EVMVersion::default()).unwrap();
+
+    // The deployed runtime bytecode has the values the constructor set
+    let mut expected = str_to_vec(&artifact.runtime).unwrap();
+    expected[32] = 0x01;
+    expected[65] = 0x03;
+    assert_eq!(code, expected);
+}
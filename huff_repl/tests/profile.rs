@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_repl::profile::GasProfile;
+use huff_utils::prelude::*;
+use revm_interpreter::InstructionResult;
+
+const SOURCE: &str = r#"#define macro CONSTRUCTOR() = takes(0) returns(0) {
+    0x01 0x01 sstore
+}
+
+#define macro STORE() = takes(1) returns(0) {
+    0x00 sstore
+}
+
+#define macro LOAD_TWICE() = takes(0) returns(0) {
+    0x01 sload pop
+    0x01 sload pop
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    0x04 calldataload
+    STORE()
+    LOAD_TWICE()
+    LOAD_TWICE()
+    0x00 0x00 return
+}
+"#;
+
+fn profile_call(calldata: &str) -> GasProfile {
+    let file = Arc::new(FileSource {
+        path: "Profile.huff".to_string(),
+        source: Some(SOURCE.to_string()),
+        ..Default::default()
+    });
+    let compiler = Compiler::default();
+    let artifact = compiler.gen_artifact(Arc::clone(&file)).unwrap();
+    let source_map = compiler.runtime_source_map(file).unwrap();
+    let initcode = str_to_vec(&artifact.bytecode).unwrap();
+    let calldata = str_to_vec(calldata).unwrap();
+    GasProfile::run(&initcode, &source_map, &calldata, 0, EVMVersion::default()).unwrap()
+}
+
+#[test]
+fn test_profile_by_macro() {
+    let profile = profile_call(&format!("00000000{:064x}", 7));
+    assert_eq!(profile.result, InstructionResult::Return);
+    assert_eq!(profile.root.name, "MAIN");
+    assert_eq!(profile.root.gas, profile.gas_used);
+
+    // Storing a non zero value in a fresh slot costs a cold access and a set
+    let store = &profile.root.children[0];
+    assert_eq!(store.name, "STORE");
+    assert_eq!(store.gas, 3 + 22100);
+
+    // Both invocations of LOAD_TWICE are merged, and only the first load is cold
+    let load = &profile.root.children[1];
+    assert_eq!(load.name, "LOAD_TWICE");
+    assert_eq!(load.instructions, 12);
+    assert_eq!(load.gas, 4 * (3 + 2) + 2100 + 3 * 100);
+    assert_eq!(load.self_gas, load.gas);
+    assert_eq!(profile.root.self_gas, profile.gas_used - store.gas - load.gas);
+
+    // Storing zero in a zero slot only costs the cold access and a no-op
+    let profile = profile_call("00000000");
+    assert_eq!(profile.root.children[0].name, "LOAD_TWICE");
+    assert_eq!(profile.root.children[1].gas, 3 + 2200);
+}
+
+#[test]
+fn test_profile_by_line() {
+    let profile = profile_call(&format!("00000000{:064x}", 7));
+    let first = &profile.lines[0];
+    assert_eq!((first.path.as_str(), first.line), ("Profile.huff", 6));
+    assert_eq!(first.code, "0x00 sstore");
+    assert_eq!(first.instructions, 2);
+
+    // The first line of LOAD_TWICE has the cold load of both invocations
+    let line = |n: usize| profile.lines.iter().find(|l| l.line == n).unwrap();
+    assert_eq!(line(10).gas, 2 * 5 + 2100 + 100);
+    assert_eq!(line(11).gas, 2 * 5 + 2 * 100);
+    assert_eq!(line(10).instructions, 6);
+    assert!(profile.lines.windows(2).all(|w| w[0].gas >= w[1].gas));
+}
+
+#[test]
+fn test_render_profile() {
+    let profile = profile_call(&format!("00000000{:064x}", 7));
+    let rendered = profile.render();
+    let lines = rendered.lines().collect::<Vec<_>>();
+    assert!(lines[0].starts_with("Executed 19 instructions using "), "{}", lines[0]);
+    assert!(lines[5].ends_with("  MAIN()"), "{}", rendered);
+    assert!(lines[5].contains("100.0%  ████████████████████  MAIN()"), "{}", rendered);
+    assert!(lines[6].ends_with("    STORE()"), "{}", rendered);
+    assert!(lines[7].ends_with("    LOAD_TWICE()"), "{}", rendered);
+    assert!(rendered.contains("Profile.huff:6  0x00 sstore"), "{}", rendered);
+
+    assert_eq!(
+        profile.folded(),
+        format!("MAIN {}\nMAIN;STORE 22103\nMAIN;LOAD_TWICE 2420", profile.root.self_gas)
+    );
+}
@@ -0,0 +1,122 @@
+use huff_core::Compiler;
+use huff_repl::Repl;
+use huff_utils::prelude::EVMVersion;
+use revm_interpreter::{primitives::U256, InstructionResult};
+
+#[test]
+fn test_executes_snippets() {
+    let mut repl = Repl::new(Compiler::builder().build());
+
+    let execution = repl.execute("0x01 0x02 add").unwrap();
+    assert_eq!(execution.result, InstructionResult::Stop);
+    assert_eq!(execution.bytecode, "6001600201");
+    assert_eq!(execution.stack, vec![U256::from(3)]);
+    assert_eq!(execution.gas_used, 9);
+
+    // The stack is kept for the next snippet
+    let execution = repl.execute("dup1 mul").unwrap();
+    assert_eq!(execution.stack, vec![U256::from(9)]);
+    assert_eq!(repl.stack, vec![U256::from(9)]);
+}
+
+#[test]
+fn test_memory_and_storage() {
+    let mut repl = Repl::new(Compiler::builder().build());
+
+    let execution = repl.execute("0x2a 0x00 mstore").unwrap();
+    // push, push, mstore and the expansion to one word
+    assert_eq!(execution.gas_used, 3 + 3 + 3 + 3);
+    assert_eq!(repl.memory.len(), 32);
+    assert_eq!(repl.memory[31], 0x2a);
+
+    // The memory expanded by a previous snippet isn't charged again
+    let execution = repl.execute("0x2b 0x00 mstore").unwrap();
+    assert_eq!(execution.gas_used, 3 + 3 + 3);
+    assert_eq!(repl.memory[31], 0x2b);
+
+    let execution = repl.execute("0x00 mload 0x01 sstore").unwrap();
+    assert_eq!(
+        execution.storage_diff.into_iter().collect::<Vec<_>>(),
+        vec![(U256::from(1), (U256::ZERO, U256::from(0x2b)))]
+    );
+    assert_eq!(repl.storage().get(&U256::from(1)), Some(&U256::from(0x2b)));
+
+    // Storing the same value again doesn't change the slot
+    let execution = repl.execute("0x2b 0x01 sstore").unwrap();
+    assert!(execution.storage_diff.is_empty());
+}
+
+#[test]
+fn test_failures_keep_the_state() {
+    let mut repl = Repl::new(Compiler::builder().build());
+    repl.execute("0x01 0x00 sstore 0x05").unwrap();
+
+    let execution = repl.execute("0x02 0x00 sstore 0x06 0x20 0x00 revert").unwrap();
+    assert_eq!(execution.result, InstructionResult::Revert);
+    assert!(!execution.is_success());
+    assert_eq!(execution.return_data, vec![0; 32]);
+    assert_eq!(repl.stack, vec![U256::from(5)]);
+    assert_eq!(repl.storage().get(&U256::ZERO), Some(&U256::from(1)));
+
+    let execution = repl.execute("pop pop").unwrap();
+    assert_eq!(execution.result, InstructionResult::StackUnderflow);
+    assert_eq!(repl.stack, vec![U256::from(5)]);
+
+    // Snippets that don't compile don't execute
+    assert!(repl.execute("0x01 undefined_label jump").is_err());
+    assert_eq!(repl.stack, vec![U256::from(5)]);
+}
+
+#[test]
+fn test_loaded_definitions() {
+    let source = r#"
+        #define constant VALUE = 0x10
+
+        #define macro DOUBLE() = takes(1) returns(1) {
+            dup1 add
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            [VALUE] DOUBLE()
+        }
+    "#;
+    let path = std::env::temp_dir().join(format!("huff_repl_{}.huff", std::process::id()));
+    std::fs::write(&path, source).unwrap();
+
+    let mut repl = Repl::new(Compiler::builder().build());
+    let loaded = repl.load(&path.to_string_lossy());
+    std::fs::remove_file(&path).unwrap();
+    loaded.unwrap();
+
+    let execution = repl.execute("[VALUE] DOUBLE()").unwrap();
+    assert_eq!(execution.stack, vec![U256::from(0x20)]);
+    let execution = repl.execute("MAIN()").unwrap();
+    assert_eq!(execution.stack, vec![U256::from(0x20), U256::from(0x20)]);
+
+    assert!(repl.load("./does_not_exist.huff").is_err());
+    assert!(repl.file.is_some());
+}
+
+#[test]
+fn test_commands() {
+    let mut repl = Repl::new(Compiler::builder().evm_version(EVMVersion::Cancun).build());
+
+    assert_eq!(repl.eval(":calldata 0xdeadbeef").unwrap(), "calldata: 0xdeadbeef");
+    assert!(repl.eval(":calldata 0xdea").is_err());
+    let output = repl.eval("0x00 calldataload 0xe0 shr").unwrap();
+    assert!(output.contains("  0: 0xdeadbeef"), "{}", output);
+
+    // Transient storage doesn't outlive the snippet
+    repl.eval("0x01 0x00 tstore").unwrap();
+    repl.eval("0x00 tload").unwrap();
+    assert_eq!(repl.stack, vec![U256::from(0xdeadbeefu64), U256::ZERO]);
+
+    assert_eq!(repl.eval(":stack").unwrap(), "stack:\n  0: 0x0\n  1: 0xdeadbeef");
+    assert_eq!(repl.eval(":bytecode 0x01 0x02").unwrap(), "0x60016002");
+    assert!(repl.eval(":unknown").is_err());
+
+    repl.eval(":reset").unwrap();
+    assert!(repl.stack.is_empty() && repl.calldata.is_empty());
+    assert_eq!(repl.eval(":stack").unwrap(), "stack: empty");
+    assert_eq!(repl.eval(":storage").unwrap(), "storage: empty");
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use huff_core::Compiler;
+use huff_repl::test_runner::{TestResult, TestRunner};
+use huff_utils::prelude::*;
+
+const SOURCE: &str = r#"#define macro ASSERT() = takes(1) returns(0) {
+    ok jumpi
+    0x00 0x00 revert
+    ok:
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    stop
+}
+
+#define test TEST_MSTORE_STRING() = {
+    0x80 __MSTORE_STRING("hello") 0x60 eq ASSERT()
+    0x80 mload 0x20 eq ASSERT()
+    0xa0 mload 0x05 eq ASSERT()
+    0xc0 mload 0x68656c6c6f 0xd8 shl eq ASSERT()
+}
+
+#define test TEST_MSTORE_LONG_STRING() = {
+    0x00 __MSTORE_STRING("a string longer than a word of 32 bytes") 0x80 eq ASSERT()
+    0x20 mload 0x27 eq ASSERT()
+}
+
+#define test TEST_REVERT_STRING() = {
+    __REVERT_STRING("insufficient \"balance\"")
+}
+
+#define test TEST_REVERT_LONG_STRING() = {
+    __REVERT_STRING("a revert reason longer than a word of 32 bytes")
+}
+"#;
+
+fn run_tests() -> Vec<TestResult> {
+    let file = Arc::new(FileSource {
+        path: "Tests.huff".to_string(),
+        source: Some(SOURCE.to_string()),
+        ..Default::default()
+    });
+    let tests = Compiler::default().tests(file).unwrap();
+    TestRunner::default().run_all(&tests).unwrap()
+}
+
+#[test]
+fn test_string_builtins_execute() {
+    let results = run_tests();
+    let reason = |name: &str| {
+        let result = results.iter().find(|r| r.name == name).unwrap();
+        result.failure.as_ref().map(|f| f.reason.clone())
+    };
+
+    // Strings are stored abi encoded, leaving the size stored
+    assert_eq!(reason("TEST_MSTORE_STRING"), None);
+    assert_eq!(reason("TEST_MSTORE_LONG_STRING"), None);
+
+    // Reverts decode as `Error(string)`, with escapes resolved
+    assert_eq!(reason("TEST_REVERT_STRING").unwrap(), "Revert: insufficient \"balance\"");
+    assert_eq!(
+        reason("TEST_REVERT_LONG_STRING").unwrap(),
+        "Revert: a revert reason longer than a word of 32 bytes"
+    );
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use ethers_core::{abi::Token, types::U256};
+use huff_core::Compiler;
+use huff_repl::test_runner::{render_results, shrink_token, TestResult, TestRunner};
+use huff_utils::prelude::*;
+
+const SOURCE: &str = r#"#define macro ADD() = takes(2) returns(1) {
+    add
+}
+
+#define macro ASSERT() = takes(1) returns(0) {
+    ok jumpi
+    0x00 0x00 revert
+    ok:
+}
+
+#define macro MAIN() = takes(0) returns(0) {
+    stop
+}
+
+#define test TEST_ADD() = {
+    0x01 0x02 ADD() 0x03 eq ASSERT()
+}
+
+#define test TEST_ADD_COMMUTES(uint256 a, uint256 b) = {
+    0x00 calldataload 0x20 calldataload ADD()
+    0x20 calldataload 0x00 calldataload ADD()
+    eq ASSERT()
+}
+
+#define test TEST_SMALL(uint256 a, bool flag) = {
+    0x00 calldataload 0x3e8 gt ASSERT()
+}
+
+#define test TEST_MESSAGE() = {
+    0x08c379a0 0xe0 shl 0x00 mstore
+    0x20 0x04 mstore
+    0x04 0x24 mstore
+    0x6661696c 0xe0 shl 0x44 mstore
+    0x64 0x00 revert
+}
+"#;
+
+fn run_tests(runs: usize) -> Vec<TestResult> {
+    let file = Arc::new(FileSource {
+        path: "Tests.huff".to_string(),
+        source: Some(SOURCE.to_string()),
+        ..Default::default()
+    });
+    let tests = Compiler::default().tests(file).unwrap();
+    TestRunner { runs, seed: 42, ..Default::default() }.run_all(&tests).unwrap()
+}
+
+#[test]
+fn test_compiles_tests() {
+    let file = Arc::new(FileSource { source: Some(SOURCE.to_string()), ..Default::default() });
+    let tests = Compiler::default().tests(file).unwrap();
+    assert_eq!(
+        tests.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+        vec!["TEST_ADD", "TEST_ADD_COMMUTES", "TEST_SMALL", "TEST_MESSAGE"]
+    );
+    assert_eq!(tests[1].inputs, vec![FunctionParamType::Uint(256), FunctionParamType::Uint(256)]);
+    assert_eq!(tests[0].bytecode, "60016002016003146100115760006000fd5b");
+}
+
+#[test]
+fn test_runs_tests() {
+    let results = run_tests(64);
+
+    // Tests without parameters run once
+    assert!(results[0].passed());
+    assert_eq!(results[0].runs, 1);
+    assert_eq!(results[0].to_string(), format!("[PASS] TEST_ADD() (gas: {})", results[0].mean_gas));
+
+    // Tests with parameters run with random arguments
+    assert!(results[1].passed());
+    assert_eq!(results[1].runs, 64);
+    assert!(results[1]
+        .to_string()
+        .starts_with("[PASS] TEST_ADD_COMMUTES(uint256,uint256) (runs: 64"));
+
+    // The runs are reproducible from the seed
+    assert!(run_tests(64) == results);
+}
+
+#[test]
+fn test_shrinks_failures() {
+    let results = run_tests(64);
+    let failure = results[2].failure.as_ref().unwrap();
+    assert_eq!(failure.reason, "Revert");
+    assert_eq!(failure.args, vec![Token::Uint(U256::from(1000)), Token::Bool(false)]);
+    assert!(failure.shrinks > 0);
+    assert!(results[2].to_string().ends_with(&format!(
+        "counterexample: args=[1000, false] calldata=0x{:064x}{:064x}",
+        1000, 0
+    )));
+
+    let failure = results[3].failure.as_ref().unwrap();
+    assert_eq!(failure.reason, "Revert: fail");
+    assert_eq!(results[3].to_string(), "[FAIL. Reason: Revert: fail] TEST_MESSAGE()");
+
+    assert!(render_results(&results).ends_with("\nTest result: FAILED. 2 passed; 2 failed"));
+}
+
+#[test]
+fn test_shrink_token() {
+    // Integers halve their distance to the original value, to find boundaries quickly
+    let uint = |v: u64| Token::Uint(U256::from(v));
+    assert_eq!(
+        shrink_token(&uint(100)),
+        vec![uint(0), uint(50), uint(75), uint(88), uint(94), uint(97), uint(99)]
+    );
+    assert!(shrink_token(&Token::Bool(false)).is_empty());
+    assert_eq!(shrink_token(&Token::Bool(true)), vec![Token::Bool(false)]);
+    assert_eq!(shrink_token(&Token::Bytes(vec![1, 2, 3]))[0], Token::Bytes(vec![]));
+}
@@ -14,20 +14,35 @@
 //! // Generate a default contract for demonstrative purposes.
 //! // Realistically, contract generation would be done as shown in [huff_parser](./huff_parser)
 //! let contract = Contract {
-//!     macros: vec![],
+//!     macros: Arena::new(),
 //!     invocations: vec![],
 //!     imports: vec![],
-//!     constants: vec![],
-//!     functions: vec![huff_utils::ast::Function {
-//!         name: "CONSTRUCTOR".to_string(),
+//!     constants: Arena::new(),
+//!     immutables: Arena::new(),
+//!     aliases: Arena::new(),
+//!     functions: Arena::from(vec![huff_utils::ast::Function {
+//!         name: "CONSTRUCTOR".into(),
 //!         signature: [0u8, 0u8, 0u8, 0u8],
 //!         inputs: vec![],
 //!         fn_type: FunctionType::NonPayable,
 //!         outputs: vec![],
 //!         span: AstSpan(vec![]),
-//!     }],
-//!     events: vec![],
-//!     tables: vec![],
+//!     }]),
+//!     events: Arena::new(),
+//!     errors: Arena::new(),
+//!     tables: Arena::new(),
+//!     assertions: vec![],
+//!     fallback: None,
+//!     receive: None,
+//!     tests: vec![],
+//!     natspec: Default::default(),
+//!     artifacts: vec![],
+//!     storage_layout: Default::default(),
+//!     max_expansion_depth: None,
+//!     panics: Default::default(),
+//!     flags: Default::default(),
+//!     repeat_counts: Default::default(),
+//!     namespaces: vec![],
 //! };
 //!
 //! // Create an ABI using that generate contract
@@ -36,9 +51,13 @@
 //! ```
 
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use serde_json::json;
+use std::{collections::BTreeMap, fmt};
 
-use crate::ast::{self, FunctionType};
+use crate::{
+    ast::{self, FunctionType},
+    intern::SymbolId,
+};
 
 /// #### Abi
 ///
@@ -51,6 +70,9 @@ pub struct Abi {
     pub functions: BTreeMap<String, Function>,
     /// A list of events and their definitions
     pub events: BTreeMap<String, Event>,
+    /// A list of custom errors and their definitions
+    #[serde(default)]
+    pub errors: BTreeMap<String, AbiError>,
     /// If the contract defines receive logic
     pub receive: bool,
     /// If the contract defines fallback logic
@@ -62,6 +84,88 @@ impl Abi {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// The hex selectors of the functions, by signature, like the `methodIdentifiers` of solc
+    pub fn method_identifiers(&self) -> BTreeMap<String, String> {
+        self.functions.values().map(|f| (f.signature(), hex::encode(f.selector()))).collect()
+    }
+
+    /// The Solidity JSON ABI, the list of entries consumed by ethers, web3 and Hardhat
+    pub fn json_abi(&self) -> Vec<serde_json::Value> {
+        let params = |params: &[FunctionParam]| {
+            params.iter().map(|p| json_param(&p.name, &p.kind)).collect::<Vec<_>>()
+        };
+        let mut entries = vec![];
+        if let Some(constructor) = &self.constructor {
+            entries.push(json!({
+                "type": "constructor",
+                "inputs": params(&constructor.inputs),
+                "stateMutability": if constructor.payable { "payable" } else { "nonpayable" },
+            }));
+        }
+        entries.extend(self.functions.values().map(|f| {
+            json!({
+                "type": "function",
+                "name": f.name,
+                "inputs": params(&f.inputs),
+                "outputs": params(&f.outputs),
+                "stateMutability": f.state_mutability.to_string(),
+            })
+        }));
+        entries.extend(self.events.values().map(|e| {
+            let inputs = e.inputs.iter().map(|i| {
+                let mut param = json_param(&i.name, &i.kind);
+                param["indexed"] = json!(i.indexed);
+                param
+            });
+            json!({
+                "type": "event",
+                "name": e.name,
+                "inputs": inputs.collect::<Vec<_>>(),
+                "anonymous": e.anonymous,
+            })
+        }));
+        entries.extend(self.errors.values().map(|e| {
+            json!({ "type": "error", "name": e.name, "inputs": params(&e.inputs) })
+        }));
+        // Huff doesn't check the call value unless the contract does, so both accept ether
+        if self.receive {
+            entries.push(json!({ "type": "receive", "stateMutability": "payable" }));
+        }
+        if self.fallback {
+            entries.push(json!({ "type": "fallback", "stateMutability": "payable" }));
+        }
+        entries
+    }
+}
+
+/// A parameter of the Solidity JSON ABI, tuples listing their unnamed components
+fn json_param(name: &str, kind: &FunctionParamType) -> serde_json::Value {
+    let (tuple, sizes) = match kind {
+        FunctionParamType::Tuple(kinds) => (Some(kinds), vec![]),
+        FunctionParamType::Array(inner, sizes) => match inner.as_ref() {
+            FunctionParamType::Tuple(kinds) => (Some(kinds), sizes.clone()),
+            _ => (None, vec![]),
+        },
+        _ => (None, vec![]),
+    };
+    match tuple {
+        Some(kinds) => {
+            let suffix: String = sizes
+                .iter()
+                .map(|size| match size {
+                    0 => "[]".to_string(),
+                    size => format!("[{}]", size),
+                })
+                .collect();
+            json!({
+                "name": name,
+                "type": format!("tuple{}", suffix),
+                "components": kinds.iter().map(|k| json_param("", k)).collect::<Vec<_>>(),
+            })
+        }
+        None => json!({ "name": name, "type": kind.to_string() }),
+    }
 }
 
 // Allows for simple ABI Generation by directly translating the AST
@@ -73,7 +177,9 @@ impl From<ast::Contract> for Abi {
             .filter(|m| m.name == "CONSTRUCTOR")
             .cloned()
             .collect::<Vec<ast::MacroDefinition>>();
-        let constructor: Option<&ast::MacroDefinition> = constructors.get(0);
+        let constructor: Option<&ast::MacroDefinition> = constructors.first();
+        // The constructor's signature may be declared as the `CONSTRUCTOR` function
+        let constructor_function = contract.functions.iter().find(|f| f.name == "CONSTRUCTOR");
 
         // Instantiate functions and events
         let mut functions = BTreeMap::new();
@@ -89,7 +195,7 @@ impl From<ast::Contract> for Abi {
                 (
                     function.name.to_string(),
                     Function {
-                        name: function.name.to_string(),
+                        name: function.name,
                         inputs: function
                             .inputs
                             .iter()
@@ -132,7 +238,7 @@ impl From<ast::Contract> for Abi {
                             .map(|argument| EventParam {
                                 name: argument.name.clone().unwrap_or_default(),
                                 kind: argument.arg_type.clone().unwrap_or_default().into(),
-                                indexed: false, // TODO: This is not present in `argument`
+                                indexed: argument.indexed,
                             })
                             .collect(),
                         anonymous: false,
@@ -143,8 +249,38 @@ impl From<ast::Contract> for Abi {
                 let _ = events.insert(val.0, val.1);
             });
 
-        Self {
-            constructor: constructor.map(|c| Constructor {
+        // Translate contract errors
+        let errors = contract
+            .errors
+            .iter()
+            .map(|error| {
+                let inputs = error
+                    .parameters
+                    .iter()
+                    .map(|argument| FunctionParam {
+                        name: argument.name.clone().unwrap_or_default(),
+                        kind: argument.arg_type.clone().unwrap_or_default().into(),
+                        internal_type: None,
+                    })
+                    .collect();
+                (error.name.to_string(), AbiError { name: error.name.to_string(), inputs })
+            })
+            .collect();
+
+        let constructor = match (constructor_function, constructor) {
+            (Some(f), _) => Some(Constructor {
+                inputs: f
+                    .inputs
+                    .iter()
+                    .map(|argument| FunctionParam {
+                        name: argument.name.clone().unwrap_or_default(),
+                        kind: argument.arg_type.clone().unwrap_or_default().into(),
+                        internal_type: None,
+                    })
+                    .collect(),
+                payable: f.fn_type == FunctionType::Payable,
+            }),
+            (None, Some(c)) => Some(Constructor {
                 inputs: c
                     .parameters
                     .iter()
@@ -154,11 +290,18 @@ impl From<ast::Contract> for Abi {
                         internal_type: None,
                     })
                     .collect(),
+                payable: false,
             }),
+            (None, None) => None,
+        };
+
+        Self {
+            constructor,
             functions,
             events,
-            receive: false,
-            fallback: false,
+            errors,
+            receive: contract.receive.is_some(),
+            fallback: contract.fallback.is_some(),
         }
     }
 }
@@ -169,7 +312,7 @@ impl From<ast::Contract> for Abi {
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct Function {
     /// The function name
-    pub name: String,
+    pub name: SymbolId,
     /// The function inputs
     pub inputs: Vec<FunctionParam>,
     /// The function outputs
@@ -180,6 +323,20 @@ pub struct Function {
     pub state_mutability: FunctionType,
 }
 
+impl Function {
+    /// The canonical signature of the function, eg. `transfer(address,uint256)`
+    pub fn signature(&self) -> String {
+        let inputs = self.inputs.iter().map(|i| i.kind.to_string()).collect::<Vec<String>>();
+        format!("{}({})", self.name, inputs.join(","))
+    }
+
+    /// The selector of the function, the first 4 bytes of the keccak256 hash of its signature
+    pub fn selector(&self) -> [u8; 4] {
+        let hash = ethers_core::utils::keccak256(self.signature());
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+}
+
 /// #### Event
 ///
 /// An Event definition.
@@ -193,6 +350,45 @@ pub struct Event {
     pub anonymous: bool,
 }
 
+impl Event {
+    /// The canonical signature of the event, eg. `Transfer(address,address,uint256)`
+    pub fn signature(&self) -> String {
+        let inputs = self.inputs.iter().map(|i| i.kind.to_string()).collect::<Vec<String>>();
+        format!("{}({})", self.name, inputs.join(","))
+    }
+
+    /// The topic logged for the event, the keccak256 hash of its signature
+    pub fn topic(&self) -> [u8; 32] {
+        ethers_core::utils::keccak256(self.signature())
+    }
+}
+
+/// #### AbiError
+///
+/// A custom error definition.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct AbiError {
+    /// The error name
+    pub name: String,
+    /// The error inputs
+    pub inputs: Vec<FunctionParam>,
+}
+
+impl AbiError {
+    /// The canonical signature of the error, eg. `InsufficientBalance(uint256,uint256)`
+    pub fn signature(&self) -> String {
+        let inputs = self.inputs.iter().map(|i| i.kind.to_string()).collect::<Vec<String>>();
+        format!("{}({})", self.name, inputs.join(","))
+    }
+
+    /// The selector the error reverts with, the first 4 bytes of the keccak256 hash of its
+    /// signature
+    pub fn selector(&self) -> [u8; 4] {
+        let hash = ethers_core::utils::keccak256(self.signature());
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+}
+
 /// #### EventParam
 ///
 /// Event parameters.
@@ -213,6 +409,9 @@ pub struct EventParam {
 pub struct Constructor {
     /// Contstructor inputs
     pub inputs: Vec<FunctionParam>,
+    /// If the constructor accepts ether
+    #[serde(default)]
+    pub payable: bool,
 }
 
 /// #### FunctionParam
@@ -257,6 +456,9 @@ impl FunctionParamType {
     /// Convert string to type
     pub fn convert_string_to_type(string: &str) -> Result<Self, Box<dyn std::error::Error>> {
         let input = string.to_string().to_lowercase();
+        if input.starts_with('(') {
+            return FunctionParamType::convert_string_to_tuple(&input)
+        }
         let split_input: Vec<&str> = input.split('[').collect();
         if split_input.len() > 1 {
             let mut cleaned: Vec<String> = split_input
@@ -310,6 +512,93 @@ impl FunctionParamType {
             Err(format!("Failed to create FunctionParamType from string: {}", string))?
         }
     }
+
+    /// If the type is encoded after the head of an abi encoding, at the offset in its head
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            FunctionParamType::Bytes | FunctionParamType::String => true,
+            FunctionParamType::Array(kind, sizes) => sizes.contains(&0) || kind.is_dynamic(),
+            FunctionParamType::Tuple(kinds) => kinds.iter().any(|k| k.is_dynamic()),
+            _ => false,
+        }
+    }
+
+    /// The size of the type in the head of an abi encoding, the offset of dynamic types
+    pub fn head_size(&self) -> usize {
+        match self {
+            kind if kind.is_dynamic() => 32,
+            FunctionParamType::Array(kind, sizes) => {
+                sizes.iter().product::<usize>() * kind.head_size()
+            }
+            FunctionParamType::Tuple(kinds) => kinds.iter().map(|k| k.head_size()).sum(),
+            _ => 32,
+        }
+    }
+
+    /// Convert a tuple, or an array of tuples, to a type ; (address,uint256)[] =>
+    /// Array(Tuple([Address, Uint(256)]), [0])
+    fn convert_string_to_tuple(input: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        // Split the components at the commas outside of nested tuples
+        let mut components = vec![];
+        let mut depth = 0;
+        let mut start = 1;
+        let mut close = None;
+        for (i, c) in input.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 1 => {
+                    components.push(FunctionParamType::convert_string_to_type(&input[start..i])?);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+            if depth == 0 {
+                close = Some(i);
+                break
+            }
+        }
+        let close = close.ok_or_else(|| format!("Unclosed tuple type: {}", input))?;
+        components.push(FunctionParamType::convert_string_to_type(&input[start..close])?);
+        let tuple = Self::Tuple(components);
+
+        let suffix = &input[close + 1..];
+        if suffix.is_empty() {
+            return Ok(tuple)
+        }
+        let sizes = suffix
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split("][")
+            .map(|size| if size.is_empty() { Ok(0) } else { size.parse::<usize>() })
+            .collect::<Result<Vec<usize>, _>>()?;
+        Ok(Self::Array(Box::new(tuple), sizes))
+    }
+}
+
+impl fmt::Display for FunctionParamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FunctionParamType::Address => write!(f, "address"),
+            FunctionParamType::Bytes => write!(f, "bytes"),
+            FunctionParamType::Int(size) => write!(f, "int{}", size),
+            FunctionParamType::Uint(size) => write!(f, "uint{}", size),
+            FunctionParamType::Bool => write!(f, "bool"),
+            FunctionParamType::String => write!(f, "string"),
+            FunctionParamType::Array(kind, sizes) => {
+                write!(f, "{}", kind)?;
+                sizes.iter().try_for_each(|size| match size {
+                    0 => write!(f, "[]"),
+                    size => write!(f, "[{}]", size),
+                })
+            }
+            FunctionParamType::FixedBytes(size) => write!(f, "bytes{}", size),
+            FunctionParamType::Tuple(kinds) => {
+                let kinds = kinds.iter().map(|k| k.to_string()).collect::<Vec<String>>();
+                write!(f, "({})", kinds.join(","))
+            }
+        }
+    }
 }
 
 impl From<&str> for FunctionParamType {
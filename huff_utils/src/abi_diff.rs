@@ -0,0 +1,263 @@
+//! ## ABI Diff
+//!
+//! The differences between two ABIs, reported by `huffc abi-diff` for release notes and to
+//! detect breaking changes.
+//!
+//! Functions and events are matched by name. Any change to an item existing in both ABIs, like a
+//! changed selector, outputs or state mutability, breaks its callers or indexers. Only added
+//! items are backwards compatible.
+
+use std::fmt;
+
+use crate::abi::{Abi, Event, Function, FunctionParam};
+
+/// The kind of an ABI item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AbiItemKind {
+    /// The constructor
+    Constructor,
+    /// A function
+    Function,
+    /// An event
+    Event,
+    /// The receive entrypoint
+    Receive,
+    /// The fallback entrypoint
+    Fallback,
+}
+
+impl fmt::Display for AbiItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbiItemKind::Constructor => write!(f, "constructor"),
+            AbiItemKind::Function => write!(f, "function"),
+            AbiItemKind::Event => write!(f, "event"),
+            AbiItemKind::Receive => write!(f, "receive"),
+            AbiItemKind::Fallback => write!(f, "fallback"),
+        }
+    }
+}
+
+/// How an ABI item changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ChangeKind {
+    /// Only the new ABI defines the item
+    Added,
+    /// Only the old ABI defines the item
+    Removed,
+    /// Both ABIs define the item, differently
+    Changed,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::Added => write!(f, "Added"),
+            ChangeKind::Removed => write!(f, "Removed"),
+            ChangeKind::Changed => write!(f, "Changed"),
+        }
+    }
+}
+
+/// A change to an ABI item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiChange {
+    /// The kind of item
+    pub item: AbiItemKind,
+    /// The item name, its signature if added or removed
+    pub name: String,
+    /// How the item changed
+    pub kind: ChangeKind,
+    /// The details of the change, eg. `selector 0xa9059cbb -> 0x23b872dd`
+    pub details: Vec<String>,
+    /// If the change breaks the users of the old ABI
+    pub breaking: bool,
+}
+
+impl AbiChange {
+    fn new(item: AbiItemKind, name: String, kind: ChangeKind, details: Vec<String>) -> Self {
+        Self { item, name, kind, details, breaking: kind != ChangeKind::Added }
+    }
+}
+
+impl fmt::Display for AbiChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.item)?;
+        if !self.name.is_empty() {
+            write!(f, " {}", self.name)?;
+        }
+        if !self.details.is_empty() {
+            write!(f, ": {}", self.details.join(", "))?;
+        }
+        if self.breaking {
+            write!(f, " (breaking)")?;
+        }
+        Ok(())
+    }
+}
+
+/// The changes between two ABIs
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AbiDiff {
+    /// The changes, ordered by item kind and name
+    pub changes: Vec<AbiChange>,
+}
+
+impl AbiDiff {
+    /// Diffs an old ABI against a new one
+    pub fn new(old: &Abi, new: &Abi) -> Self {
+        let mut changes = vec![];
+
+        let old_inputs = old.constructor.as_ref().map(|c| params(&c.inputs));
+        let new_inputs = new.constructor.as_ref().map(|c| params(&c.inputs));
+        match (old_inputs, new_inputs) {
+            (None, Some(n)) => {
+                changes.push(AbiChange::new(AbiItemKind::Constructor, n, ChangeKind::Added, vec![]))
+            }
+            (Some(o), None) => changes.push(AbiChange::new(
+                AbiItemKind::Constructor,
+                o,
+                ChangeKind::Removed,
+                vec![],
+            )),
+            (Some(o), Some(n)) if o != n => changes.push(AbiChange::new(
+                AbiItemKind::Constructor,
+                String::new(),
+                ChangeKind::Changed,
+                vec![format!("inputs {} -> {}", o, n)],
+            )),
+            _ => {}
+        }
+
+        for (name, function) in old.functions.iter() {
+            match new.functions.get(name) {
+                Some(n) => {
+                    let details = function_details(function, n);
+                    if !details.is_empty() {
+                        changes.push(AbiChange::new(
+                            AbiItemKind::Function,
+                            name.clone(),
+                            ChangeKind::Changed,
+                            details,
+                        ));
+                    }
+                }
+                None => changes.push(function_change(function, ChangeKind::Removed)),
+            }
+        }
+        new.functions
+            .iter()
+            .filter(|(name, _)| !old.functions.contains_key(*name))
+            .for_each(|(_, f)| changes.push(function_change(f, ChangeKind::Added)));
+
+        for (name, event) in old.events.iter() {
+            match new.events.get(name) {
+                Some(n) => {
+                    let details = event_details(event, n);
+                    if !details.is_empty() {
+                        changes.push(AbiChange::new(
+                            AbiItemKind::Event,
+                            name.clone(),
+                            ChangeKind::Changed,
+                            details,
+                        ));
+                    }
+                }
+                None => changes.push(event_change(event, ChangeKind::Removed)),
+            }
+        }
+        new.events
+            .iter()
+            .filter(|(name, _)| !old.events.contains_key(*name))
+            .for_each(|(_, e)| changes.push(event_change(e, ChangeKind::Added)));
+
+        for (item, old_defined, new_defined) in [
+            (AbiItemKind::Receive, old.receive, new.receive),
+            (AbiItemKind::Fallback, old.fallback, new.fallback),
+        ] {
+            let kind = match (old_defined, new_defined) {
+                (false, true) => ChangeKind::Added,
+                (true, false) => ChangeKind::Removed,
+                _ => continue,
+            };
+            changes.push(AbiChange::new(item, String::new(), kind, vec![]));
+        }
+
+        changes.sort_by(|a, b| a.item.cmp(&b.item).then_with(|| a.name.cmp(&b.name)));
+        Self { changes }
+    }
+
+    /// If any change breaks the users of the old ABI
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(|c| c.breaking)
+    }
+}
+
+impl fmt::Display for AbiDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return write!(f, "No ABI changes")
+        }
+        let lines = self.changes.iter().map(|c| c.to_string()).collect::<Vec<String>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Formats a list of parameters by type, eg. `(address,uint256)`
+fn params(params: &[FunctionParam]) -> String {
+    format!("({})", params.iter().map(|p| p.kind.to_string()).collect::<Vec<String>>().join(","))
+}
+
+/// The change adding or removing a function
+fn function_change(function: &Function, kind: ChangeKind) -> AbiChange {
+    let details = vec![format!("selector 0x{}", hex::encode(function.selector()))];
+    AbiChange::new(AbiItemKind::Function, function.signature(), kind, details)
+}
+
+/// The changes between two definitions of a function
+fn function_details(old: &Function, new: &Function) -> Vec<String> {
+    let mut details = vec![];
+    if old.signature() != new.signature() {
+        details.push(format!("signature {} -> {}", old.signature(), new.signature()));
+        details.push(format!(
+            "selector 0x{} -> 0x{}",
+            hex::encode(old.selector()),
+            hex::encode(new.selector())
+        ));
+    }
+    if params(&old.outputs) != params(&new.outputs) {
+        details.push(format!("outputs {} -> {}", params(&old.outputs), params(&new.outputs)));
+    }
+    if old.state_mutability != new.state_mutability {
+        details
+            .push(format!("state mutability {} -> {}", old.state_mutability, new.state_mutability));
+    }
+    details
+}
+
+/// The change adding or removing an event
+fn event_change(event: &Event, kind: ChangeKind) -> AbiChange {
+    let details = vec![format!("topic 0x{}", hex::encode(event.topic()))];
+    AbiChange::new(AbiItemKind::Event, event.signature(), kind, details)
+}
+
+/// The changes between two definitions of an event
+fn event_details(old: &Event, new: &Event) -> Vec<String> {
+    let mut details = vec![];
+    if old.signature() != new.signature() {
+        details.push(format!("signature {} -> {}", old.signature(), new.signature()));
+        details.push(format!(
+            "topic 0x{} -> 0x{}",
+            hex::encode(old.topic()),
+            hex::encode(new.topic())
+        ));
+    }
+    let indexed = |e: &Event| e.inputs.iter().map(|i| i.indexed).collect::<Vec<bool>>();
+    if old.signature() == new.signature() && indexed(old) != indexed(new) {
+        details.push("indexed parameters".to_string());
+    }
+    if old.anonymous != new.anonymous {
+        details.push(format!("anonymous {} -> {}", old.anonymous, new.anonymous));
+    }
+    details
+}
@@ -0,0 +1,1093 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+use crate::{
+    arena::Arena,
+    ast::{
+        to_screaming_snake_case, AliasDefinition, AstSpan, BuiltinFunctionCall,
+        BuiltinFunctionKind, ConstVal, Contract, Expression, Label, Literal, MacroArg,
+        MacroDefinition, Statement, StatementType,
+    },
+    diagnostic::did_you_mean,
+    error::{CompilerWarning, Fix, WarningKind},
+    evm::Opcode,
+    files::{FileSource, Span},
+};
+
+/// The number of stack items DUP and SWAP instructions can reach
+const MAX_STACK_ACCESS: usize = 16;
+
+/// Runs every contract analysis, returning the warnings in a deterministic order.
+pub fn analyze_contract(contract: &Contract) -> Vec<CompilerWarning> {
+    let mut warnings = analyze_macro_parameters(contract);
+    warnings.extend(analyze_unused_definitions(contract));
+    warnings.extend(analyze_shadowed_labels(contract));
+    warnings.extend(analyze_unreachable_code(contract));
+    warnings.extend(analyze_jump_targets(contract));
+    warnings.extend(analyze_magic_selectors(contract));
+    warnings.extend(analyze_stack_heights(contract));
+    warnings.extend(analyze_inlined_aliases(contract));
+    warnings
+}
+
+/// Analyzes macro parameter usage across all macros of a contract.
+///
+/// Reports parameters that are declared but never referenced in the macro body, and arg calls
+/// that reference a parameter the macro doesn't declare. Arg calls that resolve to a constant or
+/// an opcode are not reported, since codegen accepts those.
+pub fn analyze_macro_parameters(contract: &Contract) -> Vec<CompilerWarning> {
+    contract.macros.iter().flat_map(|m| analyze_macro_definition(m, contract)).collect()
+}
+
+/// Analyzes the parameter usage of a single macro definition.
+pub fn analyze_macro_definition(
+    macro_def: &MacroDefinition,
+    contract: &Contract,
+) -> Vec<CompilerWarning> {
+    let mut arg_calls: Vec<(&str, &AstSpan)> = vec![];
+    collect_arg_calls(&macro_def.statements, &mut arg_calls);
+    let referenced = arg_calls.iter().map(|(name, _)| *name).collect::<BTreeSet<&str>>();
+
+    let mut warnings = vec![];
+    for param in &macro_def.parameters {
+        if let Some(name) = &param.name {
+            if !referenced.contains(name.as_str()) {
+                tracing::warn!(target: "analysis", "PARAMETER \"{}\" OF MACRO \"{}\" IS NEVER REFERENCED", name, macro_def.name);
+                warnings.push(CompilerWarning::new(
+                    WarningKind::UnusedMacroParameter(macro_def.name.to_string(), name.clone()),
+                    first_token(&param.span),
+                ));
+            }
+        }
+    }
+
+    for (name, span) in arg_calls {
+        let declared = macro_def.parameters.iter().any(|p| p.name.as_deref() == Some(name));
+        let is_constant = contract.find_constant_value(name.into()).is_some();
+        if !declared && !is_constant && Opcode::from_str(name).is_err() {
+            tracing::warn!(target: "analysis", "ARG CALL \"{}\" IN MACRO \"{}\" IS NOT A DECLARED PARAMETER", name, macro_def.name);
+            let mut warning = CompilerWarning::new(
+                WarningKind::UndeclaredArgCall(macro_def.name.to_string(), name.to_string()),
+                span.clone(),
+            );
+            let params = macro_def.parameters.iter().filter_map(|p| p.name.as_deref());
+            let constants = contract.constants.iter().map(|c| c.name.as_str());
+            warning.help = did_you_mean(name, params.chain(constants));
+            warnings.push(warning);
+        }
+    }
+
+    warnings
+}
+
+/// Analyzes definitions that are never reached from the entrypoints, or never referenced.
+///
+/// Macros are reached from the `MAIN` and `CONSTRUCTOR` entrypoints and the tests, through the
+/// macros they invoke or pass to a builtin function, so macros only used by unreached macros are
+/// reported too. Files defining no entrypoint, like libraries, are reached from every macro. A
+/// table is used if a reached macro passes it to a builtin function, and a constant if a reached
+/// macro pushes it, passes it to a macro or references it by an arg call. Pushes and builtins of
+/// the `__ASSERT`s in reached macros and at the top level count as well, the macros top-level
+/// assertions size being reached. A function is used if a reached macro pushes its selector, as a
+/// literal or a constant. The constants tested by `__IFDEF` and `__IFNDEF` blocks or counting the
+/// repetitions of `__REPEAT` blocks are used, and the constants and tables of imported artifacts
+/// and the constants defined when compiling are not reported.
+///
+/// The labels of reached macros are used if any macro or table jumps to them or passes them to a
+/// macro, and the labels generated when wiring the fallback and receive definitions into `MAIN`
+/// are skipped. An alias is used if a macro body expands it.
+///
+/// Only the definitions of the compiled file are reported, since the definitions of the files it
+/// includes, like a library, are reported when compiling those files.
+pub fn analyze_unused_definitions(contract: &Contract) -> Vec<CompilerWarning> {
+    let mut reached: BTreeSet<&str> = BTreeSet::new();
+    let mut referenced: BTreeSet<&str> = BTreeSet::new();
+    let mut used_constants: BTreeSet<&str> =
+        contract.flags.iter().chain(contract.repeat_counts.iter()).map(String::as_str).collect();
+    let mut pushed: BTreeSet<Literal> = BTreeSet::new();
+    let entrypoints = contract
+        .macros
+        .iter()
+        .filter(|m| ["MAIN", "CONSTRUCTOR"].contains(&&*m.name))
+        .chain(contract.tests.iter());
+    let mut pending = match entrypoints.clone().count() {
+        0 => contract.macros.iter().collect::<Vec<_>>(),
+        _ => entrypoints.collect(),
+    };
+    // Top-level assertions are checked on every compile, reaching the macros they size
+    let mut asserted = BTreeSet::new();
+    for a in contract.assertions.iter() {
+        collect_expression_references(&a.expression, &mut asserted, &mut used_constants);
+    }
+    pending.extend(contract.macros.iter().filter(|d| asserted.contains(d.name.as_str())));
+    referenced.extend(asserted);
+    while let Some(m) = pending.pop() {
+        if !reached.insert(&m.name) {
+            continue
+        }
+        let mut invoked = BTreeSet::new();
+        collect_references(&m.statements, &mut invoked, &mut used_constants);
+        collect_pushed_literals(&m.statements, &mut pushed);
+        pending.extend(contract.macros.iter().filter(|d| invoked.contains(d.name.as_str())));
+        referenced.extend(invoked);
+    }
+    pushed.extend(contract.constants.iter().filter_map(|c| match &c.value {
+        ConstVal::Literal(l) if used_constants.contains(c.name.as_str()) => Some(*l),
+        _ => None,
+    }));
+
+    let mut used_aliases: BTreeSet<&str> = BTreeSet::new();
+    let mut used_labels: BTreeSet<&str> = BTreeSet::new();
+    for m in contract.macros.iter().chain(contract.tests.iter()) {
+        collect_alias_uses(&m.statements, &contract.aliases, &mut used_aliases);
+        collect_label_references(&m.statements, &mut used_labels);
+    }
+    for t in &contract.tables {
+        collect_label_references(&t.statements, &mut used_labels);
+    }
+
+    let imported = contract.artifacts.iter().map(|a| &a.span).collect::<Vec<_>>();
+    let generated = contract
+        .fallback
+        .iter()
+        .chain(contract.receive.iter())
+        .map(|m| &m.span)
+        .collect::<Vec<_>>();
+    let included = included_files(contract);
+    let included = |span: &AstSpan| {
+        span.0.first().and_then(|s| s.file.as_ref()).is_some_and(|f| included.contains(&f.path))
+    };
+    let mut warnings = vec![];
+    let unreached = contract.macros.iter().filter(|m| !reached.contains(m.name.as_str()));
+    for m in unreached.filter(|m| !included(&m.span)) {
+        tracing::warn!(target: "analysis", "MACRO \"{}\" IS NEVER USED", m.name);
+        warnings.push(CompilerWarning::new(
+            WarningKind::UnusedMacro(m.name.to_string()),
+            name_token(&m.span, m.internal),
+        ));
+    }
+    for c in contract.constants.iter() {
+        let defined = c.span.0.is_empty();
+        let external = imported.contains(&&c.span) || included(&c.span) || defined;
+        if !used_constants.contains(c.name.as_str()) && !external {
+            tracing::warn!(target: "analysis", "CONSTANT \"{}\" IS NEVER USED", c.name);
+            warnings.push(CompilerWarning::new(
+                WarningKind::UnusedConstant(c.name.to_string()),
+                name_token(&c.span, c.internal),
+            ));
+        }
+    }
+    let aliases = contract.aliases.iter().filter(|a| !included(&a.span));
+    for a in aliases.filter(|a| !used_aliases.contains(a.name.as_str())) {
+        tracing::warn!(target: "analysis", "ALIAS \"{}\" IS NEVER USED", a.name);
+        warnings.push(CompilerWarning::new(
+            WarningKind::UnusedAlias(a.name.clone()),
+            name_token(&a.span, false),
+        ));
+    }
+    for t in contract.tables.iter() {
+        let external = imported.contains(&&t.span) || included(&t.span);
+        if !referenced.contains(t.name.as_str()) && !external {
+            tracing::warn!(target: "analysis", "TABLE \"{}\" IS NEVER USED", t.name);
+            warnings.push(CompilerWarning::new(
+                WarningKind::UnusedTable(t.name.to_string()),
+                name_token(&t.span, false),
+            ));
+        }
+    }
+    // The `CONSTRUCTOR` function declares the constructor's signature, it isn't dispatched
+    for f in contract.functions.iter().filter(|f| f.name != "CONSTRUCTOR" && !included(&f.span)) {
+        let selector =
+            pushed.iter().any(|l| l[..28].iter().all(|b| *b == 0) && l[28..] == f.signature);
+        if !selector {
+            tracing::warn!(target: "analysis", "FUNCTION \"{}\" IS NEVER USED", f.name);
+            warnings.push(CompilerWarning::new(
+                WarningKind::UnusedFunction(f.name.to_string()),
+                name_token(&f.span, false),
+            ));
+        }
+    }
+    let reached_macros = contract.macros.iter().filter(|m| reached.contains(m.name.as_str()));
+    for m in reached_macros.filter(|m| !included(&m.span)) {
+        let mut labels = vec![];
+        collect_label_definitions(&m.statements, &mut labels);
+        for label in labels {
+            if !used_labels.contains(label.name.as_str()) && !generated.contains(&&label.span) {
+                tracing::warn!(target: "analysis", "LABEL \"{}\" IN MACRO \"{}\" IS NEVER JUMPED TO", label.name, m.name);
+                warnings.push(CompilerWarning::new(
+                    WarningKind::UnusedLabel(m.name.to_string(), label.name.to_string()),
+                    first_token(&label.span),
+                ));
+            }
+        }
+    }
+    warnings
+}
+
+/// Analyzes labels that are defined more than once in the same macro, where the later
+/// definition shadows the earlier one.
+pub fn analyze_shadowed_labels(contract: &Contract) -> Vec<CompilerWarning> {
+    let mut warnings = vec![];
+    for m in &contract.macros {
+        let mut defined: BTreeSet<&str> = BTreeSet::new();
+        for statement in &m.statements {
+            if let StatementType::Label(label) = &statement.ty {
+                if !defined.insert(&label.name) {
+                    tracing::warn!(target: "analysis", "LABEL \"{}\" IS DEFINED MORE THAN ONCE IN MACRO \"{}\"", label.name, m.name);
+                    warnings.push(CompilerWarning::new(
+                        WarningKind::ShadowedLabel(m.name.to_string(), label.name.to_string()),
+                        first_token(&label.span),
+                    ));
+                }
+            }
+        }
+    }
+    warnings
+}
+
+/// Analyzes statements following a terminating opcode, like `revert` or `jump`, that no label
+/// makes reachable.
+///
+/// The statements after a terminating opcode are unreachable up to the next label. Scanning stops
+/// at a macro invocation, since the invoked macro may define labels. The statements generated
+/// when wiring the fallback and receive definitions into `MAIN` are skipped. Unreachable opcodes,
+/// literals and label calls are fixed by removing them.
+pub fn analyze_unreachable_code(contract: &Contract) -> Vec<CompilerWarning> {
+    let generated = contract
+        .fallback
+        .iter()
+        .chain(contract.receive.iter())
+        .map(|m| &m.span)
+        .collect::<Vec<_>>();
+    let mut warnings = vec![];
+    for m in &contract.macros {
+        collect_unreachable_code(m, &m.statements, &generated, &mut warnings);
+    }
+    warnings
+}
+
+/// Analyzes label calls to a label no macro of the contract defines, and DUP and SWAP
+/// instructions deeper than the EVM supports, which are parsed as label calls.
+pub fn analyze_jump_targets(contract: &Contract) -> Vec<CompilerWarning> {
+    let mut labels: BTreeSet<&str> = BTreeSet::new();
+    let mut label_calls: Vec<(&str, &str, &AstSpan)> = vec![];
+    for m in &contract.macros {
+        collect_labels(&m.statements, &mut labels);
+        collect_label_calls(&m.name, &m.statements, &mut label_calls);
+    }
+
+    let mut warnings = vec![];
+    for (m, label, span) in label_calls {
+        if labels.contains(label) {
+            continue
+        }
+        if is_stack_too_deep(label) {
+            tracing::warn!(target: "analysis", "\"{}\" IN MACRO \"{}\" IS TOO DEEP", label, m);
+            warnings.push(CompilerWarning::new(
+                WarningKind::StackTooDeep(m.to_string(), label.to_string()),
+                span.clone(),
+            ));
+            continue
+        }
+        tracing::warn!(target: "analysis", "LABEL \"{}\" IN MACRO \"{}\" IS NEVER DEFINED", label, m);
+        let mut warning = CompilerWarning::new(
+            WarningKind::MissingJumpTarget(m.to_string(), label.to_string()),
+            span.clone(),
+        );
+        warning.help = did_you_mean(label, labels.iter().copied());
+        warnings.push(warning);
+    }
+    warnings
+}
+
+/// Analyzes 4 byte literals compared with `eq`, or matching the selector of a function the
+/// contract declares, which read better as named constants.
+///
+/// Literals with the value of a constant the contract defines are fixed by pushing the constant
+/// instead.
+pub fn analyze_magic_selectors(contract: &Contract) -> Vec<CompilerWarning> {
+    let mut warnings = vec![];
+    for m in &contract.macros {
+        collect_magic_selectors(m, &m.statements, contract, &mut warnings);
+    }
+    warnings
+}
+
+/// Analyzes runs of statements spelling out an opcode alias, which read better as the alias.
+///
+/// Aliases of a single statement are not reported, since a literal or an opcode alone rarely
+/// spells out the idiom. Runs written on their own, with only whitespace in between, are fixed
+/// by using the alias instead.
+pub fn analyze_inlined_aliases(contract: &Contract) -> Vec<CompilerWarning> {
+    let mut warnings = vec![];
+    if contract.aliases.iter().any(|a| a.statements.len() > 1) {
+        for m in &contract.macros {
+            collect_inlined_aliases(m, &m.statements, &contract.aliases, &mut warnings);
+        }
+    }
+    warnings
+}
+
+/// Analyzes the stack height of every macro against its declared `takes` and `returns`.
+///
+/// A macro starts with `takes` stack items and must end with `returns`. Literals, constants, arg
+/// calls, label calls and builtins other than `__ASSERT_CODEHASH` and `__ASSERT` push one item,
+/// cheatcode builtins take their arguments, `__MSTORE_STRING` replaces the offset it takes with the
+/// size stored, `__DECODE_CALLDATA` pushes the parameters of its function and `__ENCODE_OUTPUTS`
+/// takes its outputs along with the offset it replaces, `__EMIT` takes the parameters of its
+/// event, opcodes apply their stack effect, and macro invocations apply the declaration of the
+/// invoked macro. Arg calls an invocation binds to an
+/// opcode have an unknown effect, and stop the analysis up to the next label.
+///
+/// Paths ending with a terminating opcode or `__REVERT_STRING`, or invoking a macro that never
+/// returns, are not checked further. A label is reached with the height falling through to it, or
+/// else with the height at the first jump to it from the same macro.
+pub fn analyze_stack_heights(contract: &Contract) -> Vec<CompilerWarning> {
+    let mut analysis = StackAnalysis::new(contract);
+    let mut warnings = vec![];
+    for m in &contract.macros {
+        analysis.walk_macro(m, &mut warnings);
+    }
+    warnings
+}
+
+/// The stack height analysis of the macros of a contract
+struct StackAnalysis<'a> {
+    contract: &'a Contract,
+    /// The macro parameters, by macro name, that an invocation binds to an opcode
+    opcode_params: BTreeSet<(&'a str, &'a str)>,
+    /// If each invoked macro returns, instead of always halting or jumping away
+    returning: BTreeMap<&'a str, bool>,
+}
+
+/// The state of a walk through the statements of a macro
+#[derive(Default)]
+struct StackState<'a> {
+    /// The stack height, unknown once the walked path halts or jumps away
+    height: Option<usize>,
+    /// The height at the first jump to each label
+    jumps: BTreeMap<&'a str, usize>,
+    /// The label pushed by the previous statement
+    label_call: Option<&'a str>,
+    /// The last statement walked, other than a label
+    last: Option<&'a Statement>,
+}
+
+impl<'a> StackAnalysis<'a> {
+    fn new(contract: &'a Contract) -> Self {
+        let mut opcode_params = BTreeSet::new();
+        for m in &contract.macros {
+            collect_opcode_params(&m.statements, contract, &mut opcode_params);
+        }
+        Self { contract, opcode_params, returning: BTreeMap::new() }
+    }
+
+    /// Walks a macro, reporting stack errors and returning its final height if it returns
+    fn walk_macro(
+        &mut self,
+        macro_def: &'a MacroDefinition,
+        warnings: &mut Vec<CompilerWarning>,
+    ) -> Option<usize> {
+        let mut state = StackState { height: Some(macro_def.takes), ..Default::default() };
+        self.walk(macro_def, &macro_def.statements, &mut state, warnings);
+        if let Some(height) = state.height.filter(|h| *h != macro_def.returns) {
+            tracing::warn!(target: "analysis", "MACRO \"{}\" ENDS WITH {} STACK ITEMS", macro_def.name, height);
+            let span = match state.last {
+                Some(s) => first_token(&s.span),
+                None => name_token(&macro_def.span, macro_def.internal),
+            };
+            warnings.push(CompilerWarning::new(
+                WarningKind::StackMismatch(
+                    macro_def.name.to_string(),
+                    macro_def.takes,
+                    macro_def.returns,
+                    height,
+                ),
+                span,
+            ));
+        }
+        state.height
+    }
+
+    /// If an invoked macro returns, walking it the first time it is invoked
+    fn returns(&mut self, macro_def: &'a MacroDefinition) -> bool {
+        if let Some(returns) = self.returning.get(macro_def.name.as_str()) {
+            return *returns
+        }
+        // Assume recursive invocations return, codegen rejects them anyway
+        self.returning.insert(&macro_def.name, true);
+        let returns = self.walk_macro(macro_def, &mut vec![]).is_some();
+        self.returning.insert(&macro_def.name, returns);
+        returns
+    }
+
+    /// Walks a list of statements, recursing into labels
+    fn walk(
+        &mut self,
+        macro_def: &'a MacroDefinition,
+        statements: &'a [Statement],
+        state: &mut StackState<'a>,
+        warnings: &mut Vec<CompilerWarning>,
+    ) {
+        for statement in statements {
+            let label_call = state.label_call.take();
+            match &statement.ty {
+                StatementType::Label(label) => {
+                    state.height =
+                        state.height.or_else(|| state.jumps.get(label.name.as_str()).copied());
+                    self.walk(macro_def, &label.inner, state, warnings);
+                    continue
+                }
+                StatementType::Opcode(op) => {
+                    let (taken, returned) = op.stack_effect();
+                    let mnemonic = Opcode::mnemonic(&op.string()).unwrap_or_default();
+                    apply(macro_def, statement, mnemonic, taken, returned, state, warnings);
+                    if let (Some(label), Some(height), Opcode::Jump | Opcode::Jumpi) =
+                        (label_call, state.height, op)
+                    {
+                        state.jumps.entry(label).or_insert(height);
+                    }
+                    if op.is_terminating() {
+                        state.height = None;
+                    }
+                }
+                StatementType::MacroInvocation(mi) => {
+                    match self.contract.macros.iter().find(|m| m.name == mi.macro_name) {
+                        Some(invoked) => {
+                            let instruction = format!("{}()", invoked.name);
+                            let (taken, returned) = (invoked.takes, invoked.returns);
+                            apply(
+                                macro_def,
+                                statement,
+                                &instruction,
+                                taken,
+                                returned,
+                                state,
+                                warnings,
+                            );
+                            if !self.returns(invoked) {
+                                state.height = None;
+                            }
+                        }
+                        None => state.height = None,
+                    }
+                }
+                StatementType::ArgCall(name) => match Opcode::from_str(name) {
+                    Ok(op) => {
+                        let (taken, returned) = op.stack_effect();
+                        apply(macro_def, statement, name, taken, returned, state, warnings);
+                    }
+                    Err(_) if self.opcode_params.contains(&(&macro_def.name, name)) => {
+                        state.height = None
+                    }
+                    Err(_) => apply(macro_def, statement, name, 0, 1, state, warnings),
+                },
+                StatementType::LabelCall(label) => {
+                    apply(macro_def, statement, label, 0, 1, state, warnings);
+                    state.label_call = Some(label);
+                }
+                StatementType::BuiltinFunctionCall(b)
+                    if matches!(
+                        b.kind,
+                        BuiltinFunctionKind::AssertCodehash(_) | BuiltinFunctionKind::Assert(_)
+                    ) => {}
+                StatementType::BuiltinFunctionCall(b)
+                    if b.kind == BuiltinFunctionKind::SetImmutable =>
+                {
+                    apply(macro_def, statement, "__SET_IMMUTABLE", 1, 0, state, warnings)
+                }
+                StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                    kind: BuiltinFunctionKind::Cheatcode(cheatcode),
+                    ..
+                }) => apply(
+                    macro_def,
+                    statement,
+                    cheatcode.builtin(),
+                    cheatcode.params(),
+                    0,
+                    state,
+                    warnings,
+                ),
+                StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                    kind: BuiltinFunctionKind::MstoreString(_),
+                    ..
+                }) => apply(macro_def, statement, "__MSTORE_STRING", 1, 1, state, warnings),
+                // The builtins of a function declaration take and push a word per parameter
+                StatementType::BuiltinFunctionCall(b)
+                    if matches!(
+                        b.kind,
+                        BuiltinFunctionKind::DecodeCalldata | BuiltinFunctionKind::EncodeOutputs
+                    ) =>
+                {
+                    let name = b.args.first().and_then(|a| a.name.as_deref()).unwrap_or_default();
+                    match (&b.kind, self.contract.functions.iter().find(|f| f.name == name)) {
+                        (BuiltinFunctionKind::DecodeCalldata, Some(f)) => apply(
+                            macro_def,
+                            statement,
+                            "__DECODE_CALLDATA",
+                            0,
+                            f.inputs.len(),
+                            state,
+                            warnings,
+                        ),
+                        (_, Some(f)) => apply(
+                            macro_def,
+                            statement,
+                            "__ENCODE_OUTPUTS",
+                            1 + f.outputs.len(),
+                            1,
+                            state,
+                            warnings,
+                        ),
+                        (_, None) => state.height = None,
+                    }
+                }
+                StatementType::BuiltinFunctionCall(b) if b.kind == BuiltinFunctionKind::Emit => {
+                    let name = b.args.first().and_then(|a| a.name.as_deref()).unwrap_or_default();
+                    match self.contract.events.iter().find(|e| e.name == name) {
+                        Some(e) => apply(
+                            macro_def,
+                            statement,
+                            "__EMIT",
+                            e.parameters.len(),
+                            0,
+                            state,
+                            warnings,
+                        ),
+                        None => state.height = None,
+                    }
+                }
+                // The stack effect of verbatim bytes and custom builtins is unknown
+                StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                    kind:
+                        BuiltinFunctionKind::RevertString(_) |
+                        BuiltinFunctionKind::Verbatim(_) |
+                        BuiltinFunctionKind::Custom(..),
+                    ..
+                }) => state.height = None,
+                StatementType::Code(_) => {}
+                _ => apply(macro_def, statement, "", 0, 1, state, warnings),
+            }
+            state.last = Some(statement);
+        }
+    }
+}
+
+/// Applies the stack effect of an instruction, reporting it if it takes more items than the
+/// stack holds. The walk goes on from an empty stack, to report each underflow once.
+fn apply(
+    macro_def: &MacroDefinition,
+    statement: &Statement,
+    instruction: &str,
+    taken: usize,
+    returned: usize,
+    state: &mut StackState,
+    warnings: &mut Vec<CompilerWarning>,
+) {
+    let height = match state.height {
+        Some(height) => height,
+        None => return,
+    };
+    if height < taken {
+        tracing::warn!(target: "analysis", "\"{}\" IN MACRO \"{}\" UNDERFLOWS THE STACK", instruction, macro_def.name);
+        warnings.push(CompilerWarning::new(
+            WarningKind::StackUnderflow(
+                macro_def.name.to_string(),
+                instruction.to_string(),
+                taken,
+                height,
+            ),
+            first_token(&statement.span),
+        ));
+    }
+    state.height = Some(height.saturating_sub(taken) + returned);
+}
+
+/// Recursively collects the macro parameters that invocations bind to an opcode
+fn collect_opcode_params<'a>(
+    statements: &'a [Statement],
+    contract: &'a Contract,
+    params: &mut BTreeSet<(&'a str, &'a str)>,
+) {
+    for statement in statements {
+        match &statement.ty {
+            StatementType::Label(label) => collect_opcode_params(&label.inner, contract, params),
+            StatementType::MacroInvocation(mi) => {
+                let invoked = match contract.macros.iter().find(|m| m.name == mi.macro_name) {
+                    Some(invoked) => invoked,
+                    None => continue,
+                };
+                for (arg, param) in mi.args.iter().zip(invoked.parameters.iter()) {
+                    if let (MacroArg::Ident(ident), Some(name)) = (arg, &param.name) {
+                        if Opcode::from_str(ident).is_ok() {
+                            params.insert((&invoked.name, name));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Recursively collects the unreachable statements of a list of statements
+fn collect_unreachable_code(
+    macro_def: &MacroDefinition,
+    statements: &[Statement],
+    generated: &[&AstSpan],
+    warnings: &mut Vec<CompilerWarning>,
+) {
+    let mut terminator: Option<&Opcode> = None;
+    let mut unreachable: Vec<&Statement> = vec![];
+    for statement in statements.iter().filter(|s| !generated.contains(&&s.span)) {
+        match (&statement.ty, terminator) {
+            (StatementType::Label(label), _) => {
+                if let Some(op) = terminator.take() {
+                    report_unreachable(macro_def, op, std::mem::take(&mut unreachable), warnings);
+                }
+                collect_unreachable_code(macro_def, &label.inner, generated, warnings);
+            }
+            (StatementType::MacroInvocation(_), Some(op)) => {
+                report_unreachable(macro_def, op, std::mem::take(&mut unreachable), warnings);
+                terminator = None;
+            }
+            (_, Some(_)) => unreachable.push(statement),
+            (StatementType::Opcode(op), None) if op.is_terminating() => terminator = Some(op),
+            _ => {}
+        }
+    }
+    if let Some(op) = terminator {
+        report_unreachable(macro_def, op, unreachable, warnings);
+    }
+}
+
+/// Reports a run of unreachable statements, fixed by removing them if they are single tokens
+fn report_unreachable(
+    macro_def: &MacroDefinition,
+    op: &Opcode,
+    unreachable: Vec<&Statement>,
+    warnings: &mut Vec<CompilerWarning>,
+) {
+    let (first, last) = match (unreachable.first(), unreachable.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return,
+    };
+    let op = Opcode::mnemonic(&op.string()).unwrap_or_default();
+    tracing::warn!(target: "analysis", "UNREACHABLE CODE AFTER \"{}\" IN MACRO \"{}\"", op, macro_def.name);
+    let mut warning = CompilerWarning::new(
+        WarningKind::UnreachableCode(macro_def.name.to_string(), op.to_string()),
+        first_token(&unreachable[0].span),
+    );
+
+    let removable = unreachable.iter().all(|s| {
+        s.span.0.len() == 1 &&
+            matches!(
+                s.ty,
+                StatementType::Opcode(_) | StatementType::Literal(_) | StatementType::LabelCall(_)
+            )
+    });
+    let file =
+        |s: &Statement| s.span.0.first().and_then(|s| s.file.as_ref()).map(|f| f.path.clone());
+    if removable && unreachable.iter().all(|s| file(s).is_some() && file(s) == file(first)) {
+        let (start, end) = (statement_start(first), last.span.0[0].end);
+        let source = first.span.0[0].file.as_ref().and_then(|f| f.source.as_deref());
+        let (start, end) = widen_to_lines(source.unwrap_or_default(), start, end);
+        warning.fix = Some(Fix {
+            span: Span { start, end, file: first.span.0[0].file.clone() },
+            replacement: String::new(),
+        });
+    }
+    warnings.push(warning);
+}
+
+/// Widens a removed range to the lines it spans, if nothing else is written on them
+fn widen_to_lines(source: &str, start: usize, end: usize) -> (usize, usize) {
+    let chars = source.chars().collect::<Vec<char>>();
+    if end > chars.len() {
+        return (start, end)
+    }
+    let (mut line_start, mut line_end) = (start, end);
+    while line_start > 0 && matches!(chars[line_start - 1], ' ' | '\t') {
+        line_start -= 1;
+    }
+    while line_end < chars.len() && matches!(chars[line_end], ' ' | '\t' | '\r') {
+        line_end += 1;
+    }
+    match (line_start.checked_sub(1).map(|i| chars[i]), chars.get(line_end)) {
+        (None | Some('\n'), None) => (line_start, line_end),
+        (None | Some('\n'), Some('\n')) => (line_start, line_end + 1),
+        _ => (start, end),
+    }
+}
+
+/// The start of a statement's source, including the `0x` prefix of hex literals and the opening
+/// bracket of constant pushes
+fn statement_start(statement: &Statement) -> usize {
+    let span = &statement.span.0[0];
+    let source = span.file.as_ref().and_then(|f| f.source.as_deref()).unwrap_or_default();
+    let before = span.start.checked_sub(1).and_then(|i| source.chars().nth(i));
+    match statement.ty {
+        StatementType::Literal(_) if has_hex_prefix(span) => span.start - 2,
+        StatementType::Constant(_) if before == Some('[') => span.start - 1,
+        _ => span.start,
+    }
+}
+
+/// The end of a statement's source, including the closing bracket of constant pushes
+fn statement_end(statement: &Statement) -> usize {
+    let span = &statement.span.0[0];
+    let source = span.file.as_ref().and_then(|f| f.source.as_deref()).unwrap_or_default();
+    match statement.ty {
+        StatementType::Constant(_) if source.chars().nth(span.end) == Some(']') => span.end + 1,
+        _ => span.end,
+    }
+}
+
+/// If the source of a literal's span is preceded by a `0x` prefix, excluded from its span
+fn has_hex_prefix(span: &Span) -> bool {
+    let source = span.file.as_ref().and_then(|f| f.source.as_deref()).unwrap_or_default();
+    span.start >= 2 && source.chars().skip(span.start - 2).take(2).eq("0x".chars())
+}
+
+/// If a label call names a DUP or SWAP instruction reaching deeper than the EVM supports
+fn is_stack_too_deep(label: &str) -> bool {
+    let label = label.to_lowercase();
+    let depth = label.strip_prefix("dup").or_else(|| label.strip_prefix("swap"));
+    depth.and_then(|d| d.parse::<usize>().ok()).map(|d| d > MAX_STACK_ACCESS).unwrap_or(false)
+}
+
+/// Recursively collects the magic selectors of a list of statements
+fn collect_magic_selectors(
+    macro_def: &MacroDefinition,
+    statements: &[Statement],
+    contract: &Contract,
+    warnings: &mut Vec<CompilerWarning>,
+) {
+    for (i, statement) in statements.iter().enumerate() {
+        let value = match &statement.ty {
+            StatementType::Label(label) => {
+                collect_magic_selectors(macro_def, &label.inner, contract, warnings);
+                continue
+            }
+            StatementType::Literal(value) => value,
+            _ => continue,
+        };
+        let span = match statement.span.0.as_slice() {
+            [span] if span.end - span.start == 8 && value[..28].iter().all(|b| *b == 0) => span,
+            _ => continue,
+        };
+        let selector = &value[28..];
+        let function = contract.functions.iter().find(|f| f.signature == selector);
+        let compared =
+            matches!(statements.get(i + 1).map(|s| &s.ty), Some(StatementType::Opcode(Opcode::Eq)));
+        if function.is_none() && !compared {
+            continue
+        }
+
+        let selector = format!("0x{}", hex::encode(selector));
+        tracing::warn!(target: "analysis", "MAGIC SELECTOR \"{}\" IN MACRO \"{}\"", selector, macro_def.name);
+        let mut warning = CompilerWarning::new(
+            WarningKind::MagicSelector(macro_def.name.to_string(), selector.clone()),
+            first_token(&statement.span),
+        );
+        let constant = contract.constants.iter().find(|c| c.value == ConstVal::Literal(*value));
+        warning.help = match (constant, function) {
+            (Some(c), _) => Some(format!("push the constant instead: [{}]", c.name)),
+            (None, Some(f)) => Some(format!(
+                "define a constant for the selector of function \"{}\": #define constant {}_SELECTOR = {}",
+                f.name,
+                to_screaming_snake_case(&f.name),
+                selector
+            )),
+            (None, None) => None,
+        };
+        if let Some(c) = constant.filter(|_| has_hex_prefix(span)) {
+            warning.fix = Some(Fix {
+                span: Span { start: span.start - 2, end: span.end, file: span.file.clone() },
+                replacement: format!("[{}]", c.name),
+            });
+        }
+        warnings.push(warning);
+    }
+}
+
+/// Recursively collects the runs of statements spelling out an alias, the longest alias first
+fn collect_inlined_aliases(
+    macro_def: &MacroDefinition,
+    statements: &[Statement],
+    aliases: &Arena<AliasDefinition>,
+    warnings: &mut Vec<CompilerWarning>,
+) {
+    let mut i = 0;
+    while i < statements.len() {
+        if let StatementType::Label(label) = &statements[i].ty {
+            collect_inlined_aliases(macro_def, &label.inner, aliases, warnings);
+        }
+        // Expanded statements are spanned by their use as well, and are never spelled out
+        let spelled = |a: &&AliasDefinition| {
+            a.statements.len() > 1 &&
+                statements.get(i..i + a.statements.len()).is_some_and(|run| {
+                    run.iter()
+                        .zip(a.statements.iter())
+                        .all(|(s, aliased)| s.span.0.len() == 1 && s.ty == aliased.ty)
+                })
+        };
+        // The first definition of an alias is used
+        let alias = match aliases.iter().rev().filter(spelled).max_by_key(|a| a.statements.len()) {
+            Some(a) => a,
+            None => {
+                i += 1;
+                continue
+            }
+        };
+        let run = &statements[i..i + alias.statements.len()];
+        i += run.len();
+
+        tracing::warn!(target: "analysis", "ALIAS \"{}\" SPELLED OUT IN MACRO \"{}\"", alias.name, macro_def.name);
+        let spans = run.iter().map(|s| s.span.0[0].clone()).collect::<Vec<Span>>();
+        let mut warning = CompilerWarning::new(
+            WarningKind::InlinedAlias(macro_def.name.to_string(), alias.name.clone()),
+            AstSpan(spans[..1].to_vec()),
+        );
+        warning.help = Some(format!("use the alias instead: {}", alias.name));
+        let file = spans[0].file.as_ref();
+        let source = file.and_then(|f| f.source.as_deref()).unwrap_or_default();
+        let written_together = run.windows(2).all(|pair| {
+            let (end, start) = (statement_end(&pair[0]), statement_start(&pair[1]));
+            start >= end && source.chars().skip(end).take(start - end).all(char::is_whitespace)
+        });
+        if file.is_some() && spans.iter().all(|s| s.file.as_ref() == file) && written_together {
+            warning.fix = Some(Fix {
+                span: Span {
+                    start: statement_start(&run[0]),
+                    end: statement_end(&run[run.len() - 1]),
+                    file: file.cloned(),
+                },
+                replacement: alias.name.clone(),
+            });
+        }
+        warnings.push(warning);
+    }
+}
+
+/// Recursively collects the aliases expanded in a list of statements
+fn collect_alias_uses<'a>(
+    statements: &[Statement],
+    aliases: &'a Arena<AliasDefinition>,
+    used: &mut BTreeSet<&'a str>,
+) {
+    for statement in statements {
+        match &statement.ty {
+            StatementType::Label(label) => collect_alias_uses(&label.inner, aliases, used),
+            _ => used.extend(
+                aliases.iter().filter(|a| a.is_expansion(statement)).map(|a| a.name.as_str()),
+            ),
+        }
+    }
+}
+
+/// The paths of the files included by the files defining the contract, directly or not
+fn included_files(contract: &Contract) -> BTreeSet<String> {
+    fn collect(file: &FileSource, included: &mut BTreeSet<String>) {
+        for dependency in file.dependencies.iter().flatten() {
+            if included.insert(dependency.path.clone()) {
+                collect(dependency, included);
+            }
+        }
+    }
+    let mut included = BTreeSet::new();
+    let constants = contract.constants.iter().map(|c| &c.span);
+    let spans = contract.macros.iter().map(|m| &m.span).chain(constants);
+    for file in spans.filter_map(|s| s.0.first().and_then(|s| s.file.as_ref())) {
+        collect(file, &mut included);
+    }
+    included
+}
+
+/// The span of the name of a definition, following `#define`, the `internal` modifier and the
+/// keyword of the definition
+fn name_token(span: &AstSpan, internal: bool) -> AstSpan {
+    let name = span.0.get(if internal { 3 } else { 2 });
+    AstSpan(name.or_else(|| span.0.first()).cloned().into_iter().collect())
+}
+
+/// The span of the first token of a node, pointing at it in a single location
+fn first_token(span: &AstSpan) -> AstSpan {
+    AstSpan(span.0.iter().take(1).cloned().collect())
+}
+
+/// Recursively collects the label definitions of a list of statements
+fn collect_label_definitions<'a>(statements: &'a [Statement], labels: &mut Vec<&'a Label>) {
+    for statement in statements {
+        if let StatementType::Label(label) = &statement.ty {
+            labels.push(label);
+            collect_label_definitions(&label.inner, labels);
+        }
+    }
+}
+
+/// Recursively collects the labels a list of statements jumps to, takes the offset of or passes
+/// to a macro
+fn collect_label_references<'a>(statements: &'a [Statement], labels: &mut BTreeSet<&'a str>) {
+    for statement in statements {
+        match &statement.ty {
+            StatementType::LabelCall(label) => {
+                labels.insert(label);
+            }
+            StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                kind: BuiltinFunctionKind::Offset(expression),
+                ..
+            }) => labels.extend(expression.labels()),
+            StatementType::MacroInvocation(mi) => {
+                labels.extend(mi.args.iter().filter_map(|arg| match arg {
+                    MacroArg::Ident(name) => Some(name.as_str()),
+                    _ => None,
+                }));
+            }
+            StatementType::Label(label) => collect_label_references(&label.inner, labels),
+            _ => {}
+        }
+    }
+}
+
+/// Recursively collects the literals a list of statements pushes or passes to a macro
+fn collect_pushed_literals(statements: &[Statement], literals: &mut BTreeSet<Literal>) {
+    for statement in statements {
+        match &statement.ty {
+            StatementType::Literal(l) => {
+                literals.insert(*l);
+            }
+            StatementType::MacroInvocation(mi) => {
+                literals.extend(mi.args.iter().filter_map(|arg| match arg {
+                    MacroArg::Literal(l) => Some(*l),
+                    _ => None,
+                }));
+            }
+            StatementType::Label(label) => collect_pushed_literals(&label.inner, literals),
+            _ => {}
+        }
+    }
+}
+
+/// Recursively collects the labels defined by a list of statements
+fn collect_labels<'a>(statements: &'a [Statement], labels: &mut BTreeSet<&'a str>) {
+    for statement in statements {
+        if let StatementType::Label(label) = &statement.ty {
+            labels.insert(&label.name);
+            collect_labels(&label.inner, labels);
+        }
+    }
+}
+
+/// Recursively collects the label calls and label offsets of a list of statements, with the
+/// macro making them
+fn collect_label_calls<'a>(
+    macro_name: &'a str,
+    statements: &'a [Statement],
+    label_calls: &mut Vec<(&'a str, &'a str, &'a AstSpan)>,
+) {
+    for statement in statements {
+        match &statement.ty {
+            StatementType::LabelCall(label) => {
+                label_calls.push((macro_name, label, &statement.span))
+            }
+            StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                kind: BuiltinFunctionKind::Offset(expression),
+                ..
+            }) => label_calls.extend(
+                expression.labels().into_iter().map(|label| (macro_name, label, &statement.span)),
+            ),
+            StatementType::Label(label) => {
+                collect_label_calls(macro_name, &label.inner, label_calls)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Recursively collects the arg calls of a list of statements, including those nested in labels
+/// and passed as macro invocation arguments.
+fn collect_arg_calls<'a>(statements: &'a [Statement], arg_calls: &mut Vec<(&'a str, &'a AstSpan)>) {
+    for statement in statements {
+        match &statement.ty {
+            StatementType::ArgCall(name) => arg_calls.push((name, &statement.span)),
+            StatementType::Label(label) => collect_arg_calls(&label.inner, arg_calls),
+            StatementType::MacroInvocation(mi) => {
+                mi.args.iter().for_each(|arg| {
+                    if let MacroArg::ArgCall(name) = arg {
+                        arg_calls.push((name, &mi.span))
+                    }
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Recursively collects the macros and constants referenced by a list of statements.
+fn collect_references<'a>(
+    statements: &'a [Statement],
+    macros: &mut BTreeSet<&'a str>,
+    constants: &mut BTreeSet<&'a str>,
+) {
+    for statement in statements {
+        match &statement.ty {
+            StatementType::MacroInvocation(mi) => {
+                macros.insert(&mi.macro_name);
+                mi.args.iter().for_each(|arg| match arg {
+                    MacroArg::ArgCall(name) | MacroArg::Ident(name) => {
+                        constants.insert(name);
+                    }
+                    MacroArg::Constant(name) => {
+                        constants.insert(name);
+                    }
+                    _ => {}
+                });
+            }
+            StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                kind:
+                    BuiltinFunctionKind::Assert(expression) | BuiltinFunctionKind::Offset(expression),
+                ..
+            }) => collect_expression_references(expression, macros, constants),
+            StatementType::BuiltinFunctionCall(bf) => {
+                bf.args.iter().filter_map(|a| a.name.as_deref()).for_each(|name| {
+                    macros.insert(name);
+                });
+            }
+            StatementType::Constant(name) => {
+                constants.insert(name);
+            }
+            StatementType::ArgCall(name) => {
+                constants.insert(name);
+            }
+            StatementType::Label(label) => collect_references(&label.inner, macros, constants),
+            _ => {}
+        }
+    }
+}
+
+/// Collects the macros, tables and constants an assertion expression references
+fn collect_expression_references<'a>(
+    expression: &'a Expression,
+    macros: &mut BTreeSet<&'a str>,
+    constants: &mut BTreeSet<&'a str>,
+) {
+    match expression {
+        Expression::Literal(_) | Expression::LabelOffset(_) => {}
+        Expression::Constant(name) => {
+            constants.insert(name);
+        }
+        Expression::BuiltinFunctionCall(bf) => {
+            bf.args.iter().filter_map(|a| a.name.as_deref()).for_each(|name| {
+                macros.insert(name);
+            });
+        }
+        Expression::Not(e) => collect_expression_references(e, macros, constants),
+        Expression::Binary(_, left, right) => {
+            collect_expression_references(left, macros, constants);
+            collect_expression_references(right, macros, constants);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! ## Arena
+//!
+//! Typed arenas holding the definitions of the AST, referenced by [Id] instead of cloned.
+//!
+//! Each node is allocated once and shared: cloning an arena copies a pointer per node, so
+//! snapshots of a contract, like the one compiled for each test, are cheap. Writing to a node of
+//! a snapshot copies that node alone, leaving the nodes it shares with other snapshots in place,
+//! and [Arena::shares] tells the nodes unchanged since a snapshot apart from the rewritten ones.
+//! The statements of a macro or table belong to its node, and are shared along with it.
+//!
+//! Arenas only grow: nodes are pushed after the others and never removed, so an id stays valid in
+//! its arena and in every snapshot taken once its node was allocated. A node is replaced by
+//! writing to its id instead.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    iter,
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+    slice,
+    sync::Arc,
+};
+
+/// The index of a node in an [Arena] of `T`s
+pub struct Id<T> {
+    index: u32,
+    node: PhantomData<fn() -> T>,
+}
+
+impl<T> Id<T> {
+    /// The id of the node at an index
+    pub fn new(index: usize) -> Self {
+        Id { index: index as u32, node: PhantomData }
+    }
+
+    /// The position of the node in its arena
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+}
+
+impl<T> Clone for Id<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Id<T> {}
+
+impl<T> PartialEq for Id<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for Id<T> {}
+
+impl<T> PartialOrd for Id<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Id<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.index.cmp(&other.index)
+    }
+}
+
+impl<T> Hash for Id<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state)
+    }
+}
+
+impl<T> Debug for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Id({})", self.index)
+    }
+}
+
+/// An iterator over the nodes of an arena
+pub type Nodes<'a, T> = iter::Map<slice::Iter<'a, Arc<T>>, fn(&Arc<T>) -> &T>;
+
+/// An iterator over the nodes of an arena, copying the nodes shared with a snapshot
+pub type NodesMut<'a, T> = iter::Map<slice::IterMut<'a, Arc<T>>, fn(&mut Arc<T>) -> &mut T>;
+
+/// Nodes of the same type, allocated once and shared by the snapshots of the arena
+pub struct Arena<T> {
+    nodes: Vec<Arc<T>>,
+}
+
+impl<T> Arena<T> {
+    /// An empty arena
+    pub fn new() -> Self {
+        Arena { nodes: vec![] }
+    }
+
+    /// Allocates a node after the others, returning its id
+    pub fn push(&mut self, node: T) -> Id<T> {
+        self.nodes.push(Arc::new(node));
+        Id::new(self.nodes.len() - 1)
+    }
+
+    /// The node of an id
+    pub fn get(&self, id: Id<T>) -> Option<&T> {
+        self.nodes.get(id.index()).map(Arc::as_ref)
+    }
+
+    /// The id of the first node matching a predicate
+    pub fn position(&self, mut matches: impl FnMut(&T) -> bool) -> Option<Id<T>> {
+        self.nodes.iter().position(|node| matches(node)).map(Id::new)
+    }
+
+    /// The ids of the nodes, in order
+    pub fn ids(&self) -> impl DoubleEndedIterator<Item = Id<T>> {
+        (0..self.nodes.len()).map(Id::new)
+    }
+
+    /// The nodes, in order
+    pub fn iter(&self) -> Nodes<'_, T> {
+        self.nodes.iter().map(Arc::as_ref)
+    }
+
+    /// The first node
+    pub fn first(&self) -> Option<&T> {
+        self.nodes.first().map(Arc::as_ref)
+    }
+
+    /// The number of nodes
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the arena has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Whether the node of an id is the one of a snapshot, unchanged since the snapshot was taken
+    pub fn shares(&self, snapshot: &Self, id: Id<T>) -> bool {
+        match (self.nodes.get(id.index()), snapshot.nodes.get(id.index())) {
+            (Some(node), Some(other)) => Arc::ptr_eq(node, other),
+            _ => false,
+        }
+    }
+}
+
+impl<T: Clone> Arena<T> {
+    /// The node of an id, copied first if a snapshot shares it
+    pub fn get_mut(&mut self, id: Id<T>) -> Option<&mut T> {
+        self.nodes.get_mut(id.index()).map(Arc::make_mut)
+    }
+
+    /// The nodes, in order, each copied first if a snapshot shares it
+    pub fn iter_mut(&mut self) -> NodesMut<'_, T> {
+        self.nodes.iter_mut().map(Arc::make_mut)
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Arena::new()
+    }
+}
+
+impl<T> Clone for Arena<T> {
+    fn clone(&self) -> Self {
+        Arena { nodes: self.nodes.clone() }
+    }
+}
+
+impl<T> Index<Id<T>> for Arena<T> {
+    type Output = T;
+
+    fn index(&self, id: Id<T>) -> &T {
+        &self.nodes[id.index()]
+    }
+}
+
+impl<T: Clone> IndexMut<Id<T>> for Arena<T> {
+    fn index_mut(&mut self, id: Id<T>) -> &mut T {
+        Arc::make_mut(&mut self.nodes[id.index()])
+    }
+}
+
+impl<T> Index<usize> for Arena<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.nodes[index]
+    }
+}
+
+impl<T: Clone> IndexMut<usize> for Arena<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        Arc::make_mut(&mut self.nodes[index])
+    }
+}
+
+impl<T> From<Vec<T>> for Arena<T> {
+    fn from(nodes: Vec<T>) -> Self {
+        nodes.into_iter().collect()
+    }
+}
+
+impl<T> FromIterator<T> for Arena<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(nodes: I) -> Self {
+        Arena { nodes: nodes.into_iter().map(Arc::new).collect() }
+    }
+}
+
+impl<T> Extend<T> for Arena<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, nodes: I) {
+        self.nodes.extend(nodes.into_iter().map(Arc::new))
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Arena<T> {
+    type Item = &'a T;
+    type IntoIter = Nodes<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: Clone> IntoIterator for &'a mut Arena<T> {
+    type Item = &'a mut T;
+    type IntoIter = NodesMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T: PartialEq> PartialEq for Arena<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for Arena<T> {}
+
+impl<T: PartialOrd> PartialOrd for Arena<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for Arena<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Debug> Debug for Arena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Serialize> Serialize for Arena<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Arena<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(Arena::from)
+    }
+}
@@ -3,10 +3,10 @@
 //! The artifacts generated from codegen.
 
 use serde::{Deserialize, Serialize};
-use std::{fs, path::Path, sync::Arc};
+use std::{collections::BTreeMap, fmt, fs, path::Path, str::FromStr, sync::Arc};
 
 pub use crate::abi::Abi;
-use crate::prelude::FileSource;
+use crate::prelude::{link_bytecode, DevDoc, FileSource, LinkReferences, UserDoc};
 
 /// A Codegen Artifact
 #[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -19,6 +19,94 @@ pub struct Artifact {
     pub runtime: String,
     /// The abi
     pub abi: Option<Abi>,
+    /// The storage slots of the free storage pointer constants, by constant name
+    #[serde(default)]
+    pub storage_layout: BTreeMap<String, String>,
+    /// The messages of the panics compiler-generated code can revert with, by panic code
+    #[serde(default)]
+    pub panics: BTreeMap<String, String>,
+    /// The NatSpec developer documentation
+    #[serde(default)]
+    pub devdoc: DevDoc,
+    /// The NatSpec user documentation
+    #[serde(default)]
+    pub userdoc: UserDoc,
+    /// The placeholders of the immutables in the runtime bytecode, by immutable name, like solc
+    #[serde(default, rename = "immutableReferences")]
+    pub immutable_references: BTreeMap<String, Vec<ImmutableReference>>,
+    /// The placeholders of the library addresses in the deployed bytecode, by library name
+    #[serde(default, rename = "linkReferences")]
+    pub link_references: LinkReferences,
+    /// The placeholders of the library addresses in the runtime bytecode, by library name
+    #[serde(default, rename = "deployedLinkReferences")]
+    pub runtime_link_references: LinkReferences,
+    /// The bytes spliced into the runtime bytecode with `__VERBATIM`, which no opcode generated
+    #[serde(default, rename = "verbatimReferences")]
+    pub verbatim_references: Vec<VerbatimReference>,
+}
+
+/// The shapes artifacts are exported in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArtifactFormat {
+    /// The [Artifact] json, at `<OUTPUT>/<PATH>.json`
+    #[default]
+    Huff,
+    /// The [HardhatArtifact](crate::hardhat::HardhatArtifact) json, at
+    /// `<OUTPUT>/<PATH>/<NAME>.json` like the `artifacts/` tree of Hardhat
+    Hardhat,
+    /// The [FoundryArtifact](crate::foundry::FoundryArtifact) json, at
+    /// `<OUTPUT>/<FILE NAME>/<NAME>.json` like the `out/` directory of forge
+    Foundry,
+}
+
+impl ArtifactFormat {
+    /// Every artifact format
+    pub const ALL: [ArtifactFormat; 3] =
+        [ArtifactFormat::Huff, ArtifactFormat::Hardhat, ArtifactFormat::Foundry];
+}
+
+impl fmt::Display for ArtifactFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArtifactFormat::Huff => write!(f, "huff"),
+            ArtifactFormat::Hardhat => write!(f, "hardhat"),
+            ArtifactFormat::Foundry => write!(f, "foundry"),
+        }
+    }
+}
+
+impl FromStr for ArtifactFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ArtifactFormat::ALL.into_iter().find(|v| v.to_string() == s.to_lowercase()).ok_or_else(
+            || {
+                format!(
+                    "Unknown artifact format \"{}\". Available formats: {}",
+                    s,
+                    ArtifactFormat::ALL.map(|v| v.to_string()).join(", ")
+                )
+            },
+        )
+    }
+}
+
+/// A placeholder of an immutable in the runtime bytecode
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ImmutableReference {
+    /// The offset of the placeholder
+    pub start: usize,
+    /// The length of the placeholder, always 32 bytes
+    pub length: usize,
+}
+
+/// Bytes spliced into the runtime bytecode with `__VERBATIM`
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct VerbatimReference {
+    /// The offset of the bytes
+    pub start: usize,
+    /// The number of bytes
+    pub length: usize,
 }
 
 impl Artifact {
@@ -32,4 +120,30 @@ impl Artifact {
         }
         fs::write(file_path, serialized_artifact)
     }
+
+    /// The name of the contract, the file stem of its source
+    pub fn contract_name(&self) -> String {
+        Path::new(&self.file.path).file_stem().unwrap_or_default().to_string_lossy().to_string()
+    }
+
+    /// The path of the source, without the leading `./`
+    pub fn source_name(&self) -> String {
+        self.file.path.replacen("./", "", 1)
+    }
+
+    /// Links library addresses, as 40 hex characters, into the bytecode
+    ///
+    /// Their link references are removed, the libraries left unlinked keep theirs.
+    pub fn link(&mut self, addresses: &BTreeMap<String, String>) {
+        self.bytecode = link_bytecode(&self.bytecode, addresses);
+        self.runtime = link_bytecode(&self.runtime, addresses);
+        self.link_references.retain(|library, _| !addresses.contains_key(library));
+        self.runtime_link_references.retain(|library, _| !addresses.contains_key(library));
+    }
+
+    /// Imports an artifact from a json file
+    pub fn import(path: &str) -> std::result::Result<Self, std::io::Error> {
+        let serialized_artifact = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&serialized_artifact)?)
+    }
 }
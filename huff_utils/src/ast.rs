@@ -2,30 +2,180 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    arena::{Arena, Id},
+    artifact::Artifact,
     bytecode::*,
     bytes_util::*,
-    error::CodegenError,
+    cheatcodes::Cheatcode,
+    error::{CodegenError, CodegenErrorKind, ParserError, ParserErrorKind},
     evm::Opcode,
-    prelude::{Span, TokenKind},
+    intern::SymbolId,
+    evm_version::EVMVersion,
+    natspec::NatSpecs,
+    panic::PanicCode,
+    prelude::{FileSource, Position, Span, TokenKind},
+    visit::{walk_statement_mut, VisitMut, Visitor},
+    well_known::WellKnownConstant,
 };
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt::{Display, Formatter},
     path::PathBuf,
+    str::FromStr,
+    sync::Arc,
 };
 
 /// A contained literal
 pub type Literal = [u8; 32];
 
+/// Serializes byte arrays, like literals and selectors, as 0x prefixed hex strings
+mod hex_bytes {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        bytes: &[u8; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[u8; N], D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let bytes = hex::decode(s.trim_start_matches("0x")).map_err(D::Error::custom)?;
+        bytes.try_into().map_err(|_| D::Error::custom(format!("expected {} bytes", N)))
+    }
+}
+
+/// Serializes code, like the code read into tables, as 0x prefixed hex strings
+mod hex_code {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(code: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(code)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        hex::decode(s.trim_start_matches("0x")).map_err(D::Error::custom)
+    }
+}
+
+/// Serializes maps of literals, like the storage layout, with the literals as hex strings
+mod hex_literals {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    use super::Literal;
+
+    /// A literal serialized as a hex string
+    #[derive(Serialize, Deserialize)]
+    struct Hex(#[serde(with = "super::hex_bytes")] Literal);
+
+    pub fn serialize<S: Serializer>(
+        literals: &BTreeMap<String, Literal>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        literals.iter().map(|(k, v)| (k, Hex(*v))).collect::<BTreeMap<_, _>>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<String, Literal>, D::Error> {
+        let literals = BTreeMap::<String, Hex>::deserialize(deserializer)?;
+        Ok(literals.into_iter().map(|(k, v)| (k, v.0)).collect())
+    }
+}
+
 /// A File Path
 ///
 /// Used for parsing the huff imports.
 pub type FilePath = PathBuf;
 
+/// A prebuilt artifact imported with `#include artifact "./out/Lib.json"`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ArtifactImport {
+    /// The prefix of the imported definitions, the artifact's file name in screaming snake case
+    pub name: String,
+    /// The path of the artifact
+    pub path: FilePath,
+    /// The span of the include
+    pub span: AstSpan,
+}
+
+/// A file included under a namespace with `#include "./math.huff" as Math`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Namespace {
+    /// The name qualifying the macros and constants of the file, like `Math.ADD`
+    pub name: String,
+    /// The path of the included file
+    pub path: FilePath,
+    /// The span of the include
+    pub span: AstSpan,
+}
+
+/// Converts a name to screaming snake case, eg. `transferFrom` to `TRANSFER_FROM` or
+/// `ERC20Token` to `ERC20_TOKEN`
+pub fn to_screaming_snake_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        let after_word = previous.map(|p| p.is_lowercase() || p.is_ascii_digit()).unwrap_or(false);
+        if c.is_uppercase() && after_word {
+            out.push('_');
+        }
+        out.push(if c.is_alphanumeric() { c.to_ascii_uppercase() } else { '_' });
+        previous = Some(c);
+    }
+    out
+}
+
 /// An AST-level Span
+///
+/// Serialized as a list of the spans' offsets, their file referenced by path rather than
+/// embedding its source in every span.
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct AstSpan(pub Vec<Span>);
 
+/// The serialized form of a span of an [AstSpan]
+#[derive(Serialize, Deserialize)]
+struct SerializedSpan {
+    start: usize,
+    end: usize,
+    file: Option<String>,
+}
+
+impl Serialize for AstSpan {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0
+            .iter()
+            .map(|s| SerializedSpan {
+                start: s.start,
+                end: s.end,
+                file: s.file.as_ref().map(|f| f.path.clone()),
+            })
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AstSpan {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let spans = Vec::<SerializedSpan>::deserialize(deserializer)?;
+        Ok(AstSpan(
+            spans
+                .into_iter()
+                .map(|s| Span {
+                    start: s.start,
+                    end: s.end,
+                    file: s.file.map(|path| Arc::new(FileSource { path, ..Default::default() })),
+                })
+                .collect(),
+        ))
+    }
+}
+
 impl AstSpan {
     /// Coalesce Multiple Spans Into an error string
     pub fn error(&self) -> String {
@@ -63,6 +213,11 @@ impl AstSpan {
         })
     }
 
+    /// The line and column ranges of each span that has an associated file source
+    pub fn positions(&self) -> Vec<(Position, Position)> {
+        self.0.iter().filter_map(|s| s.start_position().zip(s.end_position())).collect()
+    }
+
     /// Print just the file for missing
     pub fn file(&self) -> String {
         self.0.iter().fold("".to_string(), |acc, span| match &span.file {
@@ -72,38 +227,101 @@ impl AstSpan {
     }
 }
 
+/// The id of a macro in the [Arena] of a contract
+pub type MacroId = Id<MacroDefinition>;
+
 /// A Huff Contract Representation
 ///
 /// This is the representation of a contract as it is parsed from huff source code.
 /// Thus, it is also the root of the AST.
 ///
 /// For examples of Huff contracts, see the [huff-examples repository](https://github.com/huff-language/huff-examples).
-#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Contract {
-    /// Macro definitions
-    pub macros: Vec<MacroDefinition>,
+    /// Macro definitions, referenced by [MacroId]
+    pub macros: Arena<MacroDefinition>,
     /// Invocations of macros
     pub invocations: Vec<MacroInvocation>,
     /// File Imports
     pub imports: Vec<FilePath>,
     /// Constants
-    pub constants: Vec<ConstantDefinition>,
+    pub constants: Arena<ConstantDefinition>,
+    /// Immutables, set by the constructor into the runtime bytecode
+    pub immutables: Arena<ImmutableDefinition>,
+    /// Opcode aliases, expanded into the macros using them
+    pub aliases: Arena<AliasDefinition>,
     /// Functions
-    pub functions: Vec<Function>,
+    pub functions: Arena<Function>,
     /// Events
-    pub events: Vec<Event>,
+    pub events: Arena<Event>,
+    /// Custom errors
+    pub errors: Arena<ErrorDefinition>,
     /// Tables
-    pub tables: Vec<TableDefinition>,
+    pub tables: Arena<TableDefinition>,
+    /// Top-level `__ASSERT`s, checked once when compiling
+    pub assertions: Vec<Assertion>,
+    /// The fallback definition, run when no function matches the calldata
+    pub fallback: Option<MacroDefinition>,
+    /// The receive definition, run on empty calldata
+    pub receive: Option<MacroDefinition>,
+    /// Test definitions, their typed parameters ABI-encoded into the calldata of each run
+    pub tests: Vec<MacroDefinition>,
+    /// The doc comments of the functions, events and macros
+    #[serde(with = "crate::natspec::entries")]
+    pub natspec: NatSpecs,
+    /// Prebuilt artifacts imported with `#include artifact`
+    pub artifacts: Vec<ArtifactImport>,
+    /// The slots derived for free storage pointer constants, by constant name
+    #[serde(with = "hex_literals")]
+    pub storage_layout: BTreeMap<String, Literal>,
+    /// The maximum depth of nested macro invocations during codegen, defaulting to
+    /// [DEFAULT_MAX_EXPANSION_DEPTH]
+    pub max_expansion_depth: Option<usize>,
+    /// The panics the compiler-generated code of the contract can revert with
+    pub panics: BTreeSet<PanicCode>,
+    /// The constants tested by `__IFDEF` and `__IFNDEF` blocks
+    pub flags: BTreeSet<String>,
+    /// The constants counting the repetitions of `__REPEAT` blocks
+    pub repeat_counts: BTreeSet<String>,
+    /// Files included under a namespace, qualifying their macros and constants
+    pub namespaces: Vec<Namespace>,
 }
 
+/// The maximum depth of nested macro invocations, unless a contract sets its own
+pub const DEFAULT_MAX_EXPANSION_DEPTH: usize = 256;
+
 impl Contract {
+    /// Serializes the AST to json, each span with the path of its file
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
     /// Returns the first macro that matches the provided name
-    pub fn find_macro_by_name(&self, name: &str) -> Option<MacroDefinition> {
-        if let Some(m) = self.macros.iter().find(|m| m.name == name) {
-            Some(m.clone())
-        } else {
+    pub fn find_macro_by_name(&self, name: &str) -> Option<&MacroDefinition> {
+        match SymbolId::lookup(name) {
+            Some(name) => self.find_macro_id(name).map(|id| &self.macros[id]),
+            None => {
+                tracing::warn!("Failed to find macro \"{}\" in contract", name);
+                None
+            }
+        }
+    }
+
+    /// Returns the id of the first macro that matches the provided name
+    pub fn find_macro_id(&self, name: SymbolId) -> Option<MacroId> {
+        let id = self.macros.position(|m| m.name == name);
+        if id.is_none() {
             tracing::warn!("Failed to find macro \"{}\" in contract", name);
-            None
+        }
+        id
+    }
+
+    /// Returns the value of the first constant that matches the provided name, or else of the
+    /// [WellKnownConstant] of the name
+    pub fn find_constant_value(&self, name: SymbolId) -> Option<ConstVal> {
+        match self.constants.iter().find(|c| c.name == name) {
+            Some(c) => Some(c.value.clone()),
+            None => WellKnownConstant::from_name(&name).map(|w| ConstVal::Literal(w.value())),
         }
     }
 
@@ -117,37 +335,399 @@ impl Contract {
         }
     }
 
-    /// Derives the FreeStoragePointers into their bytes32 representation
-    pub fn derive_storage_pointers(&mut self) {
-        let mut storage_pointers: Vec<(String, [u8; 32])> = Vec::new();
-        let mut last_assigned_free_pointer = 0;
-
-        // Derive Constructor Storage Pointers
-        match self.find_macro_by_name("CONSTRUCTOR") {
-            Some(m) => self.recurse_ast_constants(
-                &m,
-                &mut storage_pointers,
-                &mut last_assigned_free_pointer,
-            ),
-            None => {
-                tracing::error!(target: "ast", "'CONSTRUCTOR' MACRO NOT FOUND WHILE DERIVING STORAGE POINTERS!")
+    /// Returns the first function that matches the provided name
+    pub fn find_function_by_name(&self, name: &str) -> Option<Function> {
+        if let Some(f) = self.functions.iter().find(|f| f.name == name) {
+            Some(f.clone())
+        } else {
+            tracing::warn!("Failed to find function \"{}\" in contract", name);
+            None
+        }
+    }
+
+    /// Returns the first event that matches the provided name
+    pub fn find_event_by_name(&self, name: &str) -> Option<Event> {
+        if let Some(e) = self.events.iter().find(|e| e.name == name) {
+            Some(e.clone())
+        } else {
+            tracing::warn!("Failed to find event \"{}\" in contract", name);
+            None
+        }
+    }
+
+    /// Wires the fallback and receive definitions into the `MAIN` macro
+    ///
+    /// The calldata size is checked before `MAIN` runs, jumping to the `receive` label on empty
+    /// calldata. The `fallback` label follows `MAIN`, so the fallback runs when no function
+    /// matches and `MAIN` falls through, or jumps to the label. Both labels invoke their
+    /// definition like a macro.
+    ///
+    /// Contracts without a `MAIN` macro, like libraries, are left untouched.
+    pub fn wire_entrypoints(&mut self) {
+        let main = match self.macros.iter_mut().find(|m| m.name == "MAIN") {
+            Some(m) => m,
+            None => return,
+        };
+        let statement = |ty: StatementType, span: &AstSpan| Statement { ty, span: span.clone() };
+        let entry = |e: &MacroDefinition| {
+            let invocation = MacroInvocation {
+                macro_name: e.name,
+                args: vec![],
+                span: e.span.clone(),
+            };
+            let label = Label {
+                name: e.name,
+                inner: vec![statement(StatementType::MacroInvocation(invocation), &e.span)],
+                span: e.span.clone(),
+            };
+            statement(StatementType::Label(label), &e.span)
+        };
+
+        let mut statements = vec![];
+        if let Some(r) = &self.receive {
+            statements.extend([
+                statement(StatementType::Opcode(Opcode::Calldatasize), &r.span),
+                statement(StatementType::Opcode(Opcode::Iszero), &r.span),
+                statement(StatementType::LabelCall(r.name), &r.span),
+                statement(StatementType::Opcode(Opcode::Jumpi), &r.span),
+            ]);
+        }
+        statements.append(&mut main.statements);
+        if let Some(f) = &self.fallback {
+            statements.push(entry(f));
+        }
+        if let Some(r) = &self.receive {
+            // Falling through `MAIN` or the fallback doesn't run the receive logic
+            statements.push(statement(StatementType::Opcode(Opcode::Stop), &r.span));
+            statements.push(entry(r));
+        }
+        main.statements = statements;
+        tracing::info!(target: "ast", "WIRED ENTRYPOINTS INTO \"MAIN\"");
+
+        self.macros.extend(self.fallback.iter().chain(self.receive.iter()).cloned());
+    }
+
+    /// Expands the invocations of the [NON_REENTRANT] wrapper macro into a reentrancy guard
+    ///
+    /// `NON_REENTRANT(INNER)` reverts with the [PanicCode::ReentrantCall] panic if the lock is
+    /// held, and otherwise holds it while `INNER` runs, so `INNER` must not halt for the lock to
+    /// be released. The lock is kept at the [NON_REENTRANT_SLOT] slot of transient storage on EVM
+    /// versions supporting it, and of storage otherwise.
+    ///
+    /// A `NON_REENTRANT` macro defined by the contract takes precedence over the wrapper.
+    pub fn expand_non_reentrant(&mut self, evm_version: EVMVersion) {
+        if self.macros.iter().any(|m| m.name == NON_REENTRANT) {
+            tracing::warn!(target: "ast", "\"{}\" MACRO DEFINED BY THE CONTRACT", NON_REENTRANT);
+            return
+        }
+        let mut guards = 0;
+        for m in self.macros.iter_mut() {
+            let statements = std::mem::take(&mut m.statements);
+            m.statements = expand_guards(statements, evm_version, &mut guards);
+        }
+        if guards > 0 {
+            tracing::info!(target: "ast", "EXPANDED {} REENTRANCY GUARDS FOR {}", guards, evm_version);
+            self.panics.insert(PanicCode::ReentrantCall);
+        }
+    }
+
+    /// Expands the uses of opcode aliases in macro bodies into the statements they alias
+    ///
+    /// An alias is used by its name alone, like a label call, and takes precedence over labels
+    /// of the same name. Aliases passed as macro arguments are not expanded. Each expanded
+    /// statement is spanned by the use, followed by the span of the aliased statement. The first
+    /// definition of an alias is used, like for macros.
+    pub fn expand_aliases(&mut self) {
+        if self.aliases.is_empty() {
+            return
+        }
+        let mut uses = 0;
+        let macros = self.macros.iter_mut().chain(self.fallback.iter_mut());
+        for m in macros.chain(self.receive.iter_mut()).chain(self.tests.iter_mut()) {
+            let statements = std::mem::take(&mut m.statements);
+            m.statements = expand_alias_uses(statements, &self.aliases, &mut uses);
+        }
+        tracing::info!(target: "ast", "EXPANDED {} ALIAS USES", uses);
+    }
+
+    /// Qualifies the macros and constants of the files included under a namespace with its name,
+    /// like `Math.ADD`
+    ///
+    /// Definitions belong to the file of their span. A namespaced file references its own
+    /// definitions unqualified, so the references of its macros and assertions are qualified as
+    /// well, while other files use the qualified names.
+    pub fn qualify_namespaces(&mut self) {
+        if self.namespaces.is_empty() {
+            return
+        }
+        let namespaces = self.namespaces.clone();
+        let namespace_of = |span: &AstSpan| {
+            let file = span.0.first()?.file.as_ref()?;
+            let namespace = namespaces.iter().find(|n| n.path.as_os_str() == file.path.as_str());
+            namespace.map(|n| n.name.as_str())
+        };
+
+        // The definitions of each namespace, by their unqualified name
+        let mut scopes: BTreeMap<&str, NamespaceScope> = BTreeMap::new();
+        for m in self.macros.iter() {
+            if let Some(name) = namespace_of(&m.span) {
+                let scope = scopes.entry(name).or_insert_with(|| NamespaceScope::new(name));
+                scope.macros.insert(m.name.to_string());
+            }
+        }
+        for c in self.constants.iter() {
+            if let Some(name) = namespace_of(&c.span) {
+                let scope = scopes.entry(name).or_insert_with(|| NamespaceScope::new(name));
+                scope.constants.insert(c.name.to_string());
             }
         }
 
-        // Derive Main Storage Pointers
-        match self.find_macro_by_name("MAIN") {
-            Some(m) => self.recurse_ast_constants(
-                &m,
-                &mut storage_pointers,
-                &mut last_assigned_free_pointer,
-            ),
-            None => {
-                tracing::error!(target: "ast", "'MAIN' MACRO NOT FOUND WHILE DERIVING STORAGE POINTERS!")
+        for m in self.macros.iter_mut().chain(self.tests.iter_mut()) {
+            let Some(scope) = namespace_of(&m.span).and_then(|n| scopes.get(n)) else { continue };
+            for p in m.parameters.iter_mut() {
+                if let Some(MacroArg::Constant(c)) = &mut p.default {
+                    scope.qualify_constant(c);
+                }
+            }
+            qualify_statements(&mut m.statements, scope);
+        }
+        for m in self.macros.iter_mut() {
+            if let Some(name) = namespace_of(&m.span) {
+                tracing::debug!(target: "ast", "QUALIFYING MACRO \"{}\" AS \"{}.{}\"", m.name, name, m.name);
+                m.name = format!("{}.{}", name, m.name).into();
+            }
+        }
+        for c in self.constants.iter_mut() {
+            if let Some(name) = namespace_of(&c.span) {
+                c.name = format!("{}.{}", name, c.name).into();
+            }
+        }
+        for a in self.assertions.iter_mut() {
+            if let Some(scope) = namespace_of(&a.span).and_then(|n| scopes.get(n)) {
+                qualify_expression(&mut a.expression, scope);
             }
         }
+    }
+
+    /// Checks that the `internal` macros and constants are only referenced by the file defining
+    /// them, returning an error spanning both the reference and the definition otherwise
+    pub fn check_visibility(&self) -> Vec<ParserError> {
+        let file_of = |span: &AstSpan| span.0.first()?.file.as_ref().map(|f| f.path.clone());
+        let mut references = References::default();
+        let macros = self.macros.iter().chain(self.fallback.iter()).chain(self.receive.iter());
+        for m in macros.chain(self.tests.iter()) {
+            for p in m.parameters.iter() {
+                if let Some(MacroArg::Constant(c)) = &p.default {
+                    references.constants.push((c, &p.span));
+                }
+            }
+            references.collect_statements(&m.statements);
+        }
+        for a in self.assertions.iter() {
+            references.collect_expression(&a.expression, &a.span);
+        }
+
+        let macros = references.macros.into_iter().filter_map(|(name, span)| {
+            let m = self.macros.iter().find(|m| m.name == name)?;
+            Some((name, span, m.internal, &m.span))
+        });
+        let constants = references.constants.into_iter().filter_map(|(name, span)| {
+            let c = self.constants.iter().find(|c| c.name == name)?;
+            Some((name, span, c.internal, &c.span))
+        });
+        macros
+            .chain(constants)
+            .filter(|(_, span, internal, definition)| {
+                *internal && file_of(span).is_some() && file_of(span) != file_of(definition)
+            })
+            .map(|(name, span, _, definition)| {
+                tracing::error!(target: "ast", "PRIVATE DEFINITION \"{}\" REFERENCED FROM ANOTHER FILE", name);
+                let mut spans = span.0.clone();
+                spans.extend(definition.0.first().cloned());
+                ParserError {
+                    kind: ParserErrorKind::PrivateDefinition(name.to_string()),
+                    spans: AstSpan(spans),
+                }
+            })
+            .collect()
+    }
+
+    /// Passes the defaults of macro parameters to the invocations omitting them
+    ///
+    /// Only trailing arguments can be omitted. Invocations omitting a parameter without a default
+    /// are left as written, to be reported by code generation.
+    pub fn fill_default_args(&mut self) {
+        let mut defaults: BTreeMap<String, Vec<Option<MacroArg>>> = BTreeMap::new();
+        for m in self.macros.iter() {
+            defaults
+                .entry(m.name.to_string())
+                .or_insert_with(|| m.parameters.iter().map(|p| p.default.clone()).collect());
+        }
+        if defaults.values().flatten().all(|d| d.is_none()) {
+            return
+        }
+        let macros = self.macros.iter_mut().chain(self.fallback.iter_mut());
+        for m in macros.chain(self.receive.iter_mut()).chain(self.tests.iter_mut()) {
+            fill_invocation_defaults(&mut m.statements, &defaults);
+        }
+    }
+
+    /// Expands the `__REPEAT`, `__IFDEF` and `__IFNDEF` blocks of macro bodies, returning the
+    /// errors of invalid repeat counts
+    ///
+    /// The count of a repeat is a literal, or a constant defined as one. Each repetition is a
+    /// copy of the block, with its index constant replaced by the index of the repetition. Nested
+    /// repeats are expanded with the indices of the repeats around them. The constants counting
+    /// repetitions are kept in [repeat_counts](Contract::repeat_counts).
+    ///
+    /// A conditional block is kept if its constant is defined, for `__IFDEF`, or if it isn't,
+    /// for `__IFNDEF`, whatever the value of the constant. The constants tested are kept in
+    /// [flags](Contract::flags).
+    pub fn expand_blocks(&mut self) -> Vec<ParserError> {
+        let mut errors = vec![];
+        let macros = self.macros.iter_mut().chain(self.fallback.iter_mut());
+        for m in macros.chain(self.receive.iter_mut()).chain(self.tests.iter_mut()) {
+            let statements = std::mem::take(&mut m.statements);
+            m.statements = expand_block_statements(
+                statements,
+                &self.constants,
+                &mut self.flags,
+                &mut self.repeat_counts,
+                &mut errors,
+            );
+        }
+        errors
+    }
+
+    /// Defines constants, replacing the constants of the same name
+    ///
+    /// Used for the constants defined when compiling, like with `huffc --define`.
+    pub fn define_constants(&mut self, defines: &[ConstantDefinition]) {
+        for define in defines {
+            tracing::info!(target: "ast", "DEFINING CONSTANT \"{}\"", define.name);
+            match self.constants.position(|c| c.name == define.name) {
+                Some(id) => self.constants[id] = define.clone(),
+                None => {
+                    self.constants.push(define.clone());
+                }
+            }
+        }
+    }
+
+    /// Overrides the values of constants, keeping the spans of their definitions
+    ///
+    /// Used for the constants overridden when compiling, like with `huffc -c`. Overrides naming a
+    /// constant the contract doesn't define are reported rather than defining it.
+    pub fn override_constants(&mut self, overrides: &[ConstantDefinition]) -> Vec<ParserError> {
+        let mut errors = vec![];
+        for over in overrides {
+            match self.constants.iter_mut().find(|c| c.name == over.name) {
+                Some(constant) => {
+                    tracing::info!(target: "ast", "OVERRIDING CONSTANT \"{}\"", over.name);
+                    constant.value = over.value.clone();
+                }
+                None => errors.push(ParserError {
+                    kind: ParserErrorKind::UnknownConstantOverride(over.name.to_string()),
+                    spans: over.span.clone(),
+                }),
+            }
+        }
+        errors
+    }
+
+    /// Imports the definitions of a prebuilt artifact, prefixed with the import name
+    ///
+    /// Constants are defined for the selector of each function (`<NAME>_<FUNCTION>_SELECTOR`),
+    /// the topic of each event (`<NAME>_<EVENT>_EVENT_HASH`) and each storage slot of the
+    /// artifact's layout (`<NAME>_<CONSTANT>`). Its creation and runtime bytecode are defined as
+    /// the `<NAME>_BYTECODE` and `<NAME>_RUNTIME` code tables. The definitions are spanned by the
+    /// include.
+    ///
+    /// Fails without importing anything if the bytecode of the artifact isn't hex.
+    pub fn import_artifact(
+        &mut self,
+        import: ArtifactImport,
+        artifact: &Artifact,
+    ) -> Result<(), hex::FromHexError> {
+        let bytecode = hex::decode(&artifact.bytecode)?;
+        let runtime = hex::decode(&artifact.runtime)?;
+        let constant = |name: String, value: &[u8]| {
+            let mut literal = [0u8; 32];
+            literal[32 - value.len()..].copy_from_slice(value);
+            ConstantDefinition {
+                name: name.into(),
+                value: ConstVal::Literal(literal),
+                span: import.span.clone(),
+                internal: false,
+            }
+        };
+        if let Some(abi) = &artifact.abi {
+            for f in abi.functions.values() {
+                let name = format!("{}_{}_SELECTOR", import.name, to_screaming_snake_case(&f.name));
+                self.constants.push(constant(name, &f.selector()));
+            }
+            for e in abi.events.values() {
+                let name =
+                    format!("{}_{}_EVENT_HASH", import.name, to_screaming_snake_case(&e.name));
+                self.constants.push(constant(name, &e.topic()));
+            }
+        }
+        for (name, slot) in artifact.storage_layout.iter() {
+            let slot = str_to_bytes32(slot.trim_start_matches("0x"));
+            self.constants.push(constant(format!("{}_{}", import.name, name), &slot));
+        }
+        for (suffix, code) in [("BYTECODE", bytecode), ("RUNTIME", runtime)] {
+            if code.is_empty() {
+                continue
+            }
+            let size = str_to_bytes32(code.len().to_string().as_str());
+            let statement = Statement { ty: StatementType::Code(code), span: import.span.clone() };
+            self.tables.push(TableDefinition::new(
+                format!("{}_{}", import.name, suffix),
+                TableKind::CodeTable,
+                vec![statement],
+                size,
+                import.span.clone(),
+            ));
+        }
+        tracing::info!(target: "ast", "IMPORTED ARTIFACT \"{}\" AS \"{}\"", import.path.display(), import.name);
+        self.artifacts.push(import);
+        Ok(())
+    }
+
+    /// Derives the FreeStoragePointers into their bytes32 representation
+    ///
+    /// Cycles in the walked macros are skipped, see
+    /// [try_derive_storage_pointers](Contract::try_derive_storage_pointers) to report them.
+    pub fn derive_storage_pointers(&mut self) {
+        let _ = self.try_derive_storage_pointers();
+    }
+
+    /// Derives the FreeStoragePointers into their bytes32 representation, failing on cycles and
+    /// slot collisions
+    ///
+    /// Slots are assigned in the order constants are first pushed, walking the macros expanded
+    /// from `CONSTRUCTOR` then `MAIN`. A macro invoked, or passed to `__codesize`, in its own
+    /// expansion would be walked forever, so each cycle is skipped and reported with its path.
+    /// The slots of the constants reached are still derived, and those also assigned by hand to a
+    /// literal constant are reported, see
+    /// [storage_slot_collisions](Contract::storage_slot_collisions).
+    pub fn try_derive_storage_pointers(&mut self) -> Result<(), Vec<CodegenError>> {
+        let mut derivation = StoragePointerDerivation::new(self);
+        for name in ["CONSTRUCTOR", "MAIN"] {
+            match self.find_macro_by_name(name) {
+                Some(m) => derivation.derive(m),
+                None => {
+                    tracing::error!(target: "ast", "'{}' MACRO NOT FOUND WHILE DERIVING STORAGE POINTERS!", name)
+                }
+            }
+        }
+        let StoragePointerDerivation { storage_pointers, mut cycles, .. } = derivation;
 
         tracing::debug!(target: "ast", "Generate Storage pointers: {:?}", storage_pointers);
         tracing::debug!(target: "ast", "ALL AST CONSTANTS: {:?}", storage_pointers);
+        self.storage_layout = storage_pointers.iter().cloned().collect();
+        cycles.extend(self.storage_slot_collisions(&storage_pointers));
 
         // Set all the constants to their new values
         for c in &mut self.constants {
@@ -155,13 +735,14 @@ impl Contract {
                 .iter()
                 .filter(|pointer| pointer.0.eq(&c.name))
                 .collect::<Vec<&(String, [u8; 32])>>()
-                .get(0)
+                .first()
             {
                 Some(p) => {
                     *c = ConstantDefinition {
-                        name: c.name.to_string(),
+                        name: c.name,
                         value: ConstVal::Literal(p.1),
                         span: c.span.clone(),
+                        internal: c.internal,
                     };
                 }
                 None => {
@@ -169,123 +750,630 @@ impl Contract {
                 }
             }
         }
+
+        match cycles.is_empty() {
+            true => Ok(()),
+            false => Err(cycles),
+        }
     }
 
-    /// Recurse down an AST Macro Definition to set Storage Pointers
-    ///
-    /// ## Overview
+    /// The names of the libraries the macros link with `__LINK`
+    pub fn linked_libraries(&self) -> BTreeSet<String> {
+        let mut libraries = BTreeSet::new();
+        self.macros.iter().for_each(|m| collect_linked_libraries(&m.statements, &mut libraries));
+        libraries
+    }
+
+    /// Finds the slots derived for free storage pointers that are also the slot of a literal
+    /// constant
     ///
-    /// For each statement in the macro definition:
-    ///     - If it's a free storage pointer constant, set the constant value if not already set and
-    ///       updated out `last_p` tracker value
-    ///     - If it's a literal constant, we can set the constant value directly to the literal if
-    ///       not already set
-    ///     - If it's a macro invocation, look for the macro definition and recurse into that macro
-    ///       definition using `recurse_ast_constants`
-    pub fn recurse_ast_constants(
+    /// A literal constant is a slot when it's pushed right before an `sload` or `sstore`, and
+    /// collides with every slot a free storage pointer reserves. Each collision is reported once,
+    /// spanning both constant definitions.
+    pub fn storage_slot_collisions(
+        &self,
+        storage_pointers: &[(String, [u8; 32])],
+    ) -> Vec<CodegenError> {
+        let mut slots: Vec<&str> = vec![];
+        for m in self.macros.iter() {
+            collect_storage_slots(&m.statements, &mut slots);
+        }
+        let literals = self
+            .constants
+            .iter()
+            .filter(|c| slots.contains(&c.name.as_str()))
+            .filter_map(|c| match c.value {
+                ConstVal::Literal(l) => Some((c, l)),
+                ConstVal::FreeStoragePointer(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        // The derived slots are small, and literals above 128 bits can't collide with them
+        let number = |b: &[u8; 32]| {
+            let (high, low) = b.split_at(16);
+            high.iter().all(|b| *b == 0).then(|| u128::from_be_bytes(low.try_into().unwrap()))
+        };
+        let mut collisions = vec![];
+        for c in self.constants.iter() {
+            let ConstVal::FreeStoragePointer(fsp) = &c.value else { continue };
+            let Some((_, slot)) = storage_pointers.iter().find(|(name, _)| name == &c.name) else {
+                continue
+            };
+            let Some(base) = number(slot) else { continue };
+            let reserved = base..base + fsp.size as u128;
+            for (literal, l) in literals.iter() {
+                if !number(l).is_some_and(|l| reserved.contains(&l)) {
+                    continue
+                }
+                tracing::error!(target: "ast", "STORAGE SLOT OF \"{}\" COLLIDES WITH \"{}\"", c.name, literal.name);
+                collisions.push(CodegenError {
+                    kind: CodegenErrorKind::StorageSlotCollision(c.name.to_string()),
+                    span: AstSpan([c.span.0.clone(), literal.span.0.clone()].concat()),
+                    token: None,
+                    help: Some(format!(
+                        "\"{}\" is derived the slot {}, already used by \"{}\"",
+                        c.name,
+                        bytes32_to_string(l, true),
+                        literal.name
+                    )),
+                });
+            }
+        }
+        collisions
+    }
+
+    /// Derives the storage pointer of a constant, unless it is already derived
+    fn derive_constant(
         &self,
-        macro_def: &MacroDefinition,
+        const_name: &str,
         storage_pointers: &mut Vec<(String, [u8; 32])>,
         last_p: &mut i32,
     ) {
-        let mut statements = macro_def.statements.clone();
-        let mut i = 0;
-        loop {
-            if i >= statements.len() {
-                break
-            }
-            match &statements[i].clone().ty {
-                StatementType::Constant(const_name) => {
-                    tracing::debug!(target: "ast", "Found constant \"{}\" in macro def \"{}\" statements!", const_name, macro_def.name);
-                    if storage_pointers
-                        .iter()
-                        .filter(|pointer| pointer.0.eq(const_name))
-                        .collect::<Vec<&(String, [u8; 32])>>()
-                        .get(0)
-                        .is_none()
-                    {
-                        tracing::debug!(target: "ast", "No storage pointer already set for \"{}\"!", const_name);
-                        // Get the associated constant
-                        match self
-                            .constants
-                            .iter()
-                            .filter(|c| c.name.eq(const_name))
-                            .collect::<Vec<&ConstantDefinition>>()
-                            .get(0)
-                        {
-                            Some(c) => {
-                                let new_value = match c.value {
-                                    ConstVal::Literal(l) => l,
-                                    ConstVal::FreeStoragePointer(_) => {
-                                        let old_p = *last_p;
-                                        *last_p += 1;
-                                        str_to_bytes32(&format!("{}", old_p))
-                                    }
-                                };
-                                storage_pointers.push((const_name.to_string(), new_value));
-                            }
-                            None => {
-                                tracing::warn!(target: "ast", "CONSTANT \"{}\" NOT FOUND IN AST CONSTANTS", const_name)
-                            }
-                        }
+        if storage_pointers.iter().any(|pointer| pointer.0.eq(const_name)) {
+            return
+        }
+        tracing::debug!(target: "ast", "No storage pointer already set for \"{}\"!", const_name);
+        // Get the associated constant
+        match self
+            .constants
+            .iter()
+            .filter(|c| c.name.eq(const_name))
+            .collect::<Vec<&ConstantDefinition>>()
+            .first()
+        {
+            Some(c) => {
+                let new_value = match c.value {
+                    ConstVal::Literal(l) => l,
+                    ConstVal::FreeStoragePointer(ref fsp) => {
+                        let old_p = *last_p;
+                        *last_p += fsp.size as i32;
+                        str_to_bytes32(&format!("{:x}", old_p))
                     }
+                };
+                storage_pointers.push((const_name.to_string(), new_value));
+            }
+            None => {
+                tracing::warn!(target: "ast", "CONSTANT \"{}\" NOT FOUND IN AST CONSTANTS", const_name)
+            }
+        }
+    }
+
+    /// Records a cycle back to a macro being walked, once per invoking span
+    fn record_cycle(
+        name: SymbolId,
+        span: &AstSpan,
+        expanding: &[SymbolId],
+        cycles: &mut Vec<CodegenError>,
+    ) {
+        if cycles.iter().any(|c| &c.span == span) {
+            return
+        }
+        let chain = expanding.iter().map(SymbolId::as_str).chain([name.as_str()]).join(" > ");
+        tracing::error!(target: "ast", "CYCLE WHILE DERIVING STORAGE POINTERS: {}", chain);
+        cycles.push(CodegenError {
+            kind: CodegenErrorKind::RecursiveMacroInvocation(chain),
+            span: span.clone(),
+            token: None,
+            help: Some(format!("\"{}\" is invoked in its own expansion, which never ends", name)),
+        });
+    }
+}
+
+/// Collects the constants pushed right before an `sload` or `sstore`, in labels too
+fn collect_storage_slots<'a>(statements: &'a [Statement], slots: &mut Vec<&'a str>) {
+    for (i, statement) in statements.iter().enumerate() {
+        match &statement.ty {
+            StatementType::Constant(name) => {
+                if let Some(StatementType::Opcode(Opcode::Sload | Opcode::Sstore)) =
+                    statements.get(i + 1).map(|s| &s.ty)
+                {
+                    slots.push(name);
                 }
-                StatementType::MacroInvocation(mi) => {
-                    tracing::debug!(target: "ast", "Found macro invocation: \"{}\" in macro def: \"{}\"!", mi.macro_name, macro_def.name);
-                    match self
-                        .macros
-                        .iter()
-                        .filter(|md| md.name.eq(&mi.macro_name))
-                        .collect::<Vec<&MacroDefinition>>()
-                        .get(0)
-                    {
-                        Some(&md) => self.recurse_ast_constants(md, storage_pointers, last_p),
-                        None => {
-                            tracing::warn!(target: "ast", "MACRO \"{}\" INVOKED BUT NOT FOUND IN AST!", mi.macro_name)
-                        }
+            }
+            StatementType::Label(l) => collect_storage_slots(&l.inner, slots),
+            _ => {}
+        }
+    }
+}
+
+/// Collects the libraries linked with `__LINK`, in labels too
+fn collect_linked_libraries(statements: &[Statement], libraries: &mut BTreeSet<String>) {
+    for statement in statements.iter() {
+        match &statement.ty {
+            StatementType::BuiltinFunctionCall(b) if b.kind == BuiltinFunctionKind::Link => {
+                libraries.extend(b.args.iter().filter_map(|a| a.name.clone()))
+            }
+            StatementType::Label(l) => collect_linked_libraries(&l.inner, libraries),
+            _ => {}
+        }
+    }
+}
+
+/// The name of the compiler-generated reentrancy guard wrapper macro
+pub const NON_REENTRANT: &str = "NON_REENTRANT";
+
+/// The preimage of the slot holding the reentrancy lock, keccak256 hashed
+pub const NON_REENTRANT_SLOT: &str = "huff.non_reentrant.lock";
+
+/// Recursively expands the `NON_REENTRANT(INNER)` invocations of a list of statements
+///
+/// Each guard gets its own label, with an underscore so it can't clash with a label of the
+/// source.
+fn expand_guards(
+    statements: Vec<Statement>,
+    evm_version: EVMVersion,
+    guards: &mut usize,
+) -> Vec<Statement> {
+    let (load, store) = evm_version.transient_storage_opcodes();
+    let slot = ethers_core::utils::keccak256(NON_REENTRANT_SLOT);
+    let mut locked = [0u8; 32];
+    locked[31] = 1;
+
+    let mut expanded = vec![];
+    for statement in statements {
+        let span = statement.span.clone();
+        let s = |ty: StatementType| Statement { ty, span: span.clone() };
+        match statement.ty {
+            StatementType::MacroInvocation(mi) if mi.macro_name == NON_REENTRANT => {
+                let inner = match mi.args.as_slice() {
+                    [MacroArg::Ident(inner)] => inner.clone(),
+                    _ => {
+                        // Left to fail as an invalid invocation
+                        tracing::warn!(target: "ast", "\"{}\" EXPECTS A SINGLE MACRO NAME", NON_REENTRANT);
+                        expanded.push(s(StatementType::MacroInvocation(mi)));
+                        continue
+                    }
+                };
+                let label = SymbolId::from(format!("non_reentrant_{}", guards));
+                *guards += 1;
+                let invocation = MacroInvocation {
+                    macro_name: inner.into(),
+                    args: vec![],
+                    span: mi.span.clone(),
+                };
+                expanded.extend([
+                    s(StatementType::Literal(slot)),
+                    s(StatementType::Opcode(load)),
+                    s(StatementType::Opcode(Opcode::Iszero)),
+                    s(StatementType::LabelCall(label)),
+                    s(StatementType::Opcode(Opcode::Jumpi)),
+                ]);
+                expanded.extend(PanicCode::ReentrantCall.revert_statements(&span));
+                expanded.push(s(StatementType::Label(Label {
+                    name: label,
+                    inner: vec![
+                        s(StatementType::Literal(locked)),
+                        s(StatementType::Literal(slot)),
+                        s(StatementType::Opcode(store)),
+                        s(StatementType::MacroInvocation(invocation)),
+                        s(StatementType::Literal([0u8; 32])),
+                        s(StatementType::Literal(slot)),
+                        s(StatementType::Opcode(store)),
+                    ],
+                    span: mi.span,
+                })));
+            }
+            StatementType::Label(mut l) => {
+                l.inner = expand_guards(l.inner, evm_version, guards);
+                expanded.push(s(StatementType::Label(l)));
+            }
+            ty => expanded.push(s(ty)),
+        }
+    }
+    expanded
+}
+
+/// Recursively expands the `__REPEAT`, `__IFDEF` and `__IFNDEF` blocks of a list of statements
+fn expand_block_statements(
+    statements: Vec<Statement>,
+    constants: &Arena<ConstantDefinition>,
+    flags: &mut BTreeSet<String>,
+    repeat_counts: &mut BTreeSet<String>,
+    errors: &mut Vec<ParserError>,
+) -> Vec<Statement> {
+    let mut expanded = vec![];
+    for statement in statements {
+        match statement.ty {
+            StatementType::Repeat(r) => {
+                if let MacroArg::Constant(name) = &r.count {
+                    repeat_counts.insert(name.to_string());
+                }
+                let count = match repeat_count(&r.count, constants) {
+                    Some(count) => count,
+                    None => {
+                        let count = match &r.count {
+                            MacroArg::Literal(l) => bytes32_to_string(l, true),
+                            MacroArg::Constant(name) => format!("[{}]", name),
+                            MacroArg::Ident(name) | MacroArg::ArgCall(name) => name.clone(),
+                        };
+                        tracing::error!(target: "ast", "INVALID REPEAT COUNT: {}", count);
+                        errors.push(ParserError {
+                            kind: ParserErrorKind::InvalidRepeatCount(count),
+                            spans: r.span,
+                        });
+                        continue
                     }
+                };
+                tracing::info!(target: "ast", "REPEATING {} STATEMENTS {} TIMES", r.inner.len(), count);
+                for i in 0..count {
+                    let mut inner = r.inner.clone();
+                    if let Some(index) = &r.index {
+                        let mut literal = [0u8; 32];
+                        literal[24..].copy_from_slice(&(i as u64).to_be_bytes());
+                        let mut injection = RepeatIndexInjection { index, literal };
+                        inner.iter_mut().for_each(|s| injection.visit_statement_mut(s));
+                    }
+                    expanded.extend(expand_block_statements(
+                        inner,
+                        constants,
+                        flags,
+                        repeat_counts,
+                        errors,
+                    ));
+                }
+            }
+            StatementType::Conditional(c) => {
+                let defined = constants.iter().any(|d| d.name == c.constant);
+                tracing::info!(target: "ast", "CONSTANT \"{}\" DEFINED: {}", c.constant, defined);
+                if defined == c.defined {
+                    expanded.extend(expand_block_statements(
+                        c.inner,
+                        constants,
+                        flags,
+                        repeat_counts,
+                        errors,
+                    ));
                 }
-                StatementType::BuiltinFunctionCall(bfc) => {
-                    tracing::debug!(target: "ast", "Deriving Storage Pointrs: Found builtin function {:?}", bfc.kind);
-                    for a in &bfc.args {
-                        if let Some(name) = &a.name {
-                            match self
-                                .macros
-                                .iter()
-                                .filter(|md| md.name.eq(name))
-                                .collect::<Vec<&MacroDefinition>>()
-                                .get(0)
-                            {
-                                Some(&md) => {
-                                    self.recurse_ast_constants(md, storage_pointers, last_p)
-                                }
-                                None => {
-                                    tracing::warn!(target: "ast", "BUILTIN HAS ARG NAME \"{}\" BUT NOT FOUND IN AST!", name)
-                                }
-                            }
+                flags.insert(c.constant.to_string());
+            }
+            StatementType::Label(mut l) => {
+                l.inner =
+                    expand_block_statements(l.inner, constants, flags, repeat_counts, errors);
+                expanded.push(Statement { ty: StatementType::Label(l), span: statement.span });
+            }
+            ty => expanded.push(Statement { ty, span: statement.span }),
+        }
+    }
+    expanded
+}
+
+/// The number of repetitions of a `__REPEAT` block, if its count is a literal of at most
+/// [MAX_REPEAT_COUNT]
+fn repeat_count(count: &MacroArg, constants: &Arena<ConstantDefinition>) -> Option<usize> {
+    let literal = match count {
+        MacroArg::Literal(l) => l,
+        MacroArg::Constant(name) => match constants.iter().find(|c| &c.name == name) {
+            Some(ConstantDefinition { value: ConstVal::Literal(l), .. }) => l,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    if literal[..24].iter().any(|b| *b != 0) {
+        return None
+    }
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&literal[24..]);
+    usize::try_from(u64::from_be_bytes(bytes)).ok().filter(|c| *c <= MAX_REPEAT_COUNT)
+}
+
+/// Derives the slots of free storage pointers, in the order their constants are first pushed
+/// walking the macros expanded from a root macro
+struct StoragePointerDerivation<'a> {
+    contract: &'a Contract,
+    storage_pointers: Vec<(String, [u8; 32])>,
+    last_p: i32,
+    expanding: Vec<SymbolId>,
+    cycles: Vec<CodegenError>,
+}
+
+impl<'a> StoragePointerDerivation<'a> {
+    fn new(contract: &'a Contract) -> Self {
+        Self { contract, storage_pointers: vec![], last_p: 0, expanding: vec![], cycles: vec![] }
+    }
+
+    /// Derives the constants pushed in the expansion of a root macro
+    fn derive(&mut self, macro_def: &MacroDefinition) {
+        self.expanding = vec![macro_def.name];
+        self.visit_macro(macro_def);
+    }
+
+    /// Walks a macro expanded at a span, recording a cycle if it is already being walked
+    ///
+    /// Returns whether the contract defines the macro.
+    fn expand(&mut self, name: &str, span: &AstSpan) -> bool {
+        let Some(md) = self.contract.macros.iter().find(|md| md.name == name) else {
+            return false
+        };
+        if self.expanding.contains(&md.name) {
+            Contract::record_cycle(md.name, span, &self.expanding, &mut self.cycles);
+        } else {
+            self.expanding.push(md.name);
+            self.visit_macro(md);
+            self.expanding.pop();
+        }
+        true
+    }
+}
+
+impl Visitor for StoragePointerDerivation<'_> {
+    fn visit_constant_ref(&mut self, name: &str, _span: &AstSpan) {
+        self.contract.derive_constant(name, &mut self.storage_pointers, &mut self.last_p);
+    }
+
+    // Constants passed as arguments are derived before the invoked macro
+    fn visit_macro_invocation(&mut self, invocation: &MacroInvocation) {
+        tracing::debug!(target: "ast", "Found macro invocation: \"{}\" in macro def: \"{}\"!", invocation.macro_name, self.expanding.last().cloned().unwrap_or_default());
+        invocation.args.iter().for_each(|a| self.visit_macro_arg(a, &invocation.span));
+        if !self.expand(&invocation.macro_name, &invocation.span) {
+            tracing::warn!(target: "ast", "MACRO \"{}\" INVOKED BUT NOT FOUND IN AST!", invocation.macro_name)
+        }
+    }
+
+    // Only the macros named by the arguments are walked, not the constants of expressions
+    fn visit_builtin(&mut self, builtin: &BuiltinFunctionCall) {
+        tracing::debug!(target: "ast", "Deriving Storage Pointrs: Found builtin function {:?}", builtin.kind);
+        for name in builtin.args.iter().filter_map(|a| a.name.as_ref()) {
+            if !self.expand(name, &builtin.span) {
+                tracing::warn!(target: "ast", "BUILTIN HAS ARG NAME \"{}\" BUT NOT FOUND IN AST!", name)
+            }
+        }
+    }
+}
+
+/// Injects the index of a repetition in place of the uses of the index constant of a repeat,
+/// except in nested repeats with an index of the same name
+struct RepeatIndexInjection<'a> {
+    index: &'a str,
+    literal: Literal,
+}
+
+impl VisitMut for RepeatIndexInjection<'_> {
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        match &statement.ty {
+            StatementType::Constant(name) if name == self.index => {
+                statement.ty = StatementType::Literal(self.literal);
+            }
+            _ => walk_statement_mut(self, statement),
+        }
+    }
+
+    fn visit_macro_arg_mut(&mut self, arg: &mut MacroArg, _span: &AstSpan) {
+        if matches!(arg, MacroArg::Constant(name) if name == self.index) {
+            *arg = MacroArg::Literal(self.literal);
+        }
+    }
+
+    fn visit_repeat_mut(&mut self, repeat: &mut Repeat) {
+        self.visit_macro_arg_mut(&mut repeat.count, &repeat.span);
+        if repeat.index.as_deref() != Some(self.index) {
+            repeat.inner.iter_mut().for_each(|s| self.visit_statement_mut(s));
+        }
+    }
+}
+
+/// Recursively expands the label calls naming an alias in a list of statements
+fn expand_alias_uses(
+    statements: Vec<Statement>,
+    aliases: &Arena<AliasDefinition>,
+    uses: &mut usize,
+) -> Vec<Statement> {
+    let mut expanded = vec![];
+    for mut statement in statements {
+        match &mut statement.ty {
+            StatementType::LabelCall(name) => {
+                if let Some(alias) = aliases.iter().find(|a| &a.name == name) {
+                    *uses += 1;
+                    expanded.extend(alias.statements.iter().map(|s| Statement {
+                        ty: s.ty.clone(),
+                        span: AstSpan([statement.span.0.clone(), s.span.0.clone()].concat()),
+                    }));
+                    continue
+                }
+            }
+            StatementType::Label(l) => {
+                l.inner = expand_alias_uses(std::mem::take(&mut l.inner), aliases, uses);
+            }
+            _ => {}
+        }
+        expanded.push(statement);
+    }
+    expanded
+}
+
+/// The macros and constants defined by a namespaced file, by their unqualified name
+struct NamespaceScope<'a> {
+    name: &'a str,
+    macros: BTreeSet<String>,
+    constants: BTreeSet<String>,
+}
+
+impl<'a> NamespaceScope<'a> {
+    fn new(name: &'a str) -> Self {
+        Self { name, macros: BTreeSet::new(), constants: BTreeSet::new() }
+    }
+
+    fn qualify_macro(&self, name: &mut String) {
+        if self.macros.contains(name) {
+            *name = format!("{}.{}", self.name, name);
+        }
+    }
+
+    fn qualify_constant(&self, name: &mut SymbolId) {
+        if self.constants.contains(name.as_str()) {
+            *name = format!("{}.{}", self.name, name).into();
+        }
+    }
+}
+
+/// Recursively qualifies the references of a list of statements to the definitions of their
+/// namespace
+fn qualify_statements(statements: &mut [Statement], scope: &NamespaceScope) {
+    for statement in statements.iter_mut() {
+        match &mut statement.ty {
+            StatementType::MacroInvocation(mi) => {
+                if scope.macros.contains(mi.macro_name.as_str()) {
+                    mi.macro_name = format!("{}.{}", scope.name, mi.macro_name).into();
+                }
+                for arg in mi.args.iter_mut() {
+                    if let MacroArg::Constant(c) = arg {
+                        scope.qualify_constant(c);
+                    }
+                }
+            }
+            StatementType::Constant(c) => scope.qualify_constant(c),
+            StatementType::Label(l) => qualify_statements(&mut l.inner, scope),
+            StatementType::BuiltinFunctionCall(bf) => qualify_builtin(bf, scope),
+            StatementType::Repeat(r) => {
+                if let MacroArg::Constant(c) = &mut r.count {
+                    scope.qualify_constant(c);
+                }
+                qualify_statements(&mut r.inner, scope);
+            }
+            StatementType::Conditional(c) => {
+                scope.qualify_constant(&mut c.constant);
+                qualify_statements(&mut c.inner, scope);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Qualifies the macros named by the arguments of a builtin, and the constants of its expression
+fn qualify_builtin(bf: &mut BuiltinFunctionCall, scope: &NamespaceScope) {
+    for arg in bf.args.iter_mut() {
+        if let Some(name) = &mut arg.name {
+            scope.qualify_macro(name);
+        }
+    }
+    if let BuiltinFunctionKind::Assert(e) | BuiltinFunctionKind::Offset(e) = &mut bf.kind {
+        qualify_expression(e, scope);
+    }
+}
+
+/// Recursively qualifies the constants and builtins of an expression
+fn qualify_expression(expression: &mut Expression, scope: &NamespaceScope) {
+    match expression {
+        Expression::Constant(c) => scope.qualify_constant(c),
+        Expression::BuiltinFunctionCall(bf) => qualify_builtin(bf, scope),
+        Expression::Not(e) => qualify_expression(e, scope),
+        Expression::Binary(_, left, right) => {
+            qualify_expression(left, scope);
+            qualify_expression(right, scope);
+        }
+        _ => {}
+    }
+}
+
+/// The macros and constants referenced by definitions, with the spans referencing them
+#[derive(Default)]
+struct References<'a> {
+    macros: Vec<(&'a str, &'a AstSpan)>,
+    constants: Vec<(&'a str, &'a AstSpan)>,
+}
+
+impl<'a> References<'a> {
+    /// Recursively collects the references of a list of statements
+    fn collect_statements(&mut self, statements: &'a [Statement]) {
+        for statement in statements.iter() {
+            let span = &statement.span;
+            match &statement.ty {
+                StatementType::MacroInvocation(mi) => {
+                    self.macros.push((&mi.macro_name, span));
+                    for arg in mi.args.iter() {
+                        if let MacroArg::Constant(c) = arg {
+                            self.constants.push((c, span));
                         }
                     }
                 }
-                StatementType::Label(l) => {
-                    for state in l.inner.iter().rev() {
-                        statements.insert(i + 1, state.clone());
+                StatementType::Constant(c) => self.constants.push((c, span)),
+                StatementType::Label(l) => self.collect_statements(&l.inner),
+                StatementType::BuiltinFunctionCall(bf) => self.collect_builtin(bf, span),
+                StatementType::Repeat(r) => {
+                    if let MacroArg::Constant(c) = &r.count {
+                        self.constants.push((c, span));
                     }
+                    self.collect_statements(&r.inner);
+                }
+                StatementType::Conditional(c) => {
+                    self.constants.push((&c.constant, span));
+                    self.collect_statements(&c.inner);
                 }
                 _ => {}
             }
-            i += 1;
         }
+    }
 
-        // Breadth-first
-        // if !macros_to_recurse.is_empty() {
-        //     let next_md = macros_to_recurse.remove(0);
-        //     self.recurse_ast_constants(next_md, storage_pointers, last_p, macros_to_recurse);
-        // }
+    /// Collects the macros named by the arguments of a builtin, and the constants of its
+    /// expression
+    fn collect_builtin(&mut self, bf: &'a BuiltinFunctionCall, span: &'a AstSpan) {
+        for arg in bf.args.iter() {
+            if let Some(name) = &arg.name {
+                self.macros.push((name, span));
+            }
+        }
+        if let BuiltinFunctionKind::Assert(e) | BuiltinFunctionKind::Offset(e) = &bf.kind {
+            self.collect_expression(e, span);
+        }
+    }
+
+    /// Recursively collects the constants and builtins of an expression
+    fn collect_expression(&mut self, expression: &'a Expression, span: &'a AstSpan) {
+        match expression {
+            Expression::Constant(c) => self.constants.push((c, span)),
+            Expression::BuiltinFunctionCall(bf) => self.collect_builtin(bf, span),
+            Expression::Not(e) => self.collect_expression(e, span),
+            Expression::Binary(_, left, right) => {
+                self.collect_expression(left, span);
+                self.collect_expression(right, span);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Recursively appends the defaults of the parameters omitted by the macro invocations of a list
+/// of statements
+fn fill_invocation_defaults(
+    statements: &mut [Statement],
+    defaults: &BTreeMap<String, Vec<Option<MacroArg>>>,
+) {
+    for statement in statements.iter_mut() {
+        match &mut statement.ty {
+            StatementType::MacroInvocation(mi) => {
+                let Some(params) = defaults.get(mi.macro_name.as_str()) else { continue };
+                let omitted = params.get(mi.args.len()..).unwrap_or_default();
+                if let Some(args) = omitted.iter().cloned().collect::<Option<Vec<MacroArg>>>() {
+                    mi.args.extend(args);
+                }
+            }
+            StatementType::Label(l) => fill_invocation_defaults(&mut l.inner, defaults),
+            _ => {}
+        }
     }
 }
 
 /// A function, event, or macro argument
-#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Argument {
     /// Type of the argument
     pub arg_type: Option<String>,
@@ -293,16 +1381,19 @@ pub struct Argument {
     pub name: Option<String>,
     /// Is the argument indexed? TODO: should be valid for event arguments ONLY
     pub indexed: bool,
+    /// The default of a macro parameter, passed by the invocations omitting it
+    pub default: Option<MacroArg>,
     /// The argument span
     pub span: AstSpan,
 }
 
 /// A Function Signature
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Function {
     /// The name of the function
-    pub name: String,
+    pub name: SymbolId,
     /// The function signature
+    #[serde(with = "hex_bytes")]
     pub signature: [u8; 4],
     /// The parameters of the function
     pub inputs: Vec<Argument>,
@@ -327,8 +1418,19 @@ pub enum FunctionType {
     Pure,
 }
 
+impl Display for FunctionType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FunctionType::View => write!(f, "view"),
+            FunctionType::Payable => write!(f, "payable"),
+            FunctionType::NonPayable => write!(f, "nonpayable"),
+            FunctionType::Pure => write!(f, "pure"),
+        }
+    }
+}
+
 /// An Event Signature
-#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Event {
     /// The name of the event
     pub name: String,
@@ -338,16 +1440,31 @@ pub struct Event {
     pub span: AstSpan,
 }
 
+/// A Custom Error Definition
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ErrorDefinition {
+    /// The name of the error
+    pub name: String,
+    /// The selector of the error, the first 4 bytes of the keccak256 hash of its signature
+    #[serde(with = "hex_bytes")]
+    pub selector: [u8; 4],
+    /// The parameters of the error
+    pub parameters: Vec<Argument>,
+    /// The error span
+    pub span: AstSpan,
+}
+
 /// A Table Definition
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct TableDefinition {
     /// The name of the table
-    pub name: String,
+    pub name: SymbolId,
     /// The table kind
     pub kind: TableKind,
     /// The table's statements
     pub statements: Vec<Statement>,
     /// Size of table
+    #[serde(with = "hex_bytes")]
     pub size: Literal,
     /// The table span
     pub span: AstSpan,
@@ -362,12 +1479,12 @@ impl TableDefinition {
         size: Literal,
         span: AstSpan,
     ) -> Self {
-        TableDefinition { name, kind, statements, size, span }
+        TableDefinition { name: name.into(), kind, statements, size, span }
     }
 }
 
 /// A Table Kind
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TableKind {
     /// A regular jump table
     JumpTable,
@@ -390,10 +1507,10 @@ impl From<TokenKind> for TableKind {
 }
 
 /// A Macro Definition
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct MacroDefinition {
     /// The Macro Name
-    pub name: String,
+    pub name: SymbolId,
     /// A list of Macro parameters
     pub parameters: Vec<Argument>,
     /// A list of Statements contained in the Macro
@@ -404,6 +1521,8 @@ pub struct MacroDefinition {
     pub returns: usize,
     /// The Span of the Macro Definition
     pub span: AstSpan,
+    /// Whether the Macro is hidden from the files including its own
+    pub internal: bool,
 }
 
 impl ToIRBytecode<CodegenError> for MacroDefinition {
@@ -423,7 +1542,15 @@ impl MacroDefinition {
         returns: usize,
         spans: Vec<Span>,
     ) -> Self {
-        MacroDefinition { name, parameters, statements, takes, returns, span: AstSpan(spans) }
+        MacroDefinition {
+            name: name.into(),
+            parameters,
+            statements,
+            takes,
+            returns,
+            span: AstSpan(spans),
+            internal: false,
+        }
     }
 
     /// Translate statements into IRBytes
@@ -433,17 +1560,14 @@ impl MacroDefinition {
         statements.iter().for_each(|statement| {
             match &statement.ty {
                 StatementType::Literal(l) => {
-                    let hex_literal: String = bytes32_to_string(l, false);
-                    let push_bytes = format!("{:02x}{}", 95 + hex_literal.len() / 2, hex_literal);
                     inner_irbytes.push(IRBytes {
-                        ty: IRByteType::Bytes(Bytes(push_bytes)),
+                        ty: IRByteType::Bytes(Bytes::push(l)),
                         span: statement.span.clone(),
                     });
                 }
                 StatementType::Opcode(o) => {
-                    let opcode_str = o.string();
                     inner_irbytes.push(IRBytes {
-                        ty: IRByteType::Bytes(Bytes(opcode_str)),
+                        ty: IRByteType::Bytes(Bytes::from(*o)),
                         span: statement.span.clone(),
                     });
                 }
@@ -459,7 +1583,7 @@ impl MacroDefinition {
                 StatementType::Constant(name) => {
                     // Constant needs to be evaluated at the top-level
                     inner_irbytes.push(IRBytes {
-                        ty: IRByteType::Constant(name.to_string()),
+                        ty: IRByteType::Constant(name.into()),
                         span: statement.span.clone(),
                     });
                 }
@@ -474,7 +1598,7 @@ impl MacroDefinition {
                     /* Jump To doesn't translate directly to bytecode ? */
                     inner_irbytes.push(IRBytes {
                         ty: IRByteType::Statement(Statement {
-                            ty: StatementType::LabelCall(jump_to.to_string()),
+                            ty: StatementType::LabelCall(*jump_to),
                             span: statement.span.clone(),
                         }),
                         span: statement.span.clone(),
@@ -502,6 +1626,19 @@ impl MacroDefinition {
                         span: statement.span.clone(),
                     });
                 }
+                StatementType::Code(code) => {
+                    inner_irbytes.push(IRBytes {
+                        ty: IRByteType::Bytes(Bytes(code.clone())),
+                        span: statement.span.clone(),
+                    });
+                }
+                StatementType::Repeat(_) | StatementType::Conditional(_) => {
+                    // Blocks are expanded by the parser, left to fail if one is not
+                    inner_irbytes.push(IRBytes {
+                        ty: IRByteType::Statement(statement.clone()),
+                        span: statement.span.clone(),
+                    });
+                }
             }
         });
 
@@ -510,10 +1647,10 @@ impl MacroDefinition {
 }
 
 /// A Macro Invocation
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct MacroInvocation {
     /// The Macro Name
-    pub macro_name: String,
+    pub macro_name: SymbolId,
     /// A list of Macro arguments
     pub args: Vec<MacroArg>,
     /// The Macro Invocation Span
@@ -521,53 +1658,178 @@ pub struct MacroInvocation {
 }
 
 /// An argument passed when invoking a maco
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum MacroArg {
     /// Macro Literal Argument
-    Literal(Literal),
+    Literal(#[serde(with = "hex_bytes")] Literal),
     /// Macro Iden String Argument
     Ident(String),
     /// An Arg Call
     ArgCall(String),
+    /// A Constant, resolved when the invocation is expanded
+    Constant(SymbolId),
 }
 
-/// Free Storage Pointer Unit Struct
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct FreeStoragePointer;
+/// A Free Storage Pointer, reserving consecutive storage slots
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FreeStoragePointer {
+    /// The number of slots reserved, from the derived slot, 1 for `FREE_STORAGE_POINTER()`
+    pub size: usize,
+}
+
+impl Default for FreeStoragePointer {
+    fn default() -> Self {
+        Self { size: 1 }
+    }
+}
+
+impl Display for FreeStoragePointer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.size {
+            1 => write!(f, "FREE_STORAGE_POINTER()"),
+            size => write!(f, "FREE_STORAGE_POINTER({})", size),
+        }
+    }
+}
 
 /// A Constant Value
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ConstVal {
     /// A literal value for the constant
-    Literal(Literal),
+    Literal(#[serde(with = "hex_bytes")] Literal),
     /// A Free Storage Pointer
     FreeStoragePointer(FreeStoragePointer),
 }
 
 /// A Constant Definition
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ConstantDefinition {
     /// The Constant name
-    pub name: String,
+    pub name: SymbolId,
     /// The Constant value
     pub value: ConstVal,
     /// The Span of the Constant Definition
     pub span: AstSpan,
+    /// Whether the Constant is hidden from the files including its own
+    pub internal: bool,
+}
+
+impl FromStr for ConstantDefinition {
+    type Err = String;
+
+    /// Parses a constant defined when compiling : `NAME` or `NAME=VALUE`
+    ///
+    /// The value is a hex, binary or decimal literal, defaulting to 1.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s.split_once('=').unwrap_or((s, "1"));
+        let (name, value) = (name.trim(), value.trim());
+        let valid_name = name.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_') &&
+            name.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if !valid_name {
+            return Err(format!("Invalid constant name \"{}\"", name))
+        }
+        let digits = |s: &str, radix: u32| !s.is_empty() && s.chars().all(|c| c.is_digit(radix));
+        let literal = match (value.strip_prefix("0x"), value.strip_prefix("0b")) {
+            (Some(hex), _) if digits(hex, 16) => {
+                Some((hex.len() <= 64).then(|| str_to_bytes32(hex)))
+            }
+            (_, Some(bin)) if digits(bin, 2) => Some(bin_str_to_bytes32(bin)),
+            _ if digits(value, 10) => Some(dec_str_to_bytes32(value)),
+            _ => None,
+        };
+        match literal {
+            Some(Some(l)) => Ok(ConstantDefinition {
+                name: name.into(),
+                value: ConstVal::Literal(l),
+                span: AstSpan::default(),
+                internal: false,
+            }),
+            Some(None) => {
+                Err(format!("Value \"{}\" of constant \"{}\" exceeds 32 bytes", value, name))
+            }
+            None => Err(format!("Invalid value \"{}\" of constant \"{}\"", value, name)),
+        }
+    }
+}
+
+/// An Immutable Definition, like `#define immutable OWNER`
+///
+/// Pushed with `[OWNER]` like a constant, as a zeroed 32 byte placeholder the constructor fills
+/// with `__SET_IMMUTABLE(OWNER)`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ImmutableDefinition {
+    /// The Immutable name
+    pub name: String,
+    /// The Span of the Immutable Definition
+    pub span: AstSpan,
+}
+
+/// An Opcode Alias Definition, like `#define alias fmp = 0x40 mload`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AliasDefinition {
+    /// The Alias name
+    pub name: String,
+    /// The literals, opcodes and constant pushes the alias expands to
+    pub statements: Vec<Statement>,
+    /// The Span of the Alias Definition
+    pub span: AstSpan,
+}
+
+impl AliasDefinition {
+    /// If a statement was expanded from a use of the alias
+    pub fn is_expansion(&self, statement: &Statement) -> bool {
+        statement.span.0.len() == 2 &&
+            self.statements.iter().any(|s| s.span.0.first() == statement.span.0.get(1))
+    }
 }
 
 /// A Jump Destination
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Label {
     /// The JumpDest Name
-    pub name: String,
+    pub name: SymbolId,
     /// Statements Inside The JumpDest
     pub inner: Vec<Statement>,
     /// The label span
     pub span: AstSpan,
 }
 
+/// A block of statements repeated at compile time : `__REPEAT(count, INDEX) { ... }`
+///
+/// Repeats only live in the parser, which expands them once every constant is defined.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Repeat {
+    /// The number of repetitions, a literal or a constant
+    pub count: MacroArg,
+    /// The name of the constant pushing the index of each repetition, counting from zero
+    pub index: Option<String>,
+    /// The repeated statements
+    pub inner: Vec<Statement>,
+    /// The repeat span
+    pub span: AstSpan,
+}
+
+/// A block of statements compiled only if a constant is defined, or only if it isn't :
+/// `__IFDEF(NAME) { ... }` or `__IFNDEF(NAME) { ... }`
+///
+/// Conditionals only live in the parser, which expands them once every constant is defined.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Conditional {
+    /// The name of the tested constant
+    pub constant: SymbolId,
+    /// Whether the block is compiled if the constant is defined, or if it isn't
+    pub defined: bool,
+    /// The statements compiled if the condition holds
+    pub inner: Vec<Statement>,
+    /// The conditional span
+    pub span: AstSpan,
+}
+
+/// The maximum number of repetitions of a `__REPEAT` block, the size limit of a contract
+pub const MAX_REPEAT_COUNT: usize = 0x6000;
+
 /// A Builtin Function Call
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct BuiltinFunctionCall {
     /// The Builtin Kind
     pub kind: BuiltinFunctionKind,
@@ -580,7 +1842,7 @@ pub struct BuiltinFunctionCall {
 }
 
 /// A Builtin Function Kind
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum BuiltinFunctionKind {
     /// Table size function
     Tablesize,
@@ -588,21 +1850,284 @@ pub enum BuiltinFunctionKind {
     Codesize,
     /// Table start function
     Tablestart,
+    /// Asserts the keccak256 hash of a macro or code table's bytecode at compile time
+    AssertCodehash(#[serde(with = "hex_bytes")] Literal),
+    /// Calls a cheatcode of the test runner with the arguments on the stack
+    Cheatcode(Cheatcode),
+    /// Sets an immutable to the value on the stack, in the constructor
+    SetImmutable,
+    /// Pushes the address of an external library, linked after compilation
+    Link,
+    /// Pushes the keccak256 hash of a string or bytes literal, the preimage, at compile time
+    Keccak256(Vec<u8>),
+    /// Pushes a literal padded on the right to 32 bytes
+    RightPad(#[serde(with = "hex_bytes")] Literal),
+    /// Fails compilation if a constant expression is false, when the macro is expanded
+    Assert(Box<Expression>),
+    /// Stores the abi encoding of a string in memory, at the offset on the stack, leaving its size
+    MstoreString(Vec<u8>),
+    /// Reverts with a string reason, encoded as `Error(string)`
+    RevertString(Vec<u8>),
+    /// Pushes an expression of label offsets, as in `__OFFSET(end) - __OFFSET(start)`, once
+    /// the labels are laid out
+    Offset(Box<Expression>),
+    /// Splices raw bytes into the bytecode, unmodified
+    Verbatim(Vec<u8>),
+    /// Loads the parameters of a function from the calldata onto the stack, the first on top
+    DecodeCalldata,
+    /// Stores the outputs of a function, taken from the stack, in memory at the offset on the
+    /// stack, leaving the size stored
+    EncodeOutputs,
+    /// Logs an event with its parameters taken from the stack, the first on top
+    Emit,
+    /// A builtin registered by a library consumer, with the arguments it's invoked with,
+    /// lowered to statements before the macros are expanded
+    Custom(String, Vec<MacroArg>),
 }
 
+/// The names of the builtin functions, callable in macro bodies
+pub const BUILTIN_FUNCTIONS: [&str; 25] = [
+    "__codesize",
+    "__tablesize",
+    "__tablestart",
+    "__ASSERT_CODEHASH",
+    "__SET_IMMUTABLE",
+    "__LINK",
+    "__KECCAK256",
+    "__RIGHTPAD",
+    "__MSTORE_STRING",
+    "__REVERT_STRING",
+    "__OFFSET",
+    "__VERBATIM",
+    "__ASSERT",
+    "__REPEAT",
+    "__IFDEF",
+    "__IFNDEF",
+    "__PRANK",
+    "__DEAL",
+    "__WARP",
+    "__ROLL",
+    "__EXPECT_REVERT",
+    "__EXPECT_EMIT",
+    "__DECODE_CALLDATA",
+    "__ENCODE_OUTPUTS",
+    "__EMIT",
+];
+
 impl From<&str> for BuiltinFunctionKind {
     fn from(s: &str) -> Self {
         match s {
             "__tablesize" => BuiltinFunctionKind::Tablesize,
             "__codesize" => BuiltinFunctionKind::Codesize,
             "__tablestart" => BuiltinFunctionKind::Tablestart,
-            _ => panic!("Invalid Builtin Function Kind"), // TODO: Better error handling
+            "__SET_IMMUTABLE" => BuiltinFunctionKind::SetImmutable,
+            "__LINK" => BuiltinFunctionKind::Link,
+            "__DECODE_CALLDATA" => BuiltinFunctionKind::DecodeCalldata,
+            "__ENCODE_OUTPUTS" => BuiltinFunctionKind::EncodeOutputs,
+            "__EMIT" => BuiltinFunctionKind::Emit,
+            s => match Cheatcode::from_builtin(s) {
+                Some(cheatcode) => BuiltinFunctionKind::Cheatcode(cheatcode),
+                None => panic!("Invalid Builtin Function Kind"), // TODO: Better error handling
+            },
         }
     }
 }
 
+/// A constant expression, evaluated at compile time by `__ASSERT`
+///
+/// Values are 32 byte words, like on the stack: arithmetic wraps and division by zero is zero,
+/// comparisons and logical operators evaluate to 1 or 0, and any non-zero value is true.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Expression {
+    /// A hex literal or a decimal number
+    Literal(#[serde(with = "hex_bytes")] Literal),
+    /// A constant, as in `[OWNER]`
+    Constant(SymbolId),
+    /// A builtin known at compile time: `__tablesize`, `__codesize`, `__KECCAK256` or
+    /// `__RIGHTPAD`
+    BuiltinFunctionCall(BuiltinFunctionCall),
+    /// The offset of a label, as in `__OFFSET(end)`, only known once the label is laid out
+    LabelOffset(SymbolId),
+    /// The logical negation of an expression, as in `!x`
+    Not(Box<Expression>),
+    /// A binary operation
+    Binary(BinaryOperator, Box<Expression>, Box<Expression>),
+}
+
+/// The operator of a binary expression
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BinaryOperator {
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`
+    Div,
+    /// `==`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `<`
+    Less,
+    /// `<=`
+    LessEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterEqual,
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+}
+
+impl BinaryOperator {
+    /// The operator of a token, with its precedence, higher binding tighter
+    pub fn from_token(kind: &TokenKind) -> Option<(Self, usize)> {
+        let operator = match kind {
+            TokenKind::Or => (BinaryOperator::Or, 0),
+            TokenKind::And => (BinaryOperator::And, 1),
+            TokenKind::Equal => (BinaryOperator::Equal, 2),
+            TokenKind::NotEqual => (BinaryOperator::NotEqual, 2),
+            TokenKind::LeftAngle => (BinaryOperator::Less, 3),
+            TokenKind::LessEqual => (BinaryOperator::LessEqual, 3),
+            TokenKind::RightAngle => (BinaryOperator::Greater, 3),
+            TokenKind::GreaterEqual => (BinaryOperator::GreaterEqual, 3),
+            TokenKind::Add => (BinaryOperator::Add, 4),
+            TokenKind::Sub => (BinaryOperator::Sub, 4),
+            TokenKind::Mul => (BinaryOperator::Mul, 5),
+            TokenKind::Div => (BinaryOperator::Div, 5),
+            _ => return None,
+        };
+        Some(operator)
+    }
+
+    /// Whether the operator compares its operands
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            BinaryOperator::Equal |
+                BinaryOperator::NotEqual |
+                BinaryOperator::Less |
+                BinaryOperator::LessEqual |
+                BinaryOperator::Greater |
+                BinaryOperator::GreaterEqual
+        )
+    }
+}
+
+impl Display for BinaryOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let operator = match self {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Sub => "-",
+            BinaryOperator::Mul => "*",
+            BinaryOperator::Div => "/",
+            BinaryOperator::Equal => "==",
+            BinaryOperator::NotEqual => "!=",
+            BinaryOperator::Less => "<",
+            BinaryOperator::LessEqual => "<=",
+            BinaryOperator::Greater => ">",
+            BinaryOperator::GreaterEqual => ">=",
+            BinaryOperator::And => "&&",
+            BinaryOperator::Or => "||",
+        };
+        write!(f, "{}", operator)
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expression::Literal(l) => write!(f, "{}", bytes32_to_string(l, true)),
+            Expression::Constant(name) => write!(f, "[{}]", name),
+            Expression::LabelOffset(label) => write!(f, "__OFFSET({})", label),
+            Expression::BuiltinFunctionCall(bf) => {
+                let arg = bf.args.first().and_then(|a| a.name.clone()).unwrap_or_default();
+                match &bf.kind {
+                    BuiltinFunctionKind::Tablesize => write!(f, "__tablesize({})", arg),
+                    BuiltinFunctionKind::Codesize => write!(f, "__codesize({})", arg),
+                    BuiltinFunctionKind::Keccak256(preimage) => match std::str::from_utf8(preimage)
+                    {
+                        Ok(s) => write!(f, "__KECCAK256({:?})", s),
+                        Err(_) => write!(f, "__KECCAK256(0x{})", hex::encode(preimage)),
+                    },
+                    BuiltinFunctionKind::RightPad(padded) => {
+                        let len = padded.iter().rposition(|b| *b != 0).map_or(1, |i| i + 1);
+                        write!(f, "__RIGHTPAD(0x{})", hex::encode(&padded[..len]))
+                    }
+                    kind => write!(f, "{:?}", kind),
+                }
+            }
+            Expression::Not(e) => match e.as_ref() {
+                Expression::Binary(..) => write!(f, "!({})", e),
+                e => write!(f, "!{}", e),
+            },
+            Expression::Binary(operator, left, right) => {
+                write!(
+                    f,
+                    "{} {} {}",
+                    Expression::operand(left),
+                    operator,
+                    Expression::operand(right)
+                )
+            }
+        }
+    }
+}
+
+impl Expression {
+    /// The labels whose offsets the expression uses
+    pub fn labels(&self) -> Vec<&str> {
+        match self {
+            Expression::LabelOffset(label) => vec![label.as_str()],
+            Expression::Not(e) => e.labels(),
+            Expression::Binary(_, left, right) => [left.labels(), right.labels()].concat(),
+            _ => vec![],
+        }
+    }
+
+    /// Replaces the label offsets of the expression with literals of the offsets
+    ///
+    /// Returns `None` if a label isn't laid out yet.
+    pub fn resolve_offsets(&self, label_indices: &LabelIndices) -> Option<Expression> {
+        let resolved = match self {
+            Expression::LabelOffset(label) => {
+                Expression::Literal(str_to_bytes32(&format!("{:x}", label_indices.get(label)?)))
+            }
+            Expression::Not(e) => Expression::Not(Box::new(e.resolve_offsets(label_indices)?)),
+            Expression::Binary(operator, left, right) => Expression::Binary(
+                *operator,
+                Box::new(left.resolve_offsets(label_indices)?),
+                Box::new(right.resolve_offsets(label_indices)?),
+            ),
+            e => e.clone(),
+        };
+        Some(resolved)
+    }
+
+    /// An operand of a binary expression, in parentheses if it is a binary expression itself
+    fn operand(e: &Expression) -> String {
+        match e {
+            Expression::Binary(..) => format!("({})", e),
+            e => e.to_string(),
+        }
+    }
+}
+
+/// A top-level `__ASSERT`, as in `__ASSERT(__tablesize(TABLE) <= 0x100)`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Assertion {
+    /// The asserted expression
+    pub expression: Expression,
+    /// The span of the assertion
+    pub span: AstSpan,
+}
+
 /// A Statement
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Statement {
     /// The type of statement
     pub ty: StatementType,
@@ -611,24 +2136,30 @@ pub struct Statement {
 }
 
 /// The Statement Type
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum StatementType {
     /// A Literal Statement
-    Literal(Literal),
+    Literal(#[serde(with = "hex_bytes")] Literal),
     /// An Opcode Statement
     Opcode(Opcode),
     /// A Macro Invocation Statement
     MacroInvocation(MacroInvocation),
     /// A Constant Push
-    Constant(String),
+    Constant(SymbolId),
     /// An Arg Call
     ArgCall(String),
     /// A Label
     Label(Label),
     /// A Label Reference/Call
-    LabelCall(String),
+    LabelCall(SymbolId),
     /// A built-in function call
     BuiltinFunctionCall(BuiltinFunctionCall),
+    /// Raw code read from an external file
+    Code(#[serde(with = "hex_code")] Vec<u8>),
+    /// A block repeated at compile time, expanded by the parser
+    Repeat(Repeat),
+    /// A block compiled depending on a constant, expanded by the parser
+    Conditional(Conditional),
 }
 
 impl Display for StatementType {
@@ -646,6 +2177,9 @@ impl Display for StatementType {
             StatementType::BuiltinFunctionCall(b) => {
                 write!(f, "BUILTIN FUNCTION CALL: {:?}", b.kind)
             }
+            StatementType::Code(c) => write!(f, "CODE: {} BYTES", c.len()),
+            StatementType::Repeat(r) => write!(f, "REPEAT: {} STATEMENTS", r.inner.len()),
+            StatementType::Conditional(c) => write!(f, "CONDITIONAL: {}", c.constant),
         }
     }
 }
@@ -0,0 +1,159 @@
+//! ## Bindings
+//!
+//! The typed bindings of a compiled contract, written by `huffc bindings`.
+//!
+//! Rust bindings are a module expanding the contract's Solidity JSON ABI and bytecode with the
+//! binding macro of `ethers` (`abigen!`) or `alloy` (`sol!`): a contract type with a call builder
+//! for each function, the decoders of its events, and a `deploy` helper sending the embedded
+//! creation bytecode with the ABI encoded constructor arguments.
+//!
+//! TypeScript bindings export the ABI `as const`, typing the calls and events of viem, ethers v6
+//! and other abitype-based clients, along with the bytecode constants. They are written next to
+//! the Hardhat artifact of the contract, which TypeChain generates its typings and factories from.
+//!
+//! Bytecode with unlinked library placeholders can't be embedded, and must be linked with
+//! `huffc link` first.
+
+use std::{collections::BTreeSet, fmt, fs, path::Path, str::FromStr};
+
+use crate::{artifact::Artifact, ast::to_screaming_snake_case};
+
+/// The crates Rust bindings are generated for
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RustBindingsCrate {
+    /// The `abigen!` macro of `ethers`
+    #[default]
+    Ethers,
+    /// The `sol!` macro of `alloy`, with its `json` and `contract` features
+    Alloy,
+}
+
+impl RustBindingsCrate {
+    /// Every crate bindings are generated for
+    pub const ALL: [RustBindingsCrate; 2] = [RustBindingsCrate::Ethers, RustBindingsCrate::Alloy];
+}
+
+impl fmt::Display for RustBindingsCrate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RustBindingsCrate::Ethers => write!(f, "ethers"),
+            RustBindingsCrate::Alloy => write!(f, "alloy"),
+        }
+    }
+}
+
+impl FromStr for RustBindingsCrate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RustBindingsCrate::ALL.into_iter().find(|v| v.to_string() == s.to_lowercase()).ok_or_else(
+            || {
+                format!(
+                    "Unknown bindings crate \"{}\". Available crates: {}",
+                    s,
+                    RustBindingsCrate::ALL.map(|v| v.to_string()).join(", ")
+                )
+            },
+        )
+    }
+}
+
+/// The generated bindings of a contract
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Bindings {
+    /// The name of the contract type
+    pub name: String,
+    /// The name of the file the bindings are written to
+    pub file_name: String,
+    /// The source of the bindings
+    pub source: String,
+}
+
+impl Bindings {
+    /// Generates the Rust bindings of a compiled contract
+    ///
+    /// Returns the libraries left unlinked in the bytecode otherwise.
+    pub fn rust(artifact: &Artifact, krate: RustBindingsCrate) -> Result<Self, Vec<String>> {
+        let name = Bindings::type_name(artifact)?;
+        let file_name = format!("{}.rs", to_screaming_snake_case(&name).to_lowercase());
+        let abi = artifact.abi.as_ref().map(|a| a.json_abi()).unwrap_or_default();
+        let header = format!(
+            "//! Rust bindings of the `{}` contract, generated by `huffc bindings` from \"{}\".\n",
+            name, artifact.file.path
+        );
+        let source = match krate {
+            RustBindingsCrate::Ethers => {
+                let object = serde_json::json!({
+                    "abi": abi,
+                    "bytecode": format!("0x{}", artifact.bytecode),
+                    "deployedBytecode": format!("0x{}", artifact.runtime),
+                });
+                format!(
+                    "{}\nethers::contract::abigen!(\n    {},\n    r#\"{}\"#\n);\n",
+                    header,
+                    name,
+                    serde_json::to_string(&object).unwrap_or_default()
+                )
+            }
+            RustBindingsCrate::Alloy => format!(
+                "{}\nalloy::sol!(\n    #[allow(missing_docs)]\n    #[sol(rpc, bytecode = \"{}\", deployed_bytecode = \"{}\")]\n    {},\n    r#\"{}\"#\n);\n",
+                header,
+                artifact.bytecode,
+                artifact.runtime,
+                name,
+                serde_json::to_string(&abi).unwrap_or_default()
+            ),
+        };
+        Ok(Self { name, file_name, source })
+    }
+
+    /// Generates the TypeScript bindings of a compiled contract
+    ///
+    /// Returns the libraries left unlinked in the bytecode otherwise.
+    pub fn typescript(artifact: &Artifact) -> Result<Self, Vec<String>> {
+        let name = Bindings::type_name(artifact)?;
+        let prefix = to_screaming_snake_case(&name);
+        let abi = artifact.abi.as_ref().map(|a| a.json_abi()).unwrap_or_default();
+        let source = format!(
+            "// TypeScript bindings of the `{}` contract, generated by `huffc bindings` from \"{}\".\n\nexport const {}_ABI = {} as const;\n\nexport type {}Abi = typeof {}_ABI;\n\nexport const {}_BYTECODE = \"0x{}\" as const;\n\nexport const {}_DEPLOYED_BYTECODE = \"0x{}\" as const;\n",
+            name,
+            artifact.file.path,
+            prefix,
+            serde_json::to_string_pretty(&abi).unwrap_or_default(),
+            name,
+            prefix,
+            prefix,
+            artifact.bytecode,
+            prefix,
+            artifact.runtime
+        );
+        Ok(Self { file_name: format!("{}.ts", name), name, source })
+    }
+
+    /// The name of the contract type, its file stem, if the bytecode is fully linked
+    fn type_name(artifact: &Artifact) -> Result<String, Vec<String>> {
+        let unlinked = artifact
+            .link_references
+            .keys()
+            .chain(artifact.runtime_link_references.keys())
+            .cloned()
+            .collect::<BTreeSet<String>>();
+        if !unlinked.is_empty() {
+            return Err(unlinked.into_iter().collect())
+        }
+        Ok(artifact
+            .contract_name()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect())
+    }
+
+    /// Exports the bindings to a Rust source file
+    pub fn export(&self, out: &str) -> Result<(), std::io::Error> {
+        let file_path = Path::new(out);
+        if let Some(p) = file_path.parent() {
+            fs::create_dir_all(p)?
+        }
+        fs::write(file_path, &self.source)
+    }
+}
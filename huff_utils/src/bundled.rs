@@ -0,0 +1,28 @@
+//! ## Bundled Modules
+//!
+//! The standard library of Huff modules embedded in the compiler.
+//!
+//! Bundled modules are included with their path between angle brackets, as in
+//! `#include <std/SafeMath.huff>`, and are read from the compiler binary rather than the
+//! filesystem. Their file sources keep the angle brackets in their path, telling them apart from
+//! the files on disk.
+
+/// The bundled modules, by path
+pub const BUNDLED_MODULES: [(&str, &str); 5] = [
+    ("std/Auth.huff", include_str!("../std/Auth.huff")),
+    ("std/ERC20.huff", include_str!("../std/ERC20.huff")),
+    ("std/ERC721.huff", include_str!("../std/ERC721.huff")),
+    ("std/Proxy.huff", include_str!("../std/Proxy.huff")),
+    ("std/SafeMath.huff", include_str!("../std/SafeMath.huff")),
+];
+
+/// The source of the bundled module at a path, with or without its angle brackets
+pub fn bundled_module(path: &str) -> Option<&'static str> {
+    let path = path.strip_prefix('<').and_then(|p| p.strip_suffix('>')).unwrap_or(path);
+    BUNDLED_MODULES.iter().find(|(p, _)| *p == path).map(|(_, source)| *source)
+}
+
+/// Whether an include path is the path of a bundled module, between angle brackets
+pub fn is_bundled_path(path: &str) -> bool {
+    path.starts_with('<') && path.ends_with('>')
+}
@@ -2,15 +2,67 @@
 //!
 //! Abstract translating state into bytecode.
 
-use crate::prelude::{AstSpan, Statement};
+use crate::prelude::{
+    AstSpan, CodegenError, Expression, MacroInvocation, Opcode, Statement, SymbolId,
+};
 use std::{
     collections::BTreeMap,
     fmt::{self, Display},
 };
 
-/// A string of Bytes
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Bytes(pub String);
+/// A buffer of generated bytes, hex encoded once the bytecode is assembled
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Bytes {
+    /// Pushes a word with the smallest push opcode holding its significant bytes, at least one
+    pub fn push(word: &[u8]) -> Self {
+        let start = word.iter().position(|b| *b != 0).unwrap_or(word.len().saturating_sub(1));
+        // `PUSHn` is `0x5f + n`
+        let mut bytes = vec![0x5f + (word.len() - start) as u8];
+        bytes.extend_from_slice(&word[start..]);
+        Bytes(bytes)
+    }
+
+    /// A `PUSH2` of a jump destination, table start or label offset, zeroed until it is filled
+    pub fn placeholder() -> Self {
+        Bytes(vec![Opcode::Push2.byte(), 0, 0])
+    }
+
+    /// Writes a two byte value over the placeholder starting at an index of the bytes, returning
+    /// whether a placeholder was there
+    pub fn fill(&mut self, index: usize, value: u16) -> bool {
+        match self.0.get_mut(index..index + 3) {
+            Some(push) if push[0] == Opcode::Push2.byte() => {
+                push[1..].copy_from_slice(&value.to_be_bytes());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The number of bytes
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no bytes
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Opcode> for Bytes {
+    fn from(o: Opcode) -> Self {
+        Bytes(vec![o.byte()])
+    }
+}
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.0))
+    }
+}
 
 /// Intermediate Bytecode Representation
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -29,7 +81,7 @@ pub enum IRByteType {
     /// Macro Statement to be expanded
     Statement(Statement),
     /// A Constant to be referenced
-    Constant(String),
+    Constant(SymbolId),
     /// An Arg Call needs to use the calling macro context
     ArgCall(String),
 }
@@ -60,7 +112,7 @@ pub trait ToBytecode<'a, E> {
 
 impl From<Vec<Bytes>> for Bytecode {
     fn from(b: Vec<Bytes>) -> Self {
-        Bytecode(b.iter().fold("".to_string(), |acc, b| format!("{}{}", acc, b.0)))
+        Bytecode(hex::encode(b.into_iter().flat_map(|b| b.0).collect::<Vec<u8>>()))
     }
 }
 
@@ -76,6 +128,12 @@ pub struct BytecodeRes {
     pub unmatched_jumps: Jumps,
     /// Table Instances
     pub table_instances: Jumps,
+    /// Offset expressions whose labels aren't laid out yet
+    pub unmatched_offsets: OffsetExpressions,
+    /// Errors recovered from while generating the bytes
+    pub errors: Vec<CodegenError>,
+    /// The statements that generated the bytes
+    pub source_map: Vec<SourceMapping>,
 }
 
 impl Display for BytecodeRes {
@@ -87,11 +145,13 @@ impl Display for BytecodeRes {
             label_indices: {:?},
             unmatched_jumps: {:?}
             table_instances: {:?}
+            errors: {:?}
         )"#,
-            self.bytes.iter().fold("".to_string(), |acc, b| format!("{}{}", acc, b.0)),
+            self.bytes.iter().map(|(_, b)| b.to_string()).collect::<String>(),
             self.label_indices,
             self.unmatched_jumps,
-            self.table_instances
+            self.table_instances,
+            self.errors
         )
     }
 }
@@ -100,7 +160,7 @@ impl Display for BytecodeRes {
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Jump {
     /// Jump's Label
-    pub label: String,
+    pub label: SymbolId,
     /// Index of jump within bytecode
     pub bytecode_index: usize,
     /// The Jump Span
@@ -110,8 +170,59 @@ pub struct Jump {
 /// Type for a vec of `Jump`s
 pub type Jumps = Vec<Jump>;
 
+/// A push of an expression of label offsets, filled once its labels are laid out
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OffsetExpression {
+    /// The expression pushed
+    pub expression: Expression,
+    /// Index of the push within bytecode
+    pub bytecode_index: usize,
+    /// The Offset Expression Span
+    pub span: AstSpan,
+}
+
+/// Type for a vec of `OffsetExpression`s
+pub type OffsetExpressions = Vec<OffsetExpression>;
+
+/// A placeholder in generated bytes, filled in once all bytes are generated
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Relocation {
+    /// A jump destination, filled with the offset of the label
+    JumpLabel(SymbolId),
+    /// A table start, filled with the offset of the table
+    TableStart(String),
+    /// An immutable, filled by the constructor with the value it sets
+    Immutable(String),
+    /// An expression of label offsets, filled with its value
+    LabelOffsets(String),
+}
+
+/// Maps a range of generated bytecode to the statement that generated it
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SourceMapping {
+    /// The bytecode offset of the first byte
+    pub offset: usize,
+    /// The number of bytes
+    pub size: usize,
+    /// The span of the statement, or of the table definition for table bytes
+    pub span: AstSpan,
+    /// The name of the table, for table bytes
+    pub table: Option<String>,
+    /// The macro invocations the bytes were generated through, outermost first
+    pub invocations: Vec<MacroInvocation>,
+    /// The placeholder filled in the bytes, if any
+    pub relocation: Option<Relocation>,
+}
+
+impl SourceMapping {
+    /// Whether the bytecode offset falls in the mapped bytes
+    pub fn contains(&self, offset: usize) -> bool {
+        (self.offset..self.offset + self.size).contains(&offset)
+    }
+}
+
 /// Type to map `Jump` labels to their bytecode indices
-pub type LabelIndices = BTreeMap<String, usize>;
+pub type LabelIndices = BTreeMap<SymbolId, usize>;
 
 /// Type for a map of bytecode indexes to `Jumps`. Represents a Jump Table.
 pub type JumpTable = BTreeMap<usize, Jumps>;
@@ -17,6 +17,26 @@ pub fn str_to_bytes32(s: &str) -> [u8; 32] {
     padded
 }
 
+/// Convert a decimal string slice to a `[u8; 32]`, `None` if it exceeds 32 bytes.
+pub fn dec_str_to_bytes32(s: &str) -> Option<[u8; 32]> {
+    let value = ethers_core::types::U256::from_dec_str(s).ok()?;
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    Some(bytes)
+}
+
+/// Convert a binary string slice to a `[u8; 32]`, `None` if it has more than 256 digits.
+pub fn bin_str_to_bytes32(s: &str) -> Option<[u8; 32]> {
+    if s.len() > 256 {
+        return None
+    }
+    let mut bytes = [0u8; 32];
+    for (i, digit) in s.bytes().rev().enumerate() {
+        bytes[31 - i / 8] |= (digit - b'0') << (i % 8);
+    }
+    Some(bytes)
+}
+
 /// Convert a `[u8; 32]` to a bytes string.
 pub fn bytes32_to_string(bytes: &[u8; 32], prefixed: bool) -> String {
     let mut s = String::default();
@@ -0,0 +1,184 @@
+//! ## Call Graph
+//!
+//! The graph of the macro invocations of a contract, printed by `huffc graph` as dot or json.
+//!
+//! Each macro is a node, sized by the runtime bytes its expansions generate, including the
+//! macros it invokes, so the macros dominating the bytecode size stand out. Invocation edges go
+//! from the invoking macro to the invoked one. Label edges go from a macro passed a label as an
+//! argument to the macro defining the label, since the passed macro may jump back to it.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, str::FromStr};
+
+use crate::{
+    ast::{Contract, MacroArg, MacroInvocation, Statement, StatementType},
+    bytecode::SourceMapping,
+    evm::Opcode,
+};
+
+/// The macro call graph of a contract
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct CallGraph {
+    /// The macros, in definition order
+    pub macros: Vec<CallGraphNode>,
+    /// The edges between macros, in the order they're first found
+    pub edges: Vec<CallGraphEdge>,
+}
+
+/// A macro of the call graph
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct CallGraphNode {
+    /// The macro name
+    pub name: String,
+    /// The path of the file defining the macro
+    pub file: Option<String>,
+    /// The number of times the macro is expanded in the runtime bytecode
+    pub expansions: usize,
+    /// The runtime bytes generated by the expansions of the macro, including the macros it
+    /// invokes
+    pub expanded_size: usize,
+}
+
+/// An edge of the call graph
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct CallGraphEdge {
+    /// The invoking macro, or the macro passed the label
+    pub from: String,
+    /// The invoked macro, or the macro defining the label
+    pub to: String,
+    /// The label passed as an argument, for label edges
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// The number of invocations, or of invocations passing the label
+    pub count: usize,
+}
+
+impl CallGraph {
+    /// Builds the call graph of a contract, sized from the source map of its runtime bytecode,
+    /// generated from the `MAIN` macro
+    pub fn new(contract: &Contract, source_map: &[SourceMapping]) -> Self {
+        let macros = contract
+            .macros
+            .iter()
+            .map(|m| {
+                let name = m.name.as_str();
+                let (expansions, expanded_size) = match name {
+                    "MAIN" => {
+                        let code = source_map.iter().filter(|m| m.table.is_none());
+                        let size = code.map(|m| m.size).sum::<usize>();
+                        (source_map.iter().any(|m| m.table.is_none()) as usize, size)
+                    }
+                    _ => {
+                        // Each expansion is identified by the chain of invocations leading to it
+                        let mut expansions: BTreeSet<&[MacroInvocation]> = BTreeSet::new();
+                        let mut size = 0;
+                        for mapping in source_map.iter() {
+                            let position =
+                                mapping.invocations.iter().position(|mi| mi.macro_name == name);
+                            if let Some(i) = position {
+                                expansions.insert(&mapping.invocations[..=i]);
+                                size += mapping.size;
+                            }
+                        }
+                        (expansions.len(), size)
+                    }
+                };
+                CallGraphNode {
+                    name: m.name.to_string(),
+                    file: m.span.0.first().and_then(|s| s.file.as_ref()).map(|f| f.path.clone()),
+                    expansions,
+                    expanded_size,
+                }
+            })
+            .collect();
+
+        let mut edges: Vec<CallGraphEdge> = vec![];
+        for m in contract.macros.iter() {
+            let mut invocations = vec![];
+            collect_invocations(&m.statements, &mut invocations);
+            for mi in invocations {
+                if !contract.macros.iter().any(|d| d.name == mi.macro_name) {
+                    continue
+                }
+                add_edge(&mut edges, &m.name, &mi.macro_name, None);
+                for arg in mi.args.iter() {
+                    match arg {
+                        MacroArg::Ident(label)
+                            if Opcode::from_str(label).is_err() &&
+                                defines_label(&m.statements, label) =>
+                        {
+                            add_edge(&mut edges, &mi.macro_name, &m.name, Some(label));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        Self { macros, edges }
+    }
+
+    /// Renders the call graph in the graphviz dot language
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec!["digraph calls {".to_string()];
+        for node in self.macros.iter() {
+            let plural = if node.expansions == 1 { "" } else { "s" };
+            lines.push(format!(
+                "    \"{}\" [label=\"{}\\n{} expansion{}, {} bytes\"];",
+                node.name, node.name, node.expansions, plural, node.expanded_size
+            ));
+        }
+        for edge in self.edges.iter() {
+            let attributes = match (&edge.label, edge.count) {
+                (Some(label), 1) => format!(" [style=dashed, label=\"{}\"]", label),
+                (Some(label), n) => format!(" [style=dashed, label=\"{} ({})\"]", label, n),
+                (None, 1) => String::new(),
+                (None, n) => format!(" [label=\"{}\"]", n),
+            };
+            lines.push(format!("    \"{}\" -> \"{}\"{};", edge.from, edge.to, attributes));
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    /// Serializes the call graph to json
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Adds an edge to the graph, or counts it again if it was already added
+fn add_edge(edges: &mut Vec<CallGraphEdge>, from: &str, to: &str, label: Option<&str>) {
+    let label = label.map(|l| l.to_string());
+    match edges.iter_mut().find(|e| e.from == from && e.to == to && e.label == label) {
+        Some(edge) => edge.count += 1,
+        None => edges.push(CallGraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            label,
+            count: 1,
+        }),
+    }
+}
+
+/// Recursively collects the macro invocations of a list of statements
+fn collect_invocations<'a>(
+    statements: &'a [Statement],
+    invocations: &mut Vec<&'a MacroInvocation>,
+) {
+    for statement in statements {
+        match &statement.ty {
+            StatementType::MacroInvocation(mi) => invocations.push(mi),
+            StatementType::Label(label) => collect_invocations(&label.inner, invocations),
+            _ => {}
+        }
+    }
+}
+
+/// If a list of statements defines a label, including in the statements of its labels
+fn defines_label(statements: &[Statement], name: &str) -> bool {
+    statements.iter().any(|s| match &s.ty {
+        StatementType::Label(label) => label.name == name || defines_label(&label.inner, name),
+        _ => false,
+    })
+}
@@ -0,0 +1,140 @@
+//! ## Cheatcodes
+//!
+//! The catalog of the cheatcodes `huffc test` executes, and of the builtins calling them.
+//!
+//! A cheatcode is a call to the [CHEATCODE_ADDRESS], with the calldata of a function of the
+//! cheatcode interface Foundry exposes at the same address, so tests can also call them with
+//! plain `call` instructions. The test runner intercepts these calls instead of executing code at
+//! the address. Each cheatcode has a builtin, like `__PRANK()`, taking the arguments of the call
+//! from the stack, first argument on top, and generating the call. The calldata is written at
+//! `msize`, so the memory the test uses isn't overwritten.
+
+use ethers_core::utils::keccak256;
+use serde::{Deserialize, Serialize};
+
+use crate::evm::Opcode;
+
+/// The address of the cheatcode calls, the one Foundry uses
+pub const CHEATCODE_ADDRESS: [u8; 20] = [
+    0x71, 0x09, 0x70, 0x9e, 0xcf, 0xa9, 0x1a, 0x80, 0x62, 0x6f, 0xf3, 0x98, 0x9d, 0x68, 0xf6, 0x7f,
+    0x5b, 0x1d, 0xd1, 0x2d,
+];
+
+/// A cheatcode, manipulating the state of the test EVM or setting an expectation of the test
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Cheatcode {
+    /// Sets the address `caller` returns for the rest of the test
+    Prank,
+    /// Sets the balance of an address
+    Deal,
+    /// Sets the block timestamp
+    Warp,
+    /// Sets the block number
+    Roll,
+    /// Expects the test to revert
+    ExpectRevert,
+    /// Expects the log emitted after the next one to match it, topics and data
+    ExpectEmit,
+}
+
+impl Cheatcode {
+    /// Every cheatcode of the catalog
+    pub const ALL: [Cheatcode; 6] = [
+        Cheatcode::Prank,
+        Cheatcode::Deal,
+        Cheatcode::Warp,
+        Cheatcode::Roll,
+        Cheatcode::ExpectRevert,
+        Cheatcode::ExpectEmit,
+    ];
+
+    /// The name of the builtin calling the cheatcode
+    pub fn builtin(&self) -> &'static str {
+        match self {
+            Cheatcode::Prank => "__PRANK",
+            Cheatcode::Deal => "__DEAL",
+            Cheatcode::Warp => "__WARP",
+            Cheatcode::Roll => "__ROLL",
+            Cheatcode::ExpectRevert => "__EXPECT_REVERT",
+            Cheatcode::ExpectEmit => "__EXPECT_EMIT",
+        }
+    }
+
+    /// The signature of the function of the cheatcode interface
+    pub fn signature(&self) -> &'static str {
+        match self {
+            Cheatcode::Prank => "prank(address)",
+            Cheatcode::Deal => "deal(address,uint256)",
+            Cheatcode::Warp => "warp(uint256)",
+            Cheatcode::Roll => "roll(uint256)",
+            Cheatcode::ExpectRevert => "expectRevert()",
+            Cheatcode::ExpectEmit => "expectEmit()",
+        }
+    }
+
+    /// The selector of the function, the first 4 bytes of the keccak256 hash of its signature
+    pub fn selector(&self) -> [u8; 4] {
+        let hash = keccak256(self.signature());
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+
+    /// The number of arguments of the call, taken from the stack by the builtin
+    pub fn params(&self) -> usize {
+        match self {
+            Cheatcode::Deal => 2,
+            Cheatcode::Prank | Cheatcode::Warp | Cheatcode::Roll => 1,
+            Cheatcode::ExpectRevert | Cheatcode::ExpectEmit => 0,
+        }
+    }
+
+    /// Looks up the cheatcode of a builtin name
+    pub fn from_builtin(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.builtin() == name)
+    }
+
+    /// Looks up the cheatcode of the calldata of a call, `None` if it isn't a cheatcode
+    pub fn decode(calldata: &[u8]) -> Option<Self> {
+        let selector = calldata.get(..4)?;
+        Self::ALL.into_iter().find(|c| c.selector() == selector)
+    }
+
+    /// The bytecode of the builtin, calling the cheatcode with the arguments on the stack
+    ///
+    /// The selector and arguments are stored from `msize`, which is left on the stack for the
+    /// arguments to be stored relative to it, then the call is made and its result popped.
+    pub fn bytecode(&self) -> String {
+        let mut code = format!(
+            "{}{}{}{}e0{}{}{}",
+            Opcode::Msize,
+            Opcode::Push4,
+            hex::encode(self.selector()),
+            Opcode::Push1,
+            Opcode::Shl,
+            Opcode::Dup2,
+            Opcode::Mstore
+        );
+        for i in 0..self.params() {
+            code.push_str(&format!(
+                "{}{}{}{:02x}{}{}",
+                Opcode::Swap1,
+                Opcode::Dup2,
+                Opcode::Push1,
+                4 + 32 * i,
+                Opcode::Add,
+                Opcode::Mstore
+            ));
+        }
+        code.push_str(&format!(
+            "{push1}00{push1}00{push1}{:02x}{}{push1}00{}{}{}{}{pop}{pop}",
+            4 + 32 * self.params(),
+            Opcode::Dup4,
+            Opcode::Push20,
+            hex::encode(CHEATCODE_ADDRESS),
+            Opcode::Gas,
+            Opcode::Call,
+            push1 = Opcode::Push1,
+            pop = Opcode::Pop
+        ));
+        code
+    }
+}
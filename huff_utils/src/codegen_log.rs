@@ -0,0 +1,292 @@
+//! ## Codegen Log
+//!
+//! A human readable log of code generation, printed by `huffc --debug-codegen`.
+//!
+//! Each entry lists the bytes a statement generated at their bytecode offset, the macro
+//! expansion they were generated through, and what arg calls, constants and jump labels resolved
+//! to, so wrong bytecode can be traced back to its source without reading raw compiler traces.
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    ast::{AstSpan, ConstVal, Contract, MacroArg, MacroInvocation, Statement, StatementType},
+    bytecode::{Relocation, SourceMapping},
+    bytes_util::bytes32_to_string,
+    evm::Opcode,
+};
+
+/// The bytes generated by a statement or table, in the codegen log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodegenLogEntry {
+    /// The bytecode offset of the first byte
+    pub offset: usize,
+    /// The generated bytes, as a hex string
+    pub bytes: String,
+    /// The instructions of the bytes, with their pushed values. Empty for table bytes.
+    pub instructions: Vec<String>,
+    /// The statement or table generating the bytes
+    pub source: String,
+    /// The `file:line:column` location of the statement
+    pub location: Option<String>,
+    /// The macros expanded to generate the bytes, outermost first
+    pub expansion: Vec<String>,
+    /// What an arg call, constant or jump label resolved to
+    pub resolution: Option<String>,
+}
+
+/// The codegen log of the bytecode of a macro
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CodegenLog {
+    /// The macro the bytecode was generated from
+    pub name: String,
+    /// The entries, in bytecode order
+    pub entries: Vec<CodegenLogEntry>,
+}
+
+impl CodegenLog {
+    /// Builds the log of the bytecode generated from a macro, from its source map
+    pub fn new(
+        name: &str,
+        bytecode: &str,
+        source_map: &[SourceMapping],
+        contract: &Contract,
+    ) -> Self {
+        let entries =
+            source_map.iter().map(|m| CodegenLogEntry::new(name, bytecode, m, contract)).collect();
+        Self { name: name.to_string(), entries }
+    }
+}
+
+impl CodegenLogEntry {
+    /// Builds the entry of a source mapping, of the bytecode generated from the named macro
+    pub fn new(name: &str, bytecode: &str, mapping: &SourceMapping, contract: &Contract) -> Self {
+        let bytes = bytecode
+            .get(mapping.offset * 2..(mapping.offset + mapping.size) * 2)
+            .unwrap_or_default()
+            .to_string();
+        let location = mapping.span.0.first().and_then(|s| {
+            let position = s.start_position()?;
+            Some(format!("{}:{}:{}", s.file.as_ref()?.path, position.line, position.column))
+        });
+        if let Some(table) = &mapping.table {
+            return Self {
+                offset: mapping.offset,
+                bytes,
+                instructions: vec![],
+                source: format!("table {}", table),
+                location,
+                expansion: vec![],
+                resolution: None,
+            }
+        }
+
+        let mut expansion = vec![name.to_string()];
+        expansion.extend(mapping.invocations.iter().map(|mi| mi.macro_name.to_string()));
+        let statement = contract
+            .macros
+            .iter()
+            .find(|m| expansion.last().is_some_and(|e| m.name == *e))
+            .and_then(|m| find_statement(&m.statements, &mapping.span));
+        let resolution = match (statement.map(|s| &s.ty), &mapping.relocation) {
+            (Some(StatementType::ArgCall(arg)), _) => {
+                Some(resolve_arg_call(arg, name, &mapping.invocations, contract))
+            }
+            (Some(StatementType::Constant(c)), _) => {
+                Some(format!("constant {}", describe_constant(c, contract)))
+            }
+            (_, Some(Relocation::JumpLabel(label))) => {
+                Some(format!("jump destination of label \"{}\"", label))
+            }
+            (_, Some(Relocation::TableStart(table))) => {
+                Some(format!("start offset of table \"{}\"", table))
+            }
+            (_, Some(Relocation::Immutable(name))) => {
+                Some(format!("immutable \"{}\", set by the constructor", name))
+            }
+            (_, Some(Relocation::LabelOffsets(expression))) => {
+                Some(format!("label offsets {}", expression))
+            }
+            _ => None,
+        };
+
+        Self {
+            offset: mapping.offset,
+            instructions: disassemble(&bytes),
+            bytes,
+            source: statement
+                .and_then(describe_statement)
+                .unwrap_or_else(|| source_text(&mapping.span)),
+            location,
+            expansion,
+            resolution,
+        }
+    }
+}
+
+impl fmt::Display for CodegenLogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let instructions = match self.instructions.is_empty() {
+            true => "data".to_string(),
+            false => self.instructions.join(" "),
+        };
+        write!(
+            f,
+            "{:#06x}  {:<12} {:<16} <- {}",
+            self.offset, self.bytes, instructions, self.source
+        )?;
+        if let Some(location) = &self.location {
+            write!(f, " at {}", location)?;
+        }
+        if !self.expansion.is_empty() {
+            write!(f, " in {}", self.expansion.join(" > "))?;
+        }
+        if let Some(resolution) = &self.resolution {
+            write!(f, ": {}", resolution)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CodegenLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Codegen log of {}:", self.name)?;
+        for entry in &self.entries {
+            write!(f, "\n{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+/// Recursively finds the statement with the given span in a list of statements
+fn find_statement<'a>(statements: &'a [Statement], span: &AstSpan) -> Option<&'a Statement> {
+    statements.iter().find_map(|s| match &s.ty {
+        _ if &s.span == span => Some(s),
+        StatementType::Label(label) => find_statement(&label.inner, span),
+        _ => None,
+    })
+}
+
+/// Describes a statement as written, `None` for the statements better shown from their source
+fn describe_statement(statement: &Statement) -> Option<String> {
+    match &statement.ty {
+        StatementType::Literal(l) => Some(format!("0x{}", bytes32_to_string(l, false))),
+        StatementType::Opcode(o) => Opcode::mnemonic(&o.string()).map(|m| m.to_string()),
+        StatementType::Constant(c) => Some(format!("[{}]", c)),
+        StatementType::ArgCall(a) => Some(format!("<{}>", a)),
+        StatementType::LabelCall(l) => Some(l.to_string()),
+        StatementType::Label(l) => Some(format!("{}:", l.name)),
+        _ => None,
+    }
+}
+
+/// The source code covered by a span, or its token count if it has no source
+fn source_text(span: &AstSpan) -> String {
+    let (first, last) = match (span.0.first(), span.0.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return String::new(),
+    };
+    match first.file.as_ref().and_then(|f| f.source.as_deref()) {
+        Some(source) if first.file == last.file && first.start <= last.end => {
+            source.chars().skip(first.start).take(last.end - first.start).collect()
+        }
+        _ => format!("{} tokens", span.0.len()),
+    }
+}
+
+/// Describes a constant with its value, eg. `[OWNER] = 0x00`
+fn describe_constant(name: &str, contract: &Contract) -> String {
+    match contract.find_constant_value(name.into()) {
+        Some(ConstVal::Literal(l)) => format!("[{}] = 0x{}", name, bytes32_to_string(&l, false)),
+        Some(ConstVal::FreeStoragePointer(fsp)) => format!("[{}] = {}", name, fsp),
+        None if contract.immutables.iter().any(|i| i.name == name) => {
+            format!("[{}], immutable", name)
+        }
+        None => format!("[{}], undefined", name),
+    }
+}
+
+/// Resolves an arg call through the invocations passing it, like codegen does
+///
+/// Arg calls naming a constant or an opcode resolve to it, others to the argument the innermost
+/// invocation passes for the parameter, following arguments that are themselves arg calls.
+fn resolve_arg_call(
+    arg: &str,
+    root: &str,
+    invocations: &[MacroInvocation],
+    contract: &Contract,
+) -> String {
+    let mut name = arg.to_string();
+    let mut steps: Vec<String> = vec![];
+    let mut depth = invocations.len();
+    let value = loop {
+        if contract.find_constant_value((&name).into()).is_some() ||
+            contract.immutables.iter().any(|i| i.name == name)
+        {
+            break format!("constant {}", describe_constant(&name, contract))
+        }
+        if Opcode::from_str(&name).is_ok() {
+            break format!("opcode {}", name)
+        }
+        let invocation = match depth.checked_sub(1).and_then(|i| invocations.get(i)) {
+            Some(invocation) => invocation,
+            None => break "nothing, no invocation passes it".to_string(),
+        };
+        let caller = match depth {
+            1 => root,
+            _ => &invocations[depth - 2].macro_name,
+        };
+        steps.push(format!(
+            "argument \"{}\" of {} passed by {}",
+            name, invocation.macro_name, caller
+        ));
+        let position = contract
+            .macros
+            .iter()
+            .find(|m| m.name == invocation.macro_name)
+            .and_then(|m| m.parameters.iter().position(|p| p.name.as_deref() == Some(&name)));
+        match position.and_then(|p| invocation.args.get(p)) {
+            Some(MacroArg::Literal(l)) => break format!("0x{}", bytes32_to_string(l, false)),
+            Some(MacroArg::Ident(i)) if Opcode::from_str(i).is_ok() => {
+                break format!("opcode {}", i)
+            }
+            Some(MacroArg::Ident(i)) => break format!("label \"{}\"", i),
+            Some(MacroArg::Constant(c)) => {
+                break format!("constant {}", describe_constant(c, contract))
+            }
+            Some(MacroArg::ArgCall(a)) => {
+                name = a.clone();
+                depth -= 1;
+            }
+            None => break "nothing, the invocation passes no such argument".to_string(),
+        }
+    };
+    match steps.is_empty() {
+        true => format!("<{}> resolved to {}", arg, value),
+        false => format!("<{}> resolved to {} through {}", arg, value, steps.join(", ")),
+    }
+}
+
+/// Splits hex bytes into instructions, with the values pushed by PUSH instructions
+fn disassemble(bytes: &str) -> Vec<String> {
+    let mut instructions = vec![];
+    let mut i = 0;
+    while i + 2 <= bytes.len() {
+        let byte = &bytes[i..i + 2];
+        let mnemonic = match Opcode::mnemonic(byte) {
+            Some(mnemonic) => mnemonic,
+            None => {
+                instructions.push(format!("0x{}", byte));
+                i += 2;
+                continue
+            }
+        };
+        let size = mnemonic.strip_prefix("push").and_then(|n| n.parse::<usize>().ok());
+        let end = (i + 2 + size.unwrap_or_default() * 2).min(bytes.len());
+        instructions.push(match size {
+            Some(n) if n > 0 => format!("{} 0x{}", mnemonic, &bytes[i + 2..end]),
+            _ => mnemonic.to_string(),
+        });
+        i = end;
+    }
+    instructions
+}
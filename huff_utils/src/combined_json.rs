@@ -0,0 +1,123 @@
+//! ## Combined JSON
+//!
+//! The single json document of `--combined-json`, matching the output of solc's flag of the same
+//! name for deployment tooling consuming it.
+//!
+//! The selected fields of each contract are keyed by `<SOURCE PATH>:<CONTRACT NAME>`, the
+//! contract named after its file stem, under `contracts`, next to the compiler `version`:
+//!
+//! ```json
+//! {"contracts":{"src/Counter.huff:Counter":{"bin":"...","bin-runtime":"..."}},"version":"0.1.0"}
+//! ```
+//!
+//! Like solc, the bytecode isn't 0x prefixed.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use crate::artifact::Artifact;
+
+/// The fields of a contract `--combined-json` can select
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CombinedJsonField {
+    /// The Solidity JSON ABI
+    Abi,
+    /// The creation bytecode
+    Bin,
+    /// The runtime bytecode
+    BinRuntime,
+    /// The hex selectors of the functions, by signature
+    Hashes,
+    /// The NatSpec developer documentation
+    Devdoc,
+    /// The NatSpec user documentation
+    Userdoc,
+}
+
+impl CombinedJsonField {
+    /// Every field, in the order solc lists them
+    pub const ALL: [CombinedJsonField; 6] = [
+        CombinedJsonField::Abi,
+        CombinedJsonField::Bin,
+        CombinedJsonField::BinRuntime,
+        CombinedJsonField::Hashes,
+        CombinedJsonField::Devdoc,
+        CombinedJsonField::Userdoc,
+    ];
+
+    /// The value of the field for an artifact
+    pub fn value(&self, artifact: &Artifact) -> serde_json::Value {
+        match self {
+            CombinedJsonField::Abi => {
+                serde_json::json!(artifact.abi.as_ref().map(|a| a.json_abi()).unwrap_or_default())
+            }
+            CombinedJsonField::Bin => serde_json::json!(artifact.bytecode),
+            CombinedJsonField::BinRuntime => serde_json::json!(artifact.runtime),
+            CombinedJsonField::Hashes => serde_json::json!(artifact
+                .abi
+                .as_ref()
+                .map(|a| a.method_identifiers())
+                .unwrap_or_default()),
+            CombinedJsonField::Devdoc => serde_json::json!(artifact.devdoc),
+            CombinedJsonField::Userdoc => serde_json::json!(artifact.userdoc),
+        }
+    }
+}
+
+impl fmt::Display for CombinedJsonField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CombinedJsonField::Abi => write!(f, "abi"),
+            CombinedJsonField::Bin => write!(f, "bin"),
+            CombinedJsonField::BinRuntime => write!(f, "bin-runtime"),
+            CombinedJsonField::Hashes => write!(f, "hashes"),
+            CombinedJsonField::Devdoc => write!(f, "devdoc"),
+            CombinedJsonField::Userdoc => write!(f, "userdoc"),
+        }
+    }
+}
+
+impl FromStr for CombinedJsonField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CombinedJsonField::ALL.into_iter().find(|v| v.to_string() == s.trim()).ok_or_else(|| {
+            format!(
+                "Invalid combined json field \"{}\". Available fields: {}",
+                s,
+                CombinedJsonField::ALL.map(|v| v.to_string()).join(",")
+            )
+        })
+    }
+}
+
+/// The combined json document of compiled contracts
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct CombinedJson {
+    /// The selected fields of each contract, by `<SOURCE PATH>:<CONTRACT NAME>`
+    pub contracts: BTreeMap<String, BTreeMap<String, serde_json::Value>>,
+    /// The compiler version
+    pub version: String,
+}
+
+impl CombinedJson {
+    /// Combines the selected fields of artifacts
+    pub fn new<'a>(
+        artifacts: impl Iterator<Item = &'a Artifact>,
+        fields: &[CombinedJsonField],
+        version: &str,
+    ) -> Self {
+        let contracts = artifacts
+            .map(|a| {
+                let key = format!("{}:{}", a.source_name(), a.contract_name());
+                (key, fields.iter().map(|f| (f.to_string(), f.value(a))).collect())
+            })
+            .collect();
+        Self { contracts, version: version.to_string() }
+    }
+
+    /// Serializes the document on a single line, like solc
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
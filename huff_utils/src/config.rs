@@ -0,0 +1,135 @@
+//! ## Config
+//!
+//! Project configuration read from a `huff.toml` file.
+//!
+//! Post-build hooks are commands run by the CLI for every exported artifact, eg. to upload it to
+//! a registry, patch placeholders, or generate extra bindings:
+//!
+//! ```toml
+//! [[post-build]]
+//! name = "bindings"
+//! command = "node"
+//! args = ["scripts/bindings.js"]
+//! ```
+//!
+//! The manifests of included libraries, generated by `huffc package`, are pinned to check the
+//! library symbols used against them:
+//!
+//! ```toml
+//! manifests = ["lib/Ownable.manifest.json"]
+//! ```
+//!
+//! Lints are configured by name, at the `allow`, `warn` or `deny` level. The `warnings` group
+//! sets the level of every lint without its own:
+//!
+//! ```toml
+//! [lints]
+//! warnings = "deny"
+//! unused-macro = "allow"
+//! magic-selector = "warn"
+//! ```
+//!
+//! The number of runs of each fuzzed test and the seed of their random inputs are configured for
+//! `huffc test`, which overrides them with its `--runs` and `--seed` options:
+//!
+//! ```toml
+//! [fuzz]
+//! runs = 1000
+//! seed = 42
+//! ```
+
+use serde::Deserialize;
+use std::{collections::BTreeMap, fs, path::Path, process::Command};
+
+use crate::{
+    artifact::Artifact,
+    lint::{LintLevel, LintLevels},
+};
+
+/// The default configuration file name
+pub const CONFIG_FILE: &str = "huff.toml";
+
+/// A Huff project configuration
+#[derive(Default, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct HuffConfig {
+    /// The hooks run for each artifact after it is exported
+    #[serde(default)]
+    pub post_build: Vec<PostBuildHook>,
+    /// The paths of the pinned library manifests
+    #[serde(default)]
+    pub manifests: Vec<String>,
+    /// The levels lints are reported at, by lint name
+    #[serde(default)]
+    pub lints: BTreeMap<String, LintLevel>,
+    /// The configuration of fuzzed tests
+    #[serde(default)]
+    pub fuzz: FuzzConfig,
+}
+
+/// The configuration of fuzzed tests, unset values falling back to the defaults of the runner
+#[derive(Default, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FuzzConfig {
+    /// The number of runs of each fuzzed test
+    pub runs: Option<usize>,
+    /// The seed the random inputs derive from
+    pub seed: Option<u64>,
+}
+
+impl HuffConfig {
+    /// Parses a configuration from toml
+    pub fn from_toml(toml: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(toml)
+    }
+
+    /// The configured lint levels
+    ///
+    /// Errors with the lint name if it is unknown.
+    pub fn lint_levels(&self) -> Result<LintLevels, String> {
+        let mut levels = LintLevels::default();
+        self.lints.iter().try_for_each(|(lint, level)| levels.set(lint, *level))?;
+        Ok(levels)
+    }
+
+    /// Reads the configuration file at the given path
+    pub fn load(path: &Path) -> Result<Self, std::io::Error> {
+        let config = fs::read_to_string(path)?;
+        HuffConfig::from_toml(&config)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// A command run for each exported artifact
+///
+/// The artifact path is passed as the last argument, and the artifact is described by the
+/// `HUFF_ARTIFACT`, `HUFF_SOURCE`, `HUFF_BYTECODE` and `HUFF_RUNTIME` environment variables.
+#[derive(Default, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PostBuildHook {
+    /// An optional name, used when reporting the hook
+    pub name: Option<String>,
+    /// The program to run
+    pub command: String,
+    /// The arguments passed before the artifact path
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl PostBuildHook {
+    /// The name of the hook, falling back to its command
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.command)
+    }
+
+    /// Builds the command running the hook for an artifact exported to `artifact_path`
+    pub fn command(&self, artifact: &Artifact, artifact_path: &str) -> Command {
+        let mut command = Command::new(&self.command);
+        command
+            .args(&self.args)
+            .arg(artifact_path)
+            .env("HUFF_ARTIFACT", artifact_path)
+            .env("HUFF_SOURCE", &artifact.file.path)
+            .env("HUFF_BYTECODE", &artifact.bytecode)
+            .env("HUFF_RUNTIME", &artifact.runtime);
+        command
+    }
+}
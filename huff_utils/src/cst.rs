@@ -0,0 +1,404 @@
+//! ## Concrete Syntax Tree
+//!
+//! A lossless tree of the tokens of a huff source. Unlike the AST, it retains the whitespace and
+//! comments, so tools like formatters and doc generators can rewrite source without destroying
+//! its layout.
+//!
+//! Tokens are grouped into the top-level definitions they belong to, and nested by their
+//! delimiters. Whitespace and comments are kept as the leading trivia of the token following
+//! them. Rendering the tree reproduces its source exactly.
+
+use std::fmt;
+
+use crate::{
+    files::Span,
+    token::{Token, TokenKind},
+};
+
+/// The kind of a trivia
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriviaKind {
+    /// Spaces and newlines
+    Whitespace,
+    /// A line or block comment
+    Comment,
+    /// Source the lexer couldn't tokenize
+    Skipped,
+}
+
+/// Source text without syntactic meaning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trivia {
+    /// The kind of trivia
+    pub kind: TriviaKind,
+    /// The source text
+    pub text: String,
+    /// The span of the source text
+    pub span: Span,
+}
+
+/// A token, with the trivia preceding it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxToken {
+    /// The kind of token
+    pub kind: TokenKind,
+    /// The source text
+    pub text: String,
+    /// The span of the source text
+    pub span: Span,
+    /// The whitespace and comments preceding the token
+    pub leading: Vec<Trivia>,
+}
+
+/// The delimiters nesting tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Delimiter {
+    /// `( ... )`
+    Paren,
+    /// `{ ... }`
+    Brace,
+    /// `[ ... ]`
+    Bracket,
+}
+
+impl Delimiter {
+    /// The delimiter opened by a token
+    pub fn opened_by(kind: &TokenKind) -> Option<Self> {
+        match kind {
+            TokenKind::OpenParen => Some(Delimiter::Paren),
+            TokenKind::OpenBrace => Some(Delimiter::Brace),
+            TokenKind::OpenBracket => Some(Delimiter::Bracket),
+            _ => None,
+        }
+    }
+
+    /// If a token closes the delimiter
+    pub fn is_closed_by(&self, kind: &TokenKind) -> bool {
+        matches!(
+            (self, kind),
+            (Delimiter::Paren, TokenKind::CloseParen) |
+                (Delimiter::Brace, TokenKind::CloseBrace) |
+                (Delimiter::Bracket, TokenKind::CloseBracket)
+        )
+    }
+}
+
+/// The tokens between a pair of delimiters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxGroup {
+    /// The delimiter
+    pub delimiter: Delimiter,
+    /// The opening delimiter token
+    pub open: SyntaxToken,
+    /// The elements between the delimiters
+    pub elements: Vec<SyntaxElement>,
+    /// The closing delimiter token, missing if the source is incomplete
+    pub close: Option<SyntaxToken>,
+}
+
+/// An element of a syntax tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    /// A single token
+    Token(SyntaxToken),
+    /// A delimited group of elements
+    Group(SyntaxGroup),
+}
+
+/// The kind of a top-level definition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    /// An `#include`
+    Include,
+    /// A macro or function macro
+    Macro,
+    /// The fallback entrypoint
+    Fallback,
+    /// The receive entrypoint
+    Receive,
+    /// A test
+    Test,
+    /// An ABI function
+    Function,
+    /// An ABI event
+    Event,
+    /// An ABI custom error
+    Error,
+    /// A constant
+    Constant,
+    /// An immutable
+    Immutable,
+    /// An opcode alias
+    Alias,
+    /// A jump or code table
+    Table,
+    /// A top-level `__ASSERT`
+    Assertion,
+    /// Tokens that don't start a known definition
+    Unknown,
+}
+
+/// A top-level definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxNode {
+    /// The kind of definition
+    pub kind: NodeKind,
+    /// The elements of the definition, starting at its `#define` or `#include`
+    pub elements: Vec<SyntaxElement>,
+}
+
+/// A lossless tree of the definitions of a source
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyntaxTree {
+    /// The top-level definitions
+    pub nodes: Vec<SyntaxNode>,
+    /// The trivia following the last token
+    pub trailing: Vec<Trivia>,
+}
+
+impl SyntaxTree {
+    /// Builds the tree of a source from its tokens, including the whitespace and comments
+    ///
+    /// The tokens must be lexed from the source alone, without its imports flattened. Source
+    /// between tokens, like invalid characters the lexer rejected, is kept as skipped trivia.
+    pub fn new(source: &str, tokens: &[Token]) -> Self {
+        let mut tree = SyntaxTree::default();
+        let mut stack: Vec<SyntaxGroup> = vec![];
+        let mut elements: Vec<SyntaxElement> = vec![];
+        let mut leading: Vec<Trivia> = vec![];
+        let mut cursor = 0;
+
+        for token in tokens {
+            let (mut start, end) = (token.span.start.max(cursor), token.span.end.min(source.len()));
+            // Hex literals are spanned without their `0x` prefix
+            if matches!(token.kind, TokenKind::Literal(_)) &&
+                start >= cursor + 2 &&
+                source.get(start - 2..start) == Some("0x")
+            {
+                start -= 2;
+            }
+            if start > cursor {
+                leading.push(trivia(TriviaKind::Skipped, source, cursor, start, &token.span));
+            }
+            let kind = match token.kind {
+                TokenKind::Eof => break,
+                TokenKind::Whitespace => Some(TriviaKind::Whitespace),
+                TokenKind::Comment(_) => Some(TriviaKind::Comment),
+                _ => None,
+            };
+            cursor = end.max(cursor);
+            if let Some(kind) = kind {
+                leading.push(trivia(kind, source, start, end, &token.span));
+                continue
+            }
+            let syntax_token = SyntaxToken {
+                kind: token.kind.clone(),
+                text: source.get(start..end).unwrap_or_default().to_string(),
+                span: token.span.clone(),
+                leading: std::mem::take(&mut leading),
+            };
+
+            // Definitions and top-level assertions start a new node, closing the groups left open
+            // by incomplete source
+            let assertion = matches!(&token.kind, TokenKind::BuiltinFunction(f) if f == "__ASSERT");
+            if matches!(token.kind, TokenKind::Define | TokenKind::Include) ||
+                (assertion && stack.is_empty())
+            {
+                close_groups(&mut stack, &mut elements, None);
+                if !elements.is_empty() {
+                    tree.nodes.push(SyntaxNode::new(std::mem::take(&mut elements)));
+                }
+            }
+            if let Some(delimiter) = Delimiter::opened_by(&token.kind) {
+                stack.push(SyntaxGroup {
+                    delimiter,
+                    open: syntax_token,
+                    elements: vec![],
+                    close: None,
+                });
+            } else if stack.last().map(|g| g.delimiter.is_closed_by(&token.kind)).unwrap_or(false) {
+                close_groups(&mut stack, &mut elements, Some(syntax_token));
+            } else {
+                let current = stack.last_mut().map(|g| &mut g.elements).unwrap_or(&mut elements);
+                current.push(SyntaxElement::Token(syntax_token));
+            }
+        }
+
+        close_groups(&mut stack, &mut elements, None);
+        if !elements.is_empty() {
+            tree.nodes.push(SyntaxNode::new(elements));
+        }
+        if cursor < source.len() {
+            let span = Span { start: cursor, end: source.len(), file: None };
+            leading.push(trivia(TriviaKind::Skipped, source, cursor, source.len(), &span));
+        }
+        tree.trailing = leading;
+        tree
+    }
+
+    /// The tokens of the tree, in source order
+    pub fn tokens(&self) -> Vec<&SyntaxToken> {
+        self.nodes.iter().flat_map(|n| n.tokens()).collect()
+    }
+}
+
+impl SyntaxNode {
+    /// Creates a node, its kind taken from the keyword following `#define` and its `internal`
+    /// modifier, or its leading `__ASSERT`
+    pub fn new(elements: Vec<SyntaxElement>) -> Self {
+        let mut node = Self { kind: NodeKind::Unknown, elements };
+        let tokens = node.tokens();
+        let keyword = tokens.get(1 + node.modifiers()).map(|t| &t.kind);
+        node.kind = match (tokens.first().map(|t| &t.kind), keyword) {
+            (Some(TokenKind::Include), _) => NodeKind::Include,
+            (Some(TokenKind::BuiltinFunction(f)), _) if f == "__ASSERT" => NodeKind::Assertion,
+            (Some(TokenKind::Define), Some(kind)) => match kind {
+                TokenKind::Macro => NodeKind::Macro,
+                TokenKind::Fallback => NodeKind::Fallback,
+                TokenKind::Receive => NodeKind::Receive,
+                TokenKind::Test => NodeKind::Test,
+                TokenKind::Function => NodeKind::Function,
+                TokenKind::Event => NodeKind::Event,
+                TokenKind::Error => NodeKind::Error,
+                TokenKind::Constant => NodeKind::Constant,
+                TokenKind::Immutable => NodeKind::Immutable,
+                TokenKind::Alias => NodeKind::Alias,
+                TokenKind::JumpTable | TokenKind::JumpTablePacked | TokenKind::CodeTable => {
+                    NodeKind::Table
+                }
+                _ => NodeKind::Unknown,
+            },
+            _ => NodeKind::Unknown,
+        };
+        node
+    }
+
+    /// The tokens of the definition, in source order
+    pub fn tokens(&self) -> Vec<&SyntaxToken> {
+        let mut tokens = vec![];
+        collect_tokens(&self.elements, &mut tokens);
+        tokens
+    }
+
+    /// The name of the definition, the identifier following its keyword
+    ///
+    /// Fallback and receive entrypoints are named by their keyword.
+    pub fn name(&self) -> Option<&str> {
+        let tokens = self.tokens();
+        let keyword = 1 + self.modifiers();
+        match self.kind {
+            NodeKind::Include | NodeKind::Assertion | NodeKind::Unknown => None,
+            NodeKind::Fallback | NodeKind::Receive => tokens.get(keyword).map(|t| t.text.as_str()),
+            _ => match tokens.get(keyword + 1) {
+                Some(SyntaxToken { kind: TokenKind::Ident(name), .. }) => Some(name.as_str()),
+                _ => None,
+            },
+        }
+    }
+
+    /// The number of modifiers between `#define` and the keyword of the definition
+    fn modifiers(&self) -> usize {
+        let tokens = self.tokens();
+        match (tokens.first().map(|t| &t.kind), tokens.get(1).map(|t| &t.kind)) {
+            (Some(TokenKind::Define), Some(TokenKind::Internal)) => 1,
+            _ => 0,
+        }
+    }
+
+    /// The comments documenting the definition
+    ///
+    /// These are the comments preceding the definition that aren't separated from it, or from
+    /// each other, by a blank line.
+    pub fn comments(&self) -> Vec<&Trivia> {
+        let leading = match self.tokens().first() {
+            Some(t) => &t.leading,
+            None => return vec![],
+        };
+        let mut comments = leading
+            .iter()
+            .rev()
+            .take_while(|t| t.kind != TriviaKind::Whitespace || t.text.matches('\n').count() < 2)
+            .filter(|t| t.kind == TriviaKind::Comment)
+            .collect::<Vec<&Trivia>>();
+        comments.reverse();
+        comments
+    }
+}
+
+impl fmt::Display for SyntaxTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.nodes.iter().try_for_each(|n| write!(f, "{}", n))?;
+        self.trailing.iter().try_for_each(|t| write!(f, "{}", t.text))
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.elements.iter().try_for_each(|e| write!(f, "{}", e))
+    }
+}
+
+impl fmt::Display for SyntaxElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyntaxElement::Token(t) => write!(f, "{}", t),
+            SyntaxElement::Group(g) => {
+                write!(f, "{}", g.open)?;
+                g.elements.iter().try_for_each(|e| write!(f, "{}", e))?;
+                g.close.iter().try_for_each(|t| write!(f, "{}", t))
+            }
+        }
+    }
+}
+
+impl fmt::Display for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.leading.iter().try_for_each(|t| write!(f, "{}", t.text))?;
+        write!(f, "{}", self.text)
+    }
+}
+
+/// Creates the trivia of a source range
+fn trivia(kind: TriviaKind, source: &str, start: usize, end: usize, span: &Span) -> Trivia {
+    Trivia {
+        kind,
+        text: source.get(start..end).unwrap_or_default().to_string(),
+        span: Span { start, end, file: span.file.clone() },
+    }
+}
+
+/// Closes the innermost open group with a closing token, or every open group if there's none
+fn close_groups(
+    stack: &mut Vec<SyntaxGroup>,
+    elements: &mut Vec<SyntaxElement>,
+    close: Option<SyntaxToken>,
+) {
+    let count = if close.is_some() { 1 } else { stack.len() };
+    let mut close = close;
+    for _ in 0..count {
+        let mut group = match stack.pop() {
+            Some(g) => g,
+            None => return,
+        };
+        group.close = close.take();
+        stack
+            .last_mut()
+            .map(|g| &mut g.elements)
+            .unwrap_or(elements)
+            .push(SyntaxElement::Group(group));
+    }
+}
+
+/// Recursively collects the tokens of a list of elements
+pub(crate) fn collect_tokens<'a>(elements: &'a [SyntaxElement], tokens: &mut Vec<&'a SyntaxToken>) {
+    for element in elements {
+        match element {
+            SyntaxElement::Token(t) => tokens.push(t),
+            SyntaxElement::Group(g) => {
+                tokens.push(&g.open);
+                collect_tokens(&g.elements, tokens);
+                tokens.extend(g.close.iter());
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! ## Data Contracts
+//!
+//! SSTORE2-style contracts that store data as their runtime code, and the macros to read it.
+
+use std::sync::Arc;
+
+use crate::{
+    artifact::Artifact,
+    ast::{StatementType, TableDefinition, TableKind},
+    bytes_util::format_even_bytes,
+    files::FileSource,
+};
+
+/// A contract storing data as its runtime code
+///
+/// The runtime code is prefixed with a STOP opcode, so calling the contract halts immediately
+/// instead of executing the data. The data is read with `extcodecopy`, skipping the first byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataContract {
+    /// The name of the data contract, used to name the reader macros
+    pub name: String,
+    /// The stored data
+    pub data: Vec<u8>,
+}
+
+impl DataContract {
+    /// Public associated function to instantiate a new data contract.
+    pub fn new(name: &str, data: Vec<u8>) -> Self {
+        Self { name: name.to_string(), data }
+    }
+
+    /// Instantiates the data contract of a code table
+    ///
+    /// Returns `None` if the table is a jump table, or its body isn't code.
+    pub fn from_table(table: &TableDefinition) -> Option<Self> {
+        if table.kind != TableKind::CodeTable {
+            return None
+        }
+        let code = table
+            .statements
+            .iter()
+            .map(|s| match &s.ty {
+                StatementType::LabelCall(code) => hex::decode(code.as_str()).ok(),
+                StatementType::Code(code) => Some(code.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<Vec<u8>>>>()?;
+        Some(Self::new(&table.name, code.concat()))
+    }
+
+    /// The runtime code, the STOP prefixed data
+    pub fn runtime(&self) -> String {
+        format!("00{}", hex::encode(&self.data))
+    }
+
+    /// The creation code, copying the runtime code to memory and returning it
+    pub fn creation_code(&self) -> String {
+        let runtime = self.runtime();
+        let size = format_even_bytes(format!("{:x}", runtime.len() / 2));
+        let push_size = format!("{:02x}{}", 95 + size.len() / 2, size);
+
+        // PUSH size, DUP1, PUSH1 offset, PUSH1 0, CODECOPY, PUSH1 0, RETURN
+        let offset = push_size.len() / 2 + 9;
+        format!("{}8060{:02x}6000396000f3{}", push_size, offset, runtime)
+    }
+
+    /// The artifact deploying the data contract
+    pub fn artifact(&self, file: Arc<FileSource>) -> Artifact {
+        Artifact {
+            file,
+            bytecode: self.creation_code(),
+            runtime: self.runtime(),
+            abi: None,
+            storage_layout: Default::default(),
+            panics: Default::default(),
+            devdoc: Default::default(),
+            userdoc: Default::default(),
+            immutable_references: Default::default(),
+            link_references: Default::default(),
+            runtime_link_references: Default::default(),
+            verbatim_references: Default::default(),
+        }
+    }
+
+    /// Huff macros reading the data from a deployed data contract
+    pub fn reader_macros(&self) -> String {
+        format!(
+            r#"/* Generated by huffc for the "{name}" data contract, storing {size} bytes */
+
+/// Pushes the size of the stored data
+#define macro {name}_SIZE() = takes (0) returns (1) {{
+    0x{size:x}
+}}
+
+/// Copies `size` bytes of the stored data, starting at `offset`, to memory at `dest`
+#define macro READ_{name}() = takes (4) returns (0) {{
+    // Input stack:          [address, dest, offset, size]
+    swap2 0x01 add swap2  // [address, dest, offset + 1, size]
+    extcodecopy           // []
+}}
+"#,
+            name = self.name,
+            size = self.data.len()
+        )
+    }
+}
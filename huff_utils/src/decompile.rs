@@ -0,0 +1,302 @@
+//! ## Decompiler
+//!
+//! Converts runtime bytecode into a best-effort Huff skeleton, printed by `huffc decompile`.
+//!
+//! The bytecode is split into basic blocks, starting at each jump destination and after each
+//! branch or terminating instruction. Each block becomes a macro declaring the stack items it
+//! takes and returns, invoked in bytecode order by `MAIN`, which defines a label for each jump
+//! destination. Pushes of a jump destination, by a push of at least two bytes or followed by a
+//! jump, are written as a reference to its label.
+//!
+//! The functions of the recovered dispatcher are stubbed as `#define function` definitions named
+//! after their selector, and the blocks they are dispatched to after the function. Since the
+//! names don't hash to the selectors, the dispatcher keeps comparing the selector literals.
+//!
+//! Instructions that can't be reached, from a terminating instruction to the next jump
+//! destination pushed other than as a `codecopy` offset, and undefined opcodes are raw data. Each
+//! run of raw data becomes a code table, read from a binary file written alongside the skeleton,
+//! and pushes of its offset reference the table with `__tablestart`. Compiling the skeleton places
+//! the tables after the code, and pushes literals with the fewest bytes, so offsets may not match
+//! the original bytecode.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    disassembly::{DisassembledInstruction, Disassembly},
+    dispatcher::{DispatchStep, Dispatcher},
+    evm::OPCODES_MAP,
+};
+
+/// The mnemonics ending a basic block, the instructions following them are reached by a jump
+const TERMINATORS: [&str; 7] =
+    ["stop", "return", "revert", "invalid", "selfdestruct", "jump", "jumpi"];
+
+/// A basic block of decompiled bytecode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    /// The name of the macro of the block
+    pub name: String,
+    /// The offset of the block, of its jump destination if any
+    pub offset: usize,
+    /// The label of the jump destination starting the block, if any
+    pub label: Option<String>,
+    /// The instructions of the block, its jump destination excluded
+    pub instructions: Vec<DisassembledInstruction>,
+    /// The number of stack items the block takes
+    pub takes: usize,
+    /// The number of stack items the block returns
+    pub returns: usize,
+}
+
+/// A run of raw data of decompiled bytecode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataTable {
+    /// The name of the code table
+    pub name: String,
+    /// The offset of the data in the bytecode
+    pub offset: usize,
+    /// The data
+    pub data: Vec<u8>,
+}
+
+/// A function stub recovered from the dispatcher
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionStub {
+    /// The name of the stub, after the selector
+    pub name: String,
+    /// The dispatched selector
+    pub selector: [u8; 4],
+    /// The offset the function is dispatched to
+    pub target: usize,
+}
+
+/// A Huff skeleton decompiled from runtime bytecode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decompilation {
+    /// The name of the decompiled contract, prefixing the files of its tables
+    pub name: String,
+    /// The size of the decompiled bytecode
+    pub size: usize,
+    /// The functions of the dispatcher, in comparison order
+    pub functions: Vec<FunctionStub>,
+    /// The basic blocks, in bytecode order
+    pub blocks: Vec<BasicBlock>,
+    /// The runs of raw data, in bytecode order
+    pub tables: Vec<DataTable>,
+}
+
+impl Decompilation {
+    /// Decompiles runtime bytecode into basic blocks, function stubs and data tables
+    pub fn new(name: &str, code: &[u8]) -> Self {
+        let mut functions: Vec<FunctionStub> = vec![];
+        for step in Dispatcher::analyze(&hex::encode(code)).matches() {
+            if let DispatchStep::Match { selector, target, shadowed_by: None, .. } = step {
+                if !functions.iter().any(|f| f.selector == *selector) {
+                    let name = format!("func_{}", hex::encode(selector));
+                    functions.push(FunctionStub { name, selector: *selector, target: *target });
+                }
+            }
+        }
+
+        // Jump destinations are named after the first function dispatched to them
+        let disassembly = Disassembly::new(code);
+        let mut labels = BTreeMap::new();
+        for instruction in disassembly.instructions.iter() {
+            if let Some(label) = &instruction.label {
+                let function = functions.iter().find(|f| f.target == instruction.pc);
+                labels.insert(
+                    instruction.pc,
+                    function.map(|f| f.name.clone()).unwrap_or(label.clone()),
+                );
+            }
+        }
+
+        // Raw data may decode to jump destinations, only the ones pushed to jump to end the data
+        let instructions = &disassembly.instructions;
+        let pushed = instructions
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                let next = instructions.iter().skip(i + 1).take(2);
+                !next.into_iter().any(|n| n.mnemonic == Some("codecopy"))
+            })
+            .filter_map(|(_, i)| i.push_data())
+            .filter(|d| d.len() <= std::mem::size_of::<usize>())
+            .map(|d| d.iter().fold(0, |pc, b| (pc << 8) | *b as usize))
+            .collect::<BTreeSet<usize>>();
+
+        let mut blocks: Vec<BasicBlock> = vec![];
+        let mut tables: Vec<DataTable> = vec![];
+        let mut current: Option<BasicBlock> = None;
+        let mut reachable = true;
+        for instruction in disassembly.instructions.into_iter() {
+            let label = labels
+                .get(&instruction.pc)
+                .filter(|_| reachable || pushed.contains(&instruction.pc));
+            let undefined = instruction.mnemonic.is_none() ||
+                instruction.push_data().map(|d| d.len()) !=
+                    instruction.mnemonic.and_then(push_size);
+            if label.is_some() {
+                reachable = true;
+            }
+            if !reachable || undefined {
+                blocks.extend(current.take());
+                reachable = false;
+                match tables.last_mut() {
+                    Some(t) if t.offset + t.data.len() == instruction.pc => {
+                        t.data.extend(&instruction.bytes)
+                    }
+                    _ => tables.push(DataTable {
+                        name: format!("DATA_{:04X}", instruction.pc),
+                        offset: instruction.pc,
+                        data: instruction.bytes.clone(),
+                    }),
+                }
+                continue
+            }
+            if let Some(label) = label {
+                blocks.extend(current.take());
+                current = Some(BasicBlock {
+                    name: label.to_uppercase(),
+                    offset: instruction.pc,
+                    label: Some(label.clone()),
+                    instructions: vec![],
+                    takes: 0,
+                    returns: 0,
+                });
+                continue
+            }
+            let block = current.get_or_insert_with(|| BasicBlock {
+                name: format!("BLOCK_{:04X}", instruction.pc),
+                offset: instruction.pc,
+                label: None,
+                instructions: vec![],
+                takes: 0,
+                returns: 0,
+            });
+            let mnemonic = instruction.mnemonic.unwrap_or_default();
+            block.instructions.push(instruction);
+            if TERMINATORS.contains(&mnemonic) {
+                reachable = mnemonic == "jumpi";
+                blocks.extend(current.take());
+            }
+        }
+        blocks.extend(current.take());
+
+        for block in blocks.iter_mut() {
+            (block.takes, block.returns) = stack_effect(&block.instructions);
+        }
+        Self { name: name.to_string(), size: code.len(), functions, blocks, tables }
+    }
+
+    /// The name of the file of a table, written alongside the skeleton
+    pub fn table_file(&self, table: &DataTable) -> String {
+        format!("{}.{}.bin", self.name, table.name)
+    }
+
+    /// Renders the Huff source of the skeleton
+    pub fn source(&self) -> String {
+        let mut lines = vec![
+            format!(
+                "// Decompiled from {} bytes of runtime bytecode by `huffc decompile`.",
+                self.size
+            ),
+            "// The skeleton is a best effort: name the functions and macros, and check the"
+                .to_string(),
+            "// takes and returns of each block before relying on it.".to_string(),
+        ];
+        if !self.functions.is_empty() {
+            lines.push(String::new());
+            lines.push(
+                "// The dispatched functions, to rename once their signature is known".to_string(),
+            );
+            for f in self.functions.iter() {
+                lines.push(format!("#define function {}() nonpayable returns ()", f.name));
+            }
+        }
+        if !self.tables.is_empty() {
+            lines.push(String::new());
+            lines.push("// The raw data of the bytecode, placed after the code".to_string());
+            for t in self.tables.iter() {
+                lines.push(format!(
+                    "#define table {} = file(\"./{}\")",
+                    t.name,
+                    self.table_file(t)
+                ));
+            }
+        }
+
+        for block in self.blocks.iter().filter(|b| !b.instructions.is_empty()) {
+            lines.push(String::new());
+            lines.push(format!(
+                "#define macro {}() = takes({}) returns({}) {{",
+                block.name, block.takes, block.returns
+            ));
+            for (i, instruction) in block.instructions.iter().enumerate() {
+                let next = block.instructions.get(i + 1).and_then(|n| n.mnemonic);
+                lines.push(format!("    {}", self.statement(instruction, next)));
+            }
+            lines.push("}".to_string());
+        }
+
+        lines.push(String::new());
+        lines.push("#define macro MAIN() = takes(0) returns(0) {".to_string());
+        for block in self.blocks.iter() {
+            let indent = match &block.label {
+                Some(label) => {
+                    lines.push(format!("    {}:", label));
+                    "        "
+                }
+                None => "    ",
+            };
+            if !block.instructions.is_empty() {
+                lines.push(format!("{}{}()", indent, block.name));
+            }
+        }
+        lines.push("}".to_string());
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    /// Renders the Huff statement of an instruction, followed by an instruction of a mnemonic
+    fn statement(&self, instruction: &DisassembledInstruction, next: Option<&str>) -> String {
+        let mnemonic = instruction.mnemonic.unwrap_or_default();
+        let Some(data) = instruction.push_data() else { return mnemonic.to_string() };
+        let value =
+            data.iter().fold(0u128, |v, b| v.saturating_mul(256).saturating_add(*b as u128));
+        let jumped = matches!(next, Some("jump" | "jumpi"));
+        if data.len() >= 2 || jumped {
+            let block = self.blocks.iter().find(|b| b.label.is_some() && b.offset as u128 == value);
+            if let Some(label) = block.and_then(|b| b.label.as_ref()) {
+                return label.clone()
+            }
+        }
+        if data.len() >= 2 {
+            if let Some(t) = self.tables.iter().find(|t| t.offset as u128 == value) {
+                return format!("__tablestart({})", t.name)
+            }
+        }
+        let significant = data.iter().skip_while(|b| **b == 0).copied().collect::<Vec<u8>>();
+        format!(
+            "0x{}",
+            if significant.is_empty() { "00".to_string() } else { hex::encode(significant) }
+        )
+    }
+}
+
+/// The number of bytes pushed by a push mnemonic
+fn push_size(mnemonic: &str) -> Option<usize> {
+    mnemonic.strip_prefix("push").and_then(|n| n.parse().ok())
+}
+
+/// The stack items a list of instructions takes, and the number it returns
+fn stack_effect(instructions: &[DisassembledInstruction]) -> (usize, usize) {
+    let (mut height, mut lowest) = (0i64, 0i64);
+    for opcode in instructions.iter().filter_map(|i| OPCODES_MAP.get(i.mnemonic?)) {
+        let (takes, returns) = opcode.stack_effect();
+        height -= takes as i64;
+        lowest = lowest.min(height);
+        height += returns as i64;
+    }
+    ((-lowest) as usize, (height - lowest) as usize)
+}
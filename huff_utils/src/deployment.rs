@@ -0,0 +1,105 @@
+//! ## Deployment
+//!
+//! Unsigned deployment transactions for compiled artifacts.
+
+use serde::{Deserialize, Serialize};
+
+use crate::artifact::Artifact;
+
+/// The base gas cost of a transaction
+pub const TX_BASE_GAS: u64 = 21000;
+/// The additional gas cost of a contract creation transaction
+pub const TX_CREATE_GAS: u64 = 32000;
+/// The gas cost of a zero calldata byte
+pub const TX_DATA_ZERO_GAS: u64 = 4;
+/// The gas cost of a non-zero calldata byte
+pub const TX_DATA_NON_ZERO_GAS: u64 = 16;
+/// The gas cost of each 32 byte word of initcode (EIP-3860)
+pub const INITCODE_WORD_GAS: u64 = 2;
+/// The gas cost of each byte of deployed code
+pub const CODE_DEPOSIT_GAS: u64 = 200;
+
+/// An unsigned contract deployment transaction
+///
+/// Serialized with the JSON-RPC field names and hex quantities, so it can be signed by external
+/// wallets. The EIP-1559 fields are only included when set.
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeploymentTransaction {
+    /// The transaction type, `0x2` when EIP-1559 fees are set
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub tx_type: Option<String>,
+    /// The recipient, always `None` for a contract creation
+    pub to: Option<String>,
+    /// The creation code followed by the encoded constructor arguments
+    pub data: String,
+    /// The wei sent with the transaction
+    pub value: String,
+    /// The estimated gas limit
+    pub gas: String,
+    /// The chain id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<String>,
+    /// The sender nonce
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// The maximum total fee per gas, in wei
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<String>,
+    /// The maximum priority fee per gas, in wei
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<String>,
+}
+
+impl DeploymentTransaction {
+    /// Public associated function to instantiate the deployment transaction of an artifact.
+    pub fn new(artifact: &Artifact) -> Self {
+        Self {
+            tx_type: None,
+            to: None,
+            data: format!("0x{}", artifact.bytecode),
+            value: "0x0".to_string(),
+            gas: format!("0x{:x}", DeploymentTransaction::estimate_gas(artifact)),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the chain id and sender nonce
+    pub fn set_chain(&mut self, chain_id: Option<u64>, nonce: Option<u64>) {
+        self.chain_id = chain_id.map(|c| format!("0x{:x}", c));
+        self.nonce = nonce.map(|n| format!("0x{:x}", n));
+    }
+
+    /// Sets the EIP-1559 fees, making this a type 2 transaction if either fee is set
+    pub fn set_fees(
+        &mut self,
+        max_fee_per_gas: Option<u128>,
+        max_priority_fee_per_gas: Option<u128>,
+    ) {
+        self.max_fee_per_gas = max_fee_per_gas.map(|f| format!("0x{:x}", f));
+        self.max_priority_fee_per_gas = max_priority_fee_per_gas.map(|f| format!("0x{:x}", f));
+        self.tx_type = (self.max_fee_per_gas.is_some() || self.max_priority_fee_per_gas.is_some())
+            .then(|| "0x2".to_string());
+    }
+
+    /// Estimates the gas used to deploy an artifact
+    ///
+    /// The estimate covers the intrinsic creation cost, the calldata and initcode costs, and the
+    /// code deposit of the runtime bytecode. The gas used while executing the constructor is not
+    /// included.
+    pub fn estimate_gas(artifact: &Artifact) -> u64 {
+        let data = hex::decode(&artifact.bytecode).unwrap_or_default();
+        let data_gas: u64 = data
+            .iter()
+            .map(|b| if *b == 0 { TX_DATA_ZERO_GAS } else { TX_DATA_NON_ZERO_GAS })
+            .sum();
+        let initcode_gas = INITCODE_WORD_GAS * (data.len() as u64).div_ceil(32);
+        let deposit_gas = CODE_DEPOSIT_GAS * (artifact.runtime.len() as u64 / 2);
+        TX_BASE_GAS + TX_CREATE_GAS + data_gas + initcode_gas + deposit_gas
+    }
+
+    /// Serializes the transaction as pretty json
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{
+    error::{CompilerError, CompilerWarning},
+    files::Span,
+    io::UnpackError,
+};
+
+/// The severity of a diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// A fatal diagnostic that stops compilation
+    Error,
+    /// A non-fatal diagnostic
+    Warning,
+}
+
+/// A source range of a diagnostic
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct DiagnosticSpan {
+    /// The path of the file, if known
+    pub file: Option<String>,
+    /// The start byte offset
+    pub start: usize,
+    /// The end byte offset
+    pub end: usize,
+    /// The 1-indexed start line
+    pub start_line: Option<usize>,
+    /// The 1-indexed start column
+    pub start_column: Option<usize>,
+    /// The 1-indexed end line
+    pub end_line: Option<usize>,
+    /// The 1-indexed end column
+    pub end_column: Option<usize>,
+}
+
+impl From<&Span> for DiagnosticSpan {
+    fn from(span: &Span) -> Self {
+        let start = span.start_position();
+        let end = span.end_position();
+        Self {
+            file: span.file.as_ref().map(|f| f.path.clone()),
+            start: span.start,
+            end: span.end,
+            start_line: start.map(|p| p.line),
+            start_column: start.map(|p| p.column),
+            end_line: end.map(|p| p.line),
+            end_column: end.map(|p| p.column),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticSpan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.file.as_deref().unwrap_or_default())?;
+        match (self.start_line, self.start_column) {
+            (Some(line), Some(column)) => write!(f, ":{}:{}", line, column),
+            _ => write!(f, ":{}-{}", self.start, self.end),
+        }
+    }
+}
+
+/// A machine-readable compiler diagnostic
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// The stable diagnostic code, eg. "H0201"
+    pub code: String,
+    /// The diagnostic severity
+    pub severity: Severity,
+    /// A message describing the diagnostic
+    pub message: String,
+    /// The source ranges the diagnostic crosses
+    pub spans: Vec<DiagnosticSpan>,
+    /// The lint name of a warning diagnostic
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lint: Option<String>,
+    /// A hint on how to fix the diagnostic
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+}
+
+impl From<&CompilerWarning> for Diagnostic {
+    fn from(warning: &CompilerWarning) -> Self {
+        Self {
+            code: warning.kind.code().to_string(),
+            severity: Severity::Warning,
+            message: warning.kind.message(),
+            spans: warning.span.0.iter().map(DiagnosticSpan::from).collect(),
+            lint: Some(warning.kind.lint().to_string()),
+            help: warning.help.clone(),
+        }
+    }
+}
+
+impl Diagnostic {
+    /// Serializes the diagnostic as a single line of json
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Flattens a compiler error into a list of diagnostics
+    pub fn from_compiler_error(error: &CompilerError) -> Vec<Diagnostic> {
+        let error_diagnostic = |message: String, spans: &[Span]| Diagnostic {
+            code: error.code().unwrap_or_default().to_string(),
+            severity: Severity::Error,
+            message,
+            spans: spans.iter().map(DiagnosticSpan::from).collect(),
+            lint: None,
+            help: None,
+        };
+        match error {
+            CompilerError::LexicalError(le) => {
+                vec![error_diagnostic(le.kind.message(), std::slice::from_ref(&le.span))]
+            }
+            CompilerError::FileUnpackError(ue) => {
+                let (message, path) = match ue {
+                    UnpackError::UnsupportedExtension(p) => {
+                        ("Unsupported File Extension".to_string(), p.clone())
+                    }
+                    UnpackError::InvalidDirectory(p) => {
+                        ("Invalid File Directory".to_string(), p.clone())
+                    }
+                    UnpackError::MissingFile(p) => ("File Not Found".to_string(), p.clone()),
+                    UnpackError::UnflattenableInclude(p) => {
+                        ("Unflattenable Include".to_string(), p.clone())
+                    }
+                    UnpackError::CircularInclude(cycle) => (
+                        format!("Circular Include: {}", cycle.join(" \u{2192} ")),
+                        cycle.first().cloned().unwrap_or_default(),
+                    ),
+                };
+                let mut diagnostic = error_diagnostic(message, &[]);
+                diagnostic.spans = vec![DiagnosticSpan { file: Some(path), ..Default::default() }];
+                vec![diagnostic]
+            }
+            CompilerError::ParserError(pe) => {
+                vec![error_diagnostic(pe.kind.message(), &pe.spans.0)]
+            }
+            CompilerError::PathBufRead(os_str) => {
+                vec![error_diagnostic(format!("Invalid Path: {:?}", os_str), &[])]
+            }
+            CompilerError::CodegenError(ce) => {
+                let mut diagnostic = error_diagnostic(ce.kind.message(), &ce.span.0);
+                diagnostic.help = ce.help.clone();
+                vec![diagnostic]
+            }
+            CompilerError::FailedCompiles(v) => {
+                v.iter().flat_map(Diagnostic::from_compiler_error).collect()
+            }
+            CompilerError::DeniedWarnings(v) => v
+                .iter()
+                .map(|w| Diagnostic { severity: Severity::Error, ..Diagnostic::from(w) })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)?;
+        if let Some(lint) = &self.lint {
+            write!(f, " [{}]", lint)?;
+        }
+        self.spans.iter().try_for_each(|s| write!(f, "\n-> {}", s))?;
+        match &self.help {
+            Some(help) => write!(f, "\nhelp: {}", help),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Suggests the candidate closest to an unknown name, as a "did you mean" hint
+///
+/// Names are compared case-insensitively, and only candidates within an edit distance of a third
+/// of the name's length (at least one) are suggested. Swapping two adjacent characters counts as a
+/// single edit.
+pub fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<String> {
+    let max_distance = std::cmp::max(name.len() / 3, 1);
+    let lowercase = name.to_lowercase();
+    candidates
+        .into_iter()
+        .filter(|c| *c != name)
+        .map(|c| (strsim::osa_distance(&lowercase, &c.to_lowercase()), c))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, c)| format!("did you mean '{}'?", c))
+}
@@ -0,0 +1,234 @@
+//! ## Disassembly
+//!
+//! Decodes bytecode into an annotated listing of its instructions, printed by `huffc disasm`.
+//!
+//! Each instruction is listed at its program counter, with the data pushed by pushes. Jump
+//! destinations decoded as instructions, and not hidden in push data, are labelled, and the
+//! pushes of their offset reference the label. When the source map of the bytecode is known,
+//! labels are named after the Huff labels generating them, table bytes are listed as data instead
+//! of being decoded, and the instructions are interleaved with the source lines generating them.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    bytecode::{Relocation, SourceMapping},
+    evm::Opcode,
+    files::Span,
+};
+
+/// An instruction of disassembled bytecode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisassembledInstruction {
+    /// The program counter of the instruction
+    pub pc: usize,
+    /// The bytes of the instruction, starting with the opcode, or the bytes of a table
+    pub bytes: Vec<u8>,
+    /// The mnemonic of the opcode, `None` for undefined opcodes and table bytes
+    pub mnemonic: Option<&'static str>,
+    /// The name of the table, for table bytes
+    pub table: Option<String>,
+    /// The label of a jump destination
+    pub label: Option<String>,
+    /// The label of the jump destination a push pushes the offset of
+    pub target: Option<String>,
+    /// The span of the statement generating the instruction, when the source map is known
+    pub span: Option<Span>,
+}
+
+impl DisassembledInstruction {
+    /// The data pushed by a push, missing bytes past the end of the bytecode excluded
+    pub fn push_data(&self) -> Option<&[u8]> {
+        self.push_size().map(|_| &self.bytes[1..])
+    }
+
+    /// The number of bytes pushed by a push
+    fn push_size(&self) -> Option<usize> {
+        self.mnemonic.and_then(|m| m.strip_prefix("push")).and_then(|n| n.parse().ok())
+    }
+}
+
+/// The disassembly of bytecode
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Disassembly {
+    /// The instructions, by program counter
+    pub instructions: Vec<DisassembledInstruction>,
+}
+
+impl Disassembly {
+    /// Disassembles bytecode, labelling jump destinations by their offset
+    pub fn new(code: &[u8]) -> Self {
+        let mut disassembly = Self { instructions: decode(code, 0, code.len(), None) };
+        disassembly.label_jumpdests(&BTreeMap::new());
+        disassembly
+    }
+
+    /// Disassembles bytecode generated with a source map
+    ///
+    /// Jump destinations are named after the labels pushing their offset, and table bytes are
+    /// listed as data. Bytes the source map doesn't cover are decoded as instructions.
+    pub fn with_source_map(code: &[u8], source_map: &[SourceMapping]) -> Self {
+        let mut instructions = vec![];
+        let mut labels = BTreeMap::new();
+        let mut pc = 0;
+        let mut mappings = source_map.iter().peekable();
+        while pc < code.len() {
+            let mapping = loop {
+                match mappings.peek() {
+                    Some(m) if m.offset + m.size <= pc => {
+                        mappings.next();
+                    }
+                    Some(m) if m.offset <= pc => break Some(*m),
+                    _ => break None,
+                }
+            };
+            let Some(mapping) = mapping else {
+                let end = mappings.peek().map(|m| m.offset).unwrap_or(code.len());
+                let mut unmapped = decode(code, pc, end, None);
+                pc = unmapped.last().map(|i| i.pc + i.bytes.len()).unwrap_or(end);
+                instructions.append(&mut unmapped);
+                continue
+            };
+            let end = (mapping.offset + mapping.size).min(code.len());
+            let span = mapping.span.0.first().cloned();
+            if let Some(table) = &mapping.table {
+                instructions.push(DisassembledInstruction {
+                    pc,
+                    bytes: code[pc..end].to_vec(),
+                    mnemonic: None,
+                    table: Some(table.clone()),
+                    label: None,
+                    target: None,
+                    span,
+                });
+                pc = end;
+                continue
+            }
+            let mut mapped = decode(code, pc, end, span);
+            if let Some(Relocation::JumpLabel(label)) = &mapping.relocation {
+                if let Some(offset) = mapped.first().and_then(|i| i.push_data()).map(to_offset) {
+                    labels.entry(offset).or_insert_with(|| label.to_string());
+                }
+            }
+            pc = mapped.last().map(|i| i.pc + i.bytes.len()).unwrap_or(end);
+            instructions.append(&mut mapped);
+        }
+
+        let mut disassembly = Self { instructions };
+        disassembly.label_jumpdests(&labels);
+        disassembly
+    }
+
+    /// Labels the jump destinations, and the pushes of their offset
+    fn label_jumpdests(&mut self, names: &BTreeMap<usize, String>) {
+        let mut labels = BTreeMap::new();
+        for instruction in self.instructions.iter_mut() {
+            if instruction.mnemonic == Some("jumpdest") {
+                let label = names
+                    .get(&instruction.pc)
+                    .cloned()
+                    .unwrap_or_else(|| format!("label_{:04x}", instruction.pc));
+                labels.insert(instruction.pc, label.clone());
+                instruction.label = Some(label);
+            }
+        }
+        for instruction in self.instructions.iter_mut() {
+            if let Some(data) = instruction.push_data().filter(|d| !d.is_empty()) {
+                instruction.target = labels.get(&to_offset(data)).cloned();
+            }
+        }
+    }
+
+    /// Renders the annotated listing of the instructions
+    ///
+    /// If `source` is set, each statement is preceded by the source line generating it, when
+    /// the instructions were disassembled with a source map.
+    pub fn render(&self, source: bool) -> String {
+        let mut lines = vec![];
+        let mut last_line = None;
+        for instruction in self.instructions.iter() {
+            if source {
+                let line = instruction.span.as_ref().and_then(source_line);
+                if line.is_some() && line != last_line {
+                    if let Some((path, number, code)) = &line {
+                        lines.push(format!("        // {}:{}: {}", path, number, code));
+                    }
+                    last_line = line;
+                }
+            }
+            if let Some(label) = &instruction.label {
+                lines.push(format!("{}:", label));
+            }
+            let listing = match (&instruction.table, instruction.mnemonic) {
+                (Some(table), _) => {
+                    format!("table {} 0x{}", table, hex::encode(&instruction.bytes))
+                }
+                (None, None) => format!("0x{:02x} (undefined opcode)", instruction.bytes[0]),
+                (None, Some(mnemonic)) => {
+                    match (instruction.push_data(), instruction.push_size()) {
+                        (Some([]), Some(_)) => format!("{} (truncated)", mnemonic),
+                        (Some(data), Some(size)) if data.len() < size => {
+                            format!("{} 0x{} (truncated)", mnemonic, hex::encode(data))
+                        }
+                        (Some(data), _) if !data.is_empty() => {
+                            format!("{} 0x{}", mnemonic, hex::encode(data))
+                        }
+                        _ => mnemonic.to_string(),
+                    }
+                }
+            };
+            lines.push(match &instruction.target {
+                Some(target) => {
+                    format!("{:#06x}  {:<24} // -> {}", instruction.pc, listing, target)
+                }
+                None => format!("{:#06x}  {}", instruction.pc, listing),
+            });
+        }
+        lines.join("\n")
+    }
+}
+
+/// Decodes the instructions of a range of bytecode, spanned by the statement generating them
+///
+/// The last push may read its data past the end of the range, up to the end of the bytecode.
+fn decode(
+    code: &[u8],
+    start: usize,
+    end: usize,
+    span: Option<Span>,
+) -> Vec<DisassembledInstruction> {
+    let mut instructions = vec![];
+    let mut pc = start;
+    while pc < end {
+        let mnemonic = Opcode::mnemonic(&format!("{:02x}", code[pc]));
+        let size = mnemonic.and_then(|m| m.strip_prefix("push")).and_then(|n| n.parse().ok());
+        let next = (pc + 1 + size.unwrap_or(0)).min(code.len());
+        instructions.push(DisassembledInstruction {
+            pc,
+            bytes: code[pc..next].to_vec(),
+            mnemonic,
+            table: None,
+            label: None,
+            target: None,
+            span: span.clone(),
+        });
+        pc = next;
+    }
+    instructions
+}
+
+/// Converts pushed bytes to an offset, saturating offsets that don't fit
+fn to_offset(data: &[u8]) -> usize {
+    let significant = data.iter().skip_while(|b| **b == 0).collect::<Vec<_>>();
+    if significant.len() > std::mem::size_of::<usize>() {
+        return usize::MAX
+    }
+    significant.into_iter().fold(0, |offset, b| (offset << 8) | *b as usize)
+}
+
+/// The path, number and trimmed code of the first source line of a span
+fn source_line(span: &Span) -> Option<(String, usize, String)> {
+    let file = span.file.as_ref()?;
+    let position = span.start_position()?;
+    let code = file.source.as_ref()?.lines().nth(position.line - 1)?.trim().to_string();
+    Some((file.path.clone(), position.line, code))
+}
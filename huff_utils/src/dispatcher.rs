@@ -0,0 +1,804 @@
+//! ## Dispatcher
+//!
+//! Recovers the function dispatcher of runtime bytecode as a decision tree.
+//!
+//! The bytecode is executed symbolically from its first instruction, tracking the selector
+//! loaded from calldata through the stack. Each `JUMPI` on the selector is a step of the
+//! dispatcher: an equality check dispatches a function, and an ordering check splits the
+//! selectors like binary search dispatchers do. Checks of the calldata size are recorded as the
+//! branches handling empty or short calldata, eg. the receive logic. The path taken when no
+//! selector matches is followed until it halts.
+//!
+//! Dispatchers jumping through a table indexed by the selector aren't recovered, their path
+//! ends at the computed jump.
+//!
+//! With the source map of the bytecode, each dispatched function is resolved to the macro it
+//! enters: the macro invoked at the label jumped to, or the macro defining the label when the
+//! function is implemented inline.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::{
+    ast::Contract,
+    bytecode::SourceMapping,
+    evm::{Opcode, OPCODES_MAP},
+};
+
+/// The maximum number of instructions executed over all the paths of the dispatcher
+const INSTRUCTION_BUDGET: usize = 100_000;
+
+/// The recovered dispatcher of runtime bytecode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dispatcher {
+    /// The path taken from the first instruction
+    pub root: DispatchPath,
+    /// The function signatures, eg. `transfer(address,uint256)`, by selector
+    pub names: BTreeMap<[u8; 4], String>,
+    /// The macros the dispatched functions enter, by selector
+    pub macros: BTreeMap<[u8; 4], String>,
+}
+
+/// A function selector of the dispatcher
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSelector {
+    /// The selector
+    pub selector: [u8; 4],
+    /// The signature of the function, if the contract defines it
+    pub signature: Option<String>,
+    /// The macro the function enters, if dispatched
+    pub macro_name: Option<String>,
+}
+
+/// A path through the dispatcher
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DispatchPath {
+    /// The branches on the path, in execution order
+    pub steps: Vec<DispatchStep>,
+    /// Where the path ends once it stops branching
+    pub fallthrough: Fallthrough,
+}
+
+/// A step of the dispatcher
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DispatchStep {
+    /// Loads the selector from calldata
+    Selector {
+        /// The offset of the instruction producing the selector
+        offset: usize,
+    },
+    /// Branches to the handling of short calldata, eg. the receive logic for empty calldata
+    Guard {
+        /// The offset of the `JUMPI`
+        offset: usize,
+        /// The calldata handled by the branch
+        check: CalldataCheck,
+        /// The offset the handling starts at
+        target: usize,
+    },
+    /// Dispatches a selector to its function
+    Match {
+        /// The offset of the `JUMPI`
+        offset: usize,
+        /// The selector matched
+        selector: [u8; 4],
+        /// The offset the function starts at
+        target: usize,
+        /// The static gas spent in the dispatcher before entering the function
+        gas: u64,
+        /// The offset of an earlier step the selector can't get past, if any
+        shadowed_by: Option<usize>,
+    },
+    /// Splits the selectors on a pivot
+    Split {
+        /// The offset of the `JUMPI`
+        offset: usize,
+        /// The comparison of the selectors taking the branch
+        comparison: Comparison,
+        /// The pivot selector
+        pivot: [u8; 4],
+        /// The path of the selectors taking the branch
+        taken: DispatchPath,
+    },
+    /// Branches on a condition unrelated to the calldata before the selector is loaded, eg. a
+    /// callvalue check
+    Branch {
+        /// The offset of the `JUMPI`
+        offset: usize,
+        /// The path taking the branch
+        taken: DispatchPath,
+    },
+}
+
+/// The calldata handled by a guard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalldataCheck {
+    /// Empty calldata
+    Empty,
+    /// Calldata shorter than a number of bytes
+    Shorter(u64),
+}
+
+impl fmt::Display for CalldataCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalldataCheck::Empty => write!(f, "empty calldata"),
+            CalldataCheck::Shorter(n) => write!(f, "calldata shorter than {} bytes", n),
+        }
+    }
+}
+
+/// The comparison of the selectors taking a split
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    /// Lower than the pivot
+    Less,
+    /// Lower than or equal to the pivot
+    LessOrEqual,
+    /// Greater than the pivot
+    Greater,
+    /// Greater than or equal to the pivot
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    /// The comparison of the selectors not taking the split
+    fn negate(self) -> Self {
+        match self {
+            Comparison::Less => Comparison::GreaterOrEqual,
+            Comparison::LessOrEqual => Comparison::Greater,
+            Comparison::Greater => Comparison::LessOrEqual,
+            Comparison::GreaterOrEqual => Comparison::Less,
+        }
+    }
+
+    /// Narrows an inclusive range of selectors to those satisfying the comparison
+    fn narrow(self, pivot: u32, (lo, hi): (u64, u64)) -> (u64, u64) {
+        let pivot = pivot as u64;
+        match self {
+            Comparison::Less => match pivot.checked_sub(1) {
+                Some(p) => (lo, hi.min(p)),
+                None => (lo.max(1), 0),
+            },
+            Comparison::LessOrEqual => (lo, hi.min(pivot)),
+            Comparison::Greater => (lo.max(pivot + 1), hi),
+            Comparison::GreaterOrEqual => (lo.max(pivot), hi),
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Comparison::Less => write!(f, "<"),
+            Comparison::LessOrEqual => write!(f, "<="),
+            Comparison::Greater => write!(f, ">"),
+            Comparison::GreaterOrEqual => write!(f, ">="),
+        }
+    }
+}
+
+/// Where a path of the dispatcher ends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fallthrough {
+    /// The offset after the last branch of the path
+    pub offset: usize,
+    /// The offset of the instruction ending the path
+    pub end: usize,
+    /// How the path ends
+    pub halt: Halt,
+}
+
+/// How a path of the dispatcher ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Halt {
+    /// A halting opcode, eg. `revert`
+    Opcode(&'static str),
+    /// Executing past the end of the bytecode
+    EndOfCode,
+    /// A branch on a condition unrelated to the selector, eg. in the fallback logic
+    Branch,
+    /// A jump to a destination computed at runtime, eg. through a jump table
+    ComputedJump,
+    /// A jump to an offset already executed on the path
+    Loop(usize),
+}
+
+impl fmt::Display for Halt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Halt::Opcode(o) => write!(f, "{}", o),
+            Halt::EndOfCode => write!(f, "runs past the end of the code"),
+            Halt::Branch => write!(f, "branches on a condition unrelated to the selector"),
+            Halt::ComputedJump => write!(f, "jumps to a computed destination"),
+            Halt::Loop(o) => write!(f, "jumps back to {:#06x}", o),
+        }
+    }
+}
+
+impl Dispatcher {
+    /// Recovers the dispatcher of hex encoded runtime bytecode
+    pub fn analyze(runtime: &str) -> Self {
+        let code = hex::decode(runtime.trim_start_matches("0x")).unwrap_or_default();
+        let mut walker = Walker { code: decode(&code), budget: INSTRUCTION_BUDGET };
+        let root = walker.walk(0, vec![], Constraints::default());
+        Self { root, names: BTreeMap::new(), macros: BTreeMap::new() }
+    }
+
+    /// Names the selectors after the functions of a contract
+    pub fn name_functions(&mut self, contract: &Contract) {
+        for f in contract.functions.iter() {
+            let types = f.inputs.iter().filter_map(|a| a.arg_type.clone()).collect::<Vec<_>>();
+            self.names.insert(f.signature, format!("{}({})", f.name, types.join(",")));
+        }
+    }
+
+    /// Resolves the dispatched functions to the macros they enter, from the source map of the
+    /// bytecode
+    ///
+    /// The function enters the macro invoked by the first instruction after the jump
+    /// destination, or the macro the destination's label is defined in otherwise.
+    pub fn resolve_macros(&mut self, source_map: &[SourceMapping]) {
+        let mut macros = BTreeMap::new();
+        for step in self.matches() {
+            let DispatchStep::Match { selector, target, shadowed_by: None, .. } = step else {
+                continue
+            };
+            let label = source_map.iter().find(|m| m.contains(*target));
+            let depth = label.map(|m| m.invocations.len()).unwrap_or_default();
+            let entered = source_map
+                .iter()
+                .find(|m| m.contains(target + 1))
+                .and_then(|m| m.invocations.get(depth))
+                .or_else(|| label.and_then(|m| m.invocations.last()))
+                .map(|mi| mi.macro_name.to_string())
+                .unwrap_or_else(|| "MAIN".to_string());
+            macros.entry(*selector).or_insert(entered);
+        }
+        self.macros = macros;
+    }
+
+    /// The functions dispatched, in the order their selectors are compared
+    pub fn matches(&self) -> Vec<&DispatchStep> {
+        let mut matches = vec![];
+        collect_matches(&self.root, &mut matches);
+        matches
+    }
+
+    /// The named functions no reachable step dispatches
+    pub fn undispatched(&self) -> Vec<&str> {
+        let dispatched = self
+            .matches()
+            .into_iter()
+            .filter_map(|m| match m {
+                DispatchStep::Match { selector, shadowed_by: None, .. } => Some(*selector),
+                _ => None,
+            })
+            .collect::<BTreeSet<[u8; 4]>>();
+        self.names
+            .iter()
+            .filter(|(s, _)| !dispatched.contains(*s))
+            .map(|(_, n)| n.as_str())
+            .collect()
+    }
+
+    /// Lists the dispatched functions in comparison order, one per line
+    pub fn list(&self) -> String {
+        let mut lines = self
+            .matches()
+            .into_iter()
+            .filter_map(|m| match m {
+                DispatchStep::Match { .. } => Some(self.describe(m)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        lines.extend(self.undispatched().iter().map(|n| format!("{} is never dispatched", n)));
+        lines.join("\n")
+    }
+
+    /// The selectors of the functions, the dispatched functions in comparison order followed by
+    /// the functions never dispatched
+    pub fn selectors(&self) -> Vec<FunctionSelector> {
+        let mut selectors: Vec<FunctionSelector> = vec![];
+        for step in self.matches() {
+            let DispatchStep::Match { selector, shadowed_by: None, .. } = step else { continue };
+            if selectors.iter().any(|s| s.selector == *selector) {
+                continue
+            }
+            selectors.push(FunctionSelector {
+                selector: *selector,
+                signature: self.names.get(selector).cloned(),
+                macro_name: self.macros.get(selector).cloned(),
+            });
+        }
+        for (selector, name) in self.names.iter() {
+            if !selectors.iter().any(|s| s.selector == *selector) {
+                selectors.push(FunctionSelector {
+                    selector: *selector,
+                    signature: Some(name.clone()),
+                    macro_name: None,
+                });
+            }
+        }
+        selectors
+    }
+
+    /// Renders the selectors as a table of their signatures and macros
+    pub fn table(&self) -> String {
+        let mut rows = vec![["SELECTOR".to_string(), "SIGNATURE".to_string(), "MACRO".to_string()]];
+        rows.extend(self.selectors().into_iter().map(|s| {
+            [
+                format!("0x{}", hex::encode(s.selector)),
+                s.signature.unwrap_or_else(|| "unknown".to_string()),
+                s.macro_name.unwrap_or_else(|| "-".to_string()),
+            ]
+        }));
+        let width = rows.iter().map(|[_, name, _]| name.len()).max().unwrap_or_default();
+        rows.iter()
+            .map(|[selector, name, macro_name]| {
+                format!("{:10}  {:width$}  {}", selector, name, macro_name, width = width)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Renders the selectors as a json array of their signatures and macros
+    pub fn to_json(&self) -> String {
+        let selectors = self
+            .selectors()
+            .into_iter()
+            .map(|s| {
+                serde_json::json!({
+                    "selector": format!("0x{}", hex::encode(s.selector)),
+                    "signature": s.signature,
+                    "macro": s.macro_name,
+                })
+            })
+            .collect::<Vec<_>>();
+        serde_json::to_string_pretty(&selectors).unwrap_or_default()
+    }
+
+    /// The signatures of the named functions, one per line, as imported by the 4byte directory
+    pub fn signatures(&self) -> String {
+        self.names.values().map(|n| format!("{}\n", n)).collect()
+    }
+
+    /// Renders the dispatcher as a decision tree
+    pub fn tree(&self) -> String {
+        let mut lines = vec!["Dispatcher".to_string()];
+        self.render(&self.root, "", &mut lines);
+        lines.extend(self.undispatched().iter().map(|n| format!("{} is never dispatched", n)));
+        lines.join("\n")
+    }
+
+    /// Renders the steps of a path, each prefixed by the indentation of its parents
+    fn render(&self, path: &DispatchPath, indent: &str, lines: &mut Vec<String>) {
+        for step in path.steps.iter() {
+            lines.push(format!("{}├─ {}", indent, self.describe(step)));
+            match step {
+                DispatchStep::Split { taken, .. } | DispatchStep::Branch { taken, .. } => {
+                    self.render(taken, &format!("{}│  ", indent), lines)
+                }
+                _ => {}
+            }
+        }
+        let fallthrough = &path.fallthrough;
+        lines.push(format!(
+            "{}└─ {:#06x} fallthrough: {} at {:#06x}",
+            indent, fallthrough.offset, fallthrough.halt, fallthrough.end
+        ));
+    }
+
+    /// Describes a step on a single line
+    fn describe(&self, step: &DispatchStep) -> String {
+        match step {
+            DispatchStep::Selector { offset } => format!("{:#06x} selector loaded", offset),
+            DispatchStep::Guard { offset, check, target } => {
+                let handler = match check {
+                    CalldataCheck::Empty => " (receive)",
+                    CalldataCheck::Shorter(_) => "",
+                };
+                format!("{:#06x} {} -> {:#06x}{}", offset, check, target, handler)
+            }
+            DispatchStep::Match { offset, selector, target, gas, shadowed_by } => {
+                let name = self.names.get(selector).map(String::as_str).unwrap_or("unknown");
+                let mut line = format!(
+                    "{:#06x} 0x{} {} -> {:#06x}, {} gas",
+                    offset,
+                    hex::encode(selector),
+                    name,
+                    target,
+                    gas
+                );
+                if let Some(s) = shadowed_by {
+                    line.push_str(&format!(", unreachable past {:#06x}", s));
+                }
+                line
+            }
+            DispatchStep::Split { offset, comparison, pivot, .. } => {
+                format!("{:#06x} if selector {} 0x{}", offset, comparison, hex::encode(pivot))
+            }
+            DispatchStep::Branch { offset, .. } => format!("{:#06x} if condition", offset),
+        }
+    }
+}
+
+/// Collects the match steps of a path, in comparison order
+fn collect_matches<'a>(path: &'a DispatchPath, matches: &mut Vec<&'a DispatchStep>) {
+    for step in path.steps.iter() {
+        match step {
+            DispatchStep::Match { .. } => matches.push(step),
+            DispatchStep::Split { taken, .. } | DispatchStep::Branch { taken, .. } => {
+                collect_matches(taken, matches)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A decoded instruction
+#[derive(Debug, Clone)]
+struct Instruction {
+    /// The mnemonic of the opcode, `None` for undefined opcodes
+    mnemonic: Option<&'static str>,
+    /// The opcode
+    opcode: Option<Opcode>,
+    /// The pushed bytes of a push
+    data: Vec<u8>,
+}
+
+/// Decodes bytecode into its instructions, by offset
+fn decode(code: &[u8]) -> BTreeMap<usize, Instruction> {
+    let mnemonics = (0..=u8::MAX)
+        .map(|b| Opcode::mnemonic(&format!("{:02x}", b)))
+        .collect::<Vec<Option<&'static str>>>();
+    let mut instructions = BTreeMap::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let mnemonic = mnemonics[code[pc] as usize];
+        let size = mnemonic.and_then(|m| m.strip_prefix("push")).and_then(|n| n.parse().ok());
+        let end = (pc + 1 + size.unwrap_or(0)).min(code.len());
+        instructions.insert(
+            pc,
+            Instruction {
+                mnemonic,
+                opcode: mnemonic.and_then(|m| OPCODES_MAP.get(m).cloned()),
+                data: code[pc + 1..end].to_vec(),
+            },
+        );
+        pc = end;
+    }
+    instructions
+}
+
+/// A symbolic stack value
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    /// A known constant, big endian without leading zeros
+    Const(Vec<u8>),
+    /// The first calldata word
+    CalldataWord,
+    /// The 4 byte function selector
+    Selector,
+    /// The calldata size
+    CalldataSize,
+    /// Whether the selector equals a selector
+    Equals(u32, bool),
+    /// Whether the selector compares to a pivot
+    Compares(Comparison, u32),
+    /// Whether the calldata is handled by a guard
+    Calldata(CalldataCheck, bool),
+    /// Any other value
+    Unknown,
+}
+
+impl Value {
+    /// Builds a constant from big endian bytes
+    fn constant(bytes: &[u8]) -> Self {
+        let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+        Value::Const(bytes[start..].to_vec())
+    }
+
+    /// The value of a constant fitting in 8 bytes
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Const(b) if b.len() <= 8 => {
+                Some(b.iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64))
+            }
+            _ => None,
+        }
+    }
+
+    /// The value of a constant fitting in a selector
+    fn as_selector(&self) -> Option<u32> {
+        self.as_u64().and_then(|v| u32::try_from(v).ok())
+    }
+
+    /// The negated boolean of a condition
+    fn negate(self) -> Self {
+        match self {
+            Value::Equals(s, eq) => Value::Equals(s, !eq),
+            Value::Compares(c, p) => Value::Compares(c.negate(), p),
+            Value::Calldata(c, handled) => Value::Calldata(c, !handled),
+            Value::CalldataSize => Value::Calldata(CalldataCheck::Empty, true),
+            Value::Selector => Value::Equals(0, true),
+            _ => Value::Unknown,
+        }
+    }
+}
+
+/// The constraints on the selector along a path
+#[derive(Debug, Clone)]
+struct Constraints {
+    /// The inclusive range of selectors reaching the path, with the step narrowing each bound
+    range: ((u64, Option<usize>), (u64, Option<usize>)),
+    /// The selectors already dispatched, with the step dispatching them
+    matched: BTreeMap<u32, usize>,
+    /// Whether the selector is loaded
+    loaded: bool,
+    /// The static gas spent on the path
+    gas: u64,
+    /// The offsets executed on the path
+    visited: BTreeSet<usize>,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Self {
+            range: ((0, None), (u32::MAX as u64, None)),
+            matched: BTreeMap::new(),
+            loaded: false,
+            gas: 0,
+            visited: BTreeSet::new(),
+        }
+    }
+}
+
+impl Constraints {
+    /// The offset of the step a selector can't get past, if any
+    fn shadowing(&self, selector: u32) -> Option<usize> {
+        let ((lo, lo_at), (hi, hi_at)) = self.range;
+        match self.matched.get(&selector) {
+            Some(at) => Some(*at),
+            None if (selector as u64) < lo => lo_at,
+            None if (selector as u64) > hi => hi_at,
+            None => None,
+        }
+    }
+
+    /// Narrows the selectors reaching the path by a split at an offset
+    fn narrow(&mut self, comparison: Comparison, pivot: u32, offset: usize) {
+        let ((lo, lo_at), (hi, hi_at)) = self.range;
+        let (new_lo, new_hi) = comparison.narrow(pivot, (lo, hi));
+        self.range = (
+            (new_lo, if new_lo != lo { Some(offset) } else { lo_at }),
+            (new_hi, if new_hi != hi { Some(offset) } else { hi_at }),
+        );
+    }
+}
+
+/// Symbolically executes the paths of the dispatcher
+struct Walker {
+    /// The decoded instructions
+    code: BTreeMap<usize, Instruction>,
+    /// The instructions left to execute
+    budget: usize,
+}
+
+impl Walker {
+    /// Executes a path from an offset until it stops branching on the selector
+    fn walk(&mut self, start: usize, mut stack: Vec<Value>, mut path: Constraints) -> DispatchPath {
+        let mut steps = vec![];
+        let mut pc = start;
+        let mut last_branch = start;
+        let (end, halt) = loop {
+            if !path.visited.insert(pc) {
+                break (pc, Halt::Loop(pc))
+            }
+            let instruction = match self.code.get(&pc) {
+                Some(i) if self.budget > 0 => i.clone(),
+                _ => break (pc, Halt::EndOfCode),
+            };
+            self.budget -= 1;
+            let next = pc + 1 + instruction.data.len();
+            let (mnemonic, opcode) = match (instruction.mnemonic, instruction.opcode) {
+                (Some(m), Some(o)) => (m, o),
+                _ => {
+                    let halt = Halt::Opcode("invalid");
+                    break (pc, halt)
+                }
+            };
+            path.gas += opcode.gas();
+
+            let mut pop = || stack.pop().unwrap_or(Value::Unknown);
+            let result = match opcode {
+                Opcode::Stop |
+                Opcode::Return |
+                Opcode::Revert |
+                Opcode::Invalid |
+                Opcode::Selfdestruct => {
+                    let halt = Halt::Opcode(mnemonic);
+                    break (pc, halt)
+                }
+                Opcode::Jump => match pop().as_u64() {
+                    Some(dest) => {
+                        pc = dest as usize;
+                        continue
+                    }
+                    None => {
+                        let halt = Halt::ComputedJump;
+                        break (pc, halt)
+                    }
+                },
+                Opcode::Jumpi => {
+                    let (dest, condition) = (pop().as_u64(), pop());
+                    let dest = match dest {
+                        Some(d) => d as usize,
+                        None => {
+                            let halt = Halt::ComputedJump;
+                            break (pc, halt)
+                        }
+                    };
+                    let condition = match condition {
+                        Value::CalldataSize => Value::Calldata(CalldataCheck::Empty, false),
+                        Value::Selector => Value::Equals(0, false),
+                        c => c,
+                    };
+                    match condition {
+                        // Jumps to the function on a match, or past it otherwise
+                        Value::Equals(selector, equal) => {
+                            let (target, continued) =
+                                if equal { (dest, next) } else { (next, dest) };
+                            steps.push(DispatchStep::Match {
+                                offset: pc,
+                                selector: selector.to_be_bytes(),
+                                target,
+                                gas: path.gas,
+                                shadowed_by: path.shadowing(selector),
+                            });
+                            path.matched.entry(selector).or_insert(pc);
+                            pc = continued;
+                        }
+                        Value::Compares(comparison, pivot) => {
+                            let mut taken = path.clone();
+                            taken.narrow(comparison, pivot, pc);
+                            let taken = self.walk(dest, stack.clone(), taken);
+                            steps.push(DispatchStep::Split {
+                                offset: pc,
+                                comparison,
+                                pivot: pivot.to_be_bytes(),
+                                taken,
+                            });
+                            path.narrow(comparison.negate(), pivot, pc);
+                            pc = next;
+                        }
+                        Value::Calldata(check, handled) => {
+                            let (target, continued) =
+                                if handled { (dest, next) } else { (next, dest) };
+                            steps.push(DispatchStep::Guard { offset: pc, check, target });
+                            pc = continued;
+                        }
+                        _ if !path.loaded => {
+                            let taken = self.walk(dest, stack.clone(), path.clone());
+                            steps.push(DispatchStep::Branch { offset: pc, taken });
+                            pc = next;
+                        }
+                        _ => {
+                            let halt = Halt::Branch;
+                            break (pc, halt)
+                        }
+                    }
+                    last_branch = pc;
+                    continue
+                }
+                Opcode::Calldataload => match pop().as_u64() {
+                    Some(0) => Value::CalldataWord,
+                    _ => Value::Unknown,
+                },
+                Opcode::Calldatasize => Value::CalldataSize,
+                Opcode::Shr => match (pop().as_u64(), pop()) {
+                    (Some(224), Value::CalldataWord) => Value::Selector,
+                    _ => Value::Unknown,
+                },
+                Opcode::Div => match (pop(), pop()) {
+                    (Value::CalldataWord, Value::Const(d))
+                        if d.len() == 29 && d[0] == 1 && d[1..].iter().all(|b| *b == 0) =>
+                    {
+                        Value::Selector
+                    }
+                    _ => Value::Unknown,
+                },
+                Opcode::And => match (pop(), pop()) {
+                    (Value::Selector, m) | (m, Value::Selector)
+                        if m.as_u64() == Some(0xffffffff) =>
+                    {
+                        Value::Selector
+                    }
+                    _ => Value::Unknown,
+                },
+                Opcode::Eq => match (pop(), pop()) {
+                    (Value::Selector, s) | (s, Value::Selector) if s.as_selector().is_some() => {
+                        Value::Equals(s.as_selector().unwrap_or_default(), true)
+                    }
+                    (Value::CalldataSize, z) | (z, Value::CalldataSize)
+                        if z.as_u64() == Some(0) =>
+                    {
+                        Value::Calldata(CalldataCheck::Empty, true)
+                    }
+                    _ => Value::Unknown,
+                },
+                // Nonzero when the selector differs
+                Opcode::Xor | Opcode::Sub => match (pop(), pop()) {
+                    (Value::Selector, s) | (s, Value::Selector) if s.as_selector().is_some() => {
+                        Value::Equals(s.as_selector().unwrap_or_default(), false)
+                    }
+                    _ => Value::Unknown,
+                },
+                Opcode::Iszero => pop().negate(),
+                Opcode::Lt | Opcode::Gt => {
+                    let (a, b) = (pop(), pop());
+                    let (a, b) = if opcode == Opcode::Lt { (a, b) } else { (b, a) };
+                    // a < b
+                    match (a, b) {
+                        (Value::Selector, p) if p.as_selector().is_some() => {
+                            Value::Compares(Comparison::Less, p.as_selector().unwrap_or_default())
+                        }
+                        (p, Value::Selector) if p.as_selector().is_some() => Value::Compares(
+                            Comparison::Greater,
+                            p.as_selector().unwrap_or_default(),
+                        ),
+                        (Value::CalldataSize, n) if n.as_u64().is_some() => Value::Calldata(
+                            CalldataCheck::Shorter(n.as_u64().unwrap_or_default()),
+                            true,
+                        ),
+                        (n, Value::CalldataSize) if n.as_u64() == Some(0) => {
+                            Value::Calldata(CalldataCheck::Empty, false)
+                        }
+                        _ => Value::Unknown,
+                    }
+                }
+                Opcode::Pop => {
+                    pop();
+                    pc = next;
+                    continue
+                }
+                _ => {
+                    let byte = opcode.byte();
+                    if (0x60..=0x7f).contains(&byte) {
+                        Value::constant(&instruction.data)
+                    } else if (0x80..=0x8f).contains(&byte) {
+                        let depth = (byte - 0x7f) as usize;
+                        match stack.len().checked_sub(depth) {
+                            Some(i) => stack[i].clone(),
+                            None => Value::Unknown,
+                        }
+                    } else if (0x90..=0x9f).contains(&byte) {
+                        let depth = (byte - 0x8f) as usize;
+                        while stack.len() <= depth {
+                            stack.insert(0, Value::Unknown);
+                        }
+                        let top = stack.len() - 1;
+                        stack.swap(top, top - depth);
+                        pc = next;
+                        continue
+                    } else {
+                        let (inputs, outputs) = opcode.stack_effect();
+                        stack.truncate(stack.len().saturating_sub(inputs));
+                        stack.resize(stack.len() + outputs, Value::Unknown);
+                        pc = next;
+                        continue
+                    }
+                }
+            };
+            if result == Value::Selector && !path.loaded {
+                path.loaded = true;
+                steps.push(DispatchStep::Selector { offset: pc });
+            }
+            stack.push(result);
+            pc = next;
+        };
+        DispatchPath { steps, fallthrough: Fallthrough { offset: last_branch, end, halt } }
+    }
+}
@@ -0,0 +1,303 @@
+//! ## Docs
+//!
+//! The API documentation of a contract or library, generated by `huffc doc`.
+//!
+//! Documents the functions with their selectors, the events with their topics, the constants
+//! with their values, the storage layout of the free storage pointers, and the macros with their
+//! stack effects, each with its NatSpec doc comment. The documentation is rendered as Markdown,
+//! or as a standalone HTML page.
+
+use std::{fs, path::Path};
+
+use crate::{
+    abi::Abi,
+    ast::{Argument, ConstVal, Contract},
+    manifest::SymbolManifest,
+    natspec::NatSpec,
+    symbols::SymbolKind,
+};
+
+/// The documentation of a contract
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Documentation {
+    /// The contract name, the file stem of its entrypoint
+    pub name: String,
+    /// The functions, with their selectors
+    pub functions: Vec<ItemDoc>,
+    /// The events, with their topics
+    pub events: Vec<ItemDoc>,
+    /// The constants defined with a literal, with their values
+    pub constants: Vec<ItemDoc>,
+    /// The constants defined with `FREE_STORAGE_POINTER()`, with their storage slots
+    pub storage: Vec<ItemDoc>,
+    /// The macros, with their stack effects
+    pub macros: Vec<ItemDoc>,
+}
+
+/// The documentation of a definition
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ItemDoc {
+    /// The definition name
+    pub name: String,
+    /// The definition, eg. `#define function transfer(address to, uint256 amount) nonpayable`
+    pub definition: String,
+    /// The selector of a function, the topic of an event, the value of a constant, the slot of a
+    /// storage pointer or the stack effect of a macro
+    pub value: String,
+    /// The parameter names, in order
+    pub parameters: Vec<String>,
+    /// The doc comment
+    pub natspec: NatSpec,
+}
+
+/// A cell of a documentation table
+enum Cell {
+    Code(String),
+    Text(String),
+}
+
+/// A block of the rendered documentation
+enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    Code(String),
+    Table(Vec<&'static str>, Vec<Vec<Cell>>),
+}
+
+impl Documentation {
+    /// Builds the documentation of a contract
+    ///
+    /// Like for its manifest, the contract must not have its storage pointers derived yet.
+    pub fn new(name: &str, contract: &Contract) -> Self {
+        let abi = Abi::from(contract.clone());
+        let natspec = |kind: SymbolKind, name: &str| {
+            contract.natspec.get(&(kind, name.to_string())).cloned().unwrap_or_default()
+        };
+        let arguments = |args: &[Argument]| {
+            args.iter()
+                .map(|a| {
+                    let arg_type = a.arg_type.clone().unwrap_or_default();
+                    let indexed = if a.indexed { " indexed" } else { "" };
+                    format!("{}{} {}", arg_type, indexed, a.name.clone().unwrap_or_default())
+                        .trim_end()
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let names = |args: &[Argument]| args.iter().filter_map(|a| a.name.clone()).collect();
+
+        let mut docs = Self { name: name.to_string(), ..Default::default() };
+        for f in contract.functions.iter() {
+            docs.functions.push(ItemDoc {
+                name: f.name.to_string(),
+                definition: format!(
+                    "#define function {}({}) {} returns ({})",
+                    f.name,
+                    arguments(&f.inputs),
+                    f.fn_type,
+                    arguments(&f.outputs)
+                ),
+                value: format!("0x{}", hex::encode(f.signature)),
+                parameters: names(&f.inputs),
+                natspec: natspec(SymbolKind::Function, &f.name),
+            });
+        }
+        for e in contract.events.iter() {
+            let Some(topic) = abi.events.get(&e.name).map(|e| e.topic()) else { continue };
+            docs.events.push(ItemDoc {
+                name: e.name.clone(),
+                definition: format!("#define event {}({})", e.name, arguments(&e.parameters)),
+                value: format!("0x{}", hex::encode(topic)),
+                parameters: names(&e.parameters),
+                natspec: natspec(SymbolKind::Event, &e.name),
+            });
+        }
+
+        let manifest = SymbolManifest::new(name, contract);
+        for (constants, docs, storage) in [
+            (&manifest.constants, &mut docs.constants, false),
+            (&manifest.storage, &mut docs.storage, true),
+        ] {
+            docs.extend(constants.iter().map(|c| {
+                let reservation =
+                    contract.constants.iter().find(|d| d.name == c.name).and_then(|d| {
+                        match &d.value {
+                            ConstVal::FreeStoragePointer(fsp) if storage => Some(fsp.clone()),
+                            _ => None,
+                        }
+                    });
+                let (definition, value) = match reservation {
+                    Some(fsp) if fsp.size > 1 => {
+                        (fsp.to_string(), format!("{} ({} slots)", c.value, fsp.size))
+                    }
+                    Some(fsp) => (fsp.to_string(), c.value.clone()),
+                    None => (c.value.clone(), c.value.clone()),
+                };
+                ItemDoc {
+                    name: c.name.clone(),
+                    definition: format!("#define constant {} = {}", c.name, definition),
+                    value,
+                    parameters: vec![],
+                    natspec: natspec(SymbolKind::Constant, &c.name),
+                }
+            }));
+        }
+        docs.macros = manifest
+            .macros
+            .iter()
+            .map(|m| ItemDoc {
+                name: m.name.clone(),
+                definition: format!("#define macro {}", m),
+                value: format!("takes ({}) returns ({})", m.takes, m.returns),
+                parameters: m.parameters.clone(),
+                natspec: natspec(SymbolKind::Macro, &m.name),
+            })
+            .collect();
+        docs
+    }
+
+    /// Renders the documentation as Markdown
+    pub fn to_markdown(&self) -> String {
+        let cell = |c: &Cell| match c {
+            Cell::Code(s) => format!("`{}`", s),
+            Cell::Text(s) => s.replace('|', "\\|").replace('\n', " "),
+        };
+        let mut out = String::new();
+        for block in self.blocks() {
+            match block {
+                Block::Heading(level, text) => {
+                    out.push_str(&format!("{} {}\n\n", "#".repeat(level), text))
+                }
+                Block::Paragraph(text) => out.push_str(&format!("{}\n\n", text)),
+                Block::Code(code) => out.push_str(&format!("```huff\n{}\n```\n\n", code)),
+                Block::Table(headers, rows) => {
+                    out.push_str(&format!("| {} |\n", headers.join(" | ")));
+                    out.push_str(&format!("|{}\n", "---|".repeat(headers.len())));
+                    for row in rows.iter() {
+                        let cells = row.iter().map(cell).collect::<Vec<_>>();
+                        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+                    }
+                    out.push('\n');
+                }
+            }
+        }
+        out.trim_end().to_string() + "\n"
+    }
+
+    /// Renders the documentation as a standalone HTML page
+    pub fn to_html(&self) -> String {
+        let cell = |c: &Cell| match c {
+            Cell::Code(s) => format!("<td><code>{}</code></td>", escape(s)),
+            Cell::Text(s) => format!("<td>{}</td>", escape(s)),
+        };
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+             </head>\n<body>\n",
+            escape(&self.name)
+        );
+        for block in self.blocks() {
+            match block {
+                Block::Heading(level, text) => {
+                    out.push_str(&format!("<h{level}>{}</h{level}>\n", escape(&text)))
+                }
+                Block::Paragraph(text) => {
+                    out.push_str(&format!("<p>{}</p>\n", escape(&text).replace('\n', "<br>")))
+                }
+                Block::Code(code) => {
+                    out.push_str(&format!("<pre><code>{}</code></pre>\n", escape(&code)))
+                }
+                Block::Table(headers, rows) => {
+                    let headers = headers.iter().map(|h| format!("<th>{}</th>", h));
+                    out.push_str(&format!(
+                        "<table>\n<tr>{}</tr>\n",
+                        headers.collect::<Vec<_>>().join("")
+                    ));
+                    for row in rows.iter() {
+                        let cells = row.iter().map(cell).collect::<Vec<_>>();
+                        out.push_str(&format!("<tr>{}</tr>\n", cells.join("")));
+                    }
+                    out.push_str("</table>\n");
+                }
+            }
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    /// Exports the documentation to a file, as HTML if its extension is `html` and as Markdown
+    /// otherwise
+    pub fn export(&self, out: &str) -> Result<(), std::io::Error> {
+        let file_path = Path::new(out);
+        let rendered = match file_path.extension().and_then(|e| e.to_str()) {
+            Some("html") => self.to_html(),
+            _ => self.to_markdown(),
+        };
+        if let Some(p) = file_path.parent() {
+            fs::create_dir_all(p)?
+        }
+        fs::write(file_path, rendered)
+    }
+
+    /// The blocks of the rendered documentation
+    ///
+    /// Each section lists its definitions in a table, with the first line of their notice, or
+    /// of their details if they have no notice. Functions, events and macros are then detailed
+    /// one by one, with their full doc comment.
+    fn blocks(&self) -> Vec<Block> {
+        let mut blocks = vec![Block::Heading(1, self.name.clone())];
+        let sections = [
+            ("Functions", "Function", "Selector", &self.functions, true),
+            ("Events", "Event", "Topic", &self.events, true),
+            ("Constants", "Constant", "Value", &self.constants, false),
+            ("Storage Layout", "Constant", "Slot", &self.storage, false),
+            ("Macros", "Macro", "Stack", &self.macros, true),
+        ];
+        for (title, kind, value, items, detailed) in sections {
+            if items.is_empty() {
+                continue
+            }
+            blocks.push(Block::Heading(2, title.to_string()));
+            let rows = items
+                .iter()
+                .map(|i| {
+                    let summary = i.natspec.notice.as_ref().or(i.natspec.dev.as_ref());
+                    let summary = summary.and_then(|s| s.lines().next()).unwrap_or_default();
+                    vec![
+                        Cell::Code(i.name.clone()),
+                        Cell::Code(i.value.clone()),
+                        Cell::Text(summary.to_string()),
+                    ]
+                })
+                .collect();
+            blocks.push(Block::Table(vec![kind, value, "Description"], rows));
+            if !detailed {
+                continue
+            }
+            for item in items.iter() {
+                blocks.push(Block::Heading(3, item.name.clone()));
+                blocks.push(Block::Code(item.definition.clone()));
+                blocks.extend(item.natspec.notice.iter().cloned().map(Block::Paragraph));
+                blocks.extend(item.natspec.dev.iter().cloned().map(Block::Paragraph));
+                if !item.natspec.params.is_empty() {
+                    // The parameters are listed in order, then those that aren't defined
+                    let mut params = item.natspec.params.iter().collect::<Vec<_>>();
+                    params.sort_by_key(|(p, _)| {
+                        item.parameters.iter().position(|n| n == *p).unwrap_or(usize::MAX)
+                    });
+                    let rows = params
+                        .into_iter()
+                        .map(|(p, d)| vec![Cell::Code(p.clone()), Cell::Text(d.clone())])
+                        .collect();
+                    blocks.push(Block::Table(vec!["Parameter", "Description"], rows));
+                }
+            }
+        }
+        blocks
+    }
+}
+
+/// Escapes the HTML special characters of a text
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -55,47 +55,186 @@ pub enum ParserErrorKind {
     InvalidReturnArgs,
     /// Invalid import path
     InvalidImportPath(String),
+    /// A table file that can't be read
+    InvalidTableFile(String),
+    /// An imported artifact that can't be read
+    InvalidArtifactImport(String),
+    /// Unexpected token in alias body
+    InvalidTokenInAliasBody(TokenKind),
+    /// A `__REPEAT` count that isn't a literal of at most
+    /// [MAX_REPEAT_COUNT](crate::ast::MAX_REPEAT_COUNT)
+    InvalidRepeatCount(String),
+    /// A constant override naming a constant the contract doesn't define
+    UnknownConstantOverride(String),
+    /// A `__VERBATIM` literal that isn't a whole number of bytes
+    InvalidVerbatimBytes(String),
+    /// A macro parameter without a default, following a parameter with one
+    MissingParameterDefault(String),
+    /// A reference to an internal definition of another file
+    PrivateDefinition(String),
+}
+
+impl ParserErrorKind {
+    /// The stable error code of the parser error kind
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParserErrorKind::SyntaxError(_) => "H0201",
+            ParserErrorKind::UnexpectedType(_) => "H0202",
+            ParserErrorKind::InvalidDefinition => "H0203",
+            ParserErrorKind::InvalidConstantValue(_) => "H0204",
+            ParserErrorKind::InvalidTokenInMacroBody(_) => "H0205",
+            ParserErrorKind::InvalidTokenInLabelDefinition(_) => "H0206",
+            ParserErrorKind::InvalidSingleArg(_) => "H0207",
+            ParserErrorKind::InvalidTableBodyToken(_) => "H0208",
+            ParserErrorKind::InvalidConstant(_) => "H0209",
+            ParserErrorKind::InvalidArgCallIdent(_) => "H0210",
+            ParserErrorKind::InvalidName(_) => "H0211",
+            ParserErrorKind::InvalidArgs(_) => "H0212",
+            ParserErrorKind::InvalidUint256(_) => "H0213",
+            ParserErrorKind::InvalidBytes(_) => "H0214",
+            ParserErrorKind::InvalidInt(_) => "H0215",
+            ParserErrorKind::InvalidMacroArgs(_) => "H0216",
+            ParserErrorKind::InvalidReturnArgs => "H0217",
+            ParserErrorKind::InvalidImportPath(_) => "H0218",
+            ParserErrorKind::InvalidTableFile(_) => "H0219",
+            ParserErrorKind::InvalidArtifactImport(_) => "H0220",
+            ParserErrorKind::InvalidTokenInAliasBody(_) => "H0221",
+            ParserErrorKind::InvalidRepeatCount(_) => "H0222",
+            ParserErrorKind::UnknownConstantOverride(_) => "H0223",
+            ParserErrorKind::InvalidVerbatimBytes(_) => "H0224",
+            ParserErrorKind::MissingParameterDefault(_) => "H0225",
+            ParserErrorKind::PrivateDefinition(_) => "H0226",
+        }
+    }
+
+    /// A message describing the parser error kind, without any source location
+    pub fn message(&self) -> String {
+        match self {
+            ParserErrorKind::SyntaxError(se) => format!("Syntax Error: \"{}\"", se),
+            ParserErrorKind::UnexpectedType(ut) => format!("Unexpected Type: \"{}\"", ut),
+            ParserErrorKind::InvalidDefinition => "Invalid Definition".to_string(),
+            ParserErrorKind::InvalidConstantValue(cv) => {
+                format!("Invalid Constant Value: \"{}\"", cv)
+            }
+            ParserErrorKind::InvalidTokenInMacroBody(tmb) => {
+                format!("Invalid Token In Macro Body: \"{}\"", tmb)
+            }
+            ParserErrorKind::InvalidTokenInLabelDefinition(tlb) => {
+                format!("Invalid Token In Label Definition: \"{}\"", tlb)
+            }
+            ParserErrorKind::InvalidSingleArg(sa) => format!("Invalid Argument: \"{}\"", sa),
+            ParserErrorKind::InvalidTableBodyToken(tbt) => {
+                format!("Invalid Token In Table Body: \"{}\"", tbt)
+            }
+            ParserErrorKind::InvalidConstant(c) => format!("Invalid Constant: \"{}\"", c),
+            ParserErrorKind::InvalidArgCallIdent(aci) => {
+                format!("Invalid Argument Call Identifier: \"{}\"", aci)
+            }
+            ParserErrorKind::InvalidName(name) => format!("Invalid Name: \"{}\"", name),
+            ParserErrorKind::InvalidArgs(args) => format!("Invalid Arguments: \"{}\"", args),
+            ParserErrorKind::InvalidUint256(v) => format!("Invalid Uint256 Value: \"{}\"", v),
+            ParserErrorKind::InvalidBytes(b) => format!("Invalid Bytes Value: \"{}\"", b),
+            ParserErrorKind::InvalidInt(i) => format!("Invalid Int Value: \"{}\"", i),
+            ParserErrorKind::InvalidMacroArgs(ma) => {
+                format!("Invalid Macro Arguments: \"{}\"", ma)
+            }
+            ParserErrorKind::InvalidReturnArgs => "Invalid Return Arguments".to_string(),
+            ParserErrorKind::InvalidImportPath(ip) => format!("Invalid Import Path: \"{}\"", ip),
+            ParserErrorKind::InvalidTableFile(tf) => format!("Invalid Table File: \"{}\"", tf),
+            ParserErrorKind::InvalidArtifactImport(ai) => {
+                format!("Invalid Artifact Import: \"{}\"", ai)
+            }
+            ParserErrorKind::InvalidTokenInAliasBody(tab) => {
+                format!("Invalid Token In Alias Body: \"{}\"", tab)
+            }
+            ParserErrorKind::InvalidRepeatCount(rc) => {
+                format!("Invalid Repeat Count: \"{}\"", rc)
+            }
+            ParserErrorKind::UnknownConstantOverride(uco) => {
+                format!("Unknown Constant Override: \"{}\"", uco)
+            }
+            ParserErrorKind::InvalidVerbatimBytes(ivb) => {
+                format!("Invalid Verbatim Bytes: \"{}\"", ivb)
+            }
+            ParserErrorKind::MissingParameterDefault(mpd) => {
+                format!("Missing Parameter Default: \"{}\"", mpd)
+            }
+            ParserErrorKind::PrivateDefinition(pd) => format!("Private Definition: \"{}\"", pd),
+        }
+    }
 }
 
 /// A Lexing Error
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub struct LexicalError<'a> {
+pub struct LexicalError {
     /// The kind of error
-    pub kind: LexicalErrorKind<'a>,
+    pub kind: LexicalErrorKind,
     /// The span where the error occurred
     pub span: Span,
 }
 
-impl<'a> LexicalError<'a> {
+impl LexicalError {
     /// Public associated function to instatiate a new LexicalError.
-    pub fn new(kind: LexicalErrorKind<'a>, span: Span) -> Self {
+    pub fn new(kind: LexicalErrorKind, span: Span) -> Self {
         Self { kind, span }
     }
 }
 
 /// A Lexical Error Kind
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub enum LexicalErrorKind<'a> {
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LexicalErrorKind {
     /// Unexpected end of file
     UnexpectedEof,
     /// Invalid character
     InvalidCharacter(char),
     /// Invalid Array Size
     /// String param expected to be usize parsable
-    InvalidArraySize(&'a str),
+    InvalidArraySize(String),
     /// Invalid Primitive EVM Type
-    InvalidPrimitiveType(&'a str),
+    InvalidPrimitiveType(String),
+    /// A hex literal longer than 32 bytes
+    LiteralTooLong(String),
+    /// A binary literal without any digit after its `0b` prefix
+    EmptyLiteral(String),
+}
+
+impl LexicalErrorKind {
+    /// The stable error code of the lexical error kind
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexicalErrorKind::UnexpectedEof => "H0101",
+            LexicalErrorKind::InvalidCharacter(_) => "H0102",
+            LexicalErrorKind::InvalidArraySize(_) => "H0103",
+            LexicalErrorKind::InvalidPrimitiveType(_) => "H0104",
+            LexicalErrorKind::LiteralTooLong(_) => "H0105",
+            LexicalErrorKind::EmptyLiteral(_) => "H0106",
+        }
+    }
+
+    /// A message describing the lexical error kind, without any source location
+    pub fn message(&self) -> String {
+        match self {
+            LexicalErrorKind::UnexpectedEof => "Unexpected End Of File".to_string(),
+            LexicalErrorKind::InvalidCharacter(c) => format!("Invalid Character: \"{}\"", c),
+            LexicalErrorKind::InvalidArraySize(a) => format!("Invalid Array Size: \"{}\"", a),
+            LexicalErrorKind::InvalidPrimitiveType(ty) => {
+                format!("Invalid Primitive Type: \"{}\"", ty)
+            }
+            LexicalErrorKind::LiteralTooLong(l) => format!("Literal Exceeds 32 Bytes: \"{}\"", l),
+            LexicalErrorKind::EmptyLiteral(l) => format!("Literal Has No Digits: \"{}\"", l),
+        }
+    }
 }
 
-impl<'a> Spanned for LexicalError<'a> {
+impl Spanned for LexicalError {
     fn span(&self) -> Span {
         self.span.clone()
     }
 }
 
-impl<'a, W: Write> Report<W> for LexicalError<'a> {
+impl<W: Write> Report<W> for LexicalError {
     fn report(&self, f: &mut Reporter<'_, W>) -> std::io::Result<()> {
-        match self.kind {
+        match &self.kind {
             LexicalErrorKind::InvalidCharacter(ch) => write!(f.out, "Invalid character '{}'", ch),
             LexicalErrorKind::UnexpectedEof => write!(f.out, "Found unexpected EOF"),
             LexicalErrorKind::InvalidArraySize(str) => {
@@ -104,12 +243,18 @@ impl<'a, W: Write> Report<W> for LexicalError<'a> {
             LexicalErrorKind::InvalidPrimitiveType(str) => {
                 write!(f.out, "Invalid Primitive EVM Type '{}'", str)
             }
+            LexicalErrorKind::LiteralTooLong(str) => {
+                write!(f.out, "Literal '{}' exceeds 32 bytes", str)
+            }
+            LexicalErrorKind::EmptyLiteral(str) => {
+                write!(f.out, "Literal '{}' has no digits", str)
+            }
         }
     }
 }
 
 /// A Code Generation Error
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct CodegenError {
     /// The kind of code generation error
     pub kind: CodegenErrorKind,
@@ -117,17 +262,19 @@ pub struct CodegenError {
     pub span: AstSpan,
     /// An Optional Token Kind
     pub token: Option<TokenKind>,
+    /// An Optional hint on how to fix the error
+    pub help: Option<String>,
 }
 
 impl CodegenError {
     /// Public associated function to instatiate a new CodegenError.
     pub fn new(kind: CodegenErrorKind, spans: AstSpan, token: Option<TokenKind>) -> Self {
-        Self { kind, span: spans, token }
+        Self { kind, span: spans, token, help: None }
     }
 }
 
 /// The Code Generation Error Kind
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub enum CodegenErrorKind {
     /// Storage Pointers Not Derived
     StoragePointersNotDerived,
@@ -151,6 +298,173 @@ pub enum CodegenErrorKind {
     InvalidMacroInvocation(String),
     /// Conversion Error for usize
     UsizeConversion(String),
+    /// The codehash of a macro or table doesn't match the asserted hash
+    CodehashMismatch(String),
+    /// A codehash assertion targets a table without code
+    InvalidCodehashTarget(String),
+    /// A data contract stores a table without code
+    InvalidDataTable(String),
+    /// A library symbol referenced by a consumer doesn't match its pinned manifest
+    ManifestMismatch(String),
+    /// A macro invoked in its own expansion, with the invocation chain
+    RecursiveMacroInvocation(String),
+    /// Macro invocations nested deeper than the maximum expansion depth
+    MaxExpansionDepthExceeded(usize),
+    /// The slot derived for a free storage pointer is also the literal slot of another constant
+    StorageSlotCollision(String),
+    /// An immutable is set by the runtime code, rather than by the constructor
+    ImmutableSetInRuntime(String),
+    /// A compile-time assertion whose expression evaluates to zero
+    AssertionFailed(String),
+    /// A constant defined with a well-known name, but not its well-known value
+    WellKnownConstantMismatch(String),
+    /// An expression of label offsets whose value doesn't fit in two bytes
+    OffsetOutOfRange(String),
+    /// A macro invoked with another number of arguments than it declares parameters
+    MacroArgCountMismatch(String),
+    /// A macro argument that is neither a literal, a constant, a label nor an opcode
+    InvalidMacroArgument(String),
+    /// An arg call to a parameter the invoked macro doesn't declare
+    UndeclaredArgCall(String),
+    /// A builtin names a function the contract doesn't define
+    MissingFunctionDefinition(String),
+    /// A function output encoded from the stack that isn't a value type
+    UnencodableOutput(String),
+    /// A builtin names an event the contract doesn't define
+    MissingEventDefinition(String),
+    /// An event emitted from the stack with a parameter that isn't a value type, or more than
+    /// three indexed parameters
+    UnemittableEvent(String),
+    /// A codegen plugin failed to transform the contract or its bytecode, with the plugin name
+    PluginFailed(String),
+    /// A custom builtin invoked with arguments it doesn't accept, or left unlowered
+    InvalidBuiltinArgument(String),
+}
+
+impl CodegenErrorKind {
+    /// The stable error code of the codegen error kind
+    pub fn code(&self) -> &'static str {
+        match self {
+            CodegenErrorKind::StoragePointersNotDerived => "H0301",
+            CodegenErrorKind::InvalidMacroStatement => "H0302",
+            CodegenErrorKind::MissingMacroDefinition(_) => "H0303",
+            CodegenErrorKind::MissingConstantDefinition(_) => "H0304",
+            CodegenErrorKind::AbiGenerationFailure => "H0305",
+            CodegenErrorKind::UnmatchedJumpLabel => "H0306",
+            CodegenErrorKind::IOError(_) => "H0307",
+            CodegenErrorKind::UnkownArgcallType => "H0308",
+            CodegenErrorKind::MissingMacroInvocation(_) => "H0309",
+            CodegenErrorKind::InvalidMacroInvocation(_) => "H0310",
+            CodegenErrorKind::UsizeConversion(_) => "H0311",
+            CodegenErrorKind::CodehashMismatch(_) => "H0312",
+            CodegenErrorKind::InvalidCodehashTarget(_) => "H0313",
+            CodegenErrorKind::InvalidDataTable(_) => "H0314",
+            CodegenErrorKind::ManifestMismatch(_) => "H0315",
+            CodegenErrorKind::RecursiveMacroInvocation(_) => "H0316",
+            CodegenErrorKind::MaxExpansionDepthExceeded(_) => "H0317",
+            CodegenErrorKind::StorageSlotCollision(_) => "H0318",
+            CodegenErrorKind::ImmutableSetInRuntime(_) => "H0319",
+            CodegenErrorKind::AssertionFailed(_) => "H0320",
+            CodegenErrorKind::WellKnownConstantMismatch(_) => "H0321",
+            CodegenErrorKind::OffsetOutOfRange(_) => "H0322",
+            CodegenErrorKind::MacroArgCountMismatch(..) => "H0323",
+            CodegenErrorKind::InvalidMacroArgument(..) => "H0324",
+            CodegenErrorKind::UndeclaredArgCall(..) => "H0325",
+            CodegenErrorKind::MissingFunctionDefinition(_) => "H0326",
+            CodegenErrorKind::UnencodableOutput(_) => "H0327",
+            CodegenErrorKind::MissingEventDefinition(_) => "H0328",
+            CodegenErrorKind::UnemittableEvent(_) => "H0329",
+            CodegenErrorKind::PluginFailed(_) => "H0330",
+            CodegenErrorKind::InvalidBuiltinArgument(_) => "H0331",
+        }
+    }
+
+    /// A message describing the codegen error kind, without any source location
+    pub fn message(&self) -> String {
+        match self {
+            CodegenErrorKind::StoragePointersNotDerived => {
+                "Storage Pointers Not Derived".to_string()
+            }
+            CodegenErrorKind::InvalidMacroStatement => "Invalid Macro Statement".to_string(),
+            CodegenErrorKind::MissingMacroDefinition(md) => {
+                format!("Missing Macro Definition For \"{}\"", md)
+            }
+            CodegenErrorKind::MissingConstantDefinition(cd) => {
+                format!("Missing Constant Definition For \"{}\"", cd)
+            }
+            CodegenErrorKind::AbiGenerationFailure => "ABI Generation Failed".to_string(),
+            CodegenErrorKind::UnmatchedJumpLabel => "Unmatched Jump Label".to_string(),
+            CodegenErrorKind::IOError(ioe) => format!("IO Error: {}", ioe),
+            CodegenErrorKind::UnkownArgcallType => "Unknown Arg Call Type".to_string(),
+            CodegenErrorKind::MissingMacroInvocation(mmi) => {
+                format!("Missing Macro Invocation: \"{}\"", mmi)
+            }
+            CodegenErrorKind::InvalidMacroInvocation(mmi) => {
+                format!("Missing Macro Definition For Invocation: \"{}\"", mmi)
+            }
+            CodegenErrorKind::UsizeConversion(input) => {
+                format!("Usize Conversion Failed For \"{}\"", input)
+            }
+            CodegenErrorKind::CodehashMismatch(name) => {
+                format!("Codehash Mismatch For \"{}\"", name)
+            }
+            CodegenErrorKind::InvalidCodehashTarget(name) => {
+                format!("Cannot Assert The Codehash Of Jump Table \"{}\"", name)
+            }
+            CodegenErrorKind::InvalidDataTable(name) => {
+                format!("Cannot Store Table \"{}\" As A Data Contract", name)
+            }
+            CodegenErrorKind::ManifestMismatch(name) => {
+                format!("Library Symbol \"{}\" Does Not Match Its Manifest", name)
+            }
+            CodegenErrorKind::RecursiveMacroInvocation(chain) => {
+                format!("Recursive Macro Invocation: {}", chain)
+            }
+            CodegenErrorKind::MaxExpansionDepthExceeded(depth) => {
+                format!("Macro Expansion Exceeds The Maximum Depth Of {}", depth)
+            }
+            CodegenErrorKind::StorageSlotCollision(name) => {
+                format!("Storage Slot Of \"{}\" Collides With A Literal Slot", name)
+            }
+            CodegenErrorKind::ImmutableSetInRuntime(name) => {
+                format!("Immutable \"{}\" Set In The Runtime Code", name)
+            }
+            CodegenErrorKind::AssertionFailed(expression) => {
+                format!("Assertion Failed: \"{}\"", expression)
+            }
+            CodegenErrorKind::WellKnownConstantMismatch(name) => {
+                format!("Constant \"{}\" Does Not Have Its Well-Known Value", name)
+            }
+            CodegenErrorKind::OffsetOutOfRange(expression) => {
+                format!("Offset Expression Out Of Range: \"{}\"", expression)
+            }
+            CodegenErrorKind::MacroArgCountMismatch(name) => {
+                format!("Macro \"{}\" Invoked With Wrong Number Of Arguments", name)
+            }
+            CodegenErrorKind::InvalidMacroArgument(argument) => {
+                format!("Invalid Macro Argument \"{}\"", argument)
+            }
+            CodegenErrorKind::UndeclaredArgCall(arg_call) => {
+                format!("Arg Call To Undeclared Parameter \"{}\"", arg_call)
+            }
+            CodegenErrorKind::MissingFunctionDefinition(name) => {
+                format!("Missing Function Definition \"{}\"", name)
+            }
+            CodegenErrorKind::UnencodableOutput(kind) => {
+                format!("Output Of Type \"{}\" Can't Be Encoded From The Stack", kind)
+            }
+            CodegenErrorKind::MissingEventDefinition(name) => {
+                format!("Missing Event Definition \"{}\"", name)
+            }
+            CodegenErrorKind::UnemittableEvent(name) => {
+                format!("Event \"{}\" Can't Be Emitted From The Stack", name)
+            }
+            CodegenErrorKind::PluginFailed(name) => format!("Codegen Plugin \"{}\" Failed", name),
+            CodegenErrorKind::InvalidBuiltinArgument(name) => {
+                format!("Invalid Arguments To Builtin \"{}\"", name)
+            }
+        }
+    }
 }
 
 impl Spanned for CodegenError {
@@ -185,15 +499,296 @@ impl<W: Write> Report<W> for CodegenError {
             CodegenErrorKind::UsizeConversion(input) => {
                 write!(f.out, "Usize Conversion Failed for \"{}\"", input)
             }
+            CodegenErrorKind::CodehashMismatch(name) => {
+                write!(f.out, "Codehash mismatch for \"{}\"!", name)
+            }
+            CodegenErrorKind::InvalidCodehashTarget(name) => {
+                write!(f.out, "Cannot assert the codehash of jump table \"{}\"!", name)
+            }
+            CodegenErrorKind::InvalidDataTable(name) => {
+                write!(f.out, "Cannot store table \"{}\" as a data contract!", name)
+            }
+            CodegenErrorKind::ManifestMismatch(name) => {
+                write!(f.out, "Library symbol \"{}\" does not match its manifest!", name)
+            }
+            CodegenErrorKind::RecursiveMacroInvocation(chain) => {
+                write!(f.out, "Recursive macro invocation: {}!", chain)
+            }
+            CodegenErrorKind::MaxExpansionDepthExceeded(depth) => {
+                write!(f.out, "Macro expansion exceeds the maximum depth of {}!", depth)
+            }
+            CodegenErrorKind::StorageSlotCollision(name) => {
+                write!(f.out, "Storage slot of \"{}\" collides with a literal slot!", name)
+            }
+            CodegenErrorKind::ImmutableSetInRuntime(name) => {
+                write!(f.out, "Immutable \"{}\" set in the runtime code!", name)
+            }
+            CodegenErrorKind::AssertionFailed(expression) => {
+                write!(f.out, "Assertion failed: \"{}\"!", expression)
+            }
+            CodegenErrorKind::WellKnownConstantMismatch(name) => {
+                write!(f.out, "Constant \"{}\" does not have its well-known value!", name)
+            }
+            CodegenErrorKind::OffsetOutOfRange(expression) => {
+                write!(f.out, "Offset expression \"{}\" is out of range!", expression)
+            }
+            CodegenErrorKind::MacroArgCountMismatch(name) => {
+                write!(f.out, "Macro \"{}\" invoked with the wrong number of arguments!", name)
+            }
+            CodegenErrorKind::InvalidMacroArgument(argument) => {
+                write!(f.out, "Invalid macro argument \"{}\"!", argument)
+            }
+            CodegenErrorKind::UndeclaredArgCall(arg_call) => {
+                write!(f.out, "Arg call to undeclared parameter \"{}\"!", arg_call)
+            }
+            CodegenErrorKind::MissingFunctionDefinition(name) => {
+                write!(f.out, "Missing function definition \"{}\"!", name)
+            }
+            CodegenErrorKind::UnencodableOutput(kind) => {
+                write!(f.out, "Output of type \"{}\" can't be encoded from the stack!", kind)
+            }
+            CodegenErrorKind::MissingEventDefinition(name) => {
+                write!(f.out, "Missing event definition \"{}\"!", name)
+            }
+            CodegenErrorKind::UnemittableEvent(name) => {
+                write!(f.out, "Event \"{}\" can't be emitted from the stack!", name)
+            }
+            CodegenErrorKind::PluginFailed(name) => {
+                write!(f.out, "Codegen plugin \"{}\" failed!", name)
+            }
+            CodegenErrorKind::InvalidBuiltinArgument(name) => {
+                write!(f.out, "Invalid arguments to builtin \"{}\"!", name)
+            }
+        }
+    }
+}
+
+/// A Compiler Warning
+///
+/// Warnings are advisory, and only fail compilation if their lint is denied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompilerWarning {
+    /// The kind of warning
+    pub kind: WarningKind,
+    /// The span where the warning occurred
+    pub span: AstSpan,
+    /// An Optional hint on how to fix the warning
+    pub help: Option<String>,
+    /// An Optional source edit fixing the warning, applied by `huffc lint --fix`
+    pub fix: Option<Fix>,
+}
+
+impl CompilerWarning {
+    /// Public associated function to instatiate a new CompilerWarning.
+    pub fn new(kind: WarningKind, span: AstSpan) -> Self {
+        Self { kind, span, help: None, fix: None }
+    }
+}
+
+/// A source edit fixing a warning, safe to apply without changing the contract's behavior
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    /// The span of the source to replace, relative to its file
+    pub span: Span,
+    /// The source replacing the span
+    pub replacement: String,
+}
+
+/// A Type of Compiler Warning
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WarningKind {
+    /// An opcode mnemonic that isn't lowercase, with its lowercase form
+    NonLowercaseOpcode(String, String),
+    /// A macro parameter that is never referenced: (macro, parameter)
+    UnusedMacroParameter(String, String),
+    /// An arg call to a parameter the macro doesn't declare: (macro, arg call)
+    UndeclaredArgCall(String, String),
+    /// A macro that is never invoked or referenced
+    UnusedMacro(String),
+    /// A constant that is never referenced
+    UnusedConstant(String),
+    /// A label defined more than once in a macro: (macro, label)
+    ShadowedLabel(String, String),
+    /// Runtime bytecode larger than the EIP-170 limit of 24576 bytes
+    OversizedContract(usize),
+    /// Statements following a terminating opcode, that can never run: (macro, opcode)
+    UnreachableCode(String, String),
+    /// A jump to a label no macro defines: (macro, label)
+    MissingJumpTarget(String, String),
+    /// A function selector pushed as a literal instead of a named constant: (macro, selector)
+    MagicSelector(String, String),
+    /// A DUP or SWAP reaching deeper than the 16 accessible stack items: (macro, instruction)
+    StackTooDeep(String, String),
+    /// An instruction taking more stack items than are available: (macro, instruction, taken,
+    /// available)
+    StackUnderflow(String, String, usize, usize),
+    /// A macro ending with a stack height its declaration doesn't produce: (macro, takes,
+    /// returns, height)
+    StackMismatch(String, usize, usize, usize),
+    /// An opcode alias that is never used
+    UnusedAlias(String),
+    /// Statements spelling out an opcode alias instead of using it: (macro, alias)
+    InlinedAlias(String, String),
+    /// A table that is never referenced by a reached macro
+    UnusedTable(String),
+    /// A label that is never jumped to or passed to a macro: (macro, label)
+    UnusedLabel(String, String),
+    /// A function whose selector is never pushed by a reached macro
+    UnusedFunction(String),
+}
+
+impl WarningKind {
+    /// All lint names, in warning kind order
+    pub const LINTS: [&'static str; 18] = [
+        "opcode-case",
+        "unused-macro-parameter",
+        "undeclared-arg-call",
+        "unused-macro",
+        "unused-constant",
+        "shadowed-label",
+        "oversized-contract",
+        "unreachable-code",
+        "missing-jump-target",
+        "magic-selector",
+        "stack-too-deep",
+        "stack-underflow",
+        "stack-mismatch",
+        "unused-alias",
+        "inlined-alias",
+        "unused-table",
+        "unused-label",
+        "unused-function",
+    ];
+
+    /// The lint name of the warning kind, used to allow, warn or deny it
+    pub fn lint(&self) -> &'static str {
+        match self {
+            WarningKind::NonLowercaseOpcode(..) => WarningKind::LINTS[0],
+            WarningKind::UnusedMacroParameter(..) => WarningKind::LINTS[1],
+            WarningKind::UndeclaredArgCall(..) => WarningKind::LINTS[2],
+            WarningKind::UnusedMacro(_) => WarningKind::LINTS[3],
+            WarningKind::UnusedConstant(_) => WarningKind::LINTS[4],
+            WarningKind::ShadowedLabel(..) => WarningKind::LINTS[5],
+            WarningKind::OversizedContract(_) => WarningKind::LINTS[6],
+            WarningKind::UnreachableCode(..) => WarningKind::LINTS[7],
+            WarningKind::MissingJumpTarget(..) => WarningKind::LINTS[8],
+            WarningKind::MagicSelector(..) => WarningKind::LINTS[9],
+            WarningKind::StackTooDeep(..) => WarningKind::LINTS[10],
+            WarningKind::StackUnderflow(..) => WarningKind::LINTS[11],
+            WarningKind::StackMismatch(..) => WarningKind::LINTS[12],
+            WarningKind::UnusedAlias(_) => WarningKind::LINTS[13],
+            WarningKind::InlinedAlias(..) => WarningKind::LINTS[14],
+            WarningKind::UnusedTable(_) => WarningKind::LINTS[15],
+            WarningKind::UnusedLabel(..) => WarningKind::LINTS[16],
+            WarningKind::UnusedFunction(_) => WarningKind::LINTS[17],
+        }
+    }
+
+    /// The stable warning code of the warning kind
+    pub fn code(&self) -> &'static str {
+        match self {
+            WarningKind::NonLowercaseOpcode(..) => "H0501",
+            WarningKind::UnusedMacroParameter(..) => "H0502",
+            WarningKind::UndeclaredArgCall(..) => "H0503",
+            WarningKind::UnusedMacro(_) => "H0504",
+            WarningKind::UnusedConstant(_) => "H0505",
+            WarningKind::ShadowedLabel(..) => "H0506",
+            WarningKind::OversizedContract(_) => "H0507",
+            WarningKind::UnreachableCode(..) => "H0508",
+            WarningKind::MissingJumpTarget(..) => "H0509",
+            WarningKind::MagicSelector(..) => "H0510",
+            WarningKind::StackTooDeep(..) => "H0511",
+            WarningKind::StackUnderflow(..) => "H0512",
+            WarningKind::StackMismatch(..) => "H0513",
+            WarningKind::UnusedAlias(_) => "H0514",
+            WarningKind::InlinedAlias(..) => "H0515",
+            WarningKind::UnusedTable(_) => "H0516",
+            WarningKind::UnusedLabel(..) => "H0517",
+            WarningKind::UnusedFunction(_) => "H0518",
+        }
+    }
+
+    /// A message describing the warning kind, without any source location
+    pub fn message(&self) -> String {
+        match self {
+            WarningKind::NonLowercaseOpcode(op, lower) => {
+                format!("Opcode \"{}\" should be written in lowercase: \"{}\"", op, lower)
+            }
+            WarningKind::UnusedMacroParameter(m, param) => {
+                format!("Parameter \"{}\" of macro \"{}\" is never referenced", param, m)
+            }
+            WarningKind::UndeclaredArgCall(m, arg) => {
+                format!(
+                    "Arg call \"<{}>\" references a parameter not declared by macro \"{}\"",
+                    arg, m
+                )
+            }
+            WarningKind::UnusedMacro(m) => format!("Macro \"{}\" is never used", m),
+            WarningKind::UnusedConstant(c) => format!("Constant \"{}\" is never used", c),
+            WarningKind::ShadowedLabel(m, label) => {
+                format!("Label \"{}\" is defined more than once in macro \"{}\"", label, m)
+            }
+            WarningKind::OversizedContract(size) => format!(
+                "Contract runtime bytecode is {} bytes, exceeding the 24576 byte limit (EIP-170)",
+                size
+            ),
+            WarningKind::UnreachableCode(m, op) => {
+                format!("Code following \"{}\" in macro \"{}\" is unreachable", op, m)
+            }
+            WarningKind::MissingJumpTarget(m, label) => {
+                format!("Label \"{}\" jumped to in macro \"{}\" is never defined", label, m)
+            }
+            WarningKind::MagicSelector(m, selector) => {
+                format!("Selector \"{}\" in macro \"{}\" should be a named constant", selector, m)
+            }
+            WarningKind::StackTooDeep(m, op) => format!(
+                "\"{}\" in macro \"{}\" reaches deeper than the 16 stack items DUP and SWAP can access",
+                op, m
+            ),
+            WarningKind::StackUnderflow(m, instruction, taken, available) => format!(
+                "\"{}\" in macro \"{}\" takes {} stack item(s), but only {} are available",
+                instruction, m, taken, available
+            ),
+            WarningKind::StackMismatch(m, takes, returns, height) => format!(
+                "Macro \"{}\" ends with {} stack item(s), but declares takes({}) returns({})",
+                m, height, takes, returns
+            ),
+            WarningKind::UnusedAlias(a) => format!("Alias \"{}\" is never used", a),
+            WarningKind::InlinedAlias(m, a) => {
+                format!("Macro \"{}\" spells out alias \"{}\" instead of using it", m, a)
+            }
+            WarningKind::UnusedTable(t) => format!("Table \"{}\" is never used", t),
+            WarningKind::UnusedLabel(m, label) => {
+                format!("Label \"{}\" in macro \"{}\" is never jumped to", label, m)
+            }
+            WarningKind::UnusedFunction(f) => {
+                format!("Function \"{}\" is never used, no macro pushes its selector", f)
+            }
+        }
+    }
+}
+
+impl fmt::Display for CompilerWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "\nWarning: {} [{}]\n{}\n",
+            self.kind.message(),
+            self.kind.lint(),
+            self.span.error()
+        )?;
+        match &self.help {
+            Some(help) => writeln!(f, "help: {}", help),
+            None => Ok(()),
         }
     }
 }
 
 /// CompilerError
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum CompilerError<'a> {
+pub enum CompilerError {
     /// Failed to Lex Source
-    LexicalError(LexicalError<'a>),
+    LexicalError(LexicalError),
     /// File unpacking error
     FileUnpackError(UnpackError),
     /// Parsing Error
@@ -203,13 +798,39 @@ pub enum CompilerError<'a> {
     /// Bytecode Generation Error
     CodegenError(CodegenError),
     /// Multiple Failed Compiles
-    FailedCompiles(Vec<CompilerError<'a>>),
+    FailedCompiles(Vec<CompilerError>),
+    /// Warnings whose lint is denied
+    DeniedWarnings(Vec<CompilerWarning>),
+}
+
+impl CompilerError {
+    /// The stable error code of the compiler error
+    ///
+    /// Returns `None` for [FailedCompiles](CompilerError::FailedCompiles) and
+    /// [DeniedWarnings](CompilerError::DeniedWarnings), which aggregate multiple errors or
+    /// warnings with their own codes.
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            CompilerError::LexicalError(le) => Some(le.kind.code()),
+            CompilerError::FileUnpackError(ue) => Some(match ue {
+                UnpackError::UnsupportedExtension(_) => "H0401",
+                UnpackError::InvalidDirectory(_) => "H0402",
+                UnpackError::MissingFile(_) => "H0403",
+                UnpackError::CircularInclude(_) => "H0405",
+                UnpackError::UnflattenableInclude(_) => "H0406",
+            }),
+            CompilerError::ParserError(pe) => Some(pe.kind.code()),
+            CompilerError::PathBufRead(_) => Some("H0404"),
+            CompilerError::CodegenError(ce) => Some(ce.kind.code()),
+            CompilerError::FailedCompiles(_) | CompilerError::DeniedWarnings(_) => None,
+        }
+    }
 }
 
-impl<'a> fmt::Display for CompilerError<'a> {
+impl fmt::Display for CompilerError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            CompilerError::LexicalError(le) => match le.kind {
+            CompilerError::LexicalError(le) => match &le.kind {
                 LexicalErrorKind::UnexpectedEof => {
                     write!(
                         f,
@@ -245,6 +866,24 @@ impl<'a> fmt::Display for CompilerError<'a> {
                         le.span.source_seg()
                     )
                 }
+                LexicalErrorKind::LiteralTooLong(l) => {
+                    write!(
+                        f,
+                        "\nError: Literal Exceeds 32 Bytes: \"{}\" {}{}\n",
+                        l,
+                        le.span.identifier(),
+                        le.span.source_seg()
+                    )
+                }
+                LexicalErrorKind::EmptyLiteral(l) => {
+                    write!(
+                        f,
+                        "\nError: Literal Has No Digits: \"{}\" {}{}\n",
+                        l,
+                        le.span.identifier(),
+                        le.span.source_seg()
+                    )
+                }
             },
             CompilerError::FileUnpackError(ue) => match ue {
                 UnpackError::InvalidDirectory(id) => {
@@ -261,6 +900,12 @@ impl<'a> fmt::Display for CompilerError<'a> {
                 UnpackError::MissingFile(file) => {
                     write!(f, "\nError: File Not Found \"{}\"\n", file)
                 }
+                UnpackError::CircularInclude(cycle) => {
+                    write!(f, "\nError: Circular Include {}\n", cycle.join(" \u{2192} "))
+                }
+                UnpackError::UnflattenableInclude(file) => {
+                    write!(f, "\nError: Unflattenable Include In \"{}\"\n", file)
+                }
             },
             CompilerError::ParserError(pe) => match &pe.kind {
                 ParserErrorKind::SyntaxError(se) => {
@@ -352,70 +997,164 @@ impl<'a> fmt::Display for CompilerError<'a> {
                 ParserErrorKind::InvalidImportPath(ip) => {
                     write!(f, "\nError: Invalid Import Path: \"{}\" \n{}\n", ip, pe.spans.error())
                 }
-            },
-            CompilerError::PathBufRead(os_str) => {
-                write!(
-                    f,
-                    "\nError: Invalid Import Path: \"{}\"",
-                    os_str.as_os_str().to_str().unwrap_or("<unknown import>")
-                )
-            }
-            CompilerError::CodegenError(ce) => match &ce.kind {
-                CodegenErrorKind::StoragePointersNotDerived => {
-                    write!(f, "\nError: Storage Pointers Not Derived\n{}\n", ce.span.error())
-                }
-                CodegenErrorKind::InvalidMacroStatement => {
-                    write!(f, "\nError: Invalid Macro Statement\n{}\n", ce.span.error())
+                ParserErrorKind::InvalidTableFile(tf) => {
+                    write!(f, "\nError: Invalid Table File: \"{}\" \n{}\n", tf, pe.spans.error())
                 }
-                CodegenErrorKind::MissingMacroDefinition(md) => {
+                ParserErrorKind::InvalidArtifactImport(ai) => {
                     write!(
                         f,
-                        "\nError: Missing Macro Definition For \"{}\"\n{}",
-                        md,
-                        ce.span.file()
+                        "\nError: Invalid Artifact Import: \"{}\" \n{}\n",
+                        ai,
+                        pe.spans.error()
                     )
                 }
-                CodegenErrorKind::InvalidMacroInvocation(mmi) => {
+                ParserErrorKind::InvalidTokenInAliasBody(tab) => {
                     write!(
                         f,
-                        "\nError: Missing Macro Definition For Invocation: \"{}\"\n{}\n",
-                        mmi,
-                        ce.span.error()
+                        "\nError: Invalid Token In Alias Body: \"{}\" \n{}\n",
+                        tab,
+                        pe.spans.error()
                     )
                 }
-                CodegenErrorKind::MissingConstantDefinition(_) => {
-                    write!(f, "\nError: Missing Constant Definition\n{}\n", ce.span.error())
-                }
-                CodegenErrorKind::AbiGenerationFailure => {
-                    write!(f, "\nError: ABI Generation Failed\n{}\n", ce.span.error())
+                ParserErrorKind::InvalidRepeatCount(rc) => {
+                    write!(f, "\nError: Invalid Repeat Count: \"{}\" \n{}\n", rc, pe.spans.error())
                 }
-                CodegenErrorKind::IOError(ioe) => {
-                    write!(f, "\nError: IO Error: {}\n{}", ioe, ce.span.file())
-                }
-                CodegenErrorKind::UnkownArgcallType => {
-                    write!(f, "\nError: Unknown Arg Call Type\n{}\n", ce.span.error())
+                ParserErrorKind::UnknownConstantOverride(uco) => {
+                    write!(
+                        f,
+                        "\nError: Unknown Constant Override: \"{}\" \n{}\n",
+                        uco,
+                        pe.spans.error()
+                    )
                 }
-                CodegenErrorKind::MissingMacroInvocation(mmi) => {
+                ParserErrorKind::InvalidVerbatimBytes(ivb) => {
                     write!(
                         f,
-                        "\nError: Missing Macro Invocation: \"{}\"\n{}\n",
-                        mmi,
-                        ce.span.error()
+                        "\nError: Invalid Verbatim Bytes: \"{}\" \n{}\n",
+                        ivb,
+                        pe.spans.error()
                     )
                 }
-                CodegenErrorKind::UnmatchedJumpLabel => {
-                    write!(f, "\nError: Unmatched Jump Label\n{}\n", ce.span.error())
+                ParserErrorKind::MissingParameterDefault(mpd) => {
+                    write!(
+                        f,
+                        "\nError: Missing Parameter Default: \"{}\" \n{}\n",
+                        mpd,
+                        pe.spans.error()
+                    )
                 }
-                CodegenErrorKind::UsizeConversion(_) => {
-                    write!(f, "\nError: Usize Conversion\n{}\n", ce.span.error())
+                ParserErrorKind::PrivateDefinition(pd) => {
+                    write!(
+                        f,
+                        "\nError: Private Definition: \"{}\" \n{}\n",
+                        pd,
+                        pe.spans.error()
+                    )
                 }
             },
+            CompilerError::PathBufRead(os_str) => {
+                write!(
+                    f,
+                    "\nError: Invalid Import Path: \"{}\"",
+                    os_str.as_os_str().to_str().unwrap_or("<unknown import>")
+                )
+            }
+            CompilerError::CodegenError(ce) => {
+                match &ce.kind {
+                    CodegenErrorKind::StoragePointersNotDerived => {
+                        write!(f, "\nError: Storage Pointers Not Derived\n{}\n", ce.span.error())
+                    }
+                    CodegenErrorKind::InvalidMacroStatement => {
+                        write!(f, "\nError: Invalid Macro Statement\n{}\n", ce.span.error())
+                    }
+                    CodegenErrorKind::MissingMacroDefinition(md) => {
+                        write!(
+                            f,
+                            "\nError: Missing Macro Definition For \"{}\"\n{}",
+                            md,
+                            ce.span.file()
+                        )
+                    }
+                    CodegenErrorKind::InvalidMacroInvocation(mmi) => {
+                        write!(
+                            f,
+                            "\nError: Missing Macro Definition For Invocation: \"{}\"\n{}\n",
+                            mmi,
+                            ce.span.error()
+                        )
+                    }
+                    CodegenErrorKind::MissingConstantDefinition(_) => {
+                        write!(f, "\nError: Missing Constant Definition\n{}\n", ce.span.error())
+                    }
+                    CodegenErrorKind::AbiGenerationFailure => {
+                        write!(f, "\nError: ABI Generation Failed\n{}\n", ce.span.error())
+                    }
+                    CodegenErrorKind::IOError(ioe) => {
+                        write!(f, "\nError: IO Error: {}\n{}", ioe, ce.span.file())
+                    }
+                    CodegenErrorKind::UnkownArgcallType => {
+                        write!(f, "\nError: Unknown Arg Call Type\n{}\n", ce.span.error())
+                    }
+                    CodegenErrorKind::MissingMacroInvocation(mmi) => {
+                        write!(
+                            f,
+                            "\nError: Missing Macro Invocation: \"{}\"\n{}\n",
+                            mmi,
+                            ce.span.error()
+                        )
+                    }
+                    CodegenErrorKind::UnmatchedJumpLabel => {
+                        write!(f, "\nError: Unmatched Jump Label\n{}\n", ce.span.error())
+                    }
+                    CodegenErrorKind::UsizeConversion(_) => {
+                        write!(f, "\nError: Usize Conversion\n{}\n", ce.span.error())
+                    }
+                    CodegenErrorKind::CodehashMismatch(_) |
+                    CodegenErrorKind::InvalidCodehashTarget(_) |
+                    CodegenErrorKind::InvalidDataTable(_) |
+                    CodegenErrorKind::ManifestMismatch(_) |
+                    CodegenErrorKind::RecursiveMacroInvocation(_) |
+                    CodegenErrorKind::MaxExpansionDepthExceeded(_) |
+                    CodegenErrorKind::StorageSlotCollision(_) |
+                    CodegenErrorKind::ImmutableSetInRuntime(_) |
+                    CodegenErrorKind::AssertionFailed(_) |
+                    CodegenErrorKind::WellKnownConstantMismatch(_) |
+                    CodegenErrorKind::OffsetOutOfRange(_) |
+                    CodegenErrorKind::MacroArgCountMismatch(..) |
+                    CodegenErrorKind::InvalidMacroArgument(..) |
+                    CodegenErrorKind::UndeclaredArgCall(..) |
+                    CodegenErrorKind::MissingFunctionDefinition(_) |
+                    CodegenErrorKind::UnencodableOutput(_) |
+                    CodegenErrorKind::MissingEventDefinition(_) |
+                    CodegenErrorKind::UnemittableEvent(_) |
+                    CodegenErrorKind::PluginFailed(_) |
+                    CodegenErrorKind::InvalidBuiltinArgument(_) => {
+                        write!(f, "\nError: {}\n{}\n", ce.kind.message(), ce.span.error())
+                    }
+                }?;
+                match &ce.help {
+                    Some(help) => writeln!(f, "help: {}", help),
+                    None => Ok(()),
+                }
+            }
             CompilerError::FailedCompiles(v) => {
                 v.iter().for_each(|ce| {
                     let _ = write!(f, "{}", ce);
                 });
                 Ok(())
             }
+            CompilerError::DeniedWarnings(v) => {
+                v.iter().for_each(|w| {
+                    let _ = write!(
+                        f,
+                        "\nError: {} [{}] (denied)\n{}\n",
+                        w.kind.message(),
+                        w.kind.lint(),
+                        w.span.error()
+                    );
+                });
+                Ok(())
+            }
         }
     }
 }
@@ -1,4 +1,5 @@
 use phf::phf_map;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use strum_macros::EnumString;
 
@@ -6,7 +7,7 @@ use strum_macros::EnumString;
 /// They are arranged in a particular order such that all the opcodes that have common
 /// prefixes are ordered by decreasing length to avoid mismatch when lexing.
 /// Example : [origin, or] or [push32, ..., push3]
-pub const OPCODES: [&str; 142] = [
+pub const OPCODES: [&str; 144] = [
     "lt",
     "gt",
     "slt",
@@ -42,6 +43,8 @@ pub const OPCODES: [&str; 142] = [
     "mstore",
     "sload",
     "sstore",
+    "tload",
+    "tstore",
     "jumpdest",
     "jumpi",
     "jump",
@@ -188,6 +191,8 @@ pub static OPCODES_MAP: phf::Map<&'static str, Opcode> = phf_map! {
     "mstore8" => Opcode::Mstore8,
     "sload" => Opcode::Sload,
     "sstore" => Opcode::Sstore,
+    "tload" => Opcode::Tload,
+    "tstore" => Opcode::Tstore,
     "jump" => Opcode::Jump,
     "jumpi" => Opcode::Jumpi,
     "pc" => Opcode::Pc,
@@ -299,7 +304,7 @@ pub static OPCODES_MAP: phf::Map<&'static str, Opcode> = phf_map! {
 
 /// EVM Opcodes
 /// References <https://evm.codes>
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumString)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumString)]
 pub enum Opcode {
     /// Halts execution.
     #[strum(serialize = "stop")]
@@ -418,6 +423,10 @@ pub enum Opcode {
     Sload,
     /// Store a word in Storage
     Sstore,
+    /// Load a word from Transient Storage
+    Tload,
+    /// Store a word in Transient Storage
+    Tstore,
     /// Alter the Program Counter
     Jump,
     /// Conditionally Alter the Program Counter
@@ -594,6 +603,179 @@ pub enum Opcode {
 }
 
 impl Opcode {
+    /// Finds the mnemonic of an opcode from its hex byte, eg. `jumpdest` for `5b`
+    pub fn mnemonic(byte: &str) -> Option<&'static str> {
+        OPCODES.iter().find(|o| OPCODES_MAP.get(o).is_some_and(|op| op.string() == byte)).copied()
+    }
+
+    /// The byte value of the opcode
+    pub fn byte(&self) -> u8 {
+        u8::from_str_radix(&self.string(), 16).unwrap_or_default()
+    }
+
+    /// The number of stack items the opcode takes, and the number it returns
+    pub fn stack_effect(&self) -> (usize, usize) {
+        let byte = self.byte() as usize;
+        match self {
+            // PUSHn, DUPn, SWAPn and LOGn
+            _ if (0x60..=0x7f).contains(&byte) => (0, 1),
+            _ if (0x80..=0x8f).contains(&byte) => (byte - 0x7f, byte - 0x7e),
+            _ if (0x90..=0x9f).contains(&byte) => (byte - 0x8e, byte - 0x8e),
+            _ if (0xa0..=0xa4).contains(&byte) => (byte - 0x9e, 0),
+            Opcode::Stop | Opcode::Jumpdest | Opcode::Invalid => (0, 0),
+            Opcode::Address |
+            Opcode::Origin |
+            Opcode::Caller |
+            Opcode::Callvalue |
+            Opcode::Calldatasize |
+            Opcode::Codesize |
+            Opcode::Gasprice |
+            Opcode::Returndatasize |
+            Opcode::Coinbase |
+            Opcode::Timestamp |
+            Opcode::Number |
+            Opcode::Difficulty |
+            Opcode::Gaslimit |
+            Opcode::Chainid |
+            Opcode::Selfbalance |
+            Opcode::Basefee |
+            Opcode::Pc |
+            Opcode::Msize |
+            Opcode::Gas => (0, 1),
+            Opcode::Iszero |
+            Opcode::Not |
+            Opcode::Balance |
+            Opcode::Calldataload |
+            Opcode::Extcodesize |
+            Opcode::Extcodehash |
+            Opcode::Blockhash |
+            Opcode::Mload |
+            Opcode::Sload |
+            Opcode::Tload => (1, 1),
+            Opcode::Pop | Opcode::Jump | Opcode::Selfdestruct => (1, 0),
+            Opcode::Mstore |
+            Opcode::Mstore8 |
+            Opcode::Sstore |
+            Opcode::Tstore |
+            Opcode::Jumpi |
+            Opcode::Return |
+            Opcode::Revert => (2, 0),
+            Opcode::Addmod | Opcode::Mulmod | Opcode::Create => (3, 1),
+            Opcode::Calldatacopy | Opcode::Codecopy | Opcode::Returndatacopy => (3, 0),
+            Opcode::Extcodecopy => (4, 0),
+            Opcode::Create2 => (4, 1),
+            Opcode::Delegatecall | Opcode::Staticcall => (6, 1),
+            Opcode::Call | Opcode::Callcode => (7, 1),
+            // Arithmetic, comparison, bitwise and hashing operations
+            _ => (2, 1),
+        }
+    }
+
+    /// The static gas cost of the opcode
+    ///
+    /// Opcodes with a [dynamic](Opcode::has_dynamic_gas) cost are charged more, depending on
+    /// memory expansion, account and storage access, or the size of their operands.
+    pub fn gas(&self) -> u64 {
+        let byte = self.byte();
+        match self {
+            _ if (0x60..=0x9f).contains(&byte) => 3,
+            _ if (0xa0..=0xa4).contains(&byte) => 375 * (byte - 0x9f) as u64,
+            Opcode::Stop | Opcode::Return | Opcode::Revert | Opcode::Invalid => 0,
+            Opcode::Jumpdest => 1,
+            Opcode::Address |
+            Opcode::Origin |
+            Opcode::Caller |
+            Opcode::Callvalue |
+            Opcode::Calldatasize |
+            Opcode::Codesize |
+            Opcode::Gasprice |
+            Opcode::Returndatasize |
+            Opcode::Coinbase |
+            Opcode::Timestamp |
+            Opcode::Number |
+            Opcode::Difficulty |
+            Opcode::Gaslimit |
+            Opcode::Chainid |
+            Opcode::Basefee |
+            Opcode::Pop |
+            Opcode::Pc |
+            Opcode::Msize |
+            Opcode::Gas => 2,
+            Opcode::Mul |
+            Opcode::Div |
+            Opcode::Sdiv |
+            Opcode::Mod |
+            Opcode::Smod |
+            Opcode::Signextend |
+            Opcode::Selfbalance => 5,
+            Opcode::Addmod | Opcode::Mulmod | Opcode::Jump => 8,
+            Opcode::Exp | Opcode::Jumpi => 10,
+            Opcode::Blockhash => 20,
+            Opcode::Sha3 => 30,
+            Opcode::Balance |
+            Opcode::Extcodesize |
+            Opcode::Extcodecopy |
+            Opcode::Extcodehash |
+            Opcode::Sload |
+            Opcode::Sstore |
+            Opcode::Tload |
+            Opcode::Tstore |
+            Opcode::Call |
+            Opcode::Callcode |
+            Opcode::Delegatecall |
+            Opcode::Staticcall => 100,
+            Opcode::Selfdestruct => 5000,
+            Opcode::Create | Opcode::Create2 => 32000,
+            // Addition, subtraction, comparison, bitwise and calldata operations
+            _ => 3,
+        }
+    }
+
+    /// Whether the opcode costs more gas than its static cost, depending on its operands or state
+    pub fn has_dynamic_gas(&self) -> bool {
+        let byte = self.byte();
+        (0xa0..=0xa4).contains(&byte) ||
+            matches!(
+                self,
+                Opcode::Exp |
+                    Opcode::Sha3 |
+                    Opcode::Balance |
+                    Opcode::Calldatacopy |
+                    Opcode::Codecopy |
+                    Opcode::Extcodesize |
+                    Opcode::Extcodecopy |
+                    Opcode::Returndatacopy |
+                    Opcode::Extcodehash |
+                    Opcode::Mload |
+                    Opcode::Mstore |
+                    Opcode::Mstore8 |
+                    Opcode::Sload |
+                    Opcode::Sstore |
+                    Opcode::Create |
+                    Opcode::Call |
+                    Opcode::Callcode |
+                    Opcode::Return |
+                    Opcode::Delegatecall |
+                    Opcode::Create2 |
+                    Opcode::Staticcall |
+                    Opcode::Revert |
+                    Opcode::Selfdestruct
+            )
+    }
+
+    /// Whether execution never continues to the next instruction, halting or jumping elsewhere
+    pub fn is_terminating(&self) -> bool {
+        matches!(
+            self,
+            Opcode::Stop |
+                Opcode::Return |
+                Opcode::Revert |
+                Opcode::Invalid |
+                Opcode::Selfdestruct |
+                Opcode::Jump
+        )
+    }
+
     /// Translates an Opcode into a string
     pub fn string(&self) -> String {
         let opcode_str = match self {
@@ -662,6 +844,8 @@ impl Opcode {
             Opcode::Msize => "59",
             Opcode::Gas => "5a",
             Opcode::Jumpdest => "5b",
+            Opcode::Tload => "5c",
+            Opcode::Tstore => "5d",
             Opcode::Push1 => "60",
             Opcode::Push2 => "61",
             Opcode::Push3 => "62",
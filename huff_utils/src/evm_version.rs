@@ -0,0 +1,59 @@
+use std::{fmt, str::FromStr};
+
+use crate::evm::Opcode;
+
+/// The EVM versions bytecode can be generated for, by hardfork
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EVMVersion {
+    /// The Merge
+    #[default]
+    Paris,
+    /// Introduces `PUSH0`
+    Shanghai,
+    /// Introduces transient storage, with `TLOAD` and `TSTORE`
+    Cancun,
+}
+
+impl EVMVersion {
+    /// Every EVM version, oldest first
+    pub const ALL: [EVMVersion; 3] = [EVMVersion::Paris, EVMVersion::Shanghai, EVMVersion::Cancun];
+
+    /// Whether transient storage is available
+    pub fn has_transient_storage(&self) -> bool {
+        *self >= EVMVersion::Cancun
+    }
+
+    /// The opcodes loading and storing a word in the storage generated code keeps its
+    /// per-transaction state in: transient storage if available, storage otherwise
+    pub fn transient_storage_opcodes(&self) -> (Opcode, Opcode) {
+        if self.has_transient_storage() {
+            (Opcode::Tload, Opcode::Tstore)
+        } else {
+            (Opcode::Sload, Opcode::Sstore)
+        }
+    }
+}
+
+impl fmt::Display for EVMVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EVMVersion::Paris => write!(f, "paris"),
+            EVMVersion::Shanghai => write!(f, "shanghai"),
+            EVMVersion::Cancun => write!(f, "cancun"),
+        }
+    }
+}
+
+impl FromStr for EVMVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EVMVersion::ALL.into_iter().find(|v| v.to_string() == s.to_lowercase()).ok_or_else(|| {
+            format!(
+                "Unknown EVM version \"{}\". Available versions: {}",
+                s,
+                EVMVersion::ALL.map(|v| v.to_string()).join(", ")
+            )
+        })
+    }
+}
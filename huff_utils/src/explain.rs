@@ -0,0 +1,1591 @@
+//! ## Explain
+//!
+//! Extended explanations of the stable compiler error and warning codes, printed by
+//! `huffc explain <CODE>`.
+
+/// An extended explanation of an error or warning code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Explanation {
+    /// The stable code, e.g. `H0306`
+    pub code: &'static str,
+    /// The name of the error or warning kind
+    pub name: &'static str,
+    /// The explanation, with examples
+    pub text: &'static str,
+}
+
+impl Explanation {
+    /// Looks up the explanation of a code, ignoring case
+    pub fn lookup(code: &str) -> Option<&'static Explanation> {
+        EXPLANATIONS.iter().find(|e| e.code.eq_ignore_ascii_case(code.trim()))
+    }
+}
+
+/// The explanations of every stable code, in code order
+pub const EXPLANATIONS: &[Explanation] = &[
+    Explanation {
+        code: "H0101",
+        name: "UnexpectedEof",
+        text: r#"The source file ended in the middle of a token.
+
+This usually means a string or a block comment was never closed.
+
+Erroneous code example:
+
+    #include "./utils/Ownable.huff
+
+Close the string or comment:
+
+    #include "./utils/Ownable.huff"
+"#,
+    },
+    Explanation {
+        code: "H0102",
+        name: "InvalidCharacter",
+        text: r#"The lexer found a character that cannot start any token.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x01 $ 0x02
+    }
+
+Remove the character, or put it in a comment:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x01 0x02 // $
+    }
+"#,
+    },
+    Explanation {
+        code: "H0103",
+        name: "InvalidArraySize",
+        text: r#"The size of an array type in a function or event signature is not a number.
+
+Erroneous code example:
+
+    #define function transfer(address[two]) nonpayable returns ()
+
+Use a number, or leave the brackets empty for a dynamic array:
+
+    #define function transfer(address[2]) nonpayable returns ()
+    #define function transferMany(address[]) nonpayable returns ()
+"#,
+    },
+    Explanation {
+        code: "H0104",
+        name: "InvalidPrimitiveType",
+        text: r#"The element type of an array in a function or event signature is not an EVM type.
+
+Erroneous code example:
+
+    #define function values(uint257[]) view returns ()
+
+Use a valid type such as `uint256`, `address`, `bool`, `bytes32` or `string`:
+
+    #define function values(uint256[]) view returns ()
+"#,
+    },
+    Explanation {
+        code: "H0105",
+        name: "LiteralTooLong",
+        text: r#"A hex literal has more than 64 digits, a binary literal more than 256, or a decimal literal
+exceeds 2^256 - 1, so it doesn't fit in the 32 bytes of a push.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __RIGHTPAD(0x000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20)
+    }
+
+Split the value into several pushes, or read longer data into a code table from a file:
+
+    #define table DATA = file("./data.bin")
+"#,
+    },
+    Explanation {
+        code: "H0106",
+        name: "EmptyLiteral",
+        text: r#"A binary literal has no digits after its `0b` prefix, or only underscores.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0b
+    }
+
+Write at least one binary digit, as in `0b0`.
+"#,
+    },
+    Explanation {
+        code: "H0201",
+        name: "SyntaxError",
+        text: r#"A general syntax error. The message describes what the parser expected.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x01 0x02 add
+    // missing closing brace
+
+Make sure every definition is complete:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x01 0x02 add
+    }
+"#,
+    },
+    Explanation {
+        code: "H0202",
+        name: "UnexpectedType",
+        text: r#"The parser expected a different token at this position.
+
+The error names the token that was expected.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns 0 {
+        0x00
+    }
+
+Here `returns` must be followed by an opening parenthesis:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x00
+    }
+"#,
+    },
+    Explanation {
+        code: "H0203",
+        name: "InvalidDefinition",
+        text: r#"A `#define` is not followed by a kind of definition the compiler knows.
+
+Valid definitions are `function`, `event`, `constant`, `macro`, `jumptable`,
+`jumptable__packed` and `table`.
+
+Erroneous code example:
+
+    #define variable OWNER = FREE_STORAGE_POINTER()
+
+Use one of the supported definitions:
+
+    #define constant OWNER = FREE_STORAGE_POINTER()
+"#,
+    },
+    Explanation {
+        code: "H0204",
+        name: "InvalidConstantValue",
+        text: r#"A constant is assigned something other than a literal or `FREE_STORAGE_POINTER()`.
+
+Erroneous code example:
+
+    #define constant OWNER = caller
+
+Assign a hex literal or a free storage pointer:
+
+    #define constant OWNER = FREE_STORAGE_POINTER()
+    #define constant ONE = 0x01
+
+A free storage pointer reserving several slots is sized by a number or hex literal of at least
+one slot:
+
+    #define constant BALANCES = FREE_STORAGE_POINTER(4)
+"#,
+    },
+    Explanation {
+        code: "H0205",
+        name: "InvalidTokenInMacroBody",
+        text: r#"A macro body contains a token that cannot be a macro statement.
+
+Macro bodies may contain opcodes, literals, constant references, label definitions and
+jumps, macro invocations, arg calls and builtin functions.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        #define constant ONE = 0x01
+    }
+
+Move definitions out of the macro body:
+
+    #define constant ONE = 0x01
+
+    #define macro MAIN() = takes(0) returns(0) {
+        [ONE]
+    }
+"#,
+    },
+    Explanation {
+        code: "H0206",
+        name: "InvalidTokenInLabelDefinition",
+        text: r#"A label body contains a token that cannot be a statement.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        success:
+            #define constant ONE = 0x01
+    }
+
+Label bodies may contain the same statements as a macro body:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        success:
+            0x01 0x00 mstore
+    }
+"#,
+    },
+    Explanation {
+        code: "H0207",
+        name: "InvalidSingleArg",
+        text: r#"The argument of `takes` or `returns` is not a number.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(zero) returns(0) {}
+
+Pass the number of stack items:
+
+    #define macro MAIN() = takes(0) returns(0) {}
+"#,
+    },
+    Explanation {
+        code: "H0208",
+        name: "InvalidTableBodyToken",
+        text: r#"A table body contains a token that is not allowed in tables.
+
+Jump tables may only contain label names.
+
+Erroneous code example:
+
+    #define jumptable SWITCH {
+        0x01 0x02
+    }
+
+List the labels to jump to:
+
+    #define jumptable SWITCH {
+        first second
+    }
+"#,
+    },
+    Explanation {
+        code: "H0209",
+        name: "InvalidConstant",
+        text: r#"A constant reference in square brackets does not contain a name.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        [0x01]
+    }
+
+Reference a constant by its name:
+
+    #define constant ONE = 0x01
+
+    #define macro MAIN() = takes(0) returns(0) {
+        [ONE]
+    }
+"#,
+    },
+    Explanation {
+        code: "H0210",
+        name: "InvalidArgCallIdent",
+        text: r#"An arg call in angle brackets does not contain a parameter name.
+
+Erroneous code example:
+
+    #define macro STORE(slot) = takes(1) returns(0) {
+        <0x00> sstore
+    }
+
+Reference a macro parameter by its name:
+
+    #define macro STORE(slot) = takes(1) returns(0) {
+        <slot> sstore
+    }
+"#,
+    },
+    Explanation {
+        code: "H0211",
+        name: "InvalidName",
+        text: r#"A definition is missing its name, or its name is not an identifier.
+
+Erroneous code example:
+
+    #define macro 0x01() = takes(0) returns(0) {}
+
+Give the definition an identifier as its name:
+
+    #define macro MAIN() = takes(0) returns(0) {}
+"#,
+    },
+    Explanation {
+        code: "H0212",
+        name: "InvalidArgs",
+        text: r#"The arguments of a definition or builtin function are malformed.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __codesize(0x01)
+    }
+
+Builtin functions take the name of a macro or table:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __codesize(CHILD)
+    }
+"#,
+    },
+    Explanation {
+        code: "H0213",
+        name: "InvalidUint256",
+        text: r#"An unsigned integer type has an invalid size.
+
+The size of `uintN` must be a multiple of 8 between 8 and 256.
+
+Erroneous code example:
+
+    #define function setValue(uint7) nonpayable returns ()
+
+Use a valid size:
+
+    #define function setValue(uint8) nonpayable returns ()
+"#,
+    },
+    Explanation {
+        code: "H0214",
+        name: "InvalidBytes",
+        text: r#"A fixed size bytes type has an invalid size.
+
+The size of `bytesN` must be between 1 and 32.
+
+Erroneous code example:
+
+    #define function setHash(bytes33) nonpayable returns ()
+
+Use a valid size, or `bytes` for dynamic bytes:
+
+    #define function setHash(bytes32) nonpayable returns ()
+"#,
+    },
+    Explanation {
+        code: "H0215",
+        name: "InvalidInt",
+        text: r#"A signed integer type has an invalid size.
+
+The size of `intN` must be a multiple of 8 between 8 and 256.
+
+Erroneous code example:
+
+    #define function setDelta(int300) nonpayable returns ()
+
+Use a valid size:
+
+    #define function setDelta(int256) nonpayable returns ()
+"#,
+    },
+    Explanation {
+        code: "H0216",
+        name: "InvalidMacroArgs",
+        text: r#"An argument passed to a macro invocation is not a literal, identifier or arg call.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        STORE(add)
+    }
+
+Pass a literal, a constant or label name, or an arg call:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        STORE(0x01)
+    }
+"#,
+    },
+    Explanation {
+        code: "H0217",
+        name: "InvalidReturnArgs",
+        text: r#"The return types of a function definition are malformed.
+
+Erroneous code example:
+
+    #define function balanceOf(address) view returns uint256
+
+Wrap the return types in parentheses:
+
+    #define function balanceOf(address) view returns (uint256)
+"#,
+    },
+    Explanation {
+        code: "H0218",
+        name: "InvalidImportPath",
+        text: r#"An `#include` path does not point to a huff file.
+
+Erroneous code example:
+
+    #include "./utils/Ownable.sol"
+
+Include files with the `.huff` extension:
+
+    #include "./utils/Ownable.huff"
+"#,
+    },
+    Explanation {
+        code: "H0219",
+        name: "InvalidTableFile",
+        text: r#"The file a code table is read from doesn't exist or can't be read.
+
+Erroneous code example:
+
+    #define table DATA = file("./missing.bin")
+
+Paths are relative to the file defining the table. Point the table at an existing file:
+
+    #define table DATA = file("./data/blob.bin")
+"#,
+    },
+    Explanation {
+        code: "H0220",
+        name: "InvalidArtifactImport",
+        text: r#"An `#include artifact` path doesn't point to a readable artifact json.
+
+Erroneous code example:
+
+    #include artifact "./out/Lib.huff"
+
+Paths are relative to the including file. Compile the dependency with `-a` first, and include
+the artifact it writes:
+
+    #include artifact "./out/Lib.json"
+"#,
+    },
+    Explanation {
+        code: "H0221",
+        name: "InvalidTokenInAliasBody",
+        text: r#"An opcode alias contains a token that cannot be aliased, or nothing at all.
+
+Aliases expand to the literals, opcodes and constant pushes following their `=`, up to the next
+definition. Use a macro for anything else.
+
+Erroneous code example:
+
+    #define alias load = <offset> calldataload
+
+Pass the value on the stack instead:
+
+    #define alias load = calldataload
+"#,
+    },
+    Explanation {
+        code: "H0222",
+        name: "InvalidRepeatCount",
+        text: r#"The count of a `__REPEAT` block is not a literal of at most 0x6000.
+
+Repeats are expanded when parsing, so their count must be a literal, or a constant defined as
+one. A count larger than the size limit of a contract cannot fit in one.
+
+Erroneous code example:
+
+    #define constant SLOT = FREE_STORAGE_POINTER()
+
+    #define macro CLEAR() = takes(0) returns(0) {
+        __REPEAT([SLOT]) { 0x00 0x00 sstore }
+    }
+
+Use a literal constant instead:
+
+    #define constant WORDS = 0x04
+
+    #define macro CLEAR() = takes(0) returns(0) {
+        __REPEAT([WORDS], I) { 0x00 [I] sstore }
+    }
+"#,
+    },
+    Explanation {
+        code: "H0223",
+        name: "UnknownConstantOverride",
+        text: r#"A constant override names a constant the contract doesn't define.
+
+Overrides, like `huffc -c NAME=VALUE`, replace the value of a `#define constant`. They cannot
+introduce new constants, so a misspelled name is reported instead of being ignored.
+
+Erroneous code example:
+
+    #define constant FEE = 0x64
+
+    huffc src/Pool.huff -c FEES=0x0a
+
+Name a constant of the contract, or use `--define` to define a new one:
+
+    huffc src/Pool.huff -c FEE=0x0a
+"#,
+    },
+    Explanation {
+        code: "H0224",
+        name: "InvalidVerbatimBytes",
+        text: r#"The literal of a `__VERBATIM` isn't a whole number of bytes.
+
+Verbatim bytes are spliced into the bytecode as written, so the literal must have an even number
+of hex digits, without `_` separators. Literals are at most 32 bytes, use several `__VERBATIM`
+calls for longer sequences.
+
+Erroneous code example:
+
+    #define macro PUSH0() = takes(0) returns(1) {
+        __VERBATIM(0x5f5)
+    }
+
+Write every byte in full:
+
+    #define macro PUSH0() = takes(0) returns(1) {
+        __VERBATIM(0x5f)
+    }
+"#,
+    },
+    Explanation {
+        code: "H0225",
+        name: "MissingParameterDefault",
+        text: r#"A macro parameter has no default, but follows a parameter with one.
+
+Invocations can only omit trailing arguments, which are replaced by the defaults of their
+parameters. A parameter without a default after one with a default could never be omitted.
+
+Erroneous code example:
+
+    #define macro REQUIRE(err = fail, value) = takes(0) returns(0) {
+        <value> <err> jumpi
+    }
+
+Move the parameters with a default last:
+
+    #define macro REQUIRE(value, err = fail) = takes(0) returns(0) {
+        <value> <err> jumpi
+    }
+"#,
+    },
+    Explanation {
+        code: "H0226",
+        name: "PrivateDefinition",
+        text: r#"A macro or constant marked `internal` is referenced from another file.
+
+Internal definitions are only visible to the file defining them. Libraries use them to keep
+helpers out of their public surface.
+
+Erroneous code example:
+
+    // math.huff
+    #define internal macro ADD_UNCHECKED() = takes(2) returns(1) {
+        add
+    }
+
+    // main.huff
+    #include "./math.huff"
+    #define macro MAIN() = takes(0) returns(0) {
+        0x01 0x02 ADD_UNCHECKED()
+    }
+
+Use a public macro of the library instead, or remove the `internal` modifier.
+"#,
+    },
+    Explanation {
+        code: "H0301",
+        name: "StoragePointersNotDerived",
+        text: r#"A `FREE_STORAGE_POINTER()` constant reached code generation without a slot.
+
+Storage pointers are assigned after parsing. This error means the contract was compiled
+without deriving them first, and points to a bug in the tool driving the compiler.
+
+Call `Contract::derive_storage_pointers` before generating bytecode.
+"#,
+    },
+    Explanation {
+        code: "H0302",
+        name: "InvalidMacroStatement",
+        text: r#"A macro body contains a statement that cannot be compiled to bytecode.
+
+This points to a bug in the parser, since it only produces statements code generation
+understands. Please report it with the source that caused it.
+"#,
+    },
+    Explanation {
+        code: "H0303",
+        name: "MissingMacroDefinition",
+        text: r#"A macro referenced by name is not defined.
+
+Every contract needs a `MAIN` macro, and the builtin functions must reference a defined
+macro or table.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __codesize(CHLID)
+    }
+
+Check the spelling or define the macro:
+
+    #define macro CHILD() = takes(0) returns(0) {}
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __codesize(CHILD)
+    }
+"#,
+    },
+    Explanation {
+        code: "H0304",
+        name: "MissingConstantDefinition",
+        text: r#"A constant referenced in square brackets is not defined.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        [OWNER] sload
+    }
+
+Define the constant:
+
+    #define constant OWNER = FREE_STORAGE_POINTER()
+
+    #define macro MAIN() = takes(0) returns(0) {
+        [OWNER] sload
+    }
+
+Constants passed as macro arguments, like `TAKE_FEE([FEE_BPS])`, are resolved when the
+invocation is expanded, so a library macro can use a constant defined by the file including
+it. The error then points to the invocation passing the constant, and the help lists the
+macros it was expanded from.
+"#,
+    },
+    Explanation {
+        code: "H0305",
+        name: "AbiGenerationFailure",
+        text: r#"The abi of the contract could not be generated or written.
+
+Check that the function and event definitions are valid, and that the output path is
+writable.
+"#,
+    },
+    Explanation {
+        code: "H0306",
+        name: "UnmatchedJumpLabel",
+        text: r#"A jump references a label that is not defined in scope.
+
+Labels are scoped to the macro that defines them and the macros it invokes.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        succes jumpi
+        0x00 0x00 revert
+        success:
+            stop
+    }
+
+Check the spelling of the label, and define it in the same macro or a parent macro:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        success jumpi
+        0x00 0x00 revert
+        success:
+            stop
+    }
+"#,
+    },
+    Explanation {
+        code: "H0307",
+        name: "IOError",
+        text: r#"Reading or writing a file during code generation failed.
+
+The message contains the underlying error. Check that the paths exist and are readable or
+writable.
+"#,
+    },
+    Explanation {
+        code: "H0308",
+        name: "UnkownArgcallType",
+        text: r#"An arg call resolved to an argument that cannot be placed in bytecode.
+
+Arg calls may resolve to literals, constants, labels and other arg calls.
+
+Erroneous code example:
+
+    #define macro INNER(value) = takes(0) returns(0) {
+        <value>
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        INNER(UNKNOWN)
+    }
+
+Pass a literal, a defined constant or a label:
+
+    #define constant KNOWN = 0x01
+
+    #define macro MAIN() = takes(0) returns(0) {
+        INNER(KNOWN)
+    }
+"#,
+    },
+    Explanation {
+        code: "H0309",
+        name: "MissingMacroInvocation",
+        text: r#"An arg call could not be resolved because no invocation passes the argument.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        <value>
+    }
+
+Arg calls can only be used in macros invoked with arguments:
+
+    #define macro INNER(value) = takes(0) returns(0) {
+        <value>
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        INNER(0x01)
+    }
+"#,
+    },
+    Explanation {
+        code: "H0310",
+        name: "InvalidMacroInvocation",
+        text: r#"A macro is invoked but not defined.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        TRANSFR()
+    }
+
+Check the spelling, or define or include the macro:
+
+    #define macro TRANSFER() = takes(0) returns(0) {}
+
+    #define macro MAIN() = takes(0) returns(0) {
+        TRANSFER()
+    }
+"#,
+    },
+    Explanation {
+        code: "H0311",
+        name: "UsizeConversion",
+        text: r#"A size or offset doesn't fit in the number of bytes it is encoded with.
+
+This happens when a jump table or contract is too large to address with the pushed offsets.
+Split the contract or reduce the size of the table.
+"#,
+    },
+    Explanation {
+        code: "H0312",
+        name: "CodehashMismatch",
+        text: r#"The keccak256 hash of a macro's or table's bytecode doesn't match the hash asserted
+with `__ASSERT_CODEHASH`.
+
+Erroneous code example:
+
+    #define macro CHILD() = takes(0) returns(0) {
+        0x01 0x02 add
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __ASSERT_CODEHASH(CHILD, 0x01)
+    }
+
+If the change to the bytecode is intended, update the asserted hash to the one in the
+error's help:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __ASSERT_CODEHASH(CHILD, 0xdecae447f2d47ba6808e14c600b3b7611ce1cc9a632546acfd1130ca42c07c4d)
+    }
+"#,
+    },
+    Explanation {
+        code: "H0313",
+        name: "InvalidCodehashTarget",
+        text: r#"`__ASSERT_CODEHASH` targets a jump table, which has no bytecode of its own.
+
+Jump table contents depend on the label offsets of the macro using them, so their hash
+cannot be asserted. Assert the codehash of a macro or code table instead.
+"#,
+    },
+    Explanation {
+        code: "H0314",
+        name: "InvalidDataTable",
+        text: r#"A data contract is generated from a table that doesn't hold code.
+
+Erroneous code example:
+
+    #define jumptable LABELS {
+        success failure
+    }
+
+    huffc data-contract ./src/Main.huff --table LABELS
+
+Only code tables can be stored as data contracts, for example one read from a file:
+
+    #define table DATA = file("./data/blob.bin")
+
+    huffc data-contract ./src/Main.huff --table DATA
+"#,
+    },
+    Explanation {
+        code: "H0315",
+        name: "ManifestMismatch",
+        text: r#"A library symbol referenced by the contract doesn't match the manifest pinned for the
+library in `huff.toml`.
+
+Manifests are generated with `huffc package`, and record the macros of a library with their
+parameters and stack effects, its constants, and its storage slots. A macro whose parameters,
+`takes` or `returns` changed, a constant whose value changed, or a storage slot that moved is
+a breaking change for the contracts using it.
+
+Erroneous example, with a manifest packaged when `ONLY_OWNER` took no stack items:
+
+    #define macro ONLY_OWNER() = takes(1) returns(0) {
+        caller eq authorized jumpi
+        0x00 0x00 revert
+        authorized:
+    }
+
+Update the usage of the symbol to the new library version, then package the library again to
+pin the new manifest.
+"#,
+    },
+    Explanation {
+        code: "H0316",
+        name: "RecursiveMacroInvocation",
+        text: r#"A macro is invoked, or passed to `__codesize`, in its own expansion.
+
+Macros are inlined where they are invoked, so a macro expanding into itself, directly or through
+other macros, never finishes expanding. The error lists the invocation chain from the root macro.
+
+Erroneous example:
+
+    #define macro PING() = takes(0) returns(0) {
+        PONG()
+    }
+
+    #define macro PONG() = takes(0) returns(0) {
+        PING()
+    }
+
+Use a jump to a label to repeat code at runtime instead.
+"#,
+    },
+    Explanation {
+        code: "H0317",
+        name: "MaxExpansionDepthExceeded",
+        text: r#"Macro invocations are nested deeper than the maximum expansion depth, 256 by default.
+
+The limit keeps code generation from overflowing the compiler's stack. Flatten the invocation
+chain, or raise the limit with `--max-expansion-depth`.
+"#,
+    },
+    Explanation {
+        code: "H0318",
+        name: "StorageSlotCollision",
+        text: r#"The slot derived for a `FREE_STORAGE_POINTER()` constant is also the slot of a literal
+constant used by `sload` or `sstore`.
+
+Free storage pointers are assigned the slots from 0 up, in the order `CONSTRUCTOR` then `MAIN`
+first use them, without knowing about the slots assigned by hand. Both constants would read and
+write the same storage, corrupting each other's values.
+
+Erroneous example:
+
+    #define constant OWNER = FREE_STORAGE_POINTER()
+    #define constant BALANCE_SLOT = 0x00
+
+    #define macro MAIN() = takes(0) returns(0) {
+        caller [OWNER] sstore
+        callvalue [BALANCE_SLOT] sstore
+    }
+
+Move the literal slot out of the range of the derived slots, eg. to a hashed slot, or derive it
+with `FREE_STORAGE_POINTER()` too.
+"#,
+    },
+    Explanation {
+        code: "H0319",
+        name: "ImmutableSetInRuntime",
+        text: r#"`__SET_IMMUTABLE` is called by the `MAIN` macro, or by a macro it invokes.
+
+Immutables are part of the runtime bytecode, so only the constructor can set them, before the
+runtime bytecode is deployed.
+
+Erroneous example:
+
+    #define immutable OWNER
+
+    #define macro MAIN() = takes(0) returns(0) {
+        caller __SET_IMMUTABLE(OWNER)
+    }
+
+Set the immutable in the `CONSTRUCTOR` macro:
+
+    #define macro CONSTRUCTOR() = takes(0) returns(0) {
+        caller __SET_IMMUTABLE(OWNER)
+    }
+"#,
+    },
+    Explanation {
+        code: "H0320",
+        name: "AssertionFailed",
+        text: r#"The expression of an `__ASSERT` evaluates to zero.
+
+Assertions are checked at compile time, on constants, literals and the builtins known at compile
+time: `__tablesize`, `__codesize`, `__KECCAK256` and `__RIGHTPAD`. Failed comparisons show the
+values of both sides.
+
+Erroneous example:
+
+    #define constant MAX_OWNERS = 0x10
+
+    #define table OWNERS {
+        owner_0 owner_1
+    }
+
+    __ASSERT(__tablesize(OWNERS) == [MAX_OWNERS])
+
+Fix the constants, or the assertion, so that the expression holds.
+"#,
+    },
+    Explanation {
+        code: "H0321",
+        name: "WellKnownConstantMismatch",
+        text: r#"A constant is defined with the name of a well-known constant, but another value.
+
+Well-known constants, like the EIP-1967 proxy slots and the precompile addresses, can be used
+without defining them. A definition with a well-known name takes precedence, so a mistyped value
+would silently be used instead.
+
+Erroneous example:
+
+    #define constant EIP1967_IMPLEMENTATION_SLOT = 0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbd
+
+Remove the definition to use the well-known value:
+
+    #define macro IMPLEMENTATION() = takes(0) returns(1) {
+        [EIP1967_IMPLEMENTATION_SLOT] sload
+    }
+"#,
+    },
+    Explanation {
+        code: "H0322",
+        name: "OffsetOutOfRange",
+        text: r#"An expression of label offsets evaluates to a value that doesn't fit in two bytes.
+
+Offset expressions are pushed with a `PUSH2`, like jump destinations, and filled once their
+labels are laid out. Arithmetic wraps around like the EVM's, so subtracting the offset of a
+later label from an earlier one underflows.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __OFFSET(start) - __OFFSET(end)
+        start:
+            0x00 0x00 revert
+        end:
+    }
+
+Subtract the offset of the earlier label:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __OFFSET(end) - __OFFSET(start)
+        start:
+            0x00 0x00 revert
+        end:
+    }
+"#,
+    },
+    Explanation {
+        code: "H0323",
+        name: "MacroArgCountMismatch",
+        text: r#"A macro invocation passes another number of arguments than the macro declares.
+
+Each parameter of a macro must be passed an argument, and no more. A missing argument would
+otherwise be compiled to nothing, leaving the stack short of the value the macro expects.
+
+Erroneous code example:
+
+    #define macro STORE(slot, value) = takes(0) returns(0) {
+        <value> <slot> sstore
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        STORE(0x00)
+    }
+
+Pass an argument for each parameter:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        STORE(0x00, 0x01)
+    }
+"#,
+    },
+    Explanation {
+        code: "H0324",
+        name: "InvalidMacroArgument",
+        text: r#"A macro argument is neither a literal, a constant, a label nor an opcode.
+
+Identifiers passed to a macro are pushed as the destination of the label of that name, or
+generated as the opcode of that mnemonic. An identifier naming neither is most likely a typo.
+
+Erroneous code example:
+
+    #define macro CALL_OP(op) = takes(2) returns(1) {
+        <op>
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x01 0x02 CALL_OP(addd)
+    }
+
+Pass an opcode or a label defined by the contract:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x01 0x02 CALL_OP(add)
+    }
+"#,
+    },
+    Explanation {
+        code: "H0325",
+        name: "UndeclaredArgCall",
+        text: r#"An invoked macro has an arg call to a parameter it doesn't declare.
+
+Arg calls, like `<value>`, push the argument passed for a parameter of the macro. An arg call
+naming neither a parameter nor a constant has no value to push.
+
+Erroneous code example:
+
+    #define macro STORE(slot) = takes(0) returns(0) {
+        <value> <slot> sstore
+    }
+
+Declare the parameter, and pass it:
+
+    #define macro STORE(slot, value) = takes(0) returns(0) {
+        <value> <slot> sstore
+    }
+"#,
+    },
+    Explanation {
+        code: "H0326",
+        name: "MissingFunctionDefinition",
+        text: r#"A builtin names a function the contract doesn't define.
+
+`__DECODE_CALLDATA` and `__ENCODE_OUTPUTS` generate the abi decoding of the parameters, or the
+encoding of the outputs, of a function declared with `#define function`.
+
+Erroneous code example:
+
+    #define macro TRANSFER() = takes(0) returns(0) {
+        __DECODE_CALLDATA(transfer)
+    }
+
+Declare the function:
+
+    #define function transfer(address to, uint256 amount) nonpayable returns ()
+"#,
+    },
+    Explanation {
+        code: "H0327",
+        name: "UnencodableOutput",
+        text: r#"An output of a function encoded with `__ENCODE_OUTPUTS` isn't a value type.
+
+The outputs are taken from the stack, a word each, so only value types like `uint256`,
+`address`, `bool` or `bytes32` can be encoded. Strings, bytes, arrays and tuples don't fit in a
+word.
+
+Erroneous code example:
+
+    #define function name() view returns (string)
+
+    #define macro NAME() = takes(0) returns(0) {
+        0x00 __ENCODE_OUTPUTS(name)
+    }
+
+Store the encoding in memory by hand, like `__MSTORE_STRING` does for strings:
+
+    #define macro NAME() = takes(0) returns(0) {
+        0x00 __MSTORE_STRING("Token") 0x00 return
+    }
+"#,
+    },
+    Explanation {
+        code: "H0328",
+        name: "MissingEventDefinition",
+        text: r#"`__EMIT` names an event the contract doesn't define.
+
+`__EMIT` logs an event declared with `#define event`, hashing its signature into the first
+topic.
+
+Erroneous code example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        caller 0x01 __EMIT(Ping)
+    }
+
+Declare the event:
+
+    #define event Ping(address indexed from, uint256 value)
+"#,
+    },
+    Explanation {
+        code: "H0329",
+        name: "UnemittableEvent",
+        text: r#"An event emitted with `__EMIT` can't be logged from the stack.
+
+The parameters of the event are taken from the stack, a word each, so only value types like
+`uint256`, `address`, `bool` or `bytes32` can be logged. A log also holds at most four topics,
+the hash of the event's signature and three indexed parameters.
+
+Erroneous code example:
+
+    #define event Named(string name)
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x00 __EMIT(Named)
+    }
+
+Log the abi encoding of dynamic parameters by hand, storing it in memory first:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __KECCAK256("Named(string)") 0x00 __MSTORE_STRING("Huff") 0x00 log1
+    }
+"#,
+    },
+    Explanation {
+        code: "H0330",
+        name: "PluginFailed",
+        text: r#"A codegen plugin failed to transform the contract or its bytecode.
+
+Plugins registered with `CompilerBuilder::plugin` run before the macros are expanded, after
+each of `MAIN` and `CONSTRUCTOR` is expanded, and before the bytecode is assembled. A plugin
+fails compilation when the contract doesn't meet its expectations, like a missing prelude
+macro, explaining why in the help of the error.
+
+Fix the contract as the help suggests, or remove the plugin from the compiler builder.
+"#,
+    },
+    Explanation {
+        code: "H0331",
+        name: "InvalidBuiltinArgument",
+        text: r#"A custom builtin is invoked with arguments it doesn't accept.
+
+Custom builtins are registered with `CompilerBuilder::builtin` by the crates compiling with
+`huff_core`, and declare the kind of each of their parameters: a literal, a constant, an
+identifier like a label or macro name, or a value, either a literal or a constant. Arg calls
+are accepted for any parameter.
+
+Erroneous code example, for a builtin taking a literal:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __L1_BLOCK(number)
+    }
+
+Pass the arguments the builtin declares:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __L1_BLOCK(0x01)
+    }
+"#,
+    },
+    Explanation {
+        code: "H0401",
+        name: "UnsupportedExtension",
+        text: r#"An input file does not have the `.huff` extension.
+
+Erroneous example:
+
+    huffc ./src/ERC20.sol
+
+Pass huff files or a directory containing them:
+
+    huffc ./src/ERC20.huff
+"#,
+    },
+    Explanation {
+        code: "H0402",
+        name: "InvalidDirectory",
+        text: r#"An input directory could not be read.
+
+Check that the directory exists and is readable.
+"#,
+    },
+    Explanation {
+        code: "H0403",
+        name: "MissingFile",
+        text: r#"An input file or an included file does not exist.
+
+Erroneous code example:
+
+    #include "./utils/Ownabel.huff"
+
+Check the path. Includes are resolved relative to the including file.
+"#,
+    },
+    Explanation {
+        code: "H0404",
+        name: "PathBufRead",
+        text: r#"A path could not be converted to a string.
+
+Paths containing invalid unicode cannot be compiled. Rename the file or directory.
+"#,
+    },
+    Explanation {
+        code: "H0405",
+        name: "CircularInclude",
+        text: r#"Files include each other, directly or through other files.
+
+The error lists the files of the cycle, from the first file included again back to it.
+
+Erroneous code example:
+
+    // a.huff
+    #include "./b.huff"
+
+    // b.huff
+    #include "./a.huff"
+
+Move the definitions both files need to a third file, included by each of them.
+"#,
+    },
+    Explanation {
+        code: "H0406",
+        name: "UnflattenableInclude",
+        text: r#"A file included under a namespace, or a prebuilt artifact, can't be flattened.
+
+Flattening inlines the included files into a single source. The definitions of a namespaced
+file are renamed by the compiler, and an artifact include imports compiled code rather than
+source, so neither can be inlined.
+
+Erroneous code example:
+
+    #include "./math.huff" as Math
+
+Include the file without a namespace to flatten the contract.
+"#,
+    },
+    Explanation {
+        code: "H0501",
+        name: "NonLowercaseOpcode",
+        text: r#"An opcode mnemonic is not lowercase. Lint: `opcode-case`.
+
+Mnemonics are only accepted in other cases with `--case-insensitive-opcodes`.
+
+Example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x01 0x02 ADD
+    }
+
+Run `huffc fmt` to lowercase the mnemonics:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x01 0x02 add
+    }
+"#,
+    },
+    Explanation {
+        code: "H0502",
+        name: "UnusedMacroParameter",
+        text: r#"A macro parameter is never referenced with an arg call. Lint: `unused-macro-parameter`.
+
+Example:
+
+    #define macro STORE(slot, value) = takes(0) returns(0) {
+        <value> 0x00 sstore
+    }
+
+Use the parameter, or remove it:
+
+    #define macro STORE(slot, value) = takes(0) returns(0) {
+        <value> <slot> sstore
+    }
+"#,
+    },
+    Explanation {
+        code: "H0503",
+        name: "UndeclaredArgCall",
+        text: r#"An arg call references a parameter the macro doesn't declare. Lint: `undeclared-arg-call`.
+
+Example:
+
+    #define macro STORE(slot) = takes(1) returns(0) {
+        <slto> sstore
+    }
+
+Check the spelling, or declare the parameter:
+
+    #define macro STORE(slot) = takes(1) returns(0) {
+        <slot> sstore
+    }
+"#,
+    },
+    Explanation {
+        code: "H0504",
+        name: "UnusedMacro",
+        text: r#"A macro is never reached from the entrypoints. Lint: `unused-macro`.
+
+`MAIN` and `CONSTRUCTOR` are always used. Other macros are used when they are invoked or
+referenced by a builtin function in a used macro, so a macro only invoked by unused macros is
+unused too. Files without `MAIN` or `CONSTRUCTOR`, like libraries of macros, use every macro
+they define, and only report the definitions none of them reference.
+"#,
+    },
+    Explanation {
+        code: "H0505",
+        name: "UnusedConstant",
+        text: r#"A constant is never referenced. Lint: `unused-constant`.
+
+Example:
+
+    #define constant OWNER = FREE_STORAGE_POINTER()
+
+    #define macro MAIN() = takes(0) returns(0) {}
+
+Reference the constant, or remove it:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        [OWNER] sload
+    }
+"#,
+    },
+    Explanation {
+        code: "H0506",
+        name: "ShadowedLabel",
+        text: r#"A label is defined more than once in a macro. Lint: `shadowed-label`.
+
+Jumps to the label only reach one of the definitions.
+
+Example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        done jump
+        done:
+            0x00
+        done:
+            stop
+    }
+
+Give each label a unique name.
+"#,
+    },
+    Explanation {
+        code: "H0507",
+        name: "OversizedContract",
+        text: r#"The runtime bytecode is larger than 24576 bytes. Lint: `oversized-contract`.
+
+EIP-170 limits the size of deployed code, so the deployment of this contract will fail on
+mainnet. Split the contract, or move rarely used code into separate contracts.
+"#,
+    },
+    Explanation {
+        code: "H0508",
+        name: "UnreachableCode",
+        text: r#"Code follows a terminating opcode with no label in between. Lint: `unreachable-code`.
+
+Execution never continues past `stop`, `return`, `revert`, `invalid`, `selfdestruct` or
+`jump`, so the code is only reachable if a label precedes it.
+
+Example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x00 0x00 revert
+        0x01
+    }
+
+Remove the code, or label it if jumps should reach it. `huffc lint --fix` removes unreachable
+opcodes and literals.
+"#,
+    },
+    Explanation {
+        code: "H0509",
+        name: "MissingJumpTarget",
+        text: r#"A label is jumped to, but no macro defines it. Lint: `missing-jump-target`.
+
+Example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x01 dne jumpi
+        done:
+            stop
+    }
+
+Check the spelling, or define the label. Compilation fails with H0306 if the label isn't
+defined by a macro invoked with it in scope.
+"#,
+    },
+    Explanation {
+        code: "H0510",
+        name: "MagicSelector",
+        text: r#"A function selector is written as a literal. Lint: `magic-selector`, allowed by default.
+
+Example:
+
+    #define function transfer(address,uint256) nonpayable returns ()
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x00 calldataload 0xe0 shr
+        dup1 0xa9059cbb eq transfer jumpi
+    }
+
+Push a named constant instead, like `[TRANSFER_SELECTOR]`. `huffc lint --fix` replaces literals
+with a constant of the same value.
+"#,
+    },
+    Explanation {
+        code: "H0511",
+        name: "StackTooDeep",
+        text: r#"A DUP or SWAP instruction reaches deeper than 16 stack items. Lint: `stack-too-deep`.
+
+The EVM only defines `dup1` to `dup16` and `swap1` to `swap16`, so deeper instructions are
+parsed as jumps to labels.
+
+Example:
+
+    #define macro MAIN() = takes(17) returns(17) {
+        dup17
+    }
+
+Move the value closer to the top of the stack, or store it in memory.
+"#,
+    },
+    Explanation {
+        code: "H0512",
+        name: "StackUnderflow",
+        text: r#"An opcode or macro invocation takes more stack items than are available. Lint:
+`stack-underflow`, denied by default.
+
+A macro starts with the items its `takes` declares, and an invoked macro takes and returns the
+items its own declaration states.
+
+Example:
+
+    #define macro STORE() = takes(1) returns(0) {
+        sstore
+    }
+
+Here `sstore` takes 2 items, but only 1 is available. Push the missing value, or declare
+`takes(2)`.
+"#,
+    },
+    Explanation {
+        code: "H0513",
+        name: "StackMismatch",
+        text: r#"A macro ends with a stack height its `takes` and `returns` don't produce. Lint:
+`stack-mismatch`, denied by default.
+
+Example:
+
+    #define macro LOAD() = takes(0) returns(0) {
+        0x04 calldataload
+    }
+
+Here `LOAD` ends with 1 item, but declares `returns(0)`. Fix the declaration, or pop the extra
+items. Paths halting or jumping away are not checked.
+"#,
+    },
+    Explanation {
+        code: "H0514",
+        name: "UnusedAlias",
+        text: r#"An opcode alias is never used. Lint: `unused-alias`.
+
+Example:
+
+    #define alias fmp = 0x40 mload
+
+    #define macro MAIN() = takes(0) returns(0) {}
+
+Use the alias by its name, or remove it:
+
+    #define macro MAIN() = takes(0) returns(1) {
+        fmp
+    }
+"#,
+    },
+    Explanation {
+        code: "H0515",
+        name: "InlinedAlias",
+        text: r#"A macro spells out the statements of an opcode alias. Lint: `inlined-alias`.
+
+Example:
+
+    #define alias fmp = 0x40 mload
+
+    #define macro MAIN() = takes(0) returns(1) {
+        0x40 mload
+    }
+
+Use the alias instead, which `huffc lint --fix` does when the statements are only separated by
+whitespace:
+
+    #define macro MAIN() = takes(0) returns(1) {
+        fmp
+    }
+
+Aliases of a single statement are not reported.
+"#,
+    },
+    Explanation {
+        code: "H0516",
+        name: "UnusedTable",
+        text: r#"A table is never referenced by a used macro. Lint: `unused-table`.
+
+Example:
+
+    #define table SELECTORS {
+        0x01
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {}
+
+Reference the table with a builtin function, like `__tablestart` or `__tablesize`, or remove
+it. Tables imported from artifacts are not reported.
+"#,
+    },
+    Explanation {
+        code: "H0517",
+        name: "UnusedLabel",
+        text: r#"A label of a used macro is never jumped to. Lint: `unused-label`.
+
+Example:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x00 calldataload
+        done:
+            stop
+    }
+
+A label is used when a macro or a table references it, or when it's passed to a macro as an
+argument. Remove the label, or jump to it:
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x00 calldataload done jumpi
+        done:
+            stop
+    }
+"#,
+    },
+    Explanation {
+        code: "H0518",
+        name: "UnusedFunction",
+        text: r#"A function is never dispatched by a used macro. Lint: `unused-function`.
+
+Example:
+
+    #define function balanceOf(address) view returns (uint256)
+
+    #define macro MAIN() = takes(0) returns(0) {}
+
+A function is used when a used macro pushes its selector, as a literal, through a constant or
+as a macro argument. Dispatch the function, or remove it if
+the interface doesn't include it.
+"#,
+    },
+];
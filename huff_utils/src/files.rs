@@ -1,12 +1,34 @@
 use serde::{Deserialize, Serialize};
-use std::{cell::Ref, path::PathBuf, sync::Arc, time::SystemTime};
+use std::{cell::Ref, path::PathBuf, str::FromStr, sync::Arc, time::SystemTime};
 use uuid::Uuid;
 
-#[allow(clippy::to_string_in_format_args)]
-
 /// An aliased output location to derive from the cli arguments.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Default)]
 pub struct OutputLocation(pub String);
+
+/// An import path remapping, resolving includes starting with its prefix from its path rather than
+/// relative to the including file, like the remappings of solc and forge
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Default, Serialize, Deserialize)]
+pub struct Remapping {
+    /// The prefix of the remapped includes, like `solady/`
+    pub prefix: String,
+    /// The path the prefix is replaced with, like `lib/solady/src/`
+    pub path: String,
+}
+
+impl FromStr for Remapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((prefix, path)) if !prefix.is_empty() => {
+                Ok(Remapping { prefix: prefix.to_string(), path: path.to_string() })
+            }
+            _ => Err(format!("Invalid remapping \"{}\", expected <PREFIX>=<PATH>", s)),
+        }
+    }
+}
+
 /// Full File Source
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct FullFileSource<'a> {
@@ -23,7 +45,7 @@ impl<'a> FullFileSource<'a> {
     pub fn relative_span(&self, span: Ref<'a, Span>) -> Option<Span> {
         self.spans
             .iter()
-            .filter(|s| s.1.start < span.start && s.1.end > span.end)
+            .filter(|s| s.1.start <= span.start && s.1.end >= span.end)
             .map(|s| Span {
                 start: span.start - s.1.start,
                 end: span.end - s.1.start,
@@ -44,7 +66,8 @@ pub struct FileSource {
     pub path: String,
     /// File Source
     pub source: Option<String>,
-    /// Last File Access Time
+    /// Last File Access Time, left out of artifacts so they are reproducible
+    #[serde(skip)]
     pub access: Option<SystemTime>,
     /// An Ordered List of File Dependencies
     pub dependencies: Option<Vec<Arc<FileSource>>>,
@@ -65,25 +88,32 @@ impl FileSource {
         let span = Span::new(0..full_source.len(), None);
         let mut relative_positions = vec![(Arc::clone(&self_ref), span)];
 
-        // Then recursively grab source code for dependencies
-        match &self_ref.dependencies {
-            Some(vfs) => {
-                for fs in vfs {
-                    let mut flattened = FileSource::fully_flatten(Arc::clone(fs));
-                    let span =
-                        Span::new(full_source.len()..(full_source.len() + flattened.0.len()), None);
-                    full_source.push_str(&flattened.0);
-                    relative_positions.append(&mut flattened.1);
-                    relative_positions.push((Arc::clone(fs), span))
-                }
+        // Then recursively grab source code for dependencies, each file spanning its own source
+        if let Some(vfs) = &self_ref.dependencies {
+            for fs in vfs {
+                let offset = full_source.len();
+                let flattened = FileSource::fully_flatten(Arc::clone(fs));
+                full_source.push_str(&flattened.0);
+                relative_positions.extend(flattened.1.into_iter().map(|(f, s)| {
+                    (f, Span::new((s.start + offset)..(s.end + offset), None))
+                }));
             }
-            None => {}
         }
 
         // Return the full source
         (full_source, relative_positions)
     }
 
+    /// The canonical path of a file, identifying it whatever the path it was included with
+    ///
+    /// Files missing from the filesystem, like bundled modules, are identified by their path.
+    pub fn canonical_path(path: &str) -> String {
+        match std::fs::canonicalize(path) {
+            Ok(p) => p.to_string_lossy().to_string(),
+            Err(_) => path.to_string(),
+        }
+    }
+
     /// Derives a File Path's directory
     pub fn derive_dir(path: &str) -> Option<String> {
         let path = PathBuf::from(path);
@@ -93,6 +123,15 @@ impl FileSource {
         }
     }
 
+    /// Remaps an include path with the remapping of its longest matching prefix
+    pub fn remap(path: &str, remappings: &[Remapping]) -> Option<String> {
+        remappings
+            .iter()
+            .filter(|r| path.starts_with(&r.prefix))
+            .max_by_key(|r| r.prefix.len())
+            .map(|r| format!("{}{}", r.path, &path[r.prefix.len()..]))
+    }
+
     /// Localizes a file path, if path is relative
     pub fn localize_file(parent: &str, child: &str) -> Option<String> {
         let mut prefix = match FileSource::derive_dir(parent) {
@@ -147,6 +186,34 @@ impl FileSource {
 
 use std::ops::{Add, Range};
 
+/// A line and column position in a source file.
+///
+/// Both the line and the column are 1-indexed, with the column counted in characters.
+#[derive(Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Position {
+    /// The line number
+    pub line: usize,
+    /// The column number
+    pub column: usize,
+}
+
+impl Position {
+    /// Computes the position of a byte offset in the given source code.
+    ///
+    /// Offsets past the end of the source are clamped to the end of the source.
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let mut offset = offset.min(source.len());
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &source[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+        Self { line, column }
+    }
+}
+
 /// A Span is a section of a source file.
 #[derive(Default, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub struct Span {
@@ -169,7 +236,71 @@ impl Span {
 
     /// Converts a span to a range.
     pub fn range(&self) -> Option<Range<usize>> {
-        (*self != Self::EOF).then(|| self.start..self.end)
+        (*self != Self::EOF).then_some(self.start..self.end)
+    }
+
+    /// The line and column of the start of the span.
+    ///
+    /// Returns `None` if the span has no associated file source.
+    pub fn start_position(&self) -> Option<Position> {
+        self.source().map(|s| Position::from_offset(s, self.start))
+    }
+
+    /// The line and column of the end of the span.
+    ///
+    /// Returns `None` if the span has no associated file source.
+    pub fn end_position(&self) -> Option<Position> {
+        self.source().map(|s| Position::from_offset(s, self.end))
+    }
+
+    /// Produces a code frame of the source lines covered by the span.
+    ///
+    /// Each line is prefixed with its line number, and the columns of the span are underlined.
+    /// Returns `None` if the span has no associated file source.
+    ///
+    /// ```text
+    ///   --> ./src/Main.huff:2:10
+    ///    |
+    ///  2 |     0x00 mstore
+    ///    |          ^^^^^^
+    /// ```
+    pub fn code_frame(&self) -> Option<String> {
+        let path = &self.file.as_ref()?.path;
+        let source = self.source()?;
+        let start = self.start_position()?;
+        let end = self.end_position()?.max(start);
+
+        let width = end.line.to_string().len();
+        let gutter = " ".repeat(width);
+        let mut frame =
+            format!("{} --> {}:{}:{}\n{} |", gutter, path, start.line, start.column, gutter);
+        for (i, line) in source.lines().enumerate().take(end.line).skip(start.line - 1) {
+            let line_num = i + 1;
+            let from = if line_num == start.line { start.column } else { 1 };
+            let to = if line_num == end.line { end.column } else { line.chars().count() + 1 };
+            frame.push_str(&format!(
+                "\n{:>width$} | {}\n{} | {}{}",
+                line_num,
+                line,
+                gutter,
+                " ".repeat(from - 1),
+                "^".repeat(to.saturating_sub(from).max(1)),
+                width = width
+            ));
+        }
+        Some(frame)
+    }
+
+    /// The source text the span covers.
+    ///
+    /// Returns `None` if the span has no associated file source.
+    pub fn text(&self) -> Option<&str> {
+        self.source().and_then(|s| s.get(self.start..self.end))
+    }
+
+    /// The source code of the file the span belongs to
+    fn source(&self) -> Option<&str> {
+        self.file.as_ref().and_then(|f| f.source.as_deref())
     }
 
     /// Produces a file identifier string for errors
@@ -181,6 +312,9 @@ impl Span {
     }
 
     /// Produces a source segment string
+    ///
+    /// Spans reaching past the end of the source, like the ones of errors at the end of a file,
+    /// are clamped to the end of the source.
     pub fn source_seg(&self) -> String {
         self.file
             .as_ref()
@@ -188,12 +322,19 @@ impl Span {
                 f.source
                     .as_ref()
                     .map(|s| {
+                        let clamp = |offset: usize| {
+                            let mut offset = offset.min(s.len());
+                            while !s.is_char_boundary(offset) {
+                                offset -= 1;
+                            }
+                            offset
+                        };
+                        let end = clamp(self.end);
+                        let start = clamp(self.start).min(end);
                         let line_num =
-                            &s[0..self.start].as_bytes().iter().filter(|&&c| c == b'\n').count() +
-                                1;
-                        let line_start = &s[0..self.start].rfind('\n').unwrap_or(0);
-                        let line_end = self.end +
-                            s[self.end..s.len()].find('\n').unwrap_or(s.len()).to_owned();
+                            &s.as_bytes()[0..start].iter().filter(|&&c| c == b'\n').count() + 1;
+                        let line_start = &s[0..start].rfind('\n').unwrap_or(0);
+                        let line_end = end + s[end..].find('\n').unwrap_or(s.len() - end);
                         let padding =
                             (0..line_num.to_string().len()).map(|_| " ").collect::<String>();
                         format!(
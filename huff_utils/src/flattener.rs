@@ -0,0 +1,94 @@
+//! ## Flattener
+//!
+//! The single self-contained source of a contract and its includes, written by `huffc flatten`.
+//!
+//! Files are flattened in the order the compiler reads them: each file before the files it
+//! includes, in the order of its includes, each file once. The includes are removed so the source
+//! compiles on its own, and of the definitions sharing a kind and a name only the first is kept,
+//! the one the compiler uses. Functions and events can be overloaded, and are only deduplicated
+//! if their signatures match.
+//!
+//! Flattening works on the concrete syntax trees of the files, so their comments are kept, and
+//! each file is headed by a comment with its path. Namespaced and artifact includes can't be
+//! inlined, and fail to flatten.
+
+use std::collections::HashSet;
+
+use crate::{
+    cst::{NodeKind, SyntaxNode, SyntaxTree, Trivia},
+    token::TokenKind,
+};
+
+impl SyntaxTree {
+    /// Flattens the trees of the files of a contract into a single source
+    ///
+    /// The files are given by path, in the order the compiler reads them. Returns the path of the
+    /// first file with an include that can't be inlined otherwise.
+    pub fn flatten(files: &[(String, SyntaxTree)]) -> Result<String, String> {
+        let mut definitions: HashSet<(NodeKind, String)> = HashSet::new();
+        let mut sections = vec![];
+        for (path, tree) in files {
+            let mut section = String::new();
+            for node in tree.nodes.iter() {
+                if node.kind == NodeKind::Include {
+                    if !is_inlined(node) {
+                        tracing::error!(target: "flatten", "INCLUDE OF \"{}\" CAN'T BE INLINED", path);
+                        return Err(path.clone())
+                    }
+                    // The comments above an include are kept, without the include itself
+                    let leading = node.tokens().first().map(|t| t.leading.clone());
+                    section.push_str(&trivia_text(&leading.unwrap_or_default()));
+                    continue
+                }
+                if let Some(key) = definition_key(node) {
+                    if !definitions.insert((node.kind, key)) {
+                        tracing::debug!(target: "flatten", "SKIPPING DUPLICATE {:?} IN \"{}\"", node.name(), path);
+                        continue
+                    }
+                }
+                section.push_str(&node.to_string());
+            }
+            section.push_str(&trivia_text(&tree.trailing));
+            let section = section.trim();
+            sections.push(match section.is_empty() {
+                true => format!("// {}", path),
+                false => format!("// {}\n\n{}", path, section),
+            });
+        }
+        Ok(collapse_blank_lines(&sections.join("\n\n")) + "\n")
+    }
+}
+
+/// If an include names a huff file, rather than an artifact or a namespace
+fn is_inlined(node: &SyntaxNode) -> bool {
+    !node.tokens().iter().any(|t| {
+        matches!(&t.kind, TokenKind::Ident(i) if i == "artifact" || i == "as")
+    })
+}
+
+/// The key deduplicating a definition: its name, or its signature for functions, events and
+/// errors
+fn definition_key(node: &SyntaxNode) -> Option<String> {
+    match node.kind {
+        NodeKind::Function | NodeKind::Event | NodeKind::Error => {
+            Some(node.tokens().iter().map(|t| t.text.as_str()).collect::<Vec<&str>>().join(" "))
+        }
+        _ => node.name().map(String::from),
+    }
+}
+
+/// The source text of a list of trivia
+fn trivia_text(trivia: &[Trivia]) -> String {
+    trivia.iter().map(|t| t.text.as_str()).collect()
+}
+
+/// Collapses the blank lines left by removed definitions, keeping at most one in a row
+fn collapse_blank_lines(source: &str) -> String {
+    let mut lines: Vec<&str> = vec![];
+    for line in source.lines().map(str::trim_end) {
+        if !line.is_empty() || lines.last().map(|l| !l.is_empty()).unwrap_or(false) {
+            lines.push(line);
+        }
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,478 @@
+//! ## Formatter
+//!
+//! The canonical formatting of huff source, applied by `huffc fmt`.
+//!
+//! Formatting rewrites the concrete syntax tree of a source, so comments are kept where they
+//! were written. Includes are sorted by path and moved to the top of the file, and definition
+//! headers are rendered on a single line, the parameters of long macro signatures wrapped one per
+//! line, without trailing commas. Aliases have no body, and are rendered on a single line as well.
+//! Bodies keep their line breaks but are indented by label and block, their trailing stack comments
+//! aligned.
+//! At most one blank line is kept between definitions and statements.
+//!
+//! Definitions the formatter can't safely rewrite, like those containing source the lexer
+//! rejected or comments inside their header, are kept verbatim.
+
+use crate::{
+    cst::{
+        collect_tokens, Delimiter, NodeKind, SyntaxElement, SyntaxGroup, SyntaxNode, SyntaxToken,
+        SyntaxTree, Trivia, TriviaKind,
+    },
+    token::TokenKind,
+};
+
+/// The maximum width of a macro signature before its parameters are wrapped
+pub const MAX_SIGNATURE_WIDTH: usize = 100;
+
+/// The indentation of a nesting level
+const INDENT: &str = "    ";
+
+/// The minimum gap between the code and the trailing comment of a line
+const COMMENT_GAP: usize = 4;
+
+/// A top-level definition, with the trivia it owns
+struct Entry<'a> {
+    node: &'a SyntaxNode,
+    /// The trivia preceding the definition, from the line following the previous definition
+    leading: &'a [Trivia],
+    /// The comments following the definition on its last line
+    trailing: Vec<&'a str>,
+}
+
+/// A piece of a line of code
+#[derive(Clone, Copy)]
+enum Piece<'a> {
+    Code(&'a SyntaxToken),
+    Comment(&'a str),
+}
+
+impl<'a> Piece<'a> {
+    fn text(&self) -> &'a str {
+        match self {
+            Piece::Code(t) => &t.text,
+            Piece::Comment(c) => c,
+        }
+    }
+}
+
+/// A line of a definition body
+struct BodyLine<'a> {
+    pieces: Vec<Piece<'a>>,
+    /// Whether the line is preceded by a blank line
+    blank: bool,
+}
+
+impl SyntaxTree {
+    /// Formats the source of the tree
+    pub fn format(&self) -> String {
+        // Comments on the last line of a definition belong to it, not to the next definition
+        let mut entries: Vec<Entry> = vec![];
+        for node in self.nodes.iter() {
+            let leading = node.tokens().first().map(|t| t.leading.as_slice()).unwrap_or_default();
+            let split = if entries.is_empty() { 0 } else { same_line_len(leading) };
+            if let Some(previous) = entries.last_mut() {
+                previous.trailing = comments(&leading[..split]);
+            }
+            entries.push(Entry { node, leading: &leading[split..], trailing: vec![] });
+        }
+        let mut trailing = self.trailing.as_slice();
+        if let Some(last) = entries.last_mut() {
+            let split = same_line_len(trailing);
+            last.trailing = comments(&trailing[..split]);
+            trailing = &trailing[split..];
+        }
+
+        // The comments heading the file, separated from the first definition by a blank line,
+        // stay first when the includes are moved
+        let mut lines = vec![];
+        if let Some(first) = entries.first_mut() {
+            let header = first.leading.iter().rposition(is_blank_line).unwrap_or(0);
+            lines.extend(trivia_lines(&first.leading[..header]));
+            lines.push(String::new());
+            first.leading = &first.leading[header..];
+        }
+
+        let (mut includes, definitions): (Vec<Entry>, Vec<Entry>) =
+            entries.into_iter().partition(|e| e.node.kind == NodeKind::Include);
+        includes.sort_by_cached_key(|e| format_node(e.node));
+        for entry in includes.iter() {
+            lines.extend(trivia_lines(entry.leading).into_iter().filter(|l| !l.is_empty()));
+            lines.extend(format_entry(entry));
+        }
+        lines.push(String::new());
+        for entry in definitions.iter() {
+            lines.extend(trivia_lines(entry.leading));
+            lines.extend(format_entry(entry));
+        }
+        lines.push(String::new());
+        lines.extend(trivia_lines(trailing));
+
+        render(lines)
+    }
+}
+
+/// Joins lines, dropping trailing whitespace and collapsing blank lines
+fn render(lines: Vec<String>) -> String {
+    let mut out: Vec<String> = vec![];
+    for line in lines {
+        let line = line.trim_end().to_string();
+        if !line.is_empty() || out.last().map(|l| !l.is_empty()).unwrap_or(false) {
+            out.push(line);
+        }
+    }
+    while out.last().map(|l| l.is_empty()).unwrap_or(false) {
+        out.pop();
+    }
+    match out.is_empty() {
+        true => String::new(),
+        false => out.join("\n") + "\n",
+    }
+}
+
+/// If a trivia spans a blank line
+fn is_blank_line(trivia: &Trivia) -> bool {
+    trivia.kind == TriviaKind::Whitespace && trivia.text.matches('\n').count() > 1
+}
+
+/// The number of trivia before the first line break
+fn same_line_len(trivia: &[Trivia]) -> usize {
+    trivia
+        .iter()
+        .position(|t| t.kind == TriviaKind::Whitespace && t.text.contains('\n'))
+        .unwrap_or(trivia.len())
+}
+
+/// The text of the comments, and source the lexer rejected, of a list of trivia
+fn comments(trivia: &[Trivia]) -> Vec<&str> {
+    trivia.iter().filter(|t| t.kind != TriviaKind::Whitespace).map(|t| t.text.trim()).collect()
+}
+
+/// The top-level lines of a list of trivia, each comment on its own line
+fn trivia_lines(trivia: &[Trivia]) -> Vec<String> {
+    let mut lines = vec![];
+    for t in trivia {
+        match t.kind {
+            TriviaKind::Whitespace if is_blank_line(t) => lines.push(String::new()),
+            TriviaKind::Whitespace => {}
+            _ => lines.push(t.text.trim().to_string()),
+        }
+    }
+    lines
+}
+
+/// Formats a definition, followed by the comments of its last line
+fn format_entry(entry: &Entry) -> Vec<String> {
+    let mut lines = format_node(entry.node);
+    if let Some(last) = lines.last_mut() {
+        entry.trailing.iter().for_each(|c| *last = format!("{} {}", last, c));
+    }
+    lines
+}
+
+/// Formats a definition, without its leading trivia
+fn format_node(node: &SyntaxNode) -> Vec<String> {
+    // The leading trivia of the first token is formatted with the definitions
+    let tokens = node.tokens();
+    let mut trivia = tokens.iter().skip(1).flat_map(|t| t.leading.iter());
+    if node.kind == NodeKind::Unknown || trivia.any(|t| t.kind == TriviaKind::Skipped) {
+        return verbatim(node)
+    }
+
+    let (header, body) = match node.kind {
+        NodeKind::Macro |
+        NodeKind::Fallback |
+        NodeKind::Receive |
+        NodeKind::Test |
+        NodeKind::Table => match node.elements.split_last() {
+            Some((SyntaxElement::Group(g), header))
+                if g.delimiter == Delimiter::Brace && g.close.is_some() =>
+            {
+                (header, Some(g))
+            }
+            _ => return verbatim(node),
+        },
+        _ => (node.elements.as_slice(), None),
+    };
+
+    // Comments inside the header have no canonical place
+    let mut header_tokens = vec![];
+    collect_tokens(header, &mut header_tokens);
+    if header_tokens
+        .iter()
+        .skip(1)
+        .flat_map(|t| t.leading.iter())
+        .any(|t| t.kind != TriviaKind::Whitespace)
+    {
+        return verbatim(node)
+    }
+    let abi = matches!(node.kind, NodeKind::Function | NodeKind::Event | NodeKind::Error);
+    let pieces = header_tokens
+        .iter()
+        .enumerate()
+        .filter(|(i, t)| {
+            t.kind != TokenKind::Comma ||
+                header_tokens.get(i + 1).map(|n| &n.kind) != Some(&TokenKind::CloseParen)
+        })
+        .map(|(_, t)| Piece::Code(t))
+        .collect::<Vec<Piece>>();
+    let mut lines = vec![join(&pieces, abi)];
+
+    let body = match body {
+        Some(b) => format_body(b),
+        None => return lines,
+    };
+    let opening = if body.is_empty() { " {}" } else { " {" };
+    if node.kind == NodeKind::Macro &&
+        lines[0].chars().count() + opening.len() > MAX_SIGNATURE_WIDTH
+    {
+        if let Some(wrapped) = wrap_signature(header) {
+            lines = wrapped;
+        }
+    }
+    if let Some(last) = lines.last_mut() {
+        last.push_str(opening);
+    }
+    if !body.is_empty() {
+        lines.extend(body);
+        lines.push("}".to_string());
+    }
+    lines
+}
+
+/// The source of a definition as written, without its leading trivia
+fn verbatim(node: &SyntaxNode) -> Vec<String> {
+    let source = node.to_string();
+    let leading = node
+        .tokens()
+        .first()
+        .map(|t| t.leading.iter().map(|l| l.text.len()).sum())
+        .unwrap_or_default();
+    vec![source.get(leading..).unwrap_or(&source).trim().to_string()]
+}
+
+/// Wraps the parameters of a macro signature, one per line
+fn wrap_signature(header: &[SyntaxElement]) -> Option<Vec<String>> {
+    let index = header
+        .iter()
+        .position(|e| matches!(e, SyntaxElement::Group(g) if g.delimiter == Delimiter::Paren))?;
+    let params = match &header[index] {
+        SyntaxElement::Group(g) if !g.elements.is_empty() && g.close.is_some() => g,
+        _ => return None,
+    };
+
+    let join_elements = |elements: &[SyntaxElement]| {
+        let mut tokens = vec![];
+        collect_tokens(elements, &mut tokens);
+        join(&tokens.into_iter().map(Piece::Code).collect::<Vec<Piece>>(), false)
+    };
+    let mut lines = vec![format!("{}(", join_elements(&header[..index]))];
+    let mut tokens = vec![];
+    collect_tokens(&params.elements, &mut tokens);
+    let params =
+        tokens.split(|t| t.kind == TokenKind::Comma).filter(|p| !p.is_empty()).collect::<Vec<_>>();
+    for (i, param) in params.iter().enumerate() {
+        let param = join(&param.iter().map(|t| Piece::Code(t)).collect::<Vec<Piece>>(), false);
+        let separator = if i + 1 < params.len() { "," } else { "" };
+        lines.push(format!("{}{}{}", INDENT, param, separator));
+    }
+    lines.push(format!(") {}", join_elements(&header[index + 1..])));
+    Some(lines)
+}
+
+/// Formats the lines of a body, without its delimiters
+fn format_body(body: &SyntaxGroup) -> Vec<String> {
+    let mut tokens = vec![];
+    collect_tokens(&body.elements, &mut tokens);
+
+    // Split the tokens into lines where the source breaks them, the closing delimiter only
+    // contributing its leading comments
+    let mut lines: Vec<BodyLine> = vec![];
+    let mut pieces = vec![];
+    let mut blank = false;
+    let code = tokens.iter().map(|t| (*t, true));
+    for (token, is_code) in code.chain(body.close.iter().map(|t| (t, false))) {
+        for trivia in token.leading.iter() {
+            match trivia.kind {
+                TriviaKind::Whitespace if trivia.text.contains('\n') => {
+                    if !pieces.is_empty() {
+                        lines.push(BodyLine { pieces: std::mem::take(&mut pieces), blank });
+                        blank = false;
+                    }
+                    blank |= is_blank_line(trivia) && !lines.is_empty();
+                }
+                TriviaKind::Whitespace => {}
+                _ => pieces.push(Piece::Comment(trivia.text.trim_end())),
+            }
+        }
+        if is_code {
+            pieces.push(Piece::Code(token));
+        }
+    }
+    if !pieces.is_empty() {
+        lines.push(BodyLine { pieces, blank });
+    }
+
+    // Statements are indented once, once more after a label and inside each block like
+    // `__REPEAT`; comments take the level of the code they precede
+    let mut levels = vec![];
+    let mut in_label = false;
+    let mut depth = 0usize;
+    for line in lines.iter() {
+        let code = line
+            .pieces
+            .iter()
+            .filter_map(|p| match p {
+                Piece::Code(t) => Some(&t.kind),
+                Piece::Comment(_) => None,
+            })
+            .collect::<Vec<&TokenKind>>();
+        let closing = matches!(code.first(), Some(TokenKind::CloseBrace)) as usize;
+        levels.push(match code.first() {
+            Some(TokenKind::Label(_)) => {
+                in_label = true;
+                Some(1 + depth)
+            }
+            Some(_) => Some(1 + in_label as usize + depth.saturating_sub(closing)),
+            None => None,
+        });
+        let opened = code.iter().filter(|k| matches!(k, TokenKind::OpenBrace)).count();
+        let closed = code.iter().filter(|k| matches!(k, TokenKind::CloseBrace)).count();
+        depth = (depth + opened).saturating_sub(closed);
+    }
+    let mut next = 1 + in_label as usize;
+    let levels = levels
+        .into_iter()
+        .rev()
+        .map(|level| {
+            next = level.unwrap_or(next);
+            next
+        })
+        .collect::<Vec<usize>>()
+        .into_iter()
+        .rev();
+
+    // Render each line as its code and trailing comment
+    let rendered = lines
+        .iter()
+        .zip(levels)
+        .map(|(line, level)| {
+            let indent = INDENT.repeat(level);
+            let split = line
+                .pieces
+                .iter()
+                .rposition(|p| matches!(p, Piece::Code(_)))
+                .map(|i| i + 1)
+                .unwrap_or_default();
+            let code = format!("{}{}", indent, join(&line.pieces[..split], false));
+            let comment = join(&line.pieces[split..], false);
+            match split {
+                0 => (format!("{}{}", indent, comment), String::new(), line.blank),
+                _ => (code, comment, line.blank),
+            }
+        })
+        .collect::<Vec<(String, String, bool)>>();
+
+    // Trailing comments are aligned within each run of lines not separated by a blank line
+    let mut out = vec![];
+    for run in split_runs(&rendered) {
+        let column = run
+            .iter()
+            .filter(|(_, comment, _)| !comment.is_empty())
+            .map(|(code, _, _)| code.chars().count() + COMMENT_GAP)
+            .max()
+            .unwrap_or_default();
+        for (code, comment, blank) in run.iter() {
+            if *blank {
+                out.push(String::new());
+            }
+            match comment.is_empty() {
+                true => out.push(code.clone()),
+                false => out.push(format!("{:width$}{}", code, comment, width = column)),
+            }
+        }
+    }
+    out
+}
+
+/// Splits rendered lines into runs starting at each blank line
+fn split_runs(lines: &[(String, String, bool)]) -> Vec<&[(String, String, bool)]> {
+    let mut runs = vec![];
+    let mut start = 0;
+    for (i, (_, _, blank)) in lines.iter().enumerate() {
+        if *blank && i > start {
+            runs.push(&lines[start..i]);
+            start = i;
+        }
+    }
+    if start < lines.len() {
+        runs.push(&lines[start..]);
+    }
+    runs
+}
+
+/// Joins the pieces of a line, spacing them by the kinds of their tokens
+///
+/// ABI signatures don't space their parameters, like the signatures they're hashed from.
+fn join(pieces: &[Piece], abi: bool) -> String {
+    // Angle brackets wrap arg calls, like `<value>`, but compare values in assertions
+    let kind = |i: usize| match pieces.get(i) {
+        Some(Piece::Code(t)) => Some(&t.kind),
+        _ => None,
+    };
+    let wraps = |i: usize| match kind(i) {
+        Some(TokenKind::LeftAngle) => kind(i + 2) == Some(&TokenKind::RightAngle),
+        Some(TokenKind::RightAngle) => i >= 2 && kind(i - 2) == Some(&TokenKind::LeftAngle),
+        _ => false,
+    };
+    let mut out = String::new();
+    for (i, piece) in pieces.iter().enumerate() {
+        if i > 0 && spaced(&pieces[i - 1], piece, abi, (wraps(i - 1), wraps(i))) {
+            out.push(' ');
+        }
+        out.push_str(piece.text());
+    }
+    out
+}
+
+/// If two adjacent pieces are separated by a space, given whether their angle brackets wrap an
+/// arg call
+fn spaced(previous: &Piece, next: &Piece, abi: bool, wraps: (bool, bool)) -> bool {
+    let (previous, next) = match (previous, next) {
+        (Piece::Code(p), Piece::Code(n)) => (p, n),
+        _ => return true,
+    };
+    // The size of a storage reservation follows the parenthesis lexed with its keyword
+    if previous.kind == TokenKind::FreeStoragePointer && previous.text.ends_with('(') {
+        return false
+    }
+    match (&previous.kind, &next.kind) {
+        (TokenKind::OpenParen | TokenKind::OpenBracket | TokenKind::Not, _) => false,
+        // The sizes of an array of tuples
+        (TokenKind::CloseParen, TokenKind::OpenBracket) if abi => false,
+        (TokenKind::LeftAngle, _) if wraps.0 => false,
+        (
+            _,
+            TokenKind::CloseParen | TokenKind::CloseBracket | TokenKind::Comma | TokenKind::Colon,
+        ) => false,
+        (_, TokenKind::RightAngle) if wraps.1 => false,
+        (TokenKind::Takes | TokenKind::Returns, TokenKind::OpenParen) => true,
+        (
+            TokenKind::Add |
+            TokenKind::Sub |
+            TokenKind::Mul |
+            TokenKind::Div |
+            TokenKind::Equal |
+            TokenKind::NotEqual |
+            TokenKind::LeftAngle |
+            TokenKind::RightAngle |
+            TokenKind::LessEqual |
+            TokenKind::GreaterEqual |
+            TokenKind::And |
+            TokenKind::Or,
+            TokenKind::OpenParen,
+        ) => true,
+        (_, TokenKind::OpenParen) => false,
+        (TokenKind::Comma, _) => !abi,
+        _ => true,
+    }
+}
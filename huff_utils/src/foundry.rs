@@ -0,0 +1,97 @@
+//! ## Foundry
+//!
+//! Artifacts in the shape forge expects, written to the `out/` directory of a Foundry project
+//! with `--out`, or with `--artifact-format foundry`.
+//!
+//! Like forge, each artifact is written to `<OUTPUT>/<FILE NAME>/<CONTRACT NAME>.json`, eg.
+//! `out/Counter.huff/Counter.json`, so `vm.getCode("Counter.huff:Counter")` and
+//! `deployCode("Counter.huff")` find it. The abi is the Solidity JSON ABI, and the bytecode
+//! objects are 0x prefixed.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+use crate::{
+    artifact::{Artifact, ImmutableReference},
+    link::{link_references_by_source, LinkReferences},
+};
+
+/// An artifact in the shape forge expects
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FoundryArtifact {
+    /// The Solidity JSON ABI
+    pub abi: Vec<serde_json::Value>,
+    /// The creation bytecode
+    pub bytecode: FoundryBytecode,
+    /// The runtime bytecode
+    pub deployed_bytecode: FoundryDeployedBytecode,
+    /// The selectors of the functions, by signature
+    pub method_identifiers: BTreeMap<String, String>,
+}
+
+/// A bytecode object of a forge artifact
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FoundryBytecode {
+    /// The 0x prefixed bytecode
+    pub object: String,
+    /// The placeholders of the library addresses, by source and name
+    pub link_references: BTreeMap<String, LinkReferences>,
+}
+
+/// The runtime bytecode object of a forge artifact
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FoundryDeployedBytecode {
+    /// The 0x prefixed bytecode
+    pub object: String,
+    /// The placeholders of the library addresses, by source and name
+    pub link_references: BTreeMap<String, LinkReferences>,
+    /// The placeholders of the immutables, by immutable name
+    pub immutable_references: BTreeMap<String, Vec<ImmutableReference>>,
+}
+
+impl FoundryArtifact {
+    /// The path the artifact is exported to in an output directory
+    pub fn path(artifact: &Artifact, output: &str) -> String {
+        let file_name = Path::new(&artifact.file.path).file_name().unwrap_or_default();
+        format!("{}/{}/{}.json", output, file_name.to_string_lossy(), artifact.contract_name())
+    }
+
+    /// Exports the artifact to a json file
+    pub fn export(&self, out: &str) -> Result<(), std::io::Error> {
+        let serialized = serde_json::to_string_pretty(self)?;
+        let file_path = Path::new(out);
+        if let Some(p) = file_path.parent() {
+            fs::create_dir_all(p)?
+        }
+        fs::write(file_path, serialized)
+    }
+}
+
+impl From<&Artifact> for FoundryArtifact {
+    fn from(artifact: &Artifact) -> Self {
+        let source_name = artifact.source_name();
+        Self {
+            abi: artifact.abi.as_ref().map(|a| a.json_abi()).unwrap_or_default(),
+            bytecode: FoundryBytecode {
+                object: format!("0x{}", artifact.bytecode),
+                link_references: link_references_by_source(&source_name, &artifact.link_references),
+            },
+            deployed_bytecode: FoundryDeployedBytecode {
+                object: format!("0x{}", artifact.runtime),
+                link_references: link_references_by_source(
+                    &source_name,
+                    &artifact.runtime_link_references,
+                ),
+                immutable_references: artifact.immutable_references.clone(),
+            },
+            method_identifiers: artifact
+                .abi
+                .as_ref()
+                .map(|a| a.method_identifiers())
+                .unwrap_or_default(),
+        }
+    }
+}
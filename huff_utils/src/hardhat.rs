@@ -0,0 +1,82 @@
+//! ## Hardhat
+//!
+//! Artifacts in the shape Hardhat expects, written with `--artifact-format hardhat`.
+//!
+//! Like the `artifacts/` tree of Hardhat, each artifact is written to
+//! `<OUTPUT>/<SOURCE NAME>/<CONTRACT NAME>.json`, the contract named after its file stem. The abi
+//! is the Solidity JSON ABI, and the bytecode is 0x prefixed.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+use crate::{
+    artifact::Artifact,
+    link::{link_references_by_source, LinkReferences},
+};
+
+/// The format tag of Hardhat artifacts
+pub const HARDHAT_ARTIFACT_FORMAT: &str = "hh-sol-artifact-1";
+
+/// An artifact in the shape Hardhat expects
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HardhatArtifact {
+    /// The format tag, always `hh-sol-artifact-1`
+    #[serde(rename = "_format")]
+    pub format: String,
+    /// The contract name, the file stem of its source
+    pub contract_name: String,
+    /// The path of the source, relative to the project
+    pub source_name: String,
+    /// The Solidity JSON ABI
+    pub abi: Vec<serde_json::Value>,
+    /// The 0x prefixed creation bytecode
+    pub bytecode: String,
+    /// The 0x prefixed runtime bytecode
+    pub deployed_bytecode: String,
+    /// The placeholders of the library addresses in the creation bytecode, by source and name
+    pub link_references: BTreeMap<String, LinkReferences>,
+    /// The placeholders of the library addresses in the runtime bytecode, by source and name
+    pub deployed_link_references: BTreeMap<String, LinkReferences>,
+}
+
+impl HardhatArtifact {
+    /// The path the artifact is exported to in an output directory
+    pub fn path(artifact: &Artifact, output: &str) -> String {
+        format!(
+            "{}/{}/{}.json",
+            output,
+            artifact.source_name().trim_start_matches('/'),
+            artifact.contract_name()
+        )
+    }
+
+    /// Exports the artifact to a json file
+    pub fn export(&self, out: &str) -> Result<(), std::io::Error> {
+        let serialized = serde_json::to_string_pretty(self)?;
+        let file_path = Path::new(out);
+        if let Some(p) = file_path.parent() {
+            fs::create_dir_all(p)?
+        }
+        fs::write(file_path, serialized)
+    }
+}
+
+impl From<&Artifact> for HardhatArtifact {
+    fn from(artifact: &Artifact) -> Self {
+        let source_name = artifact.source_name();
+        Self {
+            format: HARDHAT_ARTIFACT_FORMAT.to_string(),
+            contract_name: artifact.contract_name(),
+            abi: artifact.abi.as_ref().map(|a| a.json_abi()).unwrap_or_default(),
+            bytecode: format!("0x{}", artifact.bytecode),
+            deployed_bytecode: format!("0x{}", artifact.runtime),
+            link_references: link_references_by_source(&source_name, &artifact.link_references),
+            deployed_link_references: link_references_by_source(
+                &source_name,
+                &artifact.runtime_link_references,
+            ),
+            source_name,
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! ## Interface
+//!
+//! The Solidity interface of a compiled contract, written by `huffc interface` so Solidity
+//! callers stay in sync with the Huff contract they call.
+//!
+//! The interface declares the events, custom errors and external functions of the contract's
+//! ABI, along with its `receive` and `fallback` entrypoints, the events and functions with the
+//! NatSpec of their doc comment. Tuple parameters are declared as structs, named `Tuple0`,
+//! `Tuple1`, ... in the order they appear.
+
+use std::{fs, path::Path};
+
+use crate::{
+    abi::FunctionParamType,
+    artifact::Artifact,
+    ast::FunctionType,
+    natspec::{DevDocEntry, UserDocEntry},
+};
+
+/// The generated Solidity interface of a contract
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SolidityInterface {
+    /// The name of the interface, the contract name prefixed with `I`
+    pub name: String,
+    /// The name of the file the interface is written to
+    pub file_name: String,
+    /// The source of the interface
+    pub source: String,
+}
+
+/// The structs declared for the tuple types of an interface, by type
+#[derive(Default)]
+struct Structs {
+    declared: Vec<(FunctionParamType, String)>,
+    declarations: Vec<String>,
+}
+
+impl Structs {
+    /// The Solidity type of a parameter, declaring the structs of its tuples
+    fn type_name(&mut self, kind: &FunctionParamType) -> String {
+        match kind {
+            FunctionParamType::Tuple(kinds) => {
+                if let Some((_, name)) = self.declared.iter().find(|(k, _)| k == kind) {
+                    return name.clone()
+                }
+                let fields = kinds
+                    .iter()
+                    .enumerate()
+                    .map(|(i, k)| format!("        {} field{};\n", self.type_name(k), i))
+                    .collect::<String>();
+                let name = format!("Tuple{}", self.declared.len());
+                self.declarations.push(format!("    struct {} {{\n{}    }}\n", name, fields));
+                self.declared.push((kind.clone(), name.clone()));
+                name
+            }
+            FunctionParamType::Array(inner, sizes) => {
+                let suffix = sizes
+                    .iter()
+                    .map(|size| match size {
+                        0 => "[]".to_string(),
+                        size => format!("[{}]", size),
+                    })
+                    .collect::<String>();
+                format!("{}{}", self.type_name(inner), suffix)
+            }
+            kind => kind.to_string(),
+        }
+    }
+
+    /// A function parameter, with the data location of reference types
+    fn param(&mut self, kind: &FunctionParamType, location: &str, name: &str) -> String {
+        let mut param = self.type_name(kind);
+        if matches!(
+            kind,
+            FunctionParamType::Bytes |
+                FunctionParamType::String |
+                FunctionParamType::Array(..) |
+                FunctionParamType::Tuple(_)
+        ) {
+            param = format!("{} {}", param, location);
+        }
+        if !name.is_empty() {
+            param = format!("{} {}", param, name);
+        }
+        param
+    }
+}
+
+/// The NatSpec lines of a definition, from its userdoc and devdoc entries
+fn natspec(user: Option<&UserDocEntry>, dev: Option<&DevDocEntry>) -> String {
+    let mut tags = vec![];
+    if let Some(user) = user {
+        tags.push(format!("@notice {}", user.notice));
+    }
+    if let Some(dev) = dev {
+        if let Some(details) = &dev.details {
+            tags.push(format!("@dev {}", details));
+        }
+        tags.extend(dev.params.iter().map(|(name, text)| format!("@param {} {}", name, text)));
+    }
+    tags.iter().flat_map(|t| t.lines()).map(|l| format!("    /// {}\n", l)).collect()
+}
+
+impl SolidityInterface {
+    /// Generates the Solidity interface of a compiled contract
+    pub fn new(artifact: &Artifact) -> Self {
+        let name = format!(
+            "I{}",
+            artifact
+                .contract_name()
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect::<String>()
+        );
+        let abi = artifact.abi.clone().unwrap_or_default();
+        let mut structs = Structs::default();
+        let mut members = vec![];
+
+        for event in abi.events.values() {
+            let params = event
+                .inputs
+                .iter()
+                .map(|p| {
+                    let indexed = if p.indexed { " indexed" } else { "" };
+                    let kind = format!("{}{}", structs.type_name(&p.kind), indexed);
+                    if p.name.is_empty() {
+                        kind
+                    } else {
+                        format!("{} {}", kind, p.name)
+                    }
+                })
+                .collect::<Vec<_>>();
+            let signature = event.signature();
+            members.push(format!(
+                "{}    event {}({}){};\n",
+                natspec(
+                    artifact.userdoc.events.get(&signature),
+                    artifact.devdoc.events.get(&signature)
+                ),
+                event.name,
+                params.join(", "),
+                if event.anonymous { " anonymous" } else { "" }
+            ));
+        }
+
+        for error in abi.errors.values() {
+            let params = error
+                .inputs
+                .iter()
+                .map(|p| {
+                    let kind = structs.type_name(&p.kind);
+                    if p.name.is_empty() {
+                        kind
+                    } else {
+                        format!("{} {}", kind, p.name)
+                    }
+                })
+                .collect::<Vec<_>>();
+            members.push(format!("    error {}({});\n", error.name, params.join(", ")));
+        }
+
+        for function in abi.functions.values() {
+            let inputs = function
+                .inputs
+                .iter()
+                .map(|p| structs.param(&p.kind, "calldata", &p.name))
+                .collect::<Vec<_>>();
+            let outputs = function
+                .outputs
+                .iter()
+                .map(|p| structs.param(&p.kind, "memory", &p.name))
+                .collect::<Vec<_>>();
+            let mutability = match &function.state_mutability {
+                FunctionType::NonPayable => String::new(),
+                mutability => format!(" {}", mutability),
+            };
+            let returns = if outputs.is_empty() {
+                String::new()
+            } else {
+                format!(" returns ({})", outputs.join(", "))
+            };
+            let signature = function.signature();
+            members.push(format!(
+                "{}    function {}({}) external{}{};\n",
+                natspec(
+                    artifact.userdoc.methods.get(&signature),
+                    artifact.devdoc.methods.get(&signature)
+                ),
+                function.name,
+                inputs.join(", "),
+                mutability,
+                returns
+            ));
+        }
+
+        if abi.receive {
+            members.push("    receive() external payable;\n".to_string());
+        }
+        if abi.fallback {
+            members.push("    fallback() external payable;\n".to_string());
+        }
+
+        let body = structs.declarations.into_iter().chain(members).collect::<Vec<_>>().join("\n");
+        let source = format!(
+            "// SPDX-License-Identifier: UNLICENSED\npragma solidity ^0.8.0;\n\n/// @title {}\n/// @notice The interface of the `{}` contract, generated by `huffc interface` from \"{}\".\ninterface {} {{\n{}}}\n",
+            name,
+            artifact.contract_name(),
+            artifact.file.path,
+            name,
+            body
+        );
+        Self { file_name: format!("{}.sol", name), name, source }
+    }
+
+    /// Exports the interface to a Solidity source file
+    pub fn export(&self, out: &str) -> Result<(), std::io::Error> {
+        let file_path = Path::new(out);
+        if let Some(p) = file_path.parent() {
+            fs::create_dir_all(p)?
+        }
+        fs::write(file_path, &self.source)
+    }
+}
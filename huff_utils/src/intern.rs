@@ -0,0 +1,192 @@
+//! ## Interning
+//!
+//! Identifiers interned once, as they are parsed, so the AST and code generation copy and compare
+//! ids instead of cloning `String`s.
+//!
+//! A [SymbolId] is the address of its name in a process-wide interner, so it resolves to its name
+//! and compares without taking any lock. Only interning a name reads the interner, and only a name
+//! seen for the first time writes to it. Ids are ordered by their names, so maps keyed by ids
+//! iterate the same way on every compile.
+//!
+//! Names are never freed, so ids stay valid across the compiles of a long-running process like
+//! the language server. The interner is bounded by the distinct names the process parses, not by
+//! its compiles: recompiling a file interns only the names added to it, and looking a name up
+//! with [SymbolId::lookup] interns nothing. [SymbolId::interned] reports its size.
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    fmt::{self, Debug, Display},
+    hash::{Hash, Hasher},
+    ops::Deref,
+    ptr,
+    sync::RwLock,
+};
+
+lazy_static! {
+    /// The names interned so far
+    static ref INTERNER: RwLock<HashSet<&'static str>> = RwLock::new(HashSet::new());
+}
+
+/// An interned identifier, like a macro, constant, label or function name
+///
+/// Ids of the same name share the address of the interned name, compared instead of the name.
+#[derive(Clone, Copy)]
+pub struct SymbolId(&'static str);
+
+impl SymbolId {
+    /// Interns a name, returning the id of the name if it was interned before
+    pub fn intern(name: &str) -> Self {
+        if let Some(interned) = INTERNER.read().unwrap().get(name) {
+            return SymbolId(interned)
+        }
+        let mut interner = INTERNER.write().unwrap();
+        if let Some(interned) = interner.get(name) {
+            return SymbolId(interned)
+        }
+        let interned: &'static str = Box::leak(name.to_string().into_boxed_str());
+        interner.insert(interned);
+        SymbolId(interned)
+    }
+
+    /// The id of a name interned before, without interning it
+    ///
+    /// No definition is named after a name never interned, so lookups by name go through this
+    /// instead of [SymbolId::intern], leaving the interner untouched by misses.
+    pub fn lookup(name: &str) -> Option<Self> {
+        INTERNER.read().unwrap().get(name).map(|interned| SymbolId(interned))
+    }
+
+    /// The number of names interned so far
+    pub fn interned() -> usize {
+        INTERNER.read().unwrap().len()
+    }
+
+    /// The name the id was interned from
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl PartialEq for SymbolId {
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for SymbolId {}
+
+impl Hash for SymbolId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ptr::hash(self.0, state)
+    }
+}
+
+impl Default for SymbolId {
+    fn default() -> Self {
+        SymbolId::intern("")
+    }
+}
+
+impl From<&str> for SymbolId {
+    fn from(name: &str) -> Self {
+        SymbolId::intern(name)
+    }
+}
+
+impl From<String> for SymbolId {
+    fn from(name: String) -> Self {
+        SymbolId::intern(&name)
+    }
+}
+
+impl From<&String> for SymbolId {
+    fn from(name: &String) -> Self {
+        SymbolId::intern(name)
+    }
+}
+
+impl From<&SymbolId> for SymbolId {
+    fn from(id: &SymbolId) -> Self {
+        *id
+    }
+}
+
+impl PartialEq<str> for SymbolId {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SymbolId {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for SymbolId {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<SymbolId> for str {
+    fn eq(&self, other: &SymbolId) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<SymbolId> for String {
+    fn eq(&self, other: &SymbolId) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl Deref for SymbolId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialOrd for SymbolId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SymbolId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self == other {
+            return Ordering::Equal
+        }
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Display for SymbolId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Debug for SymbolId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl Serialize for SymbolId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for SymbolId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(SymbolId::from)
+    }
+}
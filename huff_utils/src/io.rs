@@ -14,6 +14,10 @@ pub enum UnpackError {
     InvalidDirectory(String),
     /// Missing File
     MissingFile(String),
+    /// Files including each other, from the first file of the cycle back to it
+    CircularInclude(Vec<String>),
+    /// A namespaced or artifact include, that can't be inlined into a flattened source
+    UnflattenableInclude(String),
 }
 
 /// Unpacks huff files into a vec of strings.
@@ -28,7 +32,7 @@ pub fn unpack_files(path: &str) -> Result<Vec<String>, UnpackError> {
         }
         None => {
             // We have a directory, try to extract huff files and parse
-            match std::fs::read_dir(&path) {
+            match std::fs::read_dir(path) {
                 Ok(files) => {
                     let input_files: Vec<String> =
                         files.map(|x| x.unwrap().path().to_str().unwrap().to_string()).collect();
@@ -9,12 +9,56 @@
 /// Abi Module
 pub mod abi;
 
+/// Abi Diff Module
+pub mod abi_diff;
+
+/// AST Analysis Module
+pub mod analysis;
+
 /// Artifact Module
 pub mod artifact;
 
+/// Macro Call Graph Module
+pub mod call_graph;
+
 /// AST Module
 pub mod ast;
 
+/// AST Arena Module
+pub mod arena;
+
+/// AST Visitor Module
+pub mod visit;
+
+/// Project Config Module
+pub mod config;
+
+/// Codegen Log Module
+pub mod codegen_log;
+
+/// Concrete Syntax Tree Module
+pub mod cst;
+
+/// Formatter Module
+pub mod formatter;
+
+/// Flattener Module
+pub mod flattener;
+/// Verification Bundle Module
+pub mod verification;
+/// Hardhat Artifacts Module
+pub mod hardhat;
+/// Foundry Artifacts Module
+pub mod foundry;
+/// Combined JSON Module
+pub mod combined_json;
+/// Bindings Module
+pub mod bindings;
+/// Solidity Interface Module
+pub mod interface;
+/// ABI Scaffold Module
+pub mod scaffold;
+
 /// Bytecode Traits Module
 pub mod bytecode;
 
@@ -24,15 +68,75 @@ pub mod token;
 /// Lexing Error Module
 pub mod error;
 
+/// Diagnostic Module
+pub mod diagnostic;
+
 /// EVM Module
 pub mod evm;
 
+/// EVM Version Module
+pub mod evm_version;
+
+/// Error Code Explanations Module
+pub mod explain;
+
 /// Files Module
 pub mod files;
 
+/// Bundled Modules Module
+pub mod bundled;
+
 /// Lexical Reporting Module
 pub mod report;
 
+/// Lint Levels Module
+pub mod lint;
+
+/// Deployment Transaction Module
+pub mod deployment;
+
+/// Data Contract Module
+pub mod data_contract;
+
+/// Symbol Manifest Module
+pub mod manifest;
+
+/// Panic Catalog Module
+pub mod panic;
+
+/// Cheatcode Catalog Module
+pub mod cheatcodes;
+
+/// NatSpec Module
+pub mod natspec;
+
+/// Documentation Module
+pub mod docs;
+
+/// Library Linking Module
+pub mod link;
+
+/// Well-Known Constants Module
+pub mod well_known;
+
+/// Symbol Table Module
+pub mod symbols;
+
+/// Identifier Interning Module
+pub mod intern;
+
+/// Compilation Statistics Module
+pub mod stats;
+
+/// Dispatcher Module
+pub mod dispatcher;
+
+/// Disassembly Module
+pub mod disassembly;
+
+/// Decompiler Module
+pub mod decompile;
+
 /// IO Module
 pub mod io;
 
@@ -44,8 +148,14 @@ pub mod bytes_util;
 
 /// Prelude wraps common utilities.
 pub mod prelude {
+    #[allow(ambiguous_glob_reexports)]
     pub use crate::{
-        abi::*, artifact::*, ast::*, bytecode::*, bytes_util::*, error::*, evm::*, files::*, io::*,
-        report::*, token::*, types::*,
+        abi::*, abi_diff::*, analysis::*, arena::*, artifact::*, ast::*, bindings::*, bundled::*,
+        bytecode::*, bytes_util::*, call_graph::*, cheatcodes::*, codegen_log::*, combined_json::*,
+        config::*, cst::*, data_contract::*, decompile::*, deployment::*, diagnostic::*,
+        disassembly::*, dispatcher::*, docs::*, error::*, evm::*, evm_version::*, explain::*,
+        files::*, formatter::*, foundry::*, hardhat::*, interface::*, intern::*, io::*, link::*,
+        lint::*, manifest::*, natspec::*, panic::*, report::*, scaffold::*, stats::*, symbols::*,
+        token::*, types::*, verification::*, visit::*, well_known::*,
     };
 }
@@ -0,0 +1,99 @@
+//! ## Link
+//!
+//! The link references of external libraries, and linking their addresses into bytecode.
+//!
+//! `__LINK(MyLib)` pushes the address of the `MyLib` library, unknown until it is deployed. Like
+//! solc, the 20 address bytes are left as the `__$<hash>$__` placeholder of the library, its
+//! name's keccak256 hash truncated to 34 hex characters. The bytecode can't be deployed until
+//! `huffc link` writes the library addresses over their placeholders.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A placeholder of a library address in bytecode
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct LinkReference {
+    /// The offset of the placeholder
+    pub start: usize,
+    /// The length of the placeholder, always 20 bytes
+    pub length: usize,
+}
+
+/// The placeholders of the libraries in bytecode, by library name
+pub type LinkReferences = BTreeMap<String, Vec<LinkReference>>;
+
+/// Keys link references by the source of their libraries, like solc
+///
+/// Huff links libraries by name alone, so they are keyed by the source of the contract linking
+/// them.
+pub fn link_references_by_source(
+    source_name: &str,
+    references: &LinkReferences,
+) -> BTreeMap<String, LinkReferences> {
+    match references.is_empty() {
+        true => BTreeMap::new(),
+        false => BTreeMap::from([(source_name.to_string(), references.clone())]),
+    }
+}
+
+/// The placeholder of a library address, 40 characters long like the hex address
+pub fn link_placeholder(library: &str) -> String {
+    let hash = hex::encode(ethers_core::utils::keccak256(library));
+    format!("__${}$__", &hash[..34])
+}
+
+/// The bytes generated in place of the placeholder of a library address, until the bytecode is
+/// hex encoded with [encode_linked]
+pub fn link_marker(library: &str) -> [u8; 20] {
+    let mut marker = [0u8; 20];
+    marker.copy_from_slice(&ethers_core::utils::keccak256(link_placeholder(library))[..20]);
+    marker
+}
+
+/// Hex encodes generated bytes, writing the placeholders of the libraries over their markers
+pub fn encode_linked<'a>(bytes: &[u8], libraries: impl Iterator<Item = &'a str>) -> String {
+    let markers = libraries.map(|l| (link_marker(l), link_placeholder(l))).collect::<Vec<_>>();
+    if markers.is_empty() {
+        return hex::encode(bytes)
+    }
+    let mut encoded = String::with_capacity(bytes.len() * 2);
+    let mut i = 0;
+    while i < bytes.len() {
+        match markers.iter().find(|(m, _)| bytes[i..].starts_with(m)) {
+            Some((marker, placeholder)) => {
+                encoded.push_str(placeholder);
+                i += marker.len();
+            }
+            None => {
+                encoded.push_str(&hex::encode([bytes[i]]));
+                i += 1;
+            }
+        }
+    }
+    encoded
+}
+
+/// Finds the placeholders of libraries in hex bytecode
+pub fn find_link_references<'a>(
+    bytecode: &str,
+    libraries: impl Iterator<Item = &'a str>,
+) -> LinkReferences {
+    let mut references = LinkReferences::new();
+    for library in libraries {
+        let starts = bytecode
+            .match_indices(&link_placeholder(library))
+            .map(|(i, _)| LinkReference { start: i / 2, length: 20 })
+            .collect::<Vec<_>>();
+        if !starts.is_empty() {
+            references.insert(library.to_string(), starts);
+        }
+    }
+    references
+}
+
+/// Writes library addresses, as 40 hex characters, over their placeholders in hex bytecode
+pub fn link_bytecode(bytecode: &str, addresses: &BTreeMap<String, String>) -> String {
+    addresses.iter().fold(bytecode.to_string(), |bytecode, (library, address)| {
+        bytecode.replace(&link_placeholder(library), &address.to_lowercase())
+    })
+}
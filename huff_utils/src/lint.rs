@@ -0,0 +1,123 @@
+use serde::Deserialize;
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use crate::error::{CompilerWarning, Fix, WarningKind};
+
+/// The lint group name matching every lint
+pub const ALL_LINTS: &str = "warnings";
+
+/// The lints with a default level other than [Warn](LintLevel::Warn), used unless their level
+/// is set explicitly. Magic selectors are often written on purpose, while stack errors break the
+/// contract at runtime.
+pub const DEFAULT_LEVELS: [(&str, LintLevel); 3] = [
+    ("magic-selector", LintLevel::Allow),
+    ("stack-underflow", LintLevel::Deny),
+    ("stack-mismatch", LintLevel::Deny),
+];
+
+/// The level a lint is reported at
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// Silently ignore the lint
+    Allow,
+    /// Report the lint as a warning
+    #[default]
+    Warn,
+    /// Report the lint as an error, failing compilation
+    Deny,
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LintLevel::Allow => write!(f, "allow"),
+            LintLevel::Warn => write!(f, "warn"),
+            LintLevel::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+/// Lint levels keyed by lint name
+///
+/// Lints without a level fall back to the level of the [ALL_LINTS] group, then to
+/// [Warn](LintLevel::Warn). The [DEFAULT_LEVELS] lints ignore the group, and keep their default
+/// level unless their own level is set.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LintLevels(pub BTreeMap<String, LintLevel>);
+
+impl LintLevels {
+    /// Sets the level of a lint, or of every lint using the [ALL_LINTS] group
+    ///
+    /// Errors with the lint name if it is unknown.
+    pub fn set(&mut self, lint: &str, level: LintLevel) -> Result<(), String> {
+        if lint != ALL_LINTS && !WarningKind::LINTS.contains(&lint) {
+            return Err(lint.to_string())
+        }
+        self.0.insert(lint.to_string(), level);
+        Ok(())
+    }
+
+    /// The level of a lint
+    pub fn level(&self, lint: &str) -> LintLevel {
+        if let Some((_, default)) = DEFAULT_LEVELS.iter().find(|(name, _)| *name == lint) {
+            return self.0.get(lint).copied().unwrap_or(*default)
+        }
+        self.0.get(lint).or_else(|| self.0.get(ALL_LINTS)).copied().unwrap_or_default()
+    }
+
+    /// Splits warnings into the reported and the denied warnings, dropping allowed ones
+    pub fn apply(
+        &self,
+        warnings: Vec<CompilerWarning>,
+    ) -> (Vec<CompilerWarning>, Vec<CompilerWarning>) {
+        let mut reported = vec![];
+        let mut denied = vec![];
+        for w in warnings {
+            match self.level(w.kind.lint()) {
+                LintLevel::Allow => {}
+                LintLevel::Warn => reported.push(w),
+                LintLevel::Deny => denied.push(w),
+            }
+        }
+        (reported, denied)
+    }
+}
+
+impl FromStr for LintLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(LintLevel::Allow),
+            "warn" => Ok(LintLevel::Warn),
+            "deny" => Ok(LintLevel::Deny),
+            _ => Err(s.to_string()),
+        }
+    }
+}
+
+/// Applies fixes to the source of a file, returning the fixed source
+///
+/// Fix spans are character offsets into the source. A fix overlapping an earlier one is skipped,
+/// so applying the fixes again fixes what is left.
+pub fn apply_fixes(source: &str, fixes: &[&Fix]) -> String {
+    let mut fixes = fixes.to_vec();
+    fixes.sort_by_key(|f| (f.span.start, f.span.end));
+    fixes.dedup();
+
+    let offsets = source.char_indices().map(|(i, _)| i).chain([source.len()]).collect::<Vec<_>>();
+    let mut fixed = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for fix in fixes {
+        let (start, end) = match (offsets.get(fix.span.start), offsets.get(fix.span.end)) {
+            (Some(start), Some(end)) if *start >= cursor && start <= end => (*start, *end),
+            _ => continue,
+        };
+        fixed.push_str(&source[cursor..start]);
+        fixed.push_str(&fix.replacement);
+        cursor = end;
+    }
+    fixed.push_str(&source[cursor..]);
+    fixed
+}
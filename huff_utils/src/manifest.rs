@@ -0,0 +1,240 @@
+//! ## Manifest
+//!
+//! Machine-readable manifests of the public symbols of a library, generated by `huffc package`.
+//!
+//! Consumers pin the manifests of the libraries they include in their `huff.toml`:
+//!
+//! ```toml
+//! manifests = ["lib/Ownable.manifest.json"]
+//! ```
+//!
+//! The compiler then checks every library symbol the consumer references against its pinned
+//! manifest, so a breaking library change fails compilation instead of misbehaving at runtime.
+
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+use crate::{
+    ast::{AstSpan, ConstVal, Contract, MacroArg, Statement, StatementType},
+    bytes_util::bytes32_to_string,
+    error::{CodegenError, CodegenErrorKind},
+};
+
+/// The start of the value of the storage slots that weren't derived when packaging
+const FREE_STORAGE_POINTER: &str = "FREE_STORAGE_POINTER(";
+
+/// The public symbols of a library
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct SymbolManifest {
+    /// The library name, the file stem of its entrypoint
+    pub name: String,
+    /// The macros, with their stack effects
+    pub macros: Vec<MacroSignature>,
+    /// The constants defined with a literal, and their values
+    pub constants: Vec<ConstantSignature>,
+    /// The constants defined with `FREE_STORAGE_POINTER()`, and their storage slots
+    ///
+    /// Slots are derived in the order the `MAIN` and `CONSTRUCTOR` macros use them, so they are
+    /// only pinned if the library defines those macros.
+    pub storage: Vec<ConstantSignature>,
+}
+
+/// The signature of a library macro
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct MacroSignature {
+    /// The macro name
+    pub name: String,
+    /// The parameter names
+    pub parameters: Vec<String>,
+    /// The number of stack items taken
+    pub takes: usize,
+    /// The number of stack items returned
+    pub returns: usize,
+}
+
+impl std::fmt::Display for MacroSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}({}) = takes ({}) returns ({})",
+            self.name,
+            self.parameters.join(", "),
+            self.takes,
+            self.returns
+        )
+    }
+}
+
+/// The value of a library constant
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConstantSignature {
+    /// The constant name
+    pub name: String,
+    /// The hex value of the constant, or its derived storage slot
+    pub value: String,
+}
+
+impl SymbolManifest {
+    /// Builds the manifest of a library from its contract
+    ///
+    /// The contract must not have its storage pointers derived yet, to tell storage slots apart
+    /// from literal constants. Internal macros and constants aren't part of the public surface
+    /// and are left out, but the storage slots of internal constants are kept, the layout being
+    /// shared with the consumers.
+    pub fn new(name: &str, contract: &Contract) -> Self {
+        let mut derived = contract.clone();
+        derived.derive_storage_pointers();
+
+        let mut manifest = Self { name: name.to_string(), ..Default::default() };
+        manifest.macros = contract
+            .macros
+            .iter()
+            .filter(|m| !m.internal)
+            .map(|m| MacroSignature {
+                name: m.name.to_string(),
+                parameters: m.parameters.iter().filter_map(|p| p.name.clone()).collect(),
+                takes: m.takes,
+                returns: m.returns,
+            })
+            .collect();
+        for (constant, derived) in contract.constants.iter().zip(derived.constants.iter()) {
+            let signature = ConstantSignature {
+                name: constant.name.to_string(),
+                value: constant_value(&derived.value),
+            };
+            match constant.value {
+                ConstVal::Literal(_) if constant.internal => {}
+                ConstVal::Literal(_) => manifest.constants.push(signature),
+                ConstVal::FreeStoragePointer(_) => manifest.storage.push(signature),
+            }
+        }
+        manifest
+    }
+
+    /// Exports the manifest to a json file
+    pub fn export(&self, out: &str) -> Result<(), std::io::Error> {
+        let serialized = serde_json::to_string_pretty(self)?;
+        let file_path = Path::new(out);
+        if let Some(p) = file_path.parent() {
+            fs::create_dir_all(p)?
+        }
+        fs::write(file_path, serialized)
+    }
+
+    /// Imports a manifest from a json file
+    pub fn import(path: &str) -> Result<Self, std::io::Error> {
+        let serialized = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&serialized)?)
+    }
+
+    /// Checks the library symbols referenced by a consumer contract against the manifest
+    ///
+    /// The macros listed in the manifest belong to the library, so only the references of the
+    /// other macros are checked. The contract must have its storage pointers derived. Returns an
+    /// error at the first reference to each symbol that was removed or changed.
+    pub fn check(&self, contract: &Contract) -> Vec<CodegenError> {
+        let mut macros: Vec<(&str, &AstSpan)> = vec![];
+        let mut constants: Vec<(&str, &AstSpan)> = vec![];
+        contract
+            .macros
+            .iter()
+            .filter(|m| !self.macros.iter().any(|s| s.name == m.name))
+            .for_each(|m| collect_references(&m.statements, &mut macros, &mut constants));
+
+        let mut errors: Vec<CodegenError> = vec![];
+        let mut reported: Vec<&str> = vec![];
+        for (name, span) in macros {
+            let signature = match self.macros.iter().find(|s| s.name == name) {
+                Some(s) if !reported.contains(&name) => s,
+                _ => continue,
+            };
+            let current = contract.find_macro_by_name(name).map(|m| MacroSignature {
+                name: m.name.to_string(),
+                parameters: m.parameters.iter().filter_map(|p| p.name.clone()).collect(),
+                takes: m.takes,
+                returns: m.returns,
+            });
+            let help = match current {
+                Some(c) if &c == signature => continue,
+                Some(c) => format!("packaged as \"{}\", now defined as \"{}\"", signature, c),
+                None => format!("packaged as \"{}\", now removed", signature),
+            };
+            reported.push(name);
+            errors.push(self.mismatch(name, span, help));
+        }
+        for (name, span) in constants {
+            let signature =
+                match self.constants.iter().chain(self.storage.iter()).find(|s| s.name == name) {
+                    Some(s) if !reported.contains(&name) => s,
+                    _ => continue,
+                };
+            let current = contract.constants.iter().find(|c| c.name == name);
+            let help = match current.map(|c| constant_value(&c.value)) {
+                Some(v)
+                    if v == signature.value ||
+                        signature.value.starts_with(FREE_STORAGE_POINTER) =>
+                {
+                    continue
+                }
+                Some(v) => format!("packaged with value {}, now {}", signature.value, v),
+                None => format!("packaged with value {}, now removed", signature.value),
+            };
+            reported.push(name);
+            errors.push(self.mismatch(name, span, help));
+        }
+        errors
+    }
+
+    /// A mismatch of the symbol referenced at a span
+    fn mismatch(&self, name: &str, span: &AstSpan, help: String) -> CodegenError {
+        tracing::error!(target: "manifest", "\"{}\" OF LIBRARY \"{}\" DOES NOT MATCH ITS MANIFEST", name, self.name);
+        CodegenError {
+            kind: CodegenErrorKind::ManifestMismatch(name.to_string()),
+            span: span.clone(),
+            token: None,
+            help: Some(format!("\"{}\" of library \"{}\" was {}", name, self.name, help)),
+        }
+    }
+}
+
+/// The hex value of a constant
+fn constant_value(value: &ConstVal) -> String {
+    match value {
+        ConstVal::Literal(l) => bytes32_to_string(l, true),
+        ConstVal::FreeStoragePointer(fsp) => fsp.to_string(),
+    }
+}
+
+/// Recursively collects the macros and constants referenced by a list of statements, with the
+/// spans referencing them.
+///
+/// Identifiers that may resolve to a constant, like arg calls, are collected as constants.
+fn collect_references<'a>(
+    statements: &'a [Statement],
+    macros: &mut Vec<(&'a str, &'a AstSpan)>,
+    constants: &mut Vec<(&'a str, &'a AstSpan)>,
+) {
+    for statement in statements {
+        match &statement.ty {
+            StatementType::MacroInvocation(mi) => {
+                macros.push((&mi.macro_name, &mi.span));
+                mi.args.iter().for_each(|arg| match arg {
+                    MacroArg::ArgCall(name) | MacroArg::Ident(name) => {
+                        constants.push((name, &mi.span))
+                    }
+                    MacroArg::Constant(name) => constants.push((name, &mi.span)),
+                    _ => {}
+                });
+            }
+            StatementType::BuiltinFunctionCall(bf) => {
+                bf.args.iter().filter_map(|a| a.name.as_deref()).for_each(|name| {
+                    macros.push((name, &statement.span));
+                });
+            }
+            StatementType::Constant(name) => constants.push((name, &statement.span)),
+            StatementType::ArgCall(name) => constants.push((name, &statement.span)),
+            StatementType::Label(label) => collect_references(&label.inner, macros, constants),
+            _ => {}
+        }
+    }
+}
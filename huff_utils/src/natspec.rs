@@ -0,0 +1,256 @@
+//! ## NatSpec
+//!
+//! The NatSpec doc comments documenting functions, events and macros, and the solc-compatible
+//! `devdoc` and `userdoc` sections of the artifact they're emitted into.
+//!
+//! A doc comment is made of the `///` lines, or the `/** */` block, right above a `#define`. Its
+//! `@notice` tag describes the definition to users, its `@dev` tag to developers, and each
+//! `@param` tag a parameter, by name. Lines without a tag continue the previous tag, and a comment
+//! starting without a tag is a notice, like solc does. Other tags are ignored.
+//!
+//! The devdoc and userdoc document methods and events by their canonical signature, like solc.
+//! The macros are documented by name in an additional `macros` section, left out when empty.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{abi::Abi, symbols::SymbolKind};
+
+/// The doc comment of a definition
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NatSpec {
+    /// The `@notice` for users
+    pub notice: Option<String>,
+    /// The `@dev` details for developers
+    pub dev: Option<String>,
+    /// The `@param` descriptions, by parameter name
+    pub params: BTreeMap<String, String>,
+}
+
+/// The doc comments of a contract, by kind and name of the documented definition
+pub type NatSpecs = BTreeMap<(SymbolKind, String), NatSpec>;
+
+/// Serializes [NatSpecs] as a list of `[kind, name, natspec]` entries, as json object keys must
+/// be strings
+pub(crate) mod entries {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{NatSpec, NatSpecs, SymbolKind};
+
+    pub fn serialize<S: Serializer>(natspecs: &NatSpecs, serializer: S) -> Result<S::Ok, S::Error> {
+        natspecs
+            .iter()
+            .map(|((kind, name), natspec)| (kind, name, natspec))
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NatSpecs, D::Error> {
+        let entries = Vec::<(SymbolKind, String, NatSpec)>::deserialize(deserializer)?;
+        Ok(entries.into_iter().map(|(kind, name, natspec)| ((kind, name), natspec)).collect())
+    }
+}
+
+/// The tag a line of a doc comment continues
+enum Tag {
+    Notice,
+    Dev,
+    Param(String),
+    Ignored,
+}
+
+impl NatSpec {
+    /// Parses the doc comment of the comment tokens above a definition
+    ///
+    /// `None` if none of them is a doc comment, starting with `///` or `/**`.
+    pub fn parse(comments: &[String]) -> Option<Self> {
+        let mut lines = vec![];
+        for comment in comments.iter() {
+            if let Some(line) = comment.strip_prefix("///") {
+                lines.push(line.trim().to_string());
+            } else if let Some(block) = comment.strip_prefix("/**") {
+                let block = block.strip_suffix("*/").unwrap_or(block);
+                lines.extend(
+                    block.lines().map(|l| l.trim().trim_start_matches('*').trim().to_string()),
+                );
+            }
+        }
+        if lines.is_empty() {
+            return None
+        }
+
+        let mut natspec = NatSpec::default();
+        let mut tag = Tag::Notice;
+        for line in lines.iter().filter(|l| !l.is_empty()) {
+            let text = match line.strip_prefix('@') {
+                Some(tagged) => {
+                    let (name, text) =
+                        tagged.split_once(char::is_whitespace).unwrap_or((tagged, ""));
+                    let text = text.trim();
+                    match name {
+                        "notice" => {
+                            tag = Tag::Notice;
+                            text
+                        }
+                        "dev" => {
+                            tag = Tag::Dev;
+                            text
+                        }
+                        "param" => {
+                            let (param, text) =
+                                text.split_once(char::is_whitespace).unwrap_or((text, ""));
+                            tag = Tag::Param(param.to_string());
+                            text.trim()
+                        }
+                        _ => {
+                            tag = Tag::Ignored;
+                            continue
+                        }
+                    }
+                }
+                None => line.as_str(),
+            };
+            let entry = match &tag {
+                Tag::Notice => natspec.notice.get_or_insert_with(String::new),
+                Tag::Dev => natspec.dev.get_or_insert_with(String::new),
+                Tag::Param(param) => natspec.params.entry(param.clone()).or_default(),
+                Tag::Ignored => continue,
+            };
+            if !entry.is_empty() && !text.is_empty() {
+                entry.push('\n');
+            }
+            entry.push_str(text);
+        }
+        Some(natspec)
+    }
+}
+
+/// The `userdoc` section of an artifact, the notices of the documented definitions
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UserDoc {
+    /// Always `user`
+    pub kind: String,
+    /// The methods, by signature
+    pub methods: BTreeMap<String, UserDocEntry>,
+    /// The events, by signature
+    pub events: BTreeMap<String, UserDocEntry>,
+    /// The macros, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub macros: BTreeMap<String, UserDocEntry>,
+    /// The version of the format
+    pub version: u32,
+}
+
+/// The documentation of a definition for users
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UserDocEntry {
+    /// The notice
+    pub notice: String,
+}
+
+/// The `devdoc` section of an artifact, the details and parameters of the documented definitions
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DevDoc {
+    /// Always `dev`
+    pub kind: String,
+    /// The methods, by signature
+    pub methods: BTreeMap<String, DevDocEntry>,
+    /// The events, by signature
+    pub events: BTreeMap<String, DevDocEntry>,
+    /// The macros, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub macros: BTreeMap<String, DevDocEntry>,
+    /// The version of the format
+    pub version: u32,
+}
+
+/// The documentation of a definition for developers
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DevDocEntry {
+    /// The details
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// The descriptions of the parameters, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+}
+
+impl Default for UserDoc {
+    fn default() -> Self {
+        Self {
+            kind: "user".to_string(),
+            methods: BTreeMap::new(),
+            events: BTreeMap::new(),
+            macros: BTreeMap::new(),
+            version: 1,
+        }
+    }
+}
+
+impl Default for DevDoc {
+    fn default() -> Self {
+        Self {
+            kind: "dev".to_string(),
+            methods: BTreeMap::new(),
+            events: BTreeMap::new(),
+            macros: BTreeMap::new(),
+            version: 1,
+        }
+    }
+}
+
+/// The sections of a doc, by the kind of definitions they document
+type Sections<'a, T> = [(SymbolKind, &'a mut BTreeMap<String, T>); 3];
+
+/// Inserts the entry of a definition into its section, methods and events by the signature of
+/// their ABI definition and macros by name
+///
+/// Only functions and events of the ABI are documented.
+fn insert<T>(sections: Sections<T>, abi: &Abi, kind: SymbolKind, name: &str, entry: T) {
+    let key = match kind {
+        SymbolKind::Function => abi.functions.get(name).map(|f| f.signature()),
+        SymbolKind::Event => abi.events.get(name).map(|e| e.signature()),
+        _ => Some(name.to_string()),
+    };
+    if let (Some(key), Some((_, section))) = (key, sections.into_iter().find(|(k, _)| *k == kind)) {
+        section.insert(key, entry);
+    }
+}
+
+impl UserDoc {
+    /// The userdoc of the doc comments of a contract, with the notices they have
+    pub fn new(natspecs: &NatSpecs, abi: &Abi) -> Self {
+        let mut userdoc = UserDoc::default();
+        for ((kind, name), natspec) in natspecs.iter() {
+            let Some(notice) = natspec.notice.clone() else { continue };
+            let sections = [
+                (SymbolKind::Function, &mut userdoc.methods),
+                (SymbolKind::Event, &mut userdoc.events),
+                (SymbolKind::Macro, &mut userdoc.macros),
+            ];
+            insert(sections, abi, *kind, name, UserDocEntry { notice });
+        }
+        userdoc
+    }
+}
+
+impl DevDoc {
+    /// The devdoc of the doc comments of a contract, with the details and parameters they have
+    pub fn new(natspecs: &NatSpecs, abi: &Abi) -> Self {
+        let mut devdoc = DevDoc::default();
+        for ((kind, name), natspec) in natspecs.iter() {
+            if natspec.dev.is_none() && natspec.params.is_empty() {
+                continue
+            }
+            let entry =
+                DevDocEntry { details: natspec.dev.clone(), params: natspec.params.clone() };
+            let sections = [
+                (SymbolKind::Function, &mut devdoc.methods),
+                (SymbolKind::Event, &mut devdoc.events),
+                (SymbolKind::Macro, &mut devdoc.macros),
+            ];
+            insert(sections, abi, *kind, name, entry);
+        }
+        devdoc
+    }
+}
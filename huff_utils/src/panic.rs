@@ -0,0 +1,96 @@
+//! ## Panics
+//!
+//! The catalog of the panics compiler-generated code reverts with.
+//!
+//! Compiler-generated code, like the `NON_REENTRANT` reentrancy guard, reverts with the
+//! `Panic(uint256)` error solc uses, so existing decoders recognize it: the revert data is the
+//! [PANIC_SELECTOR] followed by the panic code, abi encoded. The codes start at `0x100`, above the
+//! codes solc reserves, so a Huff panic is never mistaken for a solc one. The panics a contract
+//! can revert with are recorded in its artifact, by code.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{
+    ast::{AstSpan, Statement, StatementType},
+    bytes_util::str_to_bytes32,
+    evm::Opcode,
+};
+
+/// The signature of the error compiler-generated code reverts with
+pub const PANIC_SIGNATURE: &str = "Panic(uint256)";
+
+/// The selector of [PANIC_SIGNATURE], starting the revert data of a panic
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// A panic compiler-generated code reverts with
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PanicCode {
+    /// A macro wrapped by `NON_REENTRANT` was entered while its lock was held
+    ReentrantCall,
+}
+
+impl PanicCode {
+    /// Every panic of the catalog
+    pub const ALL: [PanicCode; 1] = [PanicCode::ReentrantCall];
+
+    /// The code of the panic, following the selector in the revert data
+    pub fn code(&self) -> u64 {
+        match self {
+            PanicCode::ReentrantCall => 0x100,
+        }
+    }
+
+    /// The message off-chain decoders should translate the panic to
+    pub fn message(&self) -> &'static str {
+        match self {
+            PanicCode::ReentrantCall => "reentrant call to a NON_REENTRANT guarded macro",
+        }
+    }
+
+    /// Looks up the panic of a code
+    pub fn from_code(code: u64) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.code() == code)
+    }
+
+    /// Decodes the panic of revert data, `None` if the data isn't a panic of the catalog
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() != 36 || data[..4] != PANIC_SELECTOR {
+            return None
+        }
+        // Codes fit in the last 8 bytes of the word
+        if data[4..28].iter().any(|b| *b != 0) {
+            return None
+        }
+        let mut code = [0u8; 8];
+        code.copy_from_slice(&data[28..]);
+        Self::from_code(u64::from_be_bytes(code))
+    }
+
+    /// The statements reverting with the panic, spanned by the source generating them
+    ///
+    /// The selector is shifted into the first memory word, and the code stored in the next one.
+    pub fn revert_statements(&self, span: &AstSpan) -> Vec<Statement> {
+        let s = |ty: StatementType| Statement { ty, span: span.clone() };
+        let literal = |hex: &str| s(StatementType::Literal(str_to_bytes32(hex)));
+        vec![
+            literal(&hex::encode(PANIC_SELECTOR)),
+            literal("e0"),
+            s(StatementType::Opcode(Opcode::Shl)),
+            literal("00"),
+            s(StatementType::Opcode(Opcode::Mstore)),
+            literal(&format!("{:x}", self.code())),
+            literal("04"),
+            s(StatementType::Opcode(Opcode::Mstore)),
+            literal("24"),
+            literal("00"),
+            s(StatementType::Opcode(Opcode::Revert)),
+        ]
+    }
+}
+
+impl fmt::Display for PanicCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#06x}", self.code())
+    }
+}
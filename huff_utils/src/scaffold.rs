@@ -0,0 +1,209 @@
+//! ## Scaffold
+//!
+//! Generates the Huff skeleton of a contract from its Solidity JSON ABI, written by
+//! `huffc scaffold` as a starting point to reimplement an existing interface in Huff.
+//!
+//! Each function, event and error of the ABI is declared with `#define function`, `#define event`
+//! and `#define error`, and each function gets a macro stub reverting until implemented, named
+//! after the function in screaming snake case, jumped to from a dispatcher skeleton in `MAIN`
+//! comparing the selector of the calldata. The `receive` and `fallback` entrypoints of the ABI are
+//! stubbed with their `#define receive()` and `#define fallback()` definitions, wired into `MAIN`
+//! on compilation, and the constructor with a `CONSTRUCTOR` macro, its signature declared by the
+//! `CONSTRUCTOR` function.
+
+use ethers_core::abi::{Abi, Param, ParamType, StateMutability};
+
+use crate::{ast::to_screaming_snake_case, evm::OPCODES_MAP};
+
+/// The Huff skeleton of a Solidity JSON ABI
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scaffold {
+    /// The name of the scaffolded contract
+    pub name: String,
+    /// The parsed ABI
+    pub abi: Abi,
+}
+
+/// A function of the dispatcher skeleton
+struct Entrypoint {
+    /// The selector of the function
+    selector: [u8; 4],
+    /// The label jumped to on the selector
+    label: String,
+    /// The macro invoked at the label
+    macro_name: String,
+    /// The canonical signature of the function
+    signature: String,
+}
+
+/// Renders parameters as `<type> <name>, ...`
+fn params<'a>(params: impl Iterator<Item = (&'a ParamType, &'a str, bool)>) -> String {
+    let mut rendered = vec![];
+    for (kind, name, indexed) in params {
+        let mut param = kind.to_string();
+        if indexed {
+            param.push_str(" indexed");
+        }
+        if !name.is_empty() {
+            param = format!("{} {}", param, name);
+        }
+        rendered.push(param);
+    }
+    rendered.join(", ")
+}
+
+/// The canonical types of parameters, as in a signature
+fn types(params: &[Param]) -> String {
+    params.iter().map(|p| p.kind.to_string()).collect::<Vec<_>>().join(",")
+}
+
+impl Scaffold {
+    /// Parses the Solidity JSON ABI of a contract, either a list of entries or an artifact with
+    /// an `abi` field
+    pub fn parse(name: &str, json: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let entries = match value {
+            serde_json::Value::Object(mut artifact) => {
+                artifact.remove("abi").ok_or_else(|| "Missing \"abi\" field".to_string())?
+            }
+            entries => entries,
+        };
+        let abi = serde_json::from_value(entries).map_err(|e| e.to_string())?;
+        Ok(Self { name: name.to_string(), abi })
+    }
+
+    /// The functions of the dispatcher, by name then overload order
+    ///
+    /// Overloads have their label and macro suffixed with their selector, and labels lexed as
+    /// an opcode, like `balance` or `gas_price`, are prefixed with `fn_`.
+    fn entrypoints(&self) -> Vec<Entrypoint> {
+        self.abi
+            .functions
+            .values()
+            .flat_map(|overloads| {
+                overloads.iter().map(|f| {
+                    let selector = f.short_signature();
+                    let suffix = format!("_{}", hex::encode(selector));
+                    let overloaded = overloads.len() > 1;
+                    let mut label = f.name.clone();
+                    if overloaded {
+                        label.push_str(&suffix);
+                    }
+                    let word = label.split(|c: char| !c.is_alphanumeric()).next();
+                    if word.is_some_and(|w| OPCODES_MAP.contains_key(w)) {
+                        label = format!("fn_{}", label);
+                    }
+                    let mut macro_name = to_screaming_snake_case(&f.name);
+                    if overloaded {
+                        macro_name.push_str(&suffix.to_uppercase());
+                    }
+                    let signature = format!("{}({})", f.name, types(&f.inputs));
+                    Entrypoint { selector, label, macro_name, signature }
+                })
+            })
+            .collect()
+    }
+
+    /// Renders the Huff source of the skeleton
+    pub fn source(&self) -> String {
+        let mut sections = vec![format!(
+            "// Scaffolded from the ABI of {} by `huffc scaffold`.\n// Implement each macro, and \
+             order the dispatcher by how often the functions are called.",
+            self.name
+        )];
+
+        let functions = self
+            .abi
+            .functions()
+            .map(|f| {
+                let mutability = match f.state_mutability {
+                    StateMutability::Pure => "pure",
+                    StateMutability::View => "view",
+                    StateMutability::NonPayable => "nonpayable",
+                    StateMutability::Payable => "payable",
+                };
+                let inputs = params(f.inputs.iter().map(|p| (&p.kind, p.name.as_str(), false)));
+                let outputs = params(f.outputs.iter().map(|p| (&p.kind, "", false)));
+                format!(
+                    "#define function {}({}) {} returns ({})",
+                    f.name, inputs, mutability, outputs
+                )
+            })
+            .collect::<Vec<_>>();
+        if !functions.is_empty() {
+            sections.push(format!("// Functions\n{}", functions.join("\n")));
+        }
+
+        let events = self
+            .abi
+            .events()
+            .map(|e| {
+                let inputs =
+                    params(e.inputs.iter().map(|p| (&p.kind, p.name.as_str(), p.indexed)));
+                format!("#define event {}({})", e.name, inputs)
+            })
+            .collect::<Vec<_>>();
+        if !events.is_empty() {
+            sections.push(format!("// Events\n{}", events.join("\n")));
+        }
+
+        let errors = self
+            .abi
+            .errors()
+            .map(|e| {
+                let inputs = params(e.inputs.iter().map(|p| (&p.kind, p.name.as_str(), false)));
+                format!("#define error {}({})", e.name, inputs)
+            })
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            sections.push(format!("// Errors\n{}", errors.join("\n")));
+        }
+
+        if let Some(constructor) = &self.abi.constructor {
+            let inputs =
+                params(constructor.inputs.iter().map(|p| (&p.kind, p.name.as_str(), false)));
+            sections.push(format!(
+                "#define function CONSTRUCTOR({}) nonpayable returns ()\n#define macro \
+                 CONSTRUCTOR() = takes(0) returns(0) {{\n}}",
+                inputs
+            ));
+        }
+        let entrypoints = self.entrypoints();
+        for entrypoint in entrypoints.iter() {
+            sections.push(format!(
+                "// {}\n#define macro {}() = takes(0) returns(0) {{\n    0x00 dup1 revert\n}}",
+                entrypoint.signature, entrypoint.macro_name
+            ));
+        }
+        if self.abi.receive {
+            sections.push("#define receive() = takes(0) returns(0) {\n    stop\n}".to_string());
+        }
+        if self.abi.fallback {
+            sections.push("#define fallback() = takes(0) returns(0) {\n    stop\n}".to_string());
+        }
+
+        let mut main = vec!["#define macro MAIN() = takes(0) returns(0) {".to_string()];
+        main.push("    0x00 calldataload 0xe0 shr".to_string());
+        for entrypoint in entrypoints.iter() {
+            main.push(format!(
+                "    dup1 0x{} eq {} jumpi",
+                hex::encode(entrypoint.selector),
+                entrypoint.label
+            ));
+        }
+        main.push(String::new());
+        match self.abi.fallback {
+            true => main.push("    fallback jump".to_string()),
+            false => main.push("    0x00 dup1 revert".to_string()),
+        }
+        for entrypoint in entrypoints.iter() {
+            main.push(String::new());
+            main.push(format!("    {}:", entrypoint.label));
+            main.push(format!("        {}()", entrypoint.macro_name));
+        }
+        main.push("}".to_string());
+        sections.push(main.join("\n"));
+
+        format!("{}\n", sections.join("\n\n"))
+    }
+}
@@ -0,0 +1,341 @@
+//! ## Stats
+//!
+//! Compilation statistics of files and the macros they define, exported by `huffc stats` so
+//! compile time and code size growth can be tracked over time.
+//!
+//! The phase statistics of a compilation, printed with `huffc --stats`, break the time spent
+//! compiling each file down by phase, along with the macros expanded, the expansions reused and
+//! the bytes emitted, to find where the compiler spends its time on large codebases.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    ast::{Contract, MacroDefinition, MacroInvocation, Statement, StatementType},
+    bytecode::SourceMapping,
+};
+
+/// The compilation statistics of a set of files
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CompileStats {
+    /// The statistics of each file
+    pub files: Vec<FileStats>,
+}
+
+/// The compilation statistics of a file
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct FileStats {
+    /// The file path
+    pub path: String,
+    /// The number of tokens lexed from the file and its includes, excluding whitespace and
+    /// comments
+    pub tokens: usize,
+    /// The number of AST nodes, counting every definition and macro statement
+    pub nodes: usize,
+    /// The size of the runtime bytecode
+    pub bytes: usize,
+    /// The runtime bytes generated per AST node
+    pub expansion: f64,
+    /// The time taken to parse the file and generate its runtime bytecode, in microseconds
+    pub compile_time_us: u64,
+    /// The statistics of each macro
+    pub macros: Vec<MacroStats>,
+}
+
+/// The compilation statistics of a macro
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct MacroStats {
+    /// The macro name
+    pub name: String,
+    /// The path of the file defining the macro
+    pub file: Option<String>,
+    /// The number of tokens of the definition
+    pub tokens: usize,
+    /// The number of statements, including the statements of labels
+    pub nodes: usize,
+    /// The number of times the macro is expanded in the runtime bytecode
+    pub invocations: usize,
+    /// The runtime bytes generated by the statements of the macro, excluding the macros it
+    /// invokes
+    pub bytes: usize,
+    /// The runtime bytes generated per statement
+    pub expansion: f64,
+}
+
+impl CompileStats {
+    /// Exports the statistics to a json file
+    pub fn export(&self, out: &str) -> Result<(), std::io::Error> {
+        let serialized = serde_json::to_string_pretty(self)?;
+        let file_path = Path::new(out);
+        if let Some(p) = file_path.parent() {
+            fs::create_dir_all(p)?
+        }
+        fs::write(file_path, serialized)
+    }
+}
+
+impl FileStats {
+    /// Builds the statistics of a file from its contract and the source map of its runtime
+    /// bytecode, generated from the `MAIN` macro
+    pub fn new(
+        path: &str,
+        tokens: usize,
+        contract: &Contract,
+        source_map: &[SourceMapping],
+        compile_time_us: u64,
+    ) -> Self {
+        let macros =
+            contract.macros.iter().map(|m| MacroStats::new(m, source_map)).collect::<Vec<_>>();
+        let nodes = macros.len() +
+            macros.iter().map(|m| m.nodes).sum::<usize>() +
+            contract.constants.len() +
+            contract.functions.len() +
+            contract.events.len() +
+            contract.errors.len() +
+            contract.tables.len();
+        let bytes = source_map.iter().map(|m| m.size).sum();
+        Self {
+            path: path.to_string(),
+            tokens,
+            nodes,
+            bytes,
+            expansion: ratio(bytes, nodes),
+            compile_time_us,
+            macros,
+        }
+    }
+}
+
+impl MacroStats {
+    /// Builds the statistics of a macro from the source map of the runtime bytecode
+    ///
+    /// Bytes are attributed to the innermost macro invocation generating them, or to `MAIN`.
+    pub fn new(macro_def: &MacroDefinition, source_map: &[SourceMapping]) -> Self {
+        let name = macro_def.name.as_str();
+        let own = |m: &&SourceMapping| match m.invocations.last() {
+            Some(mi) => mi.macro_name == name,
+            None => m.table.is_none() && name == "MAIN",
+        };
+        let bytes = source_map.iter().filter(own).map(|m| m.size).sum();
+
+        // Each expansion is identified by the chain of invocations leading to it
+        let mut expansions: BTreeSet<&[MacroInvocation]> = BTreeSet::new();
+        for mapping in source_map.iter() {
+            for (i, mi) in mapping.invocations.iter().enumerate() {
+                if mi.macro_name == name {
+                    expansions.insert(&mapping.invocations[..=i]);
+                }
+            }
+        }
+        let invocations = match name {
+            "MAIN" => source_map.iter().any(|m| m.table.is_none()) as usize,
+            _ => expansions.len(),
+        };
+
+        let nodes = count_statements(&macro_def.statements);
+        Self {
+            name: macro_def.name.to_string(),
+            file: macro_def.span.0.first().and_then(|s| s.file.as_ref()).map(|f| f.path.clone()),
+            tokens: macro_def.span.0.len(),
+            nodes,
+            invocations,
+            bytes,
+            expansion: ratio(bytes, nodes),
+        }
+    }
+}
+
+/// A phase of compiling a file
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Reading the file and its includes
+    FileIo,
+    /// Flattening the includes and lexing the tokens
+    Lexing,
+    /// Parsing the tokens into the AST
+    Parsing,
+    /// Deriving the slots of free storage pointers
+    StorageDerivation,
+    /// Generating the bytecode and ABI
+    Codegen,
+    /// Writing the artifact
+    ArtifactWrite,
+}
+
+impl Phase {
+    /// Every phase, in compilation order
+    pub const ALL: [Phase; 6] = [
+        Phase::FileIo,
+        Phase::Lexing,
+        Phase::Parsing,
+        Phase::StorageDerivation,
+        Phase::Codegen,
+        Phase::ArtifactWrite,
+    ];
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Phase::FileIo => write!(f, "file io"),
+            Phase::Lexing => write!(f, "lexing"),
+            Phase::Parsing => write!(f, "parsing"),
+            Phase::StorageDerivation => write!(f, "storage derivation"),
+            Phase::Codegen => write!(f, "codegen"),
+            Phase::ArtifactWrite => write!(f, "artifact write"),
+        }
+    }
+}
+
+/// The time spent in each phase of compiling a file, and the work done
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct PhaseStats {
+    /// The file path
+    pub path: String,
+    /// The time spent in each phase reached, in microseconds
+    pub timings_us: BTreeMap<Phase, u64>,
+    /// The macro expansions generating bytes, in the runtime and constructor bytecode
+    pub macros_expanded: usize,
+    /// The macro expansions reused from an earlier invocation with the same arguments, instead
+    /// of generated again
+    pub expansions_reused: usize,
+    /// The size of the runtime and constructor bytecode
+    pub bytes_emitted: usize,
+}
+
+impl PhaseStats {
+    /// Adds time spent in a phase
+    pub fn record(&mut self, phase: Phase, elapsed: Duration) {
+        *self.timings_us.entry(phase).or_default() += elapsed.as_micros() as u64;
+    }
+
+    /// Runs a phase, recording the time spent
+    pub fn time<T>(&mut self, phase: Phase, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let res = f();
+        self.record(phase, start.elapsed());
+        res
+    }
+
+    /// The time spent over every phase, in microseconds
+    pub fn total_us(&self) -> u64 {
+        self.timings_us.values().sum()
+    }
+}
+
+/// Counts the macro expansions of a source map, each identified by the chain of invocations
+/// leading to it
+pub fn count_expansions(source_map: &[SourceMapping]) -> usize {
+    source_map
+        .iter()
+        .flat_map(|m| (1..=m.invocations.len()).map(|i| &m.invocations[..i]))
+        .collect::<BTreeSet<&[MacroInvocation]>>()
+        .len()
+}
+
+/// The phase statistics of compiling a set of files
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct PhaseReport {
+    /// The statistics of each file
+    pub files: Vec<PhaseStats>,
+}
+
+impl PhaseReport {
+    /// The statistics summed over every file
+    pub fn total(&self) -> PhaseStats {
+        let mut total = PhaseStats { path: "total".to_string(), ..Default::default() };
+        for file in self.files.iter() {
+            for (phase, us) in file.timings_us.iter() {
+                *total.timings_us.entry(*phase).or_default() += us;
+            }
+            total.macros_expanded += file.macros_expanded;
+            total.expansions_reused += file.expansions_reused;
+            total.bytes_emitted += file.bytes_emitted;
+        }
+        total
+    }
+
+    /// Renders the statistics as a table, with a column per file and one for the total when
+    /// several files were compiled
+    pub fn render(&self) -> String {
+        let mut columns = self.files.clone();
+        if columns.len() > 1 {
+            columns.push(self.total());
+        }
+        let mut rows = vec![std::iter::once(String::new())
+            .chain(columns.iter().map(|c| c.path.clone()))
+            .collect::<Vec<_>>()];
+        for phase in Phase::ALL {
+            rows.push(
+                std::iter::once(format!("{} (us)", phase))
+                    .chain(columns.iter().map(|c| {
+                        c.timings_us.get(&phase).map(|us| us.to_string()).unwrap_or_default()
+                    }))
+                    .collect(),
+            );
+        }
+        rows.push(
+            std::iter::once("total (us)".to_string())
+                .chain(columns.iter().map(|c| c.total_us().to_string()))
+                .collect(),
+        );
+        rows.push(
+            std::iter::once("macros expanded".to_string())
+                .chain(columns.iter().map(|c| c.macros_expanded.to_string()))
+                .collect(),
+        );
+        rows.push(
+            std::iter::once("expansions reused".to_string())
+                .chain(columns.iter().map(|c| c.expansions_reused.to_string()))
+                .collect(),
+        );
+        rows.push(
+            std::iter::once("bytes emitted".to_string())
+                .chain(columns.iter().map(|c| c.bytes_emitted.to_string()))
+                .collect(),
+        );
+
+        let widths = (0..=columns.len())
+            .map(|i| rows.iter().map(|r| r[i].len()).max().unwrap_or_default())
+            .collect::<Vec<_>>();
+        rows.iter()
+            .map(|row| {
+                let mut cells = vec![format!("{:w$}", row[0], w = widths[0])];
+                let values = row.iter().zip(widths.iter()).skip(1);
+                cells.extend(values.map(|(c, w)| format!("{:>w$}", c, w = w)));
+                cells.join("  ").trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Serializes the statistics to json
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Recursively counts a list of statements
+fn count_statements(statements: &[Statement]) -> usize {
+    statements
+        .iter()
+        .map(|s| match &s.ty {
+            StatementType::Label(l) => 1 + count_statements(&l.inner),
+            _ => 1,
+        })
+        .sum()
+}
+
+/// The ratio of bytes to nodes, zero without nodes
+fn ratio(bytes: usize, nodes: usize) -> f64 {
+    match nodes {
+        0 => 0.0,
+        n => bytes as f64 / n as f64,
+    }
+}
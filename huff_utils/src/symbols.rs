@@ -0,0 +1,159 @@
+//! ## Symbols
+//!
+//! The identifiers defined by a contract, and the spans defining them.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast::{AstSpan, MacroDefinition, StatementType},
+    files::Span,
+};
+
+/// The kind of a defined identifier
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SymbolKind {
+    /// A macro or function macro
+    Macro,
+    /// A test
+    Test,
+    /// A constant
+    Constant,
+    /// An opcode alias
+    Alias,
+    /// A label, scoped to the macro defining it
+    Label,
+    /// An ABI function
+    Function,
+    /// An ABI event
+    Event,
+    /// An ABI custom error
+    Error,
+    /// A jump or code table
+    Table,
+}
+
+/// A defined identifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// The identifier
+    pub name: String,
+    /// The kind of definition
+    pub kind: SymbolKind,
+    /// The span of the identifier in the definition
+    pub span: Span,
+    /// The spans of the whole definition
+    pub definition: AstSpan,
+    /// The macro defining a label, `None` for top-level definitions
+    pub scope: Option<String>,
+}
+
+impl Symbol {
+    /// The path of the file defining the symbol, if known
+    pub fn file(&self) -> Option<&str> {
+        self.span.file.as_ref().map(|f| f.path.as_str())
+    }
+
+    /// Whether the position is in the span of the identifier
+    pub fn name_contains(&self, file: Option<&str>, offset: usize) -> bool {
+        in_file(&self.span, file) && self.span.start <= offset && offset <= self.span.end
+    }
+
+    /// Whether the position is in the spans of the whole definition
+    pub fn definition_contains(&self, file: Option<&str>, offset: usize) -> bool {
+        let spans = self.definition.0.iter().filter(|s| in_file(s, file));
+        match (spans.clone().map(|s| s.start).min(), spans.map(|s| s.end).max()) {
+            (Some(start), Some(end)) => start <= offset && offset <= end,
+            _ => false,
+        }
+    }
+}
+
+/// Whether a span is in the file, spans without a file matching any file
+fn in_file(span: &Span, file: Option<&str>) -> bool {
+    match (&span.file, file) {
+        (Some(f), Some(path)) => f.path == path,
+        _ => true,
+    }
+}
+
+/// The symbols of a contract, in definition order
+///
+/// Built by the parser as definitions are parsed, so it contains all the definitions that could
+/// be recovered from source code with errors.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SymbolTable {
+    /// The symbols, in definition order
+    pub symbols: Vec<Symbol>,
+}
+
+impl SymbolTable {
+    /// Public associated function to instantiate an empty symbol table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a symbol to the table
+    pub fn insert(&mut self, symbol: Symbol) {
+        self.symbols.push(symbol);
+    }
+
+    /// Adds the labels defined in the body of a macro, scoped to the macro
+    pub fn insert_labels(&mut self, macro_def: &MacroDefinition) {
+        for statement in macro_def.statements.iter() {
+            if let StatementType::Label(label) = &statement.ty {
+                let span = match label.span.0.first() {
+                    Some(s) => s.clone(),
+                    None => continue,
+                };
+                self.insert(Symbol {
+                    name: label.name.to_string(),
+                    kind: SymbolKind::Label,
+                    span,
+                    definition: label.span.clone(),
+                    scope: Some(macro_def.name.to_string()),
+                });
+            }
+        }
+    }
+
+    /// The top-level definitions, excluding labels
+    pub fn definitions(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols.iter().filter(|s| s.scope.is_none())
+    }
+
+    /// The labels defined in a macro
+    pub fn labels<'a>(&'a self, scope: &'a str) -> impl Iterator<Item = &'a Symbol> {
+        self.symbols.iter().filter(move |s| s.scope.as_deref() == Some(scope))
+    }
+
+    /// The first top-level definition of an identifier
+    pub fn get(&self, name: &str) -> Option<&Symbol> {
+        self.definitions().find(|s| s.name == name)
+    }
+
+    /// The label defined in a macro
+    pub fn label(&self, scope: &str, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|s| s.scope.as_deref() == Some(scope) && s.name == name)
+    }
+
+    /// The symbol whose identifier is at a position
+    pub fn at(&self, file: Option<&str>, offset: usize) -> Option<&Symbol> {
+        self.symbols.iter().find(|s| s.name_contains(file, offset))
+    }
+
+    /// The top-level definition containing a position
+    pub fn enclosing(&self, file: Option<&str>, offset: usize) -> Option<&Symbol> {
+        self.definitions().find(|s| s.definition_contains(file, offset))
+    }
+
+    /// Resolves an identifier referenced at a position to its definition
+    ///
+    /// Labels of the enclosing macro take precedence over top-level definitions, the same way
+    /// codegen resolves label calls.
+    pub fn resolve(&self, name: &str, file: Option<&str>, offset: usize) -> Option<&Symbol> {
+        self.enclosing(file, offset)
+            .filter(|s| s.kind == SymbolKind::Macro)
+            .and_then(|m| self.label(&m.name, name))
+            .or_else(|| self.get(name))
+    }
+}
@@ -17,6 +17,27 @@ impl Token {
     pub fn new(kind: TokenKind, span: Span) -> Self {
         Self { kind, span }
     }
+
+    /// Describes the token on a single line, for dumping the token stream: the file position
+    /// and byte range of its span, the name of its kind, and its lexeme, separated by tabs
+    pub fn dump(&self) -> String {
+        let kind = format!("{:?}", self.kind);
+        let name = kind.split('(').next().unwrap_or_default();
+        let path = self.span.file.as_ref().map(|f| f.path.as_str()).unwrap_or_default();
+        let position = self
+            .span
+            .start_position()
+            .map(|p| format!("{}:{}:{}", path, p.line, p.column))
+            .unwrap_or_else(|| path.to_string());
+        format!(
+            "{}\t{}..{}\t{}\t{:?}",
+            position,
+            self.span.start,
+            self.span.end,
+            name,
+            self.span.text().unwrap_or_default()
+        )
+    }
 }
 
 /// The kind of token
@@ -35,12 +56,26 @@ pub enum TokenKind {
     Include,
     /// "macro" keyword
     Macro,
+    /// "fallback" keyword
+    Fallback,
+    /// "receive" keyword
+    Receive,
+    /// "test" keyword
+    Test,
     /// "function" keyword
     Function,
     /// "event" keyword
     Event,
+    /// "error" keyword
+    Error,
     /// "constant" keyword
     Constant,
+    /// "immutable" keyword
+    Immutable,
+    /// "internal" keyword
+    Internal,
+    /// "alias" keyword
+    Alias,
     /// "takes" keyword
     Takes,
     /// "returns" keyword
@@ -83,6 +118,20 @@ pub enum TokenKind {
     Sub,
     /// Multiplication
     Mul,
+    /// Equality, "=="
+    Equal,
+    /// Inequality, "!="
+    NotEqual,
+    /// Less than or equal, "<="
+    LessEqual,
+    /// Greater than or equal, ">="
+    GreaterEqual,
+    /// Logical and, "&&"
+    And,
+    /// Logical or, "||"
+    Or,
+    /// Logical negation, "!"
+    Not,
     /// A comma
     Comma,
     /// A Colon
@@ -93,6 +142,8 @@ pub enum TokenKind {
     Whitespace,
     /// A string literal
     Str(String),
+    /// The path of a bundled module included between angle brackets, as in `<std/Auth.huff>`
+    BundledPath(String),
     /// Hex
     Literal(Literal),
     /// Opcode
@@ -113,7 +164,8 @@ pub enum TokenKind {
     JumpTablePacked,
     /// A Code Table
     CodeTable,
-    /// A builtin function (__codesize, __tablesize, __tablestart)
+    /// A builtin function (__codesize, __tablesize, __tablestart, __ASSERT_CODEHASH, or a
+    /// cheatcode), or a top-level __ASSERT
     BuiltinFunction(String),
 }
 
@@ -126,9 +178,16 @@ impl fmt::Display for TokenKind {
             TokenKind::Define => "#define",
             TokenKind::Include => "#include",
             TokenKind::Macro => "macro",
+            TokenKind::Fallback => "fallback",
+            TokenKind::Receive => "receive",
+            TokenKind::Test => "test",
             TokenKind::Function => "function",
             TokenKind::Event => "event",
+            TokenKind::Error => "error",
             TokenKind::Constant => "constant",
+            TokenKind::Immutable => "immutable",
+            TokenKind::Internal => "internal",
+            TokenKind::Alias => "alias",
             TokenKind::View => "view",
             TokenKind::Pure => "pure",
             TokenKind::Payable => "payable",
@@ -150,11 +209,19 @@ impl fmt::Display for TokenKind {
             TokenKind::Add => "+",
             TokenKind::Sub => "-",
             TokenKind::Mul => "*",
+            TokenKind::Equal => "==",
+            TokenKind::NotEqual => "!=",
+            TokenKind::LessEqual => "<=",
+            TokenKind::GreaterEqual => ">=",
+            TokenKind::And => "&&",
+            TokenKind::Or => "||",
+            TokenKind::Not => "!",
             TokenKind::Colon => ":",
             TokenKind::Comma => ",",
             TokenKind::Num(num) => return write!(f, "{}", num),
             TokenKind::Whitespace => " ",
             TokenKind::Str(str) => str,
+            TokenKind::BundledPath(path) => return write!(f, "<{}>", path),
             TokenKind::Literal(l) => {
                 let mut s = String::new();
                 for b in l.iter() {
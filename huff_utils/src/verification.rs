@@ -0,0 +1,144 @@
+//! ## Verification
+//!
+//! The bundles submitted to Sourcify and Etherscan-style verifiers, written by
+//! `huffc verify-bundle`.
+//!
+//! A bundle pins the compiler version and the settings a contract was compiled with. Its sources
+//! are either every file compiled, by path, like the standard JSON input of solc, or the single
+//! source flattened from them. Verifiers compile the entrypoint with the pinned compiler and
+//! settings, then compare the result with the bytecode of the bundle and the code on chain.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+use crate::{
+    abi::Abi,
+    artifact::Artifact,
+    ast::{ConstVal, ConstantDefinition},
+    bytes_util::bytes32_to_string,
+};
+
+/// The language of the bundled sources
+pub const BUNDLE_LANGUAGE: &str = "Huff";
+
+/// The name of the compiler verifiers compile the sources with
+pub const BUNDLE_COMPILER: &str = "huffc";
+
+/// The sources, settings and compiled bytecode of a contract to verify
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationBundle {
+    /// The language of the sources, always `Huff`
+    pub language: String,
+    /// The compiler the contract was compiled with
+    pub compiler: BundleCompiler,
+    /// The path of the compiled file among the sources
+    pub entrypoint: String,
+    /// The sources, by path
+    pub sources: BTreeMap<String, BundleSource>,
+    /// The settings the contract was compiled with
+    pub settings: BundleSettings,
+    /// The abi
+    pub abi: Option<Abi>,
+    /// The creation bytecode, followed by the encoded constructor arguments
+    pub bytecode: String,
+    /// The runtime bytecode
+    pub deployed_bytecode: String,
+}
+
+/// The pinned compiler of a bundle
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct BundleCompiler {
+    /// The compiler name, always `huffc`
+    pub name: String,
+    /// The compiler version
+    pub version: String,
+}
+
+/// A bundled source file
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct BundleSource {
+    /// The 0x prefixed keccak256 hash of the content
+    pub keccak256: String,
+    /// The source
+    pub content: String,
+}
+
+/// The compiler settings of a bundle, those changing the compiled bytecode
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSettings {
+    /// The EVM version bytecode was generated for
+    pub evm_version: String,
+    /// Whether uppercase and mixed-case opcode mnemonics were accepted
+    pub case_insensitive_opcodes: bool,
+    /// The maximum depth of nested macro invocations
+    pub max_expansion_depth: usize,
+    /// The constants defined when compiling, and their values
+    pub defines: BTreeMap<String, String>,
+    /// The constants overridden when compiling, and their values
+    pub overrides: BTreeMap<String, String>,
+    /// The constructor arguments, as passed to the compiler
+    pub constructor_arguments: Vec<String>,
+    /// The ABI encoded constructor arguments, appended to the creation bytecode
+    pub encoded_constructor_arguments: String,
+}
+
+impl BundleSource {
+    /// Bundles a source, hashing its content
+    pub fn new(content: String) -> Self {
+        let hash = ethers_core::utils::keccak256(content.as_bytes());
+        Self { keccak256: format!("0x{}", hex::encode(hash)), content }
+    }
+}
+
+impl BundleSettings {
+    /// The values of compile-time constants, by name
+    pub fn constant_values(constants: &[ConstantDefinition]) -> BTreeMap<String, String> {
+        constants
+            .iter()
+            .map(|c| {
+                let value = match &c.value {
+                    ConstVal::Literal(l) => bytes32_to_string(l, true),
+                    ConstVal::FreeStoragePointer(fsp) => fsp.to_string(),
+                };
+                (c.name.to_string(), value)
+            })
+            .collect()
+    }
+}
+
+impl VerificationBundle {
+    /// Bundles the sources and settings of a compiled contract, pinning the compiler version
+    pub fn new(
+        version: &str,
+        entrypoint: &str,
+        sources: BTreeMap<String, BundleSource>,
+        settings: BundleSettings,
+        artifact: &Artifact,
+    ) -> Self {
+        Self {
+            language: BUNDLE_LANGUAGE.to_string(),
+            compiler: BundleCompiler {
+                name: BUNDLE_COMPILER.to_string(),
+                version: version.to_string(),
+            },
+            entrypoint: entrypoint.to_string(),
+            sources,
+            settings,
+            abi: artifact.abi.clone(),
+            bytecode: format!("0x{}", artifact.bytecode),
+            deployed_bytecode: format!("0x{}", artifact.runtime),
+        }
+    }
+
+    /// Exports the bundle to a json file
+    pub fn export(&self, out: &str) -> Result<(), std::io::Error> {
+        let serialized = serde_json::to_string_pretty(self)?;
+        let file_path = Path::new(out);
+        if let Some(p) = file_path.parent() {
+            fs::create_dir_all(p)?
+        }
+        fs::write(file_path, serialized)
+    }
+}
@@ -0,0 +1,435 @@
+//! ## Visit
+//!
+//! Traits walking the AST of a contract, so passes over it only handle the nodes they care about.
+//!
+//! A [Visitor] reads the AST and a [VisitMut] transforms it in place. Each `visit_*` method
+//! defaults to its `walk_*` function, visiting the children of the node in source order, so an
+//! implementation overrides the methods of the nodes it handles, and calls the walk function of
+//! a node to keep walking into its children. The references to constants and macros, wherever
+//! they appear, are visited with the span of the statement or definition holding them.
+//!
+//! Macro invocations aren't followed into the macros they invoke, and the parameters of macros
+//! aren't visited: their defaults are filled into the invocations omitting them.
+
+use crate::{
+    ast::{
+        AliasDefinition, Assertion, AstSpan, BuiltinFunctionCall, BuiltinFunctionKind,
+        Conditional, ConstantDefinition, Contract, ErrorDefinition, Event, Expression, Function,
+        Label, MacroArg, MacroDefinition, MacroInvocation, Repeat, Statement, StatementType,
+        TableDefinition,
+    },
+    intern::SymbolId,
+};
+
+/// Reads the nodes of an AST
+pub trait Visitor {
+    /// Visits a contract, and every definition it holds
+    fn visit_contract(&mut self, contract: &Contract) {
+        walk_contract(self, contract)
+    }
+
+    /// Visits a macro definition, the fallback, receive and test definitions included
+    fn visit_macro(&mut self, macro_def: &MacroDefinition) {
+        walk_macro(self, macro_def)
+    }
+
+    /// Visits a constant definition
+    fn visit_constant(&mut self, _constant: &ConstantDefinition) {}
+
+    /// Visits a function definition
+    fn visit_function(&mut self, _function: &Function) {}
+
+    /// Visits an event definition
+    fn visit_event(&mut self, _event: &Event) {}
+
+    /// Visits a custom error definition
+    fn visit_error(&mut self, _error: &ErrorDefinition) {}
+
+    /// Visits a table definition
+    fn visit_table(&mut self, table: &TableDefinition) {
+        walk_table(self, table)
+    }
+
+    /// Visits an opcode alias definition
+    fn visit_alias(&mut self, alias: &AliasDefinition) {
+        walk_alias(self, alias)
+    }
+
+    /// Visits a top-level `__ASSERT`
+    fn visit_assertion(&mut self, assertion: &Assertion) {
+        walk_assertion(self, assertion)
+    }
+
+    /// Visits a statement of a macro, table, alias or block
+    fn visit_statement(&mut self, statement: &Statement) {
+        walk_statement(self, statement)
+    }
+
+    /// Visits a macro invocation
+    fn visit_macro_invocation(&mut self, invocation: &MacroInvocation) {
+        walk_macro_invocation(self, invocation)
+    }
+
+    /// Visits an argument passed to a macro invocation or as a repeat count
+    fn visit_macro_arg(&mut self, arg: &MacroArg, span: &AstSpan) {
+        walk_macro_arg(self, arg, span)
+    }
+
+    /// Visits a builtin function call
+    fn visit_builtin(&mut self, builtin: &BuiltinFunctionCall) {
+        walk_builtin(self, builtin)
+    }
+
+    /// Visits a constant expression, of an `__ASSERT` or `__OFFSET`
+    fn visit_expression(&mut self, expression: &Expression, span: &AstSpan) {
+        walk_expression(self, expression, span)
+    }
+
+    /// Visits a label and the statements it holds
+    fn visit_label(&mut self, label: &Label) {
+        walk_label(self, label)
+    }
+
+    /// Visits a `__REPEAT` block
+    fn visit_repeat(&mut self, repeat: &Repeat) {
+        walk_repeat(self, repeat)
+    }
+
+    /// Visits an `__IFDEF` or `__IFNDEF` block
+    fn visit_conditional(&mut self, conditional: &Conditional) {
+        walk_conditional(self, conditional)
+    }
+
+    /// Visits a reference to a constant, pushed, passed as an argument, tested by a conditional
+    /// or evaluated in an expression
+    fn visit_constant_ref(&mut self, _name: &str, _span: &AstSpan) {}
+
+    /// Visits a reference to a macro, invoked or named by the argument of a builtin, which may
+    /// name a table instead
+    fn visit_macro_ref(&mut self, _name: &str, _span: &AstSpan) {}
+}
+
+/// Walks the constants, macros, tables, aliases, functions, events, errors and assertions of a
+/// contract
+pub fn walk_contract<V: Visitor + ?Sized>(visitor: &mut V, contract: &Contract) {
+    contract.constants.iter().for_each(|c| visitor.visit_constant(c));
+    let macros = contract.macros.iter().chain(contract.fallback.iter());
+    macros.chain(contract.receive.iter()).chain(contract.tests.iter()).for_each(|m| {
+        visitor.visit_macro(m);
+    });
+    contract.tables.iter().for_each(|t| visitor.visit_table(t));
+    contract.aliases.iter().for_each(|a| visitor.visit_alias(a));
+    contract.functions.iter().for_each(|f| visitor.visit_function(f));
+    contract.events.iter().for_each(|e| visitor.visit_event(e));
+    contract.errors.iter().for_each(|e| visitor.visit_error(e));
+    contract.assertions.iter().for_each(|a| visitor.visit_assertion(a));
+}
+
+/// Walks the statements of a macro
+pub fn walk_macro<V: Visitor + ?Sized>(visitor: &mut V, macro_def: &MacroDefinition) {
+    macro_def.statements.iter().for_each(|s| visitor.visit_statement(s));
+}
+
+/// Walks the statements of a table
+pub fn walk_table<V: Visitor + ?Sized>(visitor: &mut V, table: &TableDefinition) {
+    table.statements.iter().for_each(|s| visitor.visit_statement(s));
+}
+
+/// Walks the statements an alias expands to
+pub fn walk_alias<V: Visitor + ?Sized>(visitor: &mut V, alias: &AliasDefinition) {
+    alias.statements.iter().for_each(|s| visitor.visit_statement(s));
+}
+
+/// Walks the expression of an assertion
+pub fn walk_assertion<V: Visitor + ?Sized>(visitor: &mut V, assertion: &Assertion) {
+    visitor.visit_expression(&assertion.expression, &assertion.span);
+}
+
+/// Walks the invocation, builtin, label, block or constant reference of a statement
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+    match &statement.ty {
+        StatementType::MacroInvocation(mi) => visitor.visit_macro_invocation(mi),
+        StatementType::Constant(name) => visitor.visit_constant_ref(name, &statement.span),
+        StatementType::BuiltinFunctionCall(bf) => visitor.visit_builtin(bf),
+        StatementType::Label(l) => visitor.visit_label(l),
+        StatementType::Repeat(r) => visitor.visit_repeat(r),
+        StatementType::Conditional(c) => visitor.visit_conditional(c),
+        _ => {}
+    }
+}
+
+/// Walks the arguments of a macro invocation, then the macro it invokes
+pub fn walk_macro_invocation<V: Visitor + ?Sized>(visitor: &mut V, invocation: &MacroInvocation) {
+    invocation.args.iter().for_each(|a| visitor.visit_macro_arg(a, &invocation.span));
+    visitor.visit_macro_ref(&invocation.macro_name, &invocation.span);
+}
+
+/// Walks the constant an argument references
+pub fn walk_macro_arg<V: Visitor + ?Sized>(visitor: &mut V, arg: &MacroArg, span: &AstSpan) {
+    if let MacroArg::Constant(name) = arg {
+        visitor.visit_constant_ref(name, span);
+    }
+}
+
+/// Walks the macros named by the arguments of a builtin, then its expression, or the arguments
+/// of a custom builtin
+pub fn walk_builtin<V: Visitor + ?Sized>(visitor: &mut V, builtin: &BuiltinFunctionCall) {
+    for name in builtin.args.iter().filter_map(|a| a.name.as_ref()) {
+        visitor.visit_macro_ref(name, &builtin.span);
+    }
+    match &builtin.kind {
+        BuiltinFunctionKind::Assert(e) | BuiltinFunctionKind::Offset(e) => {
+            visitor.visit_expression(e, &builtin.span)
+        }
+        BuiltinFunctionKind::Custom(_, args) => {
+            args.iter().for_each(|a| visitor.visit_macro_arg(a, &builtin.span))
+        }
+        _ => {}
+    }
+}
+
+/// Walks the constants, builtins and operands of an expression
+pub fn walk_expression<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    expression: &Expression,
+    span: &AstSpan,
+) {
+    match expression {
+        Expression::Constant(name) => visitor.visit_constant_ref(name, span),
+        Expression::BuiltinFunctionCall(bf) => visitor.visit_builtin(bf),
+        Expression::Not(e) => visitor.visit_expression(e, span),
+        Expression::Binary(_, left, right) => {
+            visitor.visit_expression(left, span);
+            visitor.visit_expression(right, span);
+        }
+        Expression::Literal(_) | Expression::LabelOffset(_) => {}
+    }
+}
+
+/// Walks the statements of a label
+pub fn walk_label<V: Visitor + ?Sized>(visitor: &mut V, label: &Label) {
+    label.inner.iter().for_each(|s| visitor.visit_statement(s));
+}
+
+/// Walks the count of a repeat, then its statements
+pub fn walk_repeat<V: Visitor + ?Sized>(visitor: &mut V, repeat: &Repeat) {
+    visitor.visit_macro_arg(&repeat.count, &repeat.span);
+    repeat.inner.iter().for_each(|s| visitor.visit_statement(s));
+}
+
+/// Walks the constant a conditional tests, then its statements
+pub fn walk_conditional<V: Visitor + ?Sized>(visitor: &mut V, conditional: &Conditional) {
+    visitor.visit_constant_ref(&conditional.constant, &conditional.span);
+    conditional.inner.iter().for_each(|s| visitor.visit_statement(s));
+}
+
+/// Transforms the nodes of an AST in place
+pub trait VisitMut {
+    /// Visits a contract, and every definition it holds
+    fn visit_contract_mut(&mut self, contract: &mut Contract) {
+        walk_contract_mut(self, contract)
+    }
+
+    /// Visits a macro definition, the fallback, receive and test definitions included
+    fn visit_macro_mut(&mut self, macro_def: &mut MacroDefinition) {
+        walk_macro_mut(self, macro_def)
+    }
+
+    /// Visits a constant definition
+    fn visit_constant_mut(&mut self, _constant: &mut ConstantDefinition) {}
+
+    /// Visits a function definition
+    fn visit_function_mut(&mut self, _function: &mut Function) {}
+
+    /// Visits an event definition
+    fn visit_event_mut(&mut self, _event: &mut Event) {}
+
+    /// Visits a custom error definition
+    fn visit_error_mut(&mut self, _error: &mut ErrorDefinition) {}
+
+    /// Visits a table definition
+    fn visit_table_mut(&mut self, table: &mut TableDefinition) {
+        walk_table_mut(self, table)
+    }
+
+    /// Visits an opcode alias definition
+    fn visit_alias_mut(&mut self, alias: &mut AliasDefinition) {
+        walk_alias_mut(self, alias)
+    }
+
+    /// Visits a top-level `__ASSERT`
+    fn visit_assertion_mut(&mut self, assertion: &mut Assertion) {
+        walk_assertion_mut(self, assertion)
+    }
+
+    /// Visits a statement of a macro, table, alias or block
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        walk_statement_mut(self, statement)
+    }
+
+    /// Visits a macro invocation
+    fn visit_macro_invocation_mut(&mut self, invocation: &mut MacroInvocation) {
+        walk_macro_invocation_mut(self, invocation)
+    }
+
+    /// Visits an argument passed to a macro invocation or as a repeat count
+    fn visit_macro_arg_mut(&mut self, arg: &mut MacroArg, span: &AstSpan) {
+        walk_macro_arg_mut(self, arg, span)
+    }
+
+    /// Visits a builtin function call
+    fn visit_builtin_mut(&mut self, builtin: &mut BuiltinFunctionCall) {
+        walk_builtin_mut(self, builtin)
+    }
+
+    /// Visits a constant expression, of an `__ASSERT` or `__OFFSET`
+    fn visit_expression_mut(&mut self, expression: &mut Expression, span: &AstSpan) {
+        walk_expression_mut(self, expression, span)
+    }
+
+    /// Visits a label and the statements it holds
+    fn visit_label_mut(&mut self, label: &mut Label) {
+        walk_label_mut(self, label)
+    }
+
+    /// Visits a `__REPEAT` block
+    fn visit_repeat_mut(&mut self, repeat: &mut Repeat) {
+        walk_repeat_mut(self, repeat)
+    }
+
+    /// Visits an `__IFDEF` or `__IFNDEF` block
+    fn visit_conditional_mut(&mut self, conditional: &mut Conditional) {
+        walk_conditional_mut(self, conditional)
+    }
+
+    /// Visits a reference to a constant, pushed, passed as an argument, tested by a conditional
+    /// or evaluated in an expression
+    fn visit_constant_ref_mut(&mut self, _name: &mut SymbolId, _span: &AstSpan) {}
+
+    /// Visits a reference to a macro, invoked or named by the argument of a builtin, which may
+    /// name a table instead
+    fn visit_macro_ref_mut(&mut self, _name: &mut String, _span: &AstSpan) {}
+}
+
+/// Walks the constants, macros, tables, aliases, functions, events, errors and assertions of a
+/// contract
+pub fn walk_contract_mut<V: VisitMut + ?Sized>(visitor: &mut V, contract: &mut Contract) {
+    contract.constants.iter_mut().for_each(|c| visitor.visit_constant_mut(c));
+    let macros = contract.macros.iter_mut().chain(contract.fallback.iter_mut());
+    macros.chain(contract.receive.iter_mut()).chain(contract.tests.iter_mut()).for_each(|m| {
+        visitor.visit_macro_mut(m);
+    });
+    contract.tables.iter_mut().for_each(|t| visitor.visit_table_mut(t));
+    contract.aliases.iter_mut().for_each(|a| visitor.visit_alias_mut(a));
+    contract.functions.iter_mut().for_each(|f| visitor.visit_function_mut(f));
+    contract.events.iter_mut().for_each(|e| visitor.visit_event_mut(e));
+    contract.errors.iter_mut().for_each(|e| visitor.visit_error_mut(e));
+    contract.assertions.iter_mut().for_each(|a| visitor.visit_assertion_mut(a));
+}
+
+/// Walks the statements of a macro
+pub fn walk_macro_mut<V: VisitMut + ?Sized>(visitor: &mut V, macro_def: &mut MacroDefinition) {
+    macro_def.statements.iter_mut().for_each(|s| visitor.visit_statement_mut(s));
+}
+
+/// Walks the statements of a table
+pub fn walk_table_mut<V: VisitMut + ?Sized>(visitor: &mut V, table: &mut TableDefinition) {
+    table.statements.iter_mut().for_each(|s| visitor.visit_statement_mut(s));
+}
+
+/// Walks the statements an alias expands to
+pub fn walk_alias_mut<V: VisitMut + ?Sized>(visitor: &mut V, alias: &mut AliasDefinition) {
+    alias.statements.iter_mut().for_each(|s| visitor.visit_statement_mut(s));
+}
+
+/// Walks the expression of an assertion
+pub fn walk_assertion_mut<V: VisitMut + ?Sized>(visitor: &mut V, assertion: &mut Assertion) {
+    visitor.visit_expression_mut(&mut assertion.expression, &assertion.span);
+}
+
+/// Walks the invocation, builtin, label, block or constant reference of a statement
+pub fn walk_statement_mut<V: VisitMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    match &mut statement.ty {
+        StatementType::MacroInvocation(mi) => visitor.visit_macro_invocation_mut(mi),
+        StatementType::Constant(name) => visitor.visit_constant_ref_mut(name, &statement.span),
+        StatementType::BuiltinFunctionCall(bf) => visitor.visit_builtin_mut(bf),
+        StatementType::Label(l) => visitor.visit_label_mut(l),
+        StatementType::Repeat(r) => visitor.visit_repeat_mut(r),
+        StatementType::Conditional(c) => visitor.visit_conditional_mut(c),
+        _ => {}
+    }
+}
+
+/// Walks the arguments of a macro invocation, then the macro it invokes
+pub fn walk_macro_invocation_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    invocation: &mut MacroInvocation,
+) {
+    invocation.args.iter_mut().for_each(|a| visitor.visit_macro_arg_mut(a, &invocation.span));
+    let mut name = invocation.macro_name.to_string();
+    visitor.visit_macro_ref_mut(&mut name, &invocation.span);
+    invocation.macro_name = name.into();
+}
+
+/// Walks the constant an argument references
+pub fn walk_macro_arg_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    arg: &mut MacroArg,
+    span: &AstSpan,
+) {
+    if let MacroArg::Constant(name) = arg {
+        visitor.visit_constant_ref_mut(name, span);
+    }
+}
+
+/// Walks the macros named by the arguments of a builtin, then its expression, or the arguments
+/// of a custom builtin
+pub fn walk_builtin_mut<V: VisitMut + ?Sized>(visitor: &mut V, builtin: &mut BuiltinFunctionCall) {
+    for name in builtin.args.iter_mut().filter_map(|a| a.name.as_mut()) {
+        visitor.visit_macro_ref_mut(name, &builtin.span);
+    }
+    match &mut builtin.kind {
+        BuiltinFunctionKind::Assert(e) | BuiltinFunctionKind::Offset(e) => {
+            visitor.visit_expression_mut(e, &builtin.span)
+        }
+        BuiltinFunctionKind::Custom(_, args) => {
+            args.iter_mut().for_each(|a| visitor.visit_macro_arg_mut(a, &builtin.span))
+        }
+        _ => {}
+    }
+}
+
+/// Walks the constants, builtins and operands of an expression
+pub fn walk_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut Expression,
+    span: &AstSpan,
+) {
+    match expression {
+        Expression::Constant(name) => visitor.visit_constant_ref_mut(name, span),
+        Expression::BuiltinFunctionCall(bf) => visitor.visit_builtin_mut(bf),
+        Expression::Not(e) => visitor.visit_expression_mut(e, span),
+        Expression::Binary(_, left, right) => {
+            visitor.visit_expression_mut(left, span);
+            visitor.visit_expression_mut(right, span);
+        }
+        Expression::Literal(_) | Expression::LabelOffset(_) => {}
+    }
+}
+
+/// Walks the statements of a label
+pub fn walk_label_mut<V: VisitMut + ?Sized>(visitor: &mut V, label: &mut Label) {
+    label.inner.iter_mut().for_each(|s| visitor.visit_statement_mut(s));
+}
+
+/// Walks the count of a repeat, then its statements
+pub fn walk_repeat_mut<V: VisitMut + ?Sized>(visitor: &mut V, repeat: &mut Repeat) {
+    visitor.visit_macro_arg_mut(&mut repeat.count, &repeat.span);
+    repeat.inner.iter_mut().for_each(|s| visitor.visit_statement_mut(s));
+}
+
+/// Walks the constant a conditional tests, then its statements
+pub fn walk_conditional_mut<V: VisitMut + ?Sized>(visitor: &mut V, conditional: &mut Conditional) {
+    visitor.visit_constant_ref_mut(&mut conditional.constant, &conditional.span);
+    conditional.inner.iter_mut().for_each(|s| visitor.visit_statement_mut(s));
+}
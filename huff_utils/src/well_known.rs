@@ -0,0 +1,147 @@
+//! ## Well-Known Constants
+//!
+//! The catalog of the constants every contract can use without defining them.
+//!
+//! The EIP-1967 proxy slots and the addresses of the precompiles are pushed with `[NAME]` like
+//! any constant, so projects don't copy their magic values around. The slots are computed from
+//! their EIP-1967 label, the keccak256 hash of the label minus one. A constant the contract
+//! defines takes precedence, but must have the well-known value of its name.
+
+use ethers_core::{types::U256, utils::keccak256};
+
+use crate::{
+    ast::{ConstVal, Contract, Literal},
+    error::{CodegenError, CodegenErrorKind},
+};
+
+/// A constant every contract can use without defining it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WellKnownConstant {
+    /// The EIP-1967 slot of the implementation of a proxy
+    Eip1967ImplementationSlot,
+    /// The EIP-1967 slot of the admin of a proxy
+    Eip1967AdminSlot,
+    /// The EIP-1967 slot of the beacon of a proxy
+    Eip1967BeaconSlot,
+    /// The address of the `ecrecover` precompile
+    EcrecoverPrecompile,
+    /// The address of the `sha256` precompile
+    Sha256Precompile,
+    /// The address of the `ripemd160` precompile
+    Ripemd160Precompile,
+    /// The address of the identity precompile, copying its input
+    IdentityPrecompile,
+    /// The address of the `modexp` precompile
+    ModexpPrecompile,
+    /// The address of the alt_bn128 addition precompile
+    EcaddPrecompile,
+    /// The address of the alt_bn128 scalar multiplication precompile
+    EcmulPrecompile,
+    /// The address of the alt_bn128 pairing precompile
+    EcpairingPrecompile,
+    /// The address of the `blake2f` precompile
+    Blake2fPrecompile,
+    /// The address of the KZG point evaluation precompile, from cancun
+    PointEvaluationPrecompile,
+}
+
+impl WellKnownConstant {
+    /// Every constant of the catalog
+    pub const ALL: [WellKnownConstant; 13] = [
+        WellKnownConstant::Eip1967ImplementationSlot,
+        WellKnownConstant::Eip1967AdminSlot,
+        WellKnownConstant::Eip1967BeaconSlot,
+        WellKnownConstant::EcrecoverPrecompile,
+        WellKnownConstant::Sha256Precompile,
+        WellKnownConstant::Ripemd160Precompile,
+        WellKnownConstant::IdentityPrecompile,
+        WellKnownConstant::ModexpPrecompile,
+        WellKnownConstant::EcaddPrecompile,
+        WellKnownConstant::EcmulPrecompile,
+        WellKnownConstant::EcpairingPrecompile,
+        WellKnownConstant::Blake2fPrecompile,
+        WellKnownConstant::PointEvaluationPrecompile,
+    ];
+
+    /// The name of the constant, pushed with `[NAME]`
+    pub fn name(&self) -> &'static str {
+        match self {
+            WellKnownConstant::Eip1967ImplementationSlot => "EIP1967_IMPLEMENTATION_SLOT",
+            WellKnownConstant::Eip1967AdminSlot => "EIP1967_ADMIN_SLOT",
+            WellKnownConstant::Eip1967BeaconSlot => "EIP1967_BEACON_SLOT",
+            WellKnownConstant::EcrecoverPrecompile => "ECRECOVER_PRECOMPILE",
+            WellKnownConstant::Sha256Precompile => "SHA256_PRECOMPILE",
+            WellKnownConstant::Ripemd160Precompile => "RIPEMD160_PRECOMPILE",
+            WellKnownConstant::IdentityPrecompile => "IDENTITY_PRECOMPILE",
+            WellKnownConstant::ModexpPrecompile => "MODEXP_PRECOMPILE",
+            WellKnownConstant::EcaddPrecompile => "ECADD_PRECOMPILE",
+            WellKnownConstant::EcmulPrecompile => "ECMUL_PRECOMPILE",
+            WellKnownConstant::EcpairingPrecompile => "ECPAIRING_PRECOMPILE",
+            WellKnownConstant::Blake2fPrecompile => "BLAKE2F_PRECOMPILE",
+            WellKnownConstant::PointEvaluationPrecompile => "POINT_EVALUATION_PRECOMPILE",
+        }
+    }
+
+    /// Looks up the constant of a name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    /// The value of the constant
+    pub fn value(&self) -> Literal {
+        let precompile = |address: u8| {
+            let mut literal = [0u8; 32];
+            literal[31] = address;
+            literal
+        };
+        match self {
+            WellKnownConstant::Eip1967ImplementationSlot => eip1967_slot("implementation"),
+            WellKnownConstant::Eip1967AdminSlot => eip1967_slot("admin"),
+            WellKnownConstant::Eip1967BeaconSlot => eip1967_slot("beacon"),
+            WellKnownConstant::EcrecoverPrecompile => precompile(0x01),
+            WellKnownConstant::Sha256Precompile => precompile(0x02),
+            WellKnownConstant::Ripemd160Precompile => precompile(0x03),
+            WellKnownConstant::IdentityPrecompile => precompile(0x04),
+            WellKnownConstant::ModexpPrecompile => precompile(0x05),
+            WellKnownConstant::EcaddPrecompile => precompile(0x06),
+            WellKnownConstant::EcmulPrecompile => precompile(0x07),
+            WellKnownConstant::EcpairingPrecompile => precompile(0x08),
+            WellKnownConstant::Blake2fPrecompile => precompile(0x09),
+            WellKnownConstant::PointEvaluationPrecompile => precompile(0x0a),
+        }
+    }
+}
+
+/// The EIP-1967 slot of a proxy field, `keccak256("eip1967.proxy.<field>") - 1`
+fn eip1967_slot(field: &str) -> Literal {
+    let hash = U256::from_big_endian(&keccak256(format!("eip1967.proxy.{}", field)));
+    let mut literal = [0u8; 32];
+    (hash - 1).to_big_endian(&mut literal);
+    literal
+}
+
+/// Checks that the constants a contract defines with a well-known name have its value
+///
+/// Storage pointers must be derived, a slot derived for a well-known name failing too.
+pub fn check_well_known_constants(contract: &Contract) -> Vec<CodegenError> {
+    contract
+        .constants
+        .iter()
+        .filter_map(|c| {
+            let well_known = WellKnownConstant::from_name(&c.name)?;
+            if c.value == ConstVal::Literal(well_known.value()) {
+                return None
+            }
+            tracing::error!(target: "ast", "CONSTANT \"{}\" DOES NOT HAVE ITS WELL-KNOWN VALUE", c.name);
+            Some(CodegenError {
+                kind: CodegenErrorKind::WellKnownConstantMismatch(c.name.to_string()),
+                span: c.span.clone(),
+                token: None,
+                help: Some(format!(
+                    "the well-known value is 0x{}, remove the definition to use it",
+                    hex::encode(well_known.value())
+                )),
+            })
+        })
+        .collect()
+}
@@ -1,5 +1,6 @@
 use huff_utils::abi::*;
-use std::collections::HashMap;
+use huff_utils::ast::FunctionType;
+use std::collections::{BTreeMap, HashMap};
 
 #[test]
 fn convert_function_param_type() {
@@ -32,3 +33,81 @@ fn convert_function_param_type() {
         assert_eq!(ac_func_type, *expected_fn_types.get(&index).unwrap());
     }
 }
+
+#[test]
+fn converts_to_json_abi() {
+    let abi = Abi {
+        constructor: Some(Constructor {
+            inputs: vec![FunctionParam {
+                name: "owner".to_string(),
+                kind: FunctionParamType::Address,
+                internal_type: None,
+            }],
+            payable: true,
+        }),
+        functions: BTreeMap::from([(
+            "swap".to_string(),
+            Function {
+                name: "swap".into(),
+                inputs: vec![FunctionParam {
+                    name: "orders".to_string(),
+                    kind: FunctionParamType::Array(
+                        Box::new(FunctionParamType::Tuple(vec![
+                            FunctionParamType::Address,
+                            FunctionParamType::Uint(256),
+                        ])),
+                        vec![0],
+                    ),
+                    internal_type: None,
+                }],
+                outputs: vec![],
+                constant: false,
+                state_mutability: FunctionType::Payable,
+            },
+        )]),
+        events: BTreeMap::new(),
+        errors: BTreeMap::from([(
+            "Expired".to_string(),
+            AbiError {
+                name: "Expired".to_string(),
+                inputs: vec![FunctionParam {
+                    name: "deadline".to_string(),
+                    kind: FunctionParamType::Uint(256),
+                    internal_type: None,
+                }],
+            },
+        )]),
+        receive: true,
+        fallback: false,
+    };
+    assert_eq!(
+        abi.json_abi(),
+        vec![
+            serde_json::json!({
+                "type": "constructor",
+                "inputs": [{ "name": "owner", "type": "address" }],
+                "stateMutability": "payable",
+            }),
+            serde_json::json!({
+                "type": "function",
+                "name": "swap",
+                "inputs": [{
+                    "name": "orders",
+                    "type": "tuple[]",
+                    "components": [
+                        { "name": "", "type": "address" },
+                        { "name": "", "type": "uint256" },
+                    ],
+                }],
+                "outputs": [],
+                "stateMutability": "payable",
+            }),
+            serde_json::json!({
+                "type": "error",
+                "name": "Expired",
+                "inputs": [{ "name": "deadline", "type": "uint256" }],
+            }),
+            serde_json::json!({ "type": "receive", "stateMutability": "payable" }),
+        ]
+    );
+}
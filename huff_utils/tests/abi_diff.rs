@@ -0,0 +1,118 @@
+use huff_utils::{abi::*, abi_diff::*, ast::FunctionType};
+use std::collections::BTreeMap;
+
+fn param(kind: &str) -> FunctionParam {
+    FunctionParam { name: String::new(), kind: kind.into(), internal_type: None }
+}
+
+fn function(name: &str, inputs: &[&str], outputs: &[&str], mutability: FunctionType) -> Function {
+    Function {
+        name: name.into(),
+        inputs: inputs.iter().map(|i| param(i)).collect(),
+        outputs: outputs.iter().map(|o| param(o)).collect(),
+        constant: false,
+        state_mutability: mutability,
+    }
+}
+
+fn event(name: &str, inputs: &[(&str, bool)]) -> Event {
+    Event {
+        name: name.to_string(),
+        inputs: inputs
+            .iter()
+            .map(|(kind, indexed)| EventParam {
+                name: String::new(),
+                kind: (*kind).into(),
+                indexed: *indexed,
+            })
+            .collect(),
+        anonymous: false,
+    }
+}
+
+fn abi(functions: Vec<Function>, events: Vec<Event>) -> Abi {
+    Abi {
+        constructor: None,
+        functions: functions
+            .into_iter()
+            .map(|f| (f.name.to_string(), f))
+            .collect::<BTreeMap<_, _>>(),
+        events: events.into_iter().map(|e| (e.name.clone(), e)).collect::<BTreeMap<_, _>>(),
+        errors: BTreeMap::new(),
+        receive: false,
+        fallback: false,
+    }
+}
+
+#[test]
+fn computes_signatures_and_selectors() {
+    let transfer =
+        function("transfer", &["address", "uint256"], &["bool"], FunctionType::NonPayable);
+    assert_eq!(transfer.signature(), "transfer(address,uint256)");
+    assert_eq!(transfer.selector(), [0xa9, 0x05, 0x9c, 0xbb]);
+
+    let event = event("Transfer", &[("address", true), ("address", true), ("uint256", false)]);
+    assert_eq!(event.signature(), "Transfer(address,address,uint256)");
+    assert_eq!(
+        hex::encode(event.topic()),
+        "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+    );
+
+    let kinds = ["uint[][]", "address[5][]", "bytes32", "int8"].map(FunctionParamType::from);
+    assert_eq!(
+        kinds.map(|k| k.to_string()),
+        ["uint256[][]", "address[5][]", "bytes32", "int8"].map(String::from)
+    );
+}
+
+#[test]
+fn diffs_abis() {
+    let old = abi(
+        vec![
+            function("transfer", &["address", "uint256"], &["bool"], FunctionType::NonPayable),
+            function("balanceOf", &["address"], &["uint256"], FunctionType::View),
+            function("burn", &["uint256"], &[], FunctionType::NonPayable),
+        ],
+        vec![event("Transfer", &[("address", true), ("address", true), ("uint256", false)])],
+    );
+    let mut new = abi(
+        vec![
+            function("transfer", &["address", "uint128"], &["bool"], FunctionType::NonPayable),
+            function("balanceOf", &["address"], &["uint256"], FunctionType::View),
+            function("mint", &["address", "uint256"], &[], FunctionType::Payable),
+        ],
+        vec![event("Transfer", &[("address", true), ("address", false), ("uint256", false)])],
+    );
+    new.receive = true;
+
+    let diff = AbiDiff::new(&old, &new);
+    assert!(diff.is_breaking());
+    assert_eq!(
+        diff.to_string(),
+        "Removed function burn(uint256): selector 0x42966c68 (breaking)\n\
+         Added function mint(address,uint256): selector 0x40c10f19\n\
+         Changed function transfer: signature transfer(address,uint256) -> transfer(address,uint128), selector 0xa9059cbb -> 0xfbb001d6 (breaking)\n\
+         Changed event Transfer: indexed parameters (breaking)\n\
+         Added receive"
+    );
+}
+
+#[test]
+fn additions_are_not_breaking() {
+    let transfer =
+        function("transfer", &["address", "uint256"], &["bool"], FunctionType::NonPayable);
+    let old = abi(vec![transfer.clone()], vec![]);
+    let new = abi(
+        vec![transfer, function("totalSupply", &[], &["uint256"], FunctionType::View)],
+        vec![event("Approval", &[("address", true), ("address", true), ("uint256", false)])],
+    );
+
+    assert!(AbiDiff::new(&old, &old).changes.is_empty());
+    assert_eq!(AbiDiff::new(&old, &old).to_string(), "No ABI changes");
+
+    let diff = AbiDiff::new(&old, &new);
+    assert!(!diff.is_breaking());
+    assert_eq!(diff.changes.len(), 2);
+    assert_eq!(diff.changes[0].kind, ChangeKind::Added);
+    assert_eq!(diff.changes[1].item, AbiItemKind::Event);
+}
@@ -0,0 +1,67 @@
+use huff_utils::prelude::*;
+
+fn macro_def(name: &str) -> MacroDefinition {
+    MacroDefinition::new(name.to_string(), vec![], vec![], 0, 0, vec![])
+}
+
+#[test]
+fn indexes_macros_by_id() {
+    let contract = Contract {
+        macros: vec![macro_def("MAIN"), macro_def("TRANSFER")].into(),
+        ..Default::default()
+    };
+    let id = contract.find_macro_id("TRANSFER".into()).unwrap();
+    assert_eq!(id.index(), 1);
+    assert_eq!(contract.macros[id].name, "TRANSFER");
+    assert_eq!(contract.find_macro_by_name("TRANSFER"), Some(&contract.macros[id]));
+    assert_eq!(contract.find_macro_id("MISSING".into()), None);
+    assert_eq!(contract.macros.ids().collect::<Vec<MacroId>>(), vec![MacroId::new(0), id]);
+}
+
+#[test]
+fn copies_only_the_nodes_written_by_a_snapshot() {
+    let mut arena = Arena::from(vec![macro_def("MAIN"), macro_def("TRANSFER")]);
+    let snapshot = arena.clone();
+    let (main, transfer) = (MacroId::new(0), MacroId::new(1));
+    assert!(arena.shares(&snapshot, main) && arena.shares(&snapshot, transfer));
+
+    arena[transfer].takes = 3;
+    assert!(arena.shares(&snapshot, main));
+    assert!(!arena.shares(&snapshot, transfer));
+    assert_eq!(snapshot[transfer].takes, 0);
+    assert_eq!(arena[transfer].takes, 3);
+
+    let id = arena.push(macro_def("APPROVE"));
+    assert!(!arena.shares(&snapshot, id));
+    assert_eq!(arena.len(), 3);
+    assert_eq!(snapshot.len(), 2);
+}
+
+#[test]
+fn serializes_arenas_as_lists() {
+    let arena = Arena::from(vec![macro_def("MAIN")]);
+    let json = serde_json::to_value(&arena).unwrap();
+    assert!(json.is_array());
+    assert_eq!(serde_json::from_value::<Arena<MacroDefinition>>(json).unwrap(), arena);
+}
+
+#[test]
+fn keeps_ids_when_redefining_constants() {
+    let constant = |name: &str, value: u8| ConstantDefinition {
+        name: name.into(),
+        value: ConstVal::Literal(str_to_bytes32(&value.to_string())),
+        span: AstSpan(vec![]),
+        internal: false,
+    };
+    let mut contract = Contract {
+        constants: vec![constant("OWNER", 1), constant("SUPPLY", 2)].into(),
+        ..Default::default()
+    };
+    let owner = contract.constants.position(|c| c.name == "OWNER").unwrap();
+
+    // Redefined constants are replaced in place, new ones allocated after the others
+    contract.define_constants(&[constant("OWNER", 3), constant("DECIMALS", 4)]);
+    assert_eq!(contract.constants[owner], constant("OWNER", 3));
+    assert_eq!(contract.constants.len(), 3);
+    assert_eq!(contract.constants[2].name, "DECIMALS");
+}
@@ -0,0 +1,26 @@
+use huff_utils::prelude::*;
+
+#[test]
+fn pushes_significant_bytes() {
+    assert_eq!(Bytes::push(&str_to_bytes32("0100")).to_string(), "610100");
+    assert_eq!(Bytes::push(&[0; 32]).0, vec![0x60, 0x00]);
+    assert_eq!(Bytes::push(&[0xff; 32]).len(), 33);
+}
+
+#[test]
+fn fills_placeholders() {
+    let mut bytes = Bytes([&[0x5b][..], &Bytes::placeholder().0].concat());
+    assert!(!bytes.fill(0, 0x1234));
+    assert!(bytes.fill(1, 0x1234));
+    assert_eq!(bytes.to_string(), "5b611234");
+}
+
+#[test]
+fn encodes_link_placeholders() {
+    let library = "src/Math.huff:Math";
+    let bytes = [&[0x73][..], &link_marker(library), &[0x5b]].concat();
+    assert_eq!(
+        encode_linked(&bytes, [library].into_iter()),
+        format!("73{}5b", link_placeholder(library))
+    );
+}
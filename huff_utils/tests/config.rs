@@ -0,0 +1,109 @@
+use huff_utils::prelude::*;
+use std::{ffi::OsStr, sync::Arc};
+
+#[test]
+fn test_parse_post_build_hooks() {
+    let config = HuffConfig::from_toml(
+        r#"
+        [[post-build]]
+        name = "bindings"
+        command = "node"
+        args = ["scripts/bindings.js", "--out", "bindings"]
+
+        [[post-build]]
+        command = "./upload.sh"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.post_build.len(), 2);
+    assert_eq!(config.post_build[0].name(), "bindings");
+    assert_eq!(config.post_build[0].args, vec!["scripts/bindings.js", "--out", "bindings"]);
+    assert_eq!(config.post_build[1].name(), "./upload.sh");
+    assert!(config.post_build[1].args.is_empty());
+
+    // Hooks are optional
+    assert_eq!(HuffConfig::from_toml("").unwrap(), HuffConfig::default());
+    assert!(HuffConfig::from_toml("[[post-build]]\nname = \"missing command\"").is_err());
+}
+
+#[test]
+fn test_parse_manifests() {
+    let config = HuffConfig::from_toml(
+        r#"
+        manifests = ["lib/Ownable.manifest.json"]
+
+        [[post-build]]
+        command = "./upload.sh"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.manifests, vec!["lib/Ownable.manifest.json"]);
+    assert_eq!(config.post_build.len(), 1);
+}
+
+#[test]
+fn test_post_build_hook_command() {
+    let hook = PostBuildHook {
+        name: None,
+        command: "node".to_string(),
+        args: vec!["bindings.js".to_string()],
+    };
+    let artifact = Artifact {
+        file: Arc::new(FileSource { path: "./src/ERC20.huff".to_string(), ..Default::default() }),
+        bytecode: "60016000".to_string(),
+        runtime: "6001".to_string(),
+        abi: None,
+        storage_layout: Default::default(),
+        panics: Default::default(),
+        devdoc: Default::default(),
+        userdoc: Default::default(),
+        immutable_references: Default::default(),
+        link_references: Default::default(),
+        runtime_link_references: Default::default(),
+        verbatim_references: Default::default(),
+    };
+
+    let command = hook.command(&artifact, "./artifacts/SRC/ERC20.HUFF.json");
+    assert_eq!(command.get_program(), "node");
+    assert_eq!(
+        command.get_args().collect::<Vec<&OsStr>>(),
+        vec!["bindings.js", "./artifacts/SRC/ERC20.HUFF.json"]
+    );
+    assert_eq!(
+        command.get_envs().collect::<Vec<_>>(),
+        vec![
+            (OsStr::new("HUFF_ARTIFACT"), Some(OsStr::new("./artifacts/SRC/ERC20.HUFF.json"))),
+            (OsStr::new("HUFF_BYTECODE"), Some(OsStr::new("60016000"))),
+            (OsStr::new("HUFF_RUNTIME"), Some(OsStr::new("6001"))),
+            (OsStr::new("HUFF_SOURCE"), Some(OsStr::new("./src/ERC20.huff"))),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_lint_levels() {
+    let config = HuffConfig::from_toml(
+        r#"
+        [lints]
+        warnings = "deny"
+        unused-macro = "allow"
+        "#,
+    )
+    .unwrap();
+    let levels = config.lint_levels().unwrap();
+    assert_eq!(levels.level("unused-macro"), LintLevel::Allow);
+    assert_eq!(levels.level("unreachable-code"), LintLevel::Deny);
+
+    assert!(HuffConfig::from_toml("[lints]\nunused-macro = \"forbid\"").is_err());
+    let unknown = HuffConfig::from_toml("[lints]\nunknown-lint = \"warn\"").unwrap();
+    assert_eq!(unknown.lint_levels(), Err("unknown-lint".to_string()));
+}
+
+#[test]
+fn test_parse_fuzz_config() {
+    let config = HuffConfig::from_toml("[fuzz]\nruns = 1000\nseed = 42").unwrap();
+    assert_eq!(config.fuzz, FuzzConfig { runs: Some(1000), seed: Some(42) });
+    assert_eq!(HuffConfig::from_toml("").unwrap().fuzz, FuzzConfig::default());
+    assert!(HuffConfig::from_toml("[fuzz]\nruns = -1").is_err());
+}
@@ -0,0 +1,43 @@
+use huff_utils::prelude::*;
+use std::sync::Arc;
+
+#[test]
+fn test_data_contract_code() {
+    let data_contract = DataContract::new("DATA", vec![0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(data_contract.runtime(), "00deadbeef");
+    // PUSH1 0x05 DUP1 PUSH1 0x0b PUSH1 0x00 CODECOPY PUSH1 0x00 RETURN
+    assert_eq!(data_contract.creation_code(), "600580600b6000396000f300deadbeef");
+
+    // Larger sizes are pushed with more bytes, moving the runtime code
+    let data_contract = DataContract::new("DATA", vec![0xff; 0x100]);
+    assert_eq!(&data_contract.creation_code()[..26], "61010180600c6000396000f300");
+
+    let artifact = data_contract.artifact(Arc::new(FileSource::default()));
+    assert_eq!(artifact.runtime, data_contract.runtime());
+    assert_eq!(artifact.bytecode, data_contract.creation_code());
+}
+
+#[test]
+fn test_data_contract_from_table() {
+    let table = |kind: TableKind| TableDefinition {
+        name: "DATA".into(),
+        kind,
+        statements: vec![Statement {
+            ty: StatementType::Code(vec![0xc0, 0xff, 0xee]),
+            span: AstSpan(vec![]),
+        }],
+        size: str_to_bytes32("3"),
+        span: AstSpan(vec![]),
+    };
+    assert_eq!(
+        DataContract::from_table(&table(TableKind::CodeTable)),
+        Some(DataContract::new("DATA", vec![0xc0, 0xff, 0xee]))
+    );
+    assert_eq!(DataContract::from_table(&table(TableKind::JumpTable)), None);
+
+    let reader_macros = DataContract::new("DATA", vec![0; 0x20]).reader_macros();
+    assert!(
+        reader_macros.contains("#define macro DATA_SIZE() = takes (0) returns (1) {\n    0x20\n}")
+    );
+    assert!(reader_macros.contains("#define macro READ_DATA() = takes (4) returns (0) {"));
+}
@@ -0,0 +1,30 @@
+use huff_utils::prelude::*;
+use std::str::FromStr;
+
+#[test]
+fn parses_defined_constants() {
+    let define = |s: &str| ConstantDefinition::from_str(s).map(|c| (c.name.to_string(), c.value));
+    let literal = |hex: &str| ConstVal::Literal(str_to_bytes32(hex));
+
+    // Constants default to 1, and take hex, binary or decimal values
+    assert_eq!(define("DEBUG"), Ok(("DEBUG".to_string(), literal("01"))));
+    assert_eq!(define("FEE=0x64"), Ok(("FEE".to_string(), literal("64"))));
+    assert_eq!(define("MASK=0b1010"), Ok(("MASK".to_string(), literal("0a"))));
+    assert_eq!(define("CHAIN_ID = 10"), Ok(("CHAIN_ID".to_string(), literal("0a"))));
+
+    assert_eq!(define("1ST=0x01"), Err("Invalid constant name \"1ST\"".to_string()));
+    assert_eq!(define("=0x01"), Err("Invalid constant name \"\"".to_string()));
+    assert_eq!(define("FEE=0xzz"), Err("Invalid value \"0xzz\" of constant \"FEE\"".to_string()));
+    assert_eq!(define("FEE="), Err("Invalid value \"\" of constant \"FEE\"".to_string()));
+
+    // Values are at most 32 bytes wide
+    let wide = format!("0x01{}", "00".repeat(32));
+    assert_eq!(
+        define(&format!("FEE={}", wide)),
+        Err(format!("Value \"{}\" of constant \"FEE\" exceeds 32 bytes", wide))
+    );
+    assert_eq!(
+        define(&format!("FEE={}", "1".repeat(80))),
+        Err(format!("Value \"{}\" of constant \"FEE\" exceeds 32 bytes", "1".repeat(80)))
+    );
+}
@@ -0,0 +1,47 @@
+use huff_utils::prelude::*;
+
+fn artifact() -> Artifact {
+    Artifact {
+        bytecode: "600b8060093d393df360003560005260206000f3".to_string(),
+        runtime: "60003560005260206000f3".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_estimate_deployment_gas() {
+    // 3 zero and 17 non-zero bytes in a single initcode word, depositing 11 bytes of code
+    let expected = TX_BASE_GAS + TX_CREATE_GAS + 3 * 4 + 17 * 16 + 2 + 11 * 200;
+    assert_eq!(DeploymentTransaction::estimate_gas(&artifact()), expected);
+}
+
+#[test]
+fn test_legacy_deployment_transaction() {
+    let mut tx = DeploymentTransaction::new(&artifact());
+    tx.set_chain(None, Some(3));
+    tx.set_fees(None, None);
+
+    let json: serde_json::Value = serde_json::from_str(&tx.to_json()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "to": null,
+            "data": "0x600b8060093d393df360003560005260206000f3",
+            "value": "0x0",
+            "gas": "0xd8be",
+            "nonce": "0x3"
+        })
+    );
+}
+
+#[test]
+fn test_eip1559_deployment_transaction() {
+    let mut tx = DeploymentTransaction::new(&artifact());
+    tx.set_chain(Some(1), None);
+    tx.set_fees(Some(30_000_000_000), Some(1_000_000_000));
+
+    assert_eq!(tx.tx_type, Some("0x2".to_string()));
+    assert_eq!(tx.chain_id, Some("0x1".to_string()));
+    assert_eq!(tx.max_fee_per_gas, Some("0x6fc23ac00".to_string()));
+    assert_eq!(tx.max_priority_fee_per_gas, Some("0x3b9aca00".to_string()));
+}
@@ -0,0 +1,100 @@
+use huff_utils::prelude::*;
+use std::sync::Arc;
+
+#[test]
+fn test_position_from_offset() {
+    let source = "#define macro MAIN() = {\n    0x00 mstore\n}\n";
+    assert_eq!(Position::from_offset(source, 0), Position { line: 1, column: 1 });
+    assert_eq!(Position::from_offset(source, 14), Position { line: 1, column: 15 });
+    assert_eq!(Position::from_offset(source, 30), Position { line: 2, column: 6 });
+    // Offsets past the end of the source are clamped
+    assert_eq!(Position::from_offset(source, 1000), Position { line: 4, column: 1 });
+}
+
+#[test]
+fn test_span_code_frame() {
+    let source = "#define macro MAIN() = {\n    0x00 mstore\n}\n";
+    let file = Arc::new(FileSource {
+        path: "./src/Main.huff".to_string(),
+        source: Some(source.to_string()),
+        ..Default::default()
+    });
+
+    let span = Span { start: 34, end: 40, file: Some(Arc::clone(&file)) };
+    assert_eq!(
+        span.code_frame().unwrap(),
+        "  --> ./src/Main.huff:2:10\n  |\n2 |     0x00 mstore\n  |          ^^^^^^"
+    );
+
+    // Spans over multiple lines underline every line they cover
+    let span = Span { start: 23, end: 33, file: Some(file) };
+    assert_eq!(
+        span.code_frame().unwrap(),
+        "  --> ./src/Main.huff:1:24\n  |\n1 | #define macro MAIN() = {\n  |                        ^\n2 |     0x00 mstore\n  | ^^^^^^^^"
+    );
+
+    // Spans without a source have no code frame
+    assert_eq!(Span { start: 34, end: 40, file: None }.code_frame(), None);
+}
+
+#[test]
+fn test_parser_error_diagnostic() {
+    let source = "#define macro MAIN() = {\n    0x00 mstore\n}\n";
+    let file = Arc::new(FileSource {
+        path: "./src/Main.huff".to_string(),
+        source: Some(source.to_string()),
+        ..Default::default()
+    });
+    let error = CompilerError::ParserError(ParserError {
+        kind: ParserErrorKind::InvalidName(TokenKind::Ident("mstore".to_string())),
+        spans: AstSpan(vec![Span { start: 34, end: 40, file: Some(file) }]),
+    });
+
+    let diagnostics = Diagnostic::from_compiler_error(&error);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "H0211");
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(
+        diagnostics[0].spans,
+        vec![DiagnosticSpan {
+            file: Some("./src/Main.huff".to_string()),
+            start: 34,
+            end: 40,
+            start_line: Some(2),
+            start_column: Some(10),
+            end_line: Some(2),
+            end_column: Some(16),
+        }]
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&diagnostics[0].to_json()).unwrap();
+    assert_eq!(json["code"], "H0211");
+    assert_eq!(json["severity"], "error");
+    assert_eq!(json["spans"][0]["start_line"], 2);
+}
+
+#[test]
+fn test_failed_compiles_are_flattened() {
+    let error = CompilerError::FailedCompiles(vec![
+        CompilerError::FileUnpackError(UnpackError::MissingFile("./A.huff".to_string())),
+        CompilerError::FileUnpackError(UnpackError::UnsupportedExtension("./B.sol".to_string())),
+    ]);
+    let diagnostics = Diagnostic::from_compiler_error(&error);
+    assert_eq!(
+        diagnostics.iter().map(|d| d.code.as_str()).collect::<Vec<&str>>(),
+        vec!["H0403", "H0401"]
+    );
+    assert_eq!(diagnostics[1].spans[0].file, Some("./B.sol".to_string()));
+}
+
+#[test]
+fn test_did_you_mean() {
+    let candidates = ["TRANSFER_FROM", "TRANSFER", "APPROVE"];
+    assert_eq!(
+        did_you_mean("TRANSFER_FORM", candidates),
+        Some("did you mean 'TRANSFER_FROM'?".to_string())
+    );
+    assert_eq!(did_you_mean("transfer", candidates), Some("did you mean 'TRANSFER'?".to_string()));
+    assert_eq!(did_you_mean("BALANCE_OF", candidates), None);
+    assert_eq!(did_you_mean("APPROVE", candidates), None);
+}
@@ -0,0 +1,24 @@
+use huff_utils::prelude::*;
+use std::str::FromStr;
+
+#[test]
+fn parses_evm_versions() {
+    for version in EVMVersion::ALL {
+        assert_eq!(EVMVersion::from_str(&version.to_string()), Ok(version));
+    }
+    assert_eq!(EVMVersion::from_str("Cancun"), Ok(EVMVersion::Cancun));
+    assert_eq!(
+        EVMVersion::from_str("london"),
+        Err("Unknown EVM version \"london\". Available versions: paris, shanghai, cancun"
+            .to_string())
+    );
+}
+
+#[test]
+fn transient_storage_availability() {
+    assert_eq!(EVMVersion::default(), EVMVersion::Paris);
+    assert!(!EVMVersion::Shanghai.has_transient_storage());
+    assert!(EVMVersion::Cancun.has_transient_storage());
+    assert_eq!(EVMVersion::Paris.transient_storage_opcodes(), (Opcode::Sload, Opcode::Sstore));
+    assert_eq!(EVMVersion::Cancun.transient_storage_opcodes(), (Opcode::Tload, Opcode::Tstore));
+}
@@ -0,0 +1,69 @@
+use huff_utils::prelude::*;
+
+#[test]
+fn test_lookup_explanation() {
+    let e = Explanation::lookup("H0306").unwrap();
+    assert_eq!(e.code, "H0306");
+    assert_eq!(e.name, "UnmatchedJumpLabel");
+    assert!(e.text.contains("jumpi"));
+
+    // Lookups ignore case and surrounding whitespace
+    assert_eq!(Explanation::lookup(" h0306 "), Some(e));
+    assert_eq!(Explanation::lookup("H9999"), None);
+}
+
+#[test]
+fn test_every_code_is_explained() {
+    let codes = vec![
+        LexicalErrorKind::UnexpectedEof.code(),
+        LexicalErrorKind::InvalidPrimitiveType("uint1".to_string()).code(),
+        LexicalErrorKind::LiteralTooLong("0x00".to_string()).code(),
+        LexicalErrorKind::EmptyLiteral("0b".to_string()).code(),
+        ParserErrorKind::InvalidDefinition.code(),
+        ParserErrorKind::InvalidImportPath("a.sol".to_string()).code(),
+        ParserErrorKind::InvalidTableFile("a.bin".to_string()).code(),
+        ParserErrorKind::InvalidTokenInAliasBody(TokenKind::Eof).code(),
+        ParserErrorKind::InvalidRepeatCount("0x6001".to_string()).code(),
+        ParserErrorKind::UnknownConstantOverride("FEE".to_string()).code(),
+        ParserErrorKind::InvalidVerbatimBytes("0x5f5".to_string()).code(),
+        ParserErrorKind::MissingParameterDefault("value".to_string()).code(),
+        ParserErrorKind::PrivateDefinition("ADD_UNCHECKED".to_string()).code(),
+        CodegenErrorKind::UnmatchedJumpLabel.code(),
+        CodegenErrorKind::InvalidCodehashTarget("TABLE".to_string()).code(),
+        CodegenErrorKind::ManifestMismatch("MACRO".to_string()).code(),
+        CodegenErrorKind::MaxExpansionDepthExceeded(256).code(),
+        CodegenErrorKind::StorageSlotCollision("OWNER".to_string()).code(),
+        CodegenErrorKind::ImmutableSetInRuntime("OWNER".to_string()).code(),
+        CodegenErrorKind::AssertionFailed("0x00".to_string()).code(),
+        CodegenErrorKind::WellKnownConstantMismatch("EIP1967_ADMIN_SLOT".to_string()).code(),
+        CodegenErrorKind::OffsetOutOfRange("__OFFSET(start) - __OFFSET(end)".to_string()).code(),
+        CodegenErrorKind::MacroArgCountMismatch("TRANSFER".to_string()).code(),
+        CodegenErrorKind::InvalidMacroArgument("to".to_string()).code(),
+        CodegenErrorKind::UndeclaredArgCall("amount".to_string()).code(),
+        CodegenErrorKind::MissingFunctionDefinition("transfer".to_string()).code(),
+        CodegenErrorKind::UnencodableOutput("string".to_string()).code(),
+        CodegenErrorKind::MissingEventDefinition("Transfer".to_string()).code(),
+        CodegenErrorKind::UnemittableEvent("Transfer".to_string()).code(),
+        CodegenErrorKind::PluginFailed("coverage".to_string()).code(),
+        CodegenErrorKind::InvalidBuiltinArgument("__L1_BLOCK".to_string()).code(),
+        CompilerError::PathBufRead(Default::default()).code().unwrap(),
+        CompilerError::FileUnpackError(UnpackError::CircularInclude(vec![])).code().unwrap(),
+        CompilerError::FileUnpackError(UnpackError::UnflattenableInclude(String::new()))
+            .code()
+            .unwrap(),
+        WarningKind::OversizedContract(24577).code(),
+        WarningKind::InlinedAlias("MAIN".to_string(), "fmp".to_string()).code(),
+        WarningKind::UnusedTable("TABLE".to_string()).code(),
+        WarningKind::UnusedLabel("MAIN".to_string(), "done".to_string()).code(),
+        WarningKind::UnusedFunction("balanceOf".to_string()).code(),
+    ];
+    for code in codes {
+        assert!(Explanation::lookup(code).is_some(), "{} is not explained", code);
+    }
+
+    // Codes are unique and sorted
+    let mut sorted: Vec<&str> = EXPLANATIONS.iter().map(|e| e.code).collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+    assert_eq!(sorted, EXPLANATIONS.iter().map(|e| e.code).collect::<Vec<&str>>());
+}
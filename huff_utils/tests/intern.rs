@@ -0,0 +1,38 @@
+use huff_utils::prelude::*;
+
+#[test]
+fn interns_names_once() {
+    let label = SymbolId::intern("withdraw_end");
+    assert_eq!(SymbolId::from("withdraw_end".to_string()), label);
+    assert_ne!(SymbolId::intern("withdraw_start"), label);
+    assert_eq!(label.as_str(), "withdraw_end");
+    assert_eq!(label.to_string(), "withdraw_end");
+    assert!(label == "withdraw_end");
+}
+
+#[test]
+fn orders_ids_by_name() {
+    let late = SymbolId::intern("b_interned_first");
+    let early = SymbolId::intern("a_interned_second");
+    assert!(early < late);
+    assert_eq!(serde_json::to_string(&early).unwrap(), "\"a_interned_second\"");
+    assert_eq!(serde_json::from_str::<SymbolId>("\"b_interned_first\"").unwrap(), late);
+}
+
+#[test]
+fn resolves_ids_to_one_interned_name() {
+    let name = String::from("shared_name");
+    let id = SymbolId::intern(&name);
+    assert!(std::ptr::eq(id.as_str(), SymbolId::from(name.as_str()).as_str()));
+    assert!(!std::ptr::eq(id.as_str(), name.as_str()));
+}
+
+#[test]
+fn looks_names_up_without_interning_them() {
+    let contract = Contract::default();
+    assert_eq!(contract.find_macro_by_name("never_interned"), None);
+    assert_eq!(SymbolId::lookup("never_interned"), None);
+
+    let id = SymbolId::intern("interned_once");
+    assert_eq!(SymbolId::lookup("interned_once"), Some(id));
+}
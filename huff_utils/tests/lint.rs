@@ -0,0 +1,79 @@
+use huff_utils::prelude::*;
+
+#[test]
+fn test_lint_level_fallbacks() {
+    let mut levels = LintLevels::default();
+    assert_eq!(levels.level("unused-macro"), LintLevel::Warn);
+
+    levels.set(ALL_LINTS, LintLevel::Deny).unwrap();
+    levels.set("unused-macro", LintLevel::Allow).unwrap();
+    assert_eq!(levels.level("unused-macro"), LintLevel::Allow);
+    assert_eq!(levels.level("shadowed-label"), LintLevel::Deny);
+}
+
+#[test]
+fn test_unknown_lint() {
+    let mut levels = LintLevels::default();
+    assert_eq!(levels.set("unknown-lint", LintLevel::Deny), Err("unknown-lint".to_string()));
+    assert!(levels.0.is_empty());
+}
+
+#[test]
+fn test_apply_lint_levels() {
+    let warnings = vec![
+        CompilerWarning::new(WarningKind::UnusedMacro("A".to_string()), AstSpan(vec![])),
+        CompilerWarning::new(WarningKind::UnusedConstant("B".to_string()), AstSpan(vec![])),
+        CompilerWarning::new(WarningKind::OversizedContract(30000), AstSpan(vec![])),
+    ];
+    let mut levels = LintLevels::default();
+    levels.set("unused-macro", LintLevel::Allow).unwrap();
+    levels.set("oversized-contract", LintLevel::Deny).unwrap();
+
+    let (reported, denied) = levels.apply(warnings.clone());
+    assert_eq!(reported, vec![warnings[1].clone()]);
+    assert_eq!(denied, vec![warnings[2].clone()]);
+}
+
+#[test]
+fn test_warning_diagnostic() {
+    let warning =
+        CompilerWarning::new(WarningKind::UnusedConstant("OWNER".to_string()), AstSpan(vec![]));
+    let diagnostic = Diagnostic::from(&warning);
+    assert_eq!(diagnostic.code, "H0505");
+    assert_eq!(diagnostic.severity, Severity::Warning);
+    assert_eq!(diagnostic.lint.as_deref(), Some("unused-constant"));
+    assert_eq!(
+        format!("{}", diagnostic),
+        "warning[H0505]: Constant \"OWNER\" is never used [unused-constant]"
+    );
+
+    let denied = Diagnostic::from_compiler_error(&CompilerError::DeniedWarnings(vec![warning]));
+    assert_eq!(denied[0].severity, Severity::Error);
+}
+
+#[test]
+fn test_default_levels() {
+    let mut levels = LintLevels::default();
+    levels.set(ALL_LINTS, LintLevel::Deny).unwrap();
+    assert_eq!(levels.level("magic-selector"), LintLevel::Allow);
+
+    levels.set("magic-selector", LintLevel::Warn).unwrap();
+    assert_eq!(levels.level("magic-selector"), LintLevel::Warn);
+
+    levels.set(ALL_LINTS, LintLevel::Allow).unwrap();
+    assert_eq!(levels.level("stack-underflow"), LintLevel::Deny);
+    levels.set("stack-underflow", LintLevel::Warn).unwrap();
+    assert_eq!(levels.level("stack-underflow"), LintLevel::Warn);
+}
+
+#[test]
+fn test_apply_fixes() {
+    let fix = |start: usize, end: usize, replacement: &str| Fix {
+        span: Span { start, end, file: None },
+        replacement: replacement.to_string(),
+    };
+    // Offsets are characters, and overlapping fixes are skipped
+    let source = "// ü\nSTOP 0x01 0x02";
+    let fixes = [fix(5, 9, "stop"), fix(15, 19, ""), fix(10, 14, "[ONE]"), fix(12, 19, "")];
+    assert_eq!(apply_fixes(source, &fixes.iter().collect::<Vec<_>>()), "// ü\nstop [ONE] ");
+}